@@ -42,4 +42,10 @@ pub struct SignatureRef<'a> {
     pub email: &'a BStr,
     /// The time stamp at which the signature was performed.
     pub time: git_date::Time,
+    /// The bytes of the time stamp as they were parsed, if they differ from how [`time`][SignatureRef::time] would be
+    /// serialized, like with leading zeros. These are written instead of `time` to re-serialize signatures byte-exactly,
+    /// so they have to be reset to `None` when `time` is changed.
+    ///
+    /// Owned [`Signature`]s don't keep them and are thus normalized when written.
+    pub raw_time: Option<&'a BStr>,
 }
@@ -1,4 +1,4 @@
-use bstr::ByteSlice;
+use bstr::{BStr, ByteSlice};
 use btoi::btoi;
 use nom::{
     branch::alt,
@@ -9,13 +9,15 @@ use nom::{
     IResult,
 };
 
+use git_date::SecondsSinceUnixEpoch;
+
 use crate::{Sign, SignatureRef, Time};
 
 const SPACE: &[u8] = b" ";
 
 /// Parse a signature from the bytes input `i` using `nom`.
 pub fn decode<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
-    i: &'a [u8],
+    input: &'a [u8],
 ) -> IResult<&'a [u8], SignatureRef<'a>, E> {
     let (i, (name, email, time, tzsign, hours, minutes)) = context(
         "<name> <<email>> <timestamp> <+|-><HHMM>",
@@ -24,7 +26,7 @@ pub fn decode<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
             context("<email>", terminated(take_until(&b"> "[..]), take(2usize))),
             context("<timestamp>", |i| {
                 terminated(take_until(SPACE), take(1usize))(i).and_then(|(i, v)| {
-                    btoi::<u32>(v)
+                    btoi::<SecondsSinceUnixEpoch>(v)
                         .map(|v| (i, v))
                         .map_err(|_| nom::Err::Error(E::from_error_kind(i, nom::error::ErrorKind::MapRes)))
                 })
@@ -45,26 +47,155 @@ pub fn decode<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
                 })
             }),
         )),
-    )(i)?;
+    )(input)?;
 
     debug_assert!(tzsign[0] == b'-' || tzsign[0] == b'+', "parser assure it's +|- only");
     let sign = if tzsign[0] == b'-' { Sign::Minus } else { Sign::Plus }; //
     let offset = (hours * 3600 + minutes * 60) * if sign == Sign::Minus { -1 } else { 1 };
+    let time = Time {
+        seconds_since_unix_epoch: time,
+        offset_in_seconds: offset,
+        sign,
+    };
+    let time_start = name.len() + 2 /* space < */ + email.len() + 2 /* > space */;
 
     Ok((
         i,
         SignatureRef {
             name: name.as_bstr(),
             email: email.as_bstr(),
-            time: Time {
-                seconds_since_unix_epoch: time,
-                offset_in_seconds: offset,
-                sign,
-            },
+            time,
+            raw_time: non_canonical(&input[time_start..input.len() - i.len()], time, hours, minutes),
         },
     ))
 }
 
+/// Return `raw`, the bytes `time` was parsed from with a timezone of `hours` and `minutes`, if they differ from how `time`
+/// is serialized, so they can be written instead to reproduce the input.
+fn non_canonical(raw: &[u8], time: Time, hours: i32, minutes: i32) -> Option<&BStr> {
+    if hours < 25 && minutes < 60 {
+        const MAX_LEN: usize = 32;
+        let mut buf = [0u8; MAX_LEN];
+        let len = {
+            let mut out = &mut buf[..];
+            time.write_to(&mut out).expect("enough space for the largest time");
+            MAX_LEN - out.len()
+        };
+        if buf[..len] == *raw {
+            return None;
+        }
+    }
+    Some(raw.as_bstr())
+}
+
+/// A deviation from the `<name> <<email>> <timestamp> <+|-><HHMM>` format tolerated by [`decode_lenient()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Issue {
+    /// There was no `<` to start the email, which is assumed to be empty, with everything being the name.
+    MissingEmail,
+    /// There was no `>` to end the email, which is assumed to extend to the end of the signature.
+    MissingEmailEnd,
+    /// There was no timestamp after the email, which is assumed to be `0 +0000`.
+    MissingTime,
+    /// The timestamp wasn't a number or didn't fit, and is assumed to be `0`.
+    MalformedTime,
+    /// The timezone was missing or not of the form `<+|-><HHMM>`, and is assumed to be `+0000`.
+    MalformedTimezone,
+}
+
+/// Parse a signature from the bytes input `i` like [`decode()`], but instead of failing on malformed input,
+/// fill in defaults and record each deviation from the expected format as [`Issue`].
+///
+/// The signature ends at the first tab or newline, or at the end of the timezone, whichever comes first,
+/// and the remaining input is returned along with the signature.
+pub fn decode_lenient(i: &[u8]) -> (&[u8], SignatureRef<'_>, Vec<Issue>) {
+    let mut issues = Vec::new();
+    let line = &i[..i.find_byte(b'\n').unwrap_or(i.len())];
+    let field_end = |from: usize| line[from..].find_byte(b'\t').map_or(line.len(), |pos| from + pos);
+    let name = |end: usize| {
+        let name = &line[..end];
+        name.strip_suffix(SPACE).unwrap_or(name).as_bstr()
+    };
+
+    let first_email_end = line.find_byte(b'>').unwrap_or(line.len());
+    let email_start = match line[..first_email_end].rfind_byte(b'<') {
+        Some(pos) => pos,
+        None => {
+            issues.push(Issue::MissingEmail);
+            let end = field_end(0);
+            let signature = SignatureRef {
+                name: name(end),
+                email: b"".as_bstr(),
+                time: Time::default(),
+                raw_time: None,
+            };
+            return (&i[end..], signature, issues);
+        }
+    };
+    let email_end = match line[email_start..].find_byte(b'>') {
+        Some(pos) => email_start + pos,
+        None => {
+            issues.push(Issue::MissingEmailEnd);
+            let end = field_end(email_start);
+            let signature = SignatureRef {
+                name: name(email_start),
+                email: line[email_start + 1..end].as_bstr(),
+                time: Time::default(),
+                raw_time: None,
+            };
+            return (&i[end..], signature, issues);
+        }
+    };
+    let mut signature = SignatureRef {
+        name: name(email_start),
+        email: line[email_start + 1..email_end].as_bstr(),
+        time: Time::default(),
+        raw_time: None,
+    };
+
+    let skip_spaces = |pos: usize| pos + line[pos..].iter().take_while(|b| **b == b' ').count();
+    let time_start = skip_spaces(email_end + 1);
+    let time_end = {
+        let sign_len = usize::from(line[time_start..].first() == Some(&b'-'));
+        let digits = line[time_start + sign_len..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            issues.push(Issue::MissingTime);
+            return (&i[email_end + 1..], signature, issues);
+        }
+        time_start + sign_len + digits
+    };
+    match btoi::<SecondsSinceUnixEpoch>(&line[time_start..time_end]) {
+        Ok(seconds) => signature.time.seconds_since_unix_epoch = seconds,
+        Err(_) => issues.push(Issue::MalformedTime),
+    }
+
+    let tz_start = skip_spaces(time_end);
+    let tz = &line[tz_start..];
+    let is_digits = |b: &[u8]| b.iter().all(u8::is_ascii_digit);
+    match tz.get(..5) {
+        Some(tz) if (tz[0] == b'-' || tz[0] == b'+') && is_digits(&tz[1..]) => {
+            let sign = if tz[0] == b'-' { Sign::Minus } else { Sign::Plus };
+            let hours: i32 = btoi(&tz[1..3]).expect("two digits always fit");
+            let minutes: i32 = btoi(&tz[3..5]).expect("two digits always fit");
+            signature.time.sign = sign;
+            signature.time.offset_in_seconds = (hours * 3600 + minutes * 60) * if sign == Sign::Minus { -1 } else { 1 };
+            if issues.is_empty() {
+                signature.raw_time = non_canonical(&line[time_start..tz_start + 5], signature.time, hours, minutes);
+            }
+            (&i[tz_start + 5..], signature, issues)
+        }
+        _ => {
+            issues.push(Issue::MalformedTimezone);
+            let tz_len = tz.iter().take_while(|b| !b.is_ascii_whitespace()).count();
+            let end = if tz_len == 0 { time_end } else { tz_start + tz_len };
+            (&i[end..], signature, issues)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod parse_signature {
@@ -81,7 +212,7 @@ mod tests {
         fn signature(
             name: &'static str,
             email: &'static str,
-            time: u32,
+            time: git_date::SecondsSinceUnixEpoch,
             sign: Sign,
             offset: i32,
         ) -> SignatureRef<'static> {
@@ -93,6 +224,7 @@ mod tests {
                     offset_in_seconds: offset,
                     sign,
                 },
+                raw_time: None,
             }
         }
 
@@ -1,7 +1,10 @@
 mod _ref {
     use bstr::{BStr, ByteSlice};
 
-    use crate::{signature::decode, Signature, SignatureRef};
+    use crate::{
+        signature::{decode, decode_lenient, Issue},
+        Signature, SignatureRef,
+    };
 
     impl<'a> SignatureRef<'a> {
         /// Deserialize a signature from the given `data`.
@@ -12,6 +15,15 @@ mod _ref {
             decode(data).map(|(_, t)| t)
         }
 
+        /// Deserialize a signature from the given `data` without ever failing, returning it along with all
+        /// [issues][Issue] that were worked around with defaults.
+        ///
+        /// See [`decode_lenient()`][crate::signature::decode_lenient()] for details.
+        pub fn from_bytes_lenient(data: &'a [u8]) -> (SignatureRef<'a>, Vec<Issue>) {
+            let (_, signature, issues) = decode_lenient(data);
+            (signature, issues)
+        }
+
         /// Create an owned instance from this shared one.
         pub fn to_owned(&self) -> Signature {
             Signature {
@@ -27,6 +39,7 @@ mod _ref {
                 name: self.name.trim().as_bstr(),
                 email: self.email.trim().as_bstr(),
                 time: self.time,
+                raw_time: self.raw_time,
             }
         }
 
@@ -34,6 +47,11 @@ mod _ref {
         pub fn actor(&self) -> (&BStr, &BStr) {
             (self.name, self.email)
         }
+
+        /// Return the seconds since unix epoch at which this signature was made, which are negative for times before it.
+        pub fn seconds(&self) -> git_date::SecondsSinceUnixEpoch {
+            self.time.seconds()
+        }
     }
 }
 
@@ -52,13 +70,14 @@ mod convert {
                 name: self.name.as_ref(),
                 email: self.email.as_ref(),
                 time: self.time,
+                raw_time: None,
             }
         }
     }
 
     impl From<SignatureRef<'_>> for Signature {
         fn from(other: SignatureRef<'_>) -> Signature {
-            let SignatureRef { name, email, time } = other;
+            let SignatureRef { name, email, time, .. } = other;
             Signature {
                 name: name.to_owned(),
                 email: email.to_owned(),
@@ -119,11 +138,14 @@ mod write {
             out.write_all(b"<")?;
             out.write_all(validated_token(self.email)?)?;
             out.write_all(b"> ")?;
-            self.time.write_to(out)
+            match self.raw_time {
+                Some(raw) => out.write_all(raw),
+                None => self.time.write_to(out),
+            }
         }
         /// Computes the number of bytes necessary to serialize this signature
         pub fn size(&self) -> usize {
-            self.name.len() + 2 /* space <*/ + self.email.len() +  2 /* > space */ + self.time.size()
+            self.name.len() + 2 /* space <*/ + self.email.len() +  2 /* > space */ + self.raw_time.map_or_else(|| self.time.size(), |raw| raw.len())
         }
     }
 
@@ -137,4 +159,4 @@ mod write {
 
 ///
 mod decode;
-pub use decode::decode;
+pub use decode::{decode, decode_lenient, Issue};
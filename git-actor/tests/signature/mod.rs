@@ -77,3 +77,200 @@ fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[test]
+fn round_trip_of_times_before_epoch_and_far_in_the_future() -> Result<(), Box<dyn std::error::Error>> {
+    for (input, seconds, iso8601) in [
+        (&b"name <email> 0 +0000"[..], 0, "1970-01-01 00:00:00 +0000"),
+        (
+            b"name <email> -1234567890 -0230",
+            -1234567890,
+            "1930-11-17 21:58:30 -0230",
+        ),
+        (
+            b"name <email> 884541340800 +0000",
+            884541340800,
+            "30000-01-01 00:00:00 +0000",
+        ),
+    ] {
+        let signature = git_actor::SignatureRef::from_bytes::<()>(input)?;
+        assert_eq!(signature.seconds(), seconds);
+        assert_eq!(signature.time.format_iso8601(), iso8601);
+        assert_eq!(signature.size(), input.len());
+
+        let mut output = Vec::new();
+        signature.write_to(&mut output)?;
+        assert_eq!(output.as_bstr(), input.as_bstr(), "the serialization is byte-exact");
+    }
+    Ok(())
+}
+
+#[test]
+fn round_trip_of_times_in_non_canonical_form_uses_their_raw_bytes() -> Result<(), Box<dyn std::error::Error>> {
+    for (input, raw_time, lenient_ok) in [
+        (&b"name <email> 1 -0030"[..], None, true),
+        (b"name <email> 0001 -0030", Some("0001 -0030"), true),
+        (b"name <email> -0 +0000", Some("-0 +0000"), true),
+        (b"name <email> +1 +0000", Some("+1 +0000"), false),
+        (b"name <email> 1 +9999", Some("1 +9999"), true),
+    ] {
+        let signature = git_actor::SignatureRef::from_bytes::<()>(input)?;
+        assert_eq!(signature.raw_time, raw_time.map(|raw| raw.as_bytes().as_bstr()));
+        assert_eq!(signature.size(), input.len());
+
+        let mut output = Vec::new();
+        signature.write_to(&mut output)?;
+        assert_eq!(output.as_bstr(), input.as_bstr(), "the serialization is byte-exact");
+
+        if lenient_ok {
+            let (lenient, issues) = git_actor::SignatureRef::from_bytes_lenient(input);
+            assert!(issues.is_empty());
+            assert_eq!(lenient, signature, "lenient parsing keeps them as well");
+        }
+    }
+
+    let signature: Signature = git_actor::SignatureRef::from_bytes::<()>(b"name <email> 0001 -0030")?.into();
+    let mut output = Vec::new();
+    signature.write_to(&mut output)?;
+    assert_eq!(
+        output.as_bstr(),
+        "name <email> 1 -0030",
+        "owned signatures don't keep the raw bytes and are normalized"
+    );
+    Ok(())
+}
+
+#[test]
+fn timestamps_overflowing_64_bit_are_rejected() {
+    assert!(git_actor::SignatureRef::from_bytes::<()>(b"name <email> 9223372036854775808 +0000").is_err());
+    assert!(git_actor::SignatureRef::from_bytes::<()>(b"name <email> 9223372036854775807 +0000").is_ok());
+}
+
+mod lenient {
+    use git_actor::{signature::Issue, Sign, SignatureRef, Time};
+
+    fn time(seconds_since_unix_epoch: i64, sign: Sign, offset_in_seconds: i32) -> Time {
+        Time {
+            seconds_since_unix_epoch,
+            offset_in_seconds,
+            sign,
+        }
+    }
+
+    #[test]
+    fn malformed_signatures_found_in_the_wild() {
+        let unset = Time::default();
+        for (input, name, email, time, issues, strict_ok) in [
+            (
+                &b"name <email> 1234567890 -0230"[..],
+                "name",
+                "email",
+                time(1234567890, Sign::Minus, -9000),
+                &[][..],
+                true,
+            ),
+            (
+                b"name <> 1234567890 +0000",
+                "name",
+                "",
+                time(1234567890, Sign::Plus, 0),
+                &[],
+                true,
+            ),
+            (
+                b"na<me <email> 1234567890 +0100",
+                "na<me",
+                "email",
+                time(1234567890, Sign::Plus, 3600),
+                &[],
+                true,
+            ),
+            (
+                b"name <email 1234567890 +0000",
+                "name",
+                "email 1234567890 +0000",
+                unset,
+                &[Issue::MissingEmailEnd],
+                false,
+            ),
+            (
+                b"name 1234567890 +0000",
+                "name 1234567890 +0000",
+                "",
+                unset,
+                &[Issue::MissingEmail],
+                false,
+            ),
+            (b"name <email>", "name", "email", unset, &[Issue::MissingTime], false),
+            (b"name <email> ", "name", "email", unset, &[Issue::MissingTime], false),
+            (
+                b"name <email> 1234567890",
+                "name",
+                "email",
+                time(1234567890, Sign::Plus, 0),
+                &[Issue::MalformedTimezone],
+                false,
+            ),
+            (
+                b"name <email> 1234567890 +05",
+                "name",
+                "email",
+                time(1234567890, Sign::Plus, 0),
+                &[Issue::MalformedTimezone],
+                false,
+            ),
+            (
+                b"name <email> 1234567890 CEST",
+                "name",
+                "email",
+                time(1234567890, Sign::Plus, 0),
+                &[Issue::MalformedTimezone],
+                false,
+            ),
+            (
+                b"name <email>  1234567890  +0000",
+                "name",
+                "email",
+                time(1234567890, Sign::Plus, 0),
+                &[],
+                false,
+            ),
+            (
+                b"name <email> 9223372036854775808 +0000",
+                "name",
+                "email",
+                time(0, Sign::Plus, 0),
+                &[Issue::MalformedTime],
+                false,
+            ),
+        ] {
+            let (signature, actual_issues) = SignatureRef::from_bytes_lenient(input);
+            assert_eq!(signature.name, name, "{:?}", input);
+            assert_eq!(signature.email, email, "{:?}", input);
+            assert_eq!(signature.time, time, "{:?}", input);
+            assert_eq!(actual_issues, issues, "{:?}", input);
+            match SignatureRef::from_bytes::<()>(input) {
+                Ok(strict) => {
+                    assert!(strict_ok, "{:?} should be rejected by the strict parser", input);
+                    assert_eq!(strict, signature, "both agree on well-formed signatures");
+                }
+                Err(_) => assert!(!strict_ok, "{:?} should be accepted by the strict parser", input),
+            }
+        }
+    }
+
+    #[test]
+    fn the_signature_ends_at_tabs_newlines_or_after_the_timezone() {
+        for (input, remaining) in [
+            (&b"name <email> 1 +0000\tmessage"[..], &b"\tmessage"[..]),
+            (b"name <email> 1 +0000\nnext", b"\nnext"),
+            (b"name <email> 1 +0000message", b"message"),
+            (b"name <email\tmessage", b"\tmessage"),
+            (b"name\tmessage", b"\tmessage"),
+            (b"name <email>\tmessage", b"\tmessage"),
+        ] {
+            let (actual, _, _) = git_actor::signature::decode_lenient(input);
+            assert_eq!(actual, remaining, "{:?}", input);
+        }
+    }
+}
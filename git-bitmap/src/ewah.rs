@@ -45,6 +45,74 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
     ))
 }
 
+mod encode {
+    use super::{access::RLW_LARGEST_LITERAL_COUNT, access::RLW_LARGEST_RUNNING_COUNT, access::RLW_RUNNING_BITS, Vec};
+
+    impl Vec {
+        /// Compress `words` holding `num_bits` bits, with bit `n` being bit `n % 64` of word `n / 64`.
+        ///
+        /// Bits beyond `num_bits` in the last word are expected to be unset.
+        pub fn from_words(words: &[u64], num_bits: u32) -> Self {
+            let mut bits = std::vec::Vec::new();
+            let mut rlw;
+            let mut words = words;
+            loop {
+                let run_word = match words.first() {
+                    Some(&word) if word == 0 || word == u64::MAX => word,
+                    _ => 0,
+                };
+                let running_len = words
+                    .iter()
+                    .take(RLW_LARGEST_RUNNING_COUNT as usize)
+                    .take_while(|w| **w == run_word)
+                    .count();
+                words = &words[running_len..];
+                let literal_len = words
+                    .iter()
+                    .take(RLW_LARGEST_LITERAL_COUNT as usize)
+                    .take_while(|w| **w != 0 && **w != u64::MAX)
+                    .count();
+                let (literals, rest) = words.split_at(literal_len);
+                words = rest;
+
+                rlw = bits.len() as u64;
+                bits.push(
+                    u64::from(run_word == u64::MAX)
+                        | ((running_len as u64) << 1)
+                        | ((literal_len as u64) << (1 + RLW_RUNNING_BITS)),
+                );
+                bits.extend_from_slice(literals);
+                if words.is_empty() {
+                    break;
+                }
+            }
+            Vec { num_bits, bits, rlw }
+        }
+
+        /// Decompress this instance into words, with bit `n` being bit `n % 64` of word `n / 64`.
+        pub fn to_words(&self) -> std::vec::Vec<u64> {
+            let mut words = vec![0u64; (self.num_bits() + 63) / 64];
+            self.for_each_set_bit(|index| {
+                if let Some(word) = words.get_mut(index / 64) {
+                    *word |= 1 << (index % 64);
+                }
+                Some(())
+            });
+            words
+        }
+
+        /// Serialize this instance into `out` in the same format that [`decode()`][super::decode()] reads.
+        pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(&(self.bits.len() as u32).to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(&(self.rlw as u32).to_be_bytes())
+        }
+    }
+}
+
 mod access {
     use std::convert::{TryFrom, TryInto};
 
@@ -110,13 +178,13 @@ mod access {
         w & 1 == 1
     }
 
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_RUNNING_BITS: u64 = 4 * 8;
+    pub(super) const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - 1 - RLW_RUNNING_BITS)) - 1;
 }
 
 /// A growable collection of u64 that are seen as stream of individual bits.
-#[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Vec {
     num_bits: u32,
     bits: std::vec::Vec<u64>,
@@ -9,13 +9,24 @@ mod error {
         EarlySentinelValue,
         #[error("Sentinel value wasn't found, saw {:?}", std::str::from_utf8(actual.as_ref()).unwrap_or("<non-ascii>"))]
         MissingSentinelValue { actual: crate::Id },
-        #[error("The chunk offset {offset} went past the file of length {file_length} - was it truncated?")]
+        #[error("The offset {offset} of chunk {:?} went past the file of length {file_length} - was it truncated?", std::str::from_utf8(kind.as_ref()).unwrap_or("<non-ascii>"))]
         ChunkSizeOutOfBounds {
+            kind: crate::Id,
             offset: crate::file::Offset,
             file_length: u64,
         },
-        #[error("All chunk offsets must be incrementing.")]
-        NonIncrementalChunkOffsets,
+        #[error("All chunk offsets must be incrementing, but chunk {:?} starts at {start} and ends at {end}", std::str::from_utf8(kind.as_ref()).unwrap_or("<non-ascii>"))]
+        NonIncrementalChunkOffsets {
+            kind: crate::Id,
+            start: crate::file::Offset,
+            end: crate::file::Offset,
+        },
+        #[error("The chunk {:?} starts at {offset} which is within the table of contents ending at {toc_end}", std::str::from_utf8(kind.as_ref()).unwrap_or("<non-ascii>"))]
+        ChunkOverlapsTableOfContents {
+            kind: crate::Id,
+            offset: crate::file::Offset,
+            toc_end: crate::file::Offset,
+        },
         #[error("The chunk of kind {:?} was encountered more than once", std::str::from_utf8(kind.as_ref()).unwrap_or("<non-ascii>"))]
         DuplicateChunk { kind: crate::Id },
         #[error("The table of contents would be {expected} bytes, but got only {actual}")]
@@ -31,6 +42,8 @@ use crate::{file, file::index};
 impl file::Index {
     /// Provided a mapped file at the beginning via `data`, starting at `toc_offset` decode all chunk information to return
     /// an index with `num_chunks` chunks.
+    ///
+    /// All chunks are validated to be sorted by offset, non-overlapping, located after the table of contents and within `data`.
     pub fn from_bytes(data: &[u8], toc_offset: usize, num_chunks: u32) -> Result<Self, Error> {
         if num_chunks == 0 {
             return Err(Error::Empty);
@@ -38,7 +51,7 @@ impl file::Index {
 
         let data_len: u64 = data.len() as u64;
         let mut chunks = Vec::with_capacity(num_chunks as usize);
-        let mut toc_entry = &data[toc_offset..];
+        let mut toc_entry = data.get(toc_offset..).unwrap_or_default();
         let expected_min_size = (num_chunks as usize + 1) * file::Index::ENTRY_SIZE;
         if toc_entry.len() < expected_min_size {
             return Err(Error::TocTooSmall {
@@ -46,6 +59,7 @@ impl file::Index {
                 actual: toc_entry.len(),
             });
         }
+        let toc_end = (toc_offset + expected_min_size) as u64;
 
         for _ in 0..num_chunks {
             let (kind, offset) = toc_entry.split_at(4);
@@ -60,20 +74,29 @@ impl file::Index {
             let offset = be_u64(offset);
             if offset > data_len {
                 return Err(Error::ChunkSizeOutOfBounds {
+                    kind,
                     offset,
                     file_length: data_len,
                 });
             }
+            if offset < toc_end {
+                return Err(Error::ChunkOverlapsTableOfContents { kind, offset, toc_end });
+            }
             toc_entry = &toc_entry[file::Index::ENTRY_SIZE..];
             let next_offset = be_u64(&toc_entry[4..]);
             if next_offset > data_len {
                 return Err(Error::ChunkSizeOutOfBounds {
+                    kind,
                     offset: next_offset,
                     file_length: data_len,
                 });
             }
             if next_offset <= offset {
-                return Err(Error::NonIncrementalChunkOffsets);
+                return Err(Error::NonIncrementalChunkOffsets {
+                    kind,
+                    start: offset,
+                    end: next_offset,
+                });
             }
             chunks.push(index::Entry {
                 kind,
@@ -34,8 +34,8 @@ pub mod data_by_kind {
     pub enum Error {
         #[error("The chunk wasn't found in the file index")]
         NotFound(#[from] super::offset_by_kind::Error),
-        #[error("The offsets into the file couldn't be represented by usize")]
-        FileTooLarge,
+        #[error("The offsets of chunk {:?} couldn't be represented by usize", std::str::from_utf8(kind.as_ref()).unwrap_or("<non-ascii>"))]
+        FileTooLarge { kind: crate::Id },
     }
 }
 
@@ -96,7 +96,7 @@ impl Index {
     /// Find a chunk of `kind` and return its data slice based on its offset.
     pub fn data_by_id<'a>(&self, data: &'a [u8], kind: crate::Id) -> Result<&'a [u8], data_by_kind::Error> {
         let offset = self.offset_by_id(kind)?;
-        Ok(&data[crate::range::into_usize(offset).ok_or(data_by_kind::Error::FileTooLarge)?])
+        Ok(&data[crate::range::into_usize(offset).ok_or(data_by_kind::Error::FileTooLarge { kind })?])
     }
 
     /// Return the end offset lf the last chunk, which is the highest offset as well.
@@ -7,6 +7,9 @@ mod write_chunk {
 
     /// A [`Write`][std::io::Write] implementation that validates chunk sizes while allowing the user to know
     /// which chunk is to be written next.
+    ///
+    /// Writing more bytes than planned for the current chunk fails with an [`InvalidInput`][std::io::ErrorKind::InvalidInput]
+    /// error naming the chunk, as all offsets past it would be wrong.
     pub struct Chunk<W> {
         chunks_to_write: VecDeque<index::Entry>,
         inner: W,
@@ -36,6 +39,19 @@ mod write_chunk {
         W: std::io::Write,
     {
         fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if let Some(entry) = &self.next_chunk {
+                if self.written_bytes as u64 + buf.len() as u64 > entry.offset.end {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!(
+                            "Chunk {:?} was planned with {} bytes, but {} bytes were written to it",
+                            std::str::from_utf8(&entry.kind).unwrap_or("<non-ascii>"),
+                            entry.offset.end,
+                            self.written_bytes + buf.len()
+                        ),
+                    ));
+                }
+            }
             let written = self.inner.write(buf)?;
             self.written_bytes += written;
             Ok(written)
@@ -9,6 +9,38 @@ pub type Id = [u8; 4];
 /// A special value denoting the end of the chunk file table of contents.
 pub const SENTINEL: Id = [0u8; 4];
 
+/// The ids of the chunks of the formats built on chunk files, to avoid each implementation spelling them out.
+pub mod id {
+    use crate::Id;
+
+    /// The fan-out table of object ids, used in commit-graph and multi-pack-index files.
+    pub const OID_FANOUT: Id = *b"OIDF";
+    /// The sorted list of object ids, used in commit-graph and multi-pack-index files.
+    pub const OID_LOOKUP: Id = *b"OIDL";
+
+    /// The tree, parents, generation and commit time of each commit in a commit-graph file.
+    pub const COMMIT_DATA: Id = *b"CDAT";
+    /// The corrected commit dates of each commit in a commit-graph file.
+    ///
+    /// It replaces the `GDAT` chunk, which git stopped writing after a bug was found in its implementation.
+    pub const GENERATION_DATA: Id = *b"GDA2";
+    /// The corrected commit date offsets which don't fit into the [`GENERATION_DATA`] chunk of a commit-graph file.
+    ///
+    /// It replaces the `GDOV` chunk, which git stopped writing after a bug was found in its implementation.
+    pub const GENERATION_DATA_OVERFLOW: Id = *b"GDO2";
+    /// The parents of octopus merges beyond the first one in a commit-graph file.
+    pub const EXTENDED_EDGES: Id = *b"EDGE";
+    /// The checksums of the commit-graph files a commit-graph file in a chain builds upon.
+    pub const BASE_GRAPHS: Id = *b"BASE";
+
+    /// The names of the pack indices in a multi-pack-index file.
+    pub const PACK_NAMES: Id = *b"PNAM";
+    /// The pack id and offset of each object in a multi-pack-index file.
+    pub const OBJECT_OFFSETS: Id = *b"OOFF";
+    /// The offsets which don't fit into the [`OBJECT_OFFSETS`] chunk of a multi-pack-index file.
+    pub const LARGE_OFFSETS: Id = *b"LOFF";
+}
+
 ///
 pub mod range {
     use std::{convert::TryInto, ops::Range};
@@ -0,0 +1,210 @@
+use std::io::Write;
+
+use git_chunk::file::{decode::Error, Index};
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// A simple xorshift generator keeps these tests deterministic while exploring plenty of chunk layouts.
+fn rng(mut state: u64) -> impl FnMut() -> u64 {
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    }
+}
+
+/// Write a file with `header_len` bytes of header followed by the table of contents and all `chunks`, each filled with
+/// its own id byte.
+fn file_with(header_len: usize, chunks: &[(git_chunk::Id, u64)]) -> Result<Vec<u8>> {
+    let mut index = Index::for_writing();
+    for (id, size) in chunks {
+        index.plan_chunk(*id, *size);
+    }
+    let mut out = index.into_write(vec![0xff; header_len], header_len)?;
+    while let Some(id) = out.next_chunk() {
+        let size = chunks.iter().find(|(kind, _)| *kind == id).expect("planned").1;
+        out.write_all(&vec![id[0]; size as usize])?;
+    }
+    Ok(out.into_inner())
+}
+
+fn id(n: usize) -> git_chunk::Id {
+    [b'A' + n as u8, b'B', b'C', b'D']
+}
+
+fn assert_invariants(index: &Index, header_len: usize, num_chunks: usize, file_len: usize) {
+    let mut previous_end = (header_len + Index::size_for_entries(num_chunks)) as u64;
+    for n in 0..num_chunks {
+        let range = index.offset_by_id(id(n)).expect("all chunks are present");
+        assert!(range.start >= previous_end, "chunks are sorted and don't overlap");
+        assert!(range.start < range.end);
+        assert!(range.end <= file_len as u64, "chunks are in bounds");
+        previous_end = range.end;
+    }
+}
+
+#[test]
+fn written_chunks_can_be_decoded_with_automatically_computed_offsets() -> Result {
+    let mut next = rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..500 {
+        let header_len = (next() % 16) as usize;
+        let num_chunks = (next() % 8 + 1) as usize;
+        let chunks: Vec<_> = (0..num_chunks).map(|n| (id(n), next() % 64 + 1)).collect();
+        let data = file_with(header_len, &chunks)?;
+
+        let index = Index::from_bytes(&data, header_len, num_chunks as u32)?;
+        assert_eq!(
+            index.highest_offset(),
+            data.len() as u64,
+            "the last chunk ends the file"
+        );
+        assert_invariants(&index, header_len, num_chunks, data.len());
+        for (id, size) in chunks {
+            let chunk = index.data_by_id(&data, id)?;
+            assert_eq!(chunk.len() as u64, size);
+            assert!(
+                chunk.iter().all(|b| *b == id[0]),
+                "offsets point to the data of the chunk"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn offsets_are_computed_from_planned_sizes_no_matter_how_chunks_are_written() -> Result {
+    let mut next = rng(0xd1b5_4a32_d192_ed03);
+    for _ in 0..500 {
+        let header_len = (next() % 16) as usize;
+        let num_chunks = (next() % 8 + 1) as usize;
+        let chunks: Vec<_> = (0..num_chunks).map(|n| (id(n), next() % 64 + 1)).collect();
+        let mut index = Index::for_writing();
+        for (id, size) in &chunks {
+            index.plan_chunk(*id, *size);
+        }
+        assert_eq!(index.planned_storage_size(), chunks.iter().map(|c| c.1).sum::<u64>());
+
+        let mut out = index.into_write(vec![0xff; header_len], header_len)?;
+        while let Some(id) = out.next_chunk() {
+            let mut remaining = chunks.iter().find(|(kind, _)| *kind == id).expect("planned").1 as usize;
+            while remaining != 0 {
+                let len = (next() as usize % remaining) + 1;
+                out.write_all(&vec![id[0]; len])?;
+                remaining -= len;
+            }
+        }
+        let data = out.into_inner();
+
+        let toc = &data[header_len..][..Index::size_for_entries(num_chunks)];
+        let mut expected_offset = (header_len + toc.len()) as u64;
+        for ((id, size), entry) in chunks.iter().zip(toc.chunks(12)) {
+            assert_eq!(&entry[..4], id, "chunks are written in the order they were planned");
+            assert_eq!(
+                u64::from_be_bytes(entry[4..].try_into()?),
+                expected_offset,
+                "each chunk starts where the previous one ends"
+            );
+            expected_offset += size;
+        }
+        assert_eq!(expected_offset, data.len() as u64, "the sentinel offset ends the file");
+    }
+    Ok(())
+}
+
+#[test]
+fn writing_more_than_planned_is_an_error_naming_the_chunk() -> Result {
+    let mut index = Index::for_writing();
+    index.plan_chunk(id(0), 4);
+    let mut out = index.into_write(Vec::new(), 0)?;
+    assert_eq!(out.next_chunk(), Some(id(0)));
+    out.write_all(&[0; 3])?;
+    let err = out.write_all(&[0; 2]).expect_err("one byte too many");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "Chunk \"ABCD\" was planned with 4 bytes, but 5 bytes were written to it"
+    );
+    Ok(())
+}
+
+#[test]
+fn corrupt_tables_of_contents_never_panic_and_are_validated() -> Result {
+    let mut next = rng(0x9e37_79b9_7f4a_7c15);
+    let header_len = 8;
+    let num_chunks = 4;
+    let chunks: Vec<_> = (0..num_chunks).map(|n| (id(n), 16)).collect();
+    let valid = file_with(header_len, &chunks)?;
+    let toc_len = Index::size_for_entries(num_chunks);
+
+    for _ in 0..10_000 {
+        let mut data = valid.clone();
+        for _ in 0..(next() % 4 + 1) {
+            let pos = header_len + (next() as usize % toc_len);
+            data[pos] = next() as u8;
+        }
+        data.truncate(data.len() - (next() % 2) as usize * (next() as usize % data.len()));
+        let toc_offset = if next() % 8 == 0 {
+            next() as usize % (data.len() + 16)
+        } else {
+            header_len
+        };
+        if let Ok(index) = Index::from_bytes(&data, toc_offset, num_chunks as u32) {
+            assert!(index.highest_offset() <= data.len() as u64, "chunks are in bounds");
+            let toc_end = (toc_offset + toc_len) as u64;
+            for range in (0..num_chunks).filter_map(|n| index.offset_by_id(id(n)).ok()) {
+                assert!(range.start >= toc_end, "chunks don't overlap the table of contents");
+                assert!(range.start < range.end, "chunks are sorted");
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn chunks_overlapping_the_table_of_contents_are_rejected() -> Result {
+    let mut data = file_with(0, &[(id(0), 4)])?;
+    data[4..12].copy_from_slice(&4u64.to_be_bytes());
+    assert!(matches!(
+        Index::from_bytes(&data, 0, 1),
+        Err(Error::ChunkOverlapsTableOfContents {
+            kind,
+            offset: 4,
+            toc_end: 24
+        }) if kind == id(0)
+    ));
+    Ok(())
+}
+
+#[test]
+fn errors_name_the_chunk_id() -> Result {
+    let mut data = file_with(0, &[(id(0), 4), (id(1), 4)])?;
+    data[16..24].copy_from_slice(&100u64.to_be_bytes());
+    let err = Index::from_bytes(&data, 0, 2).err().expect("invalid");
+    assert_eq!(
+        err.to_string(),
+        "The offset 100 of chunk \"ABCD\" went past the file of length 44 - was it truncated?"
+    );
+
+    data[16..24].copy_from_slice(&40u64.to_be_bytes());
+    data[28..36].copy_from_slice(&40u64.to_be_bytes());
+    let err = Index::from_bytes(&data, 0, 2).err().expect("invalid");
+    assert_eq!(
+        err.to_string(),
+        "All chunk offsets must be incrementing, but chunk \"BBCD\" starts at 40 and ends at 40"
+    );
+    Ok(())
+}
+
+#[test]
+fn table_of_contents_out_of_bounds_is_an_error() -> Result {
+    let data = file_with(0, &[(id(0), 4)])?;
+    assert!(matches!(
+        Index::from_bytes(&data, data.len() + 1, 1),
+        Err(Error::TocTooSmall {
+            actual: 0,
+            expected: 24
+        })
+    ));
+    Ok(())
+}
@@ -41,12 +41,22 @@ pub enum Error {
     UnsupportedHashVersion(u8),
     #[error("Unsupported commit-graph file version: {0}")]
     UnsupportedVersion(u8),
-    #[error(transparent)]
-    ChunkFileDecode(#[from] git_chunk::file::decode::Error),
-    #[error(transparent)]
-    MissingChunk(#[from] git_chunk::file::index::offset_by_kind::Error),
-    #[error("Commit-graph chunk {:?} has invalid size: {msg}", .id.as_bstr())]
-    InvalidChunkSize { id: ChunkId, msg: String },
+    #[error("The table of contents of commit-graph file at '{}' is invalid: {err}", .path.display())]
+    ChunkFileDecode {
+        err: git_chunk::file::decode::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("{err} of commit-graph file at '{}'", .path.display())]
+    MissingChunk {
+        err: git_chunk::file::index::offset_by_kind::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("Commit-graph chunk {:?} in file at '{}' has invalid size: {msg}", .id.as_bstr(), .path.display())]
+    InvalidChunkSize {
+        id: ChunkId,
+        msg: String,
+        path: std::path::PathBuf,
+    },
 }
 
 const MIN_FILE_SIZE: usize = HEADER_LEN
@@ -111,7 +121,16 @@ impl TryFrom<&Path> for File {
         let base_graph_count = data[ofs];
         ofs += 1;
 
-        let chunks = git_chunk::file::Index::from_bytes(&data, ofs, chunk_count as u32)?;
+        let chunks = git_chunk::file::Index::from_bytes(&data, ofs, chunk_count as u32).map_err(|err| {
+            Error::ChunkFileDecode {
+                err,
+                path: path.to_owned(),
+            }
+        })?;
+        let missing_chunk = |err| Error::MissingChunk {
+            err,
+            path: path.to_owned(),
+        };
 
         let base_graphs_list_offset = chunks
             .validated_usize_offset_by_id(BASE_GRAPHS_LIST_CHUNK_ID, |chunk_range| {
@@ -124,6 +143,7 @@ impl TryFrom<&Path> for File {
                             chunk_size,
                             object_hash.len_in_bytes()
                         ),
+                        path: path.to_owned(),
                     });
                 }
                 let chunk_base_graph_count: u32 = (chunk_size / object_hash.len_in_bytes())
@@ -140,8 +160,8 @@ impl TryFrom<&Path> for File {
             .ok()
             .transpose()?;
 
-        let (commit_data_offset, commit_data_count) =
-            chunks.validated_usize_offset_by_id(COMMIT_DATA_CHUNK_ID, |chunk_range| {
+        let (commit_data_offset, commit_data_count) = chunks
+            .validated_usize_offset_by_id(COMMIT_DATA_CHUNK_ID, |chunk_range| {
                 let chunk_size = chunk_range.len();
 
                 let entry_size = object_hash.len_in_bytes() + COMMIT_DATA_ENTRY_SIZE_SANS_HASH;
@@ -149,6 +169,7 @@ impl TryFrom<&Path> for File {
                     return Err(Error::InvalidChunkSize {
                         id: COMMIT_DATA_CHUNK_ID,
                         msg: format!("chunk size {} is not a multiple of {}", chunk_size, entry_size),
+                        path: path.to_owned(),
                     });
                 }
                 Ok((
@@ -157,23 +178,27 @@ impl TryFrom<&Path> for File {
                         .try_into()
                         .expect("number of commits in CDAT chunk to fit in 32 bits"),
                 ))
-            })??;
+            })
+            .map_err(missing_chunk)??;
 
-        let fan_offset = chunks.validated_usize_offset_by_id(OID_FAN_CHUNK_ID, |chunk_range| {
-            let chunk_size = chunk_range.len();
+        let fan_offset = chunks
+            .validated_usize_offset_by_id(OID_FAN_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
 
-            let expected_size = 4 * FAN_LEN;
-            if chunk_size != expected_size {
-                return Err(Error::InvalidChunkSize {
-                    id: OID_FAN_CHUNK_ID,
-                    msg: format!("expected chunk length {}, got {}", expected_size, chunk_size),
-                });
-            }
-            Ok(chunk_range.start)
-        })??;
+                let expected_size = 4 * FAN_LEN;
+                if chunk_size != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: OID_FAN_CHUNK_ID,
+                        msg: format!("expected chunk length {}, got {}", expected_size, chunk_size),
+                        path: path.to_owned(),
+                    });
+                }
+                Ok(chunk_range.start)
+            })
+            .map_err(missing_chunk)??;
 
-        let (oid_lookup_offset, oid_lookup_count) =
-            chunks.validated_usize_offset_by_id(OID_LOOKUP_CHUNK_ID, |chunk_range| {
+        let (oid_lookup_offset, oid_lookup_count) = chunks
+            .validated_usize_offset_by_id(OID_LOOKUP_CHUNK_ID, |chunk_range| {
                 let chunk_size = chunk_range.len();
 
                 if chunk_size % object_hash.len_in_bytes() != 0 {
@@ -184,6 +209,7 @@ impl TryFrom<&Path> for File {
                             chunk_size,
                             object_hash.len_in_bytes()
                         ),
+                        path: path.to_owned(),
                     });
                 }
                 Ok((
@@ -192,7 +218,8 @@ impl TryFrom<&Path> for File {
                         .try_into()
                         .expect("number of commits in OIDL chunk to fit in 32 bits"),
                 ))
-            })??;
+            })
+            .map_err(missing_chunk)??;
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
@@ -206,10 +233,9 @@ impl TryFrom<&Path> for File {
         }
 
         if base_graph_count > 0 && base_graphs_list_offset.is_none() {
-            return Err(git_chunk::file::index::offset_by_kind::Error {
+            return Err(missing_chunk(git_chunk::file::index::offset_by_kind::Error {
                 kind: BASE_GRAPHS_LIST_CHUNK_ID,
-            }
-            .into());
+            }));
         }
 
         let (fan, _) = read_fan(&data[fan_offset..]);
@@ -15,24 +15,26 @@ pub mod commit;
 mod init;
 pub mod verify;
 
-const COMMIT_DATA_ENTRY_SIZE_SANS_HASH: usize = 16;
-const FAN_LEN: usize = 256;
-const HEADER_LEN: usize = 8;
+pub(crate) const COMMIT_DATA_ENTRY_SIZE_SANS_HASH: usize = 16;
+pub(crate) const FAN_LEN: usize = 256;
+pub(crate) const HEADER_LEN: usize = 8;
 
-const SIGNATURE: &[u8] = b"CGPH";
+pub(crate) const SIGNATURE: &[u8] = b"CGPH";
 
-type ChunkId = git_chunk::Id;
-const BASE_GRAPHS_LIST_CHUNK_ID: ChunkId = *b"BASE";
-const COMMIT_DATA_CHUNK_ID: ChunkId = *b"CDAT";
-const EXTENDED_EDGES_LIST_CHUNK_ID: ChunkId = *b"EDGE";
-const OID_FAN_CHUNK_ID: ChunkId = *b"OIDF";
-const OID_LOOKUP_CHUNK_ID: ChunkId = *b"OIDL";
+pub(crate) type ChunkId = git_chunk::Id;
+const BASE_GRAPHS_LIST_CHUNK_ID: ChunkId = git_chunk::id::BASE_GRAPHS;
+pub(crate) const COMMIT_DATA_CHUNK_ID: ChunkId = git_chunk::id::COMMIT_DATA;
+pub(crate) const EXTENDED_EDGES_LIST_CHUNK_ID: ChunkId = git_chunk::id::EXTENDED_EDGES;
+pub(crate) const OID_FAN_CHUNK_ID: ChunkId = git_chunk::id::OID_FANOUT;
+pub(crate) const OID_LOOKUP_CHUNK_ID: ChunkId = git_chunk::id::OID_LOOKUP;
+pub(crate) const GENERATION_DATA_CHUNK_ID: ChunkId = git_chunk::id::GENERATION_DATA;
+pub(crate) const GENERATION_DATA_OVERFLOW_CHUNK_ID: ChunkId = git_chunk::id::GENERATION_DATA_OVERFLOW;
 
 // Note that git's commit-graph-format.txt as of v2.28.0 gives an incorrect value 0x0700_0000 for
 // NO_PARENT. Fixed in https://github.com/git/git/commit/4d515253afcef985e94400adbfed7044959f9121 .
-const NO_PARENT: u32 = 0x7000_0000;
-const EXTENDED_EDGES_MASK: u32 = 0x8000_0000;
-const LAST_EXTENDED_EDGE_MASK: u32 = 0x8000_0000;
+pub(crate) const NO_PARENT: u32 = 0x7000_0000;
+pub(crate) const EXTENDED_EDGES_MASK: u32 = 0x8000_0000;
+pub(crate) const LAST_EXTENDED_EDGE_MASK: u32 = 0x8000_0000;
 
 /// A single commit-graph file.
 ///
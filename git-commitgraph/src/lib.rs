@@ -7,6 +7,8 @@
 //! As generating the full commit graph from scratch can take some time, git may write new commits
 //! to separate [files][file::File] instead of overwriting the original file.
 //! Eventually, git will merge these files together as the number of files grows.
+//!
+//! Single-layer commit graphs can be created with [`write()`].
 //! ## Feature Flags
 #![cfg_attr(
     feature = "document-features",
@@ -17,8 +19,10 @@
 
 pub mod file;
 pub mod graph;
+pub mod write;
 
 pub use graph::Graph;
+pub use write::write;
 
 /// The number of generations that are considered 'infinite' commit history.
 pub const GENERATION_NUMBER_INFINITY: u32 = 0xffff_ffff;
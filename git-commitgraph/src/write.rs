@@ -0,0 +1,360 @@
+//! Write single-layer commit-graph files from commits in an object database.
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use git_hash::{oid, ObjectId};
+use git_object::{commit::ref_iter::Token, CommitRefIter};
+
+use crate::{
+    file::{
+        COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK,
+        FAN_LEN, GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID, HEADER_LEN, LAST_EXTENDED_EDGE_MASK,
+        NO_PARENT, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+/// The largest offset of a corrected commit date to the commit date that can be stored directly in the generation data chunk.
+const GENERATION_DATA_OFFSET_MAX: u64 = 0x7fff_ffff;
+/// Marks an entry in the generation data chunk as index into the generation data overflow chunk.
+const GENERATION_DATA_OVERFLOW_MASK: u32 = 0x8000_0000;
+
+/// The error returned by [`write()`][crate::write()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The commit {oid} could not be found")]
+    FindExisting {
+        oid: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("The commit {oid} could not be decoded")]
+    ObjectDecode {
+        oid: ObjectId,
+        source: git_object::decode::Error,
+    },
+    #[error("The commit {oid} lacks a tree or a committer")]
+    IncompleteCommit { oid: ObjectId },
+    #[error("The commit {oid} doesn't use the {expected:?} object hash of the commit-graph")]
+    ObjectHashMismatch { oid: ObjectId, expected: git_hash::Kind },
+    #[error("Commit-graph files can hold at most {} commits, but got {0}", MAX_COMMITS)]
+    TooManyCommits(usize),
+    #[error("Could not write commit-graph file at '{}'", .path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+}
+
+/// The kind of generation numbers to write.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum GenerationNumbers {
+    /// Only write topological levels, which are always stored alongside each commit.
+    V1,
+    /// Additionally write corrected commit dates, which is what `git` does by default.
+    V2,
+}
+
+impl Default for GenerationNumbers {
+    fn default() -> Self {
+        GenerationNumbers::V2
+    }
+}
+
+/// Options for use in [`write()`][crate::write()].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The kind of hash used by all commits.
+    pub object_hash: git_hash::Kind,
+    /// The kind of generation numbers to write.
+    pub generation_numbers: GenerationNumbers,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: git_hash::Kind::Sha1,
+            generation_numbers: Default::default(),
+        }
+    }
+}
+
+/// The result of [`write()`][crate::write()].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The path of the written commit-graph file.
+    pub path: PathBuf,
+    /// The trailing checksum of the commit-graph file.
+    pub checksum: ObjectId,
+    /// The amount of commits in the commit-graph file.
+    pub num_commits: u32,
+}
+
+/// A commit as needed to write the commit-graph file.
+struct Entry {
+    id: ObjectId,
+    tree: ObjectId,
+    parent_ids: Vec<ObjectId>,
+    commit_time: u64,
+}
+
+/// Write a single-layer commit-graph file into `out_dir`, typically `.git/objects/info`, containing `commits` and all of
+/// their ancestors, which are obtained by `find`, with the generation numbers selected by `options`.
+///
+/// The file is written to `commit-graph.lock` first and only moved to `commit-graph` once it is complete.
+/// Note that split commit-graphs in `out_dir/commit-graphs` aren't removed, and `git` will prefer the file written here.
+pub fn write<Find, E>(
+    commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+    mut find: Find,
+    out_dir: impl AsRef<Path>,
+    Options {
+        object_hash,
+        generation_numbers,
+    }: Options,
+) -> Result<Outcome, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut entries = Vec::new();
+    {
+        let mut seen = HashSet::new();
+        let mut next: Vec<ObjectId> = commits.into_iter().map(Into::into).collect();
+        let mut buf = Vec::new();
+        while let Some(id) = next.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            if id.kind() != object_hash {
+                return Err(Error::ObjectHashMismatch {
+                    oid: id,
+                    expected: object_hash,
+                });
+            }
+            let commit_iter = find(id.as_ref(), &mut buf).map_err(|err| Error::FindExisting {
+                oid: id,
+                source: err.into(),
+            })?;
+            let (mut tree, mut parent_ids, mut commit_time) = (None, Vec::new(), None);
+            for token in commit_iter {
+                match token.map_err(|err| Error::ObjectDecode { oid: id, source: err })? {
+                    Token::Tree { id } => tree = Some(id),
+                    Token::Parent { id } => parent_ids.push(id),
+                    Token::Author { .. } => {}
+                    Token::Committer { signature } => {
+                        // Times before the epoch can't be stored, so we use the earliest possible one instead.
+                        commit_time = Some(u64::try_from(signature.time.seconds_since_unix_epoch).unwrap_or(0));
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+            let (tree, commit_time) = tree.zip(commit_time).ok_or(Error::IncompleteCommit { oid: id })?;
+            next.extend(parent_ids.iter().filter(|id| !seen.contains(*id)).copied());
+            entries.push(Entry {
+                id,
+                tree,
+                parent_ids,
+                commit_time,
+            });
+        }
+    }
+    if entries.len() > MAX_COMMITS as usize {
+        return Err(Error::TooManyCommits(entries.len()));
+    }
+    entries.sort_by_key(|e| e.id);
+
+    let out_dir = out_dir.as_ref();
+    let path = out_dir.join("commit-graph");
+    let lock_path = out_dir.join("commit-graph.lock");
+    let io_err = |path: &Path| {
+        let path = path.to_owned();
+        move |source| Error::Io { source, path }
+    };
+    std::fs::create_dir_all(out_dir).map_err(io_err(out_dir))?;
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&lock_path)
+        .map_err(io_err(&lock_path))?;
+    let checksum = match write_file(&entries, generation_numbers, object_hash, file) {
+        Ok(checksum) => checksum,
+        Err(err) => {
+            std::fs::remove_file(&lock_path).ok();
+            return Err(io_err(&lock_path)(err));
+        }
+    };
+    std::fs::rename(&lock_path, &path).map_err(io_err(&path))?;
+
+    Ok(Outcome {
+        path,
+        checksum,
+        num_commits: entries.len() as u32,
+    })
+}
+
+fn write_file(
+    entries: &[Entry],
+    generation_numbers: GenerationNumbers,
+    object_hash: git_hash::Kind,
+    file: std::fs::File,
+) -> std::io::Result<ObjectId> {
+    let position = |id: &ObjectId| -> u32 {
+        entries
+            .binary_search_by_key(id, |e| e.id)
+            .expect("all parents were collected") as u32
+    };
+    let parents: Vec<Vec<u32>> = entries
+        .iter()
+        .map(|e| e.parent_ids.iter().map(position).collect())
+        .collect();
+    let (levels, corrected_dates) = generations(entries, &parents);
+
+    let num_extra_edges: usize = parents.iter().filter(|p| p.len() > 2).map(|p| p.len() - 1).sum();
+    let num_overflows = corrected_dates
+        .iter()
+        .zip(entries)
+        .filter(|(date, e)| *date - e.commit_time > GENERATION_DATA_OFFSET_MAX)
+        .count();
+
+    let hash_len = object_hash.len_in_bytes();
+    let mut cf = git_chunk::file::Index::for_writing();
+    cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+    cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (entries.len() * hash_len) as u64);
+    cf.plan_chunk(
+        COMMIT_DATA_CHUNK_ID,
+        (entries.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+    );
+    if generation_numbers == GenerationNumbers::V2 {
+        cf.plan_chunk(GENERATION_DATA_CHUNK_ID, (entries.len() * 4) as u64);
+        if num_overflows != 0 {
+            cf.plan_chunk(GENERATION_DATA_OVERFLOW_CHUNK_ID, (num_overflows * 8) as u64);
+        }
+    }
+    if num_extra_edges != 0 {
+        cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+    }
+
+    let mut out = git_features::hash::Write::new(std::io::BufWriter::new(file), object_hash);
+    out.write_all(SIGNATURE)?;
+    out.write_all(&[1 /* version */, object_hash as u8])?;
+    out.write_all(&[
+        u8::try_from(cf.num_chunks()).expect("BUG: wrote more than 256 chunks"),
+        0, /* base graphs */
+    ])?;
+
+    let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+    while let Some(chunk_to_write) = chunk_write.next_chunk() {
+        match chunk_to_write {
+            OID_FAN_CHUNK_ID => {
+                let mut fan = [0u32; FAN_LEN];
+                for e in entries {
+                    fan[e.id.first_byte() as usize] += 1;
+                }
+                let mut count = 0;
+                for slot in fan {
+                    count += slot;
+                    chunk_write.write_all(&count.to_be_bytes())?;
+                }
+            }
+            OID_LOOKUP_CHUNK_ID => {
+                for e in entries {
+                    chunk_write.write_all(e.id.as_slice())?;
+                }
+            }
+            COMMIT_DATA_CHUNK_ID => {
+                let mut extra_edge_index = 0;
+                for ((e, parents), level) in entries.iter().zip(&parents).zip(&levels) {
+                    let parent1 = parents.first().copied().unwrap_or(NO_PARENT);
+                    let parent2 = match parents.len() {
+                        0 | 1 => NO_PARENT,
+                        2 => parents[1],
+                        num_parents => {
+                            let edge = EXTENDED_EDGES_MASK | extra_edge_index;
+                            extra_edge_index += num_parents as u32 - 1;
+                            edge
+                        }
+                    };
+                    chunk_write.write_all(e.tree.as_slice())?;
+                    chunk_write.write_all(&parent1.to_be_bytes())?;
+                    chunk_write.write_all(&parent2.to_be_bytes())?;
+                    chunk_write
+                        .write_all(&(u64::from(*level) << 34 | (e.commit_time & 0x0003_ffff_ffff)).to_be_bytes())?;
+                }
+            }
+            GENERATION_DATA_CHUNK_ID => {
+                let mut overflow_index = 0;
+                for (date, e) in corrected_dates.iter().zip(entries) {
+                    let offset = date - e.commit_time;
+                    let value = if offset > GENERATION_DATA_OFFSET_MAX {
+                        overflow_index += 1;
+                        GENERATION_DATA_OVERFLOW_MASK | (overflow_index - 1)
+                    } else {
+                        offset as u32
+                    };
+                    chunk_write.write_all(&value.to_be_bytes())?;
+                }
+            }
+            GENERATION_DATA_OVERFLOW_CHUNK_ID => {
+                for (date, e) in corrected_dates.iter().zip(entries) {
+                    let offset = date - e.commit_time;
+                    if offset > GENERATION_DATA_OFFSET_MAX {
+                        chunk_write.write_all(&offset.to_be_bytes())?;
+                    }
+                }
+            }
+            EXTENDED_EDGES_LIST_CHUNK_ID => {
+                for parents in parents.iter().filter(|p| p.len() > 2) {
+                    let (last, edges) = parents[1..].split_last().expect("more than two parents");
+                    for edge in edges {
+                        chunk_write.write_all(&edge.to_be_bytes())?;
+                    }
+                    chunk_write.write_all(&(LAST_EXTENDED_EDGE_MASK | last).to_be_bytes())?;
+                }
+            }
+            unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+        }
+    }
+
+    let (mut out, checksum) = out.into_inner_and_digest();
+    out.write_all(checksum.as_slice())?;
+    out.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+    Ok(checksum)
+}
+
+/// Compute the topological level and the corrected commit date of each of `entries`, with `parents` holding the
+/// positions of each entry's parents.
+fn generations(entries: &[Entry], parents: &[Vec<u32>]) -> (Vec<u32>, Vec<u64>) {
+    const UNKNOWN: u32 = 0;
+    let mut levels = vec![UNKNOWN; entries.len()];
+    let mut corrected_dates = vec![0u64; entries.len()];
+    let mut stack = Vec::new();
+    for start in 0..entries.len() {
+        if levels[start] != UNKNOWN {
+            continue;
+        }
+        stack.push(start);
+        while let Some(&pos) = stack.last() {
+            let len_before = stack.len();
+            stack.extend(
+                parents[pos]
+                    .iter()
+                    .map(|p| *p as usize)
+                    .filter(|p| levels[*p] == UNKNOWN),
+            );
+            if stack.len() != len_before {
+                continue;
+            }
+            stack.pop();
+            let (max_level, max_date) = parents[pos].iter().fold((0, 0), |(level, date), p| {
+                let p = *p as usize;
+                (levels[p].max(level), corrected_dates[p].max(date))
+            });
+            levels[pos] = (max_level + 1).min(GENERATION_NUMBER_MAX);
+            corrected_dates[pos] = entries[pos].commit_time.max(max_date + 1);
+        }
+    }
+    (levels, corrected_dates)
+}
@@ -0,0 +1,108 @@
+use std::process::Command;
+
+use git_commitgraph::{
+    write::{GenerationNumbers, Options},
+    Graph,
+};
+use git_odb::FindExt;
+
+use crate::{check_common, inspect_refs};
+
+fn write_and_verify(script_name: &str, refs: &[&'static str], generation_numbers: GenerationNumbers) -> crate::Result {
+    let repo = git_testtools::scripted_fixture_repo_writable(script_name)?;
+    let info_dir = repo.path().join(".git").join("objects").join("info");
+    let commit_data = |graph: &Graph| {
+        graph
+            .iter_commits()
+            .map(|c| (c.id().to_owned(), c.generation(), c.committer_timestamp()))
+            .collect::<Vec<_>>()
+    };
+    let expected = commit_data(&Graph::from_info_dir(&info_dir)?);
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+
+    let refs = inspect_refs(repo.path(), refs);
+    let odb = git_odb::at(repo.path().join(".git").join("objects"))?;
+    let outcome = git_commitgraph::write(
+        refs.values().map(|r| r.id().to_owned()),
+        |id, buf| odb.find_commit_iter(id, buf),
+        &info_dir,
+        Options {
+            generation_numbers,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.path, info_dir.join("commit-graph"));
+    assert_eq!(outcome.num_commits as usize, refs.len());
+    assert!(
+        !info_dir.join("commit-graph.lock").exists(),
+        "the lock is moved into place"
+    );
+
+    let cg = Graph::from_info_dir(&info_dir)?;
+    check_common(&cg, &refs);
+    assert_eq!(
+        commit_data(&cg),
+        expected,
+        "generation numbers and timestamps match what git wrote"
+    );
+    cg.verify_integrity(|_| Ok::<_, std::io::Error>(()))?;
+
+    let output = Command::new("git")
+        .args(["commit-graph", "verify", "--no-progress"])
+        .current_dir(repo.path())
+        .env_remove("GIT_DIR")
+        .output()?;
+    assert!(
+        output.status.success(),
+        "git accepts our commit-graph: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+#[test]
+fn single_parent() -> crate::Result {
+    for generation_numbers in [GenerationNumbers::V1, GenerationNumbers::V2] {
+        write_and_verify("single_parent.sh", &["parent", "child"], generation_numbers)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn octopus_merges_use_the_extra_edges_list() -> crate::Result {
+    for generation_numbers in [GenerationNumbers::V1, GenerationNumbers::V2] {
+        write_and_verify(
+            "octopus_merges.sh",
+            &[
+                "root",
+                "parent1",
+                "parent2",
+                "parent3",
+                "parent4",
+                "three_parents",
+                "four_parents",
+            ],
+            generation_numbers,
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn existing_lock_files_prevent_writing() -> crate::Result {
+    let repo = git_testtools::scripted_fixture_repo_writable("single_parent.sh")?;
+    let info_dir = repo.path().join(".git").join("objects").join("info");
+    std::fs::write(info_dir.join("commit-graph.lock"), b"")?;
+    let odb = git_odb::at(repo.path().join(".git").join("objects"))?;
+    let refs = inspect_refs(repo.path(), &["child"]);
+
+    let err = git_commitgraph::write(
+        refs.values().map(|r| r.id().to_owned()),
+        |id, buf| odb.find_commit_iter(id, buf),
+        &info_dir,
+        Options::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, git_commitgraph::write::Error::Io { .. }));
+    Ok(())
+}
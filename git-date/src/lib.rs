@@ -17,12 +17,15 @@ pub mod time;
 pub mod parse;
 pub use parse::function::parse;
 
+/// The amount of seconds since unix epoch, which is negative for times before it.
+pub type SecondsSinceUnixEpoch = i64;
+
 /// A timestamp with timezone.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Time {
     /// time in seconds since epoch.
-    pub seconds_since_unix_epoch: u32,
+    pub seconds_since_unix_epoch: SecondsSinceUnixEpoch,
     /// time offset in seconds, may be negative to match the `sign` field.
     pub offset_in_seconds: i32,
     /// the sign of `offset`, used to encode `-0000` which would otherwise loose sign information.
@@ -5,14 +5,12 @@ pub enum Error {
     TooEarly { timestamp: i64 },
     #[error("Date string can not be parsed")]
     InvalidDateString,
-    #[error("Dates past 2038 can not be represented.")]
-    InvalidDate(#[from] std::num::TryFromIntError),
     #[error("Current time is missing.")]
     MissingCurrentTime,
 }
 
 pub(crate) mod function {
-    use std::{convert::TryInto, str::FromStr, time::SystemTime};
+    use std::{str::FromStr, time::SystemTime};
 
     use time::{Date, OffsetDateTime};
 
@@ -22,7 +20,7 @@ pub(crate) mod function {
             format::{DEFAULT, ISO8601, ISO8601_STRICT, RFC2822, SHORT},
             Sign,
         },
-        Time,
+        SecondsSinceUnixEpoch, Time,
     };
 
     #[allow(missing_docs)]
@@ -34,18 +32,18 @@ pub(crate) mod function {
 
         Ok(if let Ok(val) = Date::parse(input, SHORT) {
             let val = val.with_hms(0, 0, 0).expect("date is in range").assume_utc();
-            Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
+            Time::new(val.unix_timestamp(), val.offset().whole_seconds())
         } else if let Ok(val) = OffsetDateTime::parse(input, RFC2822) {
-            Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
+            Time::new(val.unix_timestamp(), val.offset().whole_seconds())
         } else if let Ok(val) = OffsetDateTime::parse(input, ISO8601) {
-            Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
+            Time::new(val.unix_timestamp(), val.offset().whole_seconds())
         } else if let Ok(val) = OffsetDateTime::parse(input, ISO8601_STRICT) {
-            Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
+            Time::new(val.unix_timestamp(), val.offset().whole_seconds())
         } else if let Ok(val) = OffsetDateTime::parse(input, DEFAULT) {
-            Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
+            Time::new(val.unix_timestamp(), val.offset().whole_seconds())
         } else if let Ok(val) = u32::from_str(input) {
             // Format::Unix
-            Time::new(val, 0)
+            Time::new(val.into(), 0)
         } else if let Some(val) = parse_raw(input) {
             // Format::Raw
             val
@@ -56,18 +54,18 @@ pub(crate) mod function {
         })
     }
 
-    fn timestamp(date: OffsetDateTime) -> Result<u32, Error> {
+    fn timestamp(date: OffsetDateTime) -> Result<SecondsSinceUnixEpoch, Error> {
         let timestamp = date.unix_timestamp();
         if timestamp < 0 {
             Err(Error::TooEarly { timestamp })
         } else {
-            Ok(timestamp.try_into()?)
+            Ok(timestamp)
         }
     }
 
     fn parse_raw(input: &str) -> Option<Time> {
         let mut split = input.split_whitespace();
-        let seconds_since_unix_epoch: u32 = split.next()?.parse().ok()?;
+        let seconds_since_unix_epoch: SecondsSinceUnixEpoch = split.next()?.parse().ok()?;
         let offset = split.next()?;
         if offset.len() != 5 {
             return None;
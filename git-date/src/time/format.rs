@@ -1,6 +1,9 @@
 use time::{format_description::FormatItem, macros::format_description};
 
-use crate::{time::Format, Time};
+use crate::{
+    time::{Format, Sign},
+    Time,
+};
 
 /// E.g. `2018-12-24`
 pub const SHORT: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
@@ -47,22 +50,79 @@ impl Time {
     ///
     /// Use the [`format_description`](https://time-rs.github.io/book/api/format-description.html) macro to create and
     /// validate formats at compile time, courtesy of the [`time`] crate.
+    ///
+    /// Custom formats can only show times the [`time`] crate can represent, which typically are those in the years 1 to 9999.
+    /// All other times are shown as [ISO8601][Time::format_iso8601()] instead.
     pub fn format<'a>(&self, format: impl Into<Format<'a>>) -> String {
         match format.into() {
-            Format::Custom(format) => self
-                .to_time()
-                .format(&format)
-                .expect("well-known format into memory never fails"),
+            Format::Custom(format) => match self.to_time() {
+                Some(time) => time.format(&format).expect("well-known format into memory never fails"),
+                None => self.format_iso8601(),
+            },
             Format::Unix => self.seconds_since_unix_epoch.to_string(),
             Format::Raw => self.to_bstring().to_string(),
         }
     }
+
+    /// Format this instance like `git log --date=iso` would, e.g. `2022-08-17 22:04:58 +0200`, showing the time in the timezone
+    /// of our offset.
+    ///
+    /// Unlike [`format()`][Time::format()], this works for all times we can represent, including years before 1 or after 9999,
+    /// which is why the date is computed here instead of by the [`time`] crate.
+    pub fn format_iso8601(&self) -> String {
+        let local = self
+            .seconds_since_unix_epoch
+            .saturating_add(self.offset_in_seconds.into());
+        let (year, month, day) = civil_from_days(local.div_euclid(86400));
+        let seconds_of_day = local.rem_euclid(86400);
+        let offset = self.offset_in_seconds.unsigned_abs();
+        format!(
+            "{year}-{month:02}-{day:02} {:02}:{:02}:{:02} {}{:02}{:02}",
+            seconds_of_day / 3600,
+            seconds_of_day % 3600 / 60,
+            seconds_of_day % 60,
+            match self.sign {
+                Sign::Plus => '+',
+                Sign::Minus => '-',
+            },
+            offset / 3600,
+            offset % 3600 / 60,
+            year = if year < 0 {
+                format!("-{:04}", year.unsigned_abs())
+            } else {
+                format!("{:04}", year)
+            },
+        )
+    }
+}
+
+/// Return `(year, month, day)` in the proleptic gregorian calendar for the given amount of `days` since unix epoch.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days> for how it works.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
 }
 
 impl Time {
-    fn to_time(self) -> time::OffsetDateTime {
-        time::OffsetDateTime::from_unix_timestamp(self.seconds_since_unix_epoch as i64)
-            .expect("always valid unix time")
-            .replace_offset(time::UtcOffset::from_whole_seconds(self.offset_in_seconds).expect("valid offset"))
+    /// Return this time as understood by the [`time`] crate, or `None` if it's out of its range.
+    fn to_time(self) -> Option<time::OffsetDateTime> {
+        Some(
+            time::OffsetDateTime::from_unix_timestamp(self.seconds_since_unix_epoch)
+                .ok()?
+                .replace_offset(time::UtcOffset::from_whole_seconds(self.offset_in_seconds).ok()?),
+        )
     }
 }
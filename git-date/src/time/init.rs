@@ -1,11 +1,11 @@
-use std::{convert::TryInto, ops::Sub};
+use std::ops::Sub;
 
-use crate::{time::Sign, Time};
+use crate::{time::Sign, SecondsSinceUnixEpoch, Time};
 
 /// Instantiation
 impl Time {
     /// Create a new instance from seconds and offset.
-    pub fn new(seconds_since_unix_epoch: u32, offset_in_seconds: i32) -> Self {
+    pub fn new(seconds_since_unix_epoch: SecondsSinceUnixEpoch, offset_in_seconds: i32) -> Self {
         Time {
             seconds_since_unix_epoch,
             offset_in_seconds,
@@ -17,9 +17,7 @@ impl Time {
     pub fn now_utc() -> Self {
         let seconds_since_unix_epoch = time::OffsetDateTime::now_utc()
             .sub(std::time::SystemTime::UNIX_EPOCH)
-            .whole_seconds()
-            .try_into()
-            .expect("this is not year 2038");
+            .whole_seconds();
         Self {
             seconds_since_unix_epoch,
             offset_in_seconds: 0,
@@ -30,11 +28,7 @@ impl Time {
     /// Return the current local time, or `None` if the local time wasn't available.
     pub fn now_local() -> Option<Self> {
         let now = time::OffsetDateTime::now_utc();
-        let seconds_since_unix_epoch = now
-            .sub(std::time::SystemTime::UNIX_EPOCH)
-            .whole_seconds()
-            .try_into()
-            .expect("this is not year 2038");
+        let seconds_since_unix_epoch = now.sub(std::time::SystemTime::UNIX_EPOCH).whole_seconds();
         // TODO: make this work without cfg(unsound_local_offset), see
         //       https://github.com/time-rs/time/issues/293#issuecomment-909158529
         let offset = time::UtcOffset::local_offset_at(now).ok()?;
@@ -49,11 +43,7 @@ impl Time {
     /// Return the current local time, or the one at UTC if the local time wasn't available.
     pub fn now_local_or_utc() -> Self {
         let now = time::OffsetDateTime::now_utc();
-        let seconds_since_unix_epoch = now
-            .sub(std::time::SystemTime::UNIX_EPOCH)
-            .whole_seconds()
-            .try_into()
-            .expect("this is not year 2038");
+        let seconds_since_unix_epoch = now.sub(std::time::SystemTime::UNIX_EPOCH).whole_seconds();
         // TODO: make this work without cfg(unsound_local_offset), see
         //       https://github.com/time-rs/time/issues/293#issuecomment-909158529
         let offset_in_seconds = time::UtcOffset::local_offset_at(now)
@@ -1,4 +1,4 @@
-use crate::Time;
+use crate::{SecondsSinceUnixEpoch, Time};
 
 /// Access
 impl Time {
@@ -8,9 +8,67 @@ impl Time {
     }
 
     /// Return the passed seconds since epoch since this signature was made.
-    pub fn seconds(&self) -> u32 {
+    pub fn seconds(&self) -> SecondsSinceUnixEpoch {
         self.seconds_since_unix_epoch
     }
+
+    /// Return our seconds if they are within `range`, or handle them according to `policy` otherwise.
+    ///
+    /// This is useful for storing times in formats or types which can't represent all of them.
+    pub fn seconds_within(
+        &self,
+        range: std::ops::RangeInclusive<SecondsSinceUnixEpoch>,
+        policy: OutOfRange,
+    ) -> Result<SecondsSinceUnixEpoch, out_of_range::Error> {
+        let seconds = self.seconds_since_unix_epoch;
+        if range.contains(&seconds) {
+            return Ok(seconds);
+        }
+        match policy {
+            OutOfRange::Clamp => Ok(seconds.clamp(*range.start(), *range.end())),
+            OutOfRange::Fail => Err(out_of_range::Error {
+                seconds,
+                start: *range.start(),
+                end: *range.end(),
+            }),
+        }
+    }
+
+    /// Return the point in time we represent, or `None` if it can't be represented by the system time of this platform.
+    pub fn to_system_time(&self) -> Option<std::time::SystemTime> {
+        let seconds = std::time::Duration::from_secs(self.seconds_since_unix_epoch.unsigned_abs());
+        if self.seconds_since_unix_epoch < 0 {
+            std::time::UNIX_EPOCH.checked_sub(seconds)
+        } else {
+            std::time::UNIX_EPOCH.checked_add(seconds)
+        }
+    }
+}
+
+/// What to do with times which can't be represented where they are supposed to be used, like times before the unix epoch
+/// or far in the future.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutOfRange {
+    /// Use the closest time that can be represented.
+    Clamp,
+    /// Fail with an error.
+    Fail,
+}
+
+///
+pub mod out_of_range {
+    use crate::SecondsSinceUnixEpoch;
+
+    /// The error returned by [`Time::seconds_within()`][crate::Time::seconds_within()].
+    #[derive(Debug, thiserror::Error)]
+    #[error("The time at {seconds} seconds since unix epoch is outside of the range from {start} to {end}")]
+    #[allow(missing_docs)]
+    pub struct Error {
+        pub seconds: SecondsSinceUnixEpoch,
+        pub start: SecondsSinceUnixEpoch,
+        pub end: SecondsSinceUnixEpoch,
+    }
 }
 
 /// Indicates if a number is positive or negative for use in [`Time`].
@@ -42,28 +42,13 @@ impl Time {
 
     /// Computes the number of bytes necessary to render this time.
     pub fn size(&self) -> usize {
-        // TODO: this is not year 2038 safe…but we also can't parse larger numbers (or represent them) anyway. It's a trap nonetheless
-        //       that can be fixed by increasing the size to usize.
-        (if self.seconds_since_unix_epoch >= 1_000_000_000 {
-            10
-        } else if self.seconds_since_unix_epoch >= 100_000_000 {
-            9
-        } else if self.seconds_since_unix_epoch >= 10_000_000 {
-            8
-        } else if self.seconds_since_unix_epoch >= 1_000_000 {
-            7
-        } else if self.seconds_since_unix_epoch >= 100_000 {
-            6
-        } else if self.seconds_since_unix_epoch >= 10_000 {
-            5
-        } else if self.seconds_since_unix_epoch >= 1_000 {
-            4
-        } else if self.seconds_since_unix_epoch >= 100 {
-            3
-        } else if self.seconds_since_unix_epoch >= 10 {
-            2
-        } else {
-            1
-        }) + 2 /*space + sign*/ + 2 /*hours*/ + 2 /*minutes*/
+        let mut seconds = self.seconds_since_unix_epoch.unsigned_abs();
+        let mut digits = 1;
+        while seconds >= 10 {
+            seconds /= 10;
+            digits += 1;
+        }
+        usize::from(self.seconds_since_unix_epoch < 0) + digits + 2 /*space + sign*/ + 2 /*hours*/ + 2
+        /*minutes*/
     }
 }
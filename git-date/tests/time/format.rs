@@ -28,6 +28,53 @@ fn iso8601() {
     assert_eq!(time().format(format::ISO8601), "1973-11-29 21:33:09 +0230");
 }
 
+#[test]
+fn iso8601_shows_the_time_at_the_offset_for_all_years() {
+    assert_eq!(time().format_iso8601(), "1973-11-30 00:03:09 +0230");
+    for (seconds, offset_in_seconds, sign, expected) in [
+        (0, 0, Sign::Plus, "1970-01-01 00:00:00 +0000"),
+        (-1, -1800, Sign::Minus, "1969-12-31 23:29:59 -0030"),
+        (951_782_400, 0, Sign::Plus, "2000-02-29 00:00:00 +0000"),
+        (884_541_340_800, 0, Sign::Plus, "30000-01-01 00:00:00 +0000"),
+        (-62_198_755_200, 0, Sign::Plus, "-0001-01-01 00:00:00 +0000"),
+    ] {
+        let time = Time {
+            seconds_since_unix_epoch: seconds,
+            offset_in_seconds,
+            sign,
+        };
+        assert_eq!(time.format_iso8601(), expected);
+    }
+}
+
+#[test]
+fn custom_formats_fall_back_to_iso8601_for_times_out_of_range() {
+    for seconds in [884_541_340_800, -700_000_000_000, i64::MAX, i64::MIN] {
+        let time = Time {
+            seconds_since_unix_epoch: seconds,
+            offset_in_seconds: 0,
+            sign: Sign::Plus,
+        };
+        assert_eq!(time.format(format::RFC2822), time.format_iso8601());
+        assert_eq!(
+            time.format(format::RAW),
+            time.to_bstring().to_string(),
+            "other formats are unaffected"
+        );
+    }
+
+    let time = Time {
+        seconds_since_unix_epoch: 0,
+        offset_in_seconds: 30 * 3600,
+        sign: Sign::Plus,
+    };
+    assert_eq!(
+        time.format(format::ISO8601_STRICT),
+        "1970-01-02 06:00:00 +3000",
+        "offsets out of range fall back as well"
+    );
+}
+
 #[test]
 fn iso8601_strict() {
     assert_eq!(time().format(format::ISO8601_STRICT), "1973-11-29T21:33:09+02:30");
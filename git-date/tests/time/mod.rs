@@ -1,5 +1,8 @@
 use bstr::ByteSlice;
-use git_date::{time::Sign, Time};
+use git_date::{
+    time::{OutOfRange, Sign},
+    Time,
+};
 
 mod format;
 mod parse;
@@ -14,6 +17,32 @@ fn is_set() {
     .is_set());
 }
 
+#[test]
+fn to_system_time() {
+    for seconds in [0, -1234567890, 884541340800] {
+        let time = Time::new(seconds, 0).to_system_time().expect("representable");
+        let expected = if seconds < 0 {
+            std::time::UNIX_EPOCH - std::time::Duration::from_secs(seconds.unsigned_abs())
+        } else {
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(seconds.unsigned_abs())
+        };
+        assert_eq!(time, expected);
+    }
+}
+
+#[test]
+fn seconds_within() {
+    let range = 0..=100;
+    for (seconds, clamped) in [(-1, 0), (0, 0), (100, 100), (101, 100)] {
+        let time = Time::new(seconds, 0);
+        assert_eq!(time.seconds_within(range.clone(), OutOfRange::Clamp).unwrap(), clamped);
+        assert_eq!(
+            time.seconds_within(range.clone(), OutOfRange::Fail).ok(),
+            (seconds == clamped).then(|| seconds)
+        );
+    }
+}
+
 #[test]
 fn write_to() -> Result<(), Box<dyn std::error::Error>> {
     for (time, expected) in &[
@@ -41,10 +70,27 @@ fn write_to() -> Result<(), Box<dyn std::error::Error>> {
             },
             "0 -0000",
         ),
+        (
+            Time {
+                seconds_since_unix_epoch: -1234567890,
+                offset_in_seconds: -9000,
+                sign: Sign::Minus,
+            },
+            "-1234567890 -0230",
+        ),
+        (
+            Time {
+                seconds_since_unix_epoch: 884541340800,
+                offset_in_seconds: 0,
+                sign: Sign::Plus,
+            },
+            "884541340800 +0000",
+        ),
     ] {
         let mut output = Vec::new();
         time.write_to(&mut output)?;
         assert_eq!(output.as_bstr(), expected);
+        assert_eq!(time.size(), output.len());
     }
     Ok(())
 }
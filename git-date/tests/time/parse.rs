@@ -1,12 +1,12 @@
 use std::{collections::HashMap, time::SystemTime};
 
 use bstr::{BString, ByteSlice};
-use git_date::{time::Sign, Time};
+use git_date::{time::Sign, SecondsSinceUnixEpoch, Time};
 use once_cell::sync::Lazy;
 
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-static BASELINE: Lazy<HashMap<BString, (usize, u32)>> = Lazy::new(|| {
+static BASELINE: Lazy<HashMap<BString, (usize, SecondsSinceUnixEpoch)>> = Lazy::new(|| {
     let base = git_testtools::scripted_fixture_repo_read_only("generate_git_date_baseline.sh").unwrap();
 
     (|| -> Result<_> {
@@ -15,7 +15,7 @@ static BASELINE: Lazy<HashMap<BString, (usize, u32)>> = Lazy::new(|| {
         let mut lines = baseline.lines();
         while let Some(date_str) = lines.next() {
             let exit_code = lines.next().expect("three lines per baseline").to_str()?.parse()?;
-            let output: u32 = lines
+            let output: SecondsSinceUnixEpoch = lines
                 .next()
                 .expect("three lines per baseline")
                 .to_str()
@@ -1,10 +1,11 @@
-use std::{borrow::BorrowMut, collections::VecDeque};
+use std::borrow::BorrowMut;
 
 use git_hash::{oid, ObjectId};
+use git_object::bstr::BString;
 
 use crate::{
     tree,
-    tree::{visit::Change, TreeInfoPair},
+    tree::{visit::Change, Queue},
 };
 
 /// The error returned by [tree::Changes::needed_to_obtain()].
@@ -20,6 +21,10 @@ pub enum Error {
     Cancelled,
     #[error(transparent)]
     EntriesDecode(#[from] git_object::decode::Error),
+    #[error("The tree at '{path}' is nested deeper than the maximum of {max} levels")]
+    DepthLimitExceeded { path: BString, max: usize },
+    #[error("The path of the tree at '{path}' is longer than the maximum of {max} bytes")]
+    PathLengthLimitExceeded { path: BString, max: usize },
 }
 
 impl<'a> tree::Changes<'a> {
@@ -41,6 +46,7 @@ impl<'a> tree::Changes<'a> {
     ///   by the delegate implementation which should be as specific as possible. Rename tracking can be computed on top of the changes
     ///   received by the `delegate`.
     /// * cycle checking is not performed, but can be performed in the delegate which can return [`tree::visit::Action::Cancel`] to stop the traversal.
+    /// * trees to descend into are checked against the [limits][tree::Limits] configured in `state` to fail on maliciously deep trees.
     /// * [std::mem::ManuallyDrop] is used because `Peekable` is needed. When using it as wrapper around our no-drop iterators, all of the sudden
     ///   borrowcheck complains as Drop is present (even though it's not)
     ///
@@ -128,7 +134,7 @@ impl<'a> tree::Changes<'a> {
 
 fn delete_entry_schedule_recursion<R: tree::Visit>(
     entry: git_object::tree::EntryRef<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue,
     delegate: &mut R,
 ) -> Result<(), Error> {
     delegate.push_path_component(entry.filename);
@@ -144,14 +150,14 @@ fn delete_entry_schedule_recursion<R: tree::Visit>(
     if entry.mode.is_tree() {
         delegate.pop_path_component();
         delegate.push_back_tracked_path_component(entry.filename);
-        queue.push_back((Some(entry.oid.to_owned()), None));
+        queue.push_back((Some(entry.oid.to_owned()), None), entry.filename)?;
     }
     Ok(())
 }
 
 fn add_entry_schedule_recursion<R: tree::Visit>(
     entry: git_object::tree::EntryRef<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue,
     delegate: &mut R,
 ) -> Result<(), Error> {
     delegate.push_path_component(entry.filename);
@@ -167,7 +173,7 @@ fn add_entry_schedule_recursion<R: tree::Visit>(
     if entry.mode.is_tree() {
         delegate.pop_path_component();
         delegate.push_back_tracked_path_component(entry.filename);
-        queue.push_back((None, Some(entry.oid.to_owned())))
+        queue.push_back((None, Some(entry.oid.to_owned())), entry.filename)?;
     }
     Ok(())
 }
@@ -175,7 +181,7 @@ fn catchup_rhs_with_lhs<R: tree::Visit>(
     rhs_entries: &mut IteratorType<git_object::TreeRefIter<'_>>,
     lhs: git_object::tree::EntryRef<'_>,
     rhs: git_object::tree::EntryRef<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue,
     delegate: &mut R,
 ) -> Result<(), Error> {
     use std::cmp::Ordering::*;
@@ -215,7 +221,7 @@ fn catchup_lhs_with_rhs<R: tree::Visit>(
     lhs_entries: &mut IteratorType<git_object::TreeRefIter<'_>>,
     lhs: git_object::tree::EntryRef<'_>,
     rhs: git_object::tree::EntryRef<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue,
     delegate: &mut R,
 ) -> Result<(), Error> {
     use std::cmp::Ordering::*;
@@ -254,7 +260,7 @@ fn catchup_lhs_with_rhs<R: tree::Visit>(
 fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
     lhs: git_object::tree::EntryRef<'_>,
     rhs: git_object::tree::EntryRef<'_>,
-    queue: &mut VecDeque<TreeInfoPair>,
+    queue: &mut Queue,
     delegate: &mut R,
 ) -> Result<(), Error> {
     use git_object::tree::EntryMode::*;
@@ -273,7 +279,7 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
             {
                 return Err(Error::Cancelled);
             }
-            queue.push_back((Some(lhs.oid.to_owned()), Some(rhs.oid.to_owned())));
+            queue.push_back((Some(lhs.oid.to_owned()), Some(rhs.oid.to_owned())), lhs.filename)?;
         }
         (lhs_mode, Tree) if lhs_mode.is_no_tree() => {
             delegate.push_back_tracked_path_component(lhs.filename);
@@ -295,7 +301,7 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
             {
                 return Err(Error::Cancelled);
             };
-            queue.push_back((None, Some(rhs.oid.to_owned())));
+            queue.push_back((None, Some(rhs.oid.to_owned())), lhs.filename)?;
         }
         (Tree, rhs_mode) if rhs_mode.is_no_tree() => {
             delegate.push_back_tracked_path_component(lhs.filename);
@@ -317,22 +323,28 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
             {
                 return Err(Error::Cancelled);
             };
-            queue.push_back((Some(lhs.oid.to_owned()), None));
+            queue.push_back((Some(lhs.oid.to_owned()), None), lhs.filename)?;
         }
         (lhs_non_tree, rhs_non_tree) => {
             delegate.push_path_component(lhs.filename);
             debug_assert!(lhs_non_tree.is_no_tree() && rhs_non_tree.is_no_tree());
-            if lhs.oid != rhs.oid
-                && delegate
-                    .visit(Change::Modification {
+            if lhs.oid != rhs.oid {
+                let change = if lhs_non_tree.is_commit() && rhs_non_tree.is_commit() {
+                    Change::SubmoduleChanged {
+                        old_commit: lhs.oid.to_owned(),
+                        new_commit: rhs.oid.to_owned(),
+                    }
+                } else {
+                    Change::Modification {
                         previous_entry_mode: lhs.mode,
                         previous_oid: lhs.oid.to_owned(),
                         entry_mode: rhs.mode,
                         oid: rhs.oid.to_owned(),
-                    })
-                    .cancelled()
-            {
-                return Err(Error::Cancelled);
+                    }
+                };
+                if delegate.visit(change).cancelled() {
+                    return Err(Error::Cancelled);
+                }
             }
         }
     };
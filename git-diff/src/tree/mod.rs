@@ -1,14 +1,19 @@
 use std::collections::VecDeque;
 
 use git_hash::ObjectId;
-use git_object::TreeRefIter;
+use git_object::{
+    bstr::{BStr, BString, ByteVec},
+    TreeRefIter,
+};
 
 /// The state required to visit [Changes] to be instantiated with `State::default()`.
 #[derive(Default, Clone)]
 pub struct State {
     buf1: Vec<u8>,
     buf2: Vec<u8>,
-    trees: VecDeque<TreeInfoPair>,
+    trees: Queue,
+    /// The limits to enforce when computing changes.
+    pub limits: Limits,
 }
 
 type TreeInfoPair = (Option<ObjectId>, Option<ObjectId>);
@@ -16,11 +21,82 @@ type TreeInfoPair = (Option<ObjectId>, Option<ObjectId>);
 impl State {
     fn clear(&mut self) {
         self.trees.clear();
+        self.trees.limits = self.limits;
         self.buf1.clear();
         self.buf2.clear();
     }
 }
 
+/// Limits which are enforced before descending into a pair of trees, to protect against maliciously crafted trees that are
+/// nested extremely deeply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum amount of components in the path of a tree to descend into, with trees in the root tree having a depth of 1.
+    pub max_depth: usize,
+    /// The maximum length in bytes of the path of a tree to descend into, including the `/` separating its components.
+    pub max_path_len: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_depth: 4096,
+            max_path_len: 64 * 1024,
+        }
+    }
+}
+
+/// The trees to compare next, along with their depth and path to enforce [`Limits`].
+#[derive(Default, Clone)]
+struct Queue {
+    trees: VecDeque<(TreeInfoPair, usize, BString)>,
+    /// The depth of the trees whose entries are currently compared.
+    depth: usize,
+    /// The path of the trees whose entries are currently compared.
+    path: BString,
+    limits: Limits,
+}
+
+impl Queue {
+    fn clear(&mut self) {
+        self.trees.clear();
+        self.depth = 0;
+        self.path.clear();
+    }
+
+    /// Schedule the trees of the entry named `filename` for comparison, or fail if that would exceed our limits.
+    fn push_back(&mut self, trees: TreeInfoPair, filename: &BStr) -> Result<(), changes::Error> {
+        let depth = self.depth + 1;
+        let mut path = BString::from(Vec::with_capacity(self.path.len() + 1 + filename.len()));
+        if !self.path.is_empty() {
+            path.push_str(&self.path);
+            path.push_byte(b'/');
+        }
+        path.push_str(filename);
+        if depth > self.limits.max_depth {
+            return Err(changes::Error::DepthLimitExceeded {
+                path,
+                max: self.limits.max_depth,
+            });
+        }
+        if path.len() > self.limits.max_path_len {
+            return Err(changes::Error::PathLengthLimitExceeded {
+                path,
+                max: self.limits.max_path_len,
+            });
+        }
+        self.trees.push_back((trees, depth, path));
+        Ok(())
+    }
+
+    fn pop_front(&mut self) -> Option<TreeInfoPair> {
+        let (trees, depth, path) = self.trees.pop_front()?;
+        self.depth = depth;
+        self.path = path;
+        Some(trees)
+    }
+}
+
 /// An iterator over changes of a tree, instantiated using `Changes::from(…)`.
 pub struct Changes<'a>(Option<TreeRefIter<'a>>);
 
@@ -32,6 +32,11 @@ pub enum Change {
 
         path: BString,
     },
+    SubmoduleChanged {
+        old_commit: ObjectId,
+        new_commit: ObjectId,
+        path: BString,
+    },
 }
 
 /// A [Visit][visit::Visit] implementation to record every observed change and keep track of the changed paths.
@@ -107,6 +112,11 @@ impl visit::Visit for Recorder {
                 oid,
                 path: self.path_clone(),
             },
+            SubmoduleChanged { old_commit, new_commit } => Change::SubmoduleChanged {
+                old_commit,
+                new_commit,
+                path: self.path_clone(),
+            },
         });
         visit::Action::Continue
     }
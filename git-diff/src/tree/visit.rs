@@ -31,6 +31,27 @@ pub enum Change {
         /// The object id after the modification.
         oid: ObjectId,
     },
+    /// A [gitlink][tree::EntryKind::Gitlink] was changed to point to a different commit of its submodule.
+    ///
+    /// These commits live in the repository of the submodule and can't be found in our object database.
+    SubmoduleChanged {
+        /// The commit of the submodule before the change.
+        old_commit: ObjectId,
+        /// The commit of the submodule after the change.
+        new_commit: ObjectId,
+    },
+}
+
+impl Change {
+    /// Return the kind of the entry after the change, or the kind of the deleted entry.
+    pub fn entry_kind(&self) -> tree::EntryKind {
+        match self {
+            Change::Addition { entry_mode, .. }
+            | Change::Deletion { entry_mode, .. }
+            | Change::Modification { entry_mode, .. } => entry_mode.kind(),
+            Change::SubmoduleChanged { .. } => tree::EntryKind::Gitlink,
+        }
+    }
 }
 
 /// What to do after a [Change] was [recorded][Visit::visit()].
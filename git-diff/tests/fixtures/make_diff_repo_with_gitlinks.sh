@@ -0,0 +1,18 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.autocrlf false
+
+git checkout -q -b main
+
+touch f
+git add f
+git update-index --add --cacheinfo 160000,1111111111111111111111111111111111111111,changed
+git update-index --add --cacheinfo 160000,2222222222222222222222222222222222222222,removed
+git commit -qm 'f and submodules added'
+
+git update-index --cacheinfo 160000,3333333333333333333333333333333333333333,changed
+git update-index --force-remove removed
+git update-index --add --cacheinfo 160000,4444444444444444444444444444444444444444,added
+git commit -qm 'submodules added, changed and removed'
@@ -611,5 +611,193 @@ mod changes {
             );
             Ok(())
         }
+
+        #[test]
+        fn gitlinks_are_added_changed_and_removed() -> crate::Result {
+            let db = git_odb::at(
+                git_testtools::scripted_fixture_repo_read_only("make_diff_repo_with_gitlinks.sh")?
+                    .join(".git")
+                    .join("objects"),
+            )?;
+            let all_commits = all_commits(&db);
+            let changes = diff_commits(&db, all_commits[0], &all_commits[1])?;
+            assert_eq!(
+                changes,
+                vec![
+                    Addition {
+                        entry_mode: EntryMode::Commit,
+                        oid: hex_to_id("4444444444444444444444444444444444444444"),
+                        path: "added".into()
+                    },
+                    SubmoduleChanged {
+                        old_commit: hex_to_id("1111111111111111111111111111111111111111"),
+                        new_commit: hex_to_id("3333333333333333333333333333333333333333"),
+                        path: "changed".into()
+                    },
+                    Deletion {
+                        entry_mode: EntryMode::Commit,
+                        oid: hex_to_id("2222222222222222222222222222222222222222"),
+                        path: "removed".into()
+                    }
+                ]
+            );
+
+            let mut kinds = Vec::new();
+            let mut buf = Vec::new();
+            let mut buf2 = Vec::new();
+            git_diff::tree::Changes::from(locate_tree_by_commit(&db, &all_commits[0], &mut buf).ok())
+                .needed_to_obtain(
+                    locate_tree_by_commit(&db, &all_commits[1], &mut buf2)?,
+                    git_diff::tree::State::default(),
+                    |oid, buf| {
+                        use git_odb::pack::FindExt;
+                        db.find(oid, buf)
+                            .map(|obj| obj.0.try_into_tree_iter().expect("only called for trees"))
+                    },
+                    &mut Kinds(&mut kinds),
+                )?;
+            assert_eq!(
+                kinds,
+                vec![git_object::tree::EntryKind::Gitlink; 3],
+                "gitlinks can be told apart from other entries in all changes"
+            );
+            Ok(())
+        }
+
+        struct Kinds<'a>(&'a mut Vec<git_object::tree::EntryKind>);
+
+        impl git_diff::tree::Visit for Kinds<'_> {
+            fn pop_front_tracked_path_and_set_current(&mut self) {}
+            fn push_back_tracked_path_component(&mut self, _component: &git_object::bstr::BStr) {}
+            fn push_path_component(&mut self, _component: &git_object::bstr::BStr) {}
+            fn pop_path_component(&mut self) {}
+            fn visit(&mut self, change: git_diff::tree::visit::Change) -> git_diff::tree::visit::Action {
+                self.0.push(change.entry_kind());
+                git_diff::tree::visit::Action::Continue
+            }
+        }
+    }
+
+    mod limits {
+        use std::collections::HashMap;
+
+        use git_diff::tree;
+        use git_hash::ObjectId;
+        use git_object::{
+            bstr::BString,
+            tree::{Entry, EntryMode},
+            Tree, TreeRefIter, WriteTo,
+        };
+        use git_odb::Write;
+
+        /// Synthesize `depth` trees named `name` nested in one another below a root tree, with the innermost one containing a
+        /// file with id `file_id`, and store the data of all trees by their id in `trees`. Return the id of the root tree.
+        fn deeply_nested_trees(
+            depth: usize,
+            name: &str,
+            file_id: ObjectId,
+            trees: &mut HashMap<ObjectId, Vec<u8>>,
+        ) -> crate::Result<ObjectId> {
+            let mut tree = Tree {
+                entries: vec![Entry {
+                    mode: EntryMode::Blob,
+                    filename: "file".into(),
+                    oid: file_id,
+                }],
+            };
+            for _ in 0..depth {
+                let id = git_odb::sink(git_hash::Kind::Sha1).write(&tree)?;
+                let mut data = Vec::new();
+                tree.write_to(&mut data)?;
+                trees.insert(id, data);
+                tree = Tree {
+                    entries: vec![Entry {
+                        mode: EntryMode::Tree,
+                        filename: name.into(),
+                        oid: id,
+                    }],
+                };
+            }
+            let root_id = git_odb::sink(git_hash::Kind::Sha1).write(&tree)?;
+            let mut data = Vec::new();
+            tree.write_to(&mut data)?;
+            trees.insert(root_id, data);
+            Ok(root_id)
+        }
+
+        /// Diff a chain of nested trees against the same chain with a different file, or against nothing if `with_lhs` is false.
+        fn diff(
+            depth: usize,
+            name: &str,
+            with_lhs: bool,
+            state: tree::State,
+        ) -> crate::Result<Result<tree::Recorder, tree::changes::Error>> {
+            let mut trees = HashMap::new();
+            let lhs_id = deeply_nested_trees(depth, name, ObjectId::null(git_hash::Kind::Sha1), &mut trees)?;
+            let rhs_id = deeply_nested_trees(depth, name, ObjectId::empty_tree(git_hash::Kind::Sha1), &mut trees)?;
+            let mut recorder = tree::Recorder::default();
+            let res = tree::Changes::from(with_lhs.then(|| TreeRefIter::from_bytes(&trees[&lhs_id]))).needed_to_obtain(
+                TreeRefIter::from_bytes(&trees[&rhs_id]),
+                state,
+                |oid, buf| {
+                    buf.clear();
+                    buf.extend_from_slice(trees.get(&oid.to_owned()).ok_or(std::fmt::Error)?);
+                    Ok::<_, std::fmt::Error>(TreeRefIter::from_bytes(buf))
+                },
+                &mut recorder,
+            );
+            Ok(res.map(|()| recorder))
+        }
+
+        fn path(components: usize, name: &str) -> BString {
+            vec![name; components].join("/").into()
+        }
+
+        #[test]
+        fn trees_nested_up_to_the_maximum_depth_are_compared() -> crate::Result {
+            for with_lhs in [true, false] {
+                let recorder = diff(4096, "d", with_lhs, Default::default())?.expect("within limits");
+                assert_eq!(recorder.records.len(), 4096 + 1, "all trees and the file changed");
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn trees_nested_deeper_than_the_maximum_depth_fail_with_their_path() -> crate::Result {
+            for with_lhs in [true, false] {
+                match diff(100_000, "d", with_lhs, Default::default())? {
+                    Err(tree::changes::Error::DepthLimitExceeded { path: actual, max }) => {
+                        assert_eq!(max, 4096);
+                        assert_eq!(actual, path(4097, "d"), "the path of the first tree that is too deep");
+                    }
+                    _ => unreachable!("the default limits protect against extremely deep trees"),
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn limits_are_configurable() -> crate::Result {
+            let mut state = tree::State::default();
+            state.limits.max_path_len = 10;
+            match diff(3, "abc", true, state.clone())? {
+                Err(tree::changes::Error::PathLengthLimitExceeded { path: actual, max }) => {
+                    assert_eq!(max, 10);
+                    assert_eq!(actual, path(3, "abc"), "'abc/abc/abc' is 11 bytes long");
+                }
+                _ => unreachable!("the path of the innermost tree is too long"),
+            }
+
+            state.limits = tree::Limits {
+                max_depth: 1,
+                max_path_len: usize::MAX,
+            };
+            assert!(matches!(
+                diff(2, "d", false, state.clone())?,
+                Err(tree::changes::Error::DepthLimitExceeded { max: 1, .. })
+            ));
+            assert!(diff(1, "d", true, state)?.is_ok());
+            Ok(())
+        }
     }
 }
@@ -119,7 +119,8 @@ pub fn bytes(
     progress: &mut impl crate::progress::Progress,
     should_interrupt: &std::sync::atomic::AtomicBool,
 ) -> std::io::Result<git_hash::ObjectId> {
-    let mut hasher = hasher(kind);
+    use std::io::Write as _;
+    let mut hasher = Write::new(std::io::sink(), kind);
     let start = std::time::Instant::now();
     // init progress before the possibility for failure, as convenience in case people want to recover
     progress.init(Some(num_bytes_from_start), crate::progress::bytes());
@@ -133,13 +134,13 @@ pub fn bytes(
         read.read_exact(out)?;
         bytes_left -= out.len();
         progress.inc_by(out.len());
-        hasher.update(out);
+        hasher.write_all(out)?;
         if should_interrupt.load(std::sync::atomic::Ordering::SeqCst) {
             return Err(std::io::Error::new(std::io::ErrorKind::Other, "Interrupted"));
         }
     }
 
-    let id = git_hash::ObjectId::from(hasher.digest());
+    let id = hasher.digest();
     progress.show_throughput(start);
     Ok(id)
 }
@@ -149,6 +150,10 @@ mod write {
     use crate::hash::Sha1;
 
     /// A utility to automatically generate a hash while writing into an inner writer.
+    ///
+    /// Only bytes actually accepted by the inner writer are hashed, which makes it suitable to compute trailing checksums
+    /// of files while writing them, instead of reading them back afterwards.
+    /// Use [`std::io::sink()`] as inner writer to merely hash data.
     pub struct Write<T> {
         /// The hash implementation.
         pub hash: Sha1,
@@ -185,6 +190,21 @@ mod write {
             }
         }
     }
+
+    impl<T> Write<T> {
+        /// Finalize the hash over all bytes written so far and return it, dropping the inner writer.
+        ///
+        /// Use [`into_inner_and_digest()`][Write::into_inner_and_digest()] to keep using the inner writer.
+        pub fn digest(self) -> git_hash::ObjectId {
+            self.hash.digest().into()
+        }
+
+        /// Finalize the hash over all bytes written so far and return it along with the inner writer, for example
+        /// to write the hash as trailer.
+        pub fn into_inner_and_digest(self) -> (T, git_hash::ObjectId) {
+            (self.inner, self.hash.digest().into())
+        }
+    }
 }
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
 pub use write::Write;
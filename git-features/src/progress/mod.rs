@@ -7,6 +7,10 @@ pub use prodash::{
     unit, Progress, Unit,
 };
 
+///
+pub mod snapshot;
+pub use snapshot::{Callback, Snapshot};
+
 /// A unit for displaying bytes with throughput and progress percentage.
 pub fn bytes() -> Option<Unit> {
     Some(unit::dynamic_and_mode(
@@ -0,0 +1,203 @@
+//! A [`Progress`] implementation which hands out [snapshots][Snapshot] of its state instead of rendering them,
+//! for use by applications which display progress themselves, like GUIs.
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use prodash::progress::Id;
+
+use crate::progress::{MessageLevel, Progress, Step, Unit};
+
+/// The state of a single task at the time it changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The names of all tasks from the root down to the task this snapshot is about, which is the last one.
+    pub path: Vec<String>,
+    /// The identifier of the task, which is stable across runs unless it's [unknown][crate::progress::UNKNOWN].
+    ///
+    /// Prefer it over the name to identify tasks, as names may contain paths or other dynamic information.
+    pub id: Id,
+    /// The amount of work done so far.
+    pub step: Step,
+    /// The amount of work to do, if known.
+    pub max: Option<Step>,
+    /// The label of the unit of `step` and `max`, like `objects`, if one was set.
+    pub unit: Option<String>,
+    /// A message emitted by the task, if this snapshot was produced for one.
+    pub message: Option<(MessageLevel, String)>,
+}
+
+struct Shared {
+    callback: Box<dyn FnMut(Snapshot) + Send>,
+    min_interval: Duration,
+}
+
+/// A [`Progress`] implementation which calls a function with a [`Snapshot`] of a task each time it changes, but not more
+/// often than the configured interval per task.
+///
+/// Snapshots are always produced when a task is initialized, when it reaches its maximum, for each message, and
+/// when a task is dropped with changes that weren't reported yet, so the last snapshot of each task holds its final count.
+/// All children share the same function, which is called from whichever thread the task is used on.
+///
+/// To receive snapshots on another thread, pass a function which sends them into a channel.
+pub struct Callback {
+    shared: Arc<Mutex<Shared>>,
+    path: Vec<String>,
+    id: Id,
+    step: Step,
+    max: Option<Step>,
+    unit: Option<Unit>,
+    initialized: bool,
+    last_reported: Option<(Instant, Step)>,
+}
+
+/// Initialization
+impl Callback {
+    /// Create a new root task named `name` which calls `callback` for each change to it or its children, with
+    /// at least `min_interval` between snapshots of counter changes of the same task.
+    ///
+    /// Use a `min_interval` of zero to receive a snapshot for each change.
+    pub fn new(
+        name: impl Into<String>,
+        min_interval: Duration,
+        callback: impl FnMut(Snapshot) + Send + 'static,
+    ) -> Self {
+        Callback {
+            shared: Arc::new(Mutex::new(Shared {
+                callback: Box::new(callback),
+                min_interval,
+            })),
+            path: vec![name.into()],
+            id: crate::progress::UNKNOWN,
+            step: 0,
+            max: None,
+            unit: None,
+            initialized: false,
+            last_reported: None,
+        }
+    }
+}
+
+impl Callback {
+    fn report(&mut self, message: Option<(MessageLevel, String)>) {
+        let unit = self.unit.as_ref().and_then(|unit| {
+            let mut label = String::new();
+            unit.as_display_value().display_unit(&mut label, self.step).ok()?;
+            (!label.is_empty()).then(|| label)
+        });
+        let snapshot = Snapshot {
+            path: self.path.clone(),
+            id: self.id,
+            step: self.step,
+            max: self.max,
+            unit,
+            message,
+        };
+        self.last_reported = Some((Instant::now(), self.step));
+        // A poisoned lock means the callback panicked before, so there is no one to report to anymore.
+        if let Ok(mut shared) = self.shared.lock() {
+            (shared.callback)(snapshot);
+        }
+    }
+
+    fn report_if_due(&mut self) {
+        if !self.initialized {
+            return;
+        }
+        let is_due = match self.last_reported {
+            None => true,
+            Some((_, step)) if step == self.step => false,
+            Some(_) if self.max == Some(self.step) => true,
+            Some((at, _)) => self
+                .shared
+                .lock()
+                .map_or(false, |shared| at.elapsed() >= shared.min_interval),
+        };
+        if is_due {
+            self.report(None);
+        }
+    }
+}
+
+impl Progress for Callback {
+    type SubProgress = Callback;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        self.add_child_with_id(name, crate::progress::UNKNOWN)
+    }
+
+    fn add_child_with_id(&mut self, name: impl Into<String>, id: Id) -> Self::SubProgress {
+        let mut path = self.path.clone();
+        path.push(name.into());
+        Callback {
+            shared: Arc::clone(&self.shared),
+            path,
+            id,
+            step: 0,
+            max: None,
+            unit: None,
+            initialized: false,
+            last_reported: None,
+        }
+    }
+
+    fn init(&mut self, max: Option<Step>, unit: Option<Unit>) {
+        self.max = max;
+        self.unit = unit;
+        self.step = 0;
+        self.initialized = true;
+        self.report(None);
+    }
+
+    fn set(&mut self, step: Step) {
+        self.step = step;
+        self.report_if_due();
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        self.unit.clone()
+    }
+
+    fn max(&self) -> Option<Step> {
+        self.max
+    }
+
+    fn set_max(&mut self, max: Option<Step>) -> Option<Step> {
+        std::mem::replace(&mut self.max, max)
+    }
+
+    fn step(&self) -> Step {
+        self.step
+    }
+
+    fn inc_by(&mut self, step: Step) {
+        self.step += step;
+        self.report_if_due();
+    }
+
+    fn set_name(&mut self, name: impl Into<String>) {
+        *self.path.last_mut().expect("at least our own name") = name.into();
+    }
+
+    fn name(&self) -> Option<String> {
+        self.path.last().cloned()
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
+        self.report(Some((level, message.into())));
+    }
+}
+
+impl Drop for Callback {
+    fn drop(&mut self) {
+        let has_unreported_changes = self.last_reported.map_or(false, |(_, step)| step != self.step);
+        if self.initialized && has_unreported_changes {
+            self.report(None);
+        }
+    }
+}
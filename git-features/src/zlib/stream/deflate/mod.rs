@@ -31,6 +31,17 @@ mod impls {
             }
         }
 
+        /// Create a new instance writing bytes compressed with the given zlib `level` from 0 (none) to 9 (best) to `inner`.
+        ///
+        /// [`new()`][Self::new()] uses level 1, which favors speed.
+        pub fn with_level(inner: W, level: u32) -> deflate::Write<W> {
+            deflate::Write {
+                compressor: Compress::new(Compression::new(level), true),
+                inner,
+                buf: [0; deflate::BUF_SIZE],
+            }
+        }
+
         /// Reset the compressor, starting a new compression stream.
         ///
         /// That way multiple streams can be written to the same inner writer.
@@ -38,6 +49,16 @@ mod impls {
             self.compressor.reset();
         }
 
+        /// Return the inner writer.
+        pub fn get_ref(&self) -> &W {
+            &self.inner
+        }
+
+        /// Return the inner writer mutably, which is useful to reuse it along with the compressor after a [reset][Self::reset()].
+        pub fn get_mut(&mut self) -> &mut W {
+            &mut self.inner
+        }
+
         /// Consume `self` and return the inner writer.
         pub fn into_inner(self) -> W {
             self.inner
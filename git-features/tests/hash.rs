@@ -11,3 +11,68 @@ fn size_of_sha1() {
 fn size_of_sha1() {
     assert_eq!(std::mem::size_of::<Sha1>(), 104)
 }
+
+mod write {
+    use std::io::Write;
+
+    use git_features::hash;
+
+    /// A writer which accepts at most `max_chunk` bytes per call.
+    struct ChunkedSink {
+        max_chunk: usize,
+        data: Vec<u8>,
+    }
+
+    impl std::io::Write for ChunkedSink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.max_chunk);
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn hash_of(data: &[u8]) -> git_hash::ObjectId {
+        let mut hasher = hash::hasher(git_hash::Kind::Sha1);
+        hasher.update(data);
+        hasher.digest().into()
+    }
+
+    #[test]
+    fn partial_writes_only_hash_bytes_accepted_by_the_inner_writer() -> std::io::Result<()> {
+        let input: Vec<u8> = (0..=255u8).cycle().take(10_000).collect();
+        for max_chunk in [1, 7, 64, 4096, 20_000] {
+            let mut out = hash::Write::new(
+                ChunkedSink {
+                    max_chunk,
+                    data: Vec::new(),
+                },
+                git_hash::Kind::Sha1,
+            );
+            for chunk in input.chunks(333) {
+                out.write_all(chunk)?;
+            }
+            let (sink, digest) = out.into_inner_and_digest();
+            assert_eq!(sink.data, input, "all data arrives in the inner writer");
+            assert_eq!(
+                digest,
+                hash_of(&input),
+                "chunk size {} doesn't affect the hash",
+                max_chunk
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hashing_into_a_sink_is_equivalent_to_hashing_bytes() -> std::io::Result<()> {
+        let mut out = hash::Write::new(std::io::sink(), git_hash::Kind::Sha1);
+        out.write_all(b"hello ")?;
+        out.write_all(b"world")?;
+        assert_eq!(out.digest(), hash_of(b"hello world"));
+        Ok(())
+    }
+}
@@ -9,6 +9,11 @@ use std::{
 use crate::{borrowed::oid, Kind, SIZE_OF_SHA1_DIGEST};
 
 /// An owned hash identifying objects, most commonly Sha1
+///
+/// As there is only one kind of hash, an `ObjectId` takes as much space as its digest, `20` bytes with an alignment of `1`, without
+/// any discriminant. Collections holding many of them can thus store `ObjectId`s directly, or raw digests obtained with
+/// [`into_sha1()`][ObjectId::into_sha1()] which turn back into `ObjectId`s with `From<[u8; 20]>` at no cost.
+/// This changes once another kind of hash is supported, as the largest digest and a discriminant determine the size.
 #[derive(PartialEq, Eq, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObjectId {
@@ -144,6 +149,16 @@ impl ObjectId {
         ObjectId::Sha1(id)
     }
 
+    /// Return the bytes of a Sha1 digest by value, or `None` if this is a different kind of hash.
+    ///
+    /// This is the inverse of `ObjectId::from([u8; 20])`.
+    #[inline]
+    pub fn into_sha1(self) -> Option<[u8; SIZE_OF_SHA1_DIGEST]> {
+        match self {
+            ObjectId::Sha1(bytes) => Some(bytes),
+        }
+    }
+
     /// Returns an Digest representing a Sha1 with whose memory is zeroed.
     #[inline]
     pub(crate) const fn null_sha1() -> ObjectId {
@@ -148,6 +148,14 @@ impl oid {
         out.write_all(&hex[..hex_len])
     }
 
+    /// Return our bytes as borrowed array of a Sha1 digest without copying them, or `None` if this is a different kind of hash.
+    ///
+    /// This is the inverse of `<&oid>::from(&[u8; 20])`.
+    #[inline]
+    pub fn as_sha1(&self) -> Option<&[u8; SIZE_OF_SHA1_DIGEST]> {
+        self.bytes.try_into().ok()
+    }
+
     /// Returns a Sha1 digest with all bytes being initialized to zero.
     #[inline]
     pub(crate) fn null_sha1() -> &'static Self {
@@ -41,3 +41,36 @@ mod from_hex {
         }
     }
 }
+
+mod sha1 {
+    use git_hash::{oid, ObjectId};
+
+    #[test]
+    fn array_conversions_roundtrip_without_change() {
+        let bytes = *b"0123456789abcdefghij";
+        let id = ObjectId::from(bytes);
+        assert_eq!(id.into_sha1(), Some(bytes));
+        assert_eq!(id.as_sha1(), Some(&bytes), "available through `oid` as well");
+
+        let borrowed: &oid = (&bytes).into();
+        assert_eq!(borrowed.as_sha1(), Some(&bytes));
+        assert!(
+            std::ptr::eq(borrowed.as_sha1().expect("sha1"), &bytes),
+            "borrowed conversions don't copy"
+        );
+    }
+
+    #[test]
+    fn size_in_memory_is_that_of_the_digest() {
+        assert_eq!(
+            std::mem::size_of::<ObjectId>(),
+            20,
+            "there is no space needed for the kind of hash as long as there is only one"
+        );
+        assert_eq!(
+            std::mem::align_of::<ObjectId>(),
+            1,
+            "no padding is needed when storing them in arrays or alongside other data"
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use std::hash::BuildHasher;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use git_hash::ObjectId;
+
+const COUNT: usize = 10_000_000;
+
+/// Produce `COUNT` distinct ids with uniformly distributed bytes, like those of actual hashes.
+fn ids() -> Vec<ObjectId> {
+    let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    (0..COUNT)
+        .map(|_| {
+            let mut digest = [0u8; 20];
+            digest[..8].copy_from_slice(&next().to_ne_bytes());
+            digest[8..16].copy_from_slice(&next().to_ne_bytes());
+            digest[16..].copy_from_slice(&next().to_ne_bytes()[..4]);
+            ObjectId::from(digest)
+        })
+        .collect()
+}
+
+/// Insert all `ids` into a new set twice, the second time to simulate encountering ids that were already seen.
+fn fill<S: BuildHasher + Default>(ids: &[ObjectId]) -> usize {
+    let mut set = hashbrown::HashSet::<ObjectId, S>::default();
+    for _ in 0..2 {
+        set.extend(ids.iter().copied());
+    }
+    set.len()
+}
+
+fn seen_set(c: &mut Criterion) {
+    let ids = ids();
+    let mut group = c.benchmark_group("seen set of 10M ids");
+    group.sample_size(10).throughput(Throughput::Elements(2 * COUNT as u64));
+    group.bench_function("default hasher", |b| {
+        b.iter(|| fill::<std::collections::hash_map::RandomState>(&ids))
+    });
+    group.bench_function("git_hashtable::hash::Builder", |b| {
+        b.iter(|| fill::<git_hashtable::hash::Builder>(&ids))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, seen_set);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use git_hash::ObjectId;
+
+#[test]
+fn object_ids_are_hashed_by_their_first_8_bytes() {
+    let id = ObjectId::from_hex(b"0123456789abcdef0123456789abcdef01234567").expect("valid hex");
+    let expected = u64::from_ne_bytes(id.as_slice()[..8].try_into().expect("8 bytes"));
+    let hash_of = |value: &dyn Fn(&mut git_hashtable::hash::Hasher)| {
+        let mut state = git_hashtable::hash::Builder.build_hasher();
+        value(&mut state);
+        state.finish()
+    };
+    assert_eq!(hash_of(&|state| id.hash(state)), expected);
+    assert_eq!(
+        hash_of(&|state| id.as_ref().hash(state)),
+        expected,
+        "borrowed ids hash the same, so sets of owned ids can be queried with them"
+    );
+}
+
+#[test]
+fn sets_distinguish_ids_sharing_the_hashed_prefix() {
+    let a = ObjectId::from_hex(b"0123456789abcdef0123456789abcdef01234567").expect("valid hex");
+    let b = ObjectId::from_hex(b"0123456789abcdefffffffffffffffffffffffff").expect("valid hex");
+    let mut set = git_hashtable::HashSet::default();
+    assert!(set.insert(a));
+    assert!(set.insert(b));
+    assert!(!set.insert(a));
+    assert!(set.contains(b.as_ref()));
+}
@@ -143,7 +143,7 @@ impl Snapshot {
 }
 
 fn enriched_signature<'a>(
-    SignatureRef { name, email, time }: SignatureRef<'a>,
+    SignatureRef { name, email, time, .. }: SignatureRef<'a>,
     new: ResolvedSignature<'_>,
 ) -> Signature<'a> {
     match (new.email, new.name) {
@@ -113,6 +113,56 @@ fn overwrite_entries() {
     assert_eq!(snapshot.entries().len(), 4);
 }
 
+#[test]
+fn same_email_with_different_names_from_git_test_suite() {
+    let snapshot = Snapshot::from_bytes(
+        b"A U Thor <author@example.com>
+Other Author <other@author.xx> nick2 <bugs@company.xx>
+Other Author <other@author.xx>         <nick2@company.xx>
+Some Dude <some@dude.xx>         nick1 <bugs@company.xx>
+Santa Claus <santa.claus@northpole.xx> <me@company.xx>",
+    );
+    assert_eq!(
+        snapshot.try_resolve(signature("nick1", "bugs@company.xx").to_ref()),
+        Some(signature("Some Dude", "some@dude.xx"))
+    );
+    assert_eq!(
+        snapshot.try_resolve(signature("nick2", "bugs@company.xx").to_ref()),
+        Some(signature("Other Author", "other@author.xx")),
+        "the same email maps to different identities depending on the name"
+    );
+    assert_eq!(
+        snapshot.try_resolve(signature("nick3", "bugs@company.xx").to_ref()),
+        None,
+        "unknown names with a known email aren't mapped"
+    );
+    assert_eq!(
+        snapshot.try_resolve(signature("nick2", "nick2@company.xx").to_ref()),
+        Some(signature("Other Author", "other@author.xx"))
+    );
+    assert_eq!(
+        snapshot.try_resolve(signature("CTO", "me@company.xx").to_ref()),
+        Some(signature("Santa Claus", "santa.claus@northpole.xx"))
+    );
+}
+
+#[test]
+fn replace_name_and_email_by_email_only_from_git_test_suite() {
+    let snapshot = Snapshot::from_bytes(
+        b"# the last matching entry wins
+Wrong Name <wrong@email.xx> <old@email.xx>
+New Name <new@email.xx> <old@email.xx>",
+    );
+    for name in ["Old Name", "Some Other Name"] {
+        assert_eq!(
+            snapshot.try_resolve(signature(name, "old@email.xx").to_ref()),
+            Some(signature("New Name", "new@email.xx")),
+            "any name is replaced along with the email"
+        );
+    }
+    assert_eq!(snapshot.entries().len(), 1);
+}
+
 fn signature(name: &str, email: &str) -> git_actor::Signature {
     git_actor::Signature {
         name: name.into(),
@@ -1,5 +1,7 @@
 use std::{convert::Infallible, io};
 
+use bstr::{BStr, ByteSlice};
+
 use crate::{Blob, BlobRef, Kind};
 
 impl<'a> crate::WriteTo for BlobRef<'a> {
@@ -45,3 +47,110 @@ impl<'a> BlobRef<'a> {
         Ok(BlobRef { data })
     }
 }
+
+/// The amount of bytes at the beginning of a blob which are inspected by [`BlobRef::is_binary()`], like git does.
+pub const BINARY_DETECTION_LIMIT: usize = 8000;
+
+impl<'a> BlobRef<'a> {
+    /// Return true if this blob is considered binary, using git's heuristic of finding a NUL byte
+    /// within the first [`BINARY_DETECTION_LIMIT`] bytes.
+    pub fn is_binary(&self) -> bool {
+        self.data[..self.data.len().min(BINARY_DETECTION_LIMIT)].contains(&0)
+    }
+
+    /// Return an iterator over all lines in this blob, without their line terminator which may be `\n` or `\r\n`.
+    ///
+    /// A missing trailing newline on the last line is tolerated, and no empty line is produced after a trailing newline.
+    pub fn lines(&self) -> Lines<'a> {
+        Lines { data: self.data }
+    }
+
+    /// Return at most `max_bytes` of this blob's data, truncated after the last complete line if possible,
+    /// or on a character boundary otherwise so no UTF-8 encoded character is cut in half.
+    ///
+    /// Use [`Preview::truncated`] to learn if not all data was returned.
+    pub fn preview(&self, max_bytes: usize) -> Preview<'a> {
+        if self.data.len() <= max_bytes {
+            return Preview {
+                data: self.data.as_bstr(),
+                truncated: false,
+            };
+        }
+        let candidate = &self.data[..max_bytes];
+        let end = match candidate.rfind_byte(b'\n') {
+            Some(pos) => pos + 1,
+            None => {
+                let mut end = max_bytes;
+                // Back off from continuation bytes, at most as many as a UTF-8 encoded character can have.
+                while end > 0 && max_bytes - end < 3 && is_utf8_continuation(self.data[end]) {
+                    end -= 1;
+                }
+                if is_utf8_continuation(self.data[end]) {
+                    // This isn't valid UTF-8, so there is no boundary to respect.
+                    max_bytes
+                } else {
+                    end
+                }
+            }
+        };
+        Preview {
+            data: self.data[..end].as_bstr(),
+            truncated: true,
+        }
+    }
+}
+
+impl Blob {
+    /// Return true if this blob is considered binary. See [`BlobRef::is_binary()`] for details.
+    pub fn is_binary(&self) -> bool {
+        self.to_ref().is_binary()
+    }
+
+    /// Return an iterator over all lines in this blob. See [`BlobRef::lines()`] for details.
+    pub fn lines(&self) -> Lines<'_> {
+        self.to_ref().lines()
+    }
+
+    /// Return a preview of at most `max_bytes` of this blob. See [`BlobRef::preview()`] for details.
+    pub fn preview(&self, max_bytes: usize) -> Preview<'_> {
+        self.to_ref().preview(max_bytes)
+    }
+}
+
+fn is_utf8_continuation(b: u8) -> bool {
+    b & 0b1100_0000 == 0b1000_0000
+}
+
+/// An iterator over the lines of a blob, created by [`BlobRef::lines()`].
+#[derive(Clone)]
+pub struct Lines<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a BStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let (mut line, rest) = match self.data.find_byte(b'\n') {
+            Some(pos) => (&self.data[..pos], &self.data[pos + 1..]),
+            None => (self.data, &[][..]),
+        };
+        if line.last() == Some(&b'\r') {
+            line = &line[..line.len() - 1];
+        }
+        self.data = rest;
+        Some(line.as_bstr())
+    }
+}
+
+/// A possibly truncated portion of a blob's data, created by [`BlobRef::preview()`].
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Preview<'a> {
+    /// The data to show, which is all data of the blob unless `truncated` is true.
+    pub data: &'a BStr,
+    /// If true, `data` doesn't contain all of the blob's data.
+    pub truncated: bool,
+}
@@ -5,7 +5,7 @@ use nom::{
     bytes::complete::{is_not, tag},
     combinator::{all_consuming, opt},
     error::{context, ContextError, ParseError},
-    multi::many0,
+    multi::fold_many0,
     IResult, Parser,
 };
 use smallvec::SmallVec;
@@ -33,7 +33,14 @@ pub fn commit<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
     })(i)?;
     let (i, parents) = context(
         "zero or more 'parent <40 lowercase hex char>'",
-        many0(|i| parse::header_field(i, b"parent", parse::hex_hash)),
+        fold_many0(
+            |i| parse::header_field(i, b"parent", parse::hex_hash),
+            SmallVec::new,
+            |mut parents: SmallVec<[_; 1]>, parent| {
+                parents.push(parent);
+                parents
+            },
+        ),
     )(i)?;
     let (i, author) = context("author <signature>", |i| {
         parse::header_field(i, b"author", parse::signature)
@@ -47,12 +54,20 @@ pub fn commit<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
     )(i)?;
     let (i, extra_headers) = context(
         "<field> <single-line|multi-line>",
-        many0(alt((
-            parse::any_header_field_multi_line.map(|(k, o)| (k.as_bstr(), Cow::Owned(o))),
-            |i| {
-                parse::any_header_field(i, is_not(NL)).map(|(i, (k, o))| (i, (k.as_bstr(), Cow::Borrowed(o.as_bstr()))))
+        fold_many0(
+            alt((
+                parse::any_header_field_multi_line.map(|(k, o)| (k.as_bstr(), Cow::Owned(o))),
+                |i| {
+                    parse::any_header_field(i, is_not(NL))
+                        .map(|(i, (k, o))| (i, (k.as_bstr(), Cow::Borrowed(o.as_bstr()))))
+                },
+            )),
+            Vec::new,
+            |mut headers: Vec<_>, header| {
+                headers.push(header);
+                headers
             },
-        ))),
+        ),
     )(i)?;
     let (i, message) = all_consuming(message)(i)?;
 
@@ -60,7 +75,7 @@ pub fn commit<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         i,
         CommitRef {
             tree,
-            parents: SmallVec::from(parents),
+            parents,
             author,
             committer,
             encoding: encoding.map(ByteSlice::as_bstr),
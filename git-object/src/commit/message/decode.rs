@@ -1,57 +1,55 @@
-use nom::{
-    branch::alt,
-    bytes::complete::{tag, take_till1},
-    combinator::all_consuming,
-    error::ParseError,
-    sequence::pair,
-    IResult,
-};
-
 use crate::bstr::{BStr, ByteSlice};
 
-pub(crate) fn newline<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], &'a [u8], E> {
-    alt((tag(b"\r\n"), tag(b"\n")))(i)
+/// The bytes git considers whitespace, which notably excludes form feeds and vertical tabs.
+pub(crate) const WHITESPACE: &[u8] = b" \t\n\r";
+
+/// Return `true` if `line` consists of whitespace only, which makes it a paragraph separator.
+pub(crate) fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(|b| WHITESPACE.contains(b))
+}
+
+/// Return `line` without trailing whitespace.
+pub(crate) fn trim_end(line: &[u8]) -> &[u8] {
+    &line[..line.rfind_not_byteset(WHITESPACE).map_or(0, |pos| pos + 1)]
 }
 
-fn subject_and_body<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], (&'a BStr, Option<&'a BStr>), E> {
-    let mut c = i;
-    let mut consumed_bytes = 0;
-    while !c.is_empty() {
-        c = match take_till1::<_, _, E>(|c| c == b'\n' || c == b'\r')(c) {
-            Ok((i1, segment)) => {
-                consumed_bytes += segment.len();
-                match pair::<_, _, _, E, _, _>(newline, newline)(i1) {
-                    Ok((body, _)) => {
-                        return Ok((
-                            &[],
-                            (
-                                i[0usize..consumed_bytes].as_bstr(),
-                                (!body.is_empty()).then(|| body.as_bstr()),
-                            ),
-                        ));
-                    }
-                    Err(_) => match i1.get(1..) {
-                        Some(next) => {
-                            consumed_bytes += 1;
-                            next
-                        }
-                        None => break,
-                    },
-                }
-            }
-            Err(_) => match c.get(1..) {
-                Some(next) => {
-                    consumed_bytes += 1;
-                    next
-                }
-                None => break,
-            },
-        };
+/// Return the amount of bytes taken by all blank lines at the beginning of `input`.
+pub(crate) fn blank_lines_len(input: &[u8]) -> usize {
+    input
+        .lines_with_terminator()
+        .take_while(|line| is_blank(line))
+        .map(|line| line.len())
+        .sum()
+}
+
+fn line_ending_len(line: &[u8]) -> usize {
+    if line.ends_with(b"\r\n") {
+        2
+    } else if line.ends_with(b"\n") {
+        1
+    } else {
+        0
     }
-    Ok((&[], (i.as_bstr(), None)))
 }
 
-/// Returns title and body, without separator
+/// Returns title and body, without separator.
+///
+/// Like git, leading blank lines are skipped and the title is the first paragraph, which ends at the next blank line.
+/// The body starts at the first non-blank line thereafter.
 pub fn message(input: &[u8]) -> (&BStr, Option<&BStr>) {
-    all_consuming(subject_and_body::<()>)(input).expect("cannot fail").1
+    let title_start = blank_lines_len(input);
+    let mut title_end = title_start;
+    let mut cursor = title_start;
+    for line in input[title_start..].lines_with_terminator() {
+        if is_blank(line) {
+            let body = &input[cursor + blank_lines_len(&input[cursor..])..];
+            return (
+                input[title_start..title_end].as_bstr(),
+                (!body.is_empty()).then(|| body.as_bstr()),
+            );
+        }
+        cursor += line.len();
+        title_end = cursor - line_ending_len(line);
+    }
+    (input[title_start..].as_bstr(), None)
 }
@@ -3,7 +3,7 @@ use std::borrow::Cow;
 use crate::{
     bstr::{BStr, BString, ByteSlice, ByteVec},
     commit::MessageRef,
-    CommitRef,
+    Commit, CommitRef,
 };
 
 ///
@@ -32,58 +32,67 @@ impl<'a> MessageRef<'a> {
         MessageRef { title, body }
     }
 
-    /// Produce a short commit summary for the message title.
+    /// Return the first line of the [`title`][MessageRef::title] as is, without its line ending.
+    pub fn title(&self) -> &'a BStr {
+        self.title.lines().next().unwrap_or_default().as_bstr()
+    }
+
+    /// Produce a short commit summary for the message title, matching `git log --format=%s`.
     ///
     /// This means the following
     ///
-    /// * Take the subject line which is delimited by two newlines (\n\n)
-    /// * transform intermediate consecutive whitespace including \r into one space
-    ///
-    /// The resulting summary will have folded whitespace before a newline into spaces and stopped that process
-    /// once two consecutive newlines are encountered.
+    /// * Skip leading blank lines, i.e. lines that are empty or consist of whitespace only
+    /// * Take all lines up to the next blank line and trim their trailing whitespace including \r
+    /// * Join these lines with a single space
     pub fn summary(&self) -> Cow<'a, BStr> {
         summary(self.title)
     }
 
     /// Further parse the body into into non-trailer and trailers, which can be iterated from the returned [`BodyRef`].
+    ///
+    /// The body is exactly what `git log --format=%b` would print, minus the newline git adds.
     pub fn body(&self) -> Option<BodyRef<'a>> {
         self.body.map(|b| BodyRef::from_bytes(b))
     }
 }
 
 pub(crate) fn summary(message: &BStr) -> Cow<'_, BStr> {
-    let message = message.trim();
-    match message.find_byte(b'\n') {
-        Some(mut pos) => {
-            let mut out = BString::default();
-            let mut previous_pos = None;
-            loop {
-                if let Some(previous_pos) = previous_pos {
-                    if previous_pos + 1 == pos {
-                        let len_after_trim = out.trim_end().len();
-                        out.resize(len_after_trim, 0);
-                        break out.into();
-                    }
-                }
-                let message_to_newline = &message[previous_pos.map(|p| p + 1).unwrap_or(0)..pos];
-
-                if let Some(pos_before_whitespace) = message_to_newline.rfind_not_byteset(b"\t\n\x0C\r ") {
-                    out.extend_from_slice(&message_to_newline[..pos_before_whitespace + 1]);
-                }
+    let mut lines = message[decode::blank_lines_len(message)..]
+        .lines()
+        .take_while(|line| !decode::is_blank(line))
+        .map(decode::trim_end);
+    let first = lines.next().unwrap_or_default();
+    match lines.next() {
+        Some(second) => {
+            let mut out = BString::from(first);
+            for line in std::iter::once(second).chain(lines) {
                 out.push_byte(b' ');
-                previous_pos = Some(pos);
-                match message.get(pos + 1..).and_then(|i| i.find_byte(b'\n')) {
-                    Some(next_nl_pos) => pos += next_nl_pos + 1,
-                    None => {
-                        if let Some(slice) = message.get((pos + 1)..) {
-                            out.extend_from_slice(slice);
-                        }
-                        break out.into();
-                    }
-                }
+                out.extend_from_slice(line);
+            }
+            out.into()
+        }
+        None => first.as_bstr().into(),
+    }
+}
+
+impl Commit {
+    /// Set our message to the canonical combination of `summary` and `body`, the way `git commit` would write it.
+    ///
+    /// The `summary` is folded into a single line like [`MessageRef::summary()`] does, and followed by a blank line and the
+    /// `body` without leading blank lines and trailing whitespace. The message always ends with a newline, and the body is
+    /// omitted if it is `None` or blank.
+    pub fn set_message_from(&mut self, summary: &BStr, body: Option<&BStr>) {
+        let mut message = self::summary(summary).into_owned();
+        message.push_byte(b'\n');
+        if let Some(body) = body {
+            let body = decode::trim_end(&body[decode::blank_lines_len(body)..]);
+            if !body.is_empty() {
+                message.push_byte(b'\n');
+                message.extend_from_slice(body);
+                message.push_byte(b'\n');
             }
         }
-        None => message.as_bstr().into(),
+        self.message = message;
     }
 }
 
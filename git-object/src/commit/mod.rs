@@ -1,4 +1,4 @@
-use bstr::{BStr, ByteSlice};
+use bstr::{BStr, BString, ByteSlice};
 
 use crate::{Commit, CommitRef, TagRef};
 
@@ -6,18 +6,19 @@ mod decode;
 ///
 pub mod message;
 
-/// A parsed commit message that assumes a title separated from the body by two consecutive newlines.
+/// A parsed commit message that assumes a title separated from the body by a blank line, just like git does.
 ///
 /// Titles can have any amount of whitespace
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageRef<'a> {
-    /// The title of the commit, as separated from the body with two consecutive newlines. The newlines are not included.
+    /// The title of the commit, which is the first paragraph after skipping leading blank lines, with blank lines being
+    /// empty or consisting of whitespace only. The line ending before the separating blank line is not included.
     #[cfg_attr(feature = "serde1", serde(borrow))]
     pub title: &'a BStr,
-    /// All bytes not consumed by the title, excluding the separating newlines.
+    /// All bytes after the blank lines following the title.
     ///
-    /// The body is `None` if there was now title separation or the body was empty after the separator.
+    /// The body is `None` if there was no title separation or the body was empty after the separator.
     pub body: Option<&'a BStr>,
 }
 
@@ -73,11 +74,41 @@ impl<'a> CommitRef<'a> {
     }
 }
 
+/// The names of extra headers holding a cryptographic signature of the commit, one for each hash kind.
+const SIGNATURE_HEADERS: &[&[u8]] = &[b"gpgsig", b"gpgsig-sha256"];
+
 impl Commit {
     /// Returns a convenient iterator over all extra headers.
     pub fn extra_headers(&self) -> ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (k.as_bstr(), v.as_bstr())))
     }
+
+    /// Return `true` if this commit carries a cryptographic signature in one of its extra headers.
+    ///
+    /// The signature is made over all other bytes of the serialized commit, so changing _any_ field of a signed commit,
+    /// like adding a parent, invalidates the signature even though it is written back verbatim.
+    /// Use [`remove_signature()`][Commit::remove_signature()] to avoid writing commits with an invalid signature.
+    #[must_use = "a signature is invalidated by any change to the commit, consider removing it"]
+    pub fn has_signature(&self) -> bool {
+        self.extra_headers
+            .iter()
+            .any(|(name, _)| SIGNATURE_HEADERS.contains(&name.as_slice()))
+    }
+
+    /// Remove all signature headers and return the value of the first one, or `None` if the commit wasn't signed.
+    ///
+    /// All other extra headers are kept in order.
+    pub fn remove_signature(&mut self) -> Option<BString> {
+        let mut first = None;
+        self.extra_headers.retain(|(name, value)| {
+            let is_signature = SIGNATURE_HEADERS.contains(&name.as_slice());
+            if is_signature && first.is_none() {
+                first = Some(value.clone());
+            }
+            !is_signature
+        });
+        first
+    }
 }
 
 /// An iterator over extra headers in [owned][crate::Commit] and [borrowed][crate::CommitRef] commits.
@@ -8,6 +8,7 @@ use nom::{
     combinator::{all_consuming, opt},
     error::context,
 };
+use smallvec::SmallVec;
 
 use crate::{bstr::ByteSlice, commit::decode, parse, parse::NL, CommitRefIter};
 
@@ -64,6 +65,21 @@ impl<'a> CommitRefIter<'a> {
         })
     }
 
+    /// Return the ids of all parents of the commit in `data` by parsing only its `tree` and `parent` headers, failing if
+    /// these can't be decoded.
+    ///
+    /// This is the cheapest way to learn about the edges of the commit graph, as signatures, extra headers and the message
+    /// are neither parsed nor validated, unlike with [`parent_ids()`][CommitRefIter::parent_ids()].
+    pub fn parent_ids_from_bytes(data: &[u8]) -> Result<SmallVec<[ObjectId; 2]>, crate::decode::Error> {
+        let (_tree, mut i) = header_hex(data, b"tree").ok_or_else(missing_field)?;
+        let mut parents = SmallVec::new();
+        while let Some((parent, rest)) = header_hex(i, b"parent") {
+            parents.push(ObjectId::from_hex(parent).expect("parsing validation"));
+            i = rest;
+        }
+        Ok(parents)
+    }
+
     /// Returns all signatures, first the author, then the committer, if there is no decoding error.
     ///
     /// Errors are coerced into options, hiding whether there was an error or not. The caller knows if there was an error or not
@@ -113,6 +129,16 @@ impl<'a> CommitRefIter<'a> {
     }
 }
 
+/// Parse the header line `<name> <lowercase hex>\n` at the beginning of `i` without the overhead of the parser combinators,
+/// and return the hex-encoded object id along with the remaining input.
+fn header_hex<'a>(i: &'a [u8], name: &[u8]) -> Option<(&'a [u8], &'a [u8])> {
+    let i = i.strip_prefix(name)?.strip_prefix(b" ")?;
+    let end = i.find_byte(b'\n')?;
+    let hex = &i[..end];
+    (git_hash::Kind::from_hex_len(hex.len()).is_some() && hex.iter().all(|b| parse::is_hex_digit_lc(*b)))
+        .then(|| (hex, &i[end + 1..]))
+}
+
 fn missing_field() -> crate::decode::Error {
     crate::decode::empty_error()
 }
@@ -24,7 +24,8 @@ pub mod tag;
 ///
 pub mod tree;
 
-mod blob;
+///
+pub mod blob;
 ///
 pub mod data;
 
@@ -37,6 +38,9 @@ pub(crate) mod parse;
 ///
 pub mod kind;
 
+///
+pub mod verify;
+
 /// The four types of objects that git differentiates. #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -125,6 +129,9 @@ pub struct Commit {
     pub message: BString,
     /// Extra header fields, in order of them being encountered, made accessible with the iterator returned
     /// by [`extra_headers()`][Commit::extra_headers()].
+    ///
+    /// These are written back verbatim and in order, which includes signatures that become invalid if any other field changes,
+    /// see [`has_signature()`][Commit::has_signature()].
     pub extra_headers: Vec<(BString, BString)>,
 }
 
@@ -132,11 +139,17 @@ pub struct Commit {
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct TagRef<'a> {
-    /// The hash in hexadecimal being the object this tag points to. Use [`target()`][TagRef::target()] to obtain a byte representation.
+    /// The hash in hexadecimal being the object this tag points to. Use [`target_id()`][TagRef::target_id()] to obtain a byte representation.
     #[cfg_attr(feature = "serde1", serde(borrow))]
     pub target: &'a BStr,
     /// The kind of object that `target` points to.
     pub target_kind: Kind,
+    /// The `type` field as stored in the serialized tag, but only if it differs from how `target_kind` would be serialized.
+    ///
+    /// This can only be the case when [decoding leniently][TagRef::from_bytes_checked()], and it's written instead of
+    /// `target_kind` to reproduce the original bytes.
+    #[cfg_attr(feature = "serde1", serde(borrow))]
+    pub target_kind_raw: Option<&'a BStr>,
     /// The name of the tag, e.g. "v1.0".
     pub name: &'a BStr,
     /// The author of the tag.
@@ -336,6 +349,22 @@ pub mod decode {
     pub use _decode::{Error, ParseError, ParseErrorOwned};
     impl std::error::Error for Error {}
 
+    /// Decode the signature in `data`, like `Name <email> 1234567890 +0000`, the way commits and tags decode theirs,
+    /// failing if it is malformed or followed by anything else.
+    pub fn signature(data: &[u8]) -> Result<git_actor::SignatureRef<'_>, Error> {
+        nom::combinator::all_consuming(crate::parse::signature::<ParseError<'_>>)(data)
+            .map(|(_, signature)| signature)
+            .map_err(Error::from)
+    }
+
+    /// Decode the signature in `data` like [`signature()`], but fill in defaults where it is malformed and return
+    /// each [issue][git_actor::signature::Issue] that was worked around along with it.
+    ///
+    /// This is what reflogs use, as they were written by all kinds of tools over time.
+    pub fn signature_lenient(data: &[u8]) -> (git_actor::SignatureRef<'_>, Vec<git_actor::signature::Issue>) {
+        git_actor::SignatureRef::from_bytes_lenient(data)
+    }
+
     /// Returned by [`loose_header()`]
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
@@ -352,22 +381,53 @@ pub mod decode {
         ObjectHeader(#[from] super::kind::Error),
     }
 
+    /// Options for use in [`loose_header_opts()`].
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct LooseHeaderOptions {
+        /// If `true`, sizes with leading zeros like `blob 01\0` are rejected as git would.
+        ///
+        /// Defaults to `false`, which accepts them.
+        pub strict: bool,
+    }
+
     use bstr::ByteSlice;
+
+    /// The longest possible loose object header, `commit <u64::MAX>\0`, which is the furthest we look for its terminating null byte.
+    const MAX_LOOSE_HEADER_LEN: usize = "commit ".len() + "18446744073709551615".len() + 1;
+
     /// Decode a loose object header, being `<kind> <size>\0`, returns
     /// ([`kind`](super::Kind), `size`, `consumed bytes`).
     ///
     /// `size` is the uncompressed size of the payload in bytes.
-    pub fn loose_header(input: &[u8]) -> Result<(super::Kind, usize, usize), LooseHeaderDecodeError> {
+    ///
+    /// Sizes with leading zeros are accepted, use [`loose_header_opts()`] to reject them like git does.
+    pub fn loose_header(input: &[u8]) -> Result<(super::Kind, u64, usize), LooseHeaderDecodeError> {
+        loose_header_opts(input, LooseHeaderOptions::default())
+    }
+
+    /// Like [`loose_header()`], but configured with `options`, for instance to reject sizes with leading zeros.
+    pub fn loose_header_opts(
+        input: &[u8],
+        LooseHeaderOptions { strict }: LooseHeaderOptions,
+    ) -> Result<(super::Kind, u64, usize), LooseHeaderDecodeError> {
         use LooseHeaderDecodeError::*;
-        let kind_end = input.find_byte(0x20).ok_or(InvalidHeader {
+        let header = &input[..input.len().min(MAX_LOOSE_HEADER_LEN)];
+        let kind_end = header.find_byte(0x20).ok_or(InvalidHeader {
             message: "Expected '<type> <size>'",
         })?;
-        let kind = super::Kind::from_bytes(&input[..kind_end])?;
-        let size_end = input.find_byte(0x0).ok_or(InvalidHeader {
+        let kind = super::Kind::from_bytes(&header[..kind_end])?;
+        let size_end = header.find_byte(0x0).ok_or(InvalidHeader {
             message: "Did not find 0 byte in header",
         })?;
-        let size_bytes = &input[kind_end + 1..size_end];
-        let size = btoi::btoi(size_bytes).map_err(|source| ParseIntegerError {
+        let size_bytes = header.get(kind_end + 1..size_end).ok_or(InvalidHeader {
+            message: "Expected '<type> <size>'",
+        })?;
+        if strict && size_bytes.len() > 1 && size_bytes[0] == b'0' {
+            return Err(InvalidHeader {
+                message: "Object size in header must not have leading zeros",
+            });
+        }
+        let size = btoi::btou(size_bytes).map_err(|source| ParseIntegerError {
             source,
             message: "Object size in header could not be parsed",
             number: size_bytes.into(),
@@ -375,3 +435,37 @@ pub mod decode {
         Ok((kind, size, size_end + 1))
     }
 }
+
+/// Compute the id of an object of `kind` with `data` using the `hash_kind` hash, which is the hash over its
+/// [loose header][encode::loose_header()] followed by `data`.
+pub fn compute_hash(kind: Kind, data: &[u8], hash_kind: git_hash::Kind) -> git_hash::ObjectId {
+    let mut hasher = git_features::hash::hasher(hash_kind);
+    hasher.update(&encode::loose_header(kind, data.len() as u64));
+    hasher.update(data);
+    git_hash::ObjectId::from(hasher.digest())
+}
+
+/// Like [`compute_hash()`], but read the `stream_len` bytes of data from `stream`, for objects too large to be held in memory.
+///
+/// It fails if `stream` ends before `stream_len` bytes were read.
+pub fn compute_stream_hash(
+    kind: Kind,
+    stream: &mut dyn std::io::Read,
+    stream_len: u64,
+    hash_kind: git_hash::Kind,
+) -> std::io::Result<git_hash::ObjectId> {
+    use std::io::{Read, Write};
+    let mut out = git_features::hash::Write::new(std::io::sink(), hash_kind);
+    out.write_all(&encode::loose_header(kind, stream_len))?;
+    let num_bytes = std::io::copy(&mut stream.take(stream_len), &mut out)?;
+    if num_bytes != stream_len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!(
+                "Expected {} bytes of object data, but the stream ended after {}",
+                stream_len, num_bytes
+            ),
+        ));
+    }
+    Ok(out.digest())
+}
@@ -8,6 +8,7 @@ impl From<TagRef<'_>> for Tag {
             target,
             name,
             target_kind,
+            target_kind_raw: _,
             message,
             tagger: signature,
             pgp_signature,
@@ -2,6 +2,8 @@ use crate::{Blob, Commit, Object, Tag, Tree};
 
 mod convert;
 
+mod size_in_memory;
+
 mod write {
     use std::io;
 
@@ -192,8 +194,9 @@ impl<'a> ObjectRef<'a> {
     pub fn from_loose(data: &'a [u8]) -> Result<ObjectRef<'a>, LooseDecodeError> {
         let (kind, size, offset) = loose_header(data)?;
 
-        let body = &data[offset..]
-            .get(..size)
+        let body = usize::try_from(size)
+            .ok()
+            .and_then(|size| data[offset..].get(..size))
             .ok_or(LooseHeaderDecodeError::InvalidHeader {
                 message: "object data was shorter than its size declared in the header",
             })?;
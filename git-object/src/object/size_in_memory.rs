@@ -0,0 +1,160 @@
+//! Estimate how much memory decoded objects occupy, including their heap allocations, to allow caches to enforce byte budgets.
+//!
+//! Allocations are accounted for by their capacity, and the content of blobs isn't traversed.
+use std::{borrow::Cow, mem::size_of};
+
+use bstr::{BStr, BString};
+
+use crate::{tree, Blob, BlobRef, Commit, CommitRef, Object, ObjectRef, Tag, TagRef, Tree, TreeRef};
+
+fn signature_heap_size(signature: &git_actor::Signature) -> usize {
+    signature.name.capacity() + signature.email.capacity()
+}
+
+impl Object {
+    /// Return the amount of bytes this object occupies in memory, including all of its heap allocations.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>()
+            + match self {
+                Object::Tree(v) => v.heap_size(),
+                Object::Blob(v) => v.heap_size(),
+                Object::Commit(v) => v.heap_size(),
+                Object::Tag(v) => v.heap_size(),
+            }
+    }
+}
+
+impl Blob {
+    /// Return the amount of bytes this blob occupies in memory, including its data.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        self.data.capacity()
+    }
+}
+
+impl Tree {
+    /// Return the amount of bytes this tree occupies in memory, including all of its entries and their names.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        self.entries.capacity() * size_of::<tree::Entry>()
+            + self.entries.iter().map(|e| e.filename.capacity()).sum::<usize>()
+    }
+}
+
+impl Commit {
+    /// Return the amount of bytes this commit occupies in memory, including its signatures, message and extra headers.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        (if self.parents.spilled() {
+            self.parents.capacity() * size_of::<git_hash::ObjectId>()
+        } else {
+            0
+        }) + signature_heap_size(&self.author)
+            + signature_heap_size(&self.committer)
+            + self.encoding.as_ref().map_or(0, |e| e.capacity())
+            + self.message.capacity()
+            + self.extra_headers.capacity() * size_of::<(BString, BString)>()
+            + self
+                .extra_headers
+                .iter()
+                .map(|(k, v)| k.capacity() + v.capacity())
+                .sum::<usize>()
+    }
+}
+
+impl Tag {
+    /// Return the amount of bytes this tag occupies in memory, including its signature and message.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        self.name.capacity()
+            + self.tagger.as_ref().map_or(0, signature_heap_size)
+            + self.message.capacity()
+            + self.pgp_signature.as_ref().map_or(0, |s| s.capacity())
+    }
+}
+
+impl<'a> ObjectRef<'a> {
+    /// Return the amount of bytes this object occupies in memory, including the heap allocations it owns.
+    ///
+    /// As it borrows most of its data, the length of the buffer it was [decoded from][ObjectRef::from_bytes()] has to be
+    /// added to learn the total amount of memory needed to keep it.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>()
+            + match self {
+                ObjectRef::Tree(v) => v.heap_size(),
+                ObjectRef::Blob(v) => v.heap_size(),
+                ObjectRef::Commit(v) => v.heap_size(),
+                ObjectRef::Tag(v) => v.heap_size(),
+            }
+    }
+}
+
+impl<'a> BlobRef<'a> {
+    /// Return the amount of bytes this blob occupies in memory, without the buffer it borrows its data from.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<'a> TreeRef<'a> {
+    /// Return the amount of bytes this tree occupies in memory, including its entries but without the buffer it borrows their
+    /// names and ids from.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        self.entries.capacity() * size_of::<tree::EntryRef<'_>>()
+    }
+}
+
+impl<'a> CommitRef<'a> {
+    /// Return the amount of bytes this commit occupies in memory, including the heap allocations it owns but without the
+    /// buffer it borrows from.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        (if self.parents.spilled() {
+            self.parents.capacity() * size_of::<&BStr>()
+        } else {
+            0
+        }) + self.extra_headers.capacity() * size_of::<(&BStr, Cow<'_, BStr>)>()
+            + self
+                .extra_headers
+                .iter()
+                .map(|(_, v)| match v {
+                    Cow::Owned(v) => v.capacity(),
+                    Cow::Borrowed(_) => 0,
+                })
+                .sum::<usize>()
+    }
+}
+
+impl<'a> TagRef<'a> {
+    /// Return the amount of bytes this tag occupies in memory, without the buffer it borrows from.
+    pub fn size_in_memory(&self) -> usize {
+        size_of::<Self>() + self.heap_size()
+    }
+
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
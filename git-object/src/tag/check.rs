@@ -0,0 +1,83 @@
+use crate::bstr::{BString, ByteSlice};
+
+/// Determine how the `type` field is handled when decoding a tag with [`TagRef::from_bytes_checked()`][crate::TagRef::from_bytes_checked()].
+///
+/// The default is strict and fails on anything that isn't one of the known object kinds in lower case.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Accept object kinds in any case, like `Commit`, and report them as [issue][Kind::BadType] instead of failing.
+    pub case_insensitive_target_kind: bool,
+}
+
+impl Options {
+    /// Accept everything that can be decoded unambiguously and report it, as is appropriate when verifying objects
+    /// that already exist.
+    pub fn lenient() -> Self {
+        Options {
+            case_insensitive_target_kind: true,
+        }
+    }
+
+    pub(crate) fn target_kind(&self, raw: &[u8], issues: &mut Vec<Issue>) -> Option<crate::Kind> {
+        if let Ok(kind) = crate::Kind::from_bytes(raw) {
+            return Some(kind);
+        }
+        if !self.case_insensitive_target_kind {
+            return None;
+        }
+        let kind = crate::Kind::from_bytes(&raw.to_ascii_lowercase()).ok()?;
+        issues.push(Issue {
+            kind: Kind::BadType,
+            value: raw.into(),
+        });
+        Some(kind)
+    }
+}
+
+mod error {
+    use crate::bstr::BString;
+
+    /// Returned by [`TagRef::from_bytes_checked()`][crate::TagRef::from_bytes_checked()].
+    #[derive(Debug, Clone, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Decode(#[from] crate::decode::Error),
+        #[error("The tag points to an object of unknown type {kind:?}")]
+        UnknownTargetKind { kind: BString },
+    }
+}
+pub use error::Error;
+
+/// The kind of problem found in a tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Kind {
+    /// The `type` field names a known object kind, but not in lower case.
+    BadType,
+}
+
+impl Kind {
+    /// The identifier `git fsck` uses for this kind of problem.
+    pub fn fsck_id(&self) -> &'static str {
+        match self {
+            Kind::BadType => "badType",
+        }
+    }
+}
+
+/// A problem with a tag that could still be decoded.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Issue {
+    /// The kind of problem.
+    pub kind: Kind,
+    /// The offending value, as stored in the tag.
+    pub value: BString,
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {:?}", self.kind.fsck_id(), self.value.as_bstr())
+    }
+}
@@ -11,15 +11,23 @@ use nom::{
 use crate::{parse, parse::NL, BStr, ByteSlice, TagRef};
 
 pub fn git_tag<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(i: &'a [u8]) -> IResult<&[u8], TagRef<'a>, E> {
+    git_tag_with_kind(i, |kind| crate::Kind::from_bytes(kind).ok())
+}
+
+/// Like [`git_tag()`], but use `to_kind` to turn the value of the `type` field into a kind, failing if it returns `None`.
+pub fn git_tag_with_kind<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+    i: &'a [u8],
+    mut to_kind: impl FnMut(&[u8]) -> Option<crate::Kind>,
+) -> IResult<&[u8], TagRef<'a>, E> {
     let (i, target) = context("object <40 lowercase hex char>", |i| {
         parse::header_field(i, b"object", parse::hex_hash)
     })(i)?;
 
-    let (i, kind) = context("type <object kind>", |i| {
+    let (i, raw_kind) = context("type <object kind>", |i| {
         parse::header_field(i, b"type", take_while1(is_alphabetic))
     })(i)?;
-    let kind = crate::Kind::from_bytes(kind)
-        .map_err(|_| nom::Err::Error(E::from_error_kind(i, nom::error::ErrorKind::MapRes)))?;
+    let kind =
+        to_kind(raw_kind).ok_or_else(|| nom::Err::Error(E::from_error_kind(i, nom::error::ErrorKind::MapRes)))?;
 
     let (i, tag_version) = context("tag <version>", |i| {
         parse::header_field(i, b"tag", take_while1(|b| b != NL[0]))
@@ -36,6 +44,7 @@ pub fn git_tag<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(i: &'a [u8]
             target,
             name: tag_version.as_bstr(),
             target_kind: kind,
+            target_kind_raw: (kind.as_bytes() != raw_kind).then(|| raw_kind.as_bstr()),
             message,
             tagger: signature,
             pgp_signature,
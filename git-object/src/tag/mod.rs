@@ -1,4 +1,4 @@
-use crate::TagRef;
+use crate::{tag, TagRef};
 
 mod decode;
 
@@ -8,15 +8,54 @@ pub mod write;
 ///
 pub mod ref_iter;
 
+///
+pub mod check;
+
 impl<'a> TagRef<'a> {
     /// Deserialize a tag from `data`.
+    ///
+    /// The `type` field must be one of the known object kinds in lower case, like `commit`.
     pub fn from_bytes(data: &'a [u8]) -> Result<TagRef<'a>, crate::decode::Error> {
         decode::git_tag(data)
             .map(|(_, t)| t)
             .map_err(crate::decode::Error::from)
     }
+
+    /// Deserialize a tag from `data` while handling its `type` field as configured by `options`, and return all
+    /// problems that were tolerated along with it.
+    ///
+    /// Unknown object kinds always fail with an error carrying the value of the `type` field. If a `type` field is
+    /// accepted despite its case, it's kept in [`target_kind_raw`][TagRef::target_kind_raw] so that the tag serializes
+    /// to the bytes it was decoded from.
+    pub fn from_bytes_checked(
+        data: &'a [u8],
+        options: tag::check::Options,
+    ) -> Result<(TagRef<'a>, Vec<tag::check::Issue>), tag::check::Error> {
+        let mut issues = Vec::new();
+        let mut unknown_kind = None;
+        let res = decode::git_tag_with_kind(data, |raw| {
+            let kind = options.target_kind(raw, &mut issues);
+            if kind.is_none() {
+                unknown_kind = Some(raw.into());
+            }
+            kind
+        });
+        match res {
+            Ok((_, parsed)) => Ok((parsed, issues)),
+            Err(err) => Err(match unknown_kind {
+                Some(kind) => tag::check::Error::UnknownTargetKind { kind },
+                None => crate::decode::Error::from(err).into(),
+            }),
+        }
+    }
+
     /// The object this tag points to as `Id`.
+    pub fn target_id(&self) -> git_hash::ObjectId {
+        git_hash::ObjectId::from_hex(self.target).expect("prior validation of the hash length")
+    }
+
+    /// The object this tag points to as `Id`, the same as [`target_id()`][TagRef::target_id()].
     pub fn target(&self) -> git_hash::ObjectId {
-        git_hash::ObjectId::from_hex(self.target).expect("prior validation")
+        self.target_id()
     }
 }
@@ -61,7 +61,7 @@ impl crate::WriteTo for Tag {
 impl<'a> crate::WriteTo for TagRef<'a> {
     fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
         encode::trusted_header_field(b"object", self.target, &mut out)?;
-        encode::trusted_header_field(b"type", self.target_kind.as_bytes(), &mut out)?;
+        encode::trusted_header_field(b"type", self.target_kind_bytes(), &mut out)?;
         encode::header_field(b"tag", validated_name(self.name)?, &mut out)?;
         if let Some(tagger) = &self.tagger {
             encode::trusted_header_signature(b"tagger", tagger, &mut out)?;
@@ -83,8 +83,8 @@ impl<'a> crate::WriteTo for TagRef<'a> {
     }
 
     fn size(&self) -> usize {
-        b"object".len() + 1 /* space */ + self.target().kind().len_in_hex() + 1 /* nl */
-            + b"type".len() + 1 /* space */ + self.target_kind.as_bytes().len() + 1 /* nl */
+        b"object".len() + 1 /* space */ + self.target_id().kind().len_in_hex() + 1 /* nl */
+            + b"type".len() + 1 /* space */ + self.target_kind_bytes().len() + 1 /* nl */
             + b"tag".len() + 1 /* space */ + self.name.len() + 1 /* nl */
             + self
                 .tagger
@@ -96,6 +96,15 @@ impl<'a> crate::WriteTo for TagRef<'a> {
     }
 }
 
+impl<'a> TagRef<'a> {
+    fn target_kind_bytes(&self) -> &[u8] {
+        match self.target_kind_raw {
+            Some(raw) => raw,
+            None => self.target_kind.as_bytes(),
+        }
+    }
+}
+
 fn validated_name(name: &BStr) -> Result<&BStr, Error> {
     git_validate::tag::name(name)?;
     if name[0] == b'-' {
@@ -21,7 +21,7 @@ pub trait WriteTo {
 
     /// Returns a loose object header based on the object's data
     fn loose_header(&self) -> smallvec::SmallVec<[u8; 28]> {
-        crate::encode::loose_header(self.kind(), self.size())
+        crate::encode::loose_header(self.kind(), self.size() as u64)
     }
 }
 
@@ -0,0 +1,92 @@
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// Determine which of the entry problems that `git fsck` knows about are reported when decoding a tree with
+/// [`TreeRef::from_bytes_checked()`][crate::TreeRef::from_bytes_checked()].
+///
+/// The default is lenient and doesn't report anything, as is appropriate for read paths.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Report entries with an empty name.
+    pub empty_name: bool,
+    /// Report entries whose name contains a slash, making it look like a path instead of a single path component.
+    pub full_pathname: bool,
+    /// Report entries whose mode starts with a zero, like `040000`.
+    pub zero_padded_filemode: bool,
+}
+
+impl Options {
+    /// Report every kind of problem, as is appropriate when verifying objects from untrusted sources.
+    pub fn strict() -> Self {
+        Options {
+            empty_name: true,
+            full_pathname: true,
+            zero_padded_filemode: true,
+        }
+    }
+
+    pub(crate) fn check_entry(&self, entry_index: usize, mode: &[u8], name: &BStr, issues: &mut Vec<Issue>) {
+        let mut report = |kind| {
+            issues.push(Issue {
+                kind,
+                entry_index,
+                name: name.to_owned(),
+            })
+        };
+        if self.zero_padded_filemode && mode.first() == Some(&b'0') {
+            report(Kind::ZeroPaddedFilemode);
+        }
+        if self.empty_name && name.is_empty() {
+            report(Kind::EmptyName);
+        }
+        if self.full_pathname && name.contains(&b'/') {
+            report(Kind::FullPathname);
+        }
+    }
+}
+
+/// The kind of problem found in a tree entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Kind {
+    /// The name of the entry is empty.
+    EmptyName,
+    /// The name of the entry contains a slash.
+    FullPathname,
+    /// The mode of the entry has a leading zero.
+    ZeroPaddedFilemode,
+}
+
+impl Kind {
+    /// The identifier `git fsck` uses for this kind of problem.
+    pub fn fsck_id(&self) -> &'static str {
+        match self {
+            Kind::EmptyName => "emptyName",
+            Kind::FullPathname => "fullPathname",
+            Kind::ZeroPaddedFilemode => "zeroPaddedFilemode",
+        }
+    }
+}
+
+/// A problem with a single tree entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Issue {
+    /// The kind of problem.
+    pub kind: Kind,
+    /// The index of the entry in the tree, starting at 0.
+    pub entry_index: usize,
+    /// The name of the entry, as stored in the tree.
+    pub name: BString,
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: entry {} named {:?}",
+            self.kind.fsck_id(),
+            self.entry_index,
+            self.name.as_bstr()
+        )
+    }
+}
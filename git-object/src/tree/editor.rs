@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+
+use git_hash::{oid, ObjectId};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    tree::{compare_names, Entry, EntryMode},
+    Tree, TreeRefIter,
+};
+
+/// The error returned by [`Editor`] methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The path {path:?} is empty or has empty components")]
+    EmptyPathComponent { path: BString },
+    #[error("{path:?} can't be both a file and a directory")]
+    PathConflict { path: BString },
+    #[error("The tree {id} at {path:?} could not be found")]
+    TreeNotFound { id: ObjectId, path: BString },
+    #[error("The tree {id} at {path:?} could not be decoded")]
+    Decode {
+        id: ObjectId,
+        path: BString,
+        source: crate::decode::Error,
+    },
+}
+
+/// Edit a tree and its subtrees by path, and write the trees that changed bottom-up to obtain a new root tree.
+///
+/// Subtrees are only looked up when they are edited, so trees that aren't edited keep their ids without being rewritten.
+/// Directories are created as needed, and trees that end up empty are removed from their parent.
+pub struct Editor<Find> {
+    find: Find,
+    /// All trees that are loaded to be edited, keyed by their path, with the root tree at the empty path.
+    trees: BTreeMap<BString, Tree>,
+    buf: Vec<u8>,
+}
+
+impl<Find> Editor<Find>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
+{
+    /// Start editing `root`, which is [empty][Tree::empty()] to build a tree from scratch, using `find` to look up subtrees
+    /// by id once they need to be edited.
+    pub fn new(root: Tree, find: Find) -> Self {
+        Editor {
+            find,
+            trees: std::iter::once((BString::default(), root)).collect(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Insert an entry with `mode` and `id` at the slash-separated `path`, or update the entry that is already there,
+    /// creating all directories leading to it.
+    ///
+    /// Fail if the entry or one of its directories would turn a file into a directory or vice versa, which requires
+    /// [removing][Editor::remove()] it first. Upserting a tree replaces all edits made below `path` so far.
+    pub fn upsert(&mut self, path: impl AsRef<BStr>, mode: EntryMode, id: ObjectId) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        let name = file_name(path);
+        let tree = self
+            .parent_tree(path, Some(id.kind()))?
+            .expect("directories are created as needed");
+        match find_entry(&tree.entries, name, mode.is_tree()) {
+            Ok(index) => {
+                let entry = &mut tree.entries[index];
+                if entry.mode.is_tree() != mode.is_tree() {
+                    return Err(Error::PathConflict { path: path.into() });
+                }
+                entry.mode = mode;
+                entry.oid = id;
+            }
+            Err(index) => tree.entries.insert(
+                index,
+                Entry {
+                    mode,
+                    filename: name.into(),
+                    oid: id,
+                },
+            ),
+        }
+        if mode.is_tree() {
+            self.forget_trees_at(path);
+        }
+        Ok(self)
+    }
+
+    /// Remove the entry at the slash-separated `path` along with everything below it, or do nothing if there is no such entry.
+    pub fn remove(&mut self, path: impl AsRef<BStr>) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        if let Some(tree) = self.parent_tree(path, None)? {
+            if let Ok(index) = find_entry(&tree.entries, file_name(path), false) {
+                tree.entries.remove(index);
+            }
+            self.forget_trees_at(path);
+        }
+        Ok(self)
+    }
+
+    /// Write all edited trees bottom-up with `out`, which writes a single tree and returns its id, and return the id of the root tree.
+    ///
+    /// Trees that became empty are removed from their parent instead of being written, except for the root tree.
+    /// Afterwards, the editor continues from the written root tree, whose subtrees are looked up again as needed.
+    pub fn write<E>(&mut self, mut out: impl FnMut(&Tree) -> Result<ObjectId, E>) -> Result<ObjectId, E> {
+        let mut paths: Vec<BString> = self.trees.keys().filter(|path| !path.is_empty()).cloned().collect();
+        paths.sort_by_key(|path| std::cmp::Reverse(path.iter().filter(|b| **b == b'/').count()));
+        for path in paths {
+            let tree = &self.trees[&path];
+            let id = if tree.entries.is_empty() {
+                self.trees.remove(&path);
+                None
+            } else {
+                Some(out(tree)?)
+            };
+            let path: &[u8] = &path;
+            let (parent_path, name) = match path.rfind_byte(b'/') {
+                Some(pos) => (&path[..pos], &path[pos + 1..]),
+                None => (&b""[..], path),
+            };
+            let parent = self
+                .trees
+                .get_mut(parent_path.as_bstr())
+                .expect("parents of edited trees are loaded");
+            let index =
+                find_entry(&parent.entries, name.as_bstr(), true).expect("edited trees have an entry in their parent");
+            match id {
+                Some(id) => parent.entries[index].oid = id,
+                None => {
+                    parent.entries.remove(index);
+                }
+            }
+        }
+        let id = out(&self.trees[BStr::new("")])?;
+        self.trees.retain(|path, _| path.is_empty());
+        Ok(id)
+    }
+
+    /// Return the tree containing the entry at `path`, after loading all trees leading to it.
+    ///
+    /// If `create` is set, missing directories are created with null ids of the given kind, and otherwise `None` is returned
+    /// if one of them doesn't exist or isn't a directory.
+    fn parent_tree(&mut self, path: &BStr, create: Option<git_hash::Kind>) -> Result<Option<&mut Tree>, Error> {
+        if path.is_empty() || path.split_str("/").any(|component| component.is_empty()) {
+            return Err(Error::EmptyPathComponent { path: path.into() });
+        }
+        let mut dir = BString::default();
+        if let Some(pos) = path.rfind_byte(b'/') {
+            for name in path[..pos].split_str("/") {
+                let mut subdir = dir.clone();
+                if !subdir.is_empty() {
+                    subdir.push(b'/');
+                }
+                subdir.extend_from_slice(name);
+                if !self.trees.contains_key(&subdir) {
+                    let tree = self
+                        .trees
+                        .get_mut(&dir)
+                        .expect("parents are loaded before their children");
+                    let subtree = match (find_entry(&tree.entries, name.as_bstr(), true), create) {
+                        (Ok(index), _) if tree.entries[index].mode.is_tree() => {
+                            let id = tree.entries[index].oid;
+                            let entries = (self.find)(&id, &mut self.buf)
+                                .ok_or_else(|| Error::TreeNotFound {
+                                    id,
+                                    path: subdir.clone(),
+                                })?
+                                .map(|entry| entry.map(Into::into))
+                                .collect::<Result<_, _>>()
+                                .map_err(|source| Error::Decode {
+                                    id,
+                                    path: subdir.clone(),
+                                    source,
+                                })?;
+                            Tree { entries }
+                        }
+                        (Ok(_), Some(_)) => return Err(Error::PathConflict { path: subdir }),
+                        (Err(index), Some(object_hash)) => {
+                            tree.entries.insert(
+                                index,
+                                Entry {
+                                    mode: EntryMode::Tree,
+                                    filename: name.into(),
+                                    oid: ObjectId::null(object_hash),
+                                },
+                            );
+                            Tree::empty()
+                        }
+                        (_, None) => return Ok(None),
+                    };
+                    self.trees.insert(subdir.clone(), subtree);
+                }
+                dir = subdir;
+            }
+        }
+        Ok(self.trees.get_mut(&dir))
+    }
+
+    /// Forget about all edits of the tree at `path` and the trees below it.
+    fn forget_trees_at(&mut self, path: &BStr) {
+        self.trees.retain(|tree_path, _| {
+            tree_path
+                .strip_prefix(path.as_bytes())
+                .map_or(true, |rest| !(rest.is_empty() || rest[0] == b'/'))
+        });
+    }
+}
+
+/// Return the last component of `path`.
+fn file_name(path: &BStr) -> &BStr {
+    path.rfind_byte(b'/').map_or(path, |pos| path[pos + 1..].as_bstr())
+}
+
+/// Return the index of the entry named `name` whether it's a tree or not, or the index at which an entry named `name`
+/// would have to be inserted if it is a tree according to `is_tree`.
+fn find_entry(entries: &[Entry], name: &BStr, is_tree: bool) -> Result<usize, usize> {
+    let search = |is_tree: bool| {
+        entries.binary_search_by(|entry| compare_names(&entry.filename, entry.mode.is_tree(), name, is_tree))
+    };
+    search(is_tree).or_else(|index| search(!is_tree).map_err(|_| index))
+}
@@ -5,6 +5,11 @@ use crate::{
     tree,
 };
 
+///
+pub mod check;
+///
+pub mod editor;
+pub use editor::Editor;
 mod ref_iter;
 ///
 pub mod write;
@@ -44,6 +49,22 @@ impl EntryMode {
         matches!(self, EntryMode::Blob | EntryMode::BlobExecutable)
     }
 
+    /// Return true if the entry is a commit of a submodule, also known as gitlink, which lives in another repository.
+    pub fn is_commit(&self) -> bool {
+        *self == EntryMode::Commit
+    }
+
+    /// Return the kind of object this entry points to.
+    pub fn kind(&self) -> EntryKind {
+        use EntryMode::*;
+        match self {
+            Tree => EntryKind::Tree,
+            Blob | BlobExecutable => EntryKind::Blob,
+            Link => EntryKind::Link,
+            Commit => EntryKind::Gitlink,
+        }
+    }
+
     /// Represent the mode as descriptive string.
     pub fn as_str(&self) -> &'static str {
         use EntryMode::*;
@@ -57,6 +78,20 @@ impl EntryMode {
     }
 }
 
+/// The kind of object a tree entry points to, as determined by its [mode][EntryMode::kind()].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum EntryKind {
+    /// A tree in the same repository.
+    Tree,
+    /// A blob in the same repository, which may be executable.
+    Blob,
+    /// A blob in the same repository with the path a symbolic link points to.
+    Link,
+    /// A commit of a submodule, which lives in another repository and thus can't be found in the object database.
+    Gitlink,
+}
+
 /// An element of a [`TreeRef`][crate::TreeRef::entries].
 #[derive(PartialEq, Eq, Debug, Hash, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -105,15 +140,27 @@ impl PartialOrd for Entry {
 }
 
 impl Ord for Entry {
-    /// Entries compare by the common portion of the filename. This is critical for proper functioning of algorithms working on trees.
+    /// Entries are ordered like git orders them in trees, which is critical for proper functioning of algorithms working on trees.
     fn cmp(&self, other: &Self) -> Ordering {
-        let common_len = self.filename.len().min(other.filename.len());
-        self.filename[..common_len]
-            .cmp(&other.filename[..common_len])
-            .then_with(|| self.filename.len().cmp(&other.filename.len()))
+        compare_names(
+            &self.filename,
+            self.mode.is_tree(),
+            &other.filename,
+            other.mode.is_tree(),
+        )
     }
 }
 
+/// Compare entry names the way git orders the entries of a tree, that is as if the names of trees had a trailing slash.
+pub(crate) fn compare_names(a: &[u8], a_is_tree: bool, b: &[u8], b_is_tree: bool) -> Ordering {
+    let common_len = a.len().min(b.len());
+    a[..common_len].cmp(&b[..common_len]).then_with(|| {
+        let a = a.get(common_len).copied().or_else(|| a_is_tree.then(|| b'/'));
+        let b = b.get(common_len).copied().or_else(|| b_is_tree.then(|| b'/'));
+        a.cmp(&b)
+    })
+}
+
 /// Serialization
 impl EntryMode {
     /// Return the representation as used in the git internal format.
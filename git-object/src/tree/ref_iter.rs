@@ -1,4 +1,4 @@
-use std::convert::TryFrom;
+use std::{cmp::Ordering, convert::TryFrom};
 
 use nom::error::ParseError;
 
@@ -17,6 +17,75 @@ impl<'a> TreeRef<'a> {
         decode::tree(data).map(|(_, t)| t).map_err(crate::decode::Error::from)
     }
 
+    /// Deserialize a Tree from `data` while accepting entries with empty names or zero-padded modes, and return all entries
+    /// that have problems as selected by `options` along with it.
+    ///
+    /// This is useful when verifying trees from untrusted sources, as it's possible to learn about all problematic entries
+    /// instead of failing on the first one.
+    pub fn from_bytes_checked(
+        data: &'a [u8],
+        options: tree::check::Options,
+    ) -> Result<(TreeRef<'a>, Vec<tree::check::Issue>), crate::decode::Error> {
+        let mut entries = Vec::new();
+        let mut issues = Vec::new();
+        let mut data = data;
+        while !data.is_empty() {
+            let (data_left, mode, entry) = decode::fast_entry_with_mode(data).ok_or_else(decode_error)?;
+            options.check_entry(entries.len(), mode, entry.filename, &mut issues);
+            entries.push(entry);
+            data = data_left;
+        }
+        Ok((TreeRef { entries }, issues))
+    }
+
+    /// Like [`from_bytes_checked()`][TreeRef::from_bytes_checked()], but only append the problems of the tree in `data` to
+    /// `issues` without keeping its entries.
+    ///
+    /// This doesn't allocate unless problems are found, which makes it suitable for checking many trees with `issues` being reused.
+    pub fn check(
+        data: &[u8],
+        options: tree::check::Options,
+        issues: &mut Vec<tree::check::Issue>,
+    ) -> Result<(), crate::decode::Error> {
+        let mut data = data;
+        let mut entry_index = 0;
+        while !data.is_empty() {
+            let (data_left, mode, entry) = decode::fast_entry_with_mode(data).ok_or_else(decode_error)?;
+            options.check_entry(entry_index, mode, entry.filename, issues);
+            entry_index += 1;
+            data = data_left;
+        }
+        Ok(())
+    }
+
+    /// Return true if `data` is a tree in its canonical serialization, which is when it decodes with
+    /// [`from_bytes()`][TreeRef::from_bytes()] and re-encodes into exactly the same bytes.
+    ///
+    /// This is determined by a single scan over all entries and is much cheaper than decoding and re-encoding.
+    /// It's stricter than necessary though as it also rejects trees whose entries aren't sorted or have duplicate names,
+    /// so a `false` return value only means that the tree needs closer inspection.
+    pub fn is_canonical(data: &[u8]) -> bool {
+        let mut previous: Option<EntryRef<'_>> = None;
+        let mut data = data;
+        while !data.is_empty() {
+            let (data_left, mode, entry) = match decode::fast_entry_with_mode(data) {
+                Some(res) => res,
+                None => return false,
+            };
+            if mode != entry.mode.as_bytes() || entry.filename.is_empty() || entry.filename.contains(&b'\n') {
+                return false;
+            }
+            if let Some(previous) = previous {
+                if previous.filename == entry.filename || cmp_in_tree_order(&previous, &entry) != Ordering::Less {
+                    return false;
+                }
+            }
+            previous = Some(entry);
+            data = data_left;
+        }
+        true
+    }
+
     /// Create an instance of the empty tree.
     ///
     /// It's particularly useful as static part of a program.
@@ -46,17 +115,31 @@ impl<'a> Iterator for TreeRefIter<'a> {
             }
             None => {
                 self.data = &[];
-                #[allow(clippy::unit_arg)]
-                Some(Err(nom::Err::Error(crate::decode::ParseError::from_error_kind(
-                    &[] as &[u8],
-                    nom::error::ErrorKind::MapRes,
-                ))
-                .into()))
+                Some(Err(decode_error()))
             }
         }
     }
 }
 
+/// Compare entries like git sorts them in trees, as if the names of trees had a trailing slash.
+fn cmp_in_tree_order(a: &EntryRef<'_>, b: &EntryRef<'_>) -> Ordering {
+    let with_slash_if_tree = |e: &EntryRef<'_>| if e.mode.is_tree() { Some(b'/') } else { None };
+    a.filename
+        .iter()
+        .copied()
+        .chain(with_slash_if_tree(a))
+        .cmp(b.filename.iter().copied().chain(with_slash_if_tree(b)))
+}
+
+fn decode_error() -> crate::decode::Error {
+    #[allow(clippy::unit_arg)]
+    nom::Err::Error(crate::decode::ParseError::from_error_kind(
+        &[] as &[u8],
+        nom::error::ErrorKind::MapRes,
+    ))
+    .into()
+}
+
 impl<'a> TryFrom<&'a [u8]> for tree::EntryMode {
     type Error = &'a [u8];
 
@@ -110,6 +193,11 @@ mod decode {
     const NULL: &[u8] = b"\0";
 
     pub fn fast_entry(i: &[u8]) -> Option<(&[u8], EntryRef<'_>)> {
+        fast_entry_with_mode(i).map(|(i, _mode, entry)| (i, entry))
+    }
+
+    /// Like [`fast_entry()`], but also return the mode exactly as it is stored.
+    pub fn fast_entry_with_mode(i: &[u8]) -> Option<(&[u8], &[u8], EntryRef<'_>)> {
         let mut mode = 0u32;
         let mut spacer_pos = 1;
         for b in i.iter().take_while(|b| **b != b' ') {
@@ -119,7 +207,11 @@ mod decode {
             mode = (mode << 3) + (b - b'0') as u32;
             spacer_pos += 1;
         }
-        let (_, i) = i.split_at(spacer_pos);
+        if spacer_pos > i.len() {
+            return None;
+        }
+        let (mode_bytes, i) = i.split_at(spacer_pos);
+        let mode_bytes = &mode_bytes[..mode_bytes.len() - 1];
         let mode = tree::EntryMode::try_from(mode).ok()?;
         let (filename, i) = i.split_at(i.find_byte(0)?);
         let i = &i[1..];
@@ -130,6 +222,7 @@ mod decode {
         };
         Some((
             i,
+            mode_bytes,
             EntryRef {
                 mode,
                 filename: filename.as_bstr(),
@@ -0,0 +1,152 @@
+use bstr::ByteSlice;
+
+use crate::{Kind, ObjectRef, WriteTo};
+
+///
+pub mod roundtrip {
+    use std::fmt;
+
+    use bstr::BString;
+
+    /// A known and benign reason for an object to not re-serialize into its original bytes.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Normalization {
+        /// A tree contains entries with the non-standard modes `100664` or `100640` which are written as `100644` instead.
+        ///
+        /// Such trees were produced by old versions of git and are still valid.
+        TreeEntryMode,
+    }
+
+    impl fmt::Display for Normalization {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Normalization::TreeEntryMode => f.write_str("replacing mode '100664|100640' with '100644'"),
+            }
+        }
+    }
+
+    /// Returned by [`roundtrip()`][super::roundtrip()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Decode(#[from] crate::decode::Error),
+        #[error("{kind} object wasn't re-encoded without change{}", .normalization.map(|n| format!(", which is expected when {}", n)).unwrap_or_default())]
+        Mismatch {
+            kind: crate::Kind,
+            /// The original bytes of the object.
+            expected: BString,
+            /// The bytes of the object after decoding and encoding it.
+            actual: BString,
+            /// If set, the mismatch is known to be benign.
+            normalization: Option<Normalization>,
+        },
+    }
+
+    impl Error {
+        /// Return the reason for a mismatch if it is known to be benign, or `None` for all other errors.
+        pub fn normalization(&self) -> Option<Normalization> {
+            match self {
+                Error::Mismatch { normalization, .. } => *normalization,
+                Error::Decode(_) => None,
+            }
+        }
+    }
+}
+
+/// The class of a finding that doesn't fail the verification of an object, used to count affected objects by class.
+///
+/// It covers both [benign normalizations][roundtrip::Normalization] and the [tree entry problems][crate::tree::check::Kind]
+/// `git fsck` knows about, with new classes only ever being added. When serialized or [displayed][WarningClass::as_str()],
+/// the `git fsck` identifier is used where there is one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum WarningClass {
+    /// A tree contains entries with the non-standard modes `100664` or `100640`, see
+    /// [`Normalization::TreeEntryMode`][roundtrip::Normalization::TreeEntryMode].
+    #[cfg_attr(feature = "serde1", serde(rename = "nonCanonicalTreeEntryMode"))]
+    NonCanonicalTreeEntryMode,
+    /// A tree contains an entry with an empty name, see [`Kind::EmptyName`][crate::tree::check::Kind::EmptyName].
+    #[cfg_attr(feature = "serde1", serde(rename = "emptyName"))]
+    EmptyName,
+    /// A tree contains an entry whose name contains a slash, see [`Kind::FullPathname`][crate::tree::check::Kind::FullPathname].
+    #[cfg_attr(feature = "serde1", serde(rename = "fullPathname"))]
+    FullPathname,
+    /// A tree contains an entry whose mode has a leading zero, see
+    /// [`Kind::ZeroPaddedFilemode`][crate::tree::check::Kind::ZeroPaddedFilemode].
+    #[cfg_attr(feature = "serde1", serde(rename = "zeroPaddedFilemode"))]
+    ZeroPaddedFilemode,
+}
+
+impl WarningClass {
+    /// All classes, in their natural order.
+    pub const ALL: &'static [WarningClass] = &[
+        WarningClass::NonCanonicalTreeEntryMode,
+        WarningClass::EmptyName,
+        WarningClass::FullPathname,
+        WarningClass::ZeroPaddedFilemode,
+    ];
+
+    /// The stable identifier of this class, which is also used when serializing it.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningClass::NonCanonicalTreeEntryMode => "nonCanonicalTreeEntryMode",
+            WarningClass::EmptyName => crate::tree::check::Kind::EmptyName.fsck_id(),
+            WarningClass::FullPathname => crate::tree::check::Kind::FullPathname.fsck_id(),
+            WarningClass::ZeroPaddedFilemode => crate::tree::check::Kind::ZeroPaddedFilemode.fsck_id(),
+        }
+    }
+}
+
+impl std::fmt::Display for WarningClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<roundtrip::Normalization> for WarningClass {
+    fn from(normalization: roundtrip::Normalization) -> Self {
+        match normalization {
+            roundtrip::Normalization::TreeEntryMode => WarningClass::NonCanonicalTreeEntryMode,
+        }
+    }
+}
+
+impl From<crate::tree::check::Kind> for WarningClass {
+    fn from(kind: crate::tree::check::Kind) -> Self {
+        use crate::tree::check::Kind;
+        match kind {
+            Kind::EmptyName => WarningClass::EmptyName,
+            Kind::FullPathname => WarningClass::FullPathname,
+            Kind::ZeroPaddedFilemode => WarningClass::ZeroPaddedFilemode,
+        }
+    }
+}
+
+/// Decode `data` as object of `kind` and encode it again, failing if the result isn't exactly `data`.
+///
+/// This assures that an object is valid and that writing it after decoding will not change its hash.
+/// Blobs can't be decoded incorrectly and are always accepted. Objects that decode but can't be encoded anymore fail with a mismatch
+/// as well.
+///
+/// Trees which contain the non-standard modes `100664` or `100640` will fail with a mismatch whose
+/// [`normalization()`][roundtrip::Error::normalization()] is set, which callers may choose to tolerate.
+pub fn roundtrip(kind: Kind, data: &[u8]) -> Result<(), roundtrip::Error> {
+    if kind == Kind::Blob {
+        return Ok(());
+    }
+    let object = ObjectRef::from_bytes(kind, data)?;
+    let mut buf = Vec::with_capacity(data.len());
+    // Objects that can't be written, like trees with newlines in filenames, end up with a partial buffer which never matches.
+    if object.write_to(&mut buf).is_ok() && buf == data {
+        return Ok(());
+    }
+    let normalization = (kind == Kind::Tree && (data.find(b"100664").is_some() || data.find(b"100640").is_some()))
+        .then(|| roundtrip::Normalization::TreeEntryMode);
+    Err(roundtrip::Error::Mismatch {
+        kind,
+        expected: data.into(),
+        actual: buf.into(),
+        normalization,
+    })
+}
@@ -0,0 +1,60 @@
+use git_object::{compute_hash, compute_stream_hash, Kind};
+
+use crate::{fixture_bytes, hex_to_id};
+
+#[test]
+fn well_known_ids() {
+    for (kind, data, expected) in [
+        (Kind::Blob, &b""[..], "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        (Kind::Tree, b"", "4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+        (Kind::Blob, b"hello world\n", "3b18e512dba79e4c8300dd08aeb37f8e728b8dad"),
+    ] {
+        let expected = hex_to_id(expected);
+        assert_eq!(compute_hash(kind, data, git_hash::Kind::Sha1), expected);
+        assert_eq!(
+            compute_hash(kind, data, git_hash::Kind::Sha1).kind(),
+            git_hash::Kind::Sha1
+        );
+        assert_eq!(
+            compute_stream_hash(kind, &mut &*data, data.len() as u64, git_hash::Kind::Sha1).unwrap(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn streaming_and_in_memory_hashing_agree_on_all_fixtures() -> crate::Result {
+    for (kind, dir) in [(Kind::Commit, "commit"), (Kind::Tag, "tag"), (Kind::Tree, "tree")] {
+        for entry in std::fs::read_dir(crate::fixture(dir))? {
+            let path = entry?.path();
+            let data = fixture_bytes(&format!(
+                "{}/{}",
+                dir,
+                path.file_name().expect("file").to_string_lossy()
+            ));
+            let id = compute_hash(kind, &data, git_hash::Kind::Sha1);
+            assert_eq!(
+                compute_stream_hash(kind, &mut data.as_slice(), data.len() as u64, git_hash::Kind::Sha1)?,
+                id,
+                "{:?}",
+                path
+            );
+            git_object::Data::new(kind, &data).verify_checksum(id)?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn streams_ending_early_are_an_error() {
+    let err = compute_stream_hash(Kind::Blob, &mut &b"abc"[..], 4, git_hash::Kind::Sha1).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn streams_are_only_read_up_to_the_given_length() {
+    assert_eq!(
+        compute_stream_hash(Kind::Blob, &mut &b"hello world\nand more"[..], 12, git_hash::Kind::Sha1).unwrap(),
+        hex_to_id("3b18e512dba79e4c8300dd08aeb37f8e728b8dad")
+    );
+}
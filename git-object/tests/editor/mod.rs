@@ -0,0 +1,157 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use git_hash::ObjectId;
+use git_object::{
+    bstr::BString,
+    tree::{editor, EntryMode},
+    Tree, TreeRefIter, WriteTo,
+};
+
+use crate::hex_to_id;
+
+/// An in-memory object database for trees.
+#[derive(Default)]
+struct Trees {
+    objects: RefCell<HashMap<ObjectId, Vec<u8>>>,
+    writes: RefCell<usize>,
+}
+
+impl Trees {
+    fn find<'a>(&self, id: &git_hash::oid, buf: &'a mut Vec<u8>) -> Option<TreeRefIter<'a>> {
+        let objects = self.objects.borrow();
+        buf.clear();
+        buf.extend_from_slice(objects.get(&id.to_owned())?);
+        Some(TreeRefIter::from_bytes(buf))
+    }
+
+    fn write(&self, tree: &Tree) -> std::io::Result<ObjectId> {
+        let mut buf = Vec::new();
+        tree.write_to(&mut buf)?;
+        let mut hasher = git_features::hash::hasher(git_hash::Kind::Sha1);
+        hasher.update(&tree.loose_header());
+        hasher.update(&buf);
+        let id = ObjectId::from(hasher.digest());
+        self.objects.borrow_mut().insert(id, buf);
+        *self.writes.borrow_mut() += 1;
+        Ok(id)
+    }
+}
+
+fn empty_blob() -> ObjectId {
+    hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")
+}
+
+fn hello_blob() -> ObjectId {
+    hex_to_id("ce013625030ba8dba906f756967f9e9ca394464a")
+}
+
+/// Produce the tree which `git mktree --missing` produces bottom-up with these inputs:
+///
+/// ```text
+/// bin:  100755 blob ce01362…  main.rs
+/// src:  040000 tree <bin>     bin
+///       100644 blob e69de29…  lib.rs
+/// root: 100644 blob ce01362…  README
+///       120000 blob e69de29…  link
+///       040000 tree <src>     src
+///       100644 blob ce01362…  src.rs
+/// ```
+fn upsert_all(
+    editor: &mut editor::Editor<impl for<'a> FnMut(&git_hash::oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>>,
+) {
+    editor
+        .upsert("src/bin/main.rs", EntryMode::BlobExecutable, hello_blob())
+        .unwrap()
+        .upsert("src.rs", EntryMode::Blob, hello_blob())
+        .unwrap()
+        .upsert("README", EntryMode::Blob, hello_blob())
+        .unwrap()
+        .upsert("src/lib.rs", EntryMode::Blob, empty_blob())
+        .unwrap()
+        .upsert("link", EntryMode::Link, empty_blob())
+        .unwrap();
+}
+
+#[test]
+fn nested_trees_are_synthesized_and_sorted_like_git() -> crate::Result {
+    let odb = Trees::default();
+    let mut editor = editor::Editor::new(Tree::empty(), |id, buf| odb.find(id, buf));
+    upsert_all(&mut editor);
+    let root = editor.write(|tree| odb.write(tree))?;
+    assert_eq!(root, hex_to_id("f670d4ad84a7c8e17392edc34ab0ec7f7e711a31"));
+    assert_eq!(*odb.writes.borrow(), 3, "one write per tree");
+    Ok(())
+}
+
+#[test]
+fn editing_a_base_tree_only_rewrites_changed_subtrees() -> crate::Result {
+    let odb = Trees::default();
+    let base = {
+        let mut editor = editor::Editor::new(Tree::empty(), |id, buf| odb.find(id, buf));
+        upsert_all(&mut editor);
+        editor.write(|tree| odb.write(tree))?
+    };
+    let base_tree = Tree {
+        entries: TreeRefIter::from_bytes(&odb.objects.borrow()[&base])
+            .map(|entry| entry.map(Into::into))
+            .collect::<Result<_, _>>()?,
+    };
+    *odb.writes.borrow_mut() = 0;
+
+    let mut editor = editor::Editor::new(base_tree, |id, buf| odb.find(id, buf));
+    editor.upsert("docs/guide.md", EntryMode::Blob, hello_blob())?.upsert(
+        "README",
+        EntryMode::BlobExecutable,
+        hello_blob(),
+    )?;
+    assert_eq!(
+        editor.write(|tree| odb.write(tree))?,
+        hex_to_id("435f4a7caacfec8dabc079474d88fb6f22565ccf"),
+        "root and docs, as `git mktree` would produce with 'docs' added and 'README' being executable"
+    );
+    assert_eq!(*odb.writes.borrow(), 2, "'src' isn't rewritten as it didn't change");
+
+    editor.remove("src/bin/main.rs")?.remove("does/not/exist")?;
+    assert_eq!(
+        editor.write(|tree| odb.write(tree))?,
+        hex_to_id("d994597c44e7ddd3fc480a2f7ec169fe272e41eb"),
+        "'src/bin' became empty and was removed, similar to what `git mktree` would produce without it"
+    );
+    Ok(())
+}
+
+#[test]
+fn files_and_directories_at_the_same_path_conflict() -> crate::Result {
+    let odb = Trees::default();
+    let mut editor = editor::Editor::new(Tree::empty(), |id, buf| odb.find(id, buf));
+    editor
+        .upsert("file", EntryMode::Blob, empty_blob())?
+        .upsert("dir/file", EntryMode::Blob, empty_blob())?;
+
+    for (path, mode, conflicting_path) in [
+        ("file/nested", EntryMode::Blob, "file"),
+        ("dir", EntryMode::Blob, "dir"),
+        ("file", EntryMode::Tree, "file"),
+    ] {
+        match editor.upsert(path, mode, empty_blob()) {
+            Err(editor::Error::PathConflict { path }) => assert_eq!(path, BString::from(conflicting_path)),
+            _ => unreachable!("{path:?} must conflict"),
+        }
+    }
+
+    editor
+        .remove("file")?
+        .upsert("file/nested", EntryMode::Blob, empty_blob())?;
+    Ok(())
+}
+
+#[test]
+fn empty_path_components_are_rejected() {
+    let mut editor = editor::Editor::new(Tree::empty(), |_, _| None);
+    for path in ["", "/a", "a/", "a//b"] {
+        assert!(matches!(
+            editor.upsert(path, EntryMode::Blob, empty_blob()),
+            Err(editor::Error::EmptyPathComponent { .. })
+        ));
+    }
+}
@@ -68,6 +68,25 @@ mod tag {
         "tag/with-newlines.txt",
         "tag/signed.txt"
     );
+
+    #[test]
+    fn signature_stays_in_place_if_unrelated_fields_change() {
+        use bstr::ByteSlice;
+        use git_object::{Tag, TagRef, WriteTo};
+
+        let data = crate::fixture_bytes("tag/signed.txt");
+        let mut tag: Tag = TagRef::from_bytes(&data).expect("valid tag").into();
+        assert!(tag.pgp_signature.is_some());
+        tag.name = "2.0.0".into();
+
+        let mut out = Vec::new();
+        tag.write_to(&mut out).expect("write to memory never fails");
+        assert_eq!(
+            out.as_bstr(),
+            data.replace(b"\ntag 1.0.0\n", b"\ntag 2.0.0\n").as_bstr(),
+            "only the name changed"
+        );
+    }
 }
 
 mod commit {
@@ -85,6 +104,126 @@ mod commit {
         "commit/whitespace.txt",
         "commit/with-encoding.txt"
     );
+
+    mod signature {
+        use bstr::ByteSlice;
+        use git_object::{Commit, CommitRef, WriteTo};
+
+        use crate::{fixture_bytes, hex_to_id};
+
+        const SIGNED: &[&str] = &[
+            "commit/signed.txt",
+            "commit/signed-singleline.txt",
+            "commit/signed-whitespace.txt",
+            "commit/signed-with-encoding.txt",
+        ];
+
+        fn commit(path: &str) -> (Vec<u8>, Commit) {
+            let data = fixture_bytes(path);
+            let commit = CommitRef::from_bytes(&data).expect("valid commit").into();
+            (data, commit)
+        }
+
+        fn written(commit: &Commit) -> Vec<u8> {
+            let mut out = Vec::new();
+            commit.write_to(&mut out).expect("write to memory never fails");
+            out
+        }
+
+        /// Remove the `gpgsig` header along with its continuation lines from `data`.
+        fn without_signature_header(data: &[u8]) -> Vec<u8> {
+            let mut out = Vec::new();
+            let mut in_signature = false;
+            let mut in_headers = true;
+            for line in data.lines_with_terminator() {
+                if in_headers {
+                    if line == b"\n" {
+                        in_headers = false;
+                    } else if line.starts_with(b"gpgsig ") {
+                        in_signature = true;
+                        continue;
+                    } else if in_signature && line.starts_with(b" ") {
+                        continue;
+                    } else {
+                        in_signature = false;
+                    }
+                }
+                out.extend_from_slice(line);
+            }
+            out
+        }
+
+        #[test]
+        fn is_detected_and_survives_the_conversion_to_an_owned_commit() {
+            for path in SIGNED {
+                let (data, commit) = commit(path);
+                assert!(commit.has_signature(), "{}", path);
+                assert_eq!(
+                    commit.extra_headers().pgp_signature(),
+                    CommitRef::from_bytes(&data).unwrap().extra_headers().pgp_signature(),
+                    "{}",
+                    path
+                );
+                assert_eq!(written(&commit).as_bstr(), data.as_bstr(), "{}", path);
+            }
+            for path in ["commit/unsigned.txt", "commit/mergetag.txt", "commit/with-encoding.txt"] {
+                assert!(!commit(path).1.has_signature(), "{}", path);
+            }
+        }
+
+        #[test]
+        fn stays_in_place_if_unrelated_fields_change() {
+            for path in SIGNED {
+                let (data, mut commit) = commit(path);
+                let new_parent = hex_to_id("cccccccccccccccccccccccccccccccccccccccc");
+                commit.parents.push(new_parent);
+
+                let committer_line = data.find(b"\ncommitter ").expect("committer") + 1;
+                let author_line = data[..committer_line]
+                    .rfind(b"author ")
+                    .expect("author before committer");
+                let mut expected = data[..author_line].to_vec();
+                expected.extend_from_slice(format!("parent {}\n", new_parent).as_bytes());
+                expected.extend_from_slice(&data[author_line..]);
+
+                assert!(
+                    commit.has_signature(),
+                    "the signature is still there, but is now invalid"
+                );
+                assert_eq!(
+                    written(&commit).as_bstr(),
+                    expected.as_bstr(),
+                    "{}: only the new parent line was added",
+                    path
+                );
+            }
+        }
+
+        #[test]
+        fn can_be_removed_without_affecting_other_headers() {
+            for path in SIGNED {
+                let (data, mut commit) = commit(path);
+                let expected_signature = commit.extra_headers().pgp_signature().map(ToOwned::to_owned);
+                assert_eq!(commit.remove_signature(), expected_signature, "{}", path);
+                assert!(!commit.has_signature());
+                assert_eq!(
+                    written(&commit).as_bstr(),
+                    without_signature_header(&data).as_bstr(),
+                    "{}: only the signature header was removed",
+                    path
+                );
+                assert_eq!(commit.remove_signature(), None, "there is nothing left to remove");
+            }
+
+            let (data, mut commit) = commit("commit/mergetag.txt");
+            assert_eq!(commit.remove_signature(), None);
+            assert_eq!(
+                written(&commit).as_bstr(),
+                data.as_bstr(),
+                "other multi-line headers are kept"
+            );
+        }
+    }
 }
 
 mod tree {
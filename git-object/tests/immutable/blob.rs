@@ -0,0 +1,92 @@
+use git_object::{bstr::ByteSlice, BlobRef};
+
+mod is_binary {
+    use git_object::BlobRef;
+
+    #[test]
+    fn nul_byte_within_detection_limit() {
+        assert!(BlobRef { data: b"hello\0world" }.is_binary());
+        assert!(!BlobRef { data: b"hello world\n" }.is_binary());
+        assert!(!BlobRef { data: b"" }.is_binary());
+    }
+
+    #[test]
+    fn nul_byte_past_detection_limit_is_ignored() {
+        let mut data = vec![b'a'; git_object::blob::BINARY_DETECTION_LIMIT];
+        data.push(0);
+        assert!(!BlobRef { data: &data }.is_binary());
+    }
+
+    #[test]
+    fn invalid_utf8_is_not_binary_by_itself() {
+        assert!(!BlobRef {
+            data: b"\xff\xfe invalid"
+        }
+        .is_binary());
+    }
+}
+
+#[test]
+fn lines_handle_crlf_and_missing_trailing_newline() {
+    let blob = BlobRef {
+        data: b"one\r\ntwo\n\nthree",
+    };
+    assert_eq!(
+        blob.lines().collect::<Vec<_>>(),
+        vec!["one".as_bytes().as_bstr(), "two".into(), "".into(), "three".into()]
+    );
+
+    let blob = BlobRef { data: b"one\ntwo\n" };
+    assert_eq!(blob.lines().count(), 2, "no empty line after the trailing newline");
+    assert_eq!(BlobRef { data: b"" }.lines().count(), 0);
+}
+
+#[test]
+fn lines_with_invalid_utf8() {
+    let blob = BlobRef { data: b"\xff\xfe\nok" };
+    assert_eq!(
+        blob.lines().collect::<Vec<_>>(),
+        vec![b"\xff\xfe".as_bstr(), b"ok".as_bstr()]
+    );
+}
+
+mod preview {
+    use git_object::{bstr::ByteSlice, BlobRef};
+
+    #[test]
+    fn everything_fits() {
+        let preview = BlobRef { data: b"hello\nworld" }.preview(100);
+        assert_eq!(preview.data, "hello\nworld");
+        assert!(!preview.truncated);
+    }
+
+    #[test]
+    fn truncates_after_the_last_complete_line() {
+        let preview = BlobRef {
+            data: b"hello\nworld\nand more",
+        }
+        .preview(14);
+        assert_eq!(preview.data, "hello\nworld\n");
+        assert!(preview.truncated);
+    }
+
+    #[test]
+    fn truncates_on_char_boundary_without_newline() {
+        let data = "aä€".as_bytes();
+        assert_eq!(data.len(), 6);
+        let preview = BlobRef { data }.preview(4);
+        assert_eq!(preview.data, "aä", "the euro sign would be cut in half");
+        assert!(preview.truncated);
+
+        let preview = BlobRef { data }.preview(2);
+        assert_eq!(preview.data, "a");
+    }
+
+    #[test]
+    fn invalid_utf8_is_truncated_at_max_bytes() {
+        let data = b"\x80\x80\x80\x80\x80\x80";
+        let preview = BlobRef { data }.preview(5);
+        assert_eq!(preview.data, data[..5].as_bstr());
+        assert!(preview.truncated);
+    }
+}
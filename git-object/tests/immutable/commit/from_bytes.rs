@@ -143,6 +143,7 @@ fn with_trailer() -> crate::Result {
             offset_in_seconds: 7200,
             sign: Sign::Plus,
         },
+        raw_time: None,
     };
     let backing = fixture_bytes("commit", "message-with-footer.txt");
     let commit = CommitRef::from_bytes(&backing)?;
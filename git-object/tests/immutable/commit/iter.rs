@@ -175,7 +175,7 @@ fn mergetag() -> crate::Result {
 }
 
 mod method {
-    use git_object::CommitRefIter;
+    use git_object::{bstr::ByteSlice, CommitRefIter};
 
     use crate::{
         hex_to_id,
@@ -198,6 +198,44 @@ mod method {
         Ok(())
     }
 
+    #[test]
+    fn parent_ids_from_bytes() -> crate::Result {
+        for name in [
+            "unsigned.txt",
+            "signed.txt",
+            "merge.txt",
+            "mergetag.txt",
+            "two-multiline-headers.txt",
+            "with-encoding.txt",
+        ] {
+            let input = fixture_bytes("commit", name);
+            assert_eq!(
+                CommitRefIter::parent_ids_from_bytes(&input)?.into_vec(),
+                CommitRefIter::from_bytes(&input).parent_ids().collect::<Vec<_>>(),
+                "{}",
+                name
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parent_ids_from_bytes_ignores_everything_after_the_parents() -> crate::Result {
+        let input = fixture_bytes("commit", "merge.txt");
+        let headers_end = input.find("author ").expect("author present");
+        let mut truncated = input[..headers_end].to_vec();
+        truncated.extend_from_slice(b"not a valid signature");
+        assert_eq!(
+            CommitRefIter::parent_ids_from_bytes(&truncated)?.into_vec(),
+            CommitRefIter::from_bytes(&input).parent_ids().collect::<Vec<_>>()
+        );
+        assert!(
+            CommitRefIter::parent_ids_from_bytes(&input[..10]).is_err(),
+            "the tree must be present"
+        );
+        Ok(())
+    }
+
     #[test]
     fn signatures() -> crate::Result {
         let input = fixture_bytes("commit", "unsigned.txt");
@@ -95,12 +95,16 @@ fn title_with_more_whitespace_and_body() {
 #[test]
 fn title_with_whitespace_and_body_windows_lineending() {
     let msg = MessageRef::from_bytes(b"hello \r\n \r\n there\nanother line\r\n\r\nthe body\n\r\n");
-    assert_eq!(msg.summary().as_ref(), "hello   there another line");
+    assert_eq!(
+        msg.summary().as_ref(),
+        "hello",
+        "lines with only whitespace separate paragraphs"
+    );
     assert_eq!(
         msg,
         MessageRef {
-            title: b"hello \r\n \r\n there\nanother line".as_bstr(),
-            body: Some(b"the body\n\r\n".as_bstr())
+            title: b"hello ".as_bstr(),
+            body: Some(b" there\nanother line\r\n\r\nthe body\n\r\n".as_bstr())
         }
     );
 }
@@ -131,6 +135,124 @@ fn title_with_windows_separator_and_empty_body() {
     );
 }
 
+#[test]
+fn title_is_the_first_line_as_is() {
+    let msg = MessageRef::from_bytes(b"\n \n  hello \r\nworld\n\nbody");
+    assert_eq!(msg.title(), "  hello ");
+    assert_eq!(msg.summary().as_ref(), "  hello world");
+    assert_eq!(MessageRef::from_bytes(b"").title(), "");
+}
+
+/// Expectations are the output of `git log --format=%s` and `git log --format=%b`, without the newline git adds.
+mod like_git {
+    use git_object::{bstr::ByteSlice, commit::MessageRef};
+
+    fn assert_subject_and_body(message: &[u8], subject: &str, body: Option<&str>) {
+        let msg = MessageRef::from_bytes(message);
+        assert_eq!(msg.summary().as_ref(), subject, "{:?}", message.as_bstr());
+        assert_eq!(
+            msg.body.map(|b| b.to_str_lossy().into_owned()),
+            body.map(ToOwned::to_owned),
+            "{:?}",
+            message.as_bstr()
+        );
+    }
+
+    #[test]
+    fn leading_blank_lines_are_skipped() {
+        assert_subject_and_body(b"\n\n  \nhello\nworld\n\nbody\n", "hello world", Some("body\n"));
+    }
+
+    #[test]
+    fn no_blank_line_at_all() {
+        assert_subject_and_body(b"no blank line\nat all\n", "no blank line at all", None);
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        assert_subject_and_body(
+            b"subject\r\n\r\nbody line\r\nsecond\r\n",
+            "subject",
+            Some("body line\r\nsecond\r\n"),
+        );
+        assert_subject_and_body(
+            b"subject \t\r\n  continued\r\n \t\r\nbody\n\n\n",
+            "subject   continued",
+            Some("body\n\n\n"),
+        );
+    }
+
+    #[test]
+    fn only_blank_lines() {
+        assert_subject_and_body(b"  \n\t\n", "", None);
+    }
+
+    #[test]
+    fn many_blank_lines_before_the_body() {
+        assert_subject_and_body(
+            b"title\n\n\n\nbody after many blank lines\n\n",
+            "title",
+            Some("body after many blank lines\n\n"),
+        );
+    }
+
+    #[test]
+    fn whitespace_only_lines_separate_paragraphs() {
+        assert_subject_and_body(b"a\n \nb", "a", Some("b"));
+    }
+
+    #[test]
+    fn leading_whitespace_is_retained() {
+        assert_subject_and_body(b"  hello\n\n  body", "  hello", Some("  body"));
+    }
+}
+
+mod set_message_from {
+    use git_object::{bstr::ByteSlice, commit::MessageRef, Commit};
+
+    fn message_from(summary: &str, body: Option<&str>) -> String {
+        let mut commit = Commit {
+            tree: git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1),
+            parents: Default::default(),
+            author: Default::default(),
+            committer: Default::default(),
+            encoding: None,
+            message: "to be replaced".into(),
+            extra_headers: Vec::new(),
+        };
+        commit.set_message_from(summary.into(), body.map(Into::into));
+        commit.message.to_str().expect("valid UTF-8").to_owned()
+    }
+
+    #[test]
+    fn summary_only() {
+        assert_eq!(message_from("hello", None), "hello\n");
+        assert_eq!(
+            message_from("hello", Some(" \n\t\n")),
+            "hello\n",
+            "blank bodies are omitted"
+        );
+    }
+
+    #[test]
+    fn summary_and_body() {
+        assert_eq!(message_from("hello", Some("body")), "hello\n\nbody\n");
+        assert_eq!(
+            message_from("hello \r\n world\n", Some("\n\n  body\r\nsecond line \n\n")),
+            "hello  world\n\n  body\r\nsecond line\n",
+            "the result is canonical"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_message_ref() {
+        let message = message_from("subject\ncontinued", Some("the body\n\nanother paragraph"));
+        let msg = MessageRef::from_bytes(message.as_bytes());
+        assert_eq!(msg.summary().as_ref(), "subject continued");
+        assert_eq!(msg.body, Some("the body\n\nanother paragraph\n".as_bytes().as_bstr()));
+    }
+}
+
 mod body {
     use git_object::commit::{
         message::{body::TrailerRef, BodyRef},
@@ -240,6 +362,7 @@ mod summary {
                 offset_in_seconds: 0,
                 sign: Sign::Plus,
             },
+            raw_time: None,
         };
         assert_eq!(
             CommitRef {
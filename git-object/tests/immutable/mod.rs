@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use git_actor::{Sign, Time};
 
+mod blob;
 mod commit;
 mod tag;
 mod tree;
@@ -23,7 +24,7 @@ fn fixture_bytes(kind: &str, path: &str) -> Vec<u8> {
     fixup(super::fixture_bytes(PathBuf::from(kind).join(path).to_str().unwrap()))
 }
 
-fn signature(time: u32) -> git_actor::SignatureRef<'static> {
+fn signature(time: i64) -> git_actor::SignatureRef<'static> {
     use git_object::bstr::ByteSlice;
     git_actor::SignatureRef {
         name: b"Sebastian Thiel".as_bstr(),
@@ -33,10 +34,11 @@ fn signature(time: u32) -> git_actor::SignatureRef<'static> {
             offset_in_seconds: 28800,
             sign: Sign::Plus,
         },
+        raw_time: None,
     }
 }
 
-fn linus_signature(time: u32) -> git_actor::SignatureRef<'static> {
+fn linus_signature(time: i64) -> git_actor::SignatureRef<'static> {
     use git_object::bstr::ByteSlice;
     git_actor::SignatureRef {
         name: b"Linus Torvalds".as_bstr(),
@@ -46,5 +48,32 @@ fn linus_signature(time: u32) -> git_actor::SignatureRef<'static> {
             offset_in_seconds: -25200,
             sign: Sign::Minus,
         },
+        raw_time: None,
+    }
+}
+
+mod decode_signature {
+    use git_actor::signature::Issue;
+    use git_object::decode;
+
+    use super::signature;
+
+    #[test]
+    fn strict_decoding_consumes_everything_and_agrees_with_lenient_decoding() -> crate::Result {
+        let input = b"Sebastian Thiel <sebastian.thiel@icloud.com> 1234 +0800";
+        assert_eq!(decode::signature(input)?, signature(1234));
+        assert_eq!(decode::signature_lenient(input), (signature(1234), Vec::new()));
+
+        assert!(decode::signature(b"Sebastian Thiel <sebastian.thiel@icloud.com> 1234 +0800\n").is_err());
+        assert!(decode::signature(b"Sebastian Thiel <sebastian.thiel@icloud.com> 1234").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_decoding_reports_issues() {
+        let (actual, issues) = decode::signature_lenient(b"Sebastian Thiel <sebastian.thiel@icloud.com> 1234");
+        assert_eq!(actual.time.seconds_since_unix_epoch, 1234);
+        assert_eq!(actual.time.offset_in_seconds, 0, "timezone defaults to UTC");
+        assert_eq!(issues, vec![Issue::MalformedTimezone]);
     }
 }
@@ -11,11 +11,93 @@ mod method {
         let fixture = fixture_bytes("tag", "signed.txt");
         let tag = TagRef::from_bytes(&fixture)?;
         assert_eq!(tag.target(), hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec"));
+        assert_eq!(tag.target_id(), tag.target());
         assert_eq!(tag.target, "ffa700b4aca13b80cb6b98a078e7c96804f8e0ec".as_bytes());
         Ok(())
     }
 }
 
+mod from_bytes_checked {
+    use git_object::{
+        bstr::ByteSlice,
+        tag::check::{Error, Issue, Kind as IssueKind, Options},
+        Kind, TagRef, WriteTo,
+    };
+
+    fn tag_with(target: &str, kind: &str) -> Vec<u8> {
+        format!(
+            "object {}\ntype {}\ntag v1.0\ntagger Name <name@example.com> 1592381636 +0800\n\nmessage\n",
+            target, kind
+        )
+        .into_bytes()
+    }
+
+    const TARGET: &str = "ffa700b4aca13b80cb6b98a078e7c96804f8e0ec";
+
+    #[test]
+    fn valid_tags_have_no_issues() -> crate::Result {
+        let data = tag_with(TARGET, "commit");
+        for options in [Options::default(), Options::lenient()] {
+            let (tag, issues) = TagRef::from_bytes_checked(&data, options)?;
+            assert_eq!(tag, TagRef::from_bytes(&data)?);
+            assert_eq!(tag.target_kind_raw, None);
+            assert!(issues.is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn wrongly_cased_kinds_fail_unless_lenient() -> crate::Result {
+        let data = tag_with(TARGET, "Commit");
+        assert!(TagRef::from_bytes(&data).is_err(), "the default parser is strict");
+        assert!(matches!(
+            TagRef::from_bytes_checked(&data, Options::default()),
+            Err(Error::UnknownTargetKind { kind }) if kind == "Commit"
+        ));
+
+        let (tag, issues) = TagRef::from_bytes_checked(&data, Options::lenient())?;
+        assert_eq!(tag.target_kind, Kind::Commit);
+        assert_eq!(tag.target_kind_raw, Some(b"Commit".as_bstr()));
+        assert_eq!(
+            issues,
+            vec![Issue {
+                kind: IssueKind::BadType,
+                value: "Commit".into()
+            }]
+        );
+        assert_eq!(issues[0].kind.fsck_id(), "badType");
+
+        let mut buf = Vec::new();
+        tag.write_to(&mut buf)?;
+        assert_eq!(buf.as_bstr(), data.as_bstr(), "the original bytes are reproduced");
+        assert_eq!(tag.size(), data.len());
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_kinds_always_fail() {
+        for options in [Options::default(), Options::lenient()] {
+            assert!(matches!(
+                TagRef::from_bytes_checked(&tag_with(TARGET, "foo"), options),
+                Err(Error::UnknownTargetKind { kind }) if kind == "foo"
+            ));
+        }
+    }
+
+    #[test]
+    fn target_ids_must_have_the_length_of_a_known_hash_kind() {
+        let too_long = format!("{}a", TARGET);
+        for target in [&TARGET[..39], too_long.as_str()] {
+            let data = tag_with(target, "commit");
+            assert!(TagRef::from_bytes(&data).is_err(), "{} is invalid", target);
+            assert!(matches!(
+                TagRef::from_bytes_checked(&data, Options::lenient()),
+                Err(Error::Decode(_))
+            ));
+        }
+    }
+}
+
 mod iter {
     use git_object::{bstr::ByteSlice, tag::ref_iter::Token, Kind, TagRefIter};
 
@@ -139,6 +221,7 @@ mod from_bytes {
                 target: b"01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc".as_bstr(),
                 name: b"empty".as_bstr(),
                 target_kind: Kind::Commit,
+                target_kind_raw: None,
                 message: b"".as_bstr(),
                 tagger: Some(signature(1592381636)),
                 pgp_signature: None
@@ -155,6 +238,7 @@ mod from_bytes {
                 target: b"ebdf205038b66108c0331aa590388431427493b7".as_bstr(),
                 name: b"baz".as_bstr(),
                 target_kind: Kind::Commit,
+                target_kind_raw: None,
                 message: b"hello\n\nworld".as_bstr(),
                 tagger: Some(signature(1592311808)),
                 pgp_signature: None
@@ -171,6 +255,7 @@ mod from_bytes {
                 target: b"c39ae07f393806ccf406ef966e9a15afc43cc36a".as_bstr(),
                 name: b"v2.6.11-tree".as_bstr(),
                 target_kind: Kind::Tree,
+                target_kind_raw: None,
                 message: b"This is the 2.6.11 tree object.
 
 NOTE! There's no commit for this, since it happened before I started with git.
@@ -203,6 +288,7 @@ KLMHist5yj0sw1E4hDTyQa0=
                 target: b"01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc".as_bstr(),
                 name: b"whitespace".as_bstr(),
                 target_kind: Kind::Commit,
+                target_kind_raw: None,
                 message: b" \ttab\nnewline\n\nlast-with-trailer\n".as_bstr(),
                 tagger: Some(signature(1592382888)),
                 pgp_signature: None
@@ -217,6 +303,7 @@ fn tag_fixture(offset: i32) -> TagRef<'static> {
         target: b"ffa700b4aca13b80cb6b98a078e7c96804f8e0ec".as_bstr(),
         name: b"1.0.0".as_bstr(),
         target_kind: Kind::Commit,
+        target_kind_raw: None,
         message: b"for the signature".as_bstr(),
         pgp_signature: Some(
             b"-----BEGIN PGP SIGNATURE-----
@@ -246,6 +333,7 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
                 offset_in_seconds: offset,
                 sign: git_actor::Sign::Plus,
             },
+            raw_time: None,
         }),
     }
 }
@@ -130,6 +130,242 @@ mod from_bytes {
     }
 }
 
+mod from_bytes_checked {
+    use git_object::{
+        bstr::ByteSlice,
+        tree,
+        tree::check::{Issue, Kind, Options},
+        TreeRef,
+    };
+
+    use crate::immutable::fixture_bytes;
+
+    pub fn tree_with(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for (mode, name) in entries {
+            buf.extend_from_slice(mode.as_bytes());
+            buf.push(b' ');
+            buf.extend_from_slice(name);
+            buf.push(0);
+            buf.extend_from_slice(&[0xaa; 20]);
+        }
+        buf
+    }
+
+    fn issue(kind: Kind, entry_index: usize, name: &str) -> Issue {
+        Issue {
+            kind,
+            entry_index,
+            name: name.into(),
+        }
+    }
+
+    #[test]
+    fn valid_trees_have_no_issues() -> crate::Result {
+        let data = fixture_bytes("tree", "everything.tree");
+        let (tree, issues) = TreeRef::from_bytes_checked(&data, Options::strict())?;
+        assert_eq!(tree, TreeRef::from_bytes(&data)?);
+        assert!(issues.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_name() -> crate::Result {
+        let data = tree_with(&[("100644", b"a"), ("100644", b"")]);
+        assert!(TreeRef::from_bytes(&data).is_err(), "the default parser rejects these");
+
+        let (tree, issues) = TreeRef::from_bytes_checked(&data, Options::strict())?;
+        assert_eq!(tree.entries.len(), 2);
+        assert_eq!(issues, vec![issue(Kind::EmptyName, 1, "")]);
+        assert_eq!(issues[0].kind.fsck_id(), "emptyName");
+        Ok(())
+    }
+
+    #[test]
+    fn full_pathname() -> crate::Result {
+        let data = tree_with(&[("100644", b"dir/file"), ("40000", b"/")]);
+        assert_eq!(
+            TreeRef::from_bytes(&data)?.entries.len(),
+            2,
+            "the default parser is lenient and accepts slashes"
+        );
+
+        let (_, issues) = TreeRef::from_bytes_checked(&data, Options::strict())?;
+        assert_eq!(
+            issues,
+            vec![
+                issue(Kind::FullPathname, 0, "dir/file"),
+                issue(Kind::FullPathname, 1, "/")
+            ]
+        );
+        assert_eq!(issues[0].kind.fsck_id(), "fullPathname");
+        Ok(())
+    }
+
+    #[test]
+    fn zero_padded_filemode() -> crate::Result {
+        let data = tree_with(&[("040000", b"dir")]);
+        let (tree, issues) = TreeRef::from_bytes_checked(&data, Options::strict())?;
+        assert_eq!(tree.entries[0].mode, tree::EntryMode::Tree);
+        assert_eq!(issues, vec![issue(Kind::ZeroPaddedFilemode, 0, "dir")]);
+        assert_eq!(issues[0].kind.fsck_id(), "zeroPaddedFilemode");
+        Ok(())
+    }
+
+    #[test]
+    fn all_issues_of_an_entry_are_reported() -> crate::Result {
+        let data = tree_with(&[("0100644", b"a/b"), ("100644", b"")]);
+        let (_, issues) = TreeRef::from_bytes_checked(&data, Options::strict())?;
+        assert_eq!(
+            issues,
+            vec![
+                issue(Kind::ZeroPaddedFilemode, 0, "a/b"),
+                issue(Kind::FullPathname, 0, "a/b"),
+                issue(Kind::EmptyName, 1, ""),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_options_report_nothing() -> crate::Result {
+        let data = tree_with(&[("040000", b"a/b"), ("100644", b"")]);
+        let (tree, issues) = TreeRef::from_bytes_checked(&data, Options::default())?;
+        assert_eq!(tree.entries.len(), 2);
+        assert!(issues.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn check_appends_the_same_issues_without_keeping_entries() -> crate::Result {
+        let data = tree_with(&[("0100644", b"a/b"), ("100644", b"")]);
+        let mut issues = vec![issue(Kind::EmptyName, 42, "previous")];
+        TreeRef::check(&data, Options::strict(), &mut issues)?;
+        assert_eq!(issues[0], issue(Kind::EmptyName, 42, "previous"), "issues are appended");
+        assert_eq!(
+            issues[1..],
+            TreeRef::from_bytes_checked(&data, Options::strict())?.1[..]
+        );
+
+        let mut issues = Vec::new();
+        TreeRef::check(
+            &fixture_bytes("tree", "everything.tree"),
+            Options::strict(),
+            &mut issues,
+        )?;
+        assert!(issues.is_empty());
+        assert!(TreeRef::check(&data[..data.len() - 1], Options::strict(), &mut issues).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_entries_are_errors() {
+        let data = tree_with(&[("100644", b"a")]);
+        for len in 1..data.len() {
+            assert!(
+                TreeRef::from_bytes_checked(&data[..len], Options::strict()).is_err(),
+                "{} bytes are not enough",
+                len
+            );
+        }
+        assert_eq!(
+            TreeRef::from_bytes_checked(&data, Options::strict())
+                .expect("complete entry")
+                .0
+                .entries[0]
+                .filename,
+            b"a".as_bstr()
+        );
+    }
+}
+
+mod is_canonical {
+    use git_object::{TreeRef, WriteTo};
+
+    use super::from_bytes_checked::tree_with;
+    use crate::immutable::fixture_bytes;
+
+    fn decodes_and_encodes_identically(data: &[u8]) -> bool {
+        TreeRef::from_bytes(data).map_or(false, |tree| {
+            let mut buf = Vec::new();
+            tree.write_to(&mut buf).is_ok() && buf == data
+        })
+    }
+
+    #[test]
+    fn trees_written_by_git() {
+        assert!(TreeRef::is_canonical(&[]), "the empty tree");
+        assert!(TreeRef::is_canonical(&fixture_bytes("tree", "everything.tree")));
+        for name in ["everything.tree", "maybe-special.tree", "definitely-special.tree"] {
+            let data = fixture_bytes("tree", name);
+            if TreeRef::is_canonical(&data) {
+                assert!(decodes_and_encodes_identically(&data), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn entries_are_sorted_as_if_trees_had_a_trailing_slash() {
+        assert!(TreeRef::is_canonical(&tree_with(&[
+            ("100644", b"a"),
+            ("100644", b"a.txt"),
+            ("40000", b"b"),
+        ])));
+        assert!(TreeRef::is_canonical(&tree_with(&[
+            ("100644", b"a.txt"),
+            ("40000", b"a")
+        ])));
+        assert!(!TreeRef::is_canonical(&tree_with(&[
+            ("40000", b"a"),
+            ("100644", b"a.txt")
+        ])));
+        assert!(!TreeRef::is_canonical(&tree_with(&[
+            ("100644", b"b"),
+            ("100644", b"a")
+        ])));
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        assert!(!TreeRef::is_canonical(&tree_with(&[
+            ("100644", b"a"),
+            ("100644", b"a")
+        ])));
+        assert!(
+            !TreeRef::is_canonical(&tree_with(&[("100644", b"a"), ("40000", b"a")])),
+            "even if they are sorted correctly"
+        );
+    }
+
+    #[test]
+    fn non_canonical_modes_are_rejected() {
+        for mode in ["040000", "100664", "100640", "0100644", "100000", "4000"] {
+            let data = tree_with(&[(mode, b"a")]);
+            assert!(!TreeRef::is_canonical(&data), "{}", mode);
+            assert!(!decodes_and_encodes_identically(&data), "{}", mode);
+        }
+        for mode in ["40000", "100644", "100755", "120000", "160000"] {
+            let data = tree_with(&[(mode, b"a")]);
+            assert!(TreeRef::is_canonical(&data), "{}", mode);
+            assert!(decodes_and_encodes_identically(&data), "{}", mode);
+        }
+    }
+
+    #[test]
+    fn names_that_can_not_be_encoded_or_decoded_are_rejected() {
+        assert!(!TreeRef::is_canonical(&tree_with(&[("100644", b"")])));
+        assert!(!TreeRef::is_canonical(&tree_with(&[("100644", b"a\nb")])));
+    }
+
+    #[test]
+    fn truncated_trees_are_rejected() {
+        let data = tree_with(&[("100644", b"a")]);
+        for len in 1..data.len() {
+            assert!(!TreeRef::is_canonical(&data[..len]), "{} bytes are not enough", len);
+        }
+    }
+}
+
 mod entry_mode {
     use git_object::tree::EntryMode;
 
@@ -26,3 +26,75 @@ fn shorter_than_advertised() {
         "object data was shorter than its size declared in the header"
     );
 }
+
+mod header {
+    use git_object::{decode, Kind};
+
+    #[test]
+    fn zero_size_blob() {
+        let (kind, size, consumed) = decode::loose_header(b"blob 0\0").unwrap();
+        assert_eq!(kind, Kind::Blob);
+        assert_eq!(size, 0);
+        assert_eq!(consumed, 7);
+    }
+
+    #[test]
+    fn consumed_bytes_exclude_the_payload() {
+        let (kind, size, consumed) = decode::loose_header(b"commit 3\0abc").unwrap();
+        assert_eq!(kind, Kind::Commit);
+        assert_eq!(size, 3);
+        assert_eq!(consumed, 9);
+    }
+
+    #[test]
+    fn leading_zeros_in_size_are_only_rejected_in_strict_mode() {
+        let strict = decode::LooseHeaderOptions { strict: true };
+        assert_eq!(decode::loose_header(b"blob 007\0").unwrap(), (Kind::Blob, 7, 9));
+        assert_eq!(
+            decode::loose_header_opts(b"blob 007\0", strict)
+                .unwrap_err()
+                .to_string(),
+            "Object size in header must not have leading zeros"
+        );
+        assert_eq!(
+            decode::loose_header_opts(b"blob 0\0", strict).unwrap(),
+            (Kind::Blob, 0, 7),
+            "a single zero is fine"
+        );
+    }
+
+    #[test]
+    fn sizes_up_to_u64_max_are_supported() {
+        assert_eq!(
+            decode::loose_header(b"commit 18446744073709551615\0").unwrap(),
+            (Kind::Commit, u64::MAX, 28)
+        );
+    }
+
+    #[test]
+    fn sizes_larger_than_u64_max_are_rejected() {
+        assert!(decode::loose_header(b"blob 18446744073709551616\0").is_err());
+    }
+
+    #[test]
+    fn signed_sizes_are_rejected() {
+        assert!(decode::loose_header(b"blob +1\0").is_err());
+        assert!(decode::loose_header(b"blob -1\0").is_err());
+    }
+
+    #[test]
+    fn null_byte_must_be_within_bounds() {
+        assert_eq!(
+            decode::loose_header(b"blob 1").unwrap_err().to_string(),
+            "Did not find 0 byte in header"
+        );
+        let mut header = b"blob ".to_vec();
+        header.extend(std::iter::repeat(b'0').take(50));
+        header.extend_from_slice(b"1\0");
+        assert_eq!(
+            decode::loose_header(&header).unwrap_err().to_string(),
+            "Did not find 0 byte in header",
+            "the null byte is too far away to be part of a valid header"
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use std::path::PathBuf;
 
+mod compute_hash;
+mod editor;
 mod encode;
 mod immutable;
+mod verify;
 
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -37,3 +40,111 @@ fn size_in_memory() {
         actual
     )
 }
+
+mod memory_usage {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    };
+
+    use git_object::{tree, Object, Tree};
+
+    /// Count the bytes currently allocated by each thread to compare them to what objects report.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+    }
+
+    fn track(delta: isize) {
+        ALLOCATED
+            .try_with(|allocated| allocated.set(allocated.get() + delta))
+            .ok();
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            track(layout.size() as isize);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            track(-(layout.size() as isize));
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            track(new_size as isize - layout.size() as isize);
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    fn allocated() -> isize {
+        ALLOCATED.with(Cell::get)
+    }
+
+    #[test]
+    fn tree_with_many_entries_is_within_a_reasonable_factor_of_the_allocated_memory() {
+        let before = allocated();
+        let mut tree = Tree::empty();
+        for index in 0..10_000 {
+            tree.entries.push(tree::Entry {
+                mode: tree::EntryMode::Blob,
+                filename: format!("file-{:05}.txt", index).into(),
+                oid: git_hash::ObjectId::null(git_hash::Kind::Sha1),
+            });
+        }
+        let object = Object::Tree(tree);
+        let allocated = (allocated() - before) as usize;
+
+        let reported = object.size_in_memory();
+        assert!(
+            reported >= allocated && reported <= allocated * 2,
+            "{} should be close to the {} bytes actually allocated",
+            reported,
+            allocated
+        );
+        let payload = {
+            use git_object::WriteTo;
+            object.size()
+        };
+        assert!(
+            reported > payload,
+            "the decoded tree needs more memory than its serialized form"
+        );
+    }
+
+    #[test]
+    fn blobs_are_measured_by_their_allocation() {
+        let blob = git_object::Blob {
+            data: Vec::with_capacity(1024),
+        };
+        assert_eq!(blob.size_in_memory(), std::mem::size_of::<git_object::Blob>() + 1024);
+        assert_eq!(
+            Object::Blob(blob).size_in_memory(),
+            std::mem::size_of::<Object>() + 1024,
+            "the object variant is stored inline"
+        );
+    }
+
+    #[test]
+    fn borrowed_objects_do_not_count_their_backing_buffer() -> crate::Result {
+        let data = crate::fixture_bytes("tree/everything.tree");
+        let tree = git_object::TreeRef::from_bytes(&data)?;
+        assert_eq!(
+            tree.size_in_memory(),
+            std::mem::size_of::<git_object::TreeRef<'_>>()
+                + tree.entries.capacity() * std::mem::size_of::<tree::EntryRef<'_>>(),
+            "names and ids are borrowed from the buffer"
+        );
+        let entries_size = tree.entries.capacity() * std::mem::size_of::<tree::EntryRef<'_>>();
+        assert_eq!(
+            git_object::ObjectRef::Tree(tree).size_in_memory(),
+            std::mem::size_of::<git_object::ObjectRef<'_>>() + entries_size
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+mod roundtrip {
+    use git_object::{
+        bstr::ByteSlice,
+        verify::{roundtrip, roundtrip::Normalization},
+        Kind,
+    };
+
+    use crate::fixture_bytes;
+
+    fn tree_with(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for (mode, name) in entries {
+            buf.extend_from_slice(mode.as_bytes());
+            buf.push(b' ');
+            buf.extend_from_slice(name);
+            buf.push(0);
+            buf.extend_from_slice(&[0xaa; 20]);
+        }
+        buf
+    }
+
+    #[test]
+    fn objects_written_by_git_are_accepted() {
+        for (kind, path) in [
+            (Kind::Tree, "tree/everything.tree"),
+            (Kind::Commit, "commit/unsigned.txt"),
+            (Kind::Commit, "commit/signed.txt"),
+            (Kind::Commit, "commit/merge.txt"),
+            (Kind::Commit, "commit/mergetag.txt"),
+            (Kind::Commit, "commit/with-encoding.txt"),
+            (Kind::Commit, "commit/two-multiline-headers.txt"),
+            (Kind::Tag, "tag/signed.txt"),
+            (Kind::Tag, "tag/empty.txt"),
+            (Kind::Tag, "tag/no-tagger.txt"),
+            (Kind::Tag, "tag/with-newlines.txt"),
+        ] {
+            let data = fixture_bytes(path);
+            assert!(roundtrip(kind, &data).is_ok(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn blobs_are_always_accepted() {
+        assert!(roundtrip(Kind::Blob, b"").is_ok());
+        assert!(roundtrip(Kind::Blob, &fixture_bytes("tree/everything.tree")).is_ok());
+        assert!(roundtrip(Kind::Blob, b"\0 not a commit").is_ok());
+    }
+
+    #[test]
+    fn objects_that_do_not_decode_fail_with_a_decode_error() {
+        for (kind, data) in [
+            (Kind::Commit, b"not a commit".to_vec()),
+            (Kind::Tag, b"not a tag".to_vec()),
+            (Kind::Tree, tree_with(&[("040000", b"a")])),
+        ] {
+            let err = roundtrip(kind, &data).unwrap_err();
+            assert!(matches!(err, roundtrip::Error::Decode(_)), "{:?}", err);
+            assert_eq!(err.normalization(), None);
+        }
+    }
+
+    #[test]
+    fn non_standard_tree_entry_modes_are_a_benign_mismatch() {
+        for mode in ["100664", "100640"] {
+            let data = tree_with(&[("100644", b"a"), (mode, b"b")]);
+            match roundtrip(Kind::Tree, &data).unwrap_err() {
+                roundtrip::Error::Mismatch {
+                    kind,
+                    expected,
+                    actual,
+                    normalization,
+                } => {
+                    assert_eq!(kind, Kind::Tree);
+                    assert_eq!(expected.as_bstr(), data.as_bstr());
+                    assert_eq!(
+                        actual.as_bstr(),
+                        tree_with(&[("100644", b"a"), ("100644", b"b")]).as_bstr(),
+                        "the mode is normalized to the standard one"
+                    );
+                    assert_eq!(normalization, Some(Normalization::TreeEntryMode));
+                }
+                err => unreachable!("unexpected error: {:?}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn objects_that_can_not_be_written_anymore_are_a_mismatch() {
+        let data = tree_with(&[("100644", b"a\nb")]);
+        let err = roundtrip(Kind::Tree, &data).unwrap_err();
+        assert!(
+            matches!(&err, roundtrip::Error::Mismatch { expected, normalization: None, .. } if expected.as_bstr() == data.as_bstr()),
+            "{:?}",
+            err
+        );
+        assert_eq!(
+            err.to_string(),
+            "tree object wasn't re-encoded without change",
+            "no normalization is mentioned"
+        );
+    }
+}
+
+mod warning_class {
+    use git_object::{tree::check, verify::roundtrip::Normalization, verify::WarningClass};
+
+    #[test]
+    fn fsck_issues_keep_their_identifier() {
+        for kind in [
+            check::Kind::EmptyName,
+            check::Kind::FullPathname,
+            check::Kind::ZeroPaddedFilemode,
+        ] {
+            assert_eq!(WarningClass::from(kind).as_str(), kind.fsck_id());
+        }
+    }
+
+    #[test]
+    fn all_classes_have_distinct_identifiers() {
+        let mut ids: Vec<_> = WarningClass::ALL.iter().map(|class| class.to_string()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), WarningClass::ALL.len());
+        assert_eq!(
+            WarningClass::from(Normalization::TreeEntryMode).as_str(),
+            "nonCanonicalTreeEntryMode"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::{HashSet, VecDeque};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use git_hash::ObjectId;
+use git_odb::FindExt;
+
+fn handle() -> (git_odb::Handle, ObjectId) {
+    let repo = git_testtools::scripted_fixture_repo_read_only("make_repo_with_long_history.sh").expect("fixture works");
+    let head = std::fs::read(repo.join(".git/refs/heads/main"))
+        .or_else(|_| {
+            std::fs::read(repo.join(".git/packed-refs")).map(|refs| {
+                refs.split(|b| *b == b'\n')
+                    .find(|line| line.ends_with(b" refs/heads/main"))
+                    .expect("main is packed")
+                    .to_owned()
+            })
+        })
+        .expect("main exists");
+    let head = ObjectId::from_hex(&head[..40]).expect("valid hex");
+    let handle = git_odb::at(repo.join(".git/objects"))
+        .expect("valid object path")
+        .with_pack_cache(|| Box::new(git_pack::cache::lru::StaticLinkedList::<64>::default()));
+    (handle, head)
+}
+
+/// Visit all commits reachable from `tip` once, obtaining the parents of each with `parents_of`, and return their count.
+fn walk(tip: ObjectId, mut parents_of: impl FnMut(ObjectId, &mut Vec<ObjectId>)) -> usize {
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([tip]);
+    let mut parents = Vec::new();
+    seen.insert(tip);
+    while let Some(id) = queue.pop_front() {
+        parents.clear();
+        parents_of(id, &mut parents);
+        for parent in parents.drain(..) {
+            if seen.insert(parent) {
+                queue.push_back(parent);
+            }
+        }
+    }
+    seen.len()
+}
+
+fn commit_parents_vs_full_decode(c: &mut Criterion) {
+    let (handle, tip) = handle();
+    let mut buf = Vec::new();
+    let commits: Vec<Vec<u8>> = handle
+        .iter()
+        .expect("iterable")
+        .filter_map(|id| {
+            let object = handle.find(id.expect("valid id"), &mut buf).expect("present");
+            (object.kind == git_object::Kind::Commit).then(|| object.data.to_owned())
+        })
+        .collect();
+    let mut group = c.benchmark_group("parse parents of all commits");
+    group.bench_function("decode full commits", |b| {
+        b.iter(|| {
+            commits
+                .iter()
+                .map(|data| {
+                    git_object::CommitRef::from_bytes(data)
+                        .expect("valid")
+                        .parents()
+                        .count()
+                })
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("parse parent headers only", |b| {
+        b.iter(|| {
+            commits
+                .iter()
+                .map(|data| {
+                    git_object::CommitRefIter::parent_ids_from_bytes(data)
+                        .expect("valid")
+                        .len()
+                })
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("walk all commits without commit-graph");
+    group.bench_function("decode full commits", |b| {
+        let mut buf = Vec::new();
+        b.iter(|| {
+            walk(tip, |id, parents| {
+                parents.extend(handle.find_commit(id, &mut buf).expect("present").parents())
+            })
+        })
+    });
+    group.bench_function("commit parents", |b| {
+        let mut buf = Vec::new();
+        b.iter(|| {
+            walk(tip, |id, parents| {
+                parents.extend(handle.commit_parents(id, &mut buf).expect("present"))
+            })
+        })
+    });
+    let handle = handle.with_parents_cache(4096);
+    group.bench_function("commit parents, cached", |b| {
+        let mut buf = Vec::new();
+        b.iter(|| {
+            walk(tip, |id, parents| {
+                parents.extend(handle.commit_parents(id, &mut buf).expect("present"))
+            })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, commit_parents_vs_full_decode);
+criterion_main!(benches);
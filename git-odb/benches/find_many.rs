@@ -0,0 +1,43 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use git_odb::{store::find_many, Find};
+
+fn handle() -> git_odb::Handle {
+    git_odb::at(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/objects")).expect("valid object path")
+}
+
+fn find_many_vs_sequential(c: &mut Criterion) {
+    let handle = handle().into_arc().expect("arc handle");
+    let ids = handle
+        .iter()
+        .expect("iterable")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("all ids");
+
+    let mut group = c.benchmark_group("multi-pack fixture");
+    group.bench_function("sequential try_find()", |b| {
+        let mut buf = Vec::new();
+        b.iter(|| {
+            for id in &ids {
+                black_box(handle.try_find(id, &mut buf).unwrap().expect("present"));
+            }
+        })
+    });
+    group.bench_function("find_many()", |b| {
+        b.iter(|| {
+            for obj in handle.find_many(&ids, find_many::Options::default()) {
+                black_box(obj.unwrap());
+            }
+        })
+    });
+    group.bench_function("find_many_in_order()", |b| {
+        b.iter(|| {
+            for obj in handle.find_many_in_order(&ids, find_many::Options::default()) {
+                black_box(obj.unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, find_many_vs_sequential);
+criterion_main!(benches);
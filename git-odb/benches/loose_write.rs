@@ -0,0 +1,43 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use git_odb::{loose, Write};
+
+const NUM_OBJECTS: usize = 100_000;
+
+fn buffered_vs_streamed_writes_of_small_blobs(c: &mut Criterion) {
+    let blobs: Vec<_> = (0..NUM_OBJECTS).map(|n| format!("blob {}\n", n)).collect();
+
+    let mut group = c.benchmark_group("loose write");
+    group
+        .sample_size(10)
+        .throughput(Throughput::Elements(NUM_OBJECTS as u64));
+    for (name, max_buffered_object_size) in [
+        ("buffered", loose::write::DEFAULT_MAX_BUFFERED_OBJECT_SIZE),
+        ("streamed", 0),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || tempfile::tempdir().expect("temp dir can be created"),
+                |dir| {
+                    let db = loose::Store::at_opts(
+                        dir.path(),
+                        git_hash::Kind::Sha1,
+                        loose::write::Options {
+                            max_buffered_object_size,
+                            ..Default::default()
+                        },
+                    );
+                    for blob in &blobs {
+                        db.write_buf(git_object::Kind::Blob, blob.as_bytes())
+                            .expect("write succeeds");
+                    }
+                    dir
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, buffered_vs_streamed_writes_of_small_blobs);
+criterion_main!(benches);
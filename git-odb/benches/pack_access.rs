@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use git_odb::{pack::data::Access, Find};
+
+fn handle(pack_access: Access) -> git_odb::Handle {
+    let store = git_odb::Store::at_opts(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/objects"),
+        None,
+        git_odb::store::init::Options {
+            pack_access,
+            ..Default::default()
+        },
+    )
+    .expect("valid object path");
+    git_features::threading::OwnShared::new(store).to_cache()
+}
+
+fn memory_map_vs_positioned_read(c: &mut Criterion) {
+    let ids = handle(Access::MemoryMap)
+        .iter()
+        .expect("iterable")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("all ids");
+
+    let mut group = c.benchmark_group("pack access");
+    for (name, access) in [
+        ("memory map", Access::MemoryMap),
+        ("positioned read", Access::positioned_read()),
+        (
+            "positioned read, 4KiB blocks, no cache",
+            Access::PositionedRead {
+                block_size: 4096,
+                capacity: 0,
+            },
+        ),
+    ] {
+        let handle = handle(access);
+        group.bench_function(name, |b| {
+            let mut buf = Vec::new();
+            b.iter(|| {
+                for id in &ids {
+                    black_box(handle.try_find(id, &mut buf).unwrap().expect("present"));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, memory_map_vs_positioned_read);
+criterion_main!(benches);
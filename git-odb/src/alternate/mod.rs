@@ -46,13 +46,30 @@ pub fn resolve(
     objects_directory: impl Into<PathBuf>,
     current_dir: impl AsRef<std::path::Path>,
 ) -> Result<Vec<PathBuf>, Error> {
-    let relative_base = objects_directory.into();
+    resolve_inner(objects_directory.into(), current_dir.as_ref(), None)
+}
+
+/// Like [`resolve()`], but alternates files which exist but can't be read are recorded in `unreadable` along with their error
+/// instead of failing the operation.
+pub(crate) fn resolve_skipping_unreadable(
+    objects_directory: impl Into<PathBuf>,
+    current_dir: impl AsRef<std::path::Path>,
+    unreadable: &mut Vec<(PathBuf, io::Error)>,
+) -> Result<Vec<PathBuf>, Error> {
+    resolve_inner(objects_directory.into(), current_dir.as_ref(), Some(unreadable))
+}
+
+fn resolve_inner(
+    relative_base: PathBuf,
+    cwd: &std::path::Path,
+    mut unreadable: Option<&mut Vec<(PathBuf, io::Error)>>,
+) -> Result<Vec<PathBuf>, Error> {
     let mut dirs = vec![(0, relative_base.clone())];
     let mut out = Vec::new();
-    let cwd = current_dir.as_ref();
     let mut seen = vec![git_path::realpath_opts(&relative_base, cwd, MAX_SYMLINKS)?];
     while let Some((depth, dir)) = dirs.pop() {
-        match fs::read(dir.join("info").join("alternates")) {
+        let alternates_path = dir.join("info").join("alternates");
+        match fs::read(&alternates_path) {
             Ok(input) => {
                 for path in parse::content(&input)?.into_iter() {
                     let path = relative_base.join(path);
@@ -65,7 +82,10 @@ pub fn resolve(
                 }
             }
             Err(err) if err.kind() == io::ErrorKind::NotFound => {}
-            Err(err) => return Err(err.into()),
+            Err(err) => match unreadable.as_mut() {
+                Some(unreadable) => unreadable.push((alternates_path, err)),
+                None => return Err(err.into()),
+            },
         };
         if depth != 0 {
             out.push(dir);
@@ -5,6 +5,9 @@ use std::{
     sync::Arc,
 };
 
+use git_hash::{oid, ObjectId};
+use smallvec::SmallVec;
+
 use crate::Cache;
 
 /// A type to store pack caches in boxes.
@@ -17,6 +20,68 @@ pub type ObjectCache = dyn git_pack::cache::Object + Send + 'static;
 /// A constructor for boxed object caches.
 pub type NewObjectCacheFn = dyn Fn() -> Box<ObjectCache> + Send + Sync + 'static;
 
+///
+pub mod commit_parents {
+    use git_hash::ObjectId;
+
+    /// The error returned by [`Cache::commit_parents()`][crate::Cache::commit_parents()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error<T: std::error::Error + 'static> {
+        #[error(transparent)]
+        Find(T),
+        #[error("An object with id {} could not be found", .oid)]
+        NotFound { oid: ObjectId },
+        #[error("Object {oid} was expected to be a commit, but is a {actual}")]
+        ObjectKind { oid: ObjectId, actual: git_object::Kind },
+        #[error("The parents of commit {oid} could not be decoded")]
+        Decode {
+            source: git_object::decode::Error,
+            oid: ObjectId,
+        },
+    }
+}
+
+/// A tiny cache for the parents of commits, see [`Cache::with_parents_cache()`].
+///
+/// It's direct-mapped, so each commit is stored in the single slot determined by its id, replacing the commit that was
+/// stored there before. This makes lookups cheap enough to not slow down walks which don't revisit commits.
+pub(crate) struct ParentsCache {
+    slots: Vec<Option<(ObjectId, SmallVec<[ObjectId; 2]>)>>,
+}
+
+impl ParentsCache {
+    fn new(capacity: usize) -> Self {
+        ParentsCache {
+            slots: vec![None; capacity],
+        }
+    }
+
+    fn slot(&self, id: &oid) -> usize {
+        let mut prefix = [0u8; 8];
+        prefix.copy_from_slice(&id.as_bytes()[..8]);
+        (u64::from_be_bytes(prefix) % self.slots.len() as u64) as usize
+    }
+
+    fn get(&self, id: &oid) -> Option<&SmallVec<[ObjectId; 2]>> {
+        match &self.slots[self.slot(id)] {
+            Some((stored_id, parents)) if *stored_id == id => Some(parents),
+            _ => None,
+        }
+    }
+
+    fn put(&mut self, id: ObjectId, parents: SmallVec<[ObjectId; 2]>) {
+        let slot = self.slot(&id);
+        self.slots[slot] = Some((id, parents));
+    }
+}
+
+impl Clone for ParentsCache {
+    fn clone(&self) -> Self {
+        ParentsCache::new(self.slots.len())
+    }
+}
+
 impl Cache<crate::store::Handle<Rc<crate::Store>>> {
     /// Convert this cache's handle into one that keeps its store in an arc. This creates an entirely new store,
     /// so should be done early to avoid unnecessary work (and mappings).
@@ -28,6 +93,7 @@ impl Cache<crate::store::Handle<Rc<crate::Store>>> {
             new_object_cache: self.new_object_cache,
             pack_cache: self.pack_cache,
             object_cache: self.object_cache,
+            parents_cache: self.parents_cache,
         })
     }
 }
@@ -89,6 +155,83 @@ impl<S> Cache<S> {
         self.object_cache = None;
         self.new_object_cache = None;
     }
+    /// Use this method directly after creating a new instance to cache the parents of up to `capacity` commits
+    /// looked up with [`commit_parents()`][Cache::commit_parents()], or to disable the cache if `capacity` is 0.
+    ///
+    /// This is useful for walks which visit commits more than once, for instance when computing merge bases.
+    pub fn with_parents_cache(mut self, capacity: usize) -> Self {
+        self.set_parents_cache(capacity);
+        self
+    }
+    /// Set the parents cache on this instance to hold up to `capacity` commits, or remove it if `capacity` is 0.
+    pub fn set_parents_cache(&mut self, capacity: usize) {
+        self.parents_cache = (capacity != 0).then(|| RefCell::new(ParentsCache::new(capacity)));
+    }
+    /// Return true if a parents cache is present.
+    pub fn has_parents_cache(&self) -> bool {
+        self.parents_cache.is_some()
+    }
+}
+
+impl<S> Cache<S>
+where
+    S: git_pack::Find,
+{
+    /// Like [`try_find()`][crate::Find::try_find()], but also return the location of the object's entry within its pack,
+    /// or `None` if the object is stored loose.
+    ///
+    /// The object cache is bypassed as it doesn't know where its objects are stored, but the pack cache is used.
+    /// Use the [`pack_id`][git_pack::data::entry::Location::pack_id] with [`pack_path()`][crate::store::Handle::pack_path()]
+    /// to learn which pack the object is stored in.
+    pub fn try_find_with_location<'a>(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<(git_object::Data<'a>, Option<git_pack::data::entry::Location>)>, S::Error> {
+        match self.pack_cache.as_ref().map(|rc| rc.borrow_mut()) {
+            Some(mut pack_cache) => self.inner.try_find_cached(id, buffer, pack_cache.deref_mut()),
+            None => self.inner.try_find_cached(id, buffer, &mut git_pack::cache::Never),
+        }
+    }
+
+    /// Return the ids of the parents of the commit with `id`, using `buffer` to hold its data.
+    ///
+    /// The commit still needs to be decoded from its pack, but only its `tree` and `parent` headers are parsed, which makes
+    /// this the cheapest way to traverse the commit graph if there is no commit-graph file. Use
+    /// [`with_parents_cache()`][Cache::with_parents_cache()] to avoid decoding commits that are visited more than once.
+    pub fn commit_parents(
+        &self,
+        id: impl AsRef<oid>,
+        buffer: &mut Vec<u8>,
+    ) -> Result<SmallVec<[ObjectId; 2]>, commit_parents::Error<S::Error>> {
+        let id = id.as_ref();
+        if let Some(parents) = self
+            .parents_cache
+            .as_ref()
+            .and_then(|cache| cache.borrow().get(id).cloned())
+        {
+            return Ok(parents);
+        }
+        let (object, _location) = git_pack::Find::try_find(self, id, buffer)
+            .map_err(commit_parents::Error::Find)?
+            .ok_or_else(|| commit_parents::Error::NotFound { oid: id.to_owned() })?;
+        if object.kind != git_object::Kind::Commit {
+            return Err(commit_parents::Error::ObjectKind {
+                oid: id.to_owned(),
+                actual: object.kind,
+            });
+        }
+        let parents = git_object::CommitRefIter::parent_ids_from_bytes(object.data).map_err(|source| {
+            commit_parents::Error::Decode {
+                source,
+                oid: id.to_owned(),
+            }
+        })?;
+        if let Some(cache) = &self.parents_cache {
+            cache.borrow_mut().put(id.to_owned(), parents.clone());
+        }
+        Ok(parents)
+    }
 }
 
 impl<S> From<S> for Cache<S>
@@ -102,6 +245,7 @@ where
             new_pack_cache: None,
             object_cache: None,
             new_object_cache: None,
+            parents_cache: None,
         }
     }
 }
@@ -114,6 +258,10 @@ impl<S: Clone> Clone for Cache<S> {
             new_object_cache: self.new_object_cache.clone(),
             pack_cache: self.new_pack_cache.as_ref().map(|create| RefCell::new(create())),
             object_cache: self.new_object_cache.as_ref().map(|create| RefCell::new(create())),
+            parents_cache: self
+                .parents_cache
+                .as_ref()
+                .map(|cache| RefCell::new(cache.borrow().clone())),
         }
     }
 }
@@ -208,7 +356,11 @@ mod impls {
             Ok(possibly_obj)
         }
 
-        fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<git_pack::data::entry::Location> {
+        fn location_by_oid(
+            &self,
+            id: impl AsRef<oid>,
+            buf: &mut Vec<u8>,
+        ) -> Result<Option<git_pack::data::entry::Location>, Self::Error> {
             self.inner.location_by_oid(id, buf)
         }
 
@@ -1,3 +1,5 @@
+pub use git_pack::find::{Classify, ErrorKind};
+
 /// A way to indicate if a lookup, despite successful, was ambiguous or yielded exactly
 /// one result in the particular index.
 // TODO: find better name, ambiguous with git_pack::index::PrefixLookupResult (entry_index inside)
@@ -53,6 +55,34 @@ pub mod existing {
         #[error("An object with id {} could not be found", .oid)]
         NotFound { oid: ObjectId },
     }
+
+    impl<T: std::error::Error + 'static> Error<T> {
+        /// Return `true` if the object doesn't exist.
+        pub fn is_not_found(&self) -> bool {
+            matches!(self, Error::NotFound { .. })
+        }
+
+        /// Return the kind of this error, which for lookup errors is determined by the underlying error.
+        pub fn kind(&self) -> git_pack::find::ErrorKind
+        where
+            T: git_pack::find::Classify,
+        {
+            use git_pack::find::ErrorKind;
+            match self {
+                Error::Find(err) => err.kind(),
+                Error::NotFound { .. } => ErrorKind::NotFound,
+            }
+        }
+    }
+
+    impl<T> git_pack::find::Classify for Error<T>
+    where
+        T: git_pack::find::Classify + std::error::Error + 'static,
+    {
+        fn kind(&self) -> git_pack::find::ErrorKind {
+            Error::kind(self)
+        }
+    }
 }
 
 ///
@@ -72,6 +102,36 @@ pub mod existing_object {
         #[error("Expected object of kind {expected}")]
         ObjectKind { expected: git_object::Kind },
     }
+
+    impl<T: std::error::Error + 'static> Error<T> {
+        /// Return `true` if the object doesn't exist.
+        pub fn is_not_found(&self) -> bool {
+            matches!(self, Error::NotFound { .. })
+        }
+
+        /// Return the kind of this error, which for lookup errors is determined by the underlying error.
+        pub fn kind(&self) -> git_pack::find::ErrorKind
+        where
+            T: git_pack::find::Classify,
+        {
+            use git_pack::find::ErrorKind;
+            match self {
+                Error::Find(err) => err.kind(),
+                Error::NotFound { .. } => ErrorKind::NotFound,
+                Error::Decode(_) => ErrorKind::Corrupt,
+                Error::ObjectKind { .. } => ErrorKind::Other,
+            }
+        }
+    }
+
+    impl<T> git_pack::find::Classify for Error<T>
+    where
+        T: git_pack::find::Classify + std::error::Error + 'static,
+    {
+        fn kind(&self) -> git_pack::find::ErrorKind {
+            Error::kind(self)
+        }
+    }
 }
 
 ///
@@ -89,4 +149,33 @@ pub mod existing_iter {
         #[error("Expected object of kind {expected}")]
         ObjectKind { expected: git_object::Kind },
     }
+
+    impl<T: std::error::Error + 'static> Error<T> {
+        /// Return `true` if the object doesn't exist.
+        pub fn is_not_found(&self) -> bool {
+            matches!(self, Error::NotFound { .. })
+        }
+
+        /// Return the kind of this error, which for lookup errors is determined by the underlying error.
+        pub fn kind(&self) -> git_pack::find::ErrorKind
+        where
+            T: git_pack::find::Classify,
+        {
+            use git_pack::find::ErrorKind;
+            match self {
+                Error::Find(err) => err.kind(),
+                Error::NotFound { .. } => ErrorKind::NotFound,
+                Error::ObjectKind { .. } => ErrorKind::Other,
+            }
+        }
+    }
+
+    impl<T> git_pack::find::Classify for Error<T>
+    where
+        T: git_pack::find::Classify + std::error::Error + 'static,
+    {
+        fn kind(&self) -> git_pack::find::ErrorKind {
+            Error::kind(self)
+        }
+    }
 }
@@ -30,6 +30,10 @@ pub use store_impls::{dynamic as store, loose};
 
 pub mod alternate;
 
+/// Run long-running operations on a dedicated thread pool to use them from async executors without blocking them.
+#[cfg(feature = "async-support")]
+pub mod nonblocking;
+
 /// A way to access objects along with pre-configured thread-local caches for packed base objects as well as objects themselves.
 ///
 /// By default, no cache will be used.
@@ -44,6 +48,7 @@ pub struct Cache<S> {
     new_object_cache: Option<Arc<cache::NewObjectCacheFn>>,
     pack_cache: Option<RefCell<Box<cache::PackCache>>>,
     object_cache: Option<RefCell<Box<cache::ObjectCache>>>,
+    parents_cache: Option<RefCell<cache::ParentsCache>>,
 }
 
 ///
@@ -71,10 +76,12 @@ pub mod sink;
 ///
 pub mod find;
 
+pub mod replicate;
+
 /// An object database equivalent to `/dev/null`, dropping all objects stored into it.
 mod traits;
 
-pub use traits::{Find, FindExt, Write};
+pub use traits::{Find, FindExt, Write, WriteWithHash};
 
 /// A thread-local handle to access any object.
 pub type Handle = Cache<store::Handle<OwnShared<Store>>>;
@@ -124,13 +131,37 @@ pub struct Store {
     pub(crate) num_handles_stable: AtomicUsize,
     /// The amount of handles that don't affect our ability to compact our internal data structures or unload packs or indices.
     pub(crate) num_handles_unstable: AtomicUsize,
+    /// The amount of handles whose snapshot is currently pinned.
+    pub(crate) num_pinned_handles: AtomicUsize,
 
     /// The amount of times we re-read the disk state to consolidate our in-memory representation.
     pub(crate) num_disk_state_consolidation: AtomicUsize,
+    /// If true, inaccessible directories and files cause errors, otherwise they are recorded in `warnings` and skipped.
+    strict: bool,
+    /// Object directories to use as alternates in addition to the ones listed in the `info/alternates` file.
+    pub(crate) extra_alternates: Vec<PathBuf>,
+    /// Determines which object directories may be used depending on the trust we have in them.
+    pub(crate) trust_policy: store::init::TrustPolicy,
+    /// All object directories along with their trust as classified during the most recent scan of the object database.
+    pub(crate) object_directories: parking_lot::Mutex<Vec<store::ObjectDirectory>>,
+    /// Problems encountered during the most recent scan of the object database, only collected if not `strict`.
+    pub(crate) warnings: parking_lot::Mutex<Vec<store::Warning>>,
+    /// Pack indices without a pack found during the most recent scan of the object database, only collected if not `strict`.
+    pub(crate) orphaned_indices: parking_lot::Mutex<Vec<PathBuf>>,
+    /// The `orphaned_indices` opened for lookups, or `None` if they weren't opened since the most recent scan.
+    pub(crate) orphaned_index_files: parking_lot::Mutex<Option<Vec<git_pack::index::File>>>,
+    /// Packs marked with a `.keep` file found during the most recent scan of the object database.
+    pub(crate) kept_packs: parking_lot::Mutex<Vec<store::KeptPack>>,
+    /// Packs marked with a `.promisor` file found during the most recent scan of the object database.
+    pub(crate) promisor_packs: parking_lot::Mutex<Vec<store::PromisorPack>>,
+    /// Index and pack data files which don't exist on disk but are always considered part of our state.
+    pub(crate) memory_bundles: Vec<(Arc<git_pack::index::File>, git_pack::data::File)>,
     /// If true, we are allowed to use multi-pack indices and they must have the `object_hash` or be ignored.
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
     object_hash: git_hash::Kind,
+    /// How to access the pack data files we load.
+    pack_access: git_pack::data::Access,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
@@ -0,0 +1,253 @@
+//! Each function spawns its operation onto the thread pool of the `blocking` crate and returns an [`Operation`],
+//! which provides a [stream of progress snapshots][Operation::progress] and a [future of the outcome][Operation::outcome].
+//!
+//! Dropping the [`Outcome`] future sets the interrupt flag of the operation, which then stops as soon as possible.
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::RecvTimeoutError,
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use git_hash::ObjectId;
+
+use crate::{loose, pack, store, Store};
+
+/// The state of all progress items of an operation at one point in time, sorted by their position in the progress tree.
+pub type Snapshot = Vec<(prodash::progress::Key, prodash::progress::Task)>;
+
+/// A long-running operation executing on a dedicated thread pool.
+pub struct Operation<T> {
+    /// The progress of the operation, to be polled as often as desired.
+    pub progress: Progress,
+    /// The result of the operation, which is interrupted when dropped.
+    pub outcome: Outcome<T>,
+}
+
+/// A stream of [snapshots][Snapshot] of the progress of an [`Operation`], which ends after the operation is done.
+///
+/// Only the latest snapshot is kept, so consumers which are slower than the sampling interval skip snapshots,
+/// but will always see the one taken after the operation finished.
+pub struct Progress {
+    snapshots: async_channel::Receiver<Snapshot>,
+}
+
+impl futures_core::Stream for Progress {
+    type Item = Snapshot;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.snapshots).poll_next(cx)
+    }
+}
+
+/// A future resolving to the outcome of an [`Operation`].
+///
+/// Dropping it before it is ready interrupts the operation and discards its outcome.
+pub struct Outcome<T> {
+    task: Option<blocking::Task<T>>,
+    should_interrupt: Arc<AtomicBool>,
+}
+
+impl<T> Outcome<T> {
+    /// Ask the operation to stop as soon as possible, which typically makes it fail with an error indicating the interruption.
+    pub fn interrupt(&self) {
+        self.should_interrupt.store(true, Ordering::SeqCst);
+    }
+
+    /// Return `true` if the operation was asked to stop.
+    pub fn is_interrupted(&self) -> bool {
+        self.should_interrupt.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> Future for Outcome<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let task = self
+            .task
+            .as_mut()
+            .expect("BUG: cannot poll a future again once it is ready");
+        let res = Pin::new(task).poll(cx);
+        if res.is_ready() {
+            self.task = None;
+        }
+        res
+    }
+}
+
+impl<T> Drop for Outcome<T> {
+    fn drop(&mut self) {
+        if self.task.is_some() {
+            self.interrupt();
+        }
+    }
+}
+
+/// Run [`Store::verify_integrity()`] on `store` with `options`, taking a progress snapshot every `progress_interval`.
+pub fn verify_integrity<C, F>(
+    store: Arc<Store>,
+    options: store::verify::integrity::Options<F>,
+    progress_interval: Duration,
+) -> Operation<Result<store::verify::integrity::Outcome, store::verify::integrity::Error>>
+where
+    C: pack::cache::DecodeEntry,
+    F: Fn() -> C + Send + Clone + 'static,
+{
+    spawn("verify store", progress_interval, move |progress, should_interrupt| {
+        store.verify_integrity(progress, should_interrupt, options)
+    })
+}
+
+/// Run [`pack::Bundle::verify_integrity()`] on `bundle` with `options`, taking a progress snapshot every `progress_interval`.
+pub fn verify_bundle_integrity<C, F>(
+    bundle: Arc<pack::Bundle>,
+    options: pack::index::verify::integrity::Options<F>,
+    progress_interval: Duration,
+) -> Operation<
+    Result<
+        pack::bundle::verify::integrity::Outcome,
+        pack::index::traverse::Error<pack::index::verify::integrity::Error>,
+    >,
+>
+where
+    C: pack::cache::DecodeEntry,
+    F: Fn() -> C + Send + Clone + 'static,
+{
+    spawn("verify pack", progress_interval, move |progress, should_interrupt| {
+        bundle.verify_integrity(progress, should_interrupt, options)
+    })
+}
+
+///
+pub mod for_each_object {
+    /// Returned by [`for_each_object()`][super::for_each_object()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error<E>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        #[error(transparent)]
+        LoadIndex(#[from] crate::store::load_index::Error),
+        #[error("The operation was interrupted")]
+        Interrupted,
+        #[error(transparent)]
+        Consume(E),
+    }
+}
+
+/// Iterate all objects of `store` as configured by `options` and pass each item of the [iterator][store::iter::AllObjects]
+/// to `consume`, taking a progress snapshot every `progress_interval`.
+///
+/// Return the amount of items passed to `consume`, or the first error it returned.
+pub fn for_each_object<F, E>(
+    store: Arc<Store>,
+    options: store::iter::Options,
+    progress_interval: Duration,
+    mut consume: F,
+) -> Operation<Result<usize, for_each_object::Error<E>>>
+where
+    F: FnMut(Result<ObjectId, loose::iter::Error>) -> Result<(), E> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    spawn(
+        "iterate objects",
+        progress_interval,
+        move |progress, should_interrupt| {
+            let objects = store::iter::AllObjects::new_with_options(&store, options)?;
+            progress.init(Some(objects.size_hint().0), git_features::progress::count("objects"));
+            let mut num_objects = 0;
+            for item in objects {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(for_each_object::Error::Interrupted);
+                }
+                consume(item).map_err(for_each_object::Error::Consume)?;
+                num_objects += 1;
+                progress.inc();
+            }
+            Ok(num_objects)
+        },
+    )
+}
+
+/// Run `operation` on the thread pool with a progress item named `name` and take a snapshot of its progress
+/// every `progress_interval` on another thread of the pool.
+fn spawn<T, F>(name: &str, progress_interval: Duration, operation: F) -> Operation<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut prodash::tree::Item, &AtomicBool) -> T + Send + 'static,
+{
+    let root = prodash::tree::Root::new();
+    let mut progress = root.add_child(name);
+    let should_interrupt = Arc::new(AtomicBool::new(false));
+    // The receiver is kept on our side as well to be able to drop a stale snapshot in favor of the latest one.
+    let (snapshots_tx, snapshots_rx) = async_channel::bounded(1);
+    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+    let (sampler_done_tx, sampler_done_rx) = std::sync::mpsc::channel::<()>();
+
+    blocking::unblock({
+        let root = root.clone();
+        let snapshots_tx = snapshots_tx.clone();
+        let snapshots_rx = snapshots_rx.clone();
+        move || {
+            while let Err(RecvTimeoutError::Timeout) = done_rx.recv_timeout(progress_interval) {
+                if !publish_snapshot(&root, &snapshots_tx, &snapshots_rx) {
+                    break;
+                }
+            }
+            drop(sampler_done_tx);
+        }
+    })
+    .detach();
+
+    let task = blocking::unblock({
+        let should_interrupt = should_interrupt.clone();
+        let snapshots_rx = snapshots_rx.clone();
+        move || {
+            let outcome = operation(&mut progress, &should_interrupt);
+            // Wait for the sampler to stop so that the final snapshot can't be replaced by an earlier one.
+            drop(done_tx);
+            sampler_done_rx.recv().ok();
+            publish_snapshot(&root, &snapshots_tx, &snapshots_rx);
+            snapshots_tx.close();
+            drop(progress);
+            outcome
+        }
+    });
+
+    Operation {
+        progress: Progress {
+            snapshots: snapshots_rx,
+        },
+        outcome: Outcome {
+            task: Some(task),
+            should_interrupt,
+        },
+    }
+}
+
+/// Replace the snapshot in the channel with the current progress of `root`, and return `false` if the channel was closed.
+fn publish_snapshot(
+    root: &prodash::Tree,
+    tx: &async_channel::Sender<Snapshot>,
+    rx: &async_channel::Receiver<Snapshot>,
+) -> bool {
+    let mut snapshot = Vec::new();
+    root.sorted_snapshot(&mut snapshot);
+    loop {
+        match tx.try_send(snapshot) {
+            Ok(()) => return true,
+            Err(async_channel::TrySendError::Full(unsent)) => {
+                snapshot = unsent;
+                rx.try_recv().ok();
+            }
+            Err(async_channel::TrySendError::Closed(_)) => return false,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::{io, ops::Deref};
+
+use super::{FrameKind, Manifest, FRAME_OVERHEAD, HEADER_LEN, MAGIC, VERSION};
+use crate::store;
+
+mod error {
+    use crate::{loose, store};
+
+    /// Returned by [`export()`][super::export()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Frames must be larger than their overhead of {} bytes and carry at most 4GiB of payload, got a frame size of {frame_size}", crate::replicate::FRAME_OVERHEAD)]
+        FrameSize { frame_size: usize },
+        #[error(transparent)]
+        LoadIndex(#[from] store::load_index::Error),
+        #[error(transparent)]
+        Iter(#[from] loose::iter::Error),
+        #[error(transparent)]
+        Find(#[from] store::find::Error),
+        #[error("Object {id} could not be found even though it was listed")]
+        NotFound { id: git_hash::ObjectId },
+        #[error("Could not write frame {sequence} into the sink")]
+        Io { sequence: u32, source: std::io::Error },
+    }
+}
+pub use error::Error;
+
+/// Options for use in [`export()`][super::export()].
+#[derive(Debug, Clone)]
+pub struct Options<F> {
+    /// The maximum size of each frame in bytes, including the [`FRAME_OVERHEAD`][super::FRAME_OVERHEAD].
+    ///
+    /// Defaults to 1MiB.
+    pub frame_size: usize,
+    /// A function called with the id and kind of each object, which returns `true` if the object should be exported.
+    pub filter: F,
+}
+
+impl Default for Options<fn(&git_hash::oid, git_object::Kind) -> bool> {
+    fn default() -> Self {
+        Options {
+            frame_size: 1024 * 1024,
+            filter: |_, _| true,
+        }
+    }
+}
+
+/// Returned by [`export()`][super::export()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The manifest which was written as last frame.
+    pub manifest: Manifest,
+    /// The amount of objects which were not exported as the filter rejected them.
+    pub filtered: u64,
+    /// The amount of frames written, including the manifest.
+    pub frames: u32,
+    /// The amount of bytes written into the sink across all frames.
+    pub bytes_written: u64,
+}
+
+/// Write all objects of `handle` that pass the `filter` as records into frames of at most `frame_size` bytes to `sink`,
+/// followed by a manifest frame.
+///
+/// Each frame is written in full with a single call to `write_all()`, followed by a call to `flush()`, which allows sinks to
+/// send each frame as message of its own.
+///
+/// Objects are exported as they are stored and without replacements, and objects stored in multiple places are exported only once.
+pub fn export<S, F>(
+    handle: &store::Handle<S>,
+    sink: impl io::Write,
+    Options { frame_size, mut filter }: Options<F>,
+) -> Result<Outcome, Error>
+where
+    S: Deref<Target = crate::Store> + Clone,
+    F: FnMut(&git_hash::oid, git_object::Kind) -> bool,
+{
+    if frame_size <= FRAME_OVERHEAD || frame_size - FRAME_OVERHEAD > u32::MAX as usize {
+        return Err(Error::FrameSize { frame_size });
+    }
+    let mut handle = handle.clone();
+    handle.ignore_replacements = true;
+    let object_hash = handle.store_ref().object_hash();
+
+    let mut frames = Frames {
+        sink,
+        object_hash,
+        payload_cap: frame_size - FRAME_OVERHEAD,
+        buf: Vec::with_capacity(frame_size),
+        out: Outcome::default(),
+    };
+    frames.start_frame();
+
+    let mut seen = git_hashtable::HashSet::default();
+    let mut data = Vec::new();
+    let mut record_header = Vec::with_capacity(super::record_header_len(object_hash));
+    for id in handle.iter()? {
+        let id = id?;
+        if !seen.insert(id) {
+            continue;
+        }
+        let (obj, _location) = git_pack::Find::try_find(&handle, id, &mut data)?.ok_or(Error::NotFound { id })?;
+        if !filter(&id, obj.kind) {
+            frames.out.filtered += 1;
+            continue;
+        }
+        record_header.clear();
+        super::encode_record_header(&id, obj.kind, obj.data.len() as u64, &mut record_header);
+        frames.push(&record_header)?;
+        frames.push(obj.data)?;
+        frames.out.manifest.records += 1;
+        frames.out.manifest.object_bytes += obj.data.len() as u64;
+    }
+    frames.finish()
+}
+
+struct Frames<W> {
+    sink: W,
+    object_hash: git_hash::Kind,
+    payload_cap: usize,
+    /// The frame currently being filled, starting with its header.
+    buf: Vec<u8>,
+    out: Outcome,
+}
+
+impl<W> Frames<W>
+where
+    W: io::Write,
+{
+    fn start_frame(&mut self) {
+        self.buf.clear();
+        self.buf.resize(HEADER_LEN, 0);
+    }
+
+    fn payload_len(&self) -> usize {
+        self.buf.len() - HEADER_LEN
+    }
+
+    fn push(&mut self, mut bytes: &[u8]) -> Result<(), Error> {
+        while !bytes.is_empty() {
+            let n = (self.payload_cap - self.payload_len()).min(bytes.len());
+            self.buf.extend_from_slice(&bytes[..n]);
+            bytes = &bytes[n..];
+            if self.payload_len() == self.payload_cap {
+                self.write_frame(FrameKind::Data)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Outcome, Error> {
+        if self.payload_len() != 0 {
+            self.write_frame(FrameKind::Data)?;
+        }
+        self.out.manifest.data_frames = self.out.frames;
+        let manifest = self.out.manifest.to_bytes();
+        self.buf.extend_from_slice(&manifest);
+        self.write_frame(FrameKind::Manifest)?;
+        Ok(self.out)
+    }
+
+    fn write_frame(&mut self, kind: FrameKind) -> Result<(), Error> {
+        let sequence = self.out.frames;
+        let payload_len = self.payload_len() as u32;
+        let header = &mut self.buf[..HEADER_LEN];
+        header[..4].copy_from_slice(MAGIC);
+        header[4] = VERSION;
+        header[5] = super::hash_to_byte(self.object_hash);
+        header[6] = kind.to_byte();
+        header[7..11].copy_from_slice(&sequence.to_be_bytes());
+        header[11..15].copy_from_slice(&payload_len.to_be_bytes());
+        let crc = git_features::hash::crc32(&self.buf);
+        self.buf.extend_from_slice(&crc.to_be_bytes());
+
+        self.sink
+            .write_all(&self.buf)
+            .and_then(|_| self.sink.flush())
+            .map_err(|source| Error::Io { sequence, source })?;
+        self.out.frames += 1;
+        self.out.bytes_written += self.buf.len() as u64;
+        self.start_frame();
+        Ok(())
+    }
+}
@@ -0,0 +1,319 @@
+use std::{convert::TryInto, io, io::Read, ops::Deref};
+
+use git_hash::ObjectId;
+
+use super::{FrameKind, Manifest, HEADER_LEN, MAGIC, TRAILER_LEN, VERSION};
+use crate::{store, Find, Write};
+
+mod error {
+    use git_hash::ObjectId;
+
+    use crate::{replicate::Manifest, store};
+
+    /// Returned by [`import()`][super::import()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read frame {sequence}")]
+        Io { sequence: u32, source: std::io::Error },
+        #[error("Frame {sequence} doesn't start with the expected magic bytes")]
+        Magic { sequence: u32 },
+        #[error("Frame {sequence} uses format version {version}, but only version 1 is supported")]
+        UnsupportedVersion { sequence: u32, version: u8 },
+        #[error("Frame {sequence} contains objects of hash type {actual}, but the destination uses {expected}")]
+        ObjectHash {
+            sequence: u32,
+            expected: git_hash::Kind,
+            actual: u8,
+        },
+        #[error("Frame {sequence} has the unknown kind {kind}")]
+        UnknownFrameKind { sequence: u32, kind: u8 },
+        #[error("Expected frame {expected}, but got frame {actual}")]
+        Sequence { expected: u32, actual: u32 },
+        #[error("Frame {sequence} declares a payload of {len} bytes, exceeding the limit of {limit} bytes")]
+        FrameSizeLimitExceeded { sequence: u32, len: u64, limit: u64 },
+        #[error("The checksum of frame {sequence} is {actual:08x}, but should be {expected:08x}")]
+        Checksum { sequence: u32, expected: u32, actual: u32 },
+        #[error("The stream ended after {frames} frames without a manifest")]
+        MissingManifest { frames: u32 },
+        #[error("The manifest in frame {sequence} has an invalid size of {len} bytes")]
+        InvalidManifest { sequence: u32, len: usize },
+        #[error("The manifest announced {expected:?}, but {actual:?} was received")]
+        ManifestMismatch { expected: Manifest, actual: Manifest },
+        #[error("The data frames ended in the middle of a record{}", id.map(|id| format!(" of object {id}")).unwrap_or_default())]
+        TruncatedRecord { id: Option<ObjectId> },
+        #[error("The record of object {id} declares a size of {size} bytes, exceeding the limit of {limit} bytes")]
+        SizeLimitExceeded { id: ObjectId, size: u64, limit: u64 },
+        #[error("The record of object {id} has the unknown object kind {kind}")]
+        UnknownObjectKind { id: ObjectId, kind: u8 },
+        #[error("The record of object {expected} has data which hashes to {actual}")]
+        IdMismatch { expected: ObjectId, actual: ObjectId },
+        #[error(transparent)]
+        Write(#[from] store::write::Error),
+    }
+}
+pub use error::Error;
+
+/// Options for use in [`import()`][super::import()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If set, records of objects declaring a larger size in bytes fail the import with [`Error::SizeLimitExceeded`]
+    /// before any of their data is read.
+    ///
+    /// Frames declaring a payload larger than this plus the header of a record fail the import with
+    /// [`Error::FrameSizeLimitExceeded`] before any memory is allocated for them, so the frames must have been
+    /// [exported][super::export()] with a `frame_size` of at most this plus the header of a record and
+    /// [`FRAME_OVERHEAD`][super::FRAME_OVERHEAD].
+    ///
+    /// Otherwise, objects and frames of any size that fits into memory are accepted. Their data is buffered as it arrives,
+    /// so memory use is bounded by the data actually received rather than the declared size.
+    pub max_object_size: Option<u64>,
+}
+
+/// Returned by [`import()`][super::import()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The manifest that was received as last frame, which matches all records that were received.
+    pub manifest: Manifest,
+    /// The amount of frames read, including the manifest.
+    pub frames: u32,
+    /// The amount of objects that were written into the destination.
+    pub objects_written: u64,
+    /// The amount of objects that were skipped as they already existed in the destination.
+    pub objects_skipped: u64,
+    /// The amount of bytes of object data written into the destination, before compression.
+    pub bytes_written: u64,
+}
+
+/// Read frames as written by [`export()`][super::export()] from `source` and write all objects of their records into `handle`,
+/// until the manifest frame is encountered and validated. Data following the manifest isn't read.
+///
+/// Each frame is validated, as is the data of each record which has to hash to the id of the record.
+/// Objects that already exist in `handle` are skipped, which makes it possible to resume an interrupted import by importing
+/// all frames again.
+pub fn import<S>(
+    source: impl io::Read,
+    handle: &store::Handle<S>,
+    Options { max_object_size }: Options,
+) -> Result<Outcome, Error>
+where
+    S: Deref<Target = crate::Store> + Clone,
+{
+    let object_hash = handle.store_ref().object_hash();
+    let hash_len = object_hash.len_in_bytes();
+    let mut frames = Frames {
+        source,
+        object_hash,
+        max_payload_len: max_object_size.map(|size| size.saturating_add(super::record_header_len(object_hash) as u64)),
+        next_sequence: 0,
+        data_frames: 0,
+        payload: Vec::new(),
+        pos: 0,
+        manifest: None,
+    };
+    let mut out = Outcome::default();
+    let mut received = Manifest::default();
+    let mut header = vec![0; super::record_header_len(object_hash)];
+    let mut data = Vec::new();
+    while frames
+        .read_exact(&mut header)
+        .map_err(|err| err.unwrap_or(Error::TruncatedRecord { id: None }))?
+    {
+        let id = ObjectId::from(&header[..hash_len]);
+        let kind = super::kind_from_byte(header[hash_len]).ok_or(Error::UnknownObjectKind {
+            id,
+            kind: header[hash_len],
+        })?;
+        let size = u64::from_be_bytes(header[hash_len + 1..].try_into().expect("8 bytes of size"));
+        let limit = max_object_size.unwrap_or(u64::MAX).min(usize::MAX as u64);
+        if size > limit {
+            return Err(Error::SizeLimitExceeded { id, size, limit });
+        }
+        data.clear();
+        if !frames
+            .read_with(size as usize, |chunk| data.extend_from_slice(chunk))
+            .map_err(|err| err.unwrap_or(Error::TruncatedRecord { id: Some(id) }))?
+        {
+            return Err(Error::TruncatedRecord { id: Some(id) });
+        }
+        received.records += 1;
+        received.object_bytes += size;
+
+        let actual = git_object::compute_hash(kind, &data, object_hash);
+        if actual != id {
+            return Err(Error::IdMismatch { expected: id, actual });
+        }
+        if handle.contains(id) {
+            out.objects_skipped += 1;
+            continue;
+        }
+        handle.write_buf(kind, &data)?;
+        out.objects_written += 1;
+        out.bytes_written += size;
+    }
+
+    received.data_frames = frames.data_frames;
+    let manifest = frames.manifest.expect("records end only with the manifest");
+    if manifest != received {
+        return Err(Error::ManifestMismatch {
+            expected: manifest,
+            actual: received,
+        });
+    }
+    out.manifest = manifest;
+    out.frames = frames.next_sequence;
+    Ok(out)
+}
+
+struct Frames<R> {
+    source: R,
+    object_hash: git_hash::Kind,
+    /// If set, frames with larger payloads are rejected before reading them.
+    max_payload_len: Option<u64>,
+    next_sequence: u32,
+    data_frames: u32,
+    /// The payload of the current data frame.
+    payload: Vec<u8>,
+    /// The amount of bytes of `payload` that were already consumed.
+    pos: usize,
+    manifest: Option<Manifest>,
+}
+
+impl<R> Frames<R>
+where
+    R: io::Read,
+{
+    /// Fill `buf` from the payloads of data frames, or return `false` if the manifest was reached before reading anything.
+    /// Return `Err(None)` if the manifest was reached after reading only a part of `buf`.
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<bool, Option<Error>> {
+        let len = buf.len();
+        self.read_with(len, |chunk| {
+            let (dest, rest) = std::mem::take(&mut buf).split_at_mut(chunk.len());
+            dest.copy_from_slice(chunk);
+            buf = rest;
+        })
+    }
+
+    /// Pass the next `len` bytes of the payloads of data frames to `consume` in chunks as they are available, or return `false`
+    /// if the manifest was reached before reading anything.
+    /// Return `Err(None)` if the manifest was reached after reading only a part of them.
+    fn read_with(&mut self, mut len: usize, mut consume: impl FnMut(&[u8])) -> Result<bool, Option<Error>> {
+        let mut read_any = false;
+        while len != 0 {
+            if self.pos == self.payload.len() {
+                if self.manifest.is_some() || !self.next_frame()? {
+                    return if read_any { Err(None) } else { Ok(false) };
+                }
+                continue;
+            }
+            let n = (self.payload.len() - self.pos).min(len);
+            consume(&self.payload[self.pos..][..n]);
+            self.pos += n;
+            len -= n;
+            read_any = true;
+        }
+        Ok(true)
+    }
+
+    /// Read and validate the next frame, and return `true` if it's a data frame or `false` if it's the manifest.
+    fn next_frame(&mut self) -> Result<bool, Error> {
+        let sequence = self.next_sequence;
+        let mut header = [0u8; HEADER_LEN];
+        if !read_exact_or_eof(&mut self.source, &mut header).map_err(|source| Error::Io { sequence, source })? {
+            return Err(Error::MissingManifest { frames: sequence });
+        }
+        if &header[..4] != MAGIC {
+            return Err(Error::Magic { sequence });
+        }
+        if header[4] != VERSION {
+            return Err(Error::UnsupportedVersion {
+                sequence,
+                version: header[4],
+            });
+        }
+        if header[5] != super::hash_to_byte(self.object_hash) {
+            return Err(Error::ObjectHash {
+                sequence,
+                expected: self.object_hash,
+                actual: header[5],
+            });
+        }
+        let kind = FrameKind::from_byte(header[6]).ok_or(Error::UnknownFrameKind {
+            sequence,
+            kind: header[6],
+        })?;
+        let actual_sequence = u32::from_be_bytes(header[7..11].try_into().expect("4 bytes"));
+        if actual_sequence != sequence {
+            return Err(Error::Sequence {
+                expected: sequence,
+                actual: actual_sequence,
+            });
+        }
+        let payload_len = u32::from_be_bytes(header[11..15].try_into().expect("4 bytes")) as u64;
+        if let Some(limit) = self.max_payload_len.filter(|limit| payload_len > *limit) {
+            return Err(Error::FrameSizeLimitExceeded {
+                sequence,
+                len: payload_len,
+                limit,
+            });
+        }
+
+        // Grow the buffer as data arrives to not allocate what a corrupt frame merely declares.
+        self.payload.clear();
+        let frame_rest_len = payload_len + TRAILER_LEN as u64;
+        let read = (&mut self.source)
+            .take(frame_rest_len)
+            .read_to_end(&mut self.payload)
+            .map_err(|source| Error::Io { sequence, source })?;
+        if read as u64 != frame_rest_len {
+            return Err(Error::Io {
+                sequence,
+                source: io::ErrorKind::UnexpectedEof.into(),
+            });
+        }
+        let payload_len = payload_len as usize;
+        let expected_crc = u32::from_be_bytes(self.payload[payload_len..].try_into().expect("4 bytes"));
+        self.payload.truncate(payload_len);
+        let actual_crc = git_features::hash::crc32_update(git_features::hash::crc32(&header), &self.payload);
+        if actual_crc != expected_crc {
+            return Err(Error::Checksum {
+                sequence,
+                expected: expected_crc,
+                actual: actual_crc,
+            });
+        }
+        self.pos = 0;
+        self.next_sequence += 1;
+
+        Ok(match kind {
+            FrameKind::Data => {
+                self.data_frames += 1;
+                true
+            }
+            FrameKind::Manifest => {
+                self.manifest = Some(Manifest::from_bytes(&self.payload).ok_or(Error::InvalidManifest {
+                    sequence,
+                    len: payload_len,
+                })?);
+                self.payload.clear();
+                false
+            }
+        })
+    }
+}
+
+/// Fill `buf` from `read`, or return `false` if `read` was depleted before reading anything.
+fn read_exact_or_eof(read: &mut impl io::Read, buf: &mut [u8]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match read.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(true)
+}
@@ -0,0 +1,141 @@
+//! A framed format to replicate the objects of a [store][crate::Store] into another one through transports which
+//! cap the size of their messages, like message queues.
+//!
+//! [`export()`] iterates all objects of a store and writes them as records of `(id, kind, size, data)` into a sequence of frames
+//! no larger than a configured size, followed by a manifest frame summarizing what was sent.
+//! [`import()`] reads these frames back, validates them, and writes each object into another store. Objects which already exist
+//! there are skipped, which allows to resume an import that was interrupted by simply importing the same frames again.
+//!
+//! ### Format
+//!
+//! All integers are stored in big-endian byte order. Each frame looks like this:
+//!
+//! | bytes                  | meaning                                                              |
+//! |------------------------|----------------------------------------------------------------------|
+//! | 4                      | the magic `ODBR`                                                     |
+//! | 1                      | the format version, currently `1`                                    |
+//! | 1                      | the object hash, `1` for SHA1                                        |
+//! | 1                      | the frame kind, `d` for data or `m` for the manifest                 |
+//! | 4                      | the sequence number of the frame, starting at 0                      |
+//! | 4                      | the length of the payload                                            |
+//! | length of the payload  | the payload                                                          |
+//! | 4                      | the CRC32 of all of the above                                        |
+//!
+//! The payloads of all data frames form a stream of records, each of which is the object id, its kind as single byte
+//! (`1` commit, `2` tree, `3` blob, `4` tag), its size as 8 bytes and the object data. Records may span multiple frames,
+//! which is how objects larger than a frame are transmitted.
+//!
+//! The manifest is always the last frame. Its payload is the amount of records as 8 bytes, the amount of data frames
+//! as 4 bytes and the total amount of object data as 8 bytes.
+use std::convert::TryInto;
+
+use git_hash::ObjectId;
+
+///
+pub mod export;
+pub use export::export;
+
+///
+pub mod import;
+pub use import::import;
+
+const MAGIC: &[u8; 4] = b"ODBR";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 4 + 1 + 1 + 1 + 4 + 4;
+const TRAILER_LEN: usize = 4;
+const MANIFEST_LEN: usize = 8 + 4 + 8;
+
+/// The amount of bytes each frame adds to its payload.
+pub const FRAME_OVERHEAD: usize = HEADER_LEN + TRAILER_LEN;
+
+/// The kind of a frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrameKind {
+    /// A frame with a part of the stream of records.
+    Data,
+    /// The last frame which summarizes all previous ones.
+    Manifest,
+}
+
+impl FrameKind {
+    fn to_byte(self) -> u8 {
+        match self {
+            FrameKind::Data => b'd',
+            FrameKind::Manifest => b'm',
+        }
+    }
+
+    fn from_byte(b: u8) -> Option<Self> {
+        Some(match b {
+            b'd' => FrameKind::Data,
+            b'm' => FrameKind::Manifest,
+            _ => return None,
+        })
+    }
+}
+
+/// The content of the manifest frame, which summarizes all data frames before it.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Manifest {
+    /// The amount of object records in all data frames.
+    pub records: u64,
+    /// The amount of data frames.
+    pub data_frames: u32,
+    /// The amount of bytes of object data in all records, excluding the record headers.
+    pub object_bytes: u64,
+}
+
+impl Manifest {
+    fn to_bytes(self) -> [u8; MANIFEST_LEN] {
+        let mut out = [0u8; MANIFEST_LEN];
+        out[..8].copy_from_slice(&self.records.to_be_bytes());
+        out[8..12].copy_from_slice(&self.data_frames.to_be_bytes());
+        out[12..].copy_from_slice(&self.object_bytes.to_be_bytes());
+        out
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        (bytes.len() == MANIFEST_LEN).then(|| Manifest {
+            records: u64::from_be_bytes(bytes[..8].try_into().expect("8 bytes")),
+            data_frames: u32::from_be_bytes(bytes[8..12].try_into().expect("4 bytes")),
+            object_bytes: u64::from_be_bytes(bytes[12..].try_into().expect("8 bytes")),
+        })
+    }
+}
+
+fn hash_to_byte(kind: git_hash::Kind) -> u8 {
+    match kind {
+        git_hash::Kind::Sha1 => 1,
+    }
+}
+
+fn kind_to_byte(kind: git_object::Kind) -> u8 {
+    match kind {
+        git_object::Kind::Commit => 1,
+        git_object::Kind::Tree => 2,
+        git_object::Kind::Blob => 3,
+        git_object::Kind::Tag => 4,
+    }
+}
+
+fn kind_from_byte(b: u8) -> Option<git_object::Kind> {
+    Some(match b {
+        1 => git_object::Kind::Commit,
+        2 => git_object::Kind::Tree,
+        3 => git_object::Kind::Blob,
+        4 => git_object::Kind::Tag,
+        _ => return None,
+    })
+}
+
+/// Return the length of a record header for objects of `hash`.
+fn record_header_len(hash: git_hash::Kind) -> usize {
+    hash.len_in_bytes() + 1 + 8
+}
+
+fn encode_record_header(id: &ObjectId, kind: git_object::Kind, size: u64, out: &mut Vec<u8>) {
+    out.extend_from_slice(id.as_slice());
+    out.push(kind_to_byte(kind));
+    out.extend_from_slice(&size.to_be_bytes());
+}
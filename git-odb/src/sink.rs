@@ -1,6 +1,5 @@
 use std::{
     cell::RefCell,
-    convert::TryInto,
     io::{self, Write},
 };
 
@@ -29,38 +28,38 @@ impl crate::traits::Write for Sink {
         size: u64,
         mut from: impl io::Read,
     ) -> Result<git_hash::ObjectId, Self::Error> {
-        let mut size = size.try_into().expect("object size to fit into usize");
-        use git_features::hash::Sha1;
-        let mut buf = [0u8; 8096];
-        let header = git_object::encode::loose_header(kind, size);
-
-        let possibly_compress = |buf: &[u8]| -> io::Result<()> {
-            if let Some(compressor) = self.compressor.as_ref() {
-                compressor.try_borrow_mut().expect("no recursion").write_all(buf)?;
+        match self.compressor.as_ref() {
+            Some(compressor) => {
+                let mut compressor = compressor.try_borrow_mut().expect("no recursion");
+                compressor.write_all(&git_object::encode::loose_header(kind, size))?;
+                let id = git_object::compute_stream_hash(
+                    kind,
+                    &mut Compress {
+                        from,
+                        to: &mut *compressor,
+                    },
+                    size,
+                    self.object_hash,
+                )?;
+                compressor.flush()?;
+                compressor.reset();
+                Ok(id)
             }
-            Ok(())
-        };
-        match self.object_hash {
-            git_hash::Kind::Sha1 => {
-                let mut hasher = Sha1::default();
-                hasher.update(&header);
-                possibly_compress(&header)?;
+            None => git_object::compute_stream_hash(kind, &mut from, size, self.object_hash),
+        }
+    }
+}
 
-                while size != 0 {
-                    let bytes = size.min(buf.len());
-                    from.read_exact(&mut buf[..bytes])?;
-                    hasher.update(&buf[..bytes]);
-                    possibly_compress(&buf[..bytes])?;
-                    size -= bytes;
-                }
-                if let Some(compressor) = self.compressor.as_ref() {
-                    let mut c = compressor.borrow_mut();
-                    c.flush()?;
-                    c.reset();
-                }
+/// A reader which compresses all bytes read from `from` into `to`.
+struct Compress<'a, R> {
+    from: R,
+    to: &'a mut deflate::Write<io::Sink>,
+}
 
-                Ok(hasher.digest().into())
-            }
-        }
+impl<R: io::Read> io::Read for Compress<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_bytes = self.from.read(buf)?;
+        self.to.write_all(&buf[..num_bytes])?;
+        Ok(num_bytes)
     }
 }
@@ -21,4 +21,120 @@ impl Store {
     pub fn replacements(&self) -> impl Iterator<Item = (git_hash::ObjectId, git_hash::ObjectId)> + '_ {
         self.replacements.iter().cloned()
     }
+
+    /// Whether or not inaccessible directories and files fail operations instead of being recorded as [warnings][Store::warnings()].
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// How pack data files are accessed when they are loaded.
+    pub fn pack_access(&self) -> git_pack::data::Access {
+        self.pack_access
+    }
+
+    /// Return all problems encountered when the object database was last scanned, like directories that couldn't be read due to
+    /// missing permissions.
+    ///
+    /// These are only collected if the store isn't [strict][Store::is_strict()], and are skipped otherwise.
+    pub fn warnings(&self) -> Vec<crate::store::Warning> {
+        self.warnings.lock().clone()
+    }
+
+    /// Return all object directories, the objects directory first and its alternates thereafter, along with the trust we have
+    /// in them as classified when the object database was last scanned.
+    ///
+    /// Those which aren't [used][crate::store::ObjectDirectory::used] are skipped as configured by the
+    /// [trust policy][crate::store::init::TrustPolicy].
+    pub fn object_directories(&self) -> Vec<crate::store::ObjectDirectory> {
+        self.object_directories.lock().clone()
+    }
+
+    /// Return the paths to all pack indices whose pack was missing when the object database was last scanned, sorted by path.
+    ///
+    /// Objects listed in these indices can't be retrieved, and lookups of objects that can't be found elsewhere fail
+    /// with [an error][crate::store::find::Error::OrphanedIndex] pointing to the index.
+    /// These are only collected if the store isn't [strict][Store::is_strict()], which fails instead.
+    pub fn orphaned_indices(&self) -> Vec<std::path::PathBuf> {
+        self.orphaned_indices.lock().clone()
+    }
+
+    /// Return the paths to all pack data files marked with a `.keep` file when the object database was last scanned,
+    /// sorted by path.
+    ///
+    /// Kept packs must not be deleted or rewritten by maintenance operations. Use [`kept_pack()`][Store::kept_pack()]
+    /// to learn why a pack is kept.
+    pub fn kept_packs(&self) -> Vec<std::path::PathBuf> {
+        self.kept_packs.lock().iter().map(|kept| kept.path.clone()).collect()
+    }
+
+    /// Return information about the pack data file at `pack_path` if it is marked with a `.keep` file, or `None` if it
+    /// is not kept or unknown.
+    pub fn kept_pack(&self, pack_path: impl AsRef<std::path::Path>) -> Option<crate::store::KeptPack> {
+        let pack_path = pack_path.as_ref();
+        self.kept_packs
+            .lock()
+            .iter()
+            .find(|kept| kept.path == pack_path)
+            .cloned()
+    }
+
+    /// Return the paths to all pack data files marked with a `.promisor` file when the object database was last scanned,
+    /// sorted by path.
+    ///
+    /// Their presence indicates a partial clone, whose missing objects are expected to be provided by a promisor remote.
+    pub fn promisor_packs(&self) -> Vec<std::path::PathBuf> {
+        self.promisor_packs
+            .lock()
+            .iter()
+            .map(|pack| pack.path.clone())
+            .collect()
+    }
+
+    /// Return information about the pack data file at `pack_path` if it is marked with a `.promisor` file, or `None` if it
+    /// is not a promisor pack or unknown.
+    pub fn promisor_pack(&self, pack_path: impl AsRef<std::path::Path>) -> Option<crate::store::PromisorPack> {
+        let pack_path = pack_path.as_ref();
+        self.promisor_packs
+            .lock()
+            .iter()
+            .find(|pack| pack.path == pack_path)
+            .cloned()
+    }
+
+    /// Return the id that the reference with the full `name`, like `refs/heads/main`, pointed to when a promisor pack was fetched,
+    /// or `None` if no promisor pack lists it.
+    ///
+    /// If more than one promisor pack lists the reference, the one whose path sorts first wins.
+    pub fn promisor_ref(&self, name: impl AsRef<git_object::bstr::BStr>) -> Option<git_hash::ObjectId> {
+        let name = name.as_ref();
+        self.promisor_packs
+            .lock()
+            .iter()
+            .flat_map(|pack| pack.refs.iter())
+            .find_map(|(id, ref_name)| (ref_name == name).then(|| *id))
+    }
+
+    /// Return the object directories used in addition to those listed in the `info/alternates` file, as passed
+    /// in [`Options::extra_alternates`][crate::store::init::Options::extra_alternates].
+    pub fn extra_alternates(&self) -> &[std::path::PathBuf] {
+        &self.extra_alternates
+    }
+
+    /// Return the path of the first orphaned index which lists `id`.
+    ///
+    /// The orphaned indices are opened only once after each scan of the object database, and those which can't be
+    /// opened are ignored.
+    pub(crate) fn orphaned_index_containing(&self, id: &git_hash::oid) -> Option<std::path::PathBuf> {
+        let mut files = self.orphaned_index_files.lock();
+        files
+            .get_or_insert_with(|| {
+                self.orphaned_indices()
+                    .iter()
+                    .filter_map(|path| git_pack::index::File::at(path, self.object_hash).ok())
+                    .collect()
+            })
+            .iter()
+            .find(|index| index.lookup(id).is_some())
+            .map(|index| index.path().to_owned())
+    }
 }
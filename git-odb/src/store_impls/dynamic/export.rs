@@ -0,0 +1,102 @@
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::progress::Progress;
+use git_hash::ObjectId;
+
+use crate::{loose, Write};
+
+mod error {
+    use crate::{loose, store};
+
+    /// Returned by [`Handle::export_loose()`][crate::store::Handle::export_loose()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Find(#[from] store::find::Error),
+        #[error("Object {id} could not be found")]
+        NotFound { id: git_hash::ObjectId },
+        #[error(transparent)]
+        LooseWrite(#[from] loose::write::Error),
+        #[error("Object {expected} was written as {actual}, which happens if its data is corrupt")]
+        IdMismatch {
+            expected: git_hash::ObjectId,
+            actual: git_hash::ObjectId,
+        },
+        #[error("Could not obtain the size of the object file at '{}'", .path.display())]
+        Metadata {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("Interrupted")]
+        Interrupted,
+    }
+}
+pub use error::Error;
+
+/// Returned by [`Handle::export_loose()`][crate::store::Handle::export_loose()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The amount of objects written as loose objects.
+    pub objects_written: usize,
+    /// The amount of objects which already existed in the destination and were skipped.
+    pub objects_skipped: usize,
+    /// The amount of compressed bytes written to the destination.
+    pub bytes_written: u64,
+}
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Write each object identified by `ids` into `dest` as loose object, which is useful to make packed objects
+    /// accessible to tools which only understand loose objects.
+    ///
+    /// Objects which already exist in `dest` are skipped. Each object is decoded entirely into a buffer that is reused
+    /// for all objects, and a temporary file is moved into place once it was written completely.
+    /// [Replacements][super::Handle::ignore_replacements] are never applied, so the exported objects are exactly the ones
+    /// identified by `ids`.
+    ///
+    /// `progress` counts the objects we handled, and `should_interrupt` is checked before each object.
+    pub fn export_loose<P>(
+        &self,
+        ids: impl Iterator<Item = ObjectId>,
+        dest: &loose::Store,
+        progress: &mut P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error>
+    where
+        P: Progress,
+    {
+        let mut handle = self.clone();
+        handle.ignore_replacements = true;
+        progress.init(ids.size_hint().1, git_features::progress::count("objects"));
+        let mut out = Outcome::default();
+        let mut buf = Vec::new();
+        for id in ids {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            progress.inc();
+            if dest.contains(id) {
+                out.objects_skipped += 1;
+                continue;
+            }
+            let (obj, _location) = git_pack::Find::try_find(&handle, id, &mut buf)?.ok_or(Error::NotFound { id })?;
+            let actual = dest.write_stream(obj.kind, obj.data.len() as u64, obj.data)?;
+            if actual != id {
+                return Err(Error::IdMismatch { expected: id, actual });
+            }
+            let path = loose::hash_path(&id, dest.path().to_owned());
+            out.bytes_written += std::fs::metadata(&path)
+                .map_err(|source| Error::Metadata { path, source })?
+                .len();
+            out.objects_written += 1;
+        }
+        Ok(out)
+    }
+}
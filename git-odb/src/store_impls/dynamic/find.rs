@@ -3,7 +3,7 @@ use std::{collections::HashSet, convert::TryInto, ops::Deref};
 use git_hash::{oid, ObjectId};
 use git_pack::{cache::DecodeEntry, data::entry::Location};
 
-use crate::store::{handle, load_index};
+use crate::store::{handle, load_index, MissingObjectPolicy};
 
 mod error {
     use crate::{loose, pack};
@@ -43,6 +43,18 @@ mod error {
             /// The original object to lookup
             id: git_hash::ObjectId,
         },
+        #[error("Object {} is only listed in the index at '{}' whose pack is missing", .id, .index_path.display())]
+        OrphanedIndex {
+            /// The object to lookup
+            id: git_hash::ObjectId,
+            /// The path to the index without a pack which lists the object
+            index_path: std::path::PathBuf,
+        },
+        #[error("Object {} is missing, but expected to be provided by the promisor remote of this partial clone", .id)]
+        Promised {
+            /// The object which is missing locally
+            id: git_hash::ObjectId,
+        },
         #[error("An error occurred when looking up a ref delta base object {} to decode {}", .base_id, .id)]
         DeltaBaseLookup {
             #[source]
@@ -54,6 +66,36 @@ mod error {
         },
     }
 
+    impl Error {
+        /// Return the kind of this error, classifying errors of the underlying loose or packed object stores.
+        pub fn kind(&self) -> crate::find::ErrorKind {
+            use crate::find::ErrorKind;
+            match self {
+                Error::Loose(err) => err.kind(),
+                Error::Pack(err) => err.kind(),
+                Error::LooseWalkDir(_) | Error::LoadPack(_) => ErrorKind::Io,
+                Error::LoadIndex(err) => err.kind(),
+                Error::ThinPackAtRest { .. } | Error::DeltaBaseMissing { .. } | Error::OrphanedIndex { .. } => {
+                    ErrorKind::Corrupt
+                }
+                Error::DeltaBaseRecursionLimit { .. } => ErrorKind::LimitExceeded,
+                Error::Promised { .. } => ErrorKind::Promised,
+                Error::DeltaBaseLookup { err, .. } => err.kind(),
+            }
+        }
+
+        /// Return `true` if the object doesn't exist, which is never the case as lookups of missing objects succeed with `None`.
+        pub fn is_not_found(&self) -> bool {
+            self.kind() == crate::find::ErrorKind::NotFound
+        }
+    }
+
+    impl crate::find::Classify for Error {
+        fn kind(&self) -> crate::find::ErrorKind {
+            Error::kind(self)
+        }
+    }
+
     #[derive(Copy, Clone)]
     pub(crate) struct DeltaBaseRecursion<'a> {
         pub depth: usize,
@@ -104,7 +146,9 @@ where
             Some(count) => Ok(count),
             None => {
                 let mut snapshot = self.snapshot.borrow_mut();
-                *snapshot = self.store.load_all_indices()?;
+                if !self.is_pinned() {
+                    *snapshot = self.store.load_all_indices()?;
+                }
                 let mut obj_count = 0;
                 for index in &snapshot.indices {
                     obj_count += index.num_objects() as u64;
@@ -115,6 +159,37 @@ where
         }
     }
 
+    /// Return the path to the pack data file with the intrinsic `pack_id`, as found in the [`Location`] of packed objects,
+    /// or `None` if no pack with this id is known to our current snapshot or if it couldn't be loaded.
+    pub fn pack_path(&self, pack_id: git_pack::data::Id) -> Option<std::path::PathBuf> {
+        let pack_id = PackId::from_intrinsic_pack_id(pack_id);
+        let mut snapshot = self.snapshot.borrow_mut();
+        let marker = snapshot.marker;
+        let possibly_pack = snapshot.indices.iter_mut().find_map(|index| index.pack(pack_id))?;
+        let pack = match possibly_pack {
+            Some(pack) => pack,
+            None => {
+                *possibly_pack = Some(self.store.load_pack(pack_id, marker).ok()??);
+                possibly_pack.as_ref().expect("just put it in")
+            }
+        };
+        Some(pack.path().to_owned())
+    }
+
+    /// Return `true` if the pack data file with the intrinsic `pack_id`, as found in the [`Location`] of packed objects,
+    /// is [marked to be kept][crate::Store::kept_packs()], which means objects in it must not be removed.
+    pub fn is_kept_pack(&self, pack_id: git_pack::data::Id) -> bool {
+        self.pack_path(pack_id)
+            .map_or(false, |path| self.store.kept_pack(path).is_some())
+    }
+
+    /// Return `true` if the pack data file with the intrinsic `pack_id`, as found in the [`Location`] of packed objects,
+    /// is a [promisor pack][crate::Store::promisor_packs()] received from the promisor remote of a partial clone.
+    pub fn is_promisor_pack(&self, pack_id: git_pack::data::Id) -> bool {
+        self.pack_path(pack_id)
+            .map_or(false, |path| self.store.promisor_pack(path).is_some())
+    }
+
     /// Given a prefix `candidate` with an object id and an initial `hex_len`, check if it only matches a single
     /// object within the entire object database and increment its `hex_len` by one until it is unambiguous.
     /// Return `Ok(None)` if no object with that prefix exists.
@@ -177,7 +252,7 @@ where
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker)? {
+            match self.load_one_index(snapshot.marker)? {
                 Some(new_snapshot) => {
                     drop(snapshot);
                     *self.snapshot.borrow_mut() = new_snapshot;
@@ -252,7 +327,7 @@ where
                                 }
                                 None => {
                                     // The pack wasn't available anymore so we are supposed to try another round with a fresh index
-                                    match self.store.load_one_index(self.refresh, snapshot.marker)? {
+                                    match self.load_one_index(snapshot.marker)? {
                                         Some(new_snapshot) => {
                                             *snapshot = new_snapshot;
                                             self.clear_cache();
@@ -268,29 +343,35 @@ where
                                 }
                             },
                         };
-                        let entry = pack.entry(pack_offset);
+                        let entry = pack.try_entry(pack_offset)?;
                         let header_size = entry.header_size();
-                        let res = match pack.decode_entry(
+                        let res = match pack.decode_entry_with_limit(
                             entry,
                             buffer,
                             |id, _out| {
                                 index_file
                                     .pack_offset_by_id(id)
-                                    .map(|pack_offset| git_pack::data::ResolvedBase::InPack(pack.entry(pack_offset)))
+                                    .and_then(|pack_offset| pack.try_entry(pack_offset).ok())
+                                    .map(git_pack::data::ResolvedBase::InPack)
                             },
                             pack_cache,
+                            self.max_decompressed_object_size,
+                            self.verify_delta_result_size,
                         ) {
-                            Ok(r) => Ok((
-                                git_object::Data {
-                                    kind: r.kind,
-                                    data: buffer.as_slice(),
-                                },
-                                Some(git_pack::data::entry::Location {
-                                    pack_id: pack.id,
-                                    pack_offset,
-                                    entry_size: r.compressed_size + header_size,
-                                }),
-                            )),
+                            Ok(r) => {
+                                self.record_pack_decode(pack, &r);
+                                Ok((
+                                    git_object::Data {
+                                        kind: r.kind,
+                                        data: buffer.as_slice(),
+                                    },
+                                    Some(git_pack::data::entry::Location {
+                                        pack_id: pack.id,
+                                        pack_offset,
+                                        entry_size: r.compressed_size + header_size,
+                                    }),
+                                ))
+                            }
                             Err(git_pack::data::decode_entry::Error::DeltaBaseUnresolved(base_id)) => {
                                 // Only with multi-pack indices it's allowed to jump to refer to other packs within this
                                 // multi-pack. Otherwise this would constitute a thin pack which is only allowed in transit.
@@ -352,17 +433,16 @@ where
                                 let pack = possibly_pack
                                     .as_ref()
                                     .expect("pack to still be available like just now");
-                                let entry = pack.entry(pack_offset);
+                                let entry = pack.try_entry(pack_offset)?;
                                 let header_size = entry.header_size();
-                                pack.decode_entry(
+                                pack.decode_entry_with_limit(
                                     entry,
                                     buffer,
                                     |id, out| {
                                         index_file
                                             .pack_offset_by_id(id)
-                                            .map(|pack_offset| {
-                                                git_pack::data::ResolvedBase::InPack(pack.entry(pack_offset))
-                                            })
+                                            .and_then(|pack_offset| pack.try_entry(pack_offset).ok())
+                                            .map(git_pack::data::ResolvedBase::InPack)
                                             .or_else(|| {
                                                 (id == base_id).then(|| {
                                                     out.resize(buf.len(), 0);
@@ -375,8 +455,11 @@ where
                                             })
                                     },
                                     pack_cache,
+                                    self.max_decompressed_object_size,
+                                    self.verify_delta_result_size,
                                 )
                                 .map(move |r| {
+                                    self.record_pack_decode(pack, &r);
                                     (
                                         git_object::Data {
                                             kind: r.kind,
@@ -405,23 +488,36 @@ where
                 // TODO: remove this double-lookup once the borrow checker allows it.
                 if lodb.contains(id) {
                     return lodb
-                        .try_find(id, buffer)
+                        .try_find_with_limit(id, buffer, self.max_decompressed_object_size)
                         .map(|obj| obj.map(|obj| (obj, None)))
                         .map_err(Into::into);
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker)? {
+            match self.load_one_index(snapshot.marker)? {
                 Some(new_snapshot) => {
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
-                None => return Ok(None),
+                None => {
+                    return match self.store.orphaned_index_containing(id) {
+                        Some(index_path) => Err(Error::OrphanedIndex {
+                            id: id.to_owned(),
+                            index_path,
+                        }),
+                        None => match self.missing_object_policy {
+                            MissingObjectPolicy::TreatAsPromised if !self.store.promisor_packs.lock().is_empty() => {
+                                Err(Error::Promised { id: id.to_owned() })
+                            }
+                            MissingObjectPolicy::Error | MissingObjectPolicy::TreatAsPromised => Ok(None),
+                        },
+                    }
+                }
             }
         }
     }
 
-    fn clear_cache(&self) {
+    pub(crate) fn clear_cache(&self) {
         self.packed_object_count.borrow_mut().take();
     }
 }
@@ -452,7 +548,7 @@ where
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker) {
+            match self.load_one_index(snapshot.marker) {
                 Ok(Some(new_snapshot)) => {
                     *snapshot = new_snapshot;
                     self.clear_cache();
@@ -471,10 +567,22 @@ where
     ) -> Result<Option<(git_object::Data<'a>, Option<Location>)>, Self::Error> {
         let id = id.as_ref();
         let mut snapshot = self.snapshot.borrow_mut();
-        self.try_find_cached_inner(id, buffer, pack_cache, &mut snapshot, None)
+        match self.pack_decode_statistics_cell() {
+            Some(statistics) => self.try_find_cached_inner(
+                id,
+                buffer,
+                &mut crate::store::metrics::CountingCache {
+                    inner: pack_cache,
+                    statistics,
+                },
+                &mut snapshot,
+                None,
+            ),
+            None => self.try_find_cached_inner(id, buffer, pack_cache, &mut snapshot, None),
+        }
     }
 
-    fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<Location> {
+    fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Result<Option<Location>, Self::Error> {
         assert!(
             matches!(self.token.as_ref(), Some(handle::Mode::KeepDeletedPacksAvailable)),
             "BUG: handle must be configured to `prevent_pack_unload()` before using this method"
@@ -496,14 +604,14 @@ where
                     {
                         let pack = match possibly_pack {
                             Some(pack) => pack,
-                            None => match self.store.load_pack(pack_id, marker).ok()? {
+                            None => match self.store.load_pack(pack_id, marker)? {
                                 Some(pack) => {
                                     *possibly_pack = Some(pack);
                                     possibly_pack.as_deref().expect("just put it in")
                                 }
                                 None => {
                                     // The pack wasn't available anymore so we are supposed to try another round with a fresh index
-                                    match self.store.load_one_index(self.refresh, snapshot.marker).ok()? {
+                                    match self.load_one_index(snapshot.marker)? {
                                         Some(new_snapshot) => {
                                             *snapshot = new_snapshot;
                                             self.clear_cache();
@@ -513,39 +621,48 @@ where
                                             // nothing new in the index, kind of unexpected to not have a pack but to also
                                             // to have no new index yet. We set the new index before removing any slots, so
                                             // this should be observable.
-                                            return None;
+                                            return Ok(None);
                                         }
                                     }
                                 }
                             },
                         };
-                        let entry = pack.entry(pack_offset);
+                        let entry = pack.try_entry(pack_offset)?;
+                        if let Some(limit) = self
+                            .max_decompressed_object_size
+                            .filter(|limit| entry.decompressed_size > *limit)
+                        {
+                            return Err(Error::Pack(git_pack::data::decode_entry::Error::SizeLimitExceeded {
+                                pack_offset,
+                                size: entry.decompressed_size,
+                                limit,
+                            }));
+                        }
 
                         buf.resize(entry.decompressed_size.try_into().expect("representable size"), 0);
                         assert_eq!(pack.id, pack_id.to_intrinsic_pack_id(), "both ids must always match");
 
-                        let res = pack.decompress_entry(&entry, buf).ok().map(|entry_size_past_header| {
-                            git_pack::data::entry::Location {
-                                pack_id: pack.id,
-                                pack_offset,
-                                entry_size: entry.header_size() + entry_size_past_header,
-                            }
-                        });
+                        let entry_size_past_header = pack.decompress_entry(&entry, buf)?;
+                        let res = git_pack::data::entry::Location {
+                            pack_id: pack.id,
+                            pack_offset,
+                            entry_size: entry.header_size() + entry_size_past_header,
+                        };
 
                         if idx != 0 {
                             snapshot.indices.swap(0, idx);
                         }
-                        return res;
+                        return Ok(Some(res));
                     }
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker).ok()? {
+            match self.load_one_index(snapshot.marker)? {
                 Some(new_snapshot) => {
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
-                None => return None,
+                None => return Ok(None),
             }
         }
     }
@@ -566,7 +683,7 @@ where
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker).ok()? {
+            match self.load_one_index(snapshot.marker).ok()? {
                 Some(new_snapshot) => {
                     drop(snapshot);
                     *self.snapshot.borrow_mut() = new_snapshot;
@@ -601,7 +718,7 @@ where
                         return pack
                             .entry_slice(location.entry_range(location.pack_offset))
                             .map(|data| git_pack::find::Entry {
-                                data: data.to_owned(),
+                                data: data.into_owned(),
                                 version: pack.version(),
                             });
                     }
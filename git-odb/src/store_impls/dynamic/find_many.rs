@@ -0,0 +1,140 @@
+use std::{convert::Infallible, sync::Arc};
+
+use git_features::parallel::{self, reduce::IdentityWithResult, InOrderIter};
+use git_hash::ObjectId;
+use git_object::Kind;
+
+use crate::store;
+
+mod error {
+    use crate::store;
+
+    /// Returned by [`Handle::find_many()`][crate::store::Handle::find_many()] for each object that couldn't be obtained.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Object {id} could not be found")]
+        NotFound { id: git_hash::ObjectId },
+        #[error("Object {id} could not be read")]
+        Find {
+            id: git_hash::ObjectId,
+            source: store::find::Error,
+        },
+    }
+}
+pub use error::Error;
+
+/// Options for [`Handle::find_many()`][crate::store::Handle::find_many()].
+#[derive(Debug, Clone)]
+pub struct Options<F> {
+    /// If `Some`, only use the given amount of threads. Otherwise, the amount of threads to use will be selected based on
+    /// the amount of available logical cores.
+    pub thread_limit: Option<usize>,
+    /// A function to create a pack cache for each thread.
+    pub make_pack_cache: F,
+}
+
+impl Default for Options<fn() -> git_pack::cache::Never> {
+    fn default() -> Self {
+        Options {
+            thread_limit: None,
+            make_pack_cache: || git_pack::cache::Never,
+        }
+    }
+}
+
+/// A decoded object as returned by [`Handle::find_many()`][crate::store::Handle::find_many()], with its id, kind and data.
+pub type Object = (ObjectId, Kind, Vec<u8>);
+
+impl store::Handle<Arc<store::Store>> {
+    /// Find and decode all objects identified by `ids` on multiple threads, and return them as they become available,
+    /// which is typically not in the order of `ids`.
+    ///
+    /// To keep caches effective, objects are grouped by the pack they are stored in and ordered by their offset in it
+    /// before the work is split into chunks. Each thread has its own handle and pack cache as created by
+    /// [`make_pack_cache()`][Options::make_pack_cache]. Objects which can't be found or read are returned as error
+    /// along with their id, without affecting the other objects.
+    ///
+    /// Use [`find_many_in_order()`][Self::find_many_in_order()] to receive the objects in the order of `ids`.
+    pub fn find_many<C, F>(&self, ids: &[ObjectId], options: Options<F>) -> impl Iterator<Item = Result<Object, Error>>
+    where
+        C: git_pack::cache::DecodeEntry,
+        F: Fn() -> C + Send + Clone + 'static,
+    {
+        self.find_many_with_input_index(ids, options)
+            .map(|(_input_index, res)| res)
+    }
+
+    /// Like [`find_many()`][Self::find_many()], but return the objects in the order of `ids`.
+    ///
+    /// Objects that are available early are held back until all objects before them were returned, which costs memory
+    /// and reduces throughput if some objects take much longer than others.
+    pub fn find_many_in_order<C, F>(
+        &self,
+        ids: &[ObjectId],
+        options: Options<F>,
+    ) -> impl Iterator<Item = Result<Object, Error>>
+    where
+        C: git_pack::cache::DecodeEntry,
+        F: Fn() -> C + Send + Clone + 'static,
+    {
+        InOrderIter::from(self.find_many_with_input_index(ids, options).map(Ok::<_, Infallible>))
+            .map(|res| res.unwrap_or_else(|never| match never {}))
+    }
+
+    fn find_many_with_input_index<C, F>(
+        &self,
+        ids: &[ObjectId],
+        Options {
+            thread_limit,
+            make_pack_cache,
+        }: Options<F>,
+    ) -> impl Iterator<Item = (usize, Result<Object, Error>)>
+    where
+        C: git_pack::cache::DecodeEntry,
+        F: Fn() -> C + Send + Clone + 'static,
+    {
+        let mut ids: Vec<_> = ids.iter().copied().enumerate().collect();
+        // Sorting is merely an optimization, so objects whose location is unknown, like loose ones, just go first.
+        if let Ok(mut snapshot) = self.store_ref().load_all_indices() {
+            ids.sort_by_cached_key(|(_, id)| {
+                snapshot.indices.iter_mut().find_map(|index| {
+                    index.lookup(id).map(|outcome| {
+                        (
+                            outcome.object_index.pack_id.to_intrinsic_pack_id(),
+                            outcome.object_index.pack_offset,
+                        )
+                    })
+                })
+            });
+        }
+
+        let (chunk_size, thread_limit, _) =
+            parallel::optimize_chunk_size_and_thread_limit(50, Some(ids.len()), thread_limit, None);
+        let chunks: Vec<Vec<_>> = ids.chunks(chunk_size.max(1)).map(ToOwned::to_owned).collect();
+        let handle = self.clone();
+        parallel::reduce::Stepwise::new(
+            chunks.into_iter(),
+            thread_limit,
+            move |_thread_index| (handle.clone(), make_pack_cache()),
+            |chunk: Vec<(usize, ObjectId)>, (handle, pack_cache)| {
+                Ok::<_, Infallible>(
+                    chunk
+                        .into_iter()
+                        .map(|(input_index, id)| {
+                            let mut buf = Vec::new();
+                            let res = match git_pack::Find::try_find_cached(&*handle, id, &mut buf, pack_cache) {
+                                Ok(Some((obj, _location))) => Ok(obj.kind),
+                                Ok(None) => Err(Error::NotFound { id }),
+                                Err(source) => Err(Error::Find { id, source }),
+                            };
+                            (input_index, res.map(|kind| (id, kind, buf)))
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            },
+            IdentityWithResult::<_, Infallible>::default(),
+        )
+        .flat_map(|chunk| chunk.unwrap_or_else(|never| match never {}))
+    }
+}
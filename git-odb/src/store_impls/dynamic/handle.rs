@@ -16,6 +16,7 @@ pub(crate) mod multi_index {
     pub type File = git_pack::multi_index::File;
 }
 
+#[derive(Clone)]
 pub enum SingleOrMultiIndex {
     Single {
         index: Arc<git_pack::index::File>,
@@ -55,6 +56,7 @@ impl<'a> IntraPackLookup<'a> {
     }
 }
 
+#[derive(Clone)]
 pub struct IndexLookup {
     pub(crate) file: SingleOrMultiIndex,
     /// The index we were found at in the slot map
@@ -104,6 +106,33 @@ pub(crate) mod index_lookup {
             })
         }
 
+        /// Return all packs of this index along with their ids, whether they are loaded or not.
+        pub(crate) fn packs_mut(&mut self) -> Vec<(types::PackId, &'_ mut Option<Arc<git_pack::data::File>>)> {
+            let index = self.id;
+            match &mut self.file {
+                handle::SingleOrMultiIndex::Single { data, .. } => vec![(
+                    types::PackId {
+                        index,
+                        multipack_index: None,
+                    },
+                    data,
+                )],
+                handle::SingleOrMultiIndex::Multi { data, .. } => data
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(pack_index, data)| {
+                        (
+                            types::PackId {
+                                index,
+                                multipack_index: Some(pack_index as git_pack::multi_index::PackIndex),
+                            },
+                            data,
+                        )
+                    })
+                    .collect(),
+            }
+        }
+
         pub(crate) fn pack(&mut self, pack_id: types::PackId) -> Option<&'_ mut Option<Arc<git_pack::data::File>>> {
             (self.id == pack_id.index).then(move || match &mut self.file {
                 handle::SingleOrMultiIndex::Single { data, .. } => data,
@@ -262,8 +291,13 @@ impl super::Store {
             ignore_replacements: false,
             token: Some(token),
             snapshot: RefCell::new(self.collect_snapshot()),
+            pinned: Default::default(),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
+            max_decompressed_object_size: None,
+            verify_delta_result_size: true,
+            missing_object_policy: Default::default(),
             packed_object_count: Default::default(),
+            pack_decode_statistics: None,
         }
     }
 
@@ -278,8 +312,13 @@ impl super::Store {
             ignore_replacements: false,
             token: Some(token),
             snapshot: RefCell::new(self.collect_snapshot()),
+            pinned: Default::default(),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
+            max_decompressed_object_size: None,
+            verify_delta_result_size: true,
+            missing_object_policy: Default::default(),
             packed_object_count: Default::default(),
+            pack_decode_statistics: None,
         }
     }
 
@@ -355,6 +394,10 @@ impl TryFrom<&super::Store> for super::Store {
                 object_hash: Default::default(),
                 use_multi_pack_index: false,
                 current_dir: s.current_dir.clone().into(),
+                strict: s.strict,
+                extra_alternates: s.extra_alternates.clone(),
+                trust_policy: s.trust_policy.clone(),
+                pack_access: s.pack_access,
             },
         )
     }
@@ -367,6 +410,10 @@ impl super::Handle<Rc<super::Store>> {
         let mut cache = store.to_handle_arc();
         cache.refresh = self.refresh;
         cache.max_recursion_depth = self.max_recursion_depth;
+        cache.max_decompressed_object_size = self.max_decompressed_object_size;
+        cache.verify_delta_result_size = self.verify_delta_result_size;
+        cache.missing_object_policy = self.missing_object_policy;
+        cache.collect_pack_decode_statistics(self.pack_decode_statistics.is_some());
         Ok(cache)
     }
 }
@@ -396,8 +443,13 @@ where
                 .into()
             },
             snapshot: RefCell::new(self.store.collect_snapshot()),
+            pinned: Default::default(),
             max_recursion_depth: self.max_recursion_depth,
+            max_decompressed_object_size: self.max_decompressed_object_size,
+            verify_delta_result_size: self.verify_delta_result_size,
+            missing_object_policy: self.missing_object_policy,
             packed_object_count: Default::default(),
+            pack_decode_statistics: self.pack_decode_statistics.as_ref().map(|_| Default::default()),
         }
     }
 }
@@ -0,0 +1,136 @@
+use std::path::{Path, PathBuf};
+
+use crate::Store;
+
+mod error {
+    use std::path::PathBuf;
+
+    /// Returned by [`Store::infer_object_hash()`][crate::Store::infer_object_hash()] and [`Store::at_inferred()`][crate::Store::at_inferred()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the object directory at '{}'", .path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        Index(#[from] git_pack::index::init::Error),
+        #[error("The object directory at '{}' contains neither loose objects nor pack indices to infer the object hash from", .path.display())]
+        Empty { path: PathBuf },
+        #[error("'{}' implies hashes of {} hex characters, but '{}' implies {} hex characters", .first.display(), .first_hex_len, .second.display(), .second_hex_len)]
+        Ambiguous {
+            first: PathBuf,
+            first_hex_len: usize,
+            second: PathBuf,
+            second_hex_len: usize,
+        },
+        #[error("'{}' implies hashes of {hex_len} hex characters, which isn't a supported object hash", .path.display())]
+        Unsupported { path: PathBuf, hex_len: usize },
+        #[error(transparent)]
+        Init(#[from] std::io::Error),
+    }
+}
+pub use error::Error;
+
+/// Inference of the object hash
+impl Store {
+    /// Determine the kind of hash used by the objects in `objects_dir` without any configuration, by looking at the name
+    /// of a loose object and at the size of a pack index, whichever exist.
+    ///
+    /// This is useful when opening an objects directory that isn't part of a repository, as otherwise the object hash
+    /// is configured by the `extensions.objectFormat` configuration of the repository.
+    /// Note that alternates aren't considered, and that it's an error if the object directory holds no object at all, or
+    /// if the loose objects and pack indices disagree.
+    pub fn infer_object_hash(objects_dir: impl AsRef<Path>) -> Result<git_hash::Kind, Error> {
+        let objects_dir = objects_dir.as_ref();
+        let mut evidence = loose_object_hex_len(objects_dir)?
+            .into_iter()
+            .chain(index_hex_len(objects_dir)?);
+        let (path, hex_len) = evidence.next().ok_or_else(|| Error::Empty {
+            path: objects_dir.to_owned(),
+        })?;
+        if let Some((second, second_hex_len)) = evidence.find(|(_, other_hex_len)| *other_hex_len != hex_len) {
+            return Err(Error::Ambiguous {
+                first: path,
+                first_hex_len: hex_len,
+                second,
+                second_hex_len,
+            });
+        }
+        git_hash::Kind::from_hex_len(hex_len)
+            .filter(|kind| kind.len_in_hex() == hex_len)
+            .ok_or(Error::Unsupported { path, hex_len })
+    }
+
+    /// Open the store at `objects_dir` like [`at_opts()`][Store::at_opts()] does with default options, but with the
+    /// object hash [inferred][Store::infer_object_hash()] from the objects it contains.
+    pub fn at_inferred(objects_dir: impl Into<PathBuf>) -> Result<Self, Error> {
+        let objects_dir = objects_dir.into();
+        let object_hash = Self::infer_object_hash(&objects_dir)?;
+        Ok(Self::at_opts(
+            objects_dir,
+            Vec::new(),
+            crate::store::init::Options {
+                object_hash,
+                ..Default::default()
+            },
+        )?)
+    }
+}
+
+fn read_dir(dir: &Path) -> Result<std::fs::ReadDir, Error> {
+    std::fs::read_dir(dir).map_err(|source| Error::Io {
+        path: dir.to_owned(),
+        source,
+    })
+}
+
+/// Return the path of the first loose object we find along with the length of its hex id.
+fn loose_object_hex_len(objects_dir: &Path) -> Result<Option<(PathBuf, usize)>, Error> {
+    let is_hex = |name: &str| !name.is_empty() && name.bytes().all(|b| b.is_ascii_hexdigit());
+    for entry in read_dir(objects_dir)? {
+        let entry = entry.map_err(|source| Error::Io {
+            path: objects_dir.to_owned(),
+            source,
+        })?;
+        let fan_out = entry.file_name();
+        if fan_out.len() != 2 || !fan_out.to_str().map_or(false, is_hex) || !entry.path().is_dir() {
+            continue;
+        }
+        let fan_out_dir = entry.path();
+        for entry in read_dir(&fan_out_dir)? {
+            let entry = entry.map_err(|source| Error::Io {
+                path: fan_out_dir.clone(),
+                source,
+            })?;
+            if let Some(name) = entry.file_name().to_str().filter(|name| is_hex(name)) {
+                return Ok(Some((entry.path(), fan_out.len() + name.len())));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Return the path of the first pack index we find along with the length of the hex ids it was written for.
+fn index_hex_len(objects_dir: &Path) -> Result<Option<(PathBuf, usize)>, Error> {
+    let pack_dir = objects_dir.join("pack");
+    if !pack_dir.is_dir() {
+        return Ok(None);
+    }
+    for entry in read_dir(&pack_dir)? {
+        let path = entry
+            .map_err(|source| Error::Io {
+                path: pack_dir.clone(),
+                source,
+            })?
+            .path();
+        if path.extension().map_or(true, |ext| ext != "idx") {
+            continue;
+        }
+        let hex_len = match git_pack::index::File::at(&path, git_hash::Kind::Sha1) {
+            Ok(index) => index.object_hash().len_in_hex(),
+            Err(git_pack::index::init::Error::ObjectHashMismatch { implied_hash_len, .. }) => implied_hash_len * 2,
+            Err(err) => return Err(err.into()),
+        };
+        return Ok(Some((path, hex_len)));
+    }
+    Ok(None)
+}
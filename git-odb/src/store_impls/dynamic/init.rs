@@ -1,4 +1,8 @@
-use std::{iter::FromIterator, path::PathBuf, sync::Arc};
+use std::{
+    iter::FromIterator,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use arc_swap::ArcSwap;
 
@@ -19,6 +23,25 @@ pub struct Options {
     /// The current directory of the process at the time of instantiation.
     /// If unset, it will be retrieved using `std::env::current_dir()`.
     pub current_dir: Option<std::path::PathBuf>,
+    /// If true, directories and files which exist but can't be read, for instance due to missing permissions, cause an error.
+    /// Otherwise they are skipped and recorded as [warnings][Store::warnings()] to continue with what's accessible.
+    ///
+    /// The same is true for pack indices whose pack is missing, which are otherwise recorded as
+    /// [orphaned indices][Store::orphaned_indices()].
+    pub strict: bool,
+    /// Additional object directories to use as if they were listed first in the `info/alternates` file of the objects
+    /// directory, along with their own alternates. Relative paths are relative to the current directory.
+    ///
+    /// This is typically set from the `GIT_ALTERNATE_OBJECT_DIRECTORIES` environment variable.
+    pub extra_alternates: Vec<PathBuf>,
+    /// Determines how to deal with object directories, including alternates, which we don't fully trust as they are owned
+    /// by another user.
+    pub trust_policy: TrustPolicy,
+    /// How pack data files are accessed once they are loaded, which defaults to memory-mapping them.
+    ///
+    /// Use [positioned reads][git_pack::data::Access::PositionedRead] for object directories on network filesystems, where memory
+    /// maps can be slow, or if packs might be truncated while in use, which would crash the process with memory maps.
+    pub pack_access: git_pack::data::Access,
 }
 
 impl Default for Options {
@@ -28,7 +51,94 @@ impl Default for Options {
             object_hash: Default::default(),
             use_multi_pack_index: true,
             current_dir: None,
+            strict: false,
+            extra_alternates: Vec::new(),
+            trust_policy: Default::default(),
+            pack_access: Default::default(),
+        }
+    }
+}
+
+/// Configures how object directories are used depending on their [trust level][git_sec::Trust], similar to how `git`
+/// refuses to use repositories owned by other users unless they are listed in `safe.directory`.
+///
+/// The classification of each object directory is available via [`Store::object_directories()`].
+#[derive(Clone)]
+pub struct TrustPolicy {
+    /// The trust in the objects directory as determined during repository discovery, typically the trust in the `.git` directory.
+    /// If `None`, it will be derived using `trust_of` just like for all alternates.
+    pub objects_directory: Option<git_sec::Trust>,
+    /// What to do with object directories whose trust is [reduced][git_sec::Trust::Reduced].
+    ///
+    /// With [`Allow`][git_sec::Permission::Allow] they are used like any other, with [`Deny`][git_sec::Permission::Deny]
+    /// they are skipped and recorded as [warnings][Store::warnings()], and with [`Forbid`][git_sec::Permission::Forbid]
+    /// the operation fails. Defaults to `Deny`.
+    pub reduced: git_sec::Permission,
+    /// A function to determine the trust in the object directory at the given path, which defaults to
+    /// [`Trust::from_path_ownership()`][git_sec::Trust::from_path_ownership()].
+    pub trust_of: fn(&Path) -> std::io::Result<git_sec::Trust>,
+}
+
+impl Default for TrustPolicy {
+    fn default() -> Self {
+        TrustPolicy {
+            objects_directory: None,
+            reduced: git_sec::Permission::Deny,
+            trust_of: |path| git_sec::Trust::from_path_ownership(path),
+        }
+    }
+}
+
+impl std::fmt::Debug for TrustPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrustPolicy")
+            .field("objects_directory", &self.objects_directory)
+            .field("reduced", &self.reduced)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TrustPolicy {
+    /// Classify each of `db_paths` whose first entry is the `objects_directory` and return only those that may be used,
+    /// along with the classification of all of them.
+    ///
+    /// Directories whose trust can't be determined are recorded in `warnings` and skipped unless `strict` is set, just like
+    /// directories with reduced trust if these are denied.
+    pub(crate) fn classify(
+        &self,
+        db_paths: Vec<PathBuf>,
+        strict: bool,
+        warnings: &mut Vec<crate::store::Warning>,
+    ) -> Result<(Vec<PathBuf>, Vec<crate::store::ObjectDirectory>), crate::store::load_index::Error> {
+        use crate::store::load_index::Error;
+        let mut usable = Vec::with_capacity(db_paths.len());
+        let mut classified = Vec::with_capacity(db_paths.len());
+        for (idx, path) in db_paths.into_iter().enumerate() {
+            let trust = match self.objects_directory.filter(|_| idx == 0) {
+                Some(trust) => trust,
+                None => match (self.trust_of)(&path) {
+                    Ok(trust) => trust,
+                    Err(err) if !strict => {
+                        warnings.push(crate::store::Warning::from_io_error(path, &err));
+                        continue;
+                    }
+                    Err(source) => return Err(Error::DetermineTrust { path, source }),
+                },
+            };
+            let used = match (trust, self.reduced) {
+                (git_sec::Trust::Full, _) | (_, git_sec::Permission::Allow) => true,
+                (git_sec::Trust::Reduced, git_sec::Permission::Deny) => {
+                    warnings.push(crate::store::Warning::untrusted(path.clone()));
+                    false
+                }
+                (git_sec::Trust::Reduced, git_sec::Permission::Forbid) => return Err(Error::Untrusted { path }),
+            };
+            if used {
+                usable.push(path.clone());
+            }
+            classified.push(crate::store::ObjectDirectory { path, trust, used });
         }
+        Ok((usable, classified))
     }
 }
 
@@ -77,6 +187,10 @@ impl Store {
             object_hash,
             use_multi_pack_index,
             current_dir,
+            strict,
+            extra_alternates,
+            trust_policy,
+            pack_access,
         }: Options,
     ) -> std::io::Result<Self> {
         let objects_dir = objects_dir.into();
@@ -87,15 +201,37 @@ impl Store {
                 format!("'{}' wasn't a directory", objects_dir.display()),
             ));
         }
+        let mut warnings = Vec::new();
+        let mut orphaned_indices = Vec::new();
+        let mut kept_packs = Vec::new();
+        let mut promisor_packs = Vec::new();
         let slot_count = match slots {
             Slots::Given(n) => n as usize,
             Slots::AsNeededByDiskState { multiplier, minimum } => {
-                let mut db_paths = crate::alternate::resolve(&objects_dir, &current_dir)
-                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                let mut db_paths = super::Store::resolve_alternates(
+                    &objects_dir,
+                    &extra_alternates,
+                    &current_dir,
+                    strict,
+                    &mut warnings,
+                )
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
                 db_paths.insert(0, objects_dir.clone());
-                let num_slots = super::Store::collect_indices_and_mtime_sorted_by_size(db_paths, None, None)
-                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
-                    .len();
+                let (db_paths, _) = trust_policy
+                    .classify(db_paths, strict, &mut warnings)
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                let num_slots = super::Store::collect_indices_and_mtime_sorted_by_size(
+                    db_paths,
+                    None,
+                    None,
+                    strict,
+                    &mut warnings,
+                    &mut orphaned_indices,
+                    &mut kept_packs,
+                    &mut promisor_packs,
+                )
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
+                .len();
 
                 ((num_slots as f32 * multiplier) as usize).max(minimum)
             }
@@ -118,9 +254,43 @@ impl Store {
             index: ArcSwap::new(Arc::new(SlotMapIndex::default())),
             use_multi_pack_index,
             object_hash,
+            pack_access,
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
+            num_pinned_handles: Default::default(),
             num_disk_state_consolidation: Default::default(),
+            strict,
+            extra_alternates,
+            trust_policy,
+            object_directories: Default::default(),
+            warnings: parking_lot::Mutex::new(warnings),
+            orphaned_indices: parking_lot::Mutex::new(orphaned_indices),
+            orphaned_index_files: Default::default(),
+            kept_packs: parking_lot::Mutex::new(kept_packs),
+            promisor_packs: parking_lot::Mutex::new(promisor_packs),
+            memory_bundles: Vec::new(),
         })
     }
+
+    /// Register the given pairs of index and pack data files, typically [created from bytes][git_pack::index::File::from_bytes()],
+    /// to be used by all handles of this store just like packs found on disk.
+    ///
+    /// Each bundle uses an additional slot which is added to the slots configured when creating the store.
+    /// Note that each index must belong to the pack it is paired with.
+    pub fn with_memory_bundles(
+        mut self,
+        bundles: Vec<(git_pack::index::File, git_pack::data::File)>,
+    ) -> std::io::Result<Self> {
+        if self.files.len() + bundles.len() > crate::store::types::PackId::max_indices() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Cannot use more than 1^15 slots",
+            ));
+        }
+        self.files
+            .extend(std::iter::repeat_with(MutableIndexAndPack::default).take(bundles.len()));
+        self.memory_bundles
+            .extend(bundles.into_iter().map(|(index, data)| (Arc::new(index), data)));
+        Ok(self)
+    }
 }
@@ -1,13 +1,21 @@
-use std::{ops::Deref, option::Option::None, sync::Arc, vec::IntoIter};
+use std::{
+    cmp::Reverse, collections::BinaryHeap, ops::Deref, option::Option::None, path::Path, sync::Arc, vec::IntoIter,
+};
 
 use git_hash::ObjectId;
 
 use crate::{loose, store::handle, store_impls::dynamic};
 
 enum State {
+    /// Iterating the index at `index` in [`AllObjects::indices`].
     Pack {
-        index_iter: IntoIter<handle::IndexLookup>,
-        index: handle::IndexLookup,
+        index: usize,
+        entry_index: u32,
+        num_objects: u32,
+    },
+    /// Iterating the index at `index` in [`AllObjects::orphaned_indices`].
+    OrphanedIndex {
+        index: usize,
         entry_index: u32,
         num_objects: u32,
     },
@@ -15,53 +23,309 @@ enum State {
         iter: loose::Iter,
         index: usize,
     },
+    Lexicographical {
+        sources: Vec<SortedSource>,
+        /// The smallest object id of each source that isn't depleted yet, along with the index of the source and the position
+        /// of the object id within it.
+        next: BinaryHeap<Reverse<(ObjectId, usize, usize)>>,
+        last: Option<ObjectId>,
+        errors: IntoIter<loose::iter::Error>,
+    },
     Depleted,
 }
 
+/// A source of object ids in ascending order.
+enum SortedSource {
+    Index(handle::IndexLookup),
+    OrphanedIndex(git_pack::index::File),
+    Loose(Vec<ObjectId>),
+}
+
+impl SortedSource {
+    fn num_objects(&self) -> usize {
+        match self {
+            SortedSource::Index(index) => index.num_objects() as usize,
+            SortedSource::OrphanedIndex(index) => index.num_objects() as usize,
+            SortedSource::Loose(ids) => ids.len(),
+        }
+    }
+
+    fn oid_at(&self, pos: usize) -> ObjectId {
+        match self {
+            SortedSource::Index(index) => index.oid_at_index(pos as u32).to_owned(),
+            SortedSource::OrphanedIndex(index) => index.oid_at_index(pos as u32).to_owned(),
+            SortedSource::Loose(ids) => ids[pos],
+        }
+    }
+}
+
+/// Determines how [`AllObjects`] deals with [orphaned indices][dynamic::Store::orphaned_indices()], i.e. indices whose pack is missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanedIndices {
+    /// Don't return the objects listed in orphaned indices, but record the missing pack of each as [warning][AllObjects::warnings()].
+    Skip,
+    /// Return the objects listed in orphaned indices after all packed objects, even though they can't be retrieved.
+    Include,
+}
+
+impl Default for OrphanedIndices {
+    fn default() -> Self {
+        OrphanedIndices::Skip
+    }
+}
+
+/// Determines the order in which [`AllObjects`] returns object ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ordering {
+    /// Return the objects of each pack index in turn, followed by the loose objects of each loose object database.
+    ///
+    /// The order is stable as long as the object database doesn't change, so separate iterations, even in different processes,
+    /// can divide the objects among themselves by position, for instance with `iter().skip(k).take(n)` or [`AllObjects::seek()`]:
+    ///
+    /// * indices are visited in the order of the snapshot they were loaded in, followed by [orphaned indices][OrphanedIndices::Include]
+    ///   if included, in the order of their paths,
+    /// * the entries of each index are returned in index order, which is ascending by object id,
+    /// * loose object databases are visited in order, starting with the one of the store and followed by its alternates,
+    ///   with the objects of each in ascending order of their path, which is ascending by object id.
+    ///
+    /// Skipping packed objects is done arithmetically, so positioning within packs is fast and only loose objects are walked.
+    /// Objects stored in more than one place are returned more than once.
+    PackThenLoose,
+    /// Return the objects of all sources in strictly ascending order, with objects stored in more than one place returned only once.
+    ///
+    /// This merges the already sorted pack indices with the sorted ids of all loose objects, which are collected upfront,
+    /// so memory usage is proportional to the amount of pack indices and loose objects.
+    /// Errors while reading loose objects in [strict][dynamic::Store::is_strict()] mode are returned first.
+    ///
+    /// Skipping objects requires merging all sources up to the new position.
+    Lexicographical,
+}
+
+impl Default for Ordering {
+    fn default() -> Self {
+        Ordering::PackThenLoose
+    }
+}
+
+/// Options for use in [`AllObjects::new_with_options()`] and [`Handle::iter_with_options()`][super::Handle::iter_with_options()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// How to deal with orphaned indices.
+    pub orphaned_indices: OrphanedIndices,
+    /// The order in which to return objects.
+    pub ordering: Ordering,
+}
+
 /// An iterator over all objects of an object store.
+///
+/// Unless the store is [strict][dynamic::Store::is_strict()], loose object directories which can't be read
+/// are skipped and recorded as [warnings][AllObjects::warnings()].
 pub struct AllObjects {
     state: State,
     num_objects: usize,
+    indices: Vec<handle::IndexLookup>,
     loose_dbs: Arc<Vec<loose::Store>>,
+    orphaned_indices: Vec<git_pack::index::File>,
+    strict: bool,
+    warnings: Vec<dynamic::Warning>,
 }
 
 impl AllObjects {
     /// Create a new iterator from a dynamic store, which will be forced to load all indices eagerly and in the current thread.
+    ///
+    /// [Orphaned indices][dynamic::Store::orphaned_indices()] are skipped with a warning.
     pub fn new(db: &dynamic::Store) -> Result<Self, crate::store::load_index::Error> {
-        let snapshot = db.load_all_indices()?;
+        Self::new_with_orphaned_indices(db, OrphanedIndices::default())
+    }
+
+    /// Like [`new()`][AllObjects::new()], but handles [orphaned indices][dynamic::Store::orphaned_indices()] according to `orphaned`.
+    ///
+    /// Orphaned indices which can't be opened are always skipped with a warning.
+    pub fn new_with_orphaned_indices(
+        db: &dynamic::Store,
+        orphaned: OrphanedIndices,
+    ) -> Result<Self, crate::store::load_index::Error> {
+        Self::new_with_options(
+            db,
+            Options {
+                orphaned_indices: orphaned,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Like [`new()`][AllObjects::new()], but configured by `options`.
+    pub fn new_with_options(db: &dynamic::Store, options: Options) -> Result<Self, crate::store::load_index::Error> {
+        Ok(Self::from_snapshot(db, db.load_all_indices()?, options))
+    }
+
+    fn from_snapshot(
+        db: &dynamic::Store,
+        snapshot: dynamic::load_index::Snapshot,
+        Options {
+            orphaned_indices: orphaned,
+            ordering,
+        }: Options,
+    ) -> Self {
+        let mut warnings = Vec::new();
+        let mut orphaned_indices = Vec::new();
+        for path in db.orphaned_indices() {
+            match orphaned {
+                OrphanedIndices::Skip => warnings.push(dynamic::Warning {
+                    path: path.with_extension("pack"),
+                    kind: std::io::ErrorKind::NotFound,
+                    raw_os_error: None,
+                }),
+                OrphanedIndices::Include => match git_pack::index::File::at(&path, db.object_hash()) {
+                    Ok(index) => orphaned_indices.push(index),
+                    Err(_) => warnings.push(dynamic::Warning {
+                        path,
+                        kind: std::io::ErrorKind::InvalidData,
+                        raw_os_error: None,
+                    }),
+                },
+            }
+        }
 
-        let packed_objects = snapshot
+        if ordering == Ordering::Lexicographical {
+            return Self::lexicographical(db, snapshot, orphaned_indices, warnings);
+        }
+
+        let num_objects = snapshot
             .indices
             .iter()
-            .fold(0usize, |dbc, index| dbc.saturating_add(index.num_objects() as usize));
-        let mut index_iter = snapshot.indices.into_iter();
-        let loose_dbs = snapshot.loose_dbs;
-        let state = match index_iter.next() {
-            Some(index) => {
-                let num_objects = index.num_objects();
-                State::Pack {
-                    index_iter,
-                    index,
-                    entry_index: 0,
-                    num_objects,
+            .map(|index| index.num_objects())
+            .chain(orphaned_indices.iter().map(|index| index.num_objects()))
+            .fold(0usize, |dbc, num_objects| dbc.saturating_add(num_objects as usize));
+        let mut all = AllObjects {
+            state: State::Depleted,
+            indices: snapshot.indices,
+            loose_dbs: snapshot.loose_dbs,
+            orphaned_indices,
+            num_objects,
+            strict: db.is_strict(),
+            warnings,
+        };
+        all.state = all.state_at_pack(0);
+        all
+    }
+
+    fn lexicographical(
+        db: &dynamic::Store,
+        snapshot: dynamic::load_index::Snapshot,
+        orphaned_indices: Vec<git_pack::index::File>,
+        mut warnings: Vec<dynamic::Warning>,
+    ) -> Self {
+        let strict = db.is_strict();
+        let mut loose_ids = Vec::new();
+        let mut errors = Vec::new();
+        for loose_db in snapshot.loose_dbs.iter() {
+            for res in loose_db.iter() {
+                match res {
+                    Ok(id) => loose_ids.push(id),
+                    Err(err) if !strict => warnings.push(loose_warning(&err, &loose_db.path)),
+                    Err(err) => errors.push(err),
                 }
             }
-            None => {
-                let index = 0;
-                State::Loose {
-                    iter: loose_dbs.get(index).expect("at least one loose db").iter(),
-                    index,
-                }
+        }
+        loose_ids.sort();
+        loose_ids.dedup();
+
+        let sources: Vec<_> = snapshot
+            .indices
+            .into_iter()
+            .map(SortedSource::Index)
+            .chain(orphaned_indices.into_iter().map(SortedSource::OrphanedIndex))
+            .chain(Some(SortedSource::Loose(loose_ids)))
+            .collect();
+        let next = first_of_each(&sources);
+        AllObjects {
+            num_objects: sources.iter().map(SortedSource::num_objects).max().unwrap_or_default(),
+            state: State::Lexicographical {
+                sources,
+                next,
+                last: None,
+                errors: errors.into_iter(),
+            },
+            indices: Vec::new(),
+            loose_dbs: snapshot.loose_dbs,
+            orphaned_indices: Vec::new(),
+            strict,
+            warnings,
+        }
+    }
+
+    /// Return all problems encountered thus far while iterating loose objects, like directories that couldn't be read,
+    /// along with the packs missing for skipped orphaned indices.
+    pub fn warnings(&self) -> &[dynamic::Warning] {
+        &self.warnings
+    }
+
+    /// Position the iterator so that the next call to [`next()`][Iterator::next()] returns the item at `position`,
+    /// counted from the beginning of the iteration, as if [`nth(position)`][Iterator::nth()] was called on a new iterator.
+    ///
+    /// This is efficient for positions within packs with the [default ordering][Ordering::PackThenLoose], and the iterator
+    /// is depleted if `position` is past its end.
+    /// With [lexicographical ordering][Ordering::Lexicographical], errors that were already returned aren't returned again.
+    pub fn seek(&mut self, position: usize) {
+        match &mut self.state {
+            State::Lexicographical {
+                sources, next, last, ..
+            } => {
+                *next = first_of_each(sources);
+                *last = None;
             }
-        };
-        Ok(AllObjects {
-            state,
-            loose_dbs,
-            num_objects: packed_objects,
-        })
+            _ => self.state = self.state_at_pack(0),
+        }
+        if position != 0 {
+            self.nth(position - 1);
+        }
+    }
+
+    fn state_at_pack(&self, index: usize) -> State {
+        match self.indices.get(index) {
+            Some(lookup) => State::Pack {
+                index,
+                entry_index: 0,
+                num_objects: lookup.num_objects(),
+            },
+            None => self.state_at_orphaned_index(0),
+        }
+    }
+
+    fn state_at_orphaned_index(&self, index: usize) -> State {
+        match self.orphaned_indices.get(index) {
+            Some(orphaned) => State::OrphanedIndex {
+                index,
+                entry_index: 0,
+                num_objects: orphaned.num_objects(),
+            },
+            None => self.state_of_first_loose_db(),
+        }
+    }
+
+    fn state_of_first_loose_db(&self) -> State {
+        let index = 0;
+        match self.loose_dbs.get(index) {
+            Some(loose_db) => State::Loose {
+                iter: loose_db.iter_sorted(),
+                index,
+            },
+            None => State::Depleted,
+        }
     }
 }
 
+/// The smallest object id of each non-empty source in `sources`.
+fn first_of_each(sources: &[SortedSource]) -> BinaryHeap<Reverse<(ObjectId, usize, usize)>> {
+    sources
+        .iter()
+        .enumerate()
+        .filter(|(_, source)| source.num_objects() != 0)
+        .map(|(source_index, source)| Reverse((source.oid_at(0), source_index, 0)))
+        .collect()
+}
+
 impl Iterator for AllObjects {
     type Item = Result<ObjectId, loose::iter::Error>;
 
@@ -69,38 +333,48 @@ impl Iterator for AllObjects {
         match &mut self.state {
             State::Depleted => None,
             State::Pack {
-                index_iter,
                 index,
                 entry_index,
                 num_objects,
             } => {
                 if *entry_index < *num_objects {
-                    let oid = index.oid_at_index(*entry_index).to_owned();
+                    let oid = self.indices[*index].oid_at_index(*entry_index).to_owned();
                     *entry_index += 1;
                     Some(Ok(oid))
                 } else {
-                    match index_iter.next() {
-                        Some(new_index) => {
-                            *index = new_index;
-                            *entry_index = 0;
-                            *num_objects = index.num_objects();
-                        }
-                        None => {
-                            let index = 0;
-                            self.state = State::Loose {
-                                iter: self.loose_dbs.get(index).expect("at least one loose odb").iter(),
-                                index,
-                            }
-                        }
-                    }
+                    let next_index = *index + 1;
+                    self.state = self.state_at_pack(next_index);
+                    self.next()
+                }
+            }
+            State::OrphanedIndex {
+                index,
+                entry_index,
+                num_objects,
+            } => {
+                if *entry_index < *num_objects {
+                    let oid = self.orphaned_indices[*index].oid_at_index(*entry_index).to_owned();
+                    *entry_index += 1;
+                    Some(Ok(oid))
+                } else {
+                    let next_index = *index + 1;
+                    self.state = self.state_at_orphaned_index(next_index);
                     self.next()
                 }
             }
             State::Loose { iter, index } => match iter.next() {
+                Some(Err(err)) if !self.strict => {
+                    // Loose objects are walked again after seeking backwards, which shouldn't repeat warnings.
+                    let warning = loose_warning(&err, &self.loose_dbs[*index].path);
+                    if !self.warnings.contains(&warning) {
+                        self.warnings.push(warning);
+                    }
+                    self.next()
+                }
                 Some(id) => Some(id),
                 None => {
                     *index += 1;
-                    match self.loose_dbs.get(*index).map(|ldb| ldb.iter()) {
+                    match self.loose_dbs.get(*index).map(|ldb| ldb.iter_sorted()) {
                         Some(new_iter) => {
                             *iter = new_iter;
                             self.next()
@@ -112,12 +386,90 @@ impl Iterator for AllObjects {
                     }
                 }
             },
+            State::Lexicographical {
+                sources,
+                next,
+                last,
+                errors,
+            } => {
+                if let Some(err) = errors.next() {
+                    return Some(Err(err));
+                }
+                while let Some(Reverse((oid, source_index, pos))) = next.pop() {
+                    let source = &sources[source_index];
+                    if pos + 1 < source.num_objects() {
+                        next.push(Reverse((source.oid_at(pos + 1), source_index, pos + 1)));
+                    }
+                    if last.replace(oid) != Some(oid) {
+                        return Some(Ok(oid));
+                    }
+                }
+                self.state = State::Depleted;
+                None
+            }
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         (self.num_objects, None)
     }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        loop {
+            match &mut self.state {
+                State::Pack {
+                    index,
+                    entry_index,
+                    num_objects,
+                } => {
+                    let remaining = (*num_objects - *entry_index) as usize;
+                    if n < remaining {
+                        *entry_index += n as u32;
+                        return self.next();
+                    }
+                    n -= remaining;
+                    let next_index = *index + 1;
+                    self.state = self.state_at_pack(next_index);
+                }
+                State::OrphanedIndex {
+                    index,
+                    entry_index,
+                    num_objects,
+                } => {
+                    let remaining = (*num_objects - *entry_index) as usize;
+                    if n < remaining {
+                        *entry_index += n as u32;
+                        return self.next();
+                    }
+                    n -= remaining;
+                    let next_index = *index + 1;
+                    self.state = self.state_at_orphaned_index(next_index);
+                }
+                State::Loose { .. } | State::Lexicographical { .. } => {
+                    for _ in 0..n {
+                        if let Err(err) = self.next()? {
+                            return Some(Err(err));
+                        }
+                    }
+                    return self.next();
+                }
+                State::Depleted => return None,
+            }
+        }
+    }
+}
+
+/// Turn `err` obtained while iterating the loose object database at `db_path` into a warning.
+fn loose_warning(err: &loose::iter::Error, db_path: &Path) -> dynamic::Warning {
+    let path = err.path().map_or_else(|| db_path.to_owned(), ToOwned::to_owned);
+    match err.io_error() {
+        Some(io_err) => dynamic::Warning::from_io_error(path, io_err),
+        None => dynamic::Warning {
+            path,
+            kind: std::io::ErrorKind::Other,
+            raw_os_error: None,
+        },
+    }
 }
 
 impl<S> super::Handle<S>
@@ -126,8 +478,30 @@ where
 {
     /// Return an iterator over all objects, first the ones in all packs of all linked databases (via alternates),
     /// followed by all loose objects.
+    ///
+    /// If our snapshot is [pinned][super::Handle::pin_snapshot()], packed objects are taken from it instead of the current state on disk.
     pub fn iter(&self) -> Result<AllObjects, dynamic::load_index::Error> {
-        AllObjects::new(self.store_ref())
+        self.iter_with_orphaned_indices(OrphanedIndices::default())
+    }
+
+    /// Like [`iter()`][super::Handle::iter()], but handles [orphaned indices][dynamic::Store::orphaned_indices()] according to `orphaned`.
+    pub fn iter_with_orphaned_indices(
+        &self,
+        orphaned: OrphanedIndices,
+    ) -> Result<AllObjects, dynamic::load_index::Error> {
+        self.iter_with_options(Options {
+            orphaned_indices: orphaned,
+            ..Default::default()
+        })
+    }
+
+    /// Like [`iter()`][super::Handle::iter()], but configured by `options`, for instance to obtain all objects
+    /// in [lexicographical order][Ordering::Lexicographical].
+    pub fn iter_with_options(&self, options: Options) -> Result<AllObjects, dynamic::load_index::Error> {
+        match self.pinned_snapshot() {
+            Some(snapshot) => Ok(AllObjects::from_snapshot(self.store_ref(), snapshot, options)),
+            None => AllObjects::new_with_options(self.store_ref(), options),
+        }
     }
 }
 
@@ -0,0 +1,351 @@
+use std::{
+    borrow::Cow,
+    ops::Deref,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
+};
+
+use git_features::progress;
+use git_hash::ObjectId;
+
+use crate::{loose, pack, store::types::IndexAndPacks, store_impls::dynamic};
+
+/// An object as returned by [`AllObjectsWithData`], with its id, its kind and its decompressed data.
+///
+/// The data is currently always owned as it is sent from the thread decoding it.
+pub type Item = (ObjectId, git_object::Kind, Cow<'static, [u8]>);
+
+/// Returned by [`AllObjectsWithData`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open the pack index at '{}'", .path.display())]
+    OpenIndex {
+        source: pack::index::init::Error,
+        path: PathBuf,
+    },
+    #[error("Could not open the multi-pack index at '{}'", .path.display())]
+    OpenMultiIndex {
+        source: pack::multi_index::init::Error,
+        path: PathBuf,
+    },
+    #[error("Could not open the pack at '{}'", .path.display())]
+    OpenPack {
+        source: pack::data::init::Error,
+        path: PathBuf,
+    },
+    #[error("Could not traverse the pack at '{}'", .path.display())]
+    Traverse {
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    LooseIter(#[from] loose::iter::Error),
+    #[error(transparent)]
+    LooseFind(#[from] loose::find::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Options for use in [`Handle::iter_with_data()`][super::Handle::iter_with_data()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `Some`, only return objects of the given kinds. Note that objects of other kinds are still decoded if they are
+    /// delta bases in a pack, but they aren't read at all if they are loose.
+    pub kinds: Option<Vec<git_object::Kind>>,
+    /// The amount of decoded objects that may wait to be returned by the iterator, which caps the memory used by
+    /// objects in flight. Defaults to 64.
+    pub channel_capacity: usize,
+    /// The maximum amount of bytes of decoded objects to keep in memory to resolve deltas against them while traversing
+    /// a pack, see [`Algorithm::Streaming`][pack::index::traverse::Algorithm::Streaming]. Defaults to 64MB.
+    pub pack_cache_memory_cap_in_bytes: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            kinds: None,
+            channel_capacity: 64,
+            pack_cache_memory_cap_in_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl Options {
+    fn accepts(&self, kind: git_object::Kind) -> bool {
+        self.kinds.as_ref().map_or(true, |kinds| kinds.contains(&kind))
+    }
+}
+
+/// An iterator over all objects of an object store along with their data, obtained by decoding the entries of each pack
+/// in the order in which they are stored, followed by all loose objects.
+///
+/// Objects are decoded on a separate thread which sends them through a channel bounded by
+/// [`Options::channel_capacity`], and which stops once the iterator is dropped.
+/// Errors are returned in place of the objects they concern, while iteration continues with the next object or pack.
+/// Objects stored in more than one place are returned more than once.
+pub struct AllObjectsWithData<'a> {
+    receiver: Option<mpsc::Receiver<Result<Item, Error>>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+    stop: Arc<AtomicBool>,
+    should_interrupt: &'a AtomicBool,
+}
+
+impl<'a> AllObjectsWithData<'a> {
+    /// Create a new iterator over all objects in the packs and loose object databases of `db`, configured by `options`,
+    /// which stops with an [interrupted][Error::Interrupted] error once `should_interrupt` is set.
+    pub fn new(
+        db: &dynamic::Store,
+        should_interrupt: &'a AtomicBool,
+        options: Options,
+    ) -> Result<Self, dynamic::load_index::Error> {
+        let sources = Sources::of(db)?;
+        let object_hash = db.object_hash();
+        let (sender, receiver) = mpsc::sync_channel(options.channel_capacity);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::Builder::new()
+            .name("gitoxide.odb.iter_with_data".into())
+            .spawn({
+                let stop = Arc::clone(&stop);
+                move || sources.produce(object_hash, &options, &stop, &sender)
+            })
+            .expect("valid thread name");
+        Ok(AllObjectsWithData {
+            receiver: Some(receiver),
+            thread: Some(thread),
+            stop,
+            should_interrupt,
+        })
+    }
+
+    fn finish(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Dropping the receiver unblocks the thread if it waits for space in the channel.
+        self.receiver.take();
+        if let Some(thread) = self.thread.take() {
+            thread.join().expect("no panic while producing objects");
+        }
+    }
+}
+
+impl Iterator for AllObjectsWithData<'_> {
+    type Item = Result<Item, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let receiver = self.receiver.as_ref()?;
+            if self.should_interrupt.load(Ordering::Relaxed) {
+                self.finish();
+                return Some(Err(Error::Interrupted));
+            }
+            match receiver.recv_timeout(Duration::from_millis(50)) {
+                Ok(item) => return Some(item),
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    self.finish();
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for AllObjectsWithData<'_> {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// The files to obtain objects from, which are opened by the producing thread.
+struct Sources {
+    /// The paths to each pack index along with the path to its pack.
+    packs: Vec<Result<(PathBuf, PathBuf), Error>>,
+    loose_dbs: Arc<Vec<loose::Store>>,
+}
+
+/// The error used by the processor to stop the traversal once the receiver is gone.
+#[derive(Debug, thiserror::Error)]
+#[error("The receiving end of the channel was dropped")]
+struct Disconnected;
+
+impl Sources {
+    fn of(db: &dynamic::Store) -> Result<Self, dynamic::load_index::Error> {
+        let mut index = db.index.load();
+        if !index.is_initialized() {
+            db.consolidate_with_disk_state(true, false /*load one new index*/)?;
+            index = db.index.load();
+        }
+        let mut packs = Vec::new();
+        for slot in index.slot_indices.iter().map(|idx| &db.files[*idx]) {
+            let files = slot.files.load();
+            match &**files {
+                Some(IndexAndPacks::Index(bundle)) => {
+                    packs.push(Ok((bundle.index.path().to_owned(), bundle.data.path().to_owned())))
+                }
+                Some(IndexAndPacks::MultiIndex(bundle)) => {
+                    let path = bundle.multi_index.path();
+                    let multi_index = match bundle.multi_index.loaded() {
+                        Some(multi_index) => Ok(multi_index.deref().index_names().to_owned()),
+                        None => pack::multi_index::File::at(path).map(|file| file.index_names().to_owned()),
+                    };
+                    match multi_index {
+                        Ok(index_names) => {
+                            let dir = path.parent().expect("file in a directory");
+                            packs.extend(index_names.iter().map(|name| {
+                                let index_path = dir.join(name);
+                                Ok((index_path.clone(), index_path.with_extension("pack")))
+                            }))
+                        }
+                        Err(source) => packs.push(Err(Error::OpenMultiIndex {
+                            source,
+                            path: path.to_owned(),
+                        })),
+                    }
+                }
+                None => {}
+            }
+        }
+        Ok(Sources {
+            packs,
+            loose_dbs: Arc::clone(&index.loose_dbs),
+        })
+    }
+
+    fn produce(
+        self,
+        object_hash: git_hash::Kind,
+        options: &Options,
+        stop: &AtomicBool,
+        sender: &mpsc::SyncSender<Result<Item, Error>>,
+    ) {
+        let send = |item| sender.send(item).is_ok();
+        for paths in self.packs {
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            let res = paths.and_then(|(index_path, data_path)| {
+                let index = pack::index::File::at(&index_path, object_hash).map_err(|source| Error::OpenIndex {
+                    source,
+                    path: index_path,
+                })?;
+                let pack = pack::data::File::at(&data_path, object_hash).map_err(|source| Error::OpenPack {
+                    source,
+                    path: data_path.clone(),
+                })?;
+                Ok((index, pack, data_path))
+            });
+            let (index, pack, data_path) = match res {
+                Ok(files) => files,
+                Err(err) => {
+                    if !send(Err(err)) {
+                        return;
+                    }
+                    continue;
+                }
+            };
+            let res = index.traverse(
+                &pack,
+                &mut progress::Discard,
+                stop,
+                || {
+                    |kind, data: &[u8], entry: &pack::index::Entry, _progress: &mut _| {
+                        if !options.accepts(kind) || send(Ok((entry.oid, kind, Cow::Owned(data.to_owned())))) {
+                            Ok(())
+                        } else {
+                            Err(Disconnected)
+                        }
+                    }
+                },
+                pack::index::traverse::Options {
+                    traversal: pack::index::traverse::Algorithm::Streaming {
+                        cache_memory_cap_in_bytes: options.pack_cache_memory_cap_in_bytes,
+                    },
+                    thread_limit: Some(1),
+                    check: pack::index::traverse::SafetyCheck::SkipFileChecksumVerification,
+                    make_pack_lookup_cache: || pack::cache::Never,
+                    max_decompressed_object_size: Some(pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+                    partition: None,
+                    paranoid: false,
+                    strict_entry_boundaries: false,
+                },
+            );
+            match res {
+                Ok(_) => {}
+                Err(
+                    pack::index::traverse::Error::Processor(Disconnected) | pack::index::traverse::Error::Interrupted,
+                ) => return,
+                Err(err) => {
+                    if !send(Err(Error::Traverse {
+                        source: Box::new(err),
+                        path: data_path,
+                    })) {
+                        return;
+                    }
+                }
+            }
+        }
+
+        let mut buf = Vec::new();
+        for loose_db in self.loose_dbs.iter() {
+            for id in loose_db.iter() {
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                let res = id.map_err(Error::from).and_then(|id| {
+                    if options.kinds.is_some() {
+                        match loose_db.try_header(id)? {
+                            Some(header) if options.accepts(header.kind) => {}
+                            _ => return Ok(None),
+                        }
+                    }
+                    Ok(loose_db
+                        .try_find(id, &mut buf)?
+                        .map(|data| (id, data.kind, Cow::Owned(data.data.to_owned()))))
+                });
+                let keep_going = match res {
+                    Ok(Some(item)) => send(Ok(item)),
+                    // The object was removed in the mean time.
+                    Ok(None) => true,
+                    Err(err) => send(Err(err)),
+                };
+                if !keep_going {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Return an iterator over all objects along with their data, configured by `options`, which decodes the objects of each
+    /// pack in the order they are stored in, followed by all loose objects.
+    ///
+    /// This avoids looking up each object by id after obtaining it from [`iter()`][super::Handle::iter()], which is useful
+    /// to scan the content of all objects. Note that the current state on disk is used, even if our snapshot is
+    /// [pinned][super::Handle::pin_snapshot()].
+    pub fn iter_with_data<'a>(
+        &self,
+        should_interrupt: &'a AtomicBool,
+        options: Options,
+    ) -> Result<AllObjectsWithData<'a>, dynamic::load_index::Error> {
+        AllObjectsWithData::new(self.store_ref(), should_interrupt, options)
+    }
+}
+
+impl dynamic::Store {
+    /// Like [`Handle::iter_with_data()`][super::Handle::iter_with_data()], but accessible directly on the store.
+    pub fn iter_with_data<'a>(
+        &self,
+        should_interrupt: &'a AtomicBool,
+        options: Options,
+    ) -> Result<AllObjectsWithData<'a>, dynamic::load_index::Error> {
+        AllObjectsWithData::new(self, should_interrupt, options)
+    }
+}
@@ -12,6 +12,7 @@ use std::{
 
 use crate::store::{handle, types, RefreshMode};
 
+#[derive(Clone)]
 pub(crate) struct Snapshot {
     /// Indices ready for object lookup or contains checks, ordered usually by modification data, recent ones first.
     pub(crate) indices: Vec<handle::IndexLookup>,
@@ -30,10 +31,26 @@ mod error {
     pub enum Error {
         #[error("The objects directory at '{0}' is not an accessible directory")]
         Inaccessible(PathBuf),
+        #[error("Could not read the directory at '{}'", .path.display())]
+        ReadDirectory { path: PathBuf, source: std::io::Error },
         #[error(transparent)]
         Io(#[from] std::io::Error),
         #[error(transparent)]
         Alternate(#[from] crate::alternate::Error),
+        #[error("The pack for the index at '{}' is missing", .path.display())]
+        OrphanedIndex { path: PathBuf },
+        #[error("Could not read the keep file at '{}'", .path.display())]
+        ReadKeepFile { path: PathBuf, source: std::io::Error },
+        #[error("Could not read the promisor file at '{}'", .path.display())]
+        ReadPromisorFile { path: PathBuf, source: std::io::Error },
+        #[error("Could not load the index at '{}'", .path.display())]
+        LoadIndex { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        MultiIndex(#[from] git_pack::multi_index::init::Error),
+        #[error("Could not determine the trust in the object directory at '{}'", .path.display())]
+        DetermineTrust { path: PathBuf, source: std::io::Error },
+        #[error("The object directory at '{}' is not owned by the current user and thus not trusted", .path.display())]
+        Untrusted { path: PathBuf },
         #[error("The slotmap turned out to be too small with {} entries, would need {} more", .current, .needed)]
         InsufficientSlots { current: usize, needed: usize },
         /// The problem here is that some logic assumes that more recent generations are higher than previous ones. If we would overflow,
@@ -45,6 +62,29 @@ mod error {
         )]
         GenerationOverflow,
     }
+
+    impl Error {
+        /// Return the kind of this error.
+        pub fn kind(&self) -> crate::find::ErrorKind {
+            use crate::find::ErrorKind;
+            match self {
+                Error::OrphanedIndex { .. } | Error::MultiIndex(_) => ErrorKind::Corrupt,
+                Error::Alternate(crate::alternate::Error::Parse(_) | crate::alternate::Error::Cycle(_)) => {
+                    ErrorKind::Corrupt
+                }
+                Error::Alternate(crate::alternate::Error::Io(_) | crate::alternate::Error::Realpath(_))
+                | Error::Inaccessible(_)
+                | Error::ReadDirectory { .. }
+                | Error::Io(_)
+                | Error::ReadKeepFile { .. }
+                | Error::ReadPromisorFile { .. }
+                | Error::LoadIndex { .. }
+                | Error::DetermineTrust { .. } => ErrorKind::Io,
+                Error::InsufficientSlots { .. } | Error::GenerationOverflow => ErrorKind::LimitExceeded,
+                Error::Untrusted { .. } => ErrorKind::Other,
+            }
+        }
+    }
 }
 
 pub use error::Error;
@@ -79,7 +119,7 @@ impl super::Store {
         } else {
             // always compare to the latest state
             // Nothing changed in the mean time, try to load another index…
-            if self.load_next_index(index) {
+            if self.load_next_index(index)? {
                 Ok(Some(self.collect_snapshot()))
             } else {
                 // …and if that didn't yield anything new consider refreshing our disk state.
@@ -96,7 +136,10 @@ impl super::Store {
     /// load a new index (if not yet loaded), and return true if one was indeed loaded (leading to a state_id() change) of the current index.
     /// Note that interacting with the slot-map is inherently racy and we have to deal with it, being conservative in what we even try to load
     /// as our index might already be out-of-date as we try to use it to learn what's next.
-    fn load_next_index(&self, mut index: arc_swap::Guard<Arc<SlotMapIndex>>) -> bool {
+    ///
+    /// Indices which can't be loaded are recorded as warnings, or cause an error if we are strict. Indices which disappeared
+    /// are skipped silently as the disk state changed.
+    fn load_next_index(&self, mut index: arc_swap::Guard<Arc<SlotMapIndex>>) -> Result<bool, Error> {
         'retry_with_changed_index: loop {
             let previous_state_id = index.state_id();
             'retry_with_next_slot_index: loop {
@@ -126,8 +169,15 @@ impl super::Store {
                                     slot.files.store(bundle);
                                     break 'retry_with_next_slot_index;
                                 }
-                                Err(_) => {
+                                Err(err) => {
+                                    let path = files.index_path().to_owned();
                                     slot.files.store(bundle);
+                                    if err.kind() != std::io::ErrorKind::NotFound {
+                                        if self.strict {
+                                            return Err(Error::LoadIndex { path, source: err });
+                                        }
+                                        self.warnings.lock().push(types::Warning::from_io_error(path, &err));
+                                    }
                                     continue 'retry_with_next_slot_index;
                                 }
                             }
@@ -150,7 +200,7 @@ impl super::Store {
                 let potentially_new_index = self.index.load();
                 if Arc::as_ptr(&potentially_new_index) == Arc::as_ptr(&index) {
                     // There isn't a new index with which to retry the whole ordeal, so nothing could be done here.
-                    return false;
+                    return Ok(false);
                 } else {
                     // the index changed, worth trying again
                     index = potentially_new_index;
@@ -159,7 +209,7 @@ impl super::Store {
             } else {
                 // something inarguably changed, probably an index was loaded. 'probably' because we consider failed loads valid attempts,
                 // even they don't change anything for the caller which would then do a round for nothing.
-                return true;
+                return Ok(true);
             }
         }
     }
@@ -197,9 +247,17 @@ impl super::Store {
         }
         self.num_disk_state_consolidation.fetch_add(1, Ordering::Relaxed);
 
+        let mut warnings = Vec::new();
         let db_paths: Vec<_> = std::iter::once(objects_directory.to_owned())
-            .chain(crate::alternate::resolve(objects_directory, &self.current_dir)?)
+            .chain(Self::resolve_alternates(
+                objects_directory,
+                &self.extra_alternates,
+                &self.current_dir,
+                self.strict,
+                &mut warnings,
+            )?)
             .collect();
+        let (db_paths, object_directories) = self.trust_policy.classify(db_paths, self.strict, &mut warnings)?;
 
         // turn db paths into loose object databases. Reuse what's there, but only if it is in the right order.
         let loose_dbs = if was_uninitialized
@@ -219,11 +277,35 @@ impl super::Store {
             Arc::clone(&index.loose_dbs)
         };
 
-        let indices_by_modification_time = Self::collect_indices_and_mtime_sorted_by_size(
+        let mut orphaned_indices = Vec::new();
+        let mut kept_packs = Vec::new();
+        let mut promisor_packs = Vec::new();
+        let mut indices_by_modification_time = Self::collect_indices_and_mtime_sorted_by_size(
             db_paths,
             index.slot_indices.len().into(),
             self.use_multi_pack_index.then(|| self.object_hash),
+            self.strict,
+            &mut warnings,
+            &mut orphaned_indices,
+            &mut kept_packs,
+            &mut promisor_packs,
         )?;
+        *self.warnings.lock() = warnings;
+        *self.orphaned_indices.lock() = orphaned_indices;
+        self.orphaned_index_files.lock().take();
+        *self.kept_packs.lock() = kept_packs;
+        *self.promisor_packs.lock() = promisor_packs;
+        *self.object_directories.lock() = object_directories;
+        indices_by_modification_time.extend(self.memory_bundles.iter().map(|(index, data)| {
+            (
+                Either::InMemory {
+                    index: Arc::clone(index),
+                    data: data.clone(),
+                },
+                SystemTime::UNIX_EPOCH,
+                data.data_len() as u64,
+            )
+        }));
         let mut idx_by_index_path: BTreeMap<_, _> = index
             .slot_indices
             .iter()
@@ -302,6 +384,7 @@ impl super::Store {
                         match Self::try_set_index_slot(
                             &write,
                             slot,
+                            slot_index,
                             index_info,
                             mtime,
                             index.generation,
@@ -323,6 +406,7 @@ impl super::Store {
                         match Self::try_set_index_slot(
                             &write,
                             slot,
+                            slot_index,
                             index_info,
                             mtime,
                             index.generation,
@@ -405,16 +489,93 @@ impl super::Store {
             None
         } else {
             if load_new_index {
-                self.load_next_index(new_index);
+                self.load_next_index(new_index)?;
             }
             Some(self.collect_snapshot())
         })
     }
 
+    /// Resolve all alternates of `objects_directory`, recording unreadable alternates files in `warnings` unless `strict` is set.
+    ///
+    /// `extra_alternates` come first, each followed by its own alternates, and object directories are only listed once.
+    /// Extra alternates which aren't directories are recorded in `warnings` as well, or cause an error if `strict` is set.
+    pub(crate) fn resolve_alternates(
+        objects_directory: &Path,
+        extra_alternates: &[PathBuf],
+        current_dir: &Path,
+        strict: bool,
+        warnings: &mut Vec<types::Warning>,
+    ) -> Result<Vec<PathBuf>, crate::alternate::Error> {
+        let mut out = Vec::new();
+        for dir in extra_alternates.iter().map(|dir| current_dir.join(dir)) {
+            if !dir.is_dir() {
+                let err = std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Alternate object directory '{}' does not exist", dir.display()),
+                );
+                if strict {
+                    return Err(err.into());
+                }
+                warnings.push(types::Warning::from_io_error(dir, &err));
+                continue;
+            }
+            let alternates = Self::resolve_alternates_of(&dir, current_dir, strict, warnings)?;
+            out.push(dir);
+            out.extend(alternates);
+        }
+        out.extend(Self::resolve_alternates_of(
+            objects_directory,
+            current_dir,
+            strict,
+            warnings,
+        )?);
+
+        let mut seen = vec![objects_directory.to_owned()];
+        out.retain(|dir| {
+            if seen.contains(dir) {
+                false
+            } else {
+                seen.push(dir.clone());
+                true
+            }
+        });
+        Ok(out)
+    }
+
+    fn resolve_alternates_of(
+        objects_directory: &Path,
+        current_dir: &Path,
+        strict: bool,
+        warnings: &mut Vec<types::Warning>,
+    ) -> Result<Vec<PathBuf>, crate::alternate::Error> {
+        if strict {
+            crate::alternate::resolve(objects_directory, current_dir)
+        } else {
+            let mut unreadable = Vec::new();
+            let res = crate::alternate::resolve_skipping_unreadable(objects_directory, current_dir, &mut unreadable);
+            warnings.extend(
+                unreadable
+                    .into_iter()
+                    .map(|(path, err)| types::Warning::from_io_error(path, &err)),
+            );
+            res
+        }
+    }
+
+    /// Unless `strict` is set, pack directories which can't be read are recorded in `warnings` and skipped, and indices
+    /// without a pack are recorded in `orphaned_indices` instead of failing.
+    /// Packs with a `.keep` file are recorded in `kept_packs`, along with the reason stated in it, and packs with
+    /// a `.promisor` file are recorded in `promisor_packs` along with the references listed in it.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn collect_indices_and_mtime_sorted_by_size(
         db_paths: Vec<PathBuf>,
         initial_capacity: Option<usize>,
         multi_pack_index_object_hash: Option<git_hash::Kind>,
+        strict: bool,
+        warnings: &mut Vec<types::Warning>,
+        orphaned_indices: &mut Vec<PathBuf>,
+        kept_packs: &mut Vec<types::KeptPack>,
+        promisor_packs: &mut Vec<types::PromisorPack>,
     ) -> Result<Vec<(Either, SystemTime, u64)>, Error> {
         let mut indices_by_modification_time = Vec::with_capacity(initial_capacity.unwrap_or_default());
         for db_path in db_paths {
@@ -422,33 +583,97 @@ impl super::Store {
             let entries = match std::fs::read_dir(&packs) {
                 Ok(e) => e,
                 Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
-                Err(err) => return Err(err.into()),
+                Err(err) if !strict => {
+                    warnings.push(types::Warning::from_io_error(packs, &err));
+                    continue;
+                }
+                Err(source) => return Err(Error::ReadDirectory { path: packs, source }),
             };
-            let indices = entries
+            let mut indices = Vec::new();
+            for (p, md) in entries
                 .filter_map(Result::ok)
                 .filter_map(|e| e.metadata().map(|md| (e.path(), md)).ok())
                 .filter(|(_, md)| md.file_type().is_file())
-                .filter(|(p, _)| {
-                    let ext = p.extension();
-                    (ext == Some(OsStr::new("idx")) && p.with_extension("pack").is_file())
-                        || (multi_pack_index_object_hash.is_some() && ext.is_none() && is_multipack_index(p))
-                })
-                .map(|(p, md)| md.modified().map_err(Error::from).map(|mtime| (p, mtime, md.len())))
-                .collect::<Result<Vec<_>, _>>()?;
-
-            let multi_index_info = multi_pack_index_object_hash.and_then(|hash| {
-                indices.iter().find_map(|(p, a, b)| {
-                    is_multipack_index(p)
-                        .then(|| {
-                            // we always open the multi-pack here to be able to remove indices
-                            git_pack::multi_index::File::at(p)
-                                .ok()
-                                .filter(|midx| midx.object_hash() == hash)
-                                .map(|midx| (midx, *a, *b))
-                        })
-                        .flatten()
-                })
-            });
+            {
+                let ext = p.extension();
+                if ext == Some(OsStr::new("idx")) {
+                    if !p.with_extension("pack").is_file() {
+                        if strict {
+                            return Err(Error::OrphanedIndex { path: p });
+                        }
+                        orphaned_indices.push(p);
+                        continue;
+                    }
+                    let keep_path = p.with_extension("keep");
+                    if keep_path.is_file() {
+                        let reason = match std::fs::read(&keep_path) {
+                            Ok(content) => types::KeptPack::reason_from_keep_file(&content),
+                            Err(err) if !strict => {
+                                warnings.push(types::Warning::from_io_error(&keep_path, &err));
+                                None
+                            }
+                            Err(source) => {
+                                return Err(Error::ReadKeepFile {
+                                    path: keep_path,
+                                    source,
+                                })
+                            }
+                        };
+                        kept_packs.push(types::KeptPack {
+                            path: p.with_extension("pack"),
+                            reason,
+                        });
+                    }
+                    let promisor_path = p.with_extension("promisor");
+                    if promisor_path.is_file() {
+                        let refs = match std::fs::read(&promisor_path) {
+                            Ok(content) => types::PromisorPack::refs_from_promisor_file(&content),
+                            Err(err) if !strict => {
+                                warnings.push(types::Warning::from_io_error(&promisor_path, &err));
+                                Vec::new()
+                            }
+                            Err(source) => {
+                                return Err(Error::ReadPromisorFile {
+                                    path: promisor_path,
+                                    source,
+                                })
+                            }
+                        };
+                        promisor_packs.push(types::PromisorPack {
+                            path: p.with_extension("pack"),
+                            refs,
+                        });
+                    }
+                } else if !(multi_pack_index_object_hash.is_some() && ext.is_none() && is_multipack_index(&p)) {
+                    continue;
+                }
+                indices.push((p, md.modified()?, md.len()));
+            }
+
+            let mut multi_index_info = None;
+            if let Some(hash) = multi_pack_index_object_hash {
+                for (p, a, b) in indices.iter().filter(|(p, _, _)| is_multipack_index(p)) {
+                    // we always open the multi-pack here to be able to remove indices
+                    match git_pack::multi_index::File::at(p) {
+                        Ok(midx) if midx.object_hash() == hash => {
+                            multi_index_info = Some((midx, *a, *b));
+                            break;
+                        }
+                        Ok(_) => {}
+                        Err(err) if strict => return Err(err.into()),
+                        Err(err) => warnings.push(match err {
+                            git_pack::multi_index::init::Error::Io { source, path } => {
+                                types::Warning::from_io_error(path, &source)
+                            }
+                            _ => types::Warning {
+                                path: p.to_owned(),
+                                kind: std::io::ErrorKind::InvalidData,
+                                raw_os_error: None,
+                            },
+                        }),
+                    }
+                }
+            }
             if let Some((multi_index, mtime, flen)) = multi_index_info {
                 let index_names_in_multi_index: Vec<_> =
                     multi_index.index_names().iter().map(|p| p.as_path()).collect();
@@ -475,6 +700,9 @@ impl super::Store {
         // the chance to hit an object should be higher. We leave it to the handle to sort by LRU.
         // Git itself doesn't change the order which may safe time, but we want it to be stable which also helps some tests.
         indices_by_modification_time.sort_by(|l, r| l.2.cmp(&r.2).reverse());
+        orphaned_indices.sort();
+        kept_packs.sort_by(|a, b| a.path.cmp(&b.path));
+        promisor_packs.sort_by(|a, b| a.path.cmp(&b.path));
         Ok(indices_by_modification_time)
     }
 
@@ -483,6 +711,7 @@ impl super::Store {
     fn try_set_index_slot(
         lock: &parking_lot::MutexGuard<'_, ()>,
         dest_slot: &MutableIndexAndPack,
+        dest_slot_index: usize,
         index_info: Either,
         mtime: SystemTime,
         current_generation: Generation,
@@ -516,13 +745,14 @@ impl super::Store {
                 (true, current_generation)
             }
         };
-        Self::set_slot_to_index(lock, dest_slot, index_info, mtime, generation);
+        Self::set_slot_to_index(lock, dest_slot, dest_slot_index, index_info, mtime, generation);
         Ok(dest_slot_was_empty)
     }
 
     fn set_slot_to_index(
         _lock: &parking_lot::MutexGuard<'_, ()>,
         slot: &MutableIndexAndPack,
+        slot_index: usize,
         index_info: Either,
         mtime: SystemTime,
         generation: Generation,
@@ -535,7 +765,7 @@ impl super::Store {
         // If not, they would fail unable to load a pack or index they need, but that's preferred over returning wrong objects.
         // Safety: can't race as we hold the lock, have to set the generation beforehand to help avoid others to observe the value.
         slot.generation.store(generation, Ordering::SeqCst);
-        *files_mut = Some(index_info.into_index_and_packs(mtime));
+        *files_mut = Some(index_info.into_index_and_packs(mtime, slot_index));
         slot.files.store(files);
     }
 
@@ -655,6 +885,10 @@ impl<'a> Drop for IncOnDrop<'a> {
 pub(crate) enum Either {
     IndexPath(PathBuf),
     MultiIndexFile(Arc<git_pack::multi_index::File>),
+    InMemory {
+        index: Arc<git_pack::index::File>,
+        data: git_pack::data::File,
+    },
 }
 
 impl Either {
@@ -662,13 +896,15 @@ impl Either {
         match self {
             Either::IndexPath(p) => p,
             Either::MultiIndexFile(f) => f.path(),
+            Either::InMemory { index, .. } => index.path(),
         }
     }
 
-    fn into_index_and_packs(self, mtime: SystemTime) -> IndexAndPacks {
+    fn into_index_and_packs(self, mtime: SystemTime, slot_index: usize) -> IndexAndPacks {
         match self {
             Either::IndexPath(path) => IndexAndPacks::new_single(path, mtime),
             Either::MultiIndexFile(file) => IndexAndPacks::new_multi_from_open_file(file, mtime),
+            Either::InMemory { index, data } => IndexAndPacks::new_single_in_memory(index, data, mtime, slot_index),
         }
     }
 
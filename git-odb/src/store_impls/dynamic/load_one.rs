@@ -24,15 +24,16 @@ impl super::Store {
             path: &Path,
             id: types::PackId,
             object_hash: git_hash::Kind,
+            access: git_pack::data::Access,
         ) -> std::io::Result<Arc<git_pack::data::File>> {
-            git_pack::data::File::at(path, object_hash)
+            git_pack::data::File::at_with_access(path, object_hash, access)
                 .map(|mut pack| {
                     pack.id = id.to_intrinsic_pack_id();
                     Arc::new(pack)
                 })
                 .map_err(|err| match err {
-                    git_pack::data::header::decode::Error::Io { source, .. } => source,
-                    other => std::io::Error::new(std::io::ErrorKind::Other, other),
+                    git_pack::data::init::Error::Io { source, .. } => source,
+                    other => std::io::Error::new(std::io::ErrorKind::InvalidData, other),
                 })
         }
 
@@ -56,9 +57,11 @@ impl super::Store {
                                 let mut files = slot.files.load_full();
                                 let files_mut = Arc::make_mut(&mut files);
                                 let pack = match files_mut {
-                                    Some(types::IndexAndPacks::Index(bundle)) => bundle
-                                        .data
-                                        .load_with_recovery(|path| load_pack(path, id, self.object_hash))?,
+                                    Some(types::IndexAndPacks::Index(bundle)) => {
+                                        bundle.data.load_with_recovery(|path| {
+                                            load_pack(path, id, self.object_hash, self.pack_access)
+                                        })?
+                                    }
                                     Some(types::IndexAndPacks::MultiIndex(_)) => {
                                         // something changed between us getting the lock, trigger a complete index refresh.
                                         None
@@ -100,7 +103,9 @@ impl super::Store {
                                             .data
                                             .get_mut(pack_index as usize)
                                             .expect("BUG: must set this handle to be stable")
-                                            .load_with_recovery(|path| load_pack(path, id, self.object_hash))?,
+                                            .load_with_recovery(|path| {
+                                                load_pack(path, id, self.object_hash, self.pack_access)
+                                            })?,
                                         None => {
                                             unreachable!("BUG: must set this handle to be stable to avoid slots to be cleared/changed")
                                         }
@@ -1,4 +1,4 @@
-use std::sync::atomic::Ordering;
+use std::{collections::BTreeMap, ops::Deref, sync::atomic::Ordering};
 
 use crate::store::{types, types::IndexAndPacks};
 
@@ -66,6 +66,7 @@ impl super::Store {
         types::Metrics {
             num_handles: self.num_handles_unstable.load(Ordering::Relaxed)
                 + self.num_handles_stable.load(Ordering::Relaxed),
+            num_pinned_handles: self.num_pinned_handles.load(Ordering::Relaxed),
             num_refreshes: self.num_disk_state_consolidation.load(Ordering::Relaxed),
             open_reachable_packs: open_packs,
             open_reachable_indices: open_indices,
@@ -73,8 +74,107 @@ impl super::Store {
             known_packs,
             unused_slots,
             loose_dbs: index.loose_dbs.len(),
+            extra_alternates: self.extra_alternates.len(),
             unreachable_indices,
             unreachable_packs,
+            orphaned_indices: self.orphaned_indices.lock().len(),
+            kept_packs: self.kept_packs.lock().len(),
+            promisor_packs: self.promisor_packs.lock().len(),
+            untrusted_object_directories: self
+                .object_directories
+                .lock()
+                .iter()
+                .filter(|dir| dir.trust == git_sec::Trust::Reduced)
+                .count(),
         }
     }
 }
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Start collecting per-pack decode statistics if `toggle` is `true`, or stop collecting them and discard all statistics
+    /// gathered so far.
+    ///
+    /// Collection is off by default as it costs a little on each object lookup. Clones of this handle collect statistics
+    /// if this one does, starting from zero.
+    pub fn collect_pack_decode_statistics(&mut self, toggle: bool) {
+        self.pack_decode_statistics = toggle.then(Default::default);
+    }
+
+    /// Return the decode statistics for each pack which objects were decoded from, sorted by path, or `None` if
+    /// they aren't [collected][Self::collect_pack_decode_statistics()].
+    ///
+    /// Statistics of packs with the same path are merged, which happens if the pack was assigned a new id after a refresh.
+    pub fn pack_decode_statistics(&self) -> Option<Vec<types::PackDecodeStatistics>> {
+        let statistics = self.pack_decode_statistics.as_ref()?.borrow();
+        let mut by_path = BTreeMap::<_, types::PackDecodeStatistics>::new();
+        for stats in statistics.values() {
+            let merged = by_path
+                .entry(&stats.path)
+                .or_insert_with(|| types::PackDecodeStatistics {
+                    path: stats.path.clone(),
+                    ..Default::default()
+                });
+            merged.objects += stats.objects;
+            merged.deltas += stats.deltas;
+            merged.cache_hits += stats.cache_hits;
+            merged.cache_misses += stats.cache_misses;
+            merged.inflated_bytes += stats.inflated_bytes;
+        }
+        Some(by_path.into_values().collect())
+    }
+
+    /// Record that an object was decoded from `pack` as described by `outcome`, if statistics are collected.
+    pub(crate) fn record_pack_decode(
+        &self,
+        pack: &git_pack::data::File,
+        outcome: &git_pack::data::decode_entry::Outcome,
+    ) {
+        if let Some(statistics) = &self.pack_decode_statistics {
+            let mut statistics = statistics.borrow_mut();
+            let stats = statistics.entry(pack.id).or_default();
+            if stats.path.as_os_str().is_empty() {
+                stats.path = pack.path().to_owned();
+            }
+            stats.objects += 1;
+            stats.deltas += outcome.num_deltas as u64;
+            stats.inflated_bytes += outcome.decompressed_size;
+        }
+    }
+
+    /// Return the pack statistics if they are collected, for use with [`CountingCache`].
+    pub(crate) fn pack_decode_statistics_cell(
+        &self,
+    ) -> Option<&std::cell::RefCell<BTreeMap<git_pack::data::Id, types::PackDecodeStatistics>>> {
+        self.pack_decode_statistics.as_ref()
+    }
+}
+
+/// A pack cache which counts hits and misses per pack while delegating to the actual cache.
+pub(crate) struct CountingCache<'a, C> {
+    pub inner: &'a mut C,
+    pub statistics: &'a std::cell::RefCell<BTreeMap<git_pack::data::Id, types::PackDecodeStatistics>>,
+}
+
+impl<C> git_pack::cache::DecodeEntry for CountingCache<'_, C>
+where
+    C: git_pack::cache::DecodeEntry,
+{
+    fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
+        self.inner.put(pack_id, offset, data, kind, compressed_size)
+    }
+
+    fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)> {
+        let res = self.inner.get(pack_id, offset, out);
+        let mut statistics = self.statistics.borrow_mut();
+        let stats = statistics.entry(pack_id).or_default();
+        if res.is_some() {
+            stats.cache_hits += 1;
+        } else {
+            stats.cache_misses += 1;
+        }
+        res
+    }
+}
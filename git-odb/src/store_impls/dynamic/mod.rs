@@ -1,5 +1,9 @@
 //! The standard object store which should fit all needs.
-use std::{cell::RefCell, ops::Deref};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    ops::Deref,
+};
 
 use crate::Store;
 
@@ -22,9 +26,28 @@ where
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
 
+    /// If `Some`, fail to find objects which would decompress to more than the given amount of bytes, or which need deltas
+    /// of that size to be resolved.
+    ///
+    /// This protects against objects crafted to exhaust memory, and is `None` by default.
+    pub max_decompressed_object_size: Option<u64>,
+
+    /// If true, fail to find objects if one of the deltas needed to resolve them produces less than the result size declared
+    /// in its header, which indicates corruption that would otherwise only be noticed by hashing the object, if at all.
+    ///
+    /// This is a cheap check and `true` by default.
+    pub verify_delta_result_size: bool,
+
+    /// Determines what happens when an object can't be found, which matters in partial clones.
+    pub missing_object_policy: MissingObjectPolicy,
+
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
+    /// The amount of currently alive [pins][pin::Guard] of our snapshot.
+    pinned: Cell<usize>,
     packed_object_count: RefCell<Option<u64>>,
+    /// Per-pack decode statistics keyed by pack id, or `None` if they aren't collected.
+    pack_decode_statistics: Option<RefCell<BTreeMap<git_pack::data::Id, types::PackDecodeStatistics>>>,
 }
 
 /// Decide what happens when all indices are loaded.
@@ -45,6 +68,24 @@ impl Default for RefreshMode {
     }
 }
 
+/// Decide how objects are reported that can't be found in any of our object databases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingObjectPolicy {
+    /// Report missing objects as not found, which is what most operations rely on.
+    Error,
+    /// If there is at least one [promisor pack][crate::Store::promisor_packs()], fail to find missing objects with
+    /// [`find::Error::Promised`] as they are expected to be provided by the promisor remote of the partial clone.
+    ///
+    /// Without promisor packs, missing objects are not found as usual.
+    TreatAsPromised,
+}
+
+impl Default for MissingObjectPolicy {
+    fn default() -> Self {
+        MissingObjectPolicy::Error
+    }
+}
+
 impl RefreshMode {
     /// Set this refresh mode to never refresh.
     pub fn never(&mut self) {
@@ -58,14 +99,35 @@ pub mod find;
 ///
 pub mod iter;
 
+///
+pub mod iter_with_data;
+
+///
+pub mod pin;
+
 ///
 pub mod write;
 
+///
+pub mod export;
+
+///
+pub mod find_many;
+
+///
+pub mod packs_since;
+
+///
+pub mod prune;
+
 ///
 pub mod init;
 
+///
+pub mod infer;
+
 pub(crate) mod types;
-pub use types::Metrics;
+pub use types::{KeptPack, Metrics, ObjectDirectory, PackDecodeStatistics, PromisorPack, Warning};
 
 pub(crate) mod handle;
 
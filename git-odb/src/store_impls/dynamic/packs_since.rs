@@ -0,0 +1,219 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use git_object::bstr::{BString, ByteSlice};
+
+use crate::store::types;
+
+mod error {
+    use std::path::PathBuf;
+
+    use crate::store::load_index;
+
+    /// Returned by [`Store::packs_since()`][crate::Store::packs_since()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        LoadIndex(#[from] load_index::Error),
+        #[error("Could not obtain the metadata of the pack at '{}'", .path.display())]
+        Metadata { path: PathBuf, source: std::io::Error },
+    }
+}
+pub use error::Error;
+
+///
+pub mod decode {
+    /// Returned by [`Marker::from_bytes()`][super::Marker::from_bytes()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The modification time {time:?} must be formatted as '<seconds>.<nanoseconds>' since the unix epoch")]
+        ModificationTime { time: git_object::bstr::BString },
+    }
+}
+
+/// A marker to remember which packs were seen already, obtained by [`Store::packs_since()`][crate::Store::packs_since()].
+///
+/// It records the modification time of the most recently modified pack data file, along with the names of all packs which
+/// were modified at exactly that time. That way, packs that are written later but happen to have the same modification
+/// time due to the limited resolution of the filesystem are still recognized as new.
+///
+/// Use [`to_bytes()`][Marker::to_bytes()] and [`from_bytes()`][Marker::from_bytes()] to persist it between runs.
+/// The [`Default`] marker hasn't seen any pack yet.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Marker {
+    /// The modification time of the most recently modified pack data file seen so far, or `None` if no pack was seen yet.
+    pub modified: Option<SystemTime>,
+    /// The file names of all pack data files whose modification time is `modified`, sorted.
+    pub names: Vec<BString>,
+}
+
+impl Marker {
+    /// Serialize this marker to a simple line-based format, with the modification time in the first line, followed by one
+    /// pack name per line.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        if let Some(modified) = self.modified {
+            let since_epoch = modified.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+            out.extend_from_slice(format!("{}.{:09}\n", since_epoch.as_secs(), since_epoch.subsec_nanos()).as_bytes());
+            for name in &self.names {
+                out.extend_from_slice(name);
+                out.push(b'\n');
+            }
+        }
+        out
+    }
+
+    /// Parse a marker previously serialized with [`to_bytes()`][Marker::to_bytes()]. Empty `data` is the [default][Marker::default()]
+    /// marker.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, decode::Error> {
+        let mut lines = data.lines();
+        let time = match lines.next() {
+            Some(time) => time,
+            None => return Ok(Marker::default()),
+        };
+        let modified = time
+            .to_str()
+            .ok()
+            .and_then(|time| time.split_once('.'))
+            .filter(|(_, nanos)| nanos.len() == 9)
+            .and_then(|(secs, nanos)| Some(Duration::new(secs.parse().ok()?, nanos.parse().ok()?)))
+            .ok_or_else(|| decode::Error::ModificationTime { time: time.into() })?;
+        Ok(Marker {
+            modified: Some(SystemTime::UNIX_EPOCH + modified),
+            names: lines.filter(|line| !line.is_empty()).map(Into::into).collect(),
+        })
+    }
+
+    fn is_older_than(&self, modified: SystemTime, name: &BString) -> bool {
+        match self.modified {
+            None => true,
+            Some(marker_modified) => {
+                modified > marker_modified || (modified == marker_modified && !self.names.contains(name))
+            }
+        }
+    }
+}
+
+/// Information about a pack data file as returned by [`Store::packs_since()`][crate::Store::packs_since()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackInfo {
+    /// The intrinsic id of the pack, as also found in the [`Location`][git_pack::data::entry::Location] of packed objects.
+    ///
+    /// It is only valid as long as the store keeps its pack ids stable, see [`Handle::prevent_pack_unload()`][crate::store::Handle::prevent_pack_unload()].
+    pub id: git_pack::data::Id,
+    /// The path to the pack data file.
+    pub path: PathBuf,
+    /// The path to the index which makes the pack accessible, which is either a pack index or a multi-pack index.
+    pub index_path: PathBuf,
+    /// The size of the pack data file in bytes.
+    pub size: u64,
+    /// The time at which the pack data file was last modified.
+    pub modified: SystemTime,
+}
+
+/// The outcome of [`Store::packs_since()`][crate::Store::packs_since()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All packs which were added after the marker was obtained, sorted by modification time, oldest first.
+    pub packs: Vec<PackInfo>,
+    /// The marker to pass to the next call to only receive packs that were added in the mean time.
+    pub marker: Marker,
+}
+
+impl crate::Store {
+    /// Scan the object database for packs and return those which were added after `marker` was obtained, along with a new marker
+    /// to use for the next call. Use the [default marker][Marker::default()] to obtain all packs.
+    ///
+    /// This is useful for maintenance operations which only want to process packs they haven't seen before, like verifying them.
+    ///
+    /// ### Consistency
+    ///
+    /// The returned packs represent the state on disk at the time of the scan, which can change at any time.
+    /// Thus a returned pack may already be gone once it is accessed, and packs that were added and removed between two
+    /// calls are never returned.
+    /// Packs are considered new based on the modification time of their data file, so packs that are moved into the object
+    /// database while retaining an older modification time than the one of the most recent pack seen before aren't returned.
+    pub fn packs_since(&self, marker: &Marker) -> Result<Outcome, Error> {
+        let needs_init = !self.index.load().is_initialized();
+        self.consolidate_with_disk_state(needs_init, false /* load one new index */)?;
+
+        let index = self.index.load();
+        let mut candidates = Vec::new();
+        for &slot_index in &index.slot_indices {
+            let files = self.files[slot_index].files.load();
+            let files = match Option::as_ref(&files) {
+                Some(files) if !files.is_disposable() => files,
+                _ => continue,
+            };
+            match files {
+                types::IndexAndPacks::Index(bundle) => candidates.push((
+                    types::PackId {
+                        index: slot_index,
+                        multipack_index: None,
+                    },
+                    bundle.data.path().to_owned(),
+                    bundle.index.path().to_owned(),
+                )),
+                types::IndexAndPacks::MultiIndex(bundle) => {
+                    candidates.extend(bundle.data.iter().enumerate().map(|(pack_index, data)| {
+                        (
+                            types::PackId {
+                                index: slot_index,
+                                multipack_index: Some(pack_index as git_pack::multi_index::PackIndex),
+                            },
+                            data.path().to_owned(),
+                            bundle.multi_index.path().to_owned(),
+                        )
+                    }))
+                }
+            }
+        }
+
+        let mut packs = Vec::new();
+        let mut new_marker = marker.clone();
+        for (id, path, index_path) in candidates {
+            let metadata = match std::fs::metadata(&path) {
+                Ok(metadata) => metadata,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(source) => return Err(Error::Metadata { path, source }),
+            };
+            let modified = metadata.modified().map_err(|source| Error::Metadata {
+                path: path.clone(),
+                source,
+            })?;
+            let name = git_path::into_bstr(path.file_name().map(PathBuf::from).unwrap_or_default()).into_owned();
+
+            match new_marker.modified {
+                Some(newest) if modified < newest => {}
+                Some(newest) if modified == newest => {
+                    if !new_marker.names.contains(&name) {
+                        new_marker.names.push(name.clone());
+                    }
+                }
+                _ => {
+                    new_marker.modified = Some(modified);
+                    new_marker.names = vec![name.clone()];
+                }
+            }
+            if marker.is_older_than(modified, &name) {
+                packs.push(PackInfo {
+                    id: id.to_intrinsic_pack_id(),
+                    path,
+                    index_path,
+                    size: metadata.len(),
+                    modified,
+                });
+            }
+        }
+        new_marker.names.sort();
+        packs.sort_by(|a, b| a.modified.cmp(&b.modified).then_with(|| a.path.cmp(&b.path)));
+        Ok(Outcome {
+            packs,
+            marker: new_marker,
+        })
+    }
+}
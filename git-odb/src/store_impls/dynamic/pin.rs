@@ -0,0 +1,94 @@
+use std::{ops::Deref, sync::atomic::Ordering};
+
+use crate::store::{load_index, Handle};
+
+/// A guard returned by [`Handle::pin_snapshot()`] which keeps the handle's view of the object database stable until it is dropped.
+pub struct Guard<'a, S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    handle: &'a Handle<S>,
+}
+
+impl<'a, S> Drop for Guard<'a, S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    fn drop(&mut self) {
+        let pinned = self.handle.pinned.get() - 1;
+        self.handle.pinned.set(pinned);
+        if pinned == 0 {
+            // Let go of everything we kept alive, but not more than that.
+            *self.handle.snapshot.borrow_mut() = self.handle.store.collect_snapshot();
+            self.handle.clear_cache();
+            self.handle.store.num_pinned_handles.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<S> Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Load all indices and map all of their packs, and keep using exactly these until the returned guard is dropped.
+    ///
+    /// While pinned, this handle will neither refresh from disk nor pick up indices loaded by other handles, and it won't
+    /// let go of any index or pack it has mapped. This way, a sequence of operations like iterating all objects and then
+    /// looking up each one of them sees one consistent set of objects, even if packs are deleted from disk in the mean time,
+    /// as is the case with `git repack -ad`. Objects added after pinning won't be visible until the pin is released.
+    ///
+    /// Pinning can be nested, with the snapshot being released once the last guard is dropped.
+    ///
+    /// ### Memory Implications
+    ///
+    /// All indices and packs are memory-mapped at once and can't be unmapped while pinned, which costs address space and
+    /// file handles, but only as much physical memory as is actually read. Packs deleted from disk can still be read on Unix,
+    /// but their disk space is only reclaimed once the pin is released. On Windows, such packs can't be deleted while pinned.
+    pub fn pin_snapshot(&self) -> Result<Guard<'_, S>, load_index::Error> {
+        if self.pinned.get() == 0 {
+            let mut snapshot = self.snapshot.borrow_mut();
+            'retry: loop {
+                *snapshot = self.store.load_all_indices()?;
+                let marker = snapshot.marker;
+                for index in snapshot.indices.iter_mut() {
+                    for (pack_id, pack) in index.packs_mut() {
+                        if pack.is_none() {
+                            match self.store.load_pack(pack_id, marker)? {
+                                Some(loaded) => *pack = Some(loaded),
+                                // The disk state changed in the mean time and our indices are outdated.
+                                None => continue 'retry,
+                            }
+                        }
+                    }
+                }
+                break;
+            }
+            self.clear_cache();
+            self.store.num_pinned_handles.fetch_add(1, Ordering::Relaxed);
+        }
+        self.pinned.set(self.pinned.get() + 1);
+        Ok(Guard { handle: self })
+    }
+
+    /// Return `true` if our snapshot is currently [pinned][Handle::pin_snapshot()].
+    pub fn is_pinned(&self) -> bool {
+        self.pinned.get() != 0
+    }
+
+    /// Like [`Store::load_one_index()`][super::Store::load_one_index()] with our refresh mode, but never provides a new
+    /// snapshot while pinned.
+    pub(crate) fn load_one_index(
+        &self,
+        marker: super::types::SlotIndexMarker,
+    ) -> Result<Option<load_index::Snapshot>, load_index::Error> {
+        if self.is_pinned() {
+            return Ok(None);
+        }
+        self.store.load_one_index(self.refresh, marker)
+    }
+
+    /// Return a copy of our snapshot if it is pinned.
+    pub(crate) fn pinned_snapshot(&self) -> Option<load_index::Snapshot> {
+        self.is_pinned().then(|| self.snapshot.borrow().clone())
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use crate::{loose, Store};
+
+mod error {
+    use std::path::PathBuf;
+
+    use crate::loose;
+
+    /// Returned by [`Store::prune_loose()`][crate::Store::prune_loose()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(
+            "Refusing to prune as no object is reachable, which would delete all loose objects older than the expiry"
+        )]
+        EmptyReachableSet,
+        #[error(transparent)]
+        Iter(#[from] loose::iter::Error),
+        #[error("Could not obtain the metadata of the loose object at '{}'", .path.display())]
+        Metadata { path: PathBuf, source: std::io::Error },
+        #[error("Could not delete the loose object at '{}'", .path.display())]
+        Delete { path: PathBuf, source: std::io::Error },
+    }
+}
+pub use error::Error;
+
+/// Options for use in [`Store::prune_loose()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Unreachable objects are only deleted if they were last modified at least this long ago, similar to `gc.pruneExpire`.
+    ///
+    /// Younger objects are kept as they may have been written by an operation that isn't done yet and hasn't created the
+    /// reference that makes them reachable.
+    pub expire: Duration,
+    /// If true, nothing is deleted and the [outcome][Outcome] lists what would have been deleted.
+    pub dry_run: bool,
+    /// If true, prune even if the set of reachable objects is empty, which typically indicates a problem with obtaining it.
+    pub force: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            expire: Duration::from_secs(2 * 7 * 24 * 60 * 60),
+            dry_run: true,
+            force: false,
+        }
+    }
+}
+
+/// Returned by [`Store::prune_loose()`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The paths of all deleted loose objects, or the ones that would have been deleted in a dry run, sorted.
+    pub deleted: Vec<PathBuf>,
+    /// The amount of bytes the deleted objects occupied on disk.
+    pub reclaimed_bytes: u64,
+    /// The amount of loose objects kept as they are reachable.
+    pub reachable: usize,
+    /// The amount of unreachable loose objects kept as they are younger than the expiry.
+    pub recent_unreachable: usize,
+}
+
+impl Store {
+    /// Delete all loose objects in our objects directory which aren't in `reachable` and were last modified at least
+    /// `expire` ago as configured in `options`, and return what was deleted.
+    ///
+    /// `reachable` is typically obtained by traversing all objects reachable from references and their reflogs.
+    /// As an empty `reachable` set would delete all old loose objects, this is refused with
+    /// [`EmptyReachableSet`][Error::EmptyReachableSet] unless `force` is set.
+    ///
+    /// Note that loose objects of alternates are never touched, and that fan-out directories are removed if they become empty.
+    pub fn prune_loose(&self, reachable: &git_hashtable::HashSet, options: Options) -> Result<Outcome, Error> {
+        if reachable.is_empty() && !options.force {
+            return Err(Error::EmptyReachableSet);
+        }
+        let expired_before = SystemTime::now()
+            .checked_sub(options.expire)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut out = Outcome::default();
+        let db = loose::Store::at(self.path(), self.object_hash());
+        for id in db.iter() {
+            let id = id?;
+            if reachable.contains(&id) {
+                out.reachable += 1;
+                continue;
+            }
+            let path = loose::hash_path(&id, self.path().to_owned());
+            let metadata = match std::fs::metadata(&path) {
+                Ok(metadata) => metadata,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(source) => return Err(Error::Metadata { path, source }),
+            };
+            let modified = metadata.modified().map_err(|source| Error::Metadata {
+                path: path.clone(),
+                source,
+            })?;
+            if modified >= expired_before {
+                out.recent_unreachable += 1;
+                continue;
+            }
+            if !options.dry_run {
+                match std::fs::remove_file(&path) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(source) => return Err(Error::Delete { path, source }),
+                }
+                // This fails unless the directory is empty, which is what we want.
+                std::fs::remove_dir(path.parent().expect("fan-out directory")).ok();
+            }
+            out.reclaimed_bytes += metadata.len();
+            out.deleted.push(path);
+        }
+        out.deleted.sort();
+        Ok(out)
+    }
+}
@@ -9,6 +9,7 @@ use std::{
 
 use arc_swap::ArcSwap;
 use git_features::hash;
+use git_object::bstr::{BString, ByteSlice};
 
 /// An id to refer to an index file or a multipack index file
 pub type IndexId = usize;
@@ -314,7 +315,7 @@ impl IndexAndPacks {
                     .map(Arc::new)
                     .map_err(|err| match err {
                         git_pack::index::init::Error::Io { source, .. } => source,
-                        err => std::io::Error::new(std::io::ErrorKind::Other, err),
+                        err => std::io::Error::new(std::io::ErrorKind::InvalidData, err),
                     })
             }),
             IndexAndPacks::MultiIndex(bundle) => {
@@ -323,7 +324,7 @@ impl IndexAndPacks {
                         .map(Arc::new)
                         .map_err(|err| match err {
                             git_pack::multi_index::init::Error::Io { source, .. } => source,
-                            err => std::io::Error::new(std::io::ErrorKind::Other, err),
+                            err => std::io::Error::new(std::io::ErrorKind::InvalidData, err),
                         })
                 })?;
                 if let Some(multi_index) = bundle.multi_index.loaded() {
@@ -350,6 +351,33 @@ impl IndexAndPacks {
         })
     }
 
+    /// Create a new bundle from an already opened `index` and `data` file, to be placed in the slot at `slot_index`.
+    /// These are never unloaded as they are not backed by a file on disk.
+    pub(crate) fn new_single_in_memory(
+        index: Arc<git_pack::index::File>,
+        mut data: git_pack::data::File,
+        mtime: SystemTime,
+        slot_index: IndexId,
+    ) -> Self {
+        data.id = PackId {
+            index: slot_index,
+            multipack_index: None,
+        }
+        .to_intrinsic_pack_id();
+        Self::Index(IndexFileBundle {
+            index: OnDiskFile {
+                path: Arc::new(index.path().to_owned()),
+                state: OnDiskFileState::Loaded(index),
+                mtime,
+            },
+            data: OnDiskFile {
+                path: Arc::new(data.path().to_owned()),
+                state: OnDiskFileState::Loaded(Arc::new(data)),
+                mtime,
+            },
+        })
+    }
+
     pub(crate) fn new_multi_from_open_file(multi_index: Arc<git_pack::multi_index::File>, mtime: SystemTime) -> Self {
         let data = Self::index_names_to_pack_paths(&multi_index);
         Self::MultiIndex(MultiIndexFileBundle {
@@ -389,12 +417,133 @@ pub(crate) struct MutableIndexAndPack {
     pub(crate) generation: AtomicGeneration,
 }
 
+/// A problem with accessing a path while scanning the object database, which is skipped in favor of the accessible parts
+/// unless the store is configured to be strict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// The path that couldn't be accessed.
+    pub path: PathBuf,
+    /// The kind of error that occurred when accessing `path`.
+    pub kind: std::io::ErrorKind,
+    /// The error number as provided by the operating system, if available.
+    pub raw_os_error: Option<i32>,
+}
+
+/// A pack whose data file has a sibling `.keep` file, which marks it as not to be deleted or rewritten by maintenance
+/// operations like repacking or pruning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeptPack {
+    /// The path to the kept pack data file.
+    pub path: PathBuf,
+    /// The first line of the `.keep` file which is conventionally the reason for keeping the pack, like `receive-pack 123 on host`,
+    /// or `None` if the file is empty.
+    pub reason: Option<BString>,
+}
+
+impl KeptPack {
+    pub(crate) fn reason_from_keep_file(content: &[u8]) -> Option<BString> {
+        let line = content.lines().next()?.trim();
+        (!line.is_empty()).then(|| line.into())
+    }
+}
+
+/// A pack whose data file has a sibling `.promisor` file, which marks it as received from the promisor remote of a partial
+/// clone. Objects missing locally are expected to be provided by that remote on demand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromisorPack {
+    /// The path to the promisor pack data file.
+    pub path: PathBuf,
+    /// The references advertised by the remote when the pack was fetched, as listed in the `.promisor` file, in order.
+    ///
+    /// This is empty if the file was written by an older git that doesn't record references.
+    pub refs: Vec<(git_hash::ObjectId, BString)>,
+}
+
+impl PromisorPack {
+    /// Parse lines of `<hex-id> <ref-name>`, skipping all lines that don't match.
+    pub(crate) fn refs_from_promisor_file(content: &[u8]) -> Vec<(git_hash::ObjectId, BString)> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let (hex, name) = line.split_once_str(b" ")?;
+                let name = name.trim_end();
+                let id = git_hash::ObjectId::from_hex(hex).ok()?;
+                (!name.is_empty()).then(|| (id, name.into()))
+            })
+            .collect()
+    }
+}
+
+/// An object directory, either the objects directory of the store or one of its alternates, along with the trust we have in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectDirectory {
+    /// The path to the object directory.
+    pub path: PathBuf,
+    /// The trust in the object directory, typically derived from its ownership.
+    pub trust: git_sec::Trust,
+    /// If false, the directory is not used to retrieve objects as its trust is reduced and the
+    /// [trust policy][crate::store::init::TrustPolicy::reduced] denies its use.
+    pub used: bool,
+}
+
+impl Warning {
+    pub(crate) fn from_io_error(path: impl Into<PathBuf>, err: &std::io::Error) -> Self {
+        Warning {
+            path: path.into(),
+            kind: err.kind(),
+            raw_os_error: err.raw_os_error(),
+        }
+    }
+
+    /// A warning about an object directory that isn't used as we don't trust it enough.
+    pub(crate) fn untrusted(path: PathBuf) -> Self {
+        Warning {
+            path,
+            kind: std::io::ErrorKind::PermissionDenied,
+            raw_os_error: None,
+        }
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not access '{}': {:?}", self.path.display(), self.kind)?;
+        if let Some(errno) = self.raw_os_error {
+            write!(f, " (os error {})", errno)?;
+        }
+        Ok(())
+    }
+}
+
+/// Statistics about decoding objects from a single pack through a [`Handle`][crate::store::Handle], gathered since
+/// [collecting them][crate::store::Handle::collect_pack_decode_statistics()] was enabled for the handle.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackDecodeStatistics {
+    /// The path to the pack data file.
+    pub path: PathBuf,
+    /// The amount of objects decoded from the pack.
+    pub objects: u64,
+    /// The amount of delta objects that had to be applied to resolve the decoded objects.
+    ///
+    /// Each cache hit shortens a delta chain and thus reduces this number.
+    pub deltas: u64,
+    /// The amount of entries in delta chains, including the requested one, which were found in the pack cache.
+    pub cache_hits: u64,
+    /// The amount of entries in delta chains, including the requested one, which weren't in the pack cache and had to be decoded.
+    pub cache_misses: u64,
+    /// The total amount of bytes produced by decompressing pack entries.
+    pub inflated_bytes: u64,
+}
+
 /// A snapshot about resource usage.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Metrics {
     /// The total amount of handles which can be used to access object information.
     pub num_handles: usize,
+    /// The amount of handles whose snapshot is currently [pinned][crate::store::Handle::pin_snapshot()].
+    pub num_pinned_handles: usize,
     /// The amount of refreshes performed to reconcile with the ODB state on disk.
     pub num_refreshes: usize,
     /// The amount of indices that are currently open and will be returned to handles.
@@ -421,6 +570,27 @@ pub struct Metrics {
     ///
     /// There may be more than one if 'alternates' are used.
     pub loose_dbs: usize,
+    /// The amount of object directories added to the alternates from `info/alternates`, typically from the environment.
+    ///
+    /// See [`Store::extra_alternates()`][crate::Store::extra_alternates()] for their paths.
+    pub extra_alternates: usize,
+    /// The amount of pack indices whose pack was missing when the object database was last scanned.
+    ///
+    /// See [`Store::orphaned_indices()`][crate::Store::orphaned_indices()] for their paths.
+    pub orphaned_indices: usize,
+    /// The amount of packs marked with a `.keep` file when the object database was last scanned.
+    ///
+    /// See [`Store::kept_packs()`][crate::Store::kept_packs()] for their paths.
+    pub kept_packs: usize,
+    /// The amount of packs marked with a `.promisor` file when the object database was last scanned.
+    ///
+    /// See [`Store::promisor_packs()`][crate::Store::promisor_packs()] for their paths.
+    pub promisor_packs: usize,
+    /// The amount of object directories with [reduced trust][git_sec::Trust::Reduced] when the object database was last
+    /// scanned, whether they are used or not.
+    ///
+    /// See [`Store::object_directories()`][crate::Store::object_directories()] for the classification of each object directory.
+    pub untrusted_object_directories: usize,
 }
 
 #[cfg(test)]
@@ -8,6 +8,7 @@ use git_features::progress::{MessageLevel, Progress};
 
 use crate::{
     pack,
+    pack::index::verify::integrity::merge_warnings_by_class,
     store::verify::integrity::{IndexStatistics, SingleOrMultiStatistics},
     types::IndexAndPacks,
 };
@@ -70,16 +71,31 @@ pub mod integrity {
         pub path: PathBuf,
         /// The actual statistics for the index at `path`.
         pub statistics: SingleOrMultiStatistics,
+        /// Information about the index or multi-pack index file at `path`, or `None` if it only exists in memory.
+        pub index_file: Option<pack::verify::FileInfo>,
+        /// Information about each pack data file that was verified, in the order of the packs in `statistics`,
+        /// with `None` for packs only existing in memory.
+        pub pack_files: Vec<Option<pack::verify::FileInfo>>,
     }
 
     /// Returned by [`Store::verify_integrity()`][crate::Store::verify_integrity()].
-    pub struct Outcome<P> {
+    pub struct Outcome {
         /// Statistics for validated loose object stores.
         pub loose_object_stores: Vec<LooseObjectStatistics>,
         /// Pack traversal statistics for each index and their pack(s)
         pub index_statistics: Vec<IndexStatistics>,
-        /// The provided progress instance.
-        pub progress: P,
+        /// All packed trees with problematic entries as per [`Options::tree_checks`][pack::index::verify::integrity::Options::tree_checks],
+        /// sorted by object id.
+        pub tree_issues: Vec<pack::index::verify::integrity::TreeIssues>,
+        /// Information about how packed objects were decoded, for all packs combined.
+        pub decode_statistics: pack::index::verify::integrity::DecodeStatistics,
+        /// The ids of all ref-delta bases missing in their pack as per [`Options::keep_going`][pack::index::verify::integrity::Options::keep_going],
+        /// for all packs combined, sorted and without duplicates.
+        pub missing_delta_bases: Vec<git_hash::ObjectId>,
+        /// The amount of objects that caused a warning of each class, for all packs and loose object databases combined.
+        pub warnings_by_class: pack::index::verify::integrity::WarningsByClass,
+        /// Pack indices whose pack is missing, which thus couldn't be verified, sorted by path.
+        pub orphaned_indices: Vec<PathBuf>,
     }
 }
 
@@ -88,12 +104,18 @@ impl super::Store {
     ///
     /// Note that this will not not force loading all indices or packs permanently, as we will only use the momentarily loaded disk state.
     /// This does, however, include all alternates.
+    ///
+    /// `progress` is only borrowed and thus remains usable by the caller, even if an error is returned.
+    /// It counts pack indices and then loose object stores, with one child per pack index with id `VIPI` named
+    /// `Checking integrity: <index file name>`, which in turn has the children created by
+    /// [`pack::index::File::verify_integrity()`], and one child per loose object store with id `VISP` named after its path,
+    /// which in turn has a child `Validating` with id `VILO`.
     pub fn verify_integrity<C, P, F>(
         &self,
-        mut progress: P,
+        progress: &mut P,
         should_interrupt: &AtomicBool,
         options: integrity::Options<F>,
-    ) -> Result<integrity::Outcome<P>, integrity::Error>
+    ) -> Result<integrity::Outcome, integrity::Error>
     where
         P: Progress,
         C: pack::cache::DecodeEntry,
@@ -114,6 +136,10 @@ impl super::Store {
             git_features::progress::count("pack indices"),
         );
         let mut statistics = Vec::new();
+        let mut tree_issues = Vec::new();
+        let mut decode_statistics = pack::index::verify::integrity::DecodeStatistics::default();
+        let mut missing_delta_bases = Vec::new();
+        let mut warnings_by_class = pack::index::verify::integrity::WarningsByClass::default();
         let index_check_message = |path: &std::path::Path| {
             format!(
                 "Checking integrity: {}",
@@ -131,7 +157,14 @@ impl super::Store {
             let files = Option::as_ref(&files).ok_or(integrity::Error::NeedsRetryDueToChangeOnDisk)?;
 
             let start = Instant::now();
-            let (mut child_progress, num_objects, index_path) = match files {
+            let mut child_progress = progress.add_child_with_id(
+                index_check_message(match files {
+                    IndexAndPacks::Index(bundle) => bundle.index.path(),
+                    IndexAndPacks::MultiIndex(bundle) => bundle.multi_index.path(),
+                }),
+                *b"VIPI", /* Verify Integrity Pack Index */
+            );
+            let num_objects = match files {
                 IndexAndPacks::Index(bundle) => {
                     let index;
                     let index = match bundle.index.loaded() {
@@ -145,7 +178,11 @@ impl super::Store {
                     let data = match bundle.data.loaded() {
                         Some(pack) => pack.deref(),
                         None => {
-                            pack = pack::data::File::at(bundle.data.path(), self.object_hash)?;
+                            pack = pack::data::File::at_with_access(
+                                bundle.data.path(),
+                                self.object_hash,
+                                self.pack_access,
+                            )?;
                             &pack
                         }
                     };
@@ -154,7 +191,7 @@ impl super::Store {
                             data,
                             options: options.clone(),
                         }),
-                        progress.add_child_with_id("never shown", git_features::progress::UNKNOWN),
+                        &mut child_progress,
                         should_interrupt,
                     )?;
                     statistics.push(IndexStatistics {
@@ -164,8 +201,14 @@ impl super::Store {
                                 .pack_traverse_statistics
                                 .expect("pack provided so there are stats"),
                         ),
+                        index_file: outcome.index_file,
+                        pack_files: vec![outcome.pack_file],
                     });
-                    (outcome.progress, index.num_objects(), index.path().to_owned())
+                    tree_issues.extend(outcome.tree_issues);
+                    decode_statistics += outcome.decode_statistics;
+                    missing_delta_bases.extend(outcome.missing_delta_bases);
+                    merge_warnings_by_class(&mut warnings_by_class, &outcome.warnings_by_class);
+                    index.num_objects()
                 }
                 IndexAndPacks::MultiIndex(bundle) => {
                     let index;
@@ -176,15 +219,15 @@ impl super::Store {
                             &index
                         }
                     };
-                    let outcome = index.verify_integrity(
-                        progress.add_child_with_id("never shown", git_features::progress::UNKNOWN),
-                        should_interrupt,
-                        options.clone(),
-                    )?;
+                    let outcome = index.verify_integrity(&mut child_progress, should_interrupt, options.clone())?;
 
+                    tree_issues.extend(outcome.tree_issues);
+                    decode_statistics += outcome.decode_statistics;
+                    missing_delta_bases.extend(outcome.missing_delta_bases);
+                    merge_warnings_by_class(&mut warnings_by_class, &outcome.warnings_by_class);
                     let index_dir = bundle.multi_index.path().parent().expect("file in a directory");
                     statistics.push(IndexStatistics {
-                        path: Default::default(),
+                        path: bundle.multi_index.path().to_owned(),
                         statistics: SingleOrMultiStatistics::Multi(
                             outcome
                                 .pack_traverse_statistics
@@ -193,12 +236,13 @@ impl super::Store {
                                 .map(|(statistics, index_name)| (index_dir.join(index_name), statistics))
                                 .collect(),
                         ),
+                        index_file: Some(outcome.index_file),
+                        pack_files: outcome.pack_files,
                     });
-                    (outcome.progress, index.num_objects(), index.path().to_owned())
+                    index.num_objects()
                 }
             };
 
-            child_progress.set_name(index_check_message(&index_path));
             child_progress.show_throughput_with(
                 start,
                 num_objects as usize,
@@ -214,22 +258,32 @@ impl super::Store {
         );
         let mut loose_object_stores = Vec::new();
         for loose_db in &*index.loose_dbs {
-            let out = loose_db
-                .verify_integrity(
-                    progress.add_child_with_id(loose_db.path().display().to_string(), *b"VISP"), /* Verify Integrity Store Path */
-                    should_interrupt,
-                )
-                .map(|statistics| integrity::LooseObjectStatistics {
-                    path: loose_db.path().to_owned(),
-                    statistics,
-                })?;
+            let loose_progress = progress.add_child_with_id(loose_db.path().display().to_string(), *b"VISP"); /* Verify Integrity Store Path */
+            let out = match options.verify_mode {
+                pack::index::verify::Mode::HashCrc32DecodeEncode => {
+                    loose_db.verify_integrity_with_roundtrip(loose_progress, should_interrupt)
+                }
+                _ => loose_db.verify_integrity(loose_progress, should_interrupt),
+            }
+            .map(|statistics| integrity::LooseObjectStatistics {
+                path: loose_db.path().to_owned(),
+                statistics,
+            })?;
+            merge_warnings_by_class(&mut warnings_by_class, &out.statistics.warnings_by_class);
             loose_object_stores.push(out);
         }
 
+        tree_issues.sort();
+        missing_delta_bases.sort();
+        missing_delta_bases.dedup();
         Ok(integrity::Outcome {
             loose_object_stores,
             index_statistics: statistics,
-            progress,
+            tree_issues,
+            decode_statistics,
+            missing_delta_bases,
+            warnings_by_class,
+            orphaned_indices: self.orphaned_indices(),
         })
     }
 }
@@ -0,0 +1,106 @@
+use std::path::{Path, PathBuf};
+
+use crate::{loose, WriteWithHash};
+
+/// Returned by [`Store::try_find_with_hash()`] and the [`WriteWithHash`] implementation of [`Store`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Objects hashed with {requested:?} aren't hosted, only those hashed with {configured:?}")]
+    UnsupportedObjectHash {
+        requested: git_hash::Kind,
+        configured: Vec<git_hash::Kind>,
+    },
+    #[error(transparent)]
+    Find(#[from] loose::find::Error),
+    #[error(transparent)]
+    Write(#[from] loose::write::Error),
+    #[error("An IO error occurred while writing an object")]
+    Io(#[from] std::io::Error),
+}
+
+/// A layout of loose object databases next to the one of the repository, one for each kind of hash objects are additionally
+/// written with, for instance while migrating to another kind of hash.
+///
+/// The objects of each kind of hash are stored in a directory of their own, `<objects>/compat/<kind>`, like
+/// `.git/objects/compat/sha1`, so ids of different kinds never share a directory.
+#[derive(Clone)]
+pub struct Store {
+    stores: Vec<loose::Store>,
+}
+
+/// Initialization
+impl Store {
+    /// Host loose objects hashed with each of `object_hashes` in a directory of their own below `objects_directory`,
+    /// which usually is `.git/objects`.
+    pub fn at(objects_directory: impl AsRef<Path>, object_hashes: impl IntoIterator<Item = git_hash::Kind>) -> Self {
+        let mut object_hashes: Vec<_> = object_hashes.into_iter().collect();
+        object_hashes.sort();
+        object_hashes.dedup();
+        Store {
+            stores: object_hashes
+                .into_iter()
+                .map(|object_hash| loose::Store::at(path(objects_directory.as_ref(), object_hash), object_hash))
+                .collect(),
+        }
+    }
+}
+
+impl Store {
+    /// Return the store of objects hashed with `object_hash`, if it is hosted.
+    pub fn store(&self, object_hash: git_hash::Kind) -> Option<&loose::Store> {
+        self.stores.iter().find(|store| store.object_hash() == object_hash)
+    }
+
+    /// Return all kinds of hash whose objects are hosted, sorted.
+    pub fn object_hashes(&self) -> impl Iterator<Item = git_hash::Kind> + '_ {
+        self.stores.iter().map(|store| store.object_hash())
+    }
+
+    /// Find the object with `id` among the objects hashed with `object_hash`, placing its data into `out`, or fail
+    /// with [`Error::UnsupportedObjectHash`] if these aren't hosted.
+    pub fn try_find_with_hash<'a>(
+        &self,
+        object_hash: git_hash::Kind,
+        id: impl AsRef<git_hash::oid>,
+        out: &'a mut Vec<u8>,
+    ) -> Result<Option<git_object::Data<'a>>, Error> {
+        Ok(self
+            .store_or_err(object_hash)?
+            .try_find_with_hash(object_hash, id, out)?)
+    }
+
+    fn store_or_err(&self, object_hash: git_hash::Kind) -> Result<&loose::Store, Error> {
+        self.store(object_hash).ok_or_else(|| Error::UnsupportedObjectHash {
+            requested: object_hash,
+            configured: self.object_hashes().collect(),
+        })
+    }
+}
+
+impl WriteWithHash for Store {
+    type Error = Error;
+
+    /// Write the object into the store of `object_hash`, or fail with [`Error::UnsupportedObjectHash`] if it isn't hosted.
+    ///
+    /// The directory of the store is created on first write.
+    fn write_with_hash(
+        &self,
+        object_hash: git_hash::Kind,
+        kind: git_object::Kind,
+        from: &[u8],
+    ) -> Result<git_hash::ObjectId, Self::Error> {
+        let store = self.store_or_err(object_hash)?;
+        if !store.path().is_dir() {
+            std::fs::create_dir_all(store.path())?;
+        }
+        Ok(store.write_with_hash(object_hash, kind, from)?)
+    }
+}
+
+/// Return the directory below `objects_directory` to store loose objects hashed with `object_hash` in.
+pub fn path(objects_directory: &Path, object_hash: git_hash::Kind) -> PathBuf {
+    objects_directory.join("compat").join(match object_hash {
+        git_hash::Kind::Sha1 => "sha1",
+    })
+}
@@ -15,12 +15,65 @@ pub enum Error {
     },
     #[error(transparent)]
     Decode(#[from] git_object::decode::LooseHeaderDecodeError),
+    #[error("Loose object {id} declares a size of {size} bytes, exceeding the limit of {limit} bytes")]
+    SizeLimitExceeded {
+        id: git_hash::ObjectId,
+        size: u64,
+        limit: u64,
+    },
     #[error("Could not {action} data at '{path}'")]
     Io {
         source: std::io::Error,
         action: &'static str,
         path: PathBuf,
     },
+    #[error("Cannot find objects hashed with {requested:?} in a store configured for {configured:?}")]
+    UnsupportedObjectHash {
+        requested: git_hash::Kind,
+        configured: git_hash::Kind,
+    },
+}
+
+impl Error {
+    /// Return the kind of this error.
+    ///
+    /// IO errors indicating invalid data, like those of decompressing a truncated object, are considered corrupt.
+    pub fn kind(&self) -> crate::find::ErrorKind {
+        use crate::find::ErrorKind;
+        match self {
+            Error::DecompressFile { .. } | Error::Decode(_) => ErrorKind::Corrupt,
+            Error::SizeLimitExceeded { .. } => ErrorKind::LimitExceeded,
+            Error::UnsupportedObjectHash { .. } => ErrorKind::Other,
+            Error::Io { source, .. } => match source.kind() {
+                std::io::ErrorKind::InvalidInput
+                | std::io::ErrorKind::InvalidData
+                | std::io::ErrorKind::UnexpectedEof => ErrorKind::Corrupt,
+                _ => ErrorKind::Io,
+            },
+        }
+    }
+
+    /// Return `true` if the object doesn't exist, which is never the case as lookups of missing objects succeed with `None`.
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == crate::find::ErrorKind::NotFound
+    }
+}
+
+impl crate::find::Classify for Error {
+    fn kind(&self) -> crate::find::ErrorKind {
+        Error::kind(self)
+    }
+}
+
+/// The header of a loose object as returned by [`Store::try_header()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Header {
+    /// The kind of the object.
+    pub kind: git_object::Kind,
+    /// The size of the decompressed object in bytes.
+    pub size: u64,
+    /// The size of the file storing the compressed object in bytes.
+    pub compressed_size: u64,
 }
 
 /// Object lookup
@@ -99,9 +152,43 @@ impl Store {
         &self,
         id: impl AsRef<git_hash::oid>,
         out: &'a mut Vec<u8>,
+    ) -> Result<Option<git_object::Data<'a>>, Error> {
+        self.try_find_with_limit(id, out, None)
+    }
+
+    /// Like [`try_find()`][Store::try_find()], but fail with [`Error::UnsupportedObjectHash`] unless `object_hash` is the
+    /// kind of hash this store was [configured][Store::at()] with. Ids hashed with another kind aren't looked up and yield `None`.
+    ///
+    /// This is the counterpart of [`write_with_hash()`][crate::WriteWithHash::write_with_hash()] to read back objects from the
+    /// store dedicated to their kind of hash.
+    pub fn try_find_with_hash<'a>(
+        &self,
+        object_hash: git_hash::Kind,
+        id: impl AsRef<git_hash::oid>,
+        out: &'a mut Vec<u8>,
+    ) -> Result<Option<git_object::Data<'a>>, Error> {
+        if object_hash != self.object_hash {
+            return Err(Error::UnsupportedObjectHash {
+                requested: object_hash,
+                configured: self.object_hash,
+            });
+        }
+        if id.as_ref().kind() != object_hash {
+            return Ok(None);
+        }
+        self.try_find(id, out)
+    }
+
+    /// Like [`try_find()`][Store::try_find()], but fail with [`Error::SizeLimitExceeded`] if the object declares a size
+    /// larger than `max_decompressed_object_size`, if set, before any memory is allocated for it.
+    pub fn try_find_with_limit<'a>(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+        out: &'a mut Vec<u8>,
+        max_decompressed_object_size: Option<u64>,
     ) -> Result<Option<git_object::Data<'a>>, Error> {
         debug_assert_eq!(self.object_hash, id.as_ref().kind());
-        match self.find_inner(id.as_ref(), out) {
+        match self.find_inner(id.as_ref(), out, max_decompressed_object_size) {
             Ok(obj) => Ok(Some(obj)),
             Err(err) => match err {
                 Error::Io {
@@ -124,7 +211,57 @@ impl Store {
         }
     }
 
-    fn find_inner<'a>(&self, id: &git_hash::oid, buf: &'a mut Vec<u8>) -> Result<git_object::Data<'a>, Error> {
+    /// Return the [header][Header] of the object identified by `id` by only decompressing the beginning of its file,
+    /// or `None` if there is no such object.
+    pub fn try_header(&self, id: impl AsRef<git_hash::oid>) -> Result<Option<Header>, Error> {
+        debug_assert_eq!(self.object_hash, id.as_ref().kind());
+        let path = hash_path(id.as_ref(), self.path.clone());
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => {
+                return Err(Error::Io {
+                    source,
+                    action: Self::OPEN_ACTION,
+                    path,
+                })
+            }
+        };
+        let compressed_size = file
+            .metadata()
+            .map_err(|source| Error::Io {
+                source,
+                action: "stat",
+                path: path.clone(),
+            })?
+            .len();
+
+        let mut input = Vec::with_capacity(HEADER_READ_UNCOMPRESSED_BYTES);
+        file.take(HEADER_READ_UNCOMPRESSED_BYTES as u64)
+            .read_to_end(&mut input)
+            .map_err(|source| Error::Io {
+                source,
+                action: "read",
+                path: path.clone(),
+            })?;
+        let mut out = [0u8; 64];
+        let (_status, _consumed_in, consumed_out) = zlib::Inflate::default()
+            .once(&input, &mut out)
+            .map_err(|source| Error::DecompressFile { source, path })?;
+        let (kind, size, _header_size) = git_object::decode::loose_header(&out[..consumed_out])?;
+        Ok(Some(Header {
+            kind,
+            size,
+            compressed_size,
+        }))
+    }
+
+    fn find_inner<'a>(
+        &self,
+        id: &git_hash::oid,
+        buf: &'a mut Vec<u8>,
+        limit: Option<u64>,
+    ) -> Result<git_object::Data<'a>, Error> {
         let path = hash_path(id, self.path.clone());
 
         let mut inflate = zlib::Inflate::default();
@@ -162,6 +299,18 @@ impl Store {
         let decompressed_start = bytes_read;
         let (kind, size, header_size) =
             git_object::decode::loose_header(&buf[decompressed_start..decompressed_start + consumed_out])?;
+        if let Some(limit) = limit {
+            if size > limit {
+                return Err(Error::SizeLimitExceeded {
+                    id: id.to_owned(),
+                    size,
+                    limit,
+                });
+            }
+        }
+        let size = usize::try_from(size).map_err(|_| git_object::decode::LooseHeaderDecodeError::InvalidHeader {
+            message: "Object size in header doesn't fit into memory",
+        })?;
 
         if status == zlib::Status::StreamEnd {
             let decompressed_body_bytes_sans_header =
@@ -64,12 +64,18 @@ impl loose::Store {
     /// [`loose::Iter`] is used instead of `impl Iterator<…>` to allow using this iterator in struct fields, as is currently
     /// needed if iterators need to be implemented by hand in the absence of generators.
     pub fn iter(&self) -> loose::Iter {
+        self.iter_inner(fs::walkdir_new(&self.path))
+    }
+
+    /// Like [`iter()`][loose::Store::iter()], but returns objects in ascending order of their ids, which is stable
+    /// as long as the database doesn't change.
+    pub fn iter_sorted(&self) -> loose::Iter {
+        self.iter_inner(fs::walkdir_sorted_new(&self.path))
+    }
+
+    fn iter_inner(&self, walk: fs::WalkDir) -> loose::Iter {
         loose::Iter {
-            inner: fs::walkdir_new(&self.path)
-                .min_depth(2)
-                .max_depth(3)
-                .follow_links(false)
-                .into_iter(),
+            inner: walk.min_depth(2).max_depth(3).follow_links(false).into_iter(),
             hash_hex_len: self.object_hash.len_in_hex(),
         }
     }
@@ -11,6 +11,10 @@ pub struct Store {
     pub(crate) path: PathBuf,
     /// The kind of hash we should assume during iteration and when writing new objects.
     pub(crate) object_hash: git_hash::Kind,
+    /// Options to use when writing objects.
+    pub(crate) write_options: write::Options,
+    /// A buffer to compress small objects into before writing them.
+    pub(crate) scratch: write::Scratch,
 }
 
 /// Initialization
@@ -22,9 +26,20 @@ impl Store {
     ///
     /// The `object_hash` determines which hash to use when writing, finding or iterating objects.
     pub fn at(objects_directory: impl Into<PathBuf>, object_hash: git_hash::Kind) -> Store {
+        Self::at_opts(objects_directory, object_hash, Default::default())
+    }
+
+    /// Like [`at()`][Store::at()], but with `write_options` to control how objects are written.
+    pub fn at_opts(
+        objects_directory: impl Into<PathBuf>,
+        object_hash: git_hash::Kind,
+        write_options: write::Options,
+    ) -> Store {
         Store {
             path: objects_directory.into(),
             object_hash,
+            write_options,
+            scratch: Default::default(),
         }
     }
 
@@ -39,7 +54,7 @@ impl Store {
     }
 }
 
-fn hash_path(id: &git_hash::oid, mut root: PathBuf) -> PathBuf {
+pub(crate) fn hash_path(id: &git_hash::oid, mut root: PathBuf) -> PathBuf {
     let mut hex = git_hash::Kind::hex_buf();
     let hex_len = id.hex_to_buf(hex.as_mut());
     let buf = std::str::from_utf8(&hex[..hex_len]).expect("ascii only in hex");
@@ -48,6 +63,8 @@ fn hash_path(id: &git_hash::oid, mut root: PathBuf) -> PathBuf {
     root
 }
 
+///
+pub mod compat;
 ///
 pub mod find;
 ///
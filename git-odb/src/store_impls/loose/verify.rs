@@ -5,10 +5,12 @@ use std::{
 
 use git_features::progress::Progress;
 
-use crate::{loose::Store, Write};
+use crate::loose::Store;
 
 ///
 pub mod integrity {
+    use git_object::bstr::BString;
+
     /// The error returned by [`verify_integrity()`][super::Store::verify_integrity()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
@@ -25,6 +27,13 @@ pub mod integrity {
             actual: git_hash::ObjectId,
             expected: git_hash::ObjectId,
         },
+        #[error("{kind} object {id} wasn't re-encoded without change, wanted\n{expected}\n\nGOT\n\n{actual}")]
+        ObjectEncodeMismatch {
+            kind: git_object::Kind,
+            id: git_hash::ObjectId,
+            expected: BString,
+            actual: BString,
+        },
         #[error("Objects were deleted during iteration - try again")]
         Retry,
         #[error("Interrupted")]
@@ -37,20 +46,44 @@ pub mod integrity {
     pub struct Statistics {
         /// The amount of loose objects we checked.
         pub num_objects: usize,
+        /// The amount of objects that caused a warning of each class, which can only happen when verifying
+        /// [with roundtrip][super::Store::verify_integrity_with_roundtrip()].
+        pub warnings_by_class: crate::pack::index::verify::integrity::WarningsByClass,
     }
 }
 
 impl Store {
     /// Check all loose objects for their integrity checking their hash matches the actual data and by decoding them fully.
     pub fn verify_integrity(
+        &self,
+        progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<integrity::Statistics, integrity::Error> {
+        self.verify_integrity_inner(progress, should_interrupt, false)
+    }
+
+    /// Like [`verify_integrity()`][Store::verify_integrity()], but additionally assure that each object re-encodes into exactly
+    /// the bytes it was decoded from using [`git_object::verify::roundtrip()`].
+    ///
+    /// Known benign differences, like non-standard tree entry modes, are reported to `progress` but don't fail the verification.
+    pub fn verify_integrity_with_roundtrip(
+        &self,
+        progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<integrity::Statistics, integrity::Error> {
+        self.verify_integrity_inner(progress, should_interrupt, true)
+    }
+
+    fn verify_integrity_inner(
         &self,
         mut progress: impl Progress,
         should_interrupt: &AtomicBool,
+        roundtrip: bool,
     ) -> Result<integrity::Statistics, integrity::Error> {
         let mut buf = Vec::new();
-        let sink = crate::sink(self.object_hash);
 
         let mut num_objects = 0;
+        let mut warnings_by_class = crate::pack::index::verify::integrity::WarningsByClass::default();
         let start = Instant::now();
         let mut progress = progress.add_child_with_id("Validating", *b"VILO"); /* Verify Integrity Loose Objects */
         progress.init(None, git_features::progress::count("loose objects"));
@@ -59,7 +92,7 @@ impl Store {
                 .try_find(id, &mut buf)
                 .map_err(|_| integrity::Error::Retry)?
                 .ok_or(integrity::Error::Retry)?;
-            let actual_id = sink.write_buf(object.kind, object.data).expect("sink never fails");
+            let actual_id = git_object::compute_hash(object.kind, object.data, self.object_hash);
             if actual_id != id {
                 return Err(integrity::Error::ObjectHashMismatch {
                     kind: object.kind,
@@ -67,11 +100,48 @@ impl Store {
                     expected: id,
                 });
             }
-            object.decode().map_err(|err| integrity::Error::ObjectDecode {
-                source: err,
-                kind: object.kind,
-                id,
-            })?;
+            if roundtrip {
+                use git_object::verify::roundtrip::Error;
+                match git_object::verify::roundtrip(object.kind, object.data) {
+                    Ok(()) => {}
+                    Err(Error::Decode(err)) => {
+                        return Err(integrity::Error::ObjectDecode {
+                            source: err,
+                            kind: object.kind,
+                            id,
+                        })
+                    }
+                    Err(Error::Mismatch {
+                        normalization: Some(normalization),
+                        ..
+                    }) => {
+                        *warnings_by_class.entry(normalization.into()).or_insert(0) += 1;
+                        progress.info(format!(
+                            "{} object {} would be cleaned up during re-serialization, {}",
+                            object.kind, id, normalization
+                        ))
+                    }
+                    Err(Error::Mismatch {
+                        kind,
+                        expected,
+                        actual,
+                        normalization: None,
+                    }) => {
+                        return Err(integrity::Error::ObjectEncodeMismatch {
+                            kind,
+                            id,
+                            expected,
+                            actual,
+                        })
+                    }
+                }
+            } else {
+                object.decode().map_err(|err| integrity::Error::ObjectDecode {
+                    source: err,
+                    kind: object.kind,
+                    id,
+                })?;
+            }
 
             progress.inc();
             num_objects += 1;
@@ -81,6 +151,9 @@ impl Store {
         }
         progress.show_throughput(start);
 
-        Ok(integrity::Statistics { num_objects })
+        Ok(integrity::Statistics {
+            num_objects,
+            warnings_by_class,
+        })
     }
 }
@@ -1,11 +1,87 @@
-use std::{convert::TryInto, fs, io, io::Write, path::PathBuf};
+use std::{fs, io, io::Write, path::PathBuf};
 
 use git_features::{hash, zlib::stream::deflate};
 use git_object::WriteTo;
 use tempfile::NamedTempFile;
 
 use super::Store;
-use crate::store_impls::loose;
+use crate::{store_impls::loose, Write as _};
+
+/// The default for [`Options::max_buffered_object_size`].
+pub const DEFAULT_MAX_BUFFERED_OBJECT_SIZE: u64 = 64 * 1024;
+
+/// Options to control how objects are written, for use in [`Store::at_opts()`][super::Store::at_opts()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If true, an object that already exists at its destination will be read back and its hash checked before we
+    /// consider it the same as the one we are writing. If it doesn't match, it's considered corrupt and will be replaced.
+    ///
+    /// Otherwise, an existing object is assumed to be intact, which is what its name promises.
+    pub verify_existing: bool,
+    /// If true, [`write_buf()`][crate::Write::write_buf()] won't write anything if the object is already
+    /// [contained][super::Store::contains()] as its id can be computed upfront.
+    ///
+    /// Note that all write methods skip moving their temporary file into place if the object exists by then.
+    pub skip_if_contained: bool,
+    /// Objects whose uncompressed size is at most this amount of bytes are compressed into a reusable buffer and written
+    /// to their file with a single call, which also avoids creating a file for objects that turn out to exist already.
+    ///
+    /// Larger objects are compressed while streaming them into their file to bound memory usage.
+    /// It defaults to [`DEFAULT_MAX_BUFFERED_OBJECT_SIZE`].
+    pub max_buffered_object_size: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            verify_existing: false,
+            skip_if_contained: false,
+            max_buffered_object_size: DEFAULT_MAX_BUFFERED_OBJECT_SIZE,
+        }
+    }
+}
+
+/// A compressor along with the buffer to compress small objects into, reused across writes if it isn't in use by
+/// another thread.
+///
+/// It doesn't participate in comparisons, and clones start out without one.
+#[derive(Default)]
+pub(crate) struct Scratch(parking_lot::Mutex<Option<Box<CompressedBuffer>>>);
+
+type CompressedBuffer = deflate::Write<Vec<u8>>;
+
+impl Scratch {
+    fn take(&self) -> Box<CompressedBuffer> {
+        let mut out = self
+            .0
+            .try_lock()
+            .and_then(|mut slot| slot.take())
+            .unwrap_or_else(|| Box::new(deflate::Write::new(Vec::new())));
+        out.reset();
+        out.get_mut().clear();
+        out
+    }
+
+    fn put_back(&self, out: Box<CompressedBuffer>) {
+        if let Some(mut slot) = self.0.try_lock() {
+            *slot = Some(out);
+        }
+    }
+}
+
+impl Clone for Scratch {
+    fn clone(&self) -> Self {
+        Scratch::default()
+    }
+}
+
+impl PartialEq for Scratch {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Scratch {}
 
 /// Returned by the [`crate::Write`] trait implementation of [`Store`]
 #[derive(thiserror::Error, Debug)]
@@ -24,46 +100,36 @@ pub enum Error {
         source: tempfile::PersistError,
         target: PathBuf,
     },
+    #[error("Cannot write objects hashed with {requested:?} into a store configured for {configured:?}")]
+    UnsupportedObjectHash {
+        requested: git_hash::Kind,
+        configured: git_hash::Kind,
+    },
 }
 
 impl crate::traits::Write for Store {
     type Error = Error;
 
     fn write(&self, object: impl WriteTo) -> Result<git_hash::ObjectId, Self::Error> {
-        let mut to = self.dest()?;
-        to.write_all(&object.loose_header()).map_err(|err| Error::Io {
-            source: err,
-            message: "write header to tempfile in",
-            path: self.path.to_owned(),
-        })?;
-        object.write_to(&mut to).map_err(|err| Error::Io {
-            source: err,
-            message: "stream all data into tempfile in",
-            path: self.path.to_owned(),
-        })?;
-        to.flush()?;
-        self.finalize_object(to)
+        self.write_object(&object.loose_header(), object.size() as u64, |to| object.write_to(to))
     }
 
-    /// Write the given buffer in `from` to disk in one syscall at best.
+    /// Write the given buffer in `from` to disk in one syscall if it's small enough to be
+    /// [buffered][Options::max_buffered_object_size].
     ///
     /// This will cost at least 4 IO operations.
     fn write_buf(&self, kind: git_object::Kind, from: &[u8]) -> Result<git_hash::ObjectId, Self::Error> {
-        let mut to = self.dest()?;
-        to.write_all(&git_object::encode::loose_header(kind, from.len()))
-            .map_err(|err| Error::Io {
-                source: err,
-                message: "write header to tempfile in",
-                path: self.path.to_owned(),
-            })?;
-
-        to.write_all(from).map_err(|err| Error::Io {
-            source: err,
-            message: "stream all data into tempfile in",
-            path: self.path.to_owned(),
-        })?;
-        to.flush()?;
-        self.finalize_object(to)
+        if self.write_options.skip_if_contained {
+            let id = git_object::compute_hash(kind, from, self.object_hash);
+            if self.contains_intact(&id) {
+                return Ok(id);
+            }
+        }
+        self.write_object(
+            &git_object::encode::loose_header(kind, from.len() as u64),
+            from.len() as u64,
+            |to| to.write_all(from),
+        )
     }
 
     /// Write the given stream in `from` to disk with at least one syscall.
@@ -75,37 +141,110 @@ impl crate::traits::Write for Store {
         size: u64,
         mut from: impl io::Read,
     ) -> Result<git_hash::ObjectId, Self::Error> {
+        self.write_object(&git_object::encode::loose_header(kind, size), size, |to| {
+            io::copy(&mut from, to).map(|_| ())
+        })
+    }
+}
+
+type CompressedTempfile = deflate::Write<NamedTempFile>;
+
+impl crate::traits::WriteWithHash for Store {
+    type Error = Error;
+
+    /// Like [`write_buf()`][crate::Write::write_buf()], but fail with a typed error unless `object_hash` is the kind of hash
+    /// this store was [configured][Store::at()] with, instead of silently hashing with the latter.
+    ///
+    /// Objects written like this can be read back with [`try_find_with_hash()`][Store::try_find_with_hash()], and
+    /// [`compat::Store`][super::compat::Store] hosts one store per kind of hash.
+    fn write_with_hash(
+        &self,
+        object_hash: git_hash::Kind,
+        kind: git_object::Kind,
+        from: &[u8],
+    ) -> Result<git_hash::ObjectId, Self::Error> {
+        if object_hash != self.object_hash {
+            return Err(Error::UnsupportedObjectHash {
+                requested: object_hash,
+                configured: self.object_hash,
+            });
+        }
+        self.write_buf(kind, from)
+    }
+}
+
+impl Store {
+    /// Write the object with `header` and `size` bytes of data produced by `write_data`, compressing it in memory if it's
+    /// small enough, or while streaming it into its file otherwise.
+    fn write_object(
+        &self,
+        header: &[u8],
+        size: u64,
+        write_data: impl FnOnce(&mut dyn io::Write) -> io::Result<()>,
+    ) -> Result<git_hash::ObjectId, Error> {
+        if size <= self.write_options.max_buffered_object_size {
+            let mut to = hash::Write::new(self.scratch.take(), self.object_hash);
+            let res = self
+                .write_header_and_data(&mut to, header, write_data)
+                .and_then(|()| to.flush().map_err(Error::from));
+            let hash::Write {
+                hash,
+                inner: compressed,
+            } = to;
+            let res =
+                res.and_then(|()| self.persist_buffer(git_hash::ObjectId::from(hash.digest()), compressed.get_ref()));
+            self.scratch.put_back(compressed);
+            return res;
+        }
         let mut to = self.dest()?;
-        to.write_all(&git_object::encode::loose_header(
-            kind,
-            size.try_into().expect("object size to fit into usize"),
-        ))
-        .map_err(|err| Error::Io {
+        self.write_header_and_data(&mut to, header, write_data)?;
+        to.flush()?;
+        self.finalize_object(to)
+    }
+
+    /// Write the compressed object `id` in `buf` into its file unless it exists already.
+    fn persist_buffer(&self, id: git_hash::ObjectId, buf: &[u8]) -> Result<git_hash::ObjectId, Error> {
+        if self.contains_intact(&id) {
+            return Ok(id);
+        }
+        let mut file = self.tempfile()?;
+        file.write_all(buf).map_err(|err| Error::Io {
             source: err,
-            message: "write header to tempfile in",
+            message: "write compressed object to tempfile in",
             path: self.path.to_owned(),
         })?;
+        self.persist(id, file)
+    }
 
-        io::copy(&mut from, &mut to).map_err(|err| Error::Io {
+    fn write_header_and_data(
+        &self,
+        to: &mut impl io::Write,
+        header: &[u8],
+        write_data: impl FnOnce(&mut dyn io::Write) -> io::Result<()>,
+    ) -> Result<(), Error> {
+        to.write_all(header).map_err(|err| Error::Io {
             source: err,
-            message: "stream all data into tempfile in",
+            message: "write header to tempfile in",
             path: self.path.to_owned(),
         })?;
-        to.flush()?;
-        self.finalize_object(to)
+        write_data(to).map_err(|err| Error::Io {
+            source: err,
+            message: "stream all data into tempfile in",
+            path: self.path.to_owned(),
+        })
     }
-}
 
-type CompressedTempfile = deflate::Write<NamedTempFile>;
+    fn tempfile(&self) -> Result<NamedTempFile, Error> {
+        NamedTempFile::new_in(&self.path).map_err(|err| Error::Io {
+            source: err,
+            message: "create named temp file in",
+            path: self.path.to_owned(),
+        })
+    }
 
-impl Store {
     fn dest(&self) -> Result<hash::Write<CompressedTempfile>, Error> {
         Ok(hash::Write::new(
-            deflate::Write::new(NamedTempFile::new_in(&self.path).map_err(|err| Error::Io {
-                source: err,
-                message: "create named temp file in",
-                path: self.path.to_owned(),
-            })?),
+            deflate::Write::new(self.tempfile()?),
             self.object_hash,
         ))
     }
@@ -115,21 +254,73 @@ impl Store {
         hash::Write { hash, inner: file }: hash::Write<CompressedTempfile>,
     ) -> Result<git_hash::ObjectId, Error> {
         let id = git_hash::ObjectId::from(hash.digest());
+        let file = file.into_inner();
+        if self.contains_intact(&id) {
+            // Dropping the tempfile removes it.
+            return Ok(id);
+        }
+        self.persist(id, file)
+    }
+
+    /// Move `file` with the object `id` into place, which must not exist yet or be corrupt.
+    fn persist(&self, id: git_hash::ObjectId, file: NamedTempFile) -> Result<git_hash::ObjectId, Error> {
         let object_path = loose::hash_path(&id, self.path.clone());
-        let object_dir = object_path
-            .parent()
-            .expect("each object path has a 1 hex-bytes directory");
-        if let Err(err) = fs::create_dir(object_dir) {
-            match err.kind() {
-                io::ErrorKind::AlreadyExists => {}
-                _ => return Err(err.into()),
+        // Only create the directory if it's missing, saving a syscall for all but the first object in it.
+        let file = match file.persist(&object_path) {
+            Ok(_) => return Ok(id),
+            Err(tempfile::PersistError { error, file }) if error.kind() == io::ErrorKind::NotFound => {
+                let object_dir = object_path
+                    .parent()
+                    .expect("each object path has a 1 hex-bytes directory");
+                if let Err(err) = fs::create_dir(object_dir) {
+                    match err.kind() {
+                        io::ErrorKind::AlreadyExists => {}
+                        _ => return Err(err.into()),
+                    }
+                }
+                file
+            }
+            Err(tempfile::PersistError { file, .. }) => file,
+        };
+        match file.persist(&object_path) {
+            Ok(_) => Ok(id),
+            Err(tempfile::PersistError { error, file }) => {
+                // Another writer may have beaten us to it, which is fine as it wrote the same object. On windows, this
+                // case shows as `PermissionDenied` if the destination is read-only.
+                if self.contains_intact(&id) {
+                    return Ok(id);
+                }
+                #[cfg(windows)]
+                if error.kind() == io::ErrorKind::PermissionDenied && object_path.is_file() {
+                    // The existing object is corrupt and read-only, so make it writable to be able to replace it.
+                    let mut permissions = fs::metadata(&object_path)?.permissions();
+                    permissions.set_readonly(false);
+                    fs::set_permissions(&object_path, permissions)?;
+                    return file.persist(&object_path).map(|_| id).map_err(|err| Error::Persist {
+                        source: err,
+                        target: object_path,
+                    });
+                }
+                Err(Error::Persist {
+                    source: tempfile::PersistError { error, file },
+                    target: object_path,
+                })
             }
         }
-        let file = file.into_inner();
-        file.persist(&object_path).map_err(|err| Error::Persist {
-            source: err,
-            target: object_path,
-        })?;
-        Ok(id)
+    }
+
+    /// Return true if the object with `id` exists, and if configured, has data matching its `id`.
+    fn contains_intact(&self, id: &git_hash::oid) -> bool {
+        if !self.contains(id) {
+            return false;
+        }
+        if !self.write_options.verify_existing {
+            return true;
+        }
+        let mut buf = Vec::new();
+        match self.try_find(id, &mut buf) {
+            Ok(Some(object)) => git_object::compute_hash(object.kind, object.data, self.object_hash) == id,
+            _ => false,
+        }
     }
 }
@@ -30,6 +30,22 @@ pub trait Write {
     ) -> Result<git_hash::ObjectId, Self::Error>;
 }
 
+/// Describe the capability to write git objects hashed with a kind of hash chosen for each object, which extends [`Write`]
+/// for object stores hosting objects of more than one kind of hash.
+pub trait WriteWithHash {
+    /// The error type used for all trait methods.
+    type Error: std::error::Error + From<io::Error>;
+
+    /// Like [`write_buf`][Write::write_buf], but hash the object with `object_hash`, or fail with a typed error if objects
+    /// of this kind of hash can't be stored.
+    fn write_with_hash(
+        &self,
+        object_hash: git_hash::Kind,
+        kind: git_object::Kind,
+        from: &[u8],
+    ) -> Result<git_hash::ObjectId, Self::Error>;
+}
+
 /// Describe how object can be located in an object store.
 ///
 /// ## Notes
@@ -119,6 +135,54 @@ mod _impls {
         }
     }
 
+    impl<T> crate::WriteWithHash for &T
+    where
+        T: crate::WriteWithHash,
+    {
+        type Error = T::Error;
+
+        fn write_with_hash(
+            &self,
+            object_hash: git_hash::Kind,
+            kind: Kind,
+            from: &[u8],
+        ) -> Result<ObjectId, Self::Error> {
+            (*self).write_with_hash(object_hash, kind, from)
+        }
+    }
+
+    impl<T> crate::WriteWithHash for Arc<T>
+    where
+        T: crate::WriteWithHash,
+    {
+        type Error = T::Error;
+
+        fn write_with_hash(
+            &self,
+            object_hash: git_hash::Kind,
+            kind: Kind,
+            from: &[u8],
+        ) -> Result<ObjectId, Self::Error> {
+            self.deref().write_with_hash(object_hash, kind, from)
+        }
+    }
+
+    impl<T> crate::WriteWithHash for Rc<T>
+    where
+        T: crate::WriteWithHash,
+    {
+        type Error = T::Error;
+
+        fn write_with_hash(
+            &self,
+            object_hash: git_hash::Kind,
+            kind: Kind,
+            from: &[u8],
+        ) -> Result<ObjectId, Self::Error> {
+            self.deref().write_with_hash(object_hash, kind, from)
+        }
+    }
+
     impl<T> crate::Find for &T
     where
         T: crate::Find,
@@ -0,0 +1,4 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
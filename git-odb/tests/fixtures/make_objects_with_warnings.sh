@@ -0,0 +1,26 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q --bare
+
+function raw_id() {
+  printf "$(echo "$1" | sed 's/../\\x&/g')"
+}
+
+# write a tree with a single entry of `mode`, `name` and `id` without validating it, and print its id.
+function tree_with_entry() {
+  { printf '%s %s\0' "$1" "$2"; raw_id "$3"; } | git hash-object -w --literally -t tree --stdin
+}
+
+blob=$(echo content | git hash-object -w --stdin)
+empty_tree=$(git hash-object -w -t tree /dev/null)
+
+{
+  echo "$blob"
+  tree_with_entry 100664 group-writable "$blob"
+  tree_with_entry 040000 zero-padded "$empty_tree"
+  { printf '040000 a\0'; raw_id "$empty_tree"; printf '040000 b\0'; raw_id "$empty_tree"; } | git hash-object -w --literally -t tree --stdin
+} | git pack-objects -q objects/pack/pack >/dev/null
+git prune-packed
+
+tree_with_entry 100640 group-readable "$blob" >/dev/null
@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git config uploadpack.allowFilter true
+  echo "a" > a && mkdir dir && echo "b" > dir/b
+  git add . && git commit -q -m "initial"
+  echo "a2" > a && git commit -q -am "second"
+  git tag -m "annotated" v1
+)
+
+git clone -q --bare --no-local --filter=blob:none "file://$PWD/base" blobless.git
+git clone -q --bare --no-local --filter=tree:0 "file://$PWD/base" treeless.git
@@ -0,0 +1,31 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.commitGraph false
+git symbolic-ref HEAD refs/heads/main
+
+# A history of 2000 commits with a merge every 10 commits and long messages, so commits are stored as deltas of each other.
+{
+  for i in $(seq 1 2000); do
+    echo "commit refs/heads/main"
+    echo "mark :$i"
+    echo "committer author <author@example.com> $((1000000000 + i)) +0000"
+    message="commit $i
+$(printf 'a long paragraph describing the change in detail, repeated to make the message large %.0s' $(seq 1 20))"
+    echo "data ${#message}"
+    echo "$message"
+    if [ "$i" -gt 1 ]; then
+      echo "from :$((i - 1))"
+    fi
+    if [ "$((i % 10))" -eq 0 ] && [ "$i" -gt 10 ]; then
+      echo "merge :$((i - 5))"
+    fi
+    echo "M 644 inline file"
+    echo "data ${#i}"
+    echo "$i"
+    echo
+  done
+} | git fast-import --quiet
+
+git repack -adfq --depth=50 --window=50
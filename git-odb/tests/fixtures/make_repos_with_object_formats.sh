@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+for format in sha1 sha256; do
+  git init -q --object-format=$format $format-loose
+  (cd $format-loose
+    git commit -q --allow-empty -m "first"
+  )
+
+  git init -q --object-format=$format $format-packed
+  (cd $format-packed
+    git commit -q --allow-empty -m "first"
+    git repack -adq
+  )
+done
+
+git init -q empty
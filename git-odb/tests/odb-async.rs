@@ -0,0 +1,166 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures_lite::StreamExt;
+use git_odb::nonblocking;
+use git_testtools::fixture_path;
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn store() -> Result<Arc<git_odb::Store>> {
+    Ok(Arc::new(git_odb::Store::at_opts(
+        fixture_path("objects"),
+        Vec::new(),
+        Default::default(),
+    )?))
+}
+
+fn runtime() -> Result<tokio::runtime::Runtime> {
+    Ok(tokio::runtime::Builder::new_multi_thread().worker_threads(1).build()?)
+}
+
+#[test]
+fn verify_integrity_of_store_with_progress() -> Result {
+    let nonblocking::Operation { progress, outcome } =
+        nonblocking::verify_integrity(store()?, Default::default(), Duration::from_millis(1));
+    let (snapshots, outcome) = runtime()?.block_on(async {
+        let snapshots = tokio::spawn(progress.collect::<Vec<_>>());
+        let outcome = outcome.await;
+        (snapshots.await.expect("no panic"), outcome)
+    });
+    let outcome = outcome?;
+    assert_eq!(outcome.index_statistics.len(), 3, "all packs were verified");
+    assert_eq!(outcome.loose_object_stores.len(), 1);
+
+    let last = snapshots.last().expect("the final snapshot is always delivered");
+    assert_eq!(
+        last[0].1.name, "verify store",
+        "the root item is named after the operation"
+    );
+    Ok(())
+}
+
+#[test]
+fn verify_integrity_of_bundle() -> Result {
+    let bundle = Arc::new(git_odb::pack::Bundle::at(
+        fixture_path("objects/pack/pack-a2bf8e71d8c18879e499335762dd95119d93d9f1.idx"),
+        git_hash::Kind::Sha1,
+    )?);
+    let nonblocking::Operation { progress, outcome } =
+        nonblocking::verify_bundle_integrity(bundle.clone(), Default::default(), Duration::from_millis(1));
+    drop(progress);
+    let outcome = runtime()?.block_on(outcome)?;
+    assert_eq!(
+        outcome.actual_index_checksum,
+        bundle.index.index_checksum(),
+        "dropping the progress stream doesn't affect the operation"
+    );
+    Ok(())
+}
+
+#[test]
+fn for_each_object_sees_all_objects() -> Result {
+    let store = store()?;
+    let expected = store.iter()?.count();
+    let seen = Arc::new(AtomicUsize::default());
+    let nonblocking::Operation { progress, outcome } =
+        nonblocking::for_each_object(store, Default::default(), Duration::from_millis(1), {
+            let seen = seen.clone();
+            move |item| {
+                item?;
+                seen.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, git_odb::loose::iter::Error>(())
+            }
+        });
+    let (snapshots, num_objects) =
+        runtime()?.block_on(futures_lite::future::zip(progress.collect::<Vec<_>>(), outcome));
+    assert_eq!(num_objects?, expected);
+    assert_eq!(seen.load(Ordering::SeqCst), expected);
+
+    let last = snapshots.last().expect("the final snapshot is always delivered");
+    let progress = last[0].1.progress.as_ref().expect("the root item has progress");
+    assert_eq!(
+        progress.step.load(Ordering::SeqCst),
+        expected,
+        "the final snapshot is taken after the operation is done"
+    );
+    Ok(())
+}
+
+/// An operation whose `consume` function blocks on the first object until the test allows it to proceed,
+/// to interrupt it deterministically.
+struct Blocked {
+    operation: nonblocking::Operation<
+        std::result::Result<usize, nonblocking::for_each_object::Error<std::convert::Infallible>>,
+    >,
+    started: std::sync::mpsc::Receiver<()>,
+    proceed: std::sync::mpsc::Sender<()>,
+    seen: Arc<AtomicUsize>,
+}
+
+fn blocked_for_each_object() -> Result<Blocked> {
+    let (started_tx, started) = std::sync::mpsc::channel();
+    let (proceed, proceed_rx) = std::sync::mpsc::channel();
+    let seen = Arc::new(AtomicUsize::default());
+    let operation = nonblocking::for_each_object(store()?, Default::default(), Duration::from_millis(1), {
+        let seen = seen.clone();
+        move |_item| {
+            if seen.fetch_add(1, Ordering::SeqCst) == 0 {
+                started_tx.send(()).expect("test waits");
+                proceed_rx.recv().ok();
+            }
+            Ok(())
+        }
+    });
+    Ok(Blocked {
+        operation,
+        started,
+        proceed,
+        seen,
+    })
+}
+
+#[test]
+fn interrupting_the_outcome_fails_the_operation() -> Result {
+    let Blocked {
+        operation: nonblocking::Operation { progress: _, outcome },
+        started,
+        proceed,
+        seen,
+    } = blocked_for_each_object()?;
+    started.recv()?;
+    assert!(!outcome.is_interrupted());
+    outcome.interrupt();
+    proceed.send(())?;
+
+    let err = runtime()?.block_on(outcome).unwrap_err();
+    assert!(matches!(err, nonblocking::for_each_object::Error::Interrupted));
+    assert_eq!(seen.load(Ordering::SeqCst), 1, "no further object was consumed");
+    Ok(())
+}
+
+#[test]
+fn dropping_the_outcome_interrupts_the_operation() -> Result {
+    let Blocked {
+        operation: nonblocking::Operation { progress, outcome },
+        started,
+        proceed,
+        seen,
+    } = blocked_for_each_object()?;
+    started.recv()?;
+    drop(outcome);
+    proceed.send(())?;
+
+    let snapshots = runtime()?.block_on(progress.collect::<Vec<_>>());
+    assert!(
+        !snapshots.is_empty(),
+        "the progress stream ends once the operation stopped"
+    );
+    assert_eq!(seen.load(Ordering::SeqCst), 1, "no further object was consumed");
+    Ok(())
+}
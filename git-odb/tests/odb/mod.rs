@@ -5,5 +5,6 @@ pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 pub mod alternate;
 pub mod find;
 pub mod regression;
+pub mod replicate;
 pub mod sink;
 pub mod store;
@@ -0,0 +1,342 @@
+use std::io;
+
+use git_odb::{replicate, Find};
+use git_testtools::fixture_path;
+
+fn source() -> git_odb::Handle {
+    git_odb::at(fixture_path("objects")).expect("valid object path")
+}
+
+fn empty_destination() -> crate::Result<(git_odb::Handle, tempfile::TempDir)> {
+    let dir = git_testtools::tempfile::tempdir()?;
+    Ok((git_odb::at(dir.path())?, dir))
+}
+
+fn unique_ids(handle: &git_odb::Handle) -> crate::Result<Vec<git_hash::ObjectId>> {
+    let mut ids = handle.iter()?.collect::<Result<Vec<_>, _>>()?;
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// A sink which turns each flush into a frame of its own, like a message queue would.
+#[derive(Default)]
+struct Frames {
+    current: Vec<u8>,
+    frames: Vec<Vec<u8>>,
+}
+
+impl io::Write for Frames {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.current.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.frames.push(std::mem::take(&mut self.current));
+        Ok(())
+    }
+}
+
+fn export(frame_size: usize) -> crate::Result<(replicate::export::Outcome, Vec<Vec<u8>>)> {
+    let mut sink = Frames::default();
+    let outcome = replicate::export(
+        &source(),
+        &mut sink,
+        replicate::export::Options {
+            frame_size,
+            ..Default::default()
+        },
+    )?;
+    assert!(sink.current.is_empty(), "each frame is flushed");
+    Ok((outcome, sink.frames))
+}
+
+#[test]
+fn round_trip_with_frames_smaller_than_most_objects() -> crate::Result {
+    let frame_size = 64;
+    let (exported, frames) = export(frame_size)?;
+    let ids = unique_ids(&source())?;
+
+    assert_eq!(
+        exported.manifest.records,
+        ids.len() as u64,
+        "each object is exported once"
+    );
+    assert_eq!(exported.filtered, 0);
+    assert_eq!(exported.frames as usize, frames.len());
+    assert_eq!(
+        exported.manifest.data_frames + 1,
+        exported.frames,
+        "the manifest comes last"
+    );
+    assert_eq!(
+        exported.bytes_written,
+        frames.iter().map(|f| f.len() as u64).sum::<u64>()
+    );
+    assert!(
+        frames.iter().all(|f| f.len() <= frame_size),
+        "no frame exceeds the configured size"
+    );
+
+    let (dst, _dir) = empty_destination()?;
+    let imported = replicate::import(frames.concat().as_slice(), &dst, Default::default())?;
+    assert_eq!(
+        imported,
+        replicate::import::Outcome {
+            manifest: exported.manifest,
+            frames: exported.frames,
+            objects_written: ids.len() as u64,
+            objects_skipped: 0,
+            bytes_written: exported.manifest.object_bytes,
+        }
+    );
+    assert_eq!(unique_ids(&dst)?, ids);
+
+    let src = source();
+    let (mut a, mut b) = (Vec::new(), Vec::new());
+    for id in ids {
+        let expected = src.try_find(id, &mut a)?.expect("present");
+        let actual = dst.try_find(id, &mut b)?.expect("replicated");
+        assert_eq!(actual.kind, expected.kind);
+        assert_eq!(actual.data, expected.data);
+    }
+
+    let imported = replicate::import(frames.concat().as_slice(), &dst, Default::default())?;
+    assert_eq!(imported.objects_written, 0, "importing again is a no-op…");
+    assert_eq!(
+        imported.objects_skipped, exported.manifest.records,
+        "…as all objects exist"
+    );
+    Ok(())
+}
+
+#[test]
+fn interrupted_imports_can_be_resumed() -> crate::Result {
+    let (exported, frames) = export(256)?;
+    assert!(frames.len() > 4, "need enough frames to interrupt the stream");
+    let (dst, _dir) = empty_destination()?;
+
+    let half = frames.len() / 2;
+    let err = replicate::import(frames[..half].concat().as_slice(), &dst, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, replicate::import::Error::MissingManifest { frames } if frames as usize == half),
+        "{err:?}"
+    );
+    let written_before_interruption = unique_ids(&dst)?.len() as u64;
+    assert!(written_before_interruption > 0);
+
+    let mut partial = frames[..half + 1].concat();
+    partial.truncate(partial.len() - 10);
+    let err = replicate::import(partial.as_slice(), &dst, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, replicate::import::Error::Io { sequence, .. } if sequence as usize == half),
+        "frames may also end abruptly: {err:?}"
+    );
+    let written_before_resumption = unique_ids(&dst)?.len() as u64;
+    assert!(written_before_resumption >= written_before_interruption);
+
+    let imported = replicate::import(frames.concat().as_slice(), &dst, Default::default())?;
+    assert_eq!(imported.objects_skipped, written_before_resumption);
+    assert_eq!(
+        imported.objects_written + imported.objects_skipped,
+        exported.manifest.records
+    );
+    assert_eq!(unique_ids(&dst)?, unique_ids(&source())?);
+    Ok(())
+}
+
+#[test]
+fn filtered_objects_are_not_exported() -> crate::Result {
+    let mut sink = Frames::default();
+    let exported = replicate::export(
+        &source(),
+        &mut sink,
+        replicate::export::Options {
+            frame_size: 1024,
+            filter: |_: &git_hash::oid, kind| kind == git_object::Kind::Blob,
+        },
+    )?;
+    assert!(exported.filtered > 0);
+    assert_eq!(
+        exported.manifest.records + exported.filtered,
+        unique_ids(&source())?.len() as u64
+    );
+
+    let (dst, _dir) = empty_destination()?;
+    let imported = replicate::import(sink.frames.concat().as_slice(), &dst, Default::default())?;
+    assert_eq!(imported.objects_written, exported.manifest.records);
+    let mut buf = Vec::new();
+    for id in unique_ids(&dst)? {
+        assert_eq!(
+            dst.try_find(id, &mut buf)?.expect("present").kind,
+            git_object::Kind::Blob
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn objects_larger_than_the_limit_are_rejected_before_reading_their_data() -> crate::Result {
+    let (exported, frames) = export(64)?;
+    let (dst, _dir) = empty_destination()?;
+    let largest = {
+        let src = source();
+        let mut buf = Vec::new();
+        let mut largest = 0;
+        for id in unique_ids(&src)? {
+            largest = largest.max(src.try_find(id, &mut buf)?.expect("present").data.len() as u64);
+        }
+        largest
+    };
+
+    let err = replicate::import(
+        frames.concat().as_slice(),
+        &dst,
+        replicate::import::Options {
+            max_object_size: Some(largest - 1),
+        },
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, replicate::import::Error::SizeLimitExceeded { size, limit, .. } if size == largest && limit == largest - 1),
+        "{err:?}"
+    );
+
+    let imported = replicate::import(
+        frames.concat().as_slice(),
+        &dst,
+        replicate::import::Options {
+            max_object_size: Some(largest),
+        },
+    )?;
+    assert_eq!(
+        imported.objects_written + imported.objects_skipped,
+        exported.manifest.records
+    );
+    Ok(())
+}
+
+#[test]
+fn records_declaring_more_data_than_the_stream_contains_are_truncated() -> crate::Result {
+    let mut frame = Vec::new();
+    let record = {
+        let mut record = git_hash::ObjectId::null(git_hash::Kind::Sha1).as_slice().to_vec();
+        record.push(3);
+        record.extend_from_slice(&(1u64 << 31).to_be_bytes());
+        record.extend_from_slice(b"only a few bytes");
+        record
+    };
+    for (sequence, (kind, payload)) in [(b'd', record), (b'm', vec![0; 8 + 4 + 8])].into_iter().enumerate() {
+        let start = frame.len();
+        frame.extend_from_slice(b"ODBR");
+        frame.extend_from_slice(&[1, 1, kind]);
+        frame.extend_from_slice(&(sequence as u32).to_be_bytes());
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&payload);
+        let crc = git_features::hash::crc32(&frame[start..]);
+        frame.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    let (dst, _dir) = empty_destination()?;
+    let err = replicate::import(frame.as_slice(), &dst, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, replicate::import::Error::TruncatedRecord { id: Some(_) }),
+        "the declared size isn't allocated upfront, the record merely ends early: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn frames_declaring_huge_payloads_are_rejected_before_allocating() -> crate::Result {
+    let mut frame = Vec::new();
+    frame.extend_from_slice(b"ODBR");
+    frame.extend_from_slice(&[1, 1, b'd']);
+    frame.extend_from_slice(&0u32.to_be_bytes());
+    frame.extend_from_slice(&u32::MAX.to_be_bytes());
+    frame.extend_from_slice(b"only a few bytes");
+
+    let (dst, _dir) = empty_destination()?;
+    let err = replicate::import(
+        frame.as_slice(),
+        &dst,
+        replicate::import::Options {
+            max_object_size: Some(1024),
+        },
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            replicate::import::Error::FrameSizeLimitExceeded {
+                sequence: 0,
+                len,
+                limit,
+            } if len == u64::from(u32::MAX) && limit == 1024 + 20 + 1 + 8
+        ),
+        "{err:?}"
+    );
+
+    let err = replicate::import(frame.as_slice(), &dst, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, replicate::import::Error::Io { sequence: 0, ref source } if source.kind() == io::ErrorKind::UnexpectedEof),
+        "without limit, only the data that is actually received is buffered: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn frames_too_small_to_carry_a_payload_are_rejected() {
+    let err = replicate::export(
+        &source(),
+        io::sink(),
+        replicate::export::Options {
+            frame_size: replicate::FRAME_OVERHEAD,
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, replicate::export::Error::FrameSize { .. }));
+}
+
+#[test]
+fn corrupted_frames_are_rejected() -> crate::Result {
+    let (_exported, frames) = export(512)?;
+    let (dst, _dir) = empty_destination()?;
+
+    let mut tampered = frames.clone();
+    let frame = &mut tampered[0];
+    let data_start = replicate::FRAME_OVERHEAD - 4 + 20 + 1 + 8;
+    frame[data_start] ^= 1;
+    let crc_start = frame.len() - 4;
+    let crc = git_features::hash::crc32(&frame[..crc_start]);
+    frame[crc_start..].copy_from_slice(&crc.to_be_bytes());
+    let err = replicate::import(tampered.concat().as_slice(), &dst, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, replicate::import::Error::IdMismatch { .. }),
+        "the data of each record must hash to its id even if the frame is intact: {err:?}"
+    );
+
+    assert!(
+        unique_ids(&dst)?.is_empty(),
+        "nothing was written as the very first record was invalid"
+    );
+
+    let mut corrupt = frames.clone();
+    *corrupt[1].last_mut().expect("non-empty") ^= 1;
+    let err = replicate::import(corrupt.concat().as_slice(), &dst, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, replicate::import::Error::Checksum { sequence: 1, .. }),
+        "{err:?}"
+    );
+
+    let mut reordered = frames.clone();
+    reordered.swap(0, 1);
+    let err = replicate::import(reordered.concat().as_slice(), &dst, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, replicate::import::Error::Sequence { expected: 0, actual: 1 }),
+        "{err:?}"
+    );
+
+    Ok(())
+}
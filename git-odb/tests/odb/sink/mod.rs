@@ -12,3 +12,33 @@ fn write() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[test]
+fn compressed_and_uncompressed_sinks_agree_with_computed_hashes() -> Result<(), Box<dyn std::error::Error>> {
+    let mut buf = Vec::new();
+    for oid in object_ids() {
+        let obj = locate_oid(oid, &mut buf);
+        assert_eq!(git_object::compute_hash(obj.kind, obj.data, git_hash::Kind::Sha1), oid);
+        for compress in [false, true] {
+            let sink = git_odb::sink(git_hash::Kind::Sha1).compress(compress);
+            assert_eq!(sink.write_buf(obj.kind, obj.data)?, oid, "compress = {}", compress);
+            assert_eq!(
+                sink.write_stream(obj.kind, obj.data.len() as u64, obj.data)?,
+                oid,
+                "the sink can be reused"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn streams_ending_early_are_an_error() {
+    for compress in [false, true] {
+        let err = git_odb::sink(git_hash::Kind::Sha1)
+            .compress(compress)
+            .write_stream(git_object::Kind::Blob, 10, &b"short"[..])
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+}
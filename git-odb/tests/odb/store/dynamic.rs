@@ -41,6 +41,7 @@ fn multi_index_access() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 0,
             open_reachable_indices: 0,
             known_reachable_indices: 0,
@@ -48,8 +49,13 @@ fn multi_index_access() -> crate::Result {
             known_packs: 0,
             unused_slots: 32,
             loose_dbs: 0,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "it starts out knowing nothing, it's completely lazy"
     );
@@ -68,6 +74,7 @@ fn multi_index_access() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 1,
             open_reachable_indices: 1,
             known_reachable_indices: 1,
@@ -75,8 +82,13 @@ fn multi_index_access() -> crate::Result {
             known_packs: 15,
             unused_slots: 31,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "it opened only a single multi-index and its pack - hard to see it's actually a multi-index as it's just one index anyway…"
     );
@@ -88,6 +100,7 @@ fn multi_index_access() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 2,
             open_reachable_indices: 1,
             known_reachable_indices: 1,
@@ -95,8 +108,13 @@ fn multi_index_access() -> crate::Result {
             known_packs: 15,
             unused_slots: 31,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "A miss means just another refresh with no other change"
     );
@@ -111,6 +129,7 @@ fn multi_index_access() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 2 + 1 /*legit refresh with changes*/ + 1 /*a refresh attempt with no changes, causing 'contains()' to give up*/,
             open_reachable_indices: 1,
             known_reachable_indices: 1,
@@ -118,8 +137,13 @@ fn multi_index_access() -> crate::Result {
             known_packs: 15,
             unused_slots: 31,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "everything seems to remain as it was, even though we moved our multi-index to a new slot and removed the old one"
     );
@@ -143,6 +167,7 @@ fn multi_index_keep_open() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 2,
+            num_pinned_handles: 0,
             num_refreshes: 1,
             open_reachable_indices: 1,
             known_reachable_indices: 1,
@@ -150,15 +175,20 @@ fn multi_index_keep_open() -> crate::Result {
             known_packs: 15,
             unused_slots: 31,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "it opened the multi-pack index for iteration"
     );
     let mut buf = Vec::new();
     use git_pack::Find;
     let location = stable_handle
-        .location_by_oid(oid, &mut buf)
+        .location_by_oid(oid, &mut buf)?
         .expect("oid exists and is packed");
 
     let non_existing_to_trigger_refresh = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
@@ -172,6 +202,7 @@ fn multi_index_keep_open() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 2,
+            num_pinned_handles: 0,
             num_refreshes: 3,
             open_reachable_indices: 1,
             known_reachable_indices: 1,
@@ -179,8 +210,13 @@ fn multi_index_keep_open() -> crate::Result {
             known_packs: 15,
             unused_slots: 30,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 1,
-            unreachable_packs: 1
+            unreachable_packs: 1,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "now there is an unreachable index and pack which is still loaded, but whose pack hasn't been loaded"
     );
@@ -273,6 +309,7 @@ fn contains() {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 1,
             open_reachable_indices: 0,
             known_reachable_indices: 3,
@@ -280,8 +317,13 @@ fn contains() {
             known_packs: 3,
             unused_slots: 29,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "it only refreshed the file list, yielding the loose db to find this object, but no pack was opened yet"
     );
@@ -293,6 +335,7 @@ fn contains() {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 1,
             open_reachable_indices: 1,
             known_reachable_indices: 3,
@@ -300,8 +343,13 @@ fn contains() {
             known_packs: 3,
             unused_slots: 29,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "it loaded the biggest back only, which is the first in the list"
     );
@@ -315,6 +363,7 @@ fn contains() {
         new_handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 2,
+            num_pinned_handles: 0,
             num_refreshes: 1,
             open_reachable_indices: 3,
             known_reachable_indices: 3,
@@ -322,8 +371,13 @@ fn contains() {
             known_packs: 3,
             unused_slots: 29,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "when asking for an object in the smallest pack, all in between packs are also loaded."
     );
@@ -333,6 +387,7 @@ fn contains() {
         new_handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 2,
+            num_pinned_handles: 0,
             num_refreshes: 2,
             open_reachable_indices: 3,
             known_reachable_indices: 3,
@@ -340,8 +395,13 @@ fn contains() {
             known_packs: 3,
             unused_slots: 29,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "trigger refreshes each time there is an object miss"
     );
@@ -352,6 +412,7 @@ fn contains() {
         new_handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 2,
+            num_pinned_handles: 0,
             num_refreshes: 2,
             open_reachable_indices: 3,
             known_reachable_indices: 3,
@@ -359,8 +420,13 @@ fn contains() {
             known_packs: 3,
             unused_slots: 29,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "if no refreshes are allowed, there is no additional refresh"
     );
@@ -382,6 +448,7 @@ fn lookup() {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 0,
             open_reachable_indices: 0,
             known_reachable_indices: 0,
@@ -389,8 +456,13 @@ fn lookup() {
             known_packs: 0,
             unused_slots: 32,
             loose_dbs: 0,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "nothing happened yet, the store is totally lazy"
     );
@@ -402,6 +474,7 @@ fn lookup() {
 
     let mut all_loaded = git_odb::store::Metrics {
         num_handles: 1,
+        num_pinned_handles: 0,
         num_refreshes: 1,
         open_reachable_indices: 3,
         known_reachable_indices: 3,
@@ -409,8 +482,13 @@ fn lookup() {
         known_packs: 3,
         unused_slots: 29,
         loose_dbs: 1,
+        extra_alternates: 0,
         unreachable_indices: 0,
         unreachable_packs: 0,
+        orphaned_indices: 0,
+        kept_packs: 0,
+        promisor_packs: 0,
+        untrusted_object_directories: 0,
     };
     assert_eq!(
         handle.store_ref().metrics(),
@@ -444,11 +522,349 @@ fn lookup() {
     );
 }
 
+#[test]
+fn lookup_with_size_limit() {
+    let mut handle = db();
+    let mut buf = Vec::new();
+    let packed = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+    let loose = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+
+    handle.max_decompressed_object_size = Some(1);
+    assert!(matches!(
+        handle.try_find(packed, &mut buf),
+        Err(store::find::Error::Pack(
+            git_odb::pack::data::decode_entry::Error::SizeLimitExceeded { limit: 1, .. }
+        ))
+    ));
+    assert!(matches!(
+        handle.try_find(loose, &mut buf),
+        Err(store::find::Error::Loose(git_odb::loose::find::Error::SizeLimitExceeded { id, limit: 1, .. })) if id == loose
+    ));
+
+    let mut stable_handle = handle.clone();
+    stable_handle.prevent_pack_unload();
+    assert!(
+        matches!(
+            git_pack::Find::location_by_oid(&stable_handle, packed, &mut buf),
+            Err(store::find::Error::Pack(
+                git_odb::pack::data::decode_entry::Error::SizeLimitExceeded { limit: 1, .. }
+            ))
+        ),
+        "oversized objects aren't mistaken for missing ones when locating them"
+    );
+
+    handle.max_decompressed_object_size = None;
+    assert!(handle.try_find(packed, &mut buf).expect("no limit").is_some());
+    assert!(handle.try_find(loose, &mut buf).expect("no limit").is_some());
+}
+
+mod error_kind {
+    use git_odb::{find::ErrorKind, Find, FindExt};
+    use git_testtools::{fixture_path, hex_to_id};
+
+    fn writable_db() -> crate::Result<(git_odb::Handle, tempfile::TempDir)> {
+        let objects_dir = git_testtools::tempfile::tempdir()?;
+        git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+        Ok((git_odb::at(objects_dir.path())?, objects_dir))
+    }
+
+    #[test]
+    fn absent_objects_are_not_found() {
+        let handle = super::db();
+        let mut buf = Vec::new();
+        let id = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+        let err = handle.find(id, &mut buf).unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+
+        let err = handle.find_commit(id, &mut buf).unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn truncated_loose_objects_are_corrupt() -> crate::Result {
+        let (handle, objects_dir) = writable_db()?;
+        let path = objects_dir.path().join("37/d4e6c5c48ba0d245164c4e10d5f41140cab980");
+        let data = std::fs::read(&path)?;
+        std::fs::write(&path, &data[..data.len() / 2])?;
+
+        let mut buf = Vec::new();
+        let err = handle
+            .try_find(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"), &mut buf)
+            .unwrap_err();
+        assert!(!err.is_not_found());
+        assert_eq!(err.kind(), ErrorKind::Corrupt, "{:?}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn corrupt_pack_entries_are_corrupt() -> crate::Result {
+        let (handle, objects_dir) = writable_db()?;
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let pack_path = objects_dir
+            .path()
+            .join("pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack");
+        let index = git_odb::pack::index::File::at(pack_path.with_extension("idx"), git_hash::Kind::Sha1)?;
+        let pack_offset = index.pack_offset_at_index(index.lookup(id).expect("object in pack"));
+        let data_offset = git_odb::pack::data::File::at(&pack_path, git_hash::Kind::Sha1)?
+            .entry(pack_offset)
+            .data_offset as usize;
+        let mut data = std::fs::read(&pack_path)?;
+        data[data_offset..][..8].fill(0xff);
+        std::fs::write(&pack_path, data)?;
+
+        let mut buf = Vec::new();
+        let err = handle.try_find(id, &mut buf).unwrap_err();
+        assert!(!err.is_not_found());
+        assert_eq!(err.kind(), ErrorKind::Corrupt, "{:?}", err);
+        assert_eq!(
+            handle.find(id, &mut buf).unwrap_err().kind(),
+            ErrorKind::Corrupt,
+            "the classification is passed through lookups of existing objects"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn unreadable_loose_directories_are_io_errors() -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+        let (handle, objects_dir) = writable_db()?;
+        let loose_dir = objects_dir.path().join("37");
+        let previous_permissions = std::fs::metadata(&loose_dir)?.permissions();
+        std::fs::set_permissions(&loose_dir, std::fs::Permissions::from_mode(0o000))?;
+        if std::fs::read_dir(&loose_dir).is_ok() {
+            // privileged users can read everything, there is nothing to test.
+            std::fs::set_permissions(&loose_dir, previous_permissions)?;
+            return Ok(());
+        }
+
+        let mut buf = Vec::new();
+        let res = handle.try_find(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"), &mut buf);
+        std::fs::set_permissions(&loose_dir, previous_permissions)?;
+        let err = res.unwrap_err();
+        assert!(!err.is_not_found());
+        assert_eq!(err.kind(), ErrorKind::Io, "{:?}", err);
+        Ok(())
+    }
+}
+
+#[test]
+fn pack_decode_statistics_are_collected_per_pack_only_if_enabled() -> crate::Result {
+    let mut handle = db();
+    let mut buf = Vec::new();
+    let packed = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+    let loose = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+
+    handle.try_find(packed, &mut buf)?.expect("present");
+    assert_eq!(handle.pack_decode_statistics(), None, "collection is off by default");
+
+    handle.collect_pack_decode_statistics(true);
+    assert_eq!(handle.pack_decode_statistics(), Some(Vec::new()));
+    let index = git_odb::pack::index::File::at(
+        fixture_path("objects/pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx"),
+        git_hash::Kind::Sha1,
+    )?;
+    for entry in index.iter() {
+        handle.try_find(entry.oid, &mut buf)?.expect("present");
+    }
+    handle.try_find(loose, &mut buf)?.expect("present");
+
+    let stats = handle.pack_decode_statistics().expect("enabled");
+    assert_eq!(stats.len(), 1, "loose objects aren't counted");
+    let stats = &stats[0];
+    assert_eq!(
+        stats.path,
+        fixture_path("objects/pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack")
+    );
+    assert_eq!(stats.objects, index.num_objects() as u64);
+    assert!(stats.deltas > 0, "the pack contains deltified objects");
+    assert!(stats.inflated_bytes > 0);
+    assert_eq!(stats.cache_hits, 0, "there is no pack cache");
+    assert!(
+        stats.cache_misses > stats.deltas,
+        "the cache is asked for each entry of a delta chain, including the requested one"
+    );
+
+    assert_eq!(
+        handle.clone().pack_decode_statistics(),
+        Some(Vec::new()),
+        "clones collect as well, starting from zero"
+    );
+    handle.collect_pack_decode_statistics(false);
+    assert_eq!(handle.pack_decode_statistics(), None);
+    Ok(())
+}
+
+#[test]
+fn lookup_with_location() -> crate::Result {
+    let handle = db();
+    let mut buf = Vec::new();
+    let packed = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+    let loose = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+
+    let (obj, location) = handle.try_find_with_location(packed, &mut buf)?.expect("present");
+    assert_eq!(obj.kind, git_object::Kind::Commit);
+    let location = location.expect("packed objects have a location");
+    assert_eq!(
+        handle.pack_path(location.pack_id).expect("pack is known"),
+        fixture_path("objects/pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack")
+    );
+    let index = git_odb::pack::index::File::at(
+        fixture_path("objects/pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx"),
+        git_hash::Kind::Sha1,
+    )?;
+    let entry_index = index.lookup(packed).expect("object in index");
+    assert_eq!(location.pack_offset, index.pack_offset_at_index(entry_index));
+    assert!(location.entry_size > 0);
+
+    let (_obj, location) = handle.try_find_with_location(loose, &mut buf)?.expect("present");
+    assert_eq!(location, None, "loose objects have no location");
+
+    assert!(handle
+        .try_find_with_location(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"), &mut buf)?
+        .is_none());
+    assert_eq!(handle.pack_path(u32::MAX), None, "unknown pack ids have no path");
+    Ok(())
+}
+
+#[test]
+fn commit_parents_match_the_ones_of_decoded_commits() -> crate::Result {
+    let mut buf = Vec::new();
+    let mut parents_buf = Vec::new();
+    for handle in [db(), db().with_parents_cache(3), db().with_parents_cache(1).clone()] {
+        let mut num_commits = 0;
+        for _round in 0..2 {
+            for id in handle.iter()? {
+                let id = id?;
+                let object = handle.find(id, &mut buf)?;
+                if object.kind != git_object::Kind::Commit {
+                    assert!(matches!(
+                        handle.commit_parents(id, &mut parents_buf),
+                        Err(git_odb::cache::commit_parents::Error::ObjectKind { actual, .. }) if actual == object.kind
+                    ));
+                    continue;
+                }
+                let expected: Vec<_> = object.decode()?.into_commit().expect("commit").parents().collect();
+                assert_eq!(handle.commit_parents(id, &mut parents_buf)?.into_vec(), expected);
+                num_commits += 1;
+            }
+        }
+        assert_eq!(
+            num_commits,
+            2 * 27,
+            "each commit is seen twice, possibly served from the cache"
+        );
+    }
+
+    let missing = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    assert!(matches!(
+        db().commit_parents(missing, &mut buf),
+        Err(git_odb::cache::commit_parents::Error::NotFound { oid }) if oid == missing
+    ));
+    Ok(())
+}
+
+#[test]
+fn find_many_yields_the_same_objects_as_finding_them_one_by_one() -> crate::Result {
+    let handle = db().into_arc()?;
+    let missing = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    let mut ids = handle.iter()?.collect::<Result<Vec<_>, _>>()?;
+    ids.insert(ids.len() / 2, missing);
+
+    let mut buf = Vec::new();
+    let expected: Vec<_> = ids
+        .iter()
+        .map(|id| {
+            handle
+                .try_find(id, &mut buf)
+                .expect("no read error")
+                .map(|obj| (*id, obj.kind, obj.data.to_owned()))
+        })
+        .collect();
+
+    for thread_limit in [Some(1), Some(4)] {
+        let options = || git_odb::store::find_many::Options {
+            thread_limit,
+            ..Default::default()
+        };
+        let in_order: Vec<_> = handle
+            .find_many_in_order(&ids, options())
+            .map(|res| match res {
+                Ok(obj) => Some(obj),
+                Err(git_odb::store::find_many::Error::NotFound { id }) => {
+                    assert_eq!(id, missing, "errors are attributed to the object that caused them");
+                    None
+                }
+                Err(err) => panic!("unexpected error: {}", err),
+            })
+            .collect();
+        assert_eq!(in_order, expected, "{:?}", thread_limit);
+
+        let mut unordered: Vec<_> = handle.find_many(&ids, options()).filter_map(Result::ok).collect();
+        unordered.sort_by_key(|(id, _, _)| *id);
+        let mut expected: Vec<_> = expected.iter().flatten().cloned().collect();
+        expected.sort_by_key(|(id, _, _)| *id);
+        assert_eq!(
+            unordered, expected,
+            "{:?}: all objects are returned in some order",
+            thread_limit
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn positioned_reads_yield_the_same_objects_as_memory_maps() -> crate::Result {
+    let mapped = db();
+    let store = git_odb::Store::at_opts(
+        fixture_path("objects"),
+        None,
+        git_odb::store::init::Options {
+            pack_access: git_odb::pack::data::Access::PositionedRead {
+                block_size: 16,
+                capacity: 4,
+            },
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        store.pack_access(),
+        git_odb::pack::data::Access::PositionedRead {
+            block_size: 16,
+            capacity: 4
+        }
+    );
+    let handle = git_features::threading::OwnShared::new(store).to_cache();
+
+    let (mut buf, mut expected_buf) = (Vec::new(), Vec::new());
+    let mut count = 0;
+    for id in mapped.iter()? {
+        let id = id?;
+        let expected = mapped.find(id, &mut expected_buf)?;
+        let actual = handle.find(id, &mut buf)?;
+        assert_eq!(actual.kind, expected.kind);
+        assert_eq!(actual.data, expected.data, "{}", id);
+        count += 1;
+    }
+    assert!(count > 0);
+
+    let outcome = handle.store_ref().verify_integrity(
+        &mut git_features::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert!(!outcome.index_statistics.is_empty(), "packs can be verified as well");
+    Ok(())
+}
+
 fn assert_all_indices_loaded(handle: &git_odb::Handle, num_refreshes: usize, open_reachable_indices: usize) {
     assert_eq!(
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes,
             open_reachable_indices,
             known_reachable_indices: 2,
@@ -456,8 +872,13 @@ fn assert_all_indices_loaded(handle: &git_odb::Handle, num_refreshes: usize, ope
             known_packs: 3,
             unused_slots: 30,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "all indices must be loaded and searched to assure unambiguous object ids"
     );
@@ -528,12 +949,14 @@ mod disambiguate_prefix {
             handle.store_ref().metrics(),
             git_odb::store::Metrics {
                 num_handles: 1,
+                num_pinned_handles: 0,
                 num_refreshes: 1,
                 open_reachable_indices: 1,
                 known_reachable_indices: 2,
                 known_packs: 3,
                 unused_slots: 30,
                 loose_dbs: 1,
+                extra_alternates: 0,
                 ..Default::default()
             },
             "early bailout, without doing any real work except for a contains() check"
@@ -635,6 +1058,7 @@ fn missing_objects_triggers_everything_is_loaded() {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 2,
             open_reachable_indices: 3,
             known_reachable_indices: 3,
@@ -642,8 +1066,13 @@ fn missing_objects_triggers_everything_is_loaded() {
             known_packs: 3,
             unused_slots: 29,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "first refresh triggered by on-disk check, second refresh triggered to see if something changed, contains() only sees indices"
     );
@@ -657,6 +1086,7 @@ fn missing_objects_triggers_everything_is_loaded() {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 3,
             open_reachable_indices: 3,
             known_reachable_indices: 3,
@@ -664,8 +1094,13 @@ fn missing_objects_triggers_everything_is_loaded() {
             known_packs: 3,
             unused_slots: 29,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "there are still no packs opened as no index contained the object"
     );
@@ -687,6 +1122,205 @@ fn iterate_over_a_bunch_of_loose_and_packed_objects() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn sharded_iterations_are_the_same_as_a_full_iteration() -> crate::Result {
+    let (db, _tmp) = db_with_all_object_sources()?;
+    for ordering in [
+        store::iter::Ordering::PackThenLoose,
+        store::iter::Ordering::Lexicographical,
+    ] {
+        let iter = || {
+            db.iter_with_options(store::iter::Options {
+                ordering,
+                ..Default::default()
+            })
+        };
+        let all: Vec<_> = iter()?.collect::<Result<_, _>>()?;
+        assert_eq!(
+            iter()?.collect::<Result<Vec<_>, _>>()?,
+            all,
+            "{:?}: the order is stable",
+            ordering
+        );
+
+        for shard_size in [1, 7, 139, all.len() + 1] {
+            let mut sharded = Vec::new();
+            for start in (0..all.len()).step_by(shard_size) {
+                for id in iter()?.skip(start).take(shard_size) {
+                    sharded.push(id?);
+                }
+            }
+            assert_eq!(sharded, all, "{:?}: shards of size {} add up", ordering, shard_size);
+        }
+
+        for position in [0, 1, 138, 139, 140, all.len() - 1, all.len(), all.len() + 10] {
+            assert_eq!(
+                iter()?.nth(position).transpose()?,
+                all.get(position).copied(),
+                "{:?}: nth({}) within packs, across into loose objects and past the end",
+                ordering,
+                position
+            );
+        }
+
+        let mut iter = iter()?;
+        iter.seek(140);
+        assert_eq!(
+            iter.next().transpose()?,
+            Some(all[140]),
+            "{:?}: seeking forward",
+            ordering
+        );
+        iter.seek(3);
+        assert_eq!(
+            iter.next().transpose()?,
+            Some(all[3]),
+            "{:?}: seeking backward",
+            ordering
+        );
+        iter.seek(0);
+        assert_eq!(
+            iter.collect::<Result<Vec<_>, _>>()?,
+            all,
+            "{:?}: seeking to the start iterates everything",
+            ordering
+        );
+    }
+    Ok(())
+}
+
+mod iter_with_data {
+    use std::sync::atomic::AtomicBool;
+
+    use git_odb::{store, Find};
+
+    use super::db_with_all_object_sources;
+
+    fn hash_of(kind: git_object::Kind, data: &[u8]) -> git_hash::ObjectId {
+        let mut hasher = git_features::hash::hasher(git_hash::Kind::Sha1);
+        hasher.update(&git_object::encode::loose_header(kind, data.len() as u64));
+        hasher.update(data);
+        git_hash::ObjectId::from(hasher.digest())
+    }
+
+    #[test]
+    fn payloads_hash_to_their_id_for_all_packed_and_loose_objects() -> crate::Result {
+        let (db, _tmp) = db_with_all_object_sources()?;
+        let mut expected: Vec<_> = db.iter()?.collect::<Result<_, _>>()?;
+        expected.sort();
+
+        let mut actual = Vec::new();
+        for item in db.iter_with_data(&AtomicBool::default(), Default::default())? {
+            let (id, kind, data) = item?;
+            assert_eq!(
+                hash_of(kind, &data),
+                id,
+                "the payload belongs to the id it is returned with"
+            );
+            actual.push(id);
+        }
+        actual.sort();
+        assert_eq!(actual, expected, "the same objects are seen as when iterating ids");
+        Ok(())
+    }
+
+    #[test]
+    fn kinds_can_be_filtered() -> crate::Result {
+        let (db, _tmp) = db_with_all_object_sources()?;
+        let mut buf = Vec::new();
+        let mut expected = Vec::new();
+        for id in db.iter()? {
+            let id = id?;
+            let kind = db.try_find(id, &mut buf)?.expect("present").kind;
+            if matches!(kind, git_object::Kind::Commit | git_object::Kind::Tag) {
+                expected.push(id);
+            }
+        }
+        expected.sort();
+
+        let mut actual = Vec::new();
+        for item in db.iter_with_data(
+            &AtomicBool::default(),
+            store::iter_with_data::Options {
+                kinds: Some(vec![git_object::Kind::Commit, git_object::Kind::Tag]),
+                channel_capacity: 1,
+                ..Default::default()
+            },
+        )? {
+            let (id, kind, _data) = item?;
+            assert!(matches!(kind, git_object::Kind::Commit | git_object::Kind::Tag));
+            actual.push(id);
+        }
+        actual.sort();
+        assert!(!actual.is_empty());
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn interrupts_and_early_drops_stop_the_iteration() -> crate::Result {
+        let (db, _tmp) = db_with_all_object_sources()?;
+        let should_interrupt = AtomicBool::new(true);
+        let mut iter = db.iter_with_data(&should_interrupt, Default::default())?;
+        assert!(matches!(
+            iter.next(),
+            Some(Err(store::iter_with_data::Error::Interrupted))
+        ));
+        assert!(iter.next().is_none(), "nothing is returned after an interrupt");
+
+        let first_few = db
+            .iter_with_data(
+                &AtomicBool::default(),
+                store::iter_with_data::Options {
+                    channel_capacity: 0,
+                    ..Default::default()
+                },
+            )?
+            .take(3)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            first_few.len(),
+            3,
+            "dropping the iterator stops the thread decoding objects"
+        );
+        Ok(())
+    }
+}
+
+#[test]
+fn iterate_in_lexicographical_order_across_all_sources() -> crate::Result {
+    let (db, tmp) = db_with_all_object_sources()?;
+    // Make packed objects loose as well so that sources overlap.
+    let some_packed_ids: Vec<_> = db.iter()?.take(10).collect::<Result<_, _>>()?;
+    let loose = git_odb::loose::Store::at(tmp.path(), git_hash::Kind::Sha1);
+    let mut buf = Vec::new();
+    for id in &some_packed_ids {
+        let obj = db.find(id, &mut buf)?;
+        loose.write_buf(obj.kind, obj.data)?;
+    }
+
+    let mut expected: Vec<_> = db.iter()?.collect::<Result<_, _>>()?;
+    assert!(
+        expected.len() > 146,
+        "some objects are seen multiple times in the default ordering"
+    );
+    expected.sort();
+    expected.dedup();
+
+    let actual: Vec<_> = db
+        .iter_with_options(store::iter::Options {
+            ordering: store::iter::Ordering::Lexicographical,
+            ..Default::default()
+        })?
+        .collect::<Result<_, _>>()?;
+    assert_eq!(actual.len(), 146, "duplicates are collapsed");
+    assert_eq!(
+        actual, expected,
+        "the merged output is the same as collecting and sorting"
+    );
+    Ok(())
+}
+
 #[test]
 fn auto_refresh_with_and_without_id_stability() -> crate::Result {
     let tmp = git_testtools::tempfile::TempDir::new()?;
@@ -726,6 +1360,7 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 1,
             open_reachable_indices: 1,
             known_reachable_indices: 1,
@@ -733,8 +1368,13 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             known_packs: 1,
             unused_slots: 31,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "one pack was opened"
     );
@@ -751,6 +1391,7 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 2,
             open_reachable_indices: 1,
             known_reachable_indices: 1,
@@ -758,8 +1399,13 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             known_packs: 1,
             unused_slots: 31,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 0,
-            unreachable_packs: 0
+            unreachable_packs: 0,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "the old pack was removed, the new was loaded"
     );
@@ -769,7 +1415,7 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
         let mut stable_handle = handle.clone();
         stable_handle.prevent_pack_unload();
         let location = stable_handle
-            .location_by_oid(hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"), &mut buf)
+            .location_by_oid(hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"), &mut buf)?
             .expect("object exists");
         assert!(
             stable_handle.entry_by_location(&location).is_some(),
@@ -781,7 +1427,7 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
 
         assert!(
             stable_handle
-                .location_by_oid(hex_to_id("4dac9989f96bc5b5b1263b582c08f0c5f0b58542"), &mut buf)
+                .location_by_oid(hex_to_id("4dac9989f96bc5b5b1263b582c08f0c5f0b58542"), &mut buf)?
                 .is_some(),
             "it finds the object in the newly unhidden pack, which also triggers a refresh providing it with new indices"
         );
@@ -789,6 +1435,7 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             handle.store_ref().metrics(),
             git_odb::store::Metrics {
                 num_handles: 2,
+                num_pinned_handles: 0,
                 num_refreshes: 3,
                 open_reachable_indices: 1,
                 known_reachable_indices: 1,
@@ -796,8 +1443,13 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
                 known_packs: 1,
                 unused_slots: 30,
                 loose_dbs: 1,
+                extra_alternates: 0,
                 unreachable_indices: 1,
-                unreachable_packs: 1
+                unreachable_packs: 1,
+                orphaned_indices: 0,
+                kept_packs: 0,
+                promisor_packs: 0,
+                untrusted_object_directories: 0
             },
             "the removed pack is still loaded"
         );
@@ -824,6 +1476,7 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
         handle.store_ref().metrics(),
         git_odb::store::Metrics {
             num_handles: 1,
+            num_pinned_handles: 0,
             num_refreshes: 4,
             open_reachable_indices: 1,
             known_reachable_indices: 1,
@@ -831,14 +1484,72 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
             known_packs: 1,
             unused_slots: 30,
             loose_dbs: 1,
+            extra_alternates: 0,
             unreachable_indices: 1,
-            unreachable_packs: 1
+            unreachable_packs: 1,
+            orphaned_indices: 0,
+            kept_packs: 0,
+            promisor_packs: 0,
+            untrusted_object_directories: 0
         },
         "garbaged slots aren't reclaimed until there is the need. Keeping indices open despite them not being accessible anymore."
     );
     Ok(())
 }
 
+#[test]
+fn pinned_snapshots_remain_consistent_while_packs_are_deleted() -> crate::Result {
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let handle = git_odb::at(objects_dir.path())?;
+    assert!(!handle.is_pinned());
+
+    let mut buf = Vec::new();
+    {
+        let _pin = handle.pin_snapshot()?;
+        let _nested_pin = handle.pin_snapshot()?;
+        assert!(handle.is_pinned());
+        let metrics = handle.store_ref().metrics();
+        assert_eq!(metrics.num_pinned_handles, 1, "pins are counted per handle");
+        assert_eq!(
+            metrics.open_reachable_packs, metrics.known_packs,
+            "all packs are mapped when pinning"
+        );
+
+        let ids = handle.iter()?.collect::<Result<Vec<_>, _>>()?;
+        assert!(
+            ids.len() > handle.packed_object_count()? as usize,
+            "all packed objects and loose ones"
+        );
+
+        assert!(
+            !handle.contains(hex_to_id("ffffffffffffffffffffffffffffffffffffffff")),
+            "missing objects"
+        );
+        assert_eq!(
+            handle.store_ref().metrics().num_refreshes,
+            metrics.num_refreshes,
+            "…don't cause refreshes while pinned"
+        );
+
+        if cfg!(unix) {
+            for entry in std::fs::read_dir(objects_dir.path().join("pack"))? {
+                std::fs::remove_file(entry?.path())?;
+            }
+        }
+        for id in &ids {
+            assert!(
+                handle.find(id, &mut buf).is_ok(),
+                "{} is still found after deleting packs",
+                id
+            );
+        }
+    }
+    assert!(!handle.is_pinned());
+    assert_eq!(handle.store_ref().metrics().num_pinned_handles, 0);
+    Ok(())
+}
+
 mod verify {
     use std::sync::atomic::AtomicBool;
 
@@ -852,7 +1563,7 @@ mod verify {
         let handle = db();
         let outcome = handle
             .store_ref()
-            .verify_integrity(progress::Discard, &AtomicBool::new(false), Default::default())
+            .verify_integrity(&mut progress::Discard, &AtomicBool::new(false), Default::default())
             .unwrap();
         assert_eq!(outcome.index_statistics.len(), 3, "there are only three packs to check");
         assert_eq!(
@@ -871,7 +1582,10 @@ mod verify {
             outcome.loose_object_stores,
             vec![git_odb::store::verify::integrity::LooseObjectStatistics {
                 path: fixture_path("objects"),
-                statistics: git_odb::loose::verify::integrity::Statistics { num_objects: 7 }
+                statistics: git_odb::loose::verify::integrity::Statistics {
+                    num_objects: 7,
+                    warnings_by_class: Default::default(),
+                }
             }]
         );
 
@@ -879,6 +1593,7 @@ mod verify {
             handle.store_ref().metrics(),
             git_odb::store::Metrics {
                 num_handles: 1,
+                num_pinned_handles: 0,
                 num_refreshes: 1,
                 open_reachable_indices: 0,
                 known_reachable_indices: 3,
@@ -886,10 +1601,1069 @@ mod verify {
                 known_packs: 3,
                 unused_slots: 29,
                 loose_dbs: 1,
+                extra_alternates: 0,
                 unreachable_indices: 0,
-                unreachable_packs: 0
+                unreachable_packs: 0,
+                orphaned_indices: 0,
+                kept_packs: 0,
+                promisor_packs: 0,
+                untrusted_object_directories: 0
             },
             "verification only discovers files on disk but won't cause them to be opened permanently"
         );
     }
+
+    #[test]
+    fn progress_snapshots_name_all_steps_and_count_monotonically() -> crate::Result {
+        let snapshots = std::sync::Arc::new(std::sync::Mutex::new(Vec::<progress::Snapshot>::new()));
+        let mut progress = progress::Callback::new("verify", std::time::Duration::ZERO, {
+            let snapshots = snapshots.clone();
+            move |snapshot| snapshots.lock().unwrap().push(snapshot)
+        });
+        db().store_ref()
+            .verify_integrity(&mut progress, &AtomicBool::new(false), Default::default())?;
+        drop(progress);
+        let snapshots = snapshots.lock().unwrap();
+
+        let ids = |id: &'static [u8; 4]| snapshots.iter().filter(move |s| &s.id == id);
+        let index_names: Vec<_> = ids(b"VIPI")
+            .map(|s| s.path.last().expect("named").as_str())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        assert_eq!(
+            index_names,
+            [
+                "Checking integrity: pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx",
+                "Checking integrity: pack-a2bf8e71d8c18879e499335762dd95119d93d9f1.idx",
+                "Checking integrity: pack-c0438c19fb16422b6bbcce24387b3264416d485b.idx"
+            ],
+            "each index is named from the start"
+        );
+        for id in [b"PTHP", b"PTHI", b"PTRO"] {
+            assert!(
+                ids(id).all(|s| s.path.len() == 3 && s.path[1].starts_with("Checking integrity: ")),
+                "{:?} is a child of the verification of an index",
+                id
+            );
+            assert!(ids(id).next().is_some());
+        }
+        let loose_objects = ids(b"VILO").last().expect("loose objects are validated");
+        assert_eq!(
+            loose_objects.path,
+            ["verify", &fixture_path("objects").display().to_string(), "Validating"]
+        );
+        assert_eq!(loose_objects.step, 7, "the last snapshot has the final count");
+        assert_eq!(loose_objects.unit.as_deref(), Some("loose objects"));
+
+        let mut last_step_by_task = std::collections::BTreeMap::new();
+        for snapshot in snapshots.iter().filter(|s| s.message.is_none()) {
+            let task = (snapshot.path.clone(), snapshot.id);
+            if let Some(previous) = last_step_by_task.insert(task, (snapshot.step, snapshot.max)) {
+                assert!(
+                    previous.0 <= snapshot.step || previous.1 != snapshot.max,
+                    "counters only go up unless a task is re-initialized: {:?}",
+                    snapshot
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn objects_are_counted_by_warning_class_across_packs_and_loose_objects() -> crate::Result {
+        use git_object::verify::WarningClass;
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_objects_with_warnings.sh")?;
+        let store = git_odb::at(dir.join("objects"))?;
+        let outcome = store.store_ref().verify_integrity(
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+            git_odb::pack::index::verify::integrity::Options {
+                tree_checks: git_object::tree::check::Options::strict(),
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(
+            outcome.warnings_by_class.into_iter().collect::<Vec<_>>(),
+            vec![
+                (WarningClass::NonCanonicalTreeEntryMode, 2),
+                (WarningClass::ZeroPaddedFilemode, 2)
+            ],
+            "one tree with a non-canonical mode is packed and one is loose, and trees with more than one zero-padded mode count once"
+        );
+        assert_eq!(
+            outcome.index_statistics.len(),
+            1,
+            "the zero-padded trees are only in the pack as they fail the loose object roundtrip"
+        );
+        assert_eq!(
+            outcome.loose_object_stores[0]
+                .statistics
+                .warnings_by_class
+                .get(&WarningClass::NonCanonicalTreeEntryMode),
+            Some(&1)
+        );
+        assert_eq!(outcome.tree_issues.len(), 2);
+        Ok(())
+    }
+}
+
+#[test]
+fn memory_bundles_are_used_like_packs_on_disk() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let index_path = fixture_path("objects/pack/pack-a2bf8e71d8c18879e499335762dd95119d93d9f1.idx");
+    let bundle = (
+        git_odb::pack::index::File::from_bytes(std::fs::read(&index_path)?, git_hash::Kind::Sha1)?,
+        git_odb::pack::data::File::from_bytes(std::fs::read(index_path.with_extension("pack"))?, git_hash::Kind::Sha1)?,
+    );
+    let num_objects = bundle.0.num_objects() as usize;
+    let store = git_odb::Store::at_opts(
+        dir.path(),
+        None,
+        git_odb::store::init::Options {
+            slots: git_odb::store::init::Slots::Given(1),
+            ..Default::default()
+        },
+    )?
+    .with_memory_bundles(vec![bundle])?;
+    let handle = git_features::threading::OwnShared::new(store).to_cache();
+
+    let mut buf = Vec::new();
+    let mut count = 0;
+    for id in handle.iter()? {
+        let id = id?;
+        assert!(handle.contains(id));
+        handle.find(id, &mut buf)?;
+        count += 1;
+    }
+    assert_eq!(count, num_objects, "all objects are found in the memory bundle");
+
+    let missing = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    assert!(
+        !handle.contains(missing),
+        "refreshes don't lose the memory bundle as it's not on disk"
+    );
+    assert!(handle.contains(handle.iter()?.next().expect("at least one object")?));
+
+    let outcome = handle.store_ref().verify_integrity(
+        &mut git_features::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert_eq!(outcome.index_statistics.len(), 1, "the memory bundle can be verified");
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn unreadable_pack_directories_are_skipped_with_warnings_unless_strict() -> crate::Result {
+    use std::os::unix::fs::PermissionsExt;
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let pack_dir = objects_dir.path().join("pack");
+    let previous_permissions = std::fs::metadata(&pack_dir)?.permissions();
+    std::fs::set_permissions(&pack_dir, std::fs::Permissions::from_mode(0o000))?;
+    if std::fs::read_dir(&pack_dir).is_ok() {
+        // privileged users can read everything, there is nothing to test.
+        std::fs::set_permissions(&pack_dir, previous_permissions)?;
+        return Ok(());
+    }
+
+    let handle = git_odb::at(objects_dir.path())?;
+    assert_eq!(handle.iter()?.count(), 7, "only loose objects are seen");
+    let warnings = handle.store_ref().warnings();
+    assert_eq!(warnings.len(), 1, "the unreadable pack directory is recorded");
+    assert_eq!(warnings[0].path, pack_dir);
+    assert_eq!(warnings[0].kind, std::io::ErrorKind::PermissionDenied);
+    assert!(warnings[0].raw_os_error.is_some());
+
+    let err = git_odb::at_opts(
+        objects_dir.path(),
+        None,
+        git_odb::store::init::Options {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .err()
+    .expect("strict mode fails fast");
+    assert!(
+        err.to_string().contains(&*pack_dir.to_string_lossy()),
+        "the error mentions the path: {}",
+        err
+    );
+
+    std::fs::set_permissions(&pack_dir, previous_permissions)?;
+    Ok(())
+}
+
+#[test]
+fn untrusted_object_directories_are_skipped_with_warnings_unless_allowed() -> crate::Result {
+    use git_odb::store::{init::TrustPolicy, ObjectDirectory};
+    use git_sec::{Permission, Trust};
+
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    let (objects_dir, alternate_dir) = crate::odb::alternate::alternate(tmp.path().join("a"), tmp.path().join("b"))?;
+    let id =
+        git_odb::loose::Store::at(&alternate_dir, git_hash::Kind::Sha1).write_buf(git_object::Kind::Blob, b"42")?;
+    let open = |trust_policy: TrustPolicy| {
+        git_odb::at_opts(
+            &objects_dir,
+            None,
+            git_odb::store::init::Options {
+                trust_policy,
+                ..Default::default()
+            },
+        )
+    };
+    let alternate_is_untrusted = TrustPolicy {
+        trust_of: |path| {
+            Ok(if path.ends_with("b") {
+                Trust::Reduced
+            } else {
+                Trust::Full
+            })
+        },
+        ..Default::default()
+    };
+
+    let handle = open(alternate_is_untrusted.clone())?;
+    assert!(!handle.contains(id), "objects in untrusted directories are invisible");
+    let store = handle.store_ref();
+    assert_eq!(
+        store.object_directories(),
+        vec![
+            ObjectDirectory {
+                path: objects_dir.clone(),
+                trust: Trust::Full,
+                used: true
+            },
+            ObjectDirectory {
+                path: alternate_dir.clone(),
+                trust: Trust::Reduced,
+                used: false
+            }
+        ]
+    );
+    assert_eq!(store.metrics().untrusted_object_directories, 1);
+    assert_eq!(store.metrics().loose_dbs, 1);
+    let warnings = store.warnings();
+    assert_eq!(warnings.len(), 1, "the skipped directory is recorded");
+    assert_eq!(warnings[0].path, alternate_dir);
+    assert_eq!(warnings[0].kind, std::io::ErrorKind::PermissionDenied);
+
+    let handle = open(TrustPolicy {
+        reduced: Permission::Allow,
+        ..alternate_is_untrusted.clone()
+    })?;
+    assert!(handle.contains(id), "untrusted directories are used if allowed");
+    let store = handle.store_ref();
+    assert_eq!(store.metrics().untrusted_object_directories, 1);
+    assert_eq!(store.metrics().loose_dbs, 2);
+    assert!(store.object_directories().iter().all(|dir| dir.used));
+    assert!(store.warnings().is_empty());
+
+    let err = open(TrustPolicy {
+        reduced: Permission::Forbid,
+        ..alternate_is_untrusted
+    })
+    .err()
+    .expect("untrusted directories are an error if forbidden");
+    assert!(
+        err.to_string().contains(&*alternate_dir.to_string_lossy()),
+        "the error mentions the path: {}",
+        err
+    );
+
+    let handle = open(TrustPolicy {
+        objects_directory: Some(Trust::Reduced),
+        reduced: Permission::Allow,
+        ..Default::default()
+    })?;
+    assert!(handle.contains(id));
+    assert_eq!(
+        handle.store_ref().object_directories()[0].trust,
+        Trust::Reduced,
+        "the trust level of the objects directory can be passed in from repository discovery"
+    );
+
+    let handle = open(TrustPolicy {
+        objects_directory: Some(Trust::Reduced),
+        ..Default::default()
+    })?;
+    assert!(
+        handle.contains(id),
+        "alternates are classified by themselves and remain usable even if the objects directory is untrusted"
+    );
+    assert_eq!(handle.store_ref().metrics().loose_dbs, 1);
+    Ok(())
+}
+
+#[test]
+fn packs_with_keep_file_are_recorded_as_kept() -> crate::Result {
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let pack_dir = objects_dir.path().join("pack");
+    let kept_pack_path = pack_dir.join("pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack");
+    let normal_pack_path = pack_dir.join("pack-a2bf8e71d8c18879e499335762dd95119d93d9f1.pack");
+    std::fs::write(
+        kept_pack_path.with_extension("keep"),
+        "receive-pack 42 on host\nsecond line\n",
+    )?;
+    std::fs::write(pack_dir.join("pack-c0438c19fb16422b6bbcce24387b3264416d485b.keep"), "")?;
+    std::fs::write(
+        pack_dir.join("pack-0000000000000000000000000000000000000000.keep"),
+        "no pack",
+    )?;
+
+    let mut handle = git_odb::at(objects_dir.path())?;
+    handle.prevent_pack_unload();
+    let store = handle.store_ref();
+    let empty_kept_pack_path = pack_dir.join("pack-c0438c19fb16422b6bbcce24387b3264416d485b.pack");
+    assert_eq!(
+        store.kept_packs(),
+        vec![kept_pack_path.clone(), empty_kept_pack_path.clone()],
+        "keep files without a pack are ignored"
+    );
+    assert_eq!(store.metrics().kept_packs, 2);
+    assert_eq!(
+        store.kept_pack(&kept_pack_path),
+        Some(git_odb::store::KeptPack {
+            path: kept_pack_path.clone(),
+            reason: Some("receive-pack 42 on host".into())
+        }),
+        "the first line is the reason"
+    );
+    assert_eq!(
+        store.kept_pack(&empty_kept_pack_path).expect("kept").reason,
+        None,
+        "empty keep files have no reason"
+    );
+    assert_eq!(store.kept_pack(&normal_pack_path), None);
+
+    let mut buf = Vec::new();
+    for (pack_path, expected) in [(&kept_pack_path, true), (&normal_pack_path, false)] {
+        use git_pack::Find;
+        let id = git_odb::pack::index::File::at(pack_path.with_extension("idx"), git_hash::Kind::Sha1)?
+            .oid_at_index(0)
+            .to_owned();
+        let location = handle.location_by_oid(id, &mut buf)?.expect("packed object");
+        assert_eq!(
+            handle.is_kept_pack(location.pack_id),
+            expected,
+            "locations can be checked for being in a kept pack"
+        );
+    }
+
+    std::fs::remove_file(kept_pack_path.with_extension("keep"))?;
+    assert!(
+        handle
+            .try_find(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"), &mut buf)?
+            .is_none(),
+        "missing objects trigger a refresh"
+    );
+    assert_eq!(
+        store.kept_packs(),
+        vec![empty_kept_pack_path],
+        "changes are picked up when the object database is scanned again"
+    );
+    Ok(())
+}
+
+#[test]
+fn packs_with_promisor_file_are_recorded_along_with_their_refs() -> crate::Result {
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let pack_dir = objects_dir.path().join("pack");
+    let promisor_pack_path = pack_dir.join("pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack");
+    let empty_promisor_pack_path = pack_dir.join("pack-c0438c19fb16422b6bbcce24387b3264416d485b.pack");
+    std::fs::write(
+        promisor_pack_path.with_extension("promisor"),
+        "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa refs/heads/main\nnot-a-hash refs/heads/other\n\nbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb HEAD\r\n",
+    )?;
+    std::fs::write(empty_promisor_pack_path.with_extension("promisor"), "")?;
+
+    let handle = git_odb::at(objects_dir.path())?;
+    let store = handle.store_ref();
+    assert_eq!(
+        store.promisor_packs(),
+        vec![promisor_pack_path.clone(), empty_promisor_pack_path.clone()]
+    );
+    assert_eq!(store.metrics().promisor_packs, 2);
+    assert_eq!(
+        store.promisor_pack(&promisor_pack_path),
+        Some(git_odb::store::PromisorPack {
+            path: promisor_pack_path.clone(),
+            refs: vec![
+                (
+                    hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+                    "refs/heads/main".into()
+                ),
+                (hex_to_id("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"), "HEAD".into())
+            ]
+        }),
+        "lines that don't start with a hash are skipped"
+    );
+    assert_eq!(
+        store.promisor_pack(&empty_promisor_pack_path).expect("promisor").refs,
+        Vec::new(),
+        "older versions of git write empty promisor files"
+    );
+    assert_eq!(
+        store.promisor_ref("refs/heads/main"),
+        Some(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"))
+    );
+    assert_eq!(store.promisor_ref("refs/heads/other"), None);
+    Ok(())
+}
+
+#[test]
+fn partial_clones_report_missing_objects_as_promised_if_configured() -> crate::Result {
+    use git_object::bstr::ByteSlice;
+    use git_odb::{find::ErrorKind, store::MissingObjectPolicy};
+
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_partial_clones.sh")?;
+    let base = git_odb::at(dir.join("base/.git/objects"))?;
+    let tag_id = ObjectId::from_hex(std::fs::read(dir.join("base/.git/refs/tags/v1"))?.trim())?;
+    let mut buf = Vec::new();
+    for (clone, missing_kinds, num_missing) in [
+        ("blobless.git", &[git_object::Kind::Blob][..], 3),
+        ("treeless.git", &[git_object::Kind::Tree, git_object::Kind::Blob], 6),
+    ] {
+        let mut handle = git_odb::at(dir.join(clone).join("objects"))?;
+        handle.prevent_pack_unload();
+        let store = handle.store_ref();
+        assert_eq!(
+            store.promisor_packs().len(),
+            1,
+            "{}: fetches with a filter produce promisor packs",
+            clone
+        );
+        assert_eq!(store.metrics().promisor_packs, 1);
+        assert_eq!(store.promisor_ref("refs/tags/v1"), Some(tag_id), "refs are recorded");
+
+        let location = git_pack::Find::location_by_oid(&handle, tag_id, &mut buf)?.expect("tags are always fetched");
+        assert!(handle.is_promisor_pack(location.pack_id));
+
+        let mut missing = Vec::new();
+        for id in base.iter()? {
+            let id = id?;
+            let kind = base.find(id, &mut buf)?.kind;
+            if handle.try_find(id, &mut buf)?.is_none() {
+                missing.push((id, kind));
+            }
+        }
+        assert_eq!(missing.len(), num_missing, "{}: the filter leaves out objects", clone);
+
+        handle.missing_object_policy = MissingObjectPolicy::TreatAsPromised;
+        for (id, kind) in missing {
+            assert!(missing_kinds.contains(&kind), "{}: {} must not be missing", clone, kind);
+            let err = handle.try_find(id, &mut buf).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::Promised);
+            assert!(!err.is_not_found(), "promised objects are distinct from missing ones");
+            assert!(
+                matches!(err, git_odb::store::find::Error::Promised { id: promised } if promised == id),
+                "{:?}",
+                err
+            );
+        }
+    }
+
+    let mut handle = db();
+    handle.missing_object_policy = MissingObjectPolicy::TreatAsPromised;
+    assert!(
+        handle
+            .try_find(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"), &mut buf)?
+            .is_none(),
+        "without promisor packs, objects are not found as usual"
+    );
+    Ok(())
+}
+
+#[test]
+fn packs_since_returns_packs_added_after_the_marker() -> crate::Result {
+    use git_odb::store::packs_since::Marker;
+    use std::time::{Duration, SystemTime};
+
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let pack_dir = objects_dir.path().join("pack");
+    let pack_names = [
+        "pack-11fdfa9e156ab73caae3b6da867192221f2089c2",
+        "pack-a2bf8e71d8c18879e499335762dd95119d93d9f1",
+        "pack-c0438c19fb16422b6bbcce24387b3264416d485b",
+    ];
+    let old_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let old = filetime::FileTime::from_system_time(old_time);
+    for name in &pack_names[..2] {
+        filetime::set_file_mtime(pack_dir.join(name).with_extension("pack"), old)?;
+    }
+    let new_pack_path = pack_dir.join(pack_names[2]).with_extension("pack");
+    let held_back = git_testtools::tempfile::tempdir()?;
+    for ext in ["idx", "pack"] {
+        let path = new_pack_path.with_extension(ext);
+        std::fs::rename(&path, held_back.path().join(path.file_name().expect("file name")))?;
+    }
+
+    let handle = git_odb::at(objects_dir.path())?;
+    let store = handle.store_ref();
+    let outcome = store.packs_since(&Marker::default())?;
+    assert_eq!(
+        outcome.packs.iter().map(|p| p.path.clone()).collect::<Vec<_>>(),
+        vec![
+            pack_dir.join(pack_names[0]).with_extension("pack"),
+            pack_dir.join(pack_names[1]).with_extension("pack")
+        ],
+        "the default marker yields all packs, oldest first, and paths on equal modification times"
+    );
+    for pack in &outcome.packs {
+        assert_eq!(pack.index_path, pack.path.with_extension("idx"));
+        assert_eq!(pack.size, std::fs::metadata(&pack.path)?.len());
+        assert_eq!(pack.modified, old_time);
+    }
+    assert_eq!(
+        outcome.marker,
+        Marker {
+            modified: Some(old_time),
+            names: vec![
+                format!("{}.pack", pack_names[0]).into(),
+                format!("{}.pack", pack_names[1]).into()
+            ]
+        }
+    );
+    assert_eq!(
+        Marker::from_bytes(&outcome.marker.to_bytes())?,
+        outcome.marker,
+        "markers can be persisted"
+    );
+    assert_eq!(Marker::from_bytes(b"")?, Marker::default());
+    assert!(
+        Marker::from_bytes(b"1.5\n").is_err(),
+        "nanoseconds are always written with 9 digits"
+    );
+
+    let unchanged = store.packs_since(&outcome.marker)?;
+    assert!(unchanged.packs.is_empty(), "nothing was added since");
+    assert_eq!(unchanged.marker, outcome.marker);
+
+    for ext in ["idx", "pack"] {
+        let path = new_pack_path.with_extension(ext);
+        std::fs::rename(held_back.path().join(path.file_name().expect("file name")), &path)?;
+    }
+    filetime::set_file_mtime(&new_pack_path, old)?;
+    let added = store.packs_since(&outcome.marker)?;
+    assert_eq!(
+        added.packs.iter().map(|p| p.path.clone()).collect::<Vec<_>>(),
+        vec![new_pack_path.clone()],
+        "new packs are picked up even if they have the same modification time as the marker"
+    );
+    assert_eq!(added.marker.names.len(), 3);
+    assert!(store.packs_since(&added.marker)?.packs.is_empty());
+
+    let newer_time = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000_000);
+    filetime::set_file_mtime(&new_pack_path, filetime::FileTime::from_system_time(newer_time))?;
+    let touched = store.packs_since(&added.marker)?;
+    assert_eq!(touched.packs.len(), 1, "modified packs are considered new");
+    assert_eq!(
+        touched.marker,
+        Marker {
+            modified: Some(newer_time),
+            names: vec![format!("{}.pack", pack_names[2]).into()]
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn indices_without_pack_are_recorded_as_orphaned_unless_strict() -> crate::Result {
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let index_path = objects_dir
+        .path()
+        .join("pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx");
+    let pack_path = index_path.with_extension("pack");
+    std::fs::remove_file(&pack_path)?;
+
+    let handle = git_odb::at(objects_dir.path())?;
+    let mut iter = handle.iter()?;
+    assert_eq!(
+        iter.by_ref().count(),
+        146 - 30,
+        "objects of the orphaned index are skipped by default"
+    );
+    assert_eq!(iter.warnings().len(), 1, "…but the missing pack is recorded");
+    assert_eq!(iter.warnings()[0].path, pack_path);
+    assert_eq!(iter.warnings()[0].kind, std::io::ErrorKind::NotFound);
+    assert_eq!(handle.store_ref().orphaned_indices(), vec![index_path.clone()]);
+    assert_eq!(handle.store_ref().metrics().orphaned_indices, 1);
+
+    let mut iter = handle.iter_with_orphaned_indices(store::iter::OrphanedIndices::Include)?;
+    assert_eq!(
+        iter.by_ref().count(),
+        146,
+        "objects of orphaned indices can be included"
+    );
+    assert!(iter.warnings().is_empty());
+
+    let id = git_odb::pack::index::File::at(&index_path, git_hash::Kind::Sha1)?
+        .oid_at_index(0)
+        .to_owned();
+    assert!(!handle.contains(id));
+    let mut buf = Vec::new();
+    match handle.try_find(id, &mut buf) {
+        Err(store::find::Error::OrphanedIndex {
+            id: actual_id,
+            index_path: actual_path,
+        }) => {
+            assert_eq!(actual_id, id);
+            assert_eq!(actual_path, index_path);
+        }
+        _ => unreachable!("objects only listed in orphaned indices cause a specific error"),
+    }
+    assert!(
+        handle
+            .try_find(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"), &mut buf)?
+            .is_none(),
+        "objects that don't exist anywhere are still missing"
+    );
+
+    let outcome = handle.store_ref().verify_integrity(
+        &mut git_features::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert_eq!(outcome.index_statistics.len(), 2, "the orphaned index isn't verified…");
+    assert_eq!(outcome.orphaned_indices, vec![index_path.clone()], "…but reported");
+
+    let err = git_odb::at_opts(
+        objects_dir.path(),
+        None,
+        git_odb::store::init::Options {
+            strict: true,
+            ..Default::default()
+        },
+    )
+    .err()
+    .expect("strict mode fails fast");
+    assert!(
+        err.to_string().contains(&*index_path.to_string_lossy()),
+        "the error mentions the index: {}",
+        err
+    );
+    Ok(())
+}
+
+#[test]
+fn corrupt_indices_are_skipped_with_warnings_unless_strict() -> crate::Result {
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let index_path = objects_dir
+        .path()
+        .join("pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx");
+    std::fs::write(&index_path, b"not an index")?;
+
+    let handle = git_odb::at(objects_dir.path())?;
+    assert_eq!(
+        handle.iter()?.count(),
+        146 - 30,
+        "objects of the corrupt index are skipped"
+    );
+    let warnings = handle.store_ref().warnings();
+    assert_eq!(warnings.len(), 1, "…but the index is recorded");
+    assert_eq!(warnings[0].path, index_path);
+    assert_eq!(warnings[0].kind, std::io::ErrorKind::InvalidData);
+
+    let handle = git_odb::at_opts(
+        objects_dir.path(),
+        None,
+        git_odb::store::init::Options {
+            strict: true,
+            ..Default::default()
+        },
+    )?;
+    let err = handle
+        .iter()
+        .err()
+        .expect("strict mode fails on the first corrupt index");
+    assert_eq!(
+        err.to_string(),
+        format!("Could not load the index at '{}'", index_path.display())
+    );
+    assert!(
+        std::error::Error::source(&err)
+            .expect("source")
+            .to_string()
+            .contains("is too small for even an empty index"),
+        "the source explains the problem"
+    );
+    Ok(())
+}
+
+#[test]
+fn export_loose() -> crate::Result {
+    let handle = db();
+    let dest_dir = git_testtools::tempfile::tempdir()?;
+    let dest = git_odb::loose::Store::at(dest_dir.path(), git_hash::Kind::Sha1);
+    let ids = handle.iter()?.collect::<Result<Vec<_>, _>>()?;
+    let should_interrupt = std::sync::atomic::AtomicBool::default();
+
+    let outcome = handle.export_loose(
+        ids.iter().cloned(),
+        &dest,
+        &mut git_features::progress::Discard,
+        &should_interrupt,
+    )?;
+    assert_eq!(outcome.objects_written, ids.len());
+    assert_eq!(outcome.objects_skipped, 0);
+    assert!(outcome.bytes_written > 0);
+
+    let (mut expected_buf, mut actual_buf) = (Vec::new(), Vec::new());
+    for id in &ids {
+        let expected = handle.find(id, &mut expected_buf)?;
+        let actual = dest.try_find(id, &mut actual_buf)?.expect("exported object is present");
+        assert_eq!(actual.kind, expected.kind);
+        assert_eq!(actual.data, expected.data, "the object data is byte-identical");
+    }
+
+    let outcome = handle.export_loose(
+        ids.iter().cloned(),
+        &dest,
+        &mut git_features::progress::Discard,
+        &should_interrupt,
+    )?;
+    assert_eq!(
+        outcome,
+        git_odb::store::export::Outcome {
+            objects_written: 0,
+            objects_skipped: ids.len(),
+            bytes_written: 0
+        },
+        "objects which are present already are skipped"
+    );
+
+    let missing = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    assert!(matches!(
+        handle.export_loose(
+            std::iter::once(missing),
+            &dest,
+            &mut git_features::progress::Discard,
+            &should_interrupt
+        ),
+        Err(git_odb::store::export::Error::NotFound { id }) if id == missing
+    ));
+    Ok(())
+}
+
+#[test]
+fn export_loose_ignores_replacements() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_replaced_history.sh")?;
+    let short_history_link = hex_to_id("434e5a872d6738d1fffd1e11e52a1840b73668c6");
+    let long_history_tip = hex_to_id("71f537d9d78bf6ae89a29a17e54b95a914d3d2ef");
+    let handle = git_odb::at_opts(
+        dir.join(".git/objects"),
+        vec![(short_history_link, long_history_tip)],
+        git_odb::store::init::Options::default(),
+    )?;
+    let dest_dir = git_testtools::tempfile::tempdir()?;
+    let dest = git_odb::loose::Store::at(dest_dir.path(), git_hash::Kind::Sha1);
+
+    let outcome = handle.export_loose(
+        std::iter::once(short_history_link),
+        &dest,
+        &mut git_features::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+    )?;
+    assert_eq!(outcome.objects_written, 1, "the replaced object is exported as is");
+
+    let mut buf = Vec::new();
+    let commit = dest
+        .try_find(short_history_link, &mut buf)?
+        .expect("exported object is present")
+        .decode()?
+        .into_commit()
+        .expect("commit");
+    assert_eq!(
+        commit.parents().collect::<Vec<_>>(),
+        vec![hex_to_id("0703c317e28068f39834ae61e7ab941b7d672322")],
+        "it's the original commit, not its replacement"
+    );
+    Ok(())
+}
+
+mod empty {
+    use std::sync::atomic::AtomicBool;
+
+    use git_features::progress;
+    use git_odb::store;
+
+    fn assert_empty_store(objects_dir: &std::path::Path, expected_num_packs: usize) -> crate::Result {
+        let handle = git_odb::at(objects_dir)?;
+        for ordering in [
+            store::iter::Ordering::PackThenLoose,
+            store::iter::Ordering::Lexicographical,
+        ] {
+            let options = store::iter::Options {
+                ordering,
+                ..Default::default()
+            };
+            let mut iter = handle.iter_with_options(options)?;
+            assert_eq!(iter.by_ref().count(), 0, "{:?}", ordering);
+            assert!(iter.warnings().is_empty());
+
+            let mut iter = handle.iter_with_options(options)?;
+            iter.seek(5);
+            assert!(iter.next().is_none(), "seeking past the end depletes the iterator");
+            assert!(handle.iter_with_options(options)?.nth(1).is_none());
+        }
+        assert_eq!(
+            handle
+                .iter_with_data(&AtomicBool::default(), Default::default())?
+                .count(),
+            0
+        );
+        assert_eq!(handle.packed_object_count()?, 0);
+
+        let outcome =
+            handle
+                .store_ref()
+                .verify_integrity(&mut progress::Discard, &AtomicBool::new(false), Default::default())?;
+        assert_eq!(outcome.index_statistics.len(), expected_num_packs);
+        for index in &outcome.index_statistics {
+            match &index.statistics {
+                store::verify::integrity::SingleOrMultiStatistics::Single(stats) => {
+                    assert_eq!(stats.num_objects(), 0, "empty packs are traversed like any other")
+                }
+                store::verify::integrity::SingleOrMultiStatistics::Multi(_) => unreachable!("there is no multi-index"),
+            }
+        }
+        assert_eq!(outcome.loose_object_stores.len(), 1);
+        assert_eq!(outcome.loose_object_stores[0].statistics.num_objects, 0);
+        assert!(outcome.warnings_by_class.is_empty());
+
+        let metrics = handle.store_ref().metrics();
+        assert_eq!(metrics.known_packs, expected_num_packs);
+        assert_eq!(metrics.loose_dbs, 1);
+
+        let loose = git_odb::loose::Store::at(objects_dir, git_hash::Kind::Sha1);
+        assert_eq!(loose.iter().count(), 0, "there are no fan-out directories to iterate");
+        assert_eq!(
+            loose
+                .verify_integrity(&mut progress::Discard, &AtomicBool::new(false))?
+                .num_objects,
+            0
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn repository_with_an_empty_pack() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_with_empty_pack.sh")?;
+        assert_empty_store(&dir.join(".git/objects"), 1)
+    }
+
+    #[test]
+    fn freshly_initialized_repository() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_empty_repo.sh")?;
+        let objects_dir = dir.join(".git/objects");
+        assert!(
+            std::fs::read_dir(&objects_dir)?
+                .filter_map(Result::ok)
+                .all(|e| e.file_name().len() != 2),
+            "there are no loose fan-out directories"
+        );
+        assert_empty_store(&objects_dir, 0)
+    }
+}
+
+mod infer_object_hash {
+    use git_odb::{store, Store};
+
+    fn objects_dir(name: &str) -> crate::Result<std::path::PathBuf> {
+        Ok(
+            git_testtools::scripted_fixture_repo_read_only("make_repos_with_object_formats.sh")?
+                .join(name)
+                .join(".git/objects"),
+        )
+    }
+
+    #[test]
+    fn sha1_is_inferred_from_loose_objects_and_pack_indices() -> crate::Result {
+        for name in ["sha1-loose", "sha1-packed"] {
+            let objects_dir = objects_dir(name)?;
+            assert_eq!(
+                Store::infer_object_hash(&objects_dir)?,
+                git_hash::Kind::Sha1,
+                "{}",
+                name
+            );
+
+            let store = Store::at_inferred(objects_dir)?;
+            assert_eq!(store.object_hash(), git_hash::Kind::Sha1);
+            assert_eq!(store.iter()?.count(), 2, "{}: a commit and its empty tree", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sha256_is_recognized_but_unsupported() -> crate::Result {
+        for name in ["sha256-loose", "sha256-packed"] {
+            let err = Store::infer_object_hash(objects_dir(name)?).unwrap_err();
+            assert!(
+                matches!(err, store::infer::Error::Unsupported { hex_len: 64, .. }),
+                "{}: {:?}",
+                name,
+                err
+            );
+            assert!(Store::at_inferred(objects_dir(name)?).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn empty_stores_are_ambiguous() -> crate::Result {
+        let err = Store::at_inferred(objects_dir("empty")?)
+            .err()
+            .expect("nothing to infer from");
+        assert!(matches!(err, store::infer::Error::Empty { .. }), "{:?}", err);
+        assert!(
+            err.to_string().contains("neither loose objects nor pack indices"),
+            "the message explains why nothing could be inferred"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn disagreeing_loose_objects_and_indices_are_ambiguous() -> crate::Result {
+        let objects_dir = git_testtools::tempfile::tempdir()?;
+        git_testtools::copy_recursively_into_existing_dir(self::objects_dir("sha1-packed")?, &objects_dir)?;
+        git_testtools::copy_recursively_into_existing_dir(self::objects_dir("sha256-loose")?, &objects_dir)?;
+
+        let err = Store::infer_object_hash(objects_dir.path()).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                store::infer::Error::Ambiguous {
+                    first_hex_len: 64,
+                    second_hex_len: 40,
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+        Ok(())
+    }
+}
+
+mod prune_loose {
+    use std::time::{Duration, SystemTime};
+
+    use git_odb::{store::prune, Write};
+
+    const EXPIRE: Duration = Duration::from_secs(60 * 60);
+
+    fn write_with_age(handle: &git_odb::Handle, data: &str, age: Duration) -> crate::Result<git_hash::ObjectId> {
+        let id = handle.write_buf(git_object::Kind::Blob, data.as_bytes())?;
+        let hex = id.to_hex().to_string();
+        filetime::set_file_mtime(
+            handle.store_ref().path().join(&hex[..2]).join(&hex[2..]),
+            filetime::FileTime::from_system_time(SystemTime::now() - age),
+        )?;
+        Ok(id)
+    }
+
+    fn exists(handle: &git_odb::Handle, id: git_hash::ObjectId) -> bool {
+        let hex = id.to_hex().to_string();
+        handle.store_ref().path().join(&hex[..2]).join(&hex[2..]).is_file()
+    }
+
+    #[test]
+    fn only_unreachable_objects_older_than_the_expiry_are_deleted() -> crate::Result {
+        let objects_dir = git_testtools::tempfile::tempdir()?;
+        let handle = git_odb::at(objects_dir.path())?;
+        let minute = Duration::from_secs(60);
+        let reachable_and_old = write_with_age(&handle, "reachable", EXPIRE * 24)?;
+        let reachable_and_young = write_with_age(&handle, "reachable and young", Duration::ZERO)?;
+        let unreachable_and_old = write_with_age(&handle, "unreachable", EXPIRE * 24)?;
+        let just_expired = write_with_age(&handle, "just expired", EXPIRE + minute)?;
+        let almost_expired = write_with_age(&handle, "almost expired", EXPIRE - minute)?;
+        let reachable: git_hashtable::HashSet = [reachable_and_old, reachable_and_young].into_iter().collect();
+
+        let options = prune::Options {
+            expire: EXPIRE,
+            dry_run: true,
+            force: false,
+        };
+        let dry_run = handle.store_ref().prune_loose(&reachable, options)?;
+        assert_eq!(dry_run.deleted.len(), 2);
+        assert!(
+            [unreachable_and_old, just_expired]
+                .iter()
+                .all(|id| exists(&handle, *id)),
+            "a dry run doesn't delete anything"
+        );
+
+        let outcome = handle.store_ref().prune_loose(
+            &reachable,
+            prune::Options {
+                dry_run: false,
+                ..options
+            },
+        )?;
+        assert_eq!(outcome, dry_run, "a dry run predicts exactly what happens");
+        assert_eq!(outcome.reachable, 2);
+        assert_eq!(outcome.recent_unreachable, 1);
+        assert!(outcome.reclaimed_bytes > 0);
+        let mut expected: Vec<_> = [unreachable_and_old, just_expired]
+            .iter()
+            .map(|id| {
+                let hex = id.to_hex().to_string();
+                handle.store_ref().path().join(&hex[..2]).join(&hex[2..])
+            })
+            .collect();
+        expected.sort();
+        assert_eq!(outcome.deleted, expected);
+
+        assert!(!exists(&handle, unreachable_and_old));
+        assert!(!exists(&handle, just_expired));
+        assert!(
+            exists(&handle, almost_expired),
+            "objects within the grace period are kept"
+        );
+        assert!(
+            exists(&handle, reachable_and_old) && exists(&handle, reachable_and_young),
+            "reachable objects are never deleted, no matter their age"
+        );
+        let hex = unreachable_and_old.to_hex().to_string();
+        assert!(
+            !handle.store_ref().path().join(&hex[..2]).exists(),
+            "empty fan-out directories are removed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn an_empty_reachable_set_is_refused_unless_forced() -> crate::Result {
+        let objects_dir = git_testtools::tempfile::tempdir()?;
+        let handle = git_odb::at(objects_dir.path())?;
+        let id = write_with_age(&handle, "unreachable", EXPIRE * 2)?;
+        let options = prune::Options {
+            expire: EXPIRE,
+            dry_run: false,
+            force: false,
+        };
+        assert!(matches!(
+            handle.store_ref().prune_loose(&Default::default(), options),
+            Err(prune::Error::EmptyReachableSet)
+        ));
+        assert!(exists(&handle, id));
+
+        let outcome = handle
+            .store_ref()
+            .prune_loose(&Default::default(), prune::Options { force: true, ..options })?;
+        assert_eq!(outcome.deleted.len(), 1);
+        assert!(!exists(&handle, id));
+        Ok(())
+    }
 }
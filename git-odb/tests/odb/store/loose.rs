@@ -34,6 +34,28 @@ pub fn locate_oid(id: git_hash::ObjectId, buf: &mut Vec<u8>) -> git_object::Data
     ldb().try_find(id, buf).expect("read success").expect("id present")
 }
 
+#[test]
+fn try_header() -> crate::Result {
+    let db = ldb();
+    let mut buf = Vec::new();
+    for id in object_ids() {
+        let header = db.try_header(id)?.expect("present");
+        let object = locate_oid(id, &mut buf);
+        assert_eq!(header.kind, object.kind);
+        assert_eq!(header.size, object.data.len() as u64);
+        let mut path = fixture_path("objects");
+        let hex = id.to_hex().to_string();
+        path.push(&hex[..2]);
+        path.push(&hex[2..]);
+        assert_eq!(header.compressed_size, std::fs::metadata(path)?.len());
+    }
+    assert_eq!(
+        db.try_header(hex_to_id("cccccccccccccccccccccccccccccccccccccccc"))?,
+        None
+    );
+    Ok(())
+}
+
 #[test]
 fn verify_integrity() {
     let db = ldb();
@@ -41,8 +63,46 @@ fn verify_integrity() {
     assert_eq!(outcome.num_objects, 7);
 }
 
+#[test]
+fn verify_integrity_with_roundtrip() -> crate::Result {
+    use git_odb::Write;
+
+    let outcome = ldb().verify_integrity_with_roundtrip(progress::Discard, &AtomicBool::new(false))?;
+    assert_eq!(outcome.num_objects, 7);
+
+    let tree_with_mode = |mode: &str, name: &[u8]| {
+        let mut buf = format!("{} ", mode).into_bytes();
+        buf.extend_from_slice(name);
+        buf.push(0);
+        buf.extend_from_slice(&[0xaa; 20]);
+        buf
+    };
+    let dir = tempfile::tempdir()?;
+    let db = Store::at(dir.path(), git_hash::Kind::Sha1);
+    db.write_buf(git_object::Kind::Tree, &tree_with_mode("100664", b"a"))?;
+    let outcome = db.verify_integrity_with_roundtrip(progress::Discard, &AtomicBool::new(false))?;
+    assert_eq!(outcome.num_objects, 1, "non-standard tree entry modes are tolerated");
+
+    let id = db.write_buf(git_object::Kind::Tree, &tree_with_mode("100644", b"a\nb"))?;
+    assert_eq!(
+        db.verify_integrity(progress::Discard, &AtomicBool::new(false))?
+            .num_objects,
+        2,
+        "the tree can be decoded just fine"
+    );
+    let err = db
+        .verify_integrity_with_roundtrip(progress::Discard, &AtomicBool::new(false))
+        .unwrap_err();
+    assert!(
+        matches!(err, git_odb::loose::verify::integrity::Error::ObjectEncodeMismatch { id: actual, .. } if actual == id),
+        "{:?}",
+        err
+    );
+    Ok(())
+}
+
 mod write {
-    use git_odb::{loose, Write};
+    use git_odb::{loose, Write, WriteWithHash};
 
     use crate::store::loose::{locate_oid, object_ids};
 
@@ -70,6 +130,203 @@ mod write {
         }
         Ok(())
     }
+
+    #[test]
+    fn buffered_and_streamed_writes_produce_the_same_files() -> crate::Result {
+        let (buffered_dir, streamed_dir) = (tempfile::tempdir()?, tempfile::tempdir()?);
+        let buffered = loose::Store::at(buffered_dir.path(), git_hash::Kind::Sha1);
+        let streamed = loose::Store::at_opts(
+            streamed_dir.path(),
+            git_hash::Kind::Sha1,
+            loose::write::Options {
+                max_buffered_object_size: 0,
+                ..Default::default()
+            },
+        );
+        let mut buf = Vec::new();
+        let large = vec![b'x'; loose::write::DEFAULT_MAX_BUFFERED_OBJECT_SIZE as usize + 1];
+
+        for (kind, data) in object_ids()
+            .into_iter()
+            .map(|oid| {
+                let obj = locate_oid(oid, &mut buf);
+                (obj.kind, obj.data.to_owned())
+            })
+            .chain(Some((git_object::Kind::Blob, Vec::new())))
+            .chain(Some((git_object::Kind::Blob, large)))
+        {
+            let id = buffered.write_stream(kind, data.len() as u64, data.as_slice())?;
+            assert_eq!(streamed.write_buf(kind, &data)?, id);
+            let path = |dir: &std::path::Path| {
+                let hex = id.to_string();
+                dir.join(&hex[..2]).join(&hex[2..])
+            };
+            assert_eq!(
+                std::fs::read(path(buffered_dir.path()))?,
+                std::fs::read(path(streamed_dir.path()))?,
+                "{} compresses the same either way",
+                id
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn write_with_hash_of_the_configured_kind_is_the_same_as_write_buf() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path(), git_hash::Kind::Sha1);
+        let (mut buf, mut buf2) = (Vec::new(), Vec::new());
+
+        for oid in object_ids() {
+            let obj = locate_oid(oid, &mut buf);
+            let actual = db.write_with_hash(git_hash::Kind::Sha1, obj.kind, obj.data)?;
+            assert_eq!(actual, oid);
+            assert!(db.contains(oid));
+
+            let found = db
+                .try_find_with_hash(git_hash::Kind::Sha1, oid, &mut buf2)?
+                .expect("written objects can be found by the kind of hash they were written with");
+            assert_eq!(found.kind, obj.kind);
+            assert_eq!(found.data, obj.data);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn write_with_hash_into_compat_layout_and_read_back_only_by_the_same_kind() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let compat = loose::compat::Store::at(dir.path(), Some(git_hash::Kind::Sha1));
+        let main = loose::Store::at(dir.path(), git_hash::Kind::Sha1);
+        let (mut buf, mut buf2) = (Vec::new(), Vec::new());
+
+        for oid in object_ids() {
+            let obj = locate_oid(oid, &mut buf);
+            let actual = compat.write_with_hash(git_hash::Kind::Sha1, obj.kind, obj.data)?;
+            assert_eq!(actual, oid);
+            assert!(
+                !main.contains(oid),
+                "objects of each kind of hash are kept apart from the ones of the repository"
+            );
+            assert!(loose::Store::at(dir.path().join("compat").join("sha1"), git_hash::Kind::Sha1).contains(oid));
+
+            let found = compat
+                .try_find_with_hash(git_hash::Kind::Sha1, oid, &mut buf2)?
+                .expect("written objects can be found by the kind of hash they were written with");
+            assert_eq!(found.kind, obj.kind);
+            assert_eq!(found.data, obj.data);
+        }
+
+        // Only one kind of hash is implemented, so a layout that doesn't host it stands in for one of another kind.
+        let other = loose::compat::Store::at(dir.path(), None);
+        let oid = object_ids()[0];
+        assert!(matches!(
+            other.try_find_with_hash(git_hash::Kind::Sha1, oid, &mut buf2),
+            Err(loose::compat::Error::UnsupportedObjectHash { requested: git_hash::Kind::Sha1, configured }) if configured.is_empty()
+        ));
+        let obj = locate_oid(oid, &mut buf);
+        assert!(matches!(
+            other.write_with_hash(git_hash::Kind::Sha1, obj.kind, obj.data),
+            Err(loose::compat::Error::UnsupportedObjectHash { .. })
+        ));
+        Ok(())
+    }
+
+    fn loose_files_and_temp_files(objects_dir: &std::path::Path) -> std::io::Result<(usize, usize)> {
+        let (mut objects, mut temp_files) = (0, 0);
+        for entry in std::fs::read_dir(objects_dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                objects += std::fs::read_dir(entry.path())?.count();
+            } else {
+                temp_files += 1;
+            }
+        }
+        Ok((objects, temp_files))
+    }
+
+    #[test]
+    fn concurrent_writers_of_the_same_objects_all_succeed() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let num_objects = 1000;
+        for write_options in [
+            loose::write::Options::default(),
+            loose::write::Options {
+                verify_existing: true,
+                skip_if_contained: true,
+                ..Default::default()
+            },
+            loose::write::Options {
+                max_buffered_object_size: 0,
+                ..Default::default()
+            },
+        ] {
+            let db = loose::Store::at_opts(dir.path(), git_hash::Kind::Sha1, write_options);
+            let threads: Vec<_> = (0..num_cpus::get().max(4))
+                .map(|thread_index| {
+                    let db = db.clone();
+                    std::thread::spawn(move || -> Result<Vec<git_hash::ObjectId>, loose::write::Error> {
+                        (0..num_objects)
+                            .map(|n| {
+                                let data = format!("object {}", n);
+                                if thread_index % 2 == 0 {
+                                    db.write_buf(git_object::Kind::Blob, data.as_bytes())
+                                } else {
+                                    db.write_stream(git_object::Kind::Blob, data.len() as u64, data.as_bytes())
+                                }
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+
+            let mut ids = None;
+            for thread in threads {
+                let thread_ids = thread.join().expect("no panic")?;
+                assert_eq!(ids.get_or_insert_with(|| thread_ids.clone()), &thread_ids);
+            }
+            assert_eq!(
+                loose_files_and_temp_files(dir.path())?,
+                (num_objects, 0),
+                "each object exists once and no temporary file is left behind"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn corrupt_existing_objects_are_replaced_if_verification_is_enabled() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path(), git_hash::Kind::Sha1);
+        let mut buf = Vec::new();
+        let id = db.write_buf(git_object::Kind::Blob, b"content")?;
+        let hex = id.to_string();
+        std::fs::write(dir.path().join(&hex[..2]).join(&hex[2..]), b"corrupt")?;
+
+        assert_eq!(
+            db.write_buf(git_object::Kind::Blob, b"content")?,
+            id,
+            "without verification, existing objects are assumed to be intact"
+        );
+        assert!(db.try_find(id, &mut buf).is_err(), "the object remains corrupt");
+
+        let db = loose::Store::at_opts(
+            dir.path(),
+            git_hash::Kind::Sha1,
+            loose::write::Options {
+                verify_existing: true,
+                skip_if_contained: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(db.write_buf(git_object::Kind::Blob, b"content")?, id);
+        assert_eq!(
+            db.try_find(id, &mut buf)?.expect("present").data,
+            b"content",
+            "the corrupt object was replaced"
+        );
+        assert_eq!(loose_files_and_temp_files(dir.path())?, (1, 0));
+        Ok(())
+    }
 }
 
 mod contains {
@@ -186,6 +443,7 @@ mod find {
             target: b"ffa700b4aca13b80cb6b98a078e7c96804f8e0ec".as_bstr(),
             name: b"1.0.0".as_bstr(),
             target_kind: Kind::Commit,
+            target_kind_raw: None,
             message: b"for the signature".as_bstr(),
             pgp_signature: Some(
                 b"-----BEGIN PGP SIGNATURE-----
@@ -312,7 +570,7 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
     }
 }
 
-fn signature(time: u32) -> git_actor::SignatureRef<'static> {
+fn signature(time: i64) -> git_actor::SignatureRef<'static> {
     git_actor::SignatureRef {
         name: b"Sebastian Thiel".as_bstr(),
         email: b"byronimo@gmail.com".as_bstr(),
@@ -321,5 +579,6 @@ fn signature(time: u32) -> git_actor::SignatureRef<'static> {
             offset_in_seconds: 7200,
             sign: Sign::Plus,
         },
+        raw_time: None,
     }
 }
@@ -0,0 +1,140 @@
+use std::{convert::TryInto, path::Path};
+
+use git_bitmap::ewah;
+
+use crate::bitmap::{Entry, File, Version, FLAG_FULL_DAG};
+
+mod error {
+    /// The error returned by [File::at()][super::File::at()] and [File::from_bytes()][super::File::from_bytes()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open bitmap file at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("Bitmap file at '{path}' doesn't start with the 'BITM' signature, but with {actual:?}")]
+        InvalidSignature {
+            actual: git_object::bstr::BString,
+            path: std::path::PathBuf,
+        },
+        #[error("Bitmap file at '{path}' has unsupported version {version}")]
+        UnsupportedVersion { version: u16, path: std::path::PathBuf },
+        #[error("Bitmap file at '{path}' doesn't have the required flag to indicate it covers all reachable objects")]
+        MissingFullDagFlag { path: std::path::PathBuf },
+        #[error("{message} in bitmap file at '{path}'")]
+        Corrupt {
+            message: &'static str,
+            path: std::path::PathBuf,
+        },
+        #[error("The {kind} bitmap in bitmap file at '{path}' could not be decoded")]
+        Ewah {
+            kind: &'static str,
+            source: git_bitmap::ewah::decode::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+pub use error::Error;
+
+impl File {
+    /// Open the bitmap file at `path`, with object ids of the given `object_hash`.
+    pub fn at(path: impl AsRef<Path>, object_hash: git_hash::Kind) -> Result<File, Error> {
+        let path = path.as_ref();
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        Self::from_bytes(&data, path.to_owned(), object_hash)
+    }
+
+    /// Parse the bitmap file in `data`, which is known to be located at `path`, with object ids of the given `object_hash`.
+    ///
+    /// The trailing checksum isn't verified.
+    pub fn from_bytes(data: &[u8], path: std::path::PathBuf, object_hash: git_hash::Kind) -> Result<File, Error> {
+        let hash_len = object_hash.len_in_bytes();
+        let corrupt = |message| Error::Corrupt {
+            message,
+            path: path.clone(),
+        };
+        if data.len() < Self::HEADER_LEN + hash_len + hash_len {
+            return Err(corrupt("Too small for even an empty bitmap"));
+        }
+
+        let (signature, rest) = data.split_at(Self::SIGNATURE.len());
+        if signature != Self::SIGNATURE {
+            return Err(Error::InvalidSignature {
+                actual: signature.into(),
+                path,
+            });
+        }
+        let (version, rest) = rest.split_at(2);
+        let version = match u16::from_be_bytes(version.try_into().expect("2 bytes")) {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version, path }),
+        };
+        let (flags, rest) = rest.split_at(2);
+        let flags = u16::from_be_bytes(flags.try_into().expect("2 bytes"));
+        if flags & FLAG_FULL_DAG == 0 {
+            return Err(Error::MissingFullDagFlag { path });
+        }
+        let (num_entries, rest) = rest.split_at(4);
+        let num_entries = crate::read_u32(num_entries);
+        let (pack_checksum, mut rest) = rest.split_at(hash_len);
+        let pack_checksum = git_hash::ObjectId::from(pack_checksum);
+        let checksum = git_hash::ObjectId::from(&data[data.len() - hash_len..]);
+
+        let decode_bitmap = |kind, data: &mut &[u8]| {
+            let (bitmap, rest) = ewah::decode(data).map_err(|source| Error::Ewah {
+                kind,
+                source,
+                path: path.clone(),
+            })?;
+            *data = rest;
+            Ok::<_, Error>(bitmap)
+        };
+        let commits = decode_bitmap("commit", &mut rest)?;
+        let trees = decode_bitmap("tree", &mut rest)?;
+        let blobs = decode_bitmap("blob", &mut rest)?;
+        let tags = decode_bitmap("tag", &mut rest)?;
+
+        let mut entries = Vec::with_capacity(num_entries as usize);
+        for entry_index in 0..num_entries as usize {
+            if rest.len() < 6 {
+                return Err(corrupt("Unexpected end of file while reading entry header"));
+            }
+            let (header, mut remaining) = rest.split_at(6);
+            let index_position = crate::read_u32(&header[..4]);
+            let xor_offset = header[4];
+            let flags = header[5];
+            if xor_offset as usize > entry_index {
+                return Err(corrupt("XOR offset points before the first entry"));
+            }
+            let bitmap = decode_bitmap("commit reachability", &mut remaining)?;
+            rest = remaining;
+            entries.push(Entry {
+                index_position,
+                xor_offset,
+                flags,
+                bitmap,
+            });
+        }
+        if rest.len() < hash_len {
+            return Err(corrupt("Entries overlap with the trailing checksum"));
+        }
+
+        Ok(File {
+            path,
+            version,
+            flags,
+            pack_checksum,
+            checksum,
+            commits,
+            trees,
+            blobs,
+            tags,
+            entries,
+        })
+    }
+}
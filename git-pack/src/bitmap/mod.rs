@@ -0,0 +1,138 @@
+use git_bitmap::ewah;
+
+/// Known bitmap file versions
+#[derive(PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    V1 = 1,
+}
+
+impl Default for Version {
+    fn default() -> Self {
+        Version::V1
+    }
+}
+
+/// Indicates that all objects reachable from a commit with a bitmap are contained in the pack, which git requires.
+pub const FLAG_FULL_DAG: u16 = 0x1;
+/// Indicates that a cache of name-hashes for each object follows the bitmap entries.
+pub const FLAG_HASH_CACHE: u16 = 0x4;
+/// Indicates that a table for looking up entries by commit follows the bitmap entries.
+pub const FLAG_LOOKUP_TABLE: u16 = 0x10;
+
+/// The bitmap of a single commit along with its position in the pack index.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Entry {
+    /// The position of the commit in the pack index, as used with [`index::File::oid_at_index()`][crate::index::File::oid_at_index()].
+    pub index_position: crate::index::EntryIndex,
+    /// If not 0, `bitmap` has to be XORed with the resolved bitmap of the entry this many entries before it.
+    pub xor_offset: u8,
+    /// Flags about this entry, like whether or not it's a good candidate to reuse deltas against.
+    pub flags: u8,
+    /// The bitmap as stored, with bit `n` representing the `n`th object in pack order, i.e. sorted by pack offset.
+    pub bitmap: ewah::Vec,
+}
+
+/// A representation of a bitmap file for a single pack, named after the pack with the `.bitmap` extension,
+/// which lists all objects reachable from selected commits.
+///
+/// All bitmaps index objects in pack order, so bit `n` represents the object with the `n`th smallest pack offset.
+pub struct File {
+    path: std::path::PathBuf,
+    version: Version,
+    flags: u16,
+    pack_checksum: git_hash::ObjectId,
+    checksum: git_hash::ObjectId,
+    commits: ewah::Vec,
+    trees: ewah::Vec,
+    blobs: ewah::Vec,
+    tags: ewah::Vec,
+    entries: Vec<Entry>,
+}
+
+/// Basic file information
+impl File {
+    /// The path of the opened bitmap file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+    /// The version of this file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// The flags from the header, see [`FLAG_FULL_DAG`] and friends.
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+    /// The checksum of the pack this bitmap file belongs to.
+    pub fn pack_checksum(&self) -> &git_hash::oid {
+        &self.pack_checksum
+    }
+    /// The checksum of this file, as stored in its trailer.
+    pub fn checksum(&self) -> &git_hash::oid {
+        &self.checksum
+    }
+    /// The amount of commits with a bitmap.
+    pub fn num_entries(&self) -> usize {
+        self.entries.len()
+    }
+    /// All entries in the order they are stored in.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+}
+
+/// Access
+impl File {
+    /// Return the bitmap of all objects of the given `kind` in the pack.
+    pub fn kind_bitmap(&self, kind: git_object::Kind) -> &ewah::Vec {
+        match kind {
+            git_object::Kind::Commit => &self.commits,
+            git_object::Kind::Tree => &self.trees,
+            git_object::Kind::Blob => &self.blobs,
+            git_object::Kind::Tag => &self.tags,
+        }
+    }
+
+    /// Return the index of the entry for the commit at `index_position` in the pack index, if there is a bitmap for it.
+    pub fn lookup(&self, index_position: crate::index::EntryIndex) -> Option<usize> {
+        self.entries.iter().position(|e| e.index_position == index_position)
+    }
+
+    /// Return the bitmap of all objects reachable from the commit of the entry at `entry_index`, with its XOR-compression resolved.
+    ///
+    /// # Panics
+    ///
+    /// If `entry_index` is out of bounds.
+    pub fn reachable_at(&self, entry_index: usize) -> ewah::Vec {
+        let entry = &self.entries[entry_index];
+        if entry.xor_offset == 0 {
+            return entry.bitmap.clone();
+        }
+        let mut words = entry.bitmap.to_words();
+        let mut num_bits = entry.bitmap.num_bits();
+        let mut base_index = entry_index;
+        let mut xor_offset = entry.xor_offset;
+        while xor_offset != 0 {
+            base_index -= xor_offset as usize;
+            let base = &self.entries[base_index];
+            let base_words = base.bitmap.to_words();
+            if base_words.len() > words.len() {
+                words.resize(base_words.len(), 0);
+            }
+            for (word, base_word) in words.iter_mut().zip(base_words) {
+                *word ^= base_word;
+            }
+            num_bits = num_bits.max(base.bitmap.num_bits());
+            xor_offset = base.xor_offset;
+        }
+        ewah::Vec::from_words(&words, num_bits as u32)
+    }
+}
+
+///
+pub mod init;
+
+///
+pub mod write;
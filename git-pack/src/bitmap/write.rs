@@ -0,0 +1,342 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+
+use git_bitmap::ewah;
+use git_features::progress::Progress;
+
+use crate::bitmap::{self, FLAG_FULL_DAG};
+
+mod error {
+    /// The error returned by [bitmap::File::write_from_bundle()][super::bitmap::File::write_from_bundle()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error("Interrupted")]
+        Interrupted,
+        #[error("Could not decode the object at pack offset {pack_offset}")]
+        DecodeEntry {
+            pack_offset: crate::data::Offset,
+            source: crate::data::decode_entry::Error,
+        },
+        #[error("Could not decode {kind} {id}")]
+        Decode {
+            kind: git_object::Kind,
+            id: git_hash::ObjectId,
+            source: git_object::decode::Error,
+        },
+        #[error("The object {id} isn't contained in the pack, but bitmaps can only be written for packs with all objects reachable from the selected commits")]
+        ObjectNotInPack { id: git_hash::ObjectId },
+    }
+}
+pub use error::Error;
+
+/// The default for [`Options::sample_interval`].
+pub const DEFAULT_SAMPLE_INTERVAL: usize = 100;
+
+/// Options for use in [`bitmap::File::write_from_bundle()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Besides the commits the tips point to, select every `sample_interval`th commit in history, starting at the most recent
+    /// ones, to receive a bitmap. If 0, only the commits the tips point to are selected.
+    ///
+    /// It defaults to [`DEFAULT_SAMPLE_INTERVAL`].
+    pub sample_interval: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            sample_interval: DEFAULT_SAMPLE_INTERVAL,
+        }
+    }
+}
+
+/// The result of [`bitmap::File::write_from_bundle()`].
+pub struct Outcome<P> {
+    /// The calculated checksum of the bitmap file, as written into its trailer.
+    pub bitmap_checksum: git_hash::ObjectId,
+    /// The amount of commits that received a bitmap.
+    pub num_bitmaps: usize,
+    /// The input progress
+    pub progress: P,
+}
+
+impl bitmap::File {
+    pub(crate) const SIGNATURE: &'static [u8] = b"BITM";
+    pub(crate) const HEADER_LEN: usize = 4 /*signature*/ +
+        2 /*version*/ +
+        2 /*flags*/ +
+        4 /*num entries*/;
+
+    /// Write a bitmap file for the pack and index in `bundle` into `out`, with bitmaps for the commits reachable from `tips`
+    /// as selected by `options`. The `tips` are typically the targets of all references, and annotated tags among them are peeled
+    /// to the commits they point to while tips not leading to commits are ignored.
+    ///
+    /// All objects reachable from the selected commits must be contained in the pack, and the file is conventionally
+    /// written next to the pack with the `.bitmap` extension.
+    ///
+    /// Progress is sent to `progress` and interruptions checked via `should_interrupt`.
+    pub fn write_from_bundle<P>(
+        bundle: &crate::Bundle,
+        tips: impl IntoIterator<Item = git_hash::ObjectId>,
+        out: impl std::io::Write,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+        Options { sample_interval }: Options,
+    ) -> Result<Outcome<P>, Error>
+    where
+        P: Progress,
+    {
+        let mut graph = Graph::new(bundle)?;
+        let num_objects = graph.kinds.len();
+
+        let tips = {
+            let mut commits = Vec::new();
+            for tip in tips {
+                let mut pos = graph.position_of(&tip)?;
+                while graph.kinds[pos as usize] == git_object::Kind::Tag {
+                    pos = graph.children(pos)?[0];
+                }
+                if graph.kinds[pos as usize] == git_object::Kind::Commit && !commits.contains(&pos) {
+                    commits.push(pos);
+                }
+            }
+            commits
+        };
+
+        let (commits, is_selected) = {
+            let start = Instant::now();
+            let mut progress = progress.add_child_with_id("Selecting commits", *b"BWSC"); /* Bitmap Write Selecting Commits */
+            progress.init(None, git_features::progress::count("commits"));
+
+            let mut commits = Vec::new();
+            let mut seen = vec![false; num_objects];
+            let mut stack = Vec::new();
+            for &tip in &tips {
+                if std::mem::replace(&mut seen[tip as usize], true) {
+                    continue;
+                }
+                stack.push((tip, 0));
+                while let Some((pos, next_parent)) = stack.last().copied() {
+                    let parent = graph.children(pos)?.get(1 + next_parent).copied();
+                    match parent {
+                        Some(parent) => {
+                            stack.last_mut().expect("non-empty").1 += 1;
+                            if !std::mem::replace(&mut seen[parent as usize], true) {
+                                stack.push((parent, 0));
+                            }
+                        }
+                        None => {
+                            stack.pop();
+                            commits.push(pos);
+                            progress.inc();
+                            if should_interrupt.load(Ordering::Relaxed) {
+                                return Err(Error::Interrupted);
+                            }
+                        }
+                    }
+                }
+            }
+            progress.show_throughput(start);
+
+            let mut is_selected = vec![false; num_objects];
+            for &tip in &tips {
+                is_selected[tip as usize] = true;
+            }
+            if sample_interval != 0 {
+                for &pos in commits.iter().rev().step_by(sample_interval) {
+                    is_selected[pos as usize] = true;
+                }
+            }
+            (commits, is_selected)
+        };
+
+        let bitmaps = {
+            let start = Instant::now();
+            let mut progress = progress.add_child_with_id("Computing bitmaps", *b"BWCB"); /* Bitmap Write Computing Bitmaps */
+            let num_selected = commits.iter().filter(|pos| is_selected[**pos as usize]).count();
+            progress.init(Some(num_selected), git_features::progress::count("bitmaps"));
+
+            let mut bitmaps = Vec::<(u32, Vec<u64>)>::with_capacity(num_selected);
+            let mut bitmap_by_position = HashMap::new();
+            let mut stack = Vec::new();
+            // Commits are ordered such that parents come before their children, allowing to reuse the bitmaps of the former.
+            for &commit in commits.iter().filter(|pos| is_selected[**pos as usize]) {
+                let mut words = vec![0u64; words_for(num_objects)];
+                stack.push(commit);
+                while let Some(pos) = stack.pop() {
+                    if is_set(&words, pos) {
+                        continue;
+                    }
+                    if let Some(&bitmap_index) = bitmap_by_position.get(&pos) {
+                        let (_, reachable): &(u32, Vec<u64>) = &bitmaps[bitmap_index];
+                        for (word, reachable) in words.iter_mut().zip(reachable) {
+                            *word |= reachable;
+                        }
+                        continue;
+                    }
+                    words[pos as usize / 64] |= 1 << (pos % 64);
+                    if graph.kinds[pos as usize] != git_object::Kind::Blob {
+                        stack.extend_from_slice(graph.children(pos)?);
+                    }
+                }
+                bitmap_by_position.insert(commit, bitmaps.len());
+                bitmaps.push((commit, words));
+                progress.inc();
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+            }
+            progress.show_throughput(start);
+            bitmaps
+        };
+
+        let mut out = git_features::hash::Write::new(out, bundle.index.object_hash());
+        out.write_all(Self::SIGNATURE)?;
+        out.write_all(&(bitmap::Version::V1 as u16).to_be_bytes())?;
+        out.write_all(&FLAG_FULL_DAG.to_be_bytes())?;
+        out.write_all(&(bitmaps.len() as u32).to_be_bytes())?;
+        out.write_all(bundle.index.pack_checksum().as_slice())?;
+        for kind in [
+            git_object::Kind::Commit,
+            git_object::Kind::Tree,
+            git_object::Kind::Blob,
+            git_object::Kind::Tag,
+        ] {
+            let mut words = vec![0u64; words_for(num_objects)];
+            for (pos, _) in graph.kinds.iter().enumerate().filter(|(_, k)| **k == kind) {
+                words[pos / 64] |= 1 << (pos % 64);
+            }
+            ewah::Vec::from_words(&words, num_objects as u32).write_to(&mut out)?;
+        }
+        for (pos, words) in &bitmaps {
+            out.write_all(&graph.index_position_of[*pos as usize].to_be_bytes())?;
+            out.write_all(&[0 /*xor offset*/, 0 /*flags*/])?;
+            ewah::Vec::from_words(words, num_objects as u32).write_to(&mut out)?;
+        }
+
+        let bitmap_checksum = git_hash::ObjectId::from(out.hash.digest());
+        out.inner.write_all(bitmap_checksum.as_slice())?;
+        out.inner.flush()?;
+
+        Ok(Outcome {
+            bitmap_checksum,
+            num_bitmaps: bitmaps.len(),
+            progress,
+        })
+    }
+}
+
+fn words_for(num_bits: usize) -> usize {
+    (num_bits + 63) / 64
+}
+
+fn is_set(words: &[u64], pos: u32) -> bool {
+    words[pos as usize / 64] & (1 << (pos % 64)) != 0
+}
+
+/// The objects of a pack in pack order, with their edges decoded lazily.
+struct Graph<'a> {
+    bundle: &'a crate::Bundle,
+    /// The kind of each object in pack order.
+    kinds: Vec<git_object::Kind>,
+    /// The position in pack order of each object in the index.
+    pack_position_of: Vec<u32>,
+    /// The position in the index of each object in pack order.
+    index_position_of: Vec<u32>,
+    /// For commits, their tree followed by their parents, for trees their entries without submodules, and for tags their target.
+    children: Vec<Option<Box<[u32]>>>,
+    buf: Vec<u8>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(bundle: &'a crate::Bundle) -> Result<Self, Error> {
+        let crate::Bundle { pack, index } = bundle;
+        let mut index_position_of: Vec<u32> = (0..index.num_objects()).collect();
+        index_position_of.sort_by_key(|idx| index.pack_offset_at_index(*idx));
+        let mut pack_position_of = vec![0; index_position_of.len()];
+        for (pos, idx) in index_position_of.iter().enumerate() {
+            pack_position_of[*idx as usize] = pos as u32;
+        }
+        let kinds = index_position_of
+            .iter()
+            .map(|idx| {
+                let pack_offset = index.pack_offset_at_index(*idx);
+                let unreadable_base = std::cell::RefCell::new(None);
+                pack.try_entry(pack_offset)
+                    .and_then(|entry| {
+                        pack.decode_header(entry, |id| {
+                            index
+                                .base_entry(pack, id, &unreadable_base)
+                                .map(crate::data::decode_header::ResolvedBase::InPack)
+                        })
+                    })
+                    .map(|outcome| outcome.kind)
+                    .map_err(|err| unreadable_base.take().unwrap_or(err))
+                    .map_err(|source| Error::DecodeEntry { pack_offset, source })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Graph {
+            bundle,
+            kinds,
+            pack_position_of,
+            children: vec![None; index_position_of.len()],
+            index_position_of,
+            buf: Vec::new(),
+        })
+    }
+
+    fn position_of(&self, id: &git_hash::oid) -> Result<u32, Error> {
+        self.bundle
+            .index
+            .lookup(id)
+            .map(|idx| self.pack_position_of[idx as usize])
+            .ok_or_else(|| Error::ObjectNotInPack { id: id.to_owned() })
+    }
+
+    fn children(&mut self, pos: u32) -> Result<&[u32], Error> {
+        if self.children[pos as usize].is_none() {
+            let idx = self.index_position_of[pos as usize];
+            let kind = self.kinds[pos as usize];
+            let id = self.bundle.index.oid_at_index(idx).to_owned();
+            let pack_offset = self.bundle.index.pack_offset_at_index(idx);
+            let (object, _location) = self
+                .bundle
+                .get_object_by_index(idx, &mut self.buf, &mut crate::cache::Never)
+                .map_err(|source| Error::DecodeEntry { pack_offset, source })?;
+            let decode_err = |source| Error::Decode { kind, id, source };
+            let ids = match kind {
+                git_object::Kind::Commit => {
+                    let mut iter = git_object::CommitRefIter::from_bytes(object.data);
+                    let tree = iter.tree_id().map_err(decode_err)?;
+                    std::iter::once(tree)
+                        .chain(git_object::CommitRefIter::parent_ids_from_bytes(object.data).map_err(decode_err)?)
+                        .collect()
+                }
+                git_object::Kind::Tree => git_object::TreeRefIter::from_bytes(object.data)
+                    .filter_map(|entry| match entry {
+                        Ok(entry) if entry.mode.is_commit() => None,
+                        Ok(entry) => Some(Ok(entry.oid.to_owned())),
+                        Err(err) => Some(Err(decode_err(err))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                git_object::Kind::Tag => vec![git_object::TagRefIter::from_bytes(object.data)
+                    .target_id()
+                    .map_err(decode_err)?],
+                git_object::Kind::Blob => Vec::new(),
+            };
+            let children = ids
+                .iter()
+                .map(|id| self.position_of(id))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.children[pos as usize] = Some(children.into());
+        }
+        Ok(self.children[pos as usize].as_deref().expect("just set"))
+    }
+}
@@ -31,19 +31,21 @@ impl crate::Bundle {
         cache: &mut impl crate::cache::DecodeEntry,
     ) -> Result<(git_object::Data<'a>, crate::data::entry::Location), crate::data::decode_entry::Error> {
         let ofs = self.index.pack_offset_at_index(idx);
-        let pack_entry = self.pack.entry(ofs);
+        let pack_entry = self.pack.try_entry(ofs)?;
         let header_size = pack_entry.header_size();
+        let unreadable_base = std::cell::RefCell::new(None);
         self.pack
             .decode_entry(
                 pack_entry,
                 out,
                 |id, _out| {
-                    self.index.lookup(id).map(|idx| {
-                        crate::data::ResolvedBase::InPack(self.pack.entry(self.index.pack_offset_at_index(idx)))
-                    })
+                    self.index
+                        .base_entry(&self.pack, id, &unreadable_base)
+                        .map(crate::data::ResolvedBase::InPack)
                 },
                 cache,
             )
+            .map_err(|err| unreadable_base.take().unwrap_or(err))
             .map(move |r| {
                 (
                     git_object::Data {
@@ -9,11 +9,38 @@ pub enum Error {
     #[error("An 'idx' extension is expected of an index file: '{0}'")]
     InvalidPath(PathBuf),
     #[error(transparent)]
-    Pack(#[from] crate::data::header::decode::Error),
+    Pack(#[from] crate::data::init::Error),
     #[error(transparent)]
     Index(#[from] crate::index::init::Error),
 }
 
+/// A problem found while opening a [`Bundle`] which doesn't prevent its use, but suggests that pack and index don't belong together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// The pack header declares a different amount of objects than there are entries in the index.
+    ObjectCountMismatch {
+        /// The amount of objects declared in the header of the pack.
+        pack_objects: u32,
+        /// The amount of entries in the index.
+        index_objects: u32,
+    },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::ObjectCountMismatch {
+                pack_objects,
+                index_objects,
+            } => write!(
+                f,
+                "The pack declares {} objects in its header, but its index has {} entries",
+                pack_objects, index_objects
+            ),
+        }
+    }
+}
+
 /// Initialization
 impl Bundle {
     /// Create a `Bundle` from `path`, which is either a pack file _(*.pack)_ or an index file _(*.idx)_.
@@ -26,6 +53,28 @@ impl Bundle {
         Self::at_inner(path.as_ref(), object_hash)
     }
 
+    /// Like [`at()`][Bundle::at()], but also check if pack and index agree with each other and return all [warnings][Warning]
+    /// for problems that don't prevent the bundle from being used.
+    ///
+    /// Tools that want to proceed anyway can present these, while [`verify_integrity()`][Bundle::verify_integrity()]
+    /// fails on them.
+    pub fn at_with_warnings(
+        path: impl AsRef<Path>,
+        object_hash: git_hash::Kind,
+    ) -> Result<(Self, Vec<Warning>), Error> {
+        let bundle = Self::at_inner(path.as_ref(), object_hash)?;
+        let warnings = bundle.object_count_mismatch().into_iter().collect();
+        Ok((bundle, warnings))
+    }
+
+    fn object_count_mismatch(&self) -> Option<Warning> {
+        let (pack_objects, index_objects) = (self.pack.num_objects(), self.index.num_objects());
+        (pack_objects != index_objects).then(|| Warning::ObjectCountMismatch {
+            pack_objects,
+            index_objects,
+        })
+    }
+
     fn at_inner(path: &Path, object_hash: git_hash::Kind) -> Result<Self, Error> {
         let ext = path
             .extension()
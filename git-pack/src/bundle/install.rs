@@ -0,0 +1,170 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Determines how much effort is spent on assuring installed files survive a crash of the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Durability {
+    /// Leave it to the operating system to write files and directory entries to disk eventually.
+    Relaxed,
+    /// Write the content of each file to disk before moving it, and the directory after each move.
+    ///
+    /// This is slower, but assures that after a crash of the system no bundle is discoverable while its files are incomplete.
+    Fsync,
+}
+
+impl Default for Durability {
+    fn default() -> Self {
+        Durability::Relaxed
+    }
+}
+
+/// The temporary files making up a bundle, which are moved into place by [`install()`][crate::bundle::install()].
+///
+/// They must be in the same file system as the destination directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Files {
+    /// The pack data file.
+    pub data: PathBuf,
+    /// The pack index file.
+    pub index: PathBuf,
+    /// The reverse index file, if there is one.
+    pub rev: Option<PathBuf>,
+}
+
+/// Returned by [`install()`][crate::bundle::install()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The path to the installed pack data file.
+    pub data_path: PathBuf,
+    /// The path to the installed pack index file.
+    pub index_path: PathBuf,
+    /// The path to the installed reverse index file, if one was installed.
+    pub rev_path: Option<PathBuf>,
+}
+
+/// The error returned by [`install()`][crate::bundle::install()].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not move '{}' to '{}'", from.display(), to.display())]
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+        source: io::Error,
+        installed: Vec<PathBuf>,
+    },
+    #[error("Could not write '{}' to disk", path.display())]
+    Sync {
+        path: PathBuf,
+        source: io::Error,
+        installed: Vec<PathBuf>,
+    },
+}
+
+impl Error {
+    /// The files which were installed and couldn't be removed again, and which are discoverable despite the failure.
+    ///
+    /// This is empty if the directory is as it was before the installation was attempted.
+    pub fn installed(&self) -> &[PathBuf] {
+        match self {
+            Error::Rename { installed, .. } | Error::Sync { installed, .. } => installed,
+        }
+    }
+}
+
+/// Move the temporary `files` of a bundle into `directory`, naming them after `pack_hash` like `pack-<pack_hash>.pack`,
+/// so that the bundle is never discoverable while any of its files is missing.
+///
+/// The pack data file is moved first, followed by the reverse index and finally the index, as the index is what makes
+/// the bundle discoverable. With [`Durability::Fsync`], file contents are written to disk before moving the first file
+/// and `directory` is written to disk after each move.
+///
+/// On failure, the files which were moved already are removed again, while files which existed at their destination
+/// before are left in place. [`Error::installed()`] tells which files couldn't be removed. Files which weren't moved yet
+/// remain at their temporary location.
+pub fn install(
+    directory: impl AsRef<Path>,
+    pack_hash: &git_hash::oid,
+    files: Files,
+    durability: Durability,
+) -> Result<Outcome, Error> {
+    let directory = directory.as_ref();
+    let data_path = directory.join(format!("pack-{}.pack", pack_hash.to_hex()));
+    let outcome = Outcome {
+        index_path: data_path.with_extension("idx"),
+        rev_path: files.rev.as_ref().map(|_| data_path.with_extension("rev")),
+        data_path,
+    };
+
+    let moves: Vec<_> = Some((files.data, outcome.data_path.clone()))
+        .into_iter()
+        .chain(files.rev.zip(outcome.rev_path.clone()))
+        .chain(Some((files.index, outcome.index_path.clone())))
+        .collect();
+    if durability == Durability::Fsync {
+        for (from, _) in &moves {
+            sync_file(from).map_err(|source| Error::Sync {
+                path: from.clone(),
+                source,
+                installed: Vec::new(),
+            })?;
+        }
+    }
+
+    let mut created = Vec::new();
+    for (from, to) in moves {
+        let existed = to.symlink_metadata().is_ok();
+        if let Err(source) = std::fs::rename(&from, &to) {
+            return Err(Error::Rename {
+                from,
+                to,
+                source,
+                installed: roll_back(directory, created, durability),
+            });
+        }
+        if !existed {
+            created.push(to);
+        }
+        if durability == Durability::Fsync {
+            if let Err(source) = sync_directory(directory) {
+                return Err(Error::Sync {
+                    path: directory.to_owned(),
+                    source,
+                    installed: roll_back(directory, created, durability),
+                });
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+/// Remove all `created` files in reverse order and return those which couldn't be removed.
+fn roll_back(directory: &Path, created: Vec<PathBuf>, durability: Durability) -> Vec<PathBuf> {
+    let installed: Vec<_> = created
+        .into_iter()
+        .rev()
+        .filter(|path| std::fs::remove_file(path).is_err())
+        .collect();
+    if durability == Durability::Fsync {
+        sync_directory(directory).ok();
+    }
+    installed
+}
+
+fn sync_file(path: &Path) -> io::Result<()> {
+    std::fs::OpenOptions::new().write(true).open(path)?.sync_all()
+}
+
+#[cfg(unix)]
+fn sync_directory(directory: &Path) -> io::Result<()> {
+    std::fs::File::open(directory)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_directory(_directory: &Path) -> io::Result<()> {
+    // Directories can only be opened for syncing on unix.
+    Ok(())
+}
@@ -3,6 +3,9 @@ pub mod init;
 
 mod find;
 ///
+pub mod install;
+pub use install::install;
+///
 pub mod write;
 
 ///
@@ -14,13 +17,23 @@ pub mod verify {
     ///
     pub mod integrity {
         /// Returned by [`Bundle::verify_integrity()`][crate::Bundle::verify_integrity()].
-        pub struct Outcome<P> {
+        pub struct Outcome {
             /// The computed checksum of the index which matched the stored one.
             pub actual_index_checksum: git_hash::ObjectId,
             /// The packs traversal outcome
             pub pack_traverse_outcome: crate::index::traverse::Statistics,
-            /// The provided progress instance.
-            pub progress: P,
+            /// All trees with problematic entries, sorted by object id.
+            pub tree_issues: Vec<crate::index::verify::integrity::TreeIssues>,
+            /// Information about how objects were decoded.
+            pub decode_statistics: crate::index::verify::integrity::DecodeStatistics,
+            /// The ids of all ref-delta bases missing in the pack, sorted and without duplicates.
+            pub missing_delta_bases: Vec<git_hash::ObjectId>,
+            /// The amount of objects that caused a warning of each class.
+            pub warnings_by_class: crate::index::verify::integrity::WarningsByClass,
+            /// Information about the index file that was verified, or `None` if it was created from bytes.
+            pub index_file: Option<crate::verify::FileInfo>,
+            /// Information about the pack data file that was verified, or `None` if it was created from bytes.
+            pub pack_file: Option<crate::verify::FileInfo>,
         }
     }
 
@@ -31,10 +44,10 @@ pub mod verify {
         /// pack file is a given.
         pub fn verify_integrity<C, P, F>(
             &self,
-            progress: P,
+            progress: &mut P,
             should_interrupt: &AtomicBool,
             options: crate::index::verify::integrity::Options<F>,
-        ) -> Result<integrity::Outcome<P>, crate::index::traverse::Error<crate::index::verify::integrity::Error>>
+        ) -> Result<integrity::Outcome, crate::index::traverse::Error<crate::index::verify::integrity::Error>>
         where
             P: Progress,
             C: crate::cache::DecodeEntry,
@@ -52,7 +65,12 @@ pub mod verify {
                 .map(|o| integrity::Outcome {
                     actual_index_checksum: o.actual_index_checksum,
                     pack_traverse_outcome: o.pack_traverse_statistics.expect("pack is set"),
-                    progress: o.progress,
+                    tree_issues: o.tree_issues,
+                    decode_statistics: o.decode_statistics,
+                    missing_delta_bases: o.missing_delta_bases,
+                    warnings_by_class: o.warnings_by_class,
+                    index_file: o.index_file,
+                    pack_file: o.pack_file,
                 })
         }
     }
@@ -14,4 +14,6 @@ pub enum Error {
     Perist(#[from] git_tempfile::handle::persist::Error<Writable>),
     #[error(transparent)]
     IndexWrite(#[from] crate::index::write::Error),
+    #[error(transparent)]
+    Install(#[from] crate::bundle::install::Error),
 }
@@ -254,6 +254,8 @@ impl crate::Bundle {
             iteration_mode: _,
             index_version: index_kind,
             object_hash,
+            max_decompressed_object_size,
+            durability,
         }: Options,
         data_file: SharedTempFile,
         pack_entries_iter: impl Iterator<Item = Result<data::input::Entry, data::input::Error>>,
@@ -274,6 +276,7 @@ impl crate::Bundle {
                     },
                     pack_entries_iter,
                     thread_limit,
+                    max_decompressed_object_size,
                     indexing_progress,
                     &mut index_file,
                     should_interrupt,
@@ -281,26 +284,41 @@ impl crate::Bundle {
                     pack_version,
                 )?;
 
-                let data_path = directory.join(format!("pack-{}.pack", outcome.data_hash.to_hex()));
-                let index_path = data_path.with_extension("idx");
-                let keep_path = data_path.with_extension("keep");
-
+                let keep_path = directory.join(format!("pack-{}.keep", outcome.data_hash.to_hex()));
+                let keep_existed = keep_path.is_file();
                 std::fs::write(&keep_path, b"")?;
-                Arc::try_unwrap(data_file)
+
+                let data_file = Arc::try_unwrap(data_file)
                     .expect("only one handle left after pack was consumed")
                     .into_inner()
                     .into_inner()
-                    .map_err(|err| Error::from(err.into_error()))?
-                    .persist(&data_path)?;
-                index_file
-                    .persist(&index_path)
-                    .map_err(|err| {
-                        progress.info(format!(
-                            "pack file at {} is retained despite failing to move the index file into place. You can use plumbing to make it usable.",
-                            data_path.display()
-                        ));
-                        err
-                    })?;
+                    .map_err(|err| Error::from(err.into_error()))?;
+                let (data_file, index_file) = match (data_file.take(), index_file.take()) {
+                    (Some(data_file), Some(index_file)) => (data_file.into_temp_path(), index_file.into_temp_path()),
+                    _ => {
+                        return Err(Error::Io(io::Error::new(
+                            io::ErrorKind::Interrupted,
+                            "temporary files were removed",
+                        )))
+                    }
+                };
+                let installed = crate::bundle::install(
+                    directory,
+                    &outcome.data_hash,
+                    crate::bundle::install::Files {
+                        data: data_file.to_path_buf(),
+                        index: index_file.to_path_buf(),
+                        rev: None,
+                    },
+                    durability,
+                )
+                .map_err(|err| {
+                    if !keep_existed {
+                        std::fs::remove_file(&keep_path).ok();
+                    }
+                    err
+                })?;
+                let (data_path, index_path) = (installed.data_path, installed.index_path);
                 WriteOutcome {
                     outcome,
                     data_path: Some(data_path),
@@ -314,6 +332,7 @@ impl crate::Bundle {
                     move || new_pack_file_resolver(data_file),
                     pack_entries_iter,
                     thread_limit,
+                    max_decompressed_object_size,
                     indexing_progress,
                     io::sink(),
                     should_interrupt,
@@ -14,6 +14,13 @@ pub struct Options {
     pub index_version: crate::index::Version,
     /// The kind of hash to use when writing the bundle.
     pub object_hash: git_hash::Kind,
+    /// If `Some`, fail if any object or delta in the pack would decompress to more than the given amount of bytes
+    /// while resolving it.
+    ///
+    /// Defaults to [`DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE`][crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE].
+    pub max_decompressed_object_size: Option<u64>,
+    /// How much effort to spend on assuring the written files survive a crash of the system once they are in place.
+    pub durability: crate::bundle::install::Durability,
 }
 
 impl Default for Options {
@@ -24,6 +31,8 @@ impl Default for Options {
             iteration_mode: crate::data::input::Mode::Verify,
             index_version: Default::default(),
             object_hash: Default::default(),
+            max_decompressed_object_size: Some(crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+            durability: Default::default(),
         }
     }
 }
@@ -47,7 +56,7 @@ pub struct Outcome {
     /// The path to the `.keep` file to prevent collection of the newly written pack until refs are pointing to it.
     ///
     /// The file is created right before moving the pack data and index data into place (i.e. `data_path` and `index_path`)
-    /// and is expected to be removed by the caller when ready.
+    /// and is expected to be removed by the caller when ready. It is removed again if the files couldn't be moved into place.
     pub keep_path: Option<PathBuf>,
 }
 
@@ -0,0 +1,117 @@
+use git_object::bstr::{BString, ByteSlice};
+
+use crate::bundle_file::{Capability, Header, Prerequisite, Ref, Version};
+
+/// Returned by [`Header::from_bytes()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Expected a bundle signature like '# v2 git bundle', got {line:?}")]
+    InvalidSignature { line: BString },
+    #[error("The header ended before the empty line separating it from the pack")]
+    UnexpectedEnd,
+    #[error("Object format {name:?} is unsupported")]
+    UnsupportedObjectFormat { name: BString },
+    #[error("Capabilities are only allowed in V3 bundles, got {line:?}")]
+    UnexpectedCapability { line: BString },
+    #[error("Prerequisite line {line:?} doesn't start with a valid object id")]
+    InvalidPrerequisite { line: BString },
+    #[error("Reference line {line:?} isn't an object id followed by a reference name")]
+    InvalidRef { line: BString },
+}
+
+const V2_SIGNATURE: &[u8] = b"# v2 git bundle";
+const V3_SIGNATURE: &[u8] = b"# v3 git bundle";
+
+/// Decoding
+impl Header {
+    /// Parse the header at the beginning of the bundle file `data`, returning it along with the amount of bytes it occupies,
+    /// which is the offset at which the pack starts.
+    pub fn from_bytes(data: &[u8]) -> Result<(Header, usize), Error> {
+        let mut pos = 0;
+        let version = match next_line(data, &mut pos)? {
+            V2_SIGNATURE => Version::V2,
+            V3_SIGNATURE => Version::V3,
+            line => return Err(Error::InvalidSignature { line: line.into() }),
+        };
+
+        let mut object_hash = git_hash::Kind::Sha1;
+        let mut capabilities = Vec::new();
+        let mut prerequisites = Vec::new();
+        let mut refs = Vec::new();
+        loop {
+            let line = next_line(data, &mut pos)?;
+            if line.is_empty() {
+                break;
+            }
+            if let Some(capability) = line.strip_prefix(b"@") {
+                if version == Version::V2 || !prerequisites.is_empty() || !refs.is_empty() {
+                    return Err(Error::UnexpectedCapability { line: line.into() });
+                }
+                let capability = match capability.find_byte(b'=') {
+                    Some(pos) => Capability {
+                        name: capability[..pos].into(),
+                        value: Some(capability[pos + 1..].into()),
+                    },
+                    None => Capability {
+                        name: capability.into(),
+                        value: None,
+                    },
+                };
+                if capability.name == "object-format" {
+                    let name = capability.value.clone().unwrap_or_default();
+                    object_hash = name
+                        .to_str()
+                        .ok()
+                        .and_then(|name| name.parse().ok())
+                        .ok_or(Error::UnsupportedObjectFormat { name })?;
+                }
+                capabilities.push(capability);
+            } else if let Some(prerequisite) = line.strip_prefix(b"-") {
+                let (id, comment) = split_id(prerequisite, object_hash)
+                    .ok_or_else(|| Error::InvalidPrerequisite { line: line.into() })?;
+                prerequisites.push(Prerequisite {
+                    id,
+                    comment: comment.into(),
+                });
+            } else {
+                let (target, name) = split_id(line, object_hash)
+                    .filter(|(_, name)| !name.is_empty())
+                    .ok_or_else(|| Error::InvalidRef { line: line.into() })?;
+                refs.push(Ref {
+                    name: name.into(),
+                    target,
+                });
+            }
+        }
+        Ok((
+            Header {
+                version,
+                object_hash,
+                capabilities,
+                prerequisites,
+                refs,
+            },
+            pos,
+        ))
+    }
+}
+
+fn next_line<'a>(data: &'a [u8], pos: &mut usize) -> Result<&'a [u8], Error> {
+    let rest = &data[*pos..];
+    let end = rest.find_byte(b'\n').ok_or(Error::UnexpectedEnd)?;
+    *pos += end + 1;
+    Ok(&rest[..end])
+}
+
+/// Split `line` into the object id at its beginning and whatever follows the space after it, which may be empty.
+fn split_id(line: &[u8], object_hash: git_hash::Kind) -> Option<(git_hash::ObjectId, &[u8])> {
+    let hex_len = object_hash.len_in_hex();
+    let id = git_hash::ObjectId::from_hex(line.get(..hex_len)?).ok()?;
+    let rest = match line.get(hex_len..)? {
+        [] => &[][..],
+        [b' ', rest @ ..] => rest,
+        _ => return None,
+    };
+    Some((id, rest))
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use crate::bundle_file;
+
+/// Returned by [`bundle_file::File::at()`] and [`bundle_file::File::from_bytes()`].
+///
+/// Each variant carries the path of the bundle file, which is synthetic for files [held in memory][bundle_file::File::from_bytes()].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open bundle file at '{path}'")]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("The header of bundle file at '{path}' could not be decoded")]
+    Header {
+        source: bundle_file::decode::Error,
+        path: std::path::PathBuf,
+    },
+}
+
+/// Instantiation
+impl bundle_file::File {
+    /// Open the bundle file at the given `path` and decode its header.
+    ///
+    /// The pack within it is only checked once it's [opened][bundle_file::File::pack()].
+    pub fn at(path: impl AsRef<Path>) -> Result<bundle_file::File, Error> {
+        Self::at_inner(path.as_ref())
+    }
+
+    /// Instantiate a bundle file from `data` held in memory, for example after receiving it over the network.
+    ///
+    /// As there is no path on disk, [`path()`][bundle_file::File::path()] returns a synthetic path based on the pack checksum.
+    pub fn from_bytes(data: Vec<u8>) -> Result<bundle_file::File, Error> {
+        Self::from_data(crate::mmap::Data::InMemory(data.into()), None)
+    }
+
+    fn at_inner(path: &Path) -> Result<bundle_file::File, Error> {
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        Self::from_data(crate::mmap::Data::Mapped(data.into()), Some(path))
+    }
+
+    fn from_data(data: crate::mmap::Data, path: Option<&Path>) -> Result<bundle_file::File, Error> {
+        let header = bundle_file::Header::from_bytes(&data);
+        let path = match path {
+            Some(path) => path.to_owned(),
+            None => {
+                let hash_len = header
+                    .as_ref()
+                    .map_or(git_hash::Kind::Sha1, |(header, _)| header.object_hash)
+                    .len_in_bytes();
+                crate::mmap::in_memory_path(&data[data.len().saturating_sub(hash_len)..], "bundle")
+            }
+        };
+        let (header, pack_offset) = header.map_err(|source| Error::Header {
+            source,
+            path: path.clone(),
+        })?;
+        Ok(bundle_file::File {
+            header,
+            data,
+            pack_offset,
+            path,
+        })
+    }
+}
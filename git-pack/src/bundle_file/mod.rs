@@ -0,0 +1,136 @@
+//! Git bundle files as created by `git bundle create`, which combine the references to advertise with a pack of the
+//! objects they need, to transport history without a network connection.
+use std::path::PathBuf;
+
+use git_object::bstr::BString;
+
+/// The version of a bundle file, as stated in its signature line.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Version {
+    /// The original format, which implies SHA1 and can't declare capabilities.
+    V2,
+    /// The format which can declare [capabilities][Capability], like the `object-format` of the objects within.
+    V3,
+}
+
+/// A `@name[=value]` line in the header of a [V3][Version::V3] bundle.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capability {
+    /// The name of the capability, like `object-format` or `filter`.
+    pub name: BString,
+    /// The value of the capability, if there was one.
+    pub value: Option<BString>,
+}
+
+/// An object the pack of a bundle depends on without containing it, which has to exist wherever the bundle is unbundled.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Prerequisite {
+    /// The id of the object that is required.
+    pub id: git_hash::ObjectId,
+    /// A comment to help humans, typically the subject of the commit, or an empty string.
+    pub comment: BString,
+}
+
+/// A reference advertised by a bundle.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ref {
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+    /// The object the reference points to, which is contained in the pack or is one of its prerequisites.
+    pub target: git_hash::ObjectId,
+}
+
+/// Everything in a bundle file in front of its pack.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Header {
+    /// The version of the bundle.
+    pub version: Version,
+    /// The kind of hash used for all object ids in the header and in the pack.
+    pub object_hash: git_hash::Kind,
+    /// The capabilities of [V3][Version::V3] bundles in order of appearance, which is always empty for [V2][Version::V2].
+    ///
+    /// Note that the `object-format` capability is reflected in [`object_hash`][Header::object_hash] as well.
+    pub capabilities: Vec<Capability>,
+    /// The objects the pack depends on without containing them.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references advertised by the bundle.
+    pub refs: Vec<Ref>,
+}
+
+impl Header {
+    /// Create a new header for the given `refs`, using the most compatible version able to represent `object_hash`.
+    pub fn new(refs: Vec<Ref>, prerequisites: Vec<Prerequisite>, object_hash: git_hash::Kind) -> Self {
+        Header {
+            version: match object_hash {
+                git_hash::Kind::Sha1 => Version::V2,
+            },
+            object_hash,
+            capabilities: Vec::new(),
+            prerequisites,
+            refs,
+        }
+    }
+}
+
+/// A bundle file, consisting of a [`Header`] followed by a pack.
+///
+/// Clones are cheap as they share the underlying data.
+#[derive(Clone)]
+pub struct File {
+    header: Header,
+    data: crate::mmap::Data,
+    pack_offset: usize,
+    path: PathBuf,
+}
+
+/// Access
+impl File {
+    /// The header of the bundle with its references and prerequisites.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// The path of the bundle file on disk, or a synthetic one based on the pack checksum if it was [created from bytes][File::from_bytes()].
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// The offset in bytes at which the pack starts within the bundle file.
+    pub fn pack_offset(&self) -> usize {
+        self.pack_offset
+    }
+
+    /// The bytes of the pack within the bundle, from its `PACK` signature to its trailing checksum.
+    ///
+    /// These can be passed to [`Bundle::write_to_directory()`][crate::Bundle::write_to_directory()] to index the pack.
+    pub fn pack_data(&self) -> &[u8] {
+        &self.data[self.pack_offset..]
+    }
+
+    /// Return the pack within the bundle as data file, sharing the data of the bundle without copying it.
+    ///
+    /// Its [path][crate::data::File::path()] is the one of the bundle unless the bundle is held in memory.
+    pub fn pack(&self) -> Result<crate::data::File, crate::data::init::Error> {
+        crate::data::File::from_data(
+            crate::mmap::Data::Slice {
+                data: Box::new(self.data.clone()),
+                range: self.pack_offset..self.data.len(),
+            },
+            (!self.data.is_in_memory()).then(|| self.path.as_path()),
+            self.header.object_hash,
+        )
+    }
+}
+
+///
+pub mod decode;
+///
+pub mod init;
+///
+pub mod write;
+pub use write::write;
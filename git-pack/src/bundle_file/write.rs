@@ -0,0 +1,114 @@
+use std::io;
+
+use crate::bundle_file::{Capability, Header, Version};
+
+/// Returned by [`Header::write_to()`] and [`write()`][crate::bundle_file::write()].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Refusing to write a bundle without references")]
+    NoRefs,
+    #[error("V2 bundles can't declare capabilities or use object format {object_hash}")]
+    NeedsV3 { object_hash: git_hash::Kind },
+    #[error("The name of reference {name:?} can't be represented in a bundle header")]
+    InvalidRefName { name: git_object::bstr::BString },
+    #[error("The object-format capability declares {declared:?}, but the objects are hashed with {object_hash}")]
+    ObjectFormatMismatch {
+        declared: Option<git_object::bstr::BString>,
+        object_hash: git_hash::Kind,
+    },
+}
+
+/// Encoding
+impl Header {
+    /// Write this header to `out` in a way that [`Header::from_bytes()`] can read it back, including the empty line
+    /// after which the pack is expected.
+    ///
+    /// [V3][Version::V3] headers always declare their `object-format`, which is added if it's not part of the capabilities.
+    /// If it is, it must name the [`object_hash`][Header::object_hash].
+    pub fn write_to(&self, mut out: impl io::Write) -> Result<(), Error> {
+        if self.refs.is_empty() {
+            return Err(Error::NoRefs);
+        }
+        if let Some(r) = self.refs.iter().find(|r| r.name.is_empty() || r.name.contains(&b'\n')) {
+            return Err(Error::InvalidRefName { name: r.name.clone() });
+        }
+        match self.version {
+            Version::V2 => {
+                if !self.capabilities.is_empty() || self.object_hash != git_hash::Kind::Sha1 {
+                    return Err(Error::NeedsV3 {
+                        object_hash: self.object_hash,
+                    });
+                }
+                out.write_all(b"# v2 git bundle\n")?;
+            }
+            Version::V3 => {
+                if let Some(c) = self
+                    .capabilities
+                    .iter()
+                    .find(|c| c.name == "object-format" && !declares(c, self.object_hash))
+                {
+                    return Err(Error::ObjectFormatMismatch {
+                        declared: c.value.clone(),
+                        object_hash: self.object_hash,
+                    });
+                }
+                out.write_all(b"# v3 git bundle\n")?;
+                if !self.capabilities.iter().any(|c| c.name == "object-format") {
+                    writeln!(
+                        out,
+                        "@object-format={}",
+                        self.object_hash.to_string().to_ascii_lowercase()
+                    )?;
+                }
+                for capability in &self.capabilities {
+                    out.write_all(b"@")?;
+                    out.write_all(&capability.name)?;
+                    if let Some(value) = &capability.value {
+                        out.write_all(b"=")?;
+                        out.write_all(value)?;
+                    }
+                    out.write_all(b"\n")?;
+                }
+            }
+        }
+        for prerequisite in &self.prerequisites {
+            write!(out, "-{}", prerequisite.id)?;
+            if !prerequisite.comment.is_empty() {
+                out.write_all(b" ")?;
+                out.write_all(&prerequisite.comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for r in &self.refs {
+            write!(out, "{} ", r.target)?;
+            out.write_all(&r.name)?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+fn declares(object_format: &Capability, object_hash: git_hash::Kind) -> bool {
+    object_format
+        .value
+        .as_ref()
+        .and_then(|name| std::str::from_utf8(name).ok())
+        .and_then(|name| name.parse::<git_hash::Kind>().ok())
+        == Some(object_hash)
+}
+
+/// Write a bundle file consisting of `header` and the `pack` data stream to `out`, returning the amount of bytes of the pack.
+///
+/// The `pack` is copied as is, and is expected to contain all objects reachable from the
+/// [references][Header::refs] of the `header`, except for the [prerequisites][Header::prerequisites] and their ancestry.
+/// It's typically produced by [`data::output::bytes::FromEntriesIter`][crate::data::output::bytes::FromEntriesIter].
+pub fn write(header: &Header, mut pack: impl io::Read, mut out: impl io::Write) -> Result<u64, Error> {
+    header.write_to(&mut out)?;
+    let pack_len = io::copy(&mut pack, &mut out)?;
+    out.flush()?;
+    Ok(pack_len)
+}
@@ -1,7 +1,7 @@
 use std::{
     convert::TryFrom,
     fs, io,
-    io::{BufRead, Read, Seek, SeekFrom},
+    io::{BufRead, Seek, SeekFrom},
     sync::atomic::{AtomicBool, Ordering},
     time::Instant,
 };
@@ -46,18 +46,41 @@ impl<T> Tree<T> {
         data_sorted_by_offsets: impl Iterator<Item = T>,
         get_pack_offset: impl Fn(&T) -> data::Offset,
         resolve_in_pack_id: impl Fn(&git_hash::oid) -> Option<data::Offset>,
-        mut progress: impl Progress,
+        progress: impl Progress,
         should_interrupt: &AtomicBool,
         object_hash: git_hash::Kind,
     ) -> Result<Self, Error> {
-        let mut r = io::BufReader::with_capacity(
+        let r = io::BufReader::with_capacity(
             8192 * 8, // this value directly corresponds to performance, 8k (default) is about 4x slower than 64k
             fs::File::open(pack_path).map_err(|err| Error::Io {
                 source: err,
                 message: "open pack path",
             })?,
         );
+        Self::from_offsets_in_pack_reader(
+            r,
+            data_sorted_by_offsets,
+            get_pack_offset,
+            resolve_in_pack_id,
+            progress,
+            should_interrupt,
+            object_hash,
+        )
+    }
 
+    /// Like [`from_offsets_in_pack()`][Tree::from_offsets_in_pack()], but reads pack entries from `pack`,
+    /// which must be positioned at the beginning of the pack data, instead of opening a file.
+    ///
+    /// This is useful for packs which only exist in memory.
+    pub fn from_offsets_in_pack_reader(
+        mut r: impl BufRead + Seek,
+        data_sorted_by_offsets: impl Iterator<Item = T>,
+        get_pack_offset: impl Fn(&T) -> data::Offset,
+        resolve_in_pack_id: impl Fn(&git_hash::oid) -> Option<data::Offset>,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        object_hash: git_hash::Kind,
+    ) -> Result<Self, Error> {
         let anticpiated_num_objects = if let Some(num_objects) = data_sorted_by_offsets.size_hint().1 {
             progress.init(Some(num_objects), progress::count("objects"));
             num_objects
@@ -79,10 +102,19 @@ impl<T> Tree<T> {
         let then = Instant::now();
 
         let mut previous_cursor_position = None::<u64>;
+        let mut previous_pack_offset = None::<u64>;
 
         let hash_len = object_hash.len_in_bytes();
         for (idx, data) in data_sorted_by_offsets.enumerate() {
             let pack_offset = get_pack_offset(&data);
+            if let Some(last_pack_offset) = previous_pack_offset.filter(|previous| pack_offset <= *previous) {
+                return Err(crate::cache::delta::Error::InvariantIncreasingPackOffset {
+                    last_pack_offset,
+                    pack_offset,
+                }
+                .into());
+            }
+            previous_pack_offset = Some(pack_offset);
             if let Some(previous_offset) = previous_cursor_position {
                 Self::advance_cursor_to_pack_offset(&mut r, pack_offset, previous_offset)?;
             };
@@ -122,7 +154,7 @@ impl<T> Tree<T> {
     }
 
     fn advance_cursor_to_pack_offset(
-        r: &mut io::BufReader<fs::File>,
+        r: &mut (impl BufRead + Seek),
         pack_offset: u64,
         previous_offset: u64,
     ) -> Result<(), Error> {
@@ -25,6 +25,11 @@ pub enum Error {
     },
     #[error("The resolver failed to obtain the pack entry bytes for the entry at {pack_offset}")]
     ResolveFailed { pack_offset: u64 },
+    #[error("The entry at pack offset {pack_offset} ends at {entry_end} and has no compressed data, which happens if its offset is duplicated in the index")]
+    EmptyEntry {
+        pack_offset: crate::data::Offset,
+        entry_end: crate::data::Offset,
+    },
     #[error("One of the object inspectors failed")]
     Inspect(#[from] Box<dyn std::error::Error + Send + Sync>),
     #[error("Interrupted")]
@@ -36,6 +41,19 @@ pub enum Error {
         /// The base's offset which was from a resolved ref-delta that didn't actually get added to the tree
         base_pack_offset: crate::data::Offset,
     },
+    #[error("The delta at pack offset {pack_offset} could not be applied")]
+    DeltaApply {
+        pack_offset: crate::data::Offset,
+        source: crate::data::delta::apply::Error,
+    },
+    #[error(
+        "The entry at pack offset {pack_offset} would decompress to {size} bytes, exceeding the limit of {limit} bytes"
+    )]
+    SizeLimitExceeded {
+        pack_offset: crate::data::Offset,
+        size: u64,
+        limit: u64,
+    },
 }
 
 /// Additional context passed to the `inspect_object(…)` function of the [`Tree::traverse()`] method.
@@ -67,6 +85,8 @@ pub struct Options<'a, P1, P2> {
     /// specifies what kind of hashes we expect to be stored in oid-delta entries, which is viable to decoding them
     /// with the correct size.
     pub object_hash: git_hash::Kind,
+    /// If `Some`, fail if any object or delta would decompress to more than the given amount of bytes.
+    pub max_decompressed_object_size: Option<u64>,
 }
 
 /// The outcome of [`Tree::traverse()`]
@@ -99,24 +119,55 @@ where
     ///
     /// _Note_ that this method consumed the Tree to assure safe parallel traversal with mutation support.
     pub fn traverse<F, P1, P2, MBFN, S, E>(
+        self,
+        resolve: F,
+        pack_entries_end: u64,
+        new_thread_state: impl Fn() -> S + Send + Clone,
+        inspect_object: MBFN,
+        options: Options<'_, P1, P2>,
+    ) -> Result<Outcome<T>, Error>
+    where
+        F: for<'r> Fn(EntryRange, &'r mut Vec<u8>) -> Option<()> + Send + Clone,
+        P1: Progress,
+        P2: Progress,
+        MBFN: Fn(&mut T, &mut <P1 as Progress>::SubProgress, Context<'_, S>) -> Result<(), E> + Send + Clone,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.traverse_with_thread_states(
+            resolve,
+            pack_entries_end,
+            new_thread_state,
+            inspect_object,
+            |_| (),
+            options,
+        )
+        .map(|(outcome, _)| outcome)
+    }
+
+    /// Like [`traverse()`][Tree::traverse()], but turn the state of each thread into a value with `state_to_rval(State)` once
+    /// the thread is done, and return these values along with the outcome.
+    pub(crate) fn traverse_with_thread_states<F, P1, P2, MBFN, S, R, E>(
         mut self,
         resolve: F,
         pack_entries_end: u64,
         new_thread_state: impl Fn() -> S + Send + Clone,
         inspect_object: MBFN,
+        state_to_rval: impl FnOnce(S) -> R + Send + Clone,
         Options {
             thread_limit,
             object_progress,
             mut size_progress,
             should_interrupt,
             object_hash,
+            max_decompressed_object_size,
         }: Options<'_, P1, P2>,
-    ) -> Result<Outcome<T>, Error>
+    ) -> Result<(Outcome<T>, Vec<R>), Error>
     where
         F: for<'r> Fn(EntryRange, &'r mut Vec<u8>) -> Option<()> + Send + Clone,
         P1: Progress,
         P2: Progress,
         MBFN: Fn(&mut T, &mut <P1 as Progress>::SubProgress, Context<'_, S>) -> Result<(), E> + Send + Clone,
+        R: Send,
         E: std::error::Error + Send + Sync + 'static,
     {
         self.set_pack_entries_end_and_resolve_ref_offsets(pack_entries_end)?;
@@ -133,7 +184,7 @@ where
         let child_items = self.child_items.as_mut_slice();
 
         let start = std::time::Instant::now();
-        in_parallel_with_slice(
+        let thread_rvals = in_parallel_with_slice(
             &mut self.root_items,
             thread_limit,
             {
@@ -159,19 +210,23 @@ where
                         node,
                         state,
                         object_hash.len_in_bytes(),
+                        max_decompressed_object_size,
                     )
                 }
             },
             || (!should_interrupt.load(Ordering::Relaxed)).then(|| std::time::Duration::from_millis(50)),
-            |_| (),
+            move |(_buf, _progress, state, ..)| state_to_rval(state),
         )?;
 
         lock(&object_progress).show_throughput(start);
         size_progress.show_throughput(start);
 
-        Ok(Outcome {
-            roots: self.root_items,
-            children: self.child_items,
-        })
+        Ok((
+            Outcome {
+                roots: self.root_items,
+                children: self.child_items,
+            },
+            thread_rvals,
+        ))
     }
 }
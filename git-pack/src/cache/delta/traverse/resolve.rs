@@ -29,6 +29,7 @@ pub(crate) fn deltas<T, F, P, MBFN, S, E>(
         ItemSliceSend<Item<T>>,
     ),
     hash_len: usize,
+    max_decompressed_object_size: Option<u64>,
 ) -> Result<(), Error>
 where
     T: Send,
@@ -40,13 +41,24 @@ where
     let mut decompressed_bytes_by_pack_offset = BTreeMap::new();
     let bytes_buf = RefCell::new(bytes_buf);
     let decompress_from_resolver = |slice: EntryRange| -> Result<(crate::data::Entry, u64, Vec<u8>), Error> {
+        let empty_entry = || Error::EmptyEntry {
+            pack_offset: slice.start,
+            entry_end: slice.end,
+        };
+        if slice.end <= slice.start {
+            return Err(empty_entry());
+        }
         let mut bytes_buf = bytes_buf.borrow_mut();
         bytes_buf.resize((slice.end - slice.start) as usize, 0);
         resolve(slice.clone(), &mut bytes_buf).ok_or(Error::ResolveFailed {
             pack_offset: slice.start,
         })?;
         let entry = crate::data::Entry::from_bytes(&bytes_buf, slice.start, hash_len);
-        let compressed = &bytes_buf[entry.header_size() as usize..];
+        check_size_limit(slice.start, entry.decompressed_size, max_decompressed_object_size)?;
+        let compressed = bytes_buf
+            .get(entry.header_size()..)
+            .filter(|compressed| !compressed.is_empty())
+            .ok_or_else(empty_entry)?;
         let decompressed_len = entry.decompressed_size as usize;
         Ok((entry, slice.end, decompress_all_at_once(compressed, decompressed_len)?))
     };
@@ -112,10 +124,15 @@ where
             );
             let (result_size, consumed) = crate::data::delta::decode_header_size(&delta_bytes[consumed..]);
             header_ofs += consumed;
+            check_size_limit(child.offset(), result_size, max_decompressed_object_size)?;
 
             let mut fully_resolved_delta_bytes = bytes_buf.borrow_mut();
             fully_resolved_delta_bytes.resize(result_size as usize, 0);
-            crate::data::delta::apply(&base_bytes, &mut fully_resolved_delta_bytes, &delta_bytes[header_ofs..]);
+            crate::data::delta::apply(&base_bytes, &mut fully_resolved_delta_bytes, &delta_bytes[header_ofs..])
+                .map_err(|err| Error::DeltaApply {
+                    pack_offset: child.offset(),
+                    source: err,
+                })?;
 
             // FIXME: this actually invalidates the "pack_offset()" computation, which is not obvious to consumers
             //        at all
@@ -150,6 +167,17 @@ where
     Ok(())
 }
 
+fn check_size_limit(pack_offset: crate::data::Offset, size: u64, limit: Option<u64>) -> Result<(), Error> {
+    match limit {
+        Some(limit) if size > limit => Err(Error::SizeLimitExceeded {
+            pack_offset,
+            size,
+            limit,
+        }),
+        _ => Ok(()),
+    }
+}
+
 fn decompress_all_at_once(b: &[u8], decompressed_len: usize) -> Result<Vec<u8>, Error> {
     let mut out = Vec::new();
     out.resize(decompressed_len, 0);
@@ -83,10 +83,66 @@ mod memory {
             res
         }
     }
+
+    /// An LRU cache with hash map backing and an eviction rule based on the amount of stored objects.
+    pub struct EntryCappedHashmap {
+        inner: clru::CLruCache<Key, Entry>,
+        free_list: Vec<Vec<u8>>,
+        debug: git_features::cache::Debug,
+    }
+
+    impl EntryCappedHashmap {
+        /// Return a new instance which evicts least recently used items if it stores more than `max_entries` objects.
+        pub fn new(max_entries: usize) -> EntryCappedHashmap {
+            EntryCappedHashmap {
+                inner: clru::CLruCache::new(NonZeroUsize::new(max_entries).expect("non zero")),
+                free_list: Vec::new(),
+                debug: git_features::cache::Debug::new(format!("EntryCappedHashmap({})", max_entries)),
+            }
+        }
+    }
+
+    impl DecodeEntry for EntryCappedHashmap {
+        fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
+            self.debug.put();
+            if let Some(previous_entry) = self.inner.put(
+                (pack_id, offset),
+                Entry {
+                    data: self
+                        .free_list
+                        .pop()
+                        .map(|mut v| {
+                            v.clear();
+                            v.extend_from_slice(data);
+                            v
+                        })
+                        .unwrap_or_else(|| Vec::from(data)),
+                    kind,
+                    compressed_size,
+                },
+            ) {
+                self.free_list.push(previous_entry.data)
+            }
+        }
+
+        fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)> {
+            let res = self.inner.get(&(pack_id, offset)).map(|e| {
+                out.resize(e.data.len(), 0);
+                out.copy_from_slice(&e.data);
+                (e.kind, e.compressed_size)
+            });
+            if res.is_some() {
+                self.debug.hit()
+            } else {
+                self.debug.miss()
+            }
+            res
+        }
+    }
 }
 
 #[cfg(feature = "pack-cache-lru-dynamic")]
-pub use memory::MemoryCappedHashmap;
+pub use memory::{EntryCappedHashmap, MemoryCappedHashmap};
 
 #[cfg(feature = "pack-cache-lru-static")]
 mod _static {
@@ -51,5 +51,11 @@ pub mod lru;
 
 pub mod object;
 
+///
+#[cfg(feature = "pack-cache-lru-dynamic")]
+pub mod spec;
+#[cfg(feature = "pack-cache-lru-dynamic")]
+pub use spec::{from_spec, Spec};
+
 ///
 pub(crate) mod delta;
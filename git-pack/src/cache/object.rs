@@ -23,7 +23,7 @@ mod memory {
 
     impl WeightScale<Key, Entry> for CustomScale {
         fn weight(&self, key: &Key, value: &Entry) -> usize {
-            value.data.len() + std::mem::size_of::<Entry>() + key.as_bytes().len()
+            value.data.capacity() + std::mem::size_of::<Entry>() + key.as_bytes().len()
         }
     }
 
@@ -0,0 +1,120 @@
+use std::{fmt, str::FromStr};
+
+use crate::cache::{lru, DecodeEntry, Never};
+
+mod error {
+    /// Returned when parsing a [`Spec`][super::Spec] from a string.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Unknown pack cache specification '{spec}', valid ones are 'never', 'lru-mem=<size>' like 'lru-mem=512MB' and 'lru-entries=<count>' like 'lru-entries=10000'")]
+        Unknown { spec: String },
+        #[error("Could not parse '{value}' in pack cache specification '{spec}' as {expected}")]
+        InvalidValue {
+            spec: String,
+            value: String,
+            expected: &'static str,
+        },
+        #[error("The pack cache of specification '{spec}' must be able to hold at least one byte or entry")]
+        Zero { spec: String },
+    }
+}
+pub use error::Error;
+
+/// A way to select a [`DecodeEntry`] implementation and its size by name, typically on the command-line.
+///
+/// Its string representation is one of
+///
+/// * `never` - don't cache anything, see [`Never`].
+/// * `lru-mem=<size>` - cache objects until they occupy `size` bytes, see [`lru::MemoryCappedHashmap`]. The size may have a
+///   suffix like `k`, `KB`, `KiB`, `m`, `MB`, `MiB`, `g`, `GB` or `GiB`, all of which are interpreted as powers of 1024.
+/// * `lru-entries=<count>` - cache up to `count` objects, see [`lru::EntryCappedHashmap`].
+///
+/// Note that caches are created once per thread, so the total memory used is the budget times the amount of threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spec {
+    /// Don't cache anything.
+    Never,
+    /// Cache up to `bytes` worth of decoded objects, evicting the least recently used ones.
+    LruMemory {
+        /// The amount of bytes of decoded objects to keep at most.
+        bytes: usize,
+    },
+    /// Cache up to `count` decoded objects, evicting the least recently used ones.
+    LruEntries {
+        /// The amount of objects to keep at most.
+        count: usize,
+    },
+}
+
+impl Spec {
+    /// Create a new cache according to this specification.
+    pub fn to_cache(&self) -> Box<dyn DecodeEntry + Send> {
+        match *self {
+            Spec::Never => Box::new(Never),
+            Spec::LruMemory { bytes } => Box::new(lru::MemoryCappedHashmap::new(bytes)),
+            Spec::LruEntries { count } => Box::new(lru::EntryCappedHashmap::new(count)),
+        }
+    }
+}
+
+impl FromStr for Spec {
+    type Err = Error;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match spec.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (spec, None),
+        };
+        let res = match (name, value) {
+            ("never", None) => Spec::Never,
+            ("lru-mem", Some(value)) => Spec::LruMemory {
+                bytes: parse_size(value).ok_or_else(|| Error::InvalidValue {
+                    spec: spec.into(),
+                    value: value.into(),
+                    expected: "size in bytes, like '512MB'",
+                })?,
+            },
+            ("lru-entries", Some(value)) => Spec::LruEntries {
+                count: value.parse().map_err(|_| Error::InvalidValue {
+                    spec: spec.into(),
+                    value: value.into(),
+                    expected: "amount of entries, like '10000'",
+                })?,
+            },
+            _ => return Err(Error::Unknown { spec: spec.into() }),
+        };
+        if matches!(res, Spec::LruMemory { bytes: 0 } | Spec::LruEntries { count: 0 }) {
+            return Err(Error::Zero { spec: spec.into() });
+        }
+        Ok(res)
+    }
+}
+
+impl fmt::Display for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Spec::Never => f.write_str("never"),
+            Spec::LruMemory { bytes } => write!(f, "lru-mem={}", bytes),
+            Spec::LruEntries { count } => write!(f, "lru-entries={}", count),
+        }
+    }
+}
+
+fn parse_size(value: &str) -> Option<usize> {
+    let split_at = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split_at);
+    let shift = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 10,
+        "m" | "mb" | "mib" => 20,
+        "g" | "gb" | "gib" => 30,
+        _ => return None,
+    };
+    number.parse::<usize>().ok()?.checked_mul(1 << shift)
+}
+
+/// Parse `spec` as [`Spec`] and create the cache it describes.
+pub fn from_spec(spec: &str) -> Result<Box<dyn DecodeEntry + Send>, Error> {
+    spec.parse::<Spec>().map(|spec| spec.to_cache())
+}
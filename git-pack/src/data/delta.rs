@@ -1,13 +1,14 @@
 /// Given the decompressed pack delta `d`, decode a size in bytes (either the base object size or the result object size)
 /// Equivalent to [this canonical git function](https://github.com/git/git/blob/311531c9de557d25ac087c1637818bd2aad6eb3a/delta.h#L89)
 pub fn decode_header_size(d: &[u8]) -> (u64, usize) {
-    let mut i = 0;
+    let mut i = 0u32;
     let mut size = 0u64;
     let mut consumed = 0;
     for cmd in d.iter() {
         consumed += 1;
-        size |= (*cmd as u64 & 0x7f) << i;
-        i += 7;
+        // Overlong encodings can only be crafted, so make sure these turn into an excessive size instead of overflowing.
+        size |= (*cmd as u64 & 0x7f).checked_shl(i).unwrap_or(u64::MAX);
+        i = i.saturating_add(7);
         if *cmd & 0x80 == 0 {
             break;
         }
@@ -15,56 +16,230 @@ pub fn decode_header_size(d: &[u8]) -> (u64, usize) {
     (size, consumed)
 }
 
-pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) {
+///
+pub mod apply {
+    /// Returned by [`apply()`][super::apply()] if the delta instructions are malformed or don't fit the base or result buffers.
+    #[derive(thiserror::Error, Debug, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Encountered unsupported delta command code 0 at instruction offset {instruction_offset}")]
+        UnsupportedCommand { instruction_offset: usize },
+        #[error("Delta instructions ended unexpectedly at offset {instruction_offset}")]
+        TruncatedInstructions { instruction_offset: usize },
+        #[error("Delta copies {size} bytes from offset {offset} of its base object, which has only {base_size} bytes")]
+        CopyOutOfBounds {
+            offset: usize,
+            size: usize,
+            base_size: usize,
+        },
+        #[error("Delta produced at least {produced} bytes, but declared a result size of {declared} bytes")]
+        ResultTooLarge { produced: usize, declared: usize },
+        #[error("Delta produced {produced} bytes, but declared a result size of {declared} bytes")]
+        ResultTooSmall { produced: usize, declared: usize },
+    }
+}
+
+/// Apply the delta instructions in `data` to `base`, writing the result into `target` which must be exactly as large as the
+/// result size declared by the delta.
+///
+/// All instructions are validated while they are applied, so that malformed or malicious deltas can never read outside of `base`
+/// or write more than `target` can hold.
+pub fn apply(base: &[u8], target: &mut [u8], data: &[u8]) -> Result<(), apply::Error> {
+    let declared = target.len();
+    let produced = apply_partially(base, target, data)?;
+    if produced != declared {
+        return Err(apply::Error::ResultTooSmall { produced, declared });
+    }
+    Ok(())
+}
+
+/// Like [`apply()`], but return the amount of bytes written to `target` instead of failing if the delta produced less than
+/// the declared result size, that is less than `target` can hold.
+///
+/// This allows callers to report or tolerate such deltas on their own. Note that a delta producing more than `target` can hold
+/// is still an error.
+pub fn apply_partially(base: &[u8], target: &mut [u8], data: &[u8]) -> Result<usize, apply::Error> {
+    use apply::Error;
+    let declared = target.len();
+    let mut produced = 0;
     let mut i = 0;
-    while let Some(cmd) = data.get(i) {
+    let next_byte = |i: &mut usize| -> Result<u32, Error> {
+        let byte = *data
+            .get(*i)
+            .ok_or(Error::TruncatedInstructions { instruction_offset: *i })?;
+        *i += 1;
+        Ok(byte as u32)
+    };
+    while let Some(cmd) = data.get(i).copied() {
         i += 1;
-        match cmd {
+        let (source, size) = match cmd {
             cmd if cmd & 0b1000_0000 != 0 => {
                 let (mut ofs, mut size): (u32, u32) = (0, 0);
                 if cmd & 0b0000_0001 != 0 {
-                    ofs = data[i] as u32;
-                    i += 1;
+                    ofs = next_byte(&mut i)?;
                 }
                 if cmd & 0b0000_0010 != 0 {
-                    ofs |= (data[i] as u32) << 8;
-                    i += 1;
+                    ofs |= next_byte(&mut i)? << 8;
                 }
                 if cmd & 0b0000_0100 != 0 {
-                    ofs |= (data[i] as u32) << 16;
-                    i += 1;
+                    ofs |= next_byte(&mut i)? << 16;
                 }
                 if cmd & 0b0000_1000 != 0 {
-                    ofs |= (data[i] as u32) << 24;
-                    i += 1;
+                    ofs |= next_byte(&mut i)? << 24;
                 }
                 if cmd & 0b0001_0000 != 0 {
-                    size = data[i] as u32;
-                    i += 1;
+                    size = next_byte(&mut i)?;
                 }
                 if cmd & 0b0010_0000 != 0 {
-                    size |= (data[i] as u32) << 8;
-                    i += 1;
+                    size |= next_byte(&mut i)? << 8;
                 }
                 if cmd & 0b0100_0000 != 0 {
-                    size |= (data[i] as u32) << 16;
-                    i += 1;
+                    size |= next_byte(&mut i)? << 16;
                 }
                 if size == 0 {
                     size = 0x10000; // 65536
                 }
-                let ofs = ofs as usize;
-                std::io::Write::write(&mut target, &base[ofs..ofs + size as usize])
-                    .expect("delta copy from base: byte slices must match");
+                let (ofs, size) = (ofs as usize, size as usize);
+                let source =
+                    ofs.checked_add(size)
+                        .and_then(|end| base.get(ofs..end))
+                        .ok_or(Error::CopyOutOfBounds {
+                            offset: ofs,
+                            size,
+                            base_size: base.len(),
+                        })?;
+                (source, size)
+            }
+            0 => {
+                return Err(Error::UnsupportedCommand {
+                    instruction_offset: i - 1,
+                })
             }
-            0 => panic!("encountered unsupported command code: 0"),
             size => {
-                std::io::Write::write(&mut target, &data[i..i + *size as usize])
-                    .expect("delta copy data: slice sizes to match up");
-                i += *size as usize;
+                let size = size as usize;
+                let source = data
+                    .get(i..i + size)
+                    .ok_or(Error::TruncatedInstructions { instruction_offset: i })?;
+                i += size;
+                (source, size)
+            }
+        };
+        let end = produced + size;
+        if end > declared {
+            return Err(Error::ResultTooLarge {
+                produced: end,
+                declared,
+            });
+        }
+        target[produced..end].copy_from_slice(source);
+        produced = end;
+    }
+    Ok(produced)
+}
+
+/// The amount of bytes of the base object which are indexed together to find copies for the target object.
+const BLOCK_SIZE: usize = 16;
+/// The most offsets remembered for equal blocks of the base object, to bound the time spent on repetitive content.
+const MAX_OFFSETS_PER_BLOCK: usize = 8;
+/// The most bytes a single copy instruction can produce with its three size bytes.
+const MAX_COPY_SIZE: usize = 0xff_ffff;
+/// The most bytes a single insert instruction can carry.
+const MAX_INSERT_SIZE: usize = 0x7f;
+
+/// Compute a delta which produces `target` when applied to `base`, starting with the sizes of both objects as header
+/// the way [`decode_header_size()`] expects them.
+///
+/// Returns `None` if the delta would be larger than `max_size` bytes, or if `base` is too large to be copied from.
+/// Copies are only used for runs of at least 16 equal bytes, which is enough to find the similarities between
+/// versions of the same file, without trying to find the smallest possible delta.
+pub fn encode(base: &[u8], target: &[u8], max_size: usize) -> Option<Vec<u8>> {
+    if base.len() > u32::MAX as usize {
+        return None;
+    }
+    let mut out = Vec::new();
+    encode_header_size(base.len(), &mut out);
+    encode_header_size(target.len(), &mut out);
+
+    let mut offsets_by_block = std::collections::HashMap::<&[u8], Vec<usize>>::new();
+    if base.len() >= BLOCK_SIZE {
+        for offset in (0..=base.len() - BLOCK_SIZE).step_by(BLOCK_SIZE) {
+            let offsets = offsets_by_block.entry(&base[offset..][..BLOCK_SIZE]).or_default();
+            if offsets.len() < MAX_OFFSETS_PER_BLOCK {
+                offsets.push(offset);
+            }
+        }
+    }
+
+    let mut insert_start = 0;
+    let mut pos = 0;
+    while pos + BLOCK_SIZE <= target.len() {
+        let longest_copy = offsets_by_block.get(&target[pos..][..BLOCK_SIZE]).and_then(|offsets| {
+            offsets
+                .iter()
+                .map(|&base_offset| {
+                    let forward = base[base_offset..]
+                        .iter()
+                        .zip(&target[pos..])
+                        .take_while(|(lhs, rhs)| lhs == rhs)
+                        .count();
+                    let backward = base[..base_offset]
+                        .iter()
+                        .rev()
+                        .zip(target[insert_start..pos].iter().rev())
+                        .take_while(|(lhs, rhs)| lhs == rhs)
+                        .count();
+                    (base_offset - backward, pos - backward, backward + forward)
+                })
+                .max_by_key(|(_, _, len)| *len)
+        });
+        match longest_copy {
+            Some((base_offset, target_offset, len)) if len >= BLOCK_SIZE => {
+                encode_insert(&target[insert_start..target_offset], &mut out);
+                encode_copy(base_offset, len, &mut out);
+                pos = target_offset + len;
+                insert_start = pos;
+                if out.len() > max_size {
+                    return None;
+                }
+            }
+            _ => pos += 1,
+        }
+    }
+    encode_insert(&target[insert_start..], &mut out);
+    (out.len() <= max_size).then(|| out)
+}
+
+fn encode_header_size(mut size: usize, out: &mut Vec<u8>) {
+    while size >= 0x80 {
+        out.push((size as u8 & 0x7f) | 0x80);
+        size >>= 7;
+    }
+    out.push(size as u8);
+}
+
+fn encode_insert(data: &[u8], out: &mut Vec<u8>) {
+    for chunk in data.chunks(MAX_INSERT_SIZE) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn encode_copy(mut offset: usize, mut size: usize, out: &mut Vec<u8>) {
+    while size > 0 {
+        let chunk_size = size.min(MAX_COPY_SIZE);
+        let mut instruction = [0b1000_0000, 0, 0, 0, 0, 0, 0, 0];
+        let mut len = 1;
+        let offset_bytes = (offset as u32).to_le_bytes();
+        let size_bytes = (chunk_size as u32).to_le_bytes();
+        for (bit, byte) in offset_bytes.iter().chain(&size_bytes[..3]).enumerate() {
+            if *byte != 0 {
+                instruction[0] |= 1 << bit;
+                instruction[len] = *byte;
+                len += 1;
             }
         }
+        out.extend_from_slice(&instruction[..len]);
+        offset += chunk_size;
+        size -= chunk_size;
     }
-    assert_eq!(i, data.len());
-    assert_eq!(target.len(), 0);
 }
@@ -6,7 +6,7 @@ use smallvec::SmallVec;
 use super::ResolvedBase;
 use crate::{
     cache, data,
-    data::{delta, File},
+    data::{delta, File, Storage},
 };
 
 /// Returned by [`File::decompress_entry()`] and [`File::decode_entry()`]
@@ -17,6 +17,68 @@ pub enum Error {
     ZlibInflate(#[from] zlib::inflate::Error),
     #[error("A delta chain could not be applied as the ref base with id {0} could not be found")]
     DeltaBaseUnresolved(git_hash::ObjectId),
+    #[error("The delta at pack offset {pack_offset} could not be applied")]
+    DeltaApply {
+        pack_offset: data::Offset,
+        source: delta::apply::Error,
+    },
+    #[error(
+        "The entry at pack offset {pack_offset} would decompress to {size} bytes, exceeding the limit of {limit} bytes"
+    )]
+    SizeLimitExceeded {
+        pack_offset: data::Offset,
+        size: u64,
+        limit: u64,
+    },
+    #[error("The delta at pack offset {pack_offset} declared a result size of {declared} bytes, but produced {actual} bytes")]
+    DeltaResultSizeMismatch {
+        pack_offset: data::Offset,
+        declared: u64,
+        actual: u64,
+    },
+    #[error("Could not read pack data at offset {offset}")]
+    Io {
+        offset: data::Offset,
+        source: std::io::Error,
+    },
+}
+
+impl Error {
+    /// Return the kind of this error, which is never [`NotFound`][crate::find::ErrorKind::NotFound] as the entry
+    /// exists by definition.
+    pub fn kind(&self) -> crate::find::ErrorKind {
+        use crate::find::ErrorKind;
+        match self {
+            Error::ZlibInflate(_)
+            | Error::DeltaBaseUnresolved(_)
+            | Error::DeltaApply { .. }
+            | Error::DeltaResultSizeMismatch { .. } => ErrorKind::Corrupt,
+            Error::SizeLimitExceeded { .. } => ErrorKind::LimitExceeded,
+            Error::Io { .. } => ErrorKind::Io,
+        }
+    }
+}
+
+impl crate::find::Classify for Error {
+    fn kind(&self) -> crate::find::ErrorKind {
+        Error::kind(self)
+    }
+}
+
+/// The maximum size of a decompressed object or delta that is used by default when verifying packs or when resolving received packs,
+/// which is generous enough to not get in the way while protecting against crafted packs which declare absurd object sizes.
+pub const DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE: u64 = 8 * 1024 * 1024 * 1024;
+
+/// Return an error if `size` exceeds `limit`, if set, attributing it to the entry at `pack_offset`.
+pub(crate) fn check_size_limit(pack_offset: data::Offset, size: u64, limit: Option<u64>) -> Result<(), Error> {
+    match limit {
+        Some(limit) if size > limit => Err(Error::SizeLimitExceeded {
+            pack_offset,
+            size,
+            limit,
+        }),
+        _ => Ok(()),
+    }
 }
 
 #[derive(Debug)]
@@ -27,6 +89,7 @@ struct Delta {
 
     decompressed_size: usize,
     data_offset: data::Offset,
+    pack_offset: data::Offset,
 }
 
 /// Additional information and statistics about a successfully decoded object produced by [`File::decode_entry()`].
@@ -44,7 +107,9 @@ pub struct Outcome {
     pub num_deltas: u32,
     /// The total decompressed size of all pack entries in the delta chain
     pub decompressed_size: u64,
-    /// The total compressed size of all pack entries in the delta chain
+    /// The amount of compressed bytes consumed while inflating the requested entry, excluding its header.
+    ///
+    /// Together with the entry header, this is the amount of bytes the entry occupies in the pack.
     pub compressed_size: usize,
     /// The total size of all objects decoded as part of the delta chain
     pub object_size: u64,
@@ -102,13 +167,38 @@ impl File {
     /// Obtain the [`Entry`][crate::data::Entry] at the given `offset` into the pack.
     ///
     /// The `offset` is typically obtained from the pack index file.
+    ///
+    /// # Panics
+    ///
+    /// If the entry can't be read, which can only happen when using [positioned reads][data::Access::PositionedRead].
+    /// Use [`try_entry()`][File::try_entry()] to handle this case.
     pub fn entry(&self, offset: data::Offset) -> crate::data::Entry {
+        self.try_entry(offset).expect("entry to be readable")
+    }
+
+    /// Like [`entry()`][File::entry()], but fail with [`Error::Io`] if the entry can't be read, for instance because
+    /// the pack was truncated after it was opened.
+    pub fn try_entry(&self, offset: data::Offset) -> Result<crate::data::Entry, Error> {
         self.assure_v2();
         let pack_offset: usize = offset.try_into().expect("offset representable by machine");
-        assert!(pack_offset <= self.data.len(), "offset out of bounds");
-
-        let object_data = &self.data[pack_offset..];
-        crate::data::Entry::from_bytes(object_data, offset, self.hash_len)
+        assert!(pack_offset <= self.data_len(), "offset out of bounds");
+
+        match &self.storage {
+            Storage::Data(data) => Ok(crate::data::Entry::from_bytes(
+                &data[pack_offset..],
+                offset,
+                self.hash_len,
+            )),
+            Storage::Read(reader) => {
+                // The size and the base of deltas are encoded in at most 20 bytes, followed by a hash for ref-deltas.
+                let mut header = [0u8; 20 + git_hash::Kind::longest().len_in_bytes()];
+                let header = &mut header[..(self.data_len() - pack_offset).min(20 + self.hash_len)];
+                reader
+                    .read_exact_at(pack_offset, header)
+                    .map_err(|source| Error::Io { offset, source })?;
+                Ok(crate::data::Entry::from_bytes(header, offset, self.hash_len))
+            }
+        }
     }
 
     /// Decompress the object expected at the given data offset, sans pack header. This information is only
@@ -117,13 +207,41 @@ impl File {
     /// `out` is expected to be large enough to hold `entry.size` bytes.
     /// Returns the amount of packed bytes there read from the pack data file.
     fn decompress_entry_from_data_offset(&self, data_offset: data::Offset, out: &mut [u8]) -> Result<usize, Error> {
-        let offset: usize = data_offset.try_into().expect("offset representable by machine");
-        assert!(offset < self.data.len(), "entry offset out of bounds");
+        self.inflate_at(data_offset, out)
+            .map(|(consumed_in, _consumed_out)| consumed_in)
+    }
 
-        zlib::Inflate::default()
-            .once(&self.data[offset..], out)
-            .map_err(Into::into)
-            .map(|(_status, consumed_in, _consumed_out)| consumed_in)
+    /// Decompress the zlib stream at `data_offset` into `out` until it ends or `out` is full, and return the amount
+    /// of bytes consumed and produced.
+    pub(crate) fn inflate_at(&self, data_offset: data::Offset, out: &mut [u8]) -> Result<(usize, usize), Error> {
+        let offset: usize = data_offset.try_into().expect("offset representable by machine");
+        assert!(offset < self.data_len(), "entry offset out of bounds");
+
+        let reader = match &self.storage {
+            Storage::Data(data) => {
+                return zlib::Inflate::default()
+                    .once(&data[offset..], out)
+                    .map_err(Into::into)
+                    .map(|(_status, consumed_in, consumed_out)| (consumed_in, consumed_out))
+            }
+            Storage::Read(reader) => reader,
+        };
+        let mut inflate = zlib::Inflate::default();
+        let (mut consumed_in, mut consumed_out) = (0, 0);
+        while offset + consumed_in < self.data_len() {
+            let (block, start) = reader.block_at(offset + consumed_in).map_err(|source| Error::Io {
+                offset: (offset + consumed_in) as data::Offset,
+                source,
+            })?;
+            let input = &block[start..];
+            let (status, block_consumed_in, block_consumed_out) = inflate.once(input, &mut out[consumed_out..])?;
+            consumed_in += block_consumed_in;
+            consumed_out += block_consumed_out;
+            if status == zlib::Status::StreamEnd || block_consumed_in < input.len() {
+                break;
+            }
+        }
+        Ok((consumed_in, consumed_out))
     }
 
     /// Decode an entry, resolving delta's as needed, while growing the `out` vector if there is not enough
@@ -136,16 +254,45 @@ impl File {
     ///
     /// `delta_cache` is a mechanism to avoid looking up base objects multiple times when decompressing multiple objects in a row.
     /// Use a [Noop-Cache][cache::Never] to disable caching all together at the cost of repeating work.
+    ///
+    /// Note that no limit is imposed on the size of decompressed objects, see [`decode_entry_with_limit()`][File::decode_entry_with_limit()]
+    /// when handling untrusted packs.
     pub fn decode_entry(
         &self,
         entry: crate::data::Entry,
         out: &mut Vec<u8>,
         resolve: impl Fn(&git_hash::oid, &mut Vec<u8>) -> Option<ResolvedBase>,
         delta_cache: &mut impl cache::DecodeEntry,
+    ) -> Result<Outcome, Error> {
+        self.decode_entry_with_limit(entry, out, resolve, delta_cache, None, true)
+    }
+
+    /// Like [`decode_entry()`][File::decode_entry()], but fail with [`Error::SizeLimitExceeded`] if any object or delta
+    /// involved in decoding `entry` would decompress to more than `max_decompressed_object_size` bytes, if set.
+    ///
+    /// The limit is checked before any buffer is grown, using the sizes declared by pack entries and delta headers, while
+    /// delta instructions are validated as they are applied so they can't produce more than their declared result size.
+    ///
+    /// If `verify_delta_result_size` is `true`, fail with [`Error::DeltaResultSizeMismatch`] if a delta produces less than
+    /// the result size declared in its header, which indicates corruption. Otherwise the bytes the delta didn't produce
+    /// remain unspecified.
+    pub fn decode_entry_with_limit(
+        &self,
+        entry: crate::data::Entry,
+        out: &mut Vec<u8>,
+        resolve: impl Fn(&git_hash::oid, &mut Vec<u8>) -> Option<ResolvedBase>,
+        delta_cache: &mut impl cache::DecodeEntry,
+        max_decompressed_object_size: Option<u64>,
+        verify_delta_result_size: bool,
     ) -> Result<Outcome, Error> {
         use crate::data::entry::Header::*;
         match entry.header {
             Tree | Blob | Commit | Tag => {
+                check_size_limit(
+                    entry.pack_offset(),
+                    entry.decompressed_size,
+                    max_decompressed_object_size,
+                )?;
                 out.resize(
                     entry
                         .decompressed_size
@@ -161,7 +308,14 @@ impl File {
                     )
                 })
             }
-            OfsDelta { .. } | RefDelta { .. } => self.resolve_deltas(entry, resolve, out, delta_cache),
+            OfsDelta { .. } | RefDelta { .. } => self.resolve_deltas(
+                entry,
+                resolve,
+                out,
+                delta_cache,
+                max_decompressed_object_size,
+                verify_delta_result_size,
+            ),
         }
     }
 
@@ -174,6 +328,8 @@ impl File {
         resolve: impl Fn(&git_hash::oid, &mut Vec<u8>) -> Option<ResolvedBase>,
         out: &mut Vec<u8>,
         cache: &mut impl cache::DecodeEntry,
+        limit: Option<u64>,
+        verify_result_size: bool,
     ) -> Result<Outcome, Error> {
         // all deltas, from the one that produces the desired object (first) to the oldest at the end of the chain
         let mut chain = SmallVec::<[Delta; 10]>::default();
@@ -183,9 +339,9 @@ impl File {
         let mut object_kind: Option<git_object::Kind> = None;
         let mut consumed_input: Option<usize> = None;
 
-        // Find the first full base, either an undeltified object in the pack or a reference to another object.
+        // Find the first full base, either a cached object, an undeltified object in the pack or a reference to another object.
         let mut total_delta_data_size: u64 = 0;
-        while cursor.header.is_delta() {
+        loop {
             if let Some((kind, packed_size)) = cache.get(self.id, cursor.data_offset, out) {
                 base_buffer_size = Some(out.len());
                 object_kind = Some(kind);
@@ -196,6 +352,10 @@ impl File {
                 }
                 break;
             }
+            if !cursor.header.is_delta() {
+                break;
+            }
+            check_size_limit(cursor.pack_offset(), cursor.decompressed_size, limit)?;
             total_delta_data_size += cursor.decompressed_size;
             let decompressed_size = cursor
                 .decompressed_size
@@ -210,10 +370,11 @@ impl File {
                 result_size: 0,
                 decompressed_size,
                 data_offset: cursor.data_offset,
+                pack_offset: cursor.pack_offset(),
             });
             use crate::data::entry::Header;
             cursor = match cursor.header {
-                Header::OfsDelta { base_distance } => self.entry(cursor.base_pack_offset(base_distance)),
+                Header::OfsDelta { base_distance } => self.try_entry(cursor.base_pack_offset(base_distance))?,
                 Header::RefDelta { base_id } => match resolve(base_id.as_ref(), out) {
                     Some(ResolvedBase::InPack(entry)) => entry,
                     Some(ResolvedBase::OutOfPack { end, kind }) => {
@@ -266,11 +427,13 @@ impl File {
 
                 let (base_size, offset) = delta::decode_header_size(instructions);
                 let mut bytes_consumed_by_header = offset;
+                check_size_limit(delta.pack_offset, base_size, limit)?;
                 biggest_result_size = biggest_result_size.max(base_size);
                 delta.base_size = base_size.try_into().expect("base size fits into usize");
 
                 let (result_size, offset) = delta::decode_header_size(&instructions[offset..]);
                 bytes_consumed_by_header += offset;
+                check_size_limit(delta.pack_offset, result_size, limit)?;
                 biggest_result_size = biggest_result_size.max(result_size);
                 delta.result_size = result_size.try_into().expect("result size fits into usize");
 
@@ -316,6 +479,7 @@ impl File {
             if base_buffer_size.is_none() {
                 let base_entry = cursor;
                 debug_assert!(!base_entry.header.is_delta());
+                check_size_limit(base_entry.pack_offset(), base_entry.decompressed_size, limit)?;
                 object_kind = base_entry.header.as_kind();
                 self.decompress_entry_from_data_offset(base_entry.data_offset, out)?;
             }
@@ -338,6 +502,7 @@ impl File {
                 data,
                 base_size,
                 result_size,
+                pack_offset,
                 ..
             },
         ) in chain.into_iter().rev().enumerate()
@@ -346,7 +511,18 @@ impl File {
             if delta_idx + 1 == chain_len {
                 last_result_size = Some(result_size);
             }
-            delta::apply(&source_buf[..base_size], &mut target_buf[..result_size], data);
+            let produced = delta::apply_partially(&source_buf[..base_size], &mut target_buf[..result_size], data)
+                .map_err(|err| Error::DeltaApply {
+                    pack_offset,
+                    source: err,
+                })?;
+            if verify_result_size && produced != result_size {
+                return Err(Error::DeltaResultSizeMismatch {
+                    pack_offset,
+                    declared: result_size as u64,
+                    actual: produced as u64,
+                });
+            }
             // use the target as source for the next delta
             std::mem::swap(&mut source_buf, &mut target_buf);
         }
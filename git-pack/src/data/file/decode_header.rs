@@ -0,0 +1,90 @@
+use crate::{
+    data,
+    data::{delta, file::decode_entry::Error, File},
+};
+
+/// A return value of a resolve function passed to [`File::decode_header()`], which given an [`ObjectId`][git_hash::ObjectId]
+/// determines where an object can be found.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone)]
+pub enum ResolvedBase {
+    /// Indicate an object is within this pack, at the given entry, and thus can be looked up locally.
+    InPack(data::Entry),
+    /// Indicates the object of `kind` was found outside of the pack.
+    #[allow(missing_docs)]
+    OutOfPack { kind: git_object::Kind },
+}
+
+/// Information about an object obtained by [`File::decode_header()`] without decoding it.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The kind of the object, which is the kind of the base object at the end of the delta chain.
+    pub kind: git_object::Kind,
+    /// The size of the decompressed object in bytes.
+    pub object_size: u64,
+    /// The amount of deltas in the chain of objects that lead to the base object.
+    pub num_deltas: u32,
+}
+
+/// Obtain object information quickly.
+impl File {
+    /// Learn the kind and size of the object at `entry` without decoding it, which only requires to follow its delta chain
+    /// by entry headers and to decompress the few bytes of the delta header that declares the size of the resulting object.
+    ///
+    /// `resolve` is a function to lookup objects with the given [`ObjectId`][git_hash::ObjectId], in case the full object id
+    /// is used to refer to a base object, instead of an in-pack offset.
+    pub fn decode_header(
+        &self,
+        mut entry: data::Entry,
+        resolve: impl Fn(&git_hash::oid) -> Option<ResolvedBase>,
+    ) -> Result<Outcome, Error> {
+        use crate::data::entry::Header::*;
+        let mut num_deltas = 0;
+        let mut object_size = None;
+        loop {
+            match entry.header {
+                Tree | Blob | Commit | Tag => {
+                    return Ok(Outcome {
+                        kind: entry.header.as_kind().expect("a non-delta entry"),
+                        object_size: object_size.unwrap_or(entry.decompressed_size),
+                        num_deltas,
+                    })
+                }
+                OfsDelta { base_distance } => {
+                    if object_size.is_none() {
+                        object_size = Some(self.decode_delta_result_size(&entry)?);
+                    }
+                    num_deltas += 1;
+                    entry = self.try_entry(entry.base_pack_offset(base_distance))?;
+                }
+                RefDelta { base_id } => {
+                    if object_size.is_none() {
+                        object_size = Some(self.decode_delta_result_size(&entry)?);
+                    }
+                    num_deltas += 1;
+                    entry = match resolve(base_id.as_ref()) {
+                        Some(ResolvedBase::InPack(base_entry)) => base_entry,
+                        Some(ResolvedBase::OutOfPack { kind }) => {
+                            return Ok(Outcome {
+                                kind,
+                                object_size: object_size.expect("set for the first delta"),
+                                num_deltas,
+                            })
+                        }
+                        None => return Err(Error::DeltaBaseUnresolved(base_id)),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decompress only the header of the delta at `entry` to learn the size of the object it produces.
+    fn decode_delta_result_size(&self, entry: &data::Entry) -> Result<u64, Error> {
+        // Two sizes of at most 10 bytes each
+        let mut buf = [0u8; 32];
+        let (_consumed_in, consumed_out) = self.inflate_at(entry.data_offset, &mut buf)?;
+        let header = &buf[..consumed_out];
+        let (_base_size, consumed) = delta::decode_header_size(header);
+        Ok(delta::decode_header_size(&header[consumed..]).0)
+    }
+}
@@ -2,37 +2,121 @@ use std::{convert::TryInto, path::Path};
 
 use crate::data;
 
+/// Returned by [`data::File::at()`] and [`data::File::from_bytes()`].
+///
+/// Each variant carries the path of the pack data file, which is synthetic for files [held in memory][data::File::from_bytes()].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open pack file at '{path}'")]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("Pack file at '{path}' with {size} bytes is too small for even an empty pack")]
+    TooSmall { size: usize, path: std::path::PathBuf },
+    #[error("Pack file at '{path}' doesn't start with the 'PACK' signature, but with {actual:?}")]
+    InvalidSignature {
+        actual: git_object::bstr::BString,
+        path: std::path::PathBuf,
+    },
+    #[error("Pack file at '{path}' has unsupported version {version}")]
+    UnsupportedVersion { version: u32, path: std::path::PathBuf },
+}
+
 /// Instantiation
 impl data::File {
     /// Try opening a data file at the given `path`.
     ///
     /// The `object_hash` is a way to read (and write) the same file format with different hashes, as the hash kind
     /// isn't stored within the file format itself.
-    pub fn at(path: impl AsRef<Path>, object_hash: git_hash::Kind) -> Result<data::File, data::header::decode::Error> {
-        Self::at_inner(path.as_ref(), object_hash)
+    pub fn at(path: impl AsRef<Path>, object_hash: git_hash::Kind) -> Result<data::File, Error> {
+        Self::at_inner(path.as_ref(), object_hash, data::Access::MemoryMap)
     }
 
-    fn at_inner(path: &Path, object_hash: git_hash::Kind) -> Result<data::File, data::header::decode::Error> {
-        use crate::data::header::N32_SIZE;
-        let hash_len = object_hash.len_in_bytes();
+    /// Like [`at()`][data::File::at()], but access the data file on disk as configured by `access`.
+    pub fn at_with_access(
+        path: impl AsRef<Path>,
+        object_hash: git_hash::Kind,
+        access: data::Access,
+    ) -> Result<data::File, Error> {
+        Self::at_inner(path.as_ref(), object_hash, access)
+    }
 
-        let data = crate::mmap::read_only(path).map_err(|e| data::header::decode::Error::Io {
-            source: e,
+    /// Instantiate a data file from `data` held in memory, for example after receiving it over the network.
+    ///
+    /// As there is no path on disk, [`path()`][data::File::path()] returns a synthetic path based on the pack checksum.
+    /// See [`at()`][data::File::at()] for more information on `object_hash`.
+    pub fn from_bytes(data: Vec<u8>, object_hash: git_hash::Kind) -> Result<data::File, Error> {
+        Self::from_data(crate::mmap::Data::InMemory(data.into()), None, object_hash)
+    }
+
+    fn at_inner(path: &Path, object_hash: git_hash::Kind, access: data::Access) -> Result<data::File, Error> {
+        let into_error = |source| Error::Io {
+            source,
             path: path.to_owned(),
-        })?;
-        let pack_len = data.len();
+        };
+        let storage = match access {
+            data::Access::MemoryMap => {
+                let data = crate::mmap::read_only(path).map_err(into_error)?;
+                data::Storage::Data(crate::mmap::Data::Mapped(data.into()))
+            }
+            data::Access::PositionedRead { block_size, capacity } => data::Storage::Read(
+                data::file::positioned::Reader::open(path, block_size, capacity)
+                    .map_err(into_error)?
+                    .into(),
+            ),
+        };
+        Self::from_storage(storage, Some(path), object_hash)
+    }
+
+    pub(crate) fn from_data(
+        data: crate::mmap::Data,
+        path: Option<&Path>,
+        object_hash: git_hash::Kind,
+    ) -> Result<data::File, Error> {
+        Self::from_storage(data::Storage::Data(data), path, object_hash)
+    }
+
+    fn from_storage(
+        storage: data::Storage,
+        path: Option<&Path>,
+        object_hash: git_hash::Kind,
+    ) -> Result<data::File, Error> {
+        use crate::data::header::N32_SIZE;
+        let hash_len = object_hash.len_in_bytes();
+        let pack_len = storage.len();
+        let path = match (path, &storage) {
+            (Some(path), _) => path.to_owned(),
+            (None, data::Storage::Data(data)) => {
+                crate::mmap::in_memory_path(&data[pack_len.saturating_sub(hash_len)..], "pack")
+            }
+            (None, data::Storage::Read(_)) => unreachable!("positioned reads are only performed on files on disk"),
+        };
         if pack_len < N32_SIZE * 3 + hash_len {
-            return Err(data::header::decode::Error::Corrupt(format!(
-                "Pack data of size {} is too small for even an empty pack with shortest hash",
-                pack_len
-            )));
+            return Err(Error::TooSmall { size: pack_len, path });
         }
-        let (kind, num_objects) =
-            data::header::decode(&data[..12].try_into().expect("enough data after previous check"))?;
+        let header = storage.get(0..12).ok_or_else(|| Error::Io {
+            source: std::io::ErrorKind::UnexpectedEof.into(),
+            path: path.clone(),
+        })?;
+        let (kind, num_objects) = data::header::decode(
+            header.as_ref().try_into().expect("enough data after previous check"),
+        )
+        .map_err(|err| match err {
+            data::header::decode::Error::UnsupportedVersion(version) => Error::UnsupportedVersion {
+                version,
+                path: path.clone(),
+            },
+            data::header::decode::Error::Corrupt(_) => Error::InvalidSignature {
+                actual: header[..4].into(),
+                path: path.clone(),
+            },
+        })?;
         Ok(data::File {
-            data,
-            path: path.to_owned(),
+            storage,
             id: git_features::hash::crc32(path.as_os_str().to_string_lossy().as_bytes()),
+            path,
             version: kind,
             num_objects,
             hash_len,
@@ -1,6 +1,9 @@
 ///
 pub mod decode_entry;
-mod init;
+///
+pub mod decode_header;
+pub(crate) mod init;
+pub(crate) mod positioned;
 ///
 pub mod verify;
 
@@ -0,0 +1,143 @@
+use std::{collections::HashMap, convert::TryInto, io, path::Path, sync::Arc};
+
+/// A pack data file on disk which is read with positioned reads in blocks of a fixed size, keeping the most recently
+/// used blocks in memory.
+pub(crate) struct Reader {
+    file: std::fs::File,
+    /// The length of the file at the time it was opened.
+    len: usize,
+    block_size: usize,
+    capacity: usize,
+    blocks: parking_lot::Mutex<Blocks>,
+}
+
+#[derive(Default)]
+struct Blocks {
+    /// Incremented on each access to determine the least recently used block.
+    clock: u64,
+    /// Blocks by their index, along with the clock value of their last access.
+    by_index: HashMap<usize, (u64, Arc<[u8]>)>,
+}
+
+impl Reader {
+    /// Open the file at `path` to read it in blocks of `block_size` bytes, keeping up to `capacity` of them in memory.
+    pub fn open(path: &Path, block_size: usize, capacity: usize) -> io::Result<Self> {
+        assert!(block_size > 0, "blocks must not be empty");
+        let file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Reader {
+            file,
+            len: len
+                .try_into()
+                .map_err(|_| io::Error::new(io::ErrorKind::Other, "file size doesn't fit into usize"))?,
+            block_size,
+            capacity,
+            blocks: Default::default(),
+        })
+    }
+
+    /// The length of the file at the time it was opened.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the block containing the byte at `offset` along with the offset into it.
+    ///
+    /// The block is shorter than the block size if it is the last one of the file.
+    pub fn block_at(&self, offset: usize) -> io::Result<(Arc<[u8]>, usize)> {
+        if offset >= self.len {
+            return Err(unexpected_eof(offset));
+        }
+        let index = offset / self.block_size;
+        Ok((self.block(index)?, offset % self.block_size))
+    }
+
+    /// Fill all of `buf` with the bytes starting at `offset`.
+    pub fn read_exact_at(&self, mut offset: usize, mut buf: &mut [u8]) -> io::Result<()> {
+        if offset + buf.len() > self.len {
+            return Err(unexpected_eof(self.len));
+        }
+        while !buf.is_empty() {
+            let (block, start) = self.block_at(offset)?;
+            let available = &block[start..];
+            let n = available.len().min(buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            buf = &mut buf[n..];
+            offset += n;
+        }
+        Ok(())
+    }
+
+    fn block(&self, index: usize) -> io::Result<Arc<[u8]>> {
+        {
+            let mut blocks = self.blocks.lock();
+            blocks.clock += 1;
+            let clock = blocks.clock;
+            if let Some((last_used, block)) = blocks.by_index.get_mut(&index) {
+                *last_used = clock;
+                return Ok(block.clone());
+            }
+        }
+
+        let start = index * self.block_size;
+        let mut buf = vec![0; self.block_size.min(self.len - start)];
+        read_exact_at(&self.file, &mut buf, start as u64)?;
+        let block: Arc<[u8]> = buf.into();
+        if self.capacity == 0 {
+            return Ok(block);
+        }
+
+        let mut blocks = self.blocks.lock();
+        if blocks.by_index.len() >= self.capacity && !blocks.by_index.contains_key(&index) {
+            let least_recently_used = blocks
+                .by_index
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(index, _)| *index)
+                .expect("at least one block as capacity is not 0");
+            blocks.by_index.remove(&least_recently_used);
+        }
+        let clock = blocks.clock;
+        blocks.by_index.insert(index, (clock, block.clone()));
+        Ok(block)
+    }
+}
+
+fn unexpected_eof(offset: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        format!("Pack data ends before offset {}, it might have been truncated", offset),
+    )
+}
+
+fn read_exact_at(file: &std::fs::File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    while !buf.is_empty() {
+        match read_at(file, buf, offset) {
+            Ok(0) => return Err(unexpected_eof(offset as usize)),
+            Ok(n) => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::unix::fs::FileExt::read_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::windows::fs::FileExt::seek_read(file, buf, offset)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn read_at(mut file: &std::fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    use std::io::{Read, Seek};
+    file.seek(io::SeekFrom::Start(offset))?;
+    file.read(buf)
+}
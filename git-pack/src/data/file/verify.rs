@@ -2,7 +2,7 @@ use std::sync::atomic::AtomicBool;
 
 use git_features::progress::Progress;
 
-use crate::data::File;
+use crate::data::{File, Storage};
 
 ///
 pub mod checksum {
@@ -13,8 +13,17 @@ pub mod checksum {
 /// Checksums and verify checksums
 impl File {
     /// The checksum in the trailer of this pack data file
+    ///
+    /// # Panics
+    ///
+    /// If the trailer of a pack read with [positioned reads][crate::data::Access::PositionedRead] can't be read.
     pub fn checksum(&self) -> git_hash::ObjectId {
-        git_hash::ObjectId::from(&self.data[self.data.len() - self.hash_len..])
+        git_hash::ObjectId::from(
+            self.storage
+                .get(self.pack_end()..self.data_len())
+                .expect("trailer to be readable")
+                .as_ref(),
+        )
     }
 
     /// Verifies that the checksum of the packfile over all bytes preceding it indeed matches the actual checksum,
@@ -30,9 +39,31 @@ impl File {
         progress: impl Progress,
         should_interrupt: &AtomicBool,
     ) -> Result<git_hash::ObjectId, checksum::Error> {
+        let data = match &self.storage {
+            Storage::Data(data) => data,
+            Storage::Read(_) => {
+                return crate::verify::checksum_on_disk(
+                    self.path(),
+                    self.data_len(),
+                    self.checksum(),
+                    self.object_hash,
+                    progress,
+                    should_interrupt,
+                )
+            }
+        };
+        if !self.is_entire_file() {
+            return crate::verify::checksum_of_data(
+                data,
+                self.checksum(),
+                self.object_hash,
+                progress,
+                should_interrupt,
+            );
+        }
         crate::verify::checksum_on_disk_or_mmap(
             self.path(),
-            &self.data,
+            data,
             self.checksum(),
             self.object_hash,
             progress,
@@ -42,11 +42,6 @@ pub mod decode {
     #[derive(thiserror::Error, Debug)]
     #[allow(missing_docs)]
     pub enum Error {
-        #[error("Could not open pack file at '{path}'")]
-        Io {
-            source: std::io::Error,
-            path: std::path::PathBuf,
-        },
         #[error("{0}")]
         Corrupt(String),
         #[error("Unsupported pack version: {0}")]
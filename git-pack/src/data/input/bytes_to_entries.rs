@@ -283,8 +283,13 @@ where
 
 impl crate::data::File {
     /// Returns an iterator over [`Entries`][crate::data::input::Entry], without making use of the memory mapping.
+    ///
+    /// If this pack [is in memory][crate::data::File::is_in_memory()] or embedded in a bundle file, its data is read directly.
     pub fn streaming_iter(&self) -> Result<BytesToEntriesIter<impl io::BufRead>, input::Error> {
-        let reader = io::BufReader::with_capacity(4096 * 8, fs::File::open(&self.path)?);
+        let reader: Box<dyn io::BufRead + Send> = match &self.storage {
+            crate::data::Storage::Data(data) if !data.is_entire_file() => Box::new(io::Cursor::new(data.clone())),
+            _ => Box::new(io::BufReader::with_capacity(4096 * 8, fs::File::open(&self.path)?)),
+        };
         BytesToEntriesIter::new_from_header(
             reader,
             input::Mode::Verify,
@@ -1,5 +1,5 @@
 //! a pack data file
-use std::{convert::TryInto, path::Path};
+use std::{borrow::Cow, convert::TryInto, path::Path, sync::Arc};
 
 /// The offset to an entry into the pack data file, relative to its beginning.
 pub type Offset = u64;
@@ -7,8 +7,6 @@ pub type Offset = u64;
 /// An identifier to uniquely identify all packs loaded within a known context or namespace.
 pub type Id = u32;
 
-use memmap2::Mmap;
-
 /// An representing an full- or delta-object within a pack
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -22,13 +20,13 @@ pub struct Entry {
 }
 
 mod file;
-pub use file::{decode_entry, verify, Header, ResolvedBase};
+pub use file::{decode_entry, decode_header, verify, Header, ResolvedBase};
 ///
 pub mod header;
 
 ///
 pub mod init {
-    pub use super::header::decode::Error;
+    pub use super::file::init::Error;
 }
 
 ///
@@ -60,9 +58,76 @@ impl Default for Version {
     }
 }
 
+/// The way the bytes of a pack data file on disk are accessed.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Access {
+    /// Map the entire file into memory, which is fastest but can stall the process on network filesystems, or crash it
+    /// with `SIGBUS` if the file is truncated while mapped.
+    MemoryMap,
+    /// Read the file with positioned reads in blocks of `block_size` bytes, keeping up to `capacity` of the most recently
+    /// used blocks in memory.
+    ///
+    /// Reading past the end of a file that was truncated after it was opened fails with an IO error.
+    #[allow(missing_docs)]
+    PositionedRead { block_size: usize, capacity: usize },
+}
+
+impl Default for Access {
+    fn default() -> Self {
+        Access::MemoryMap
+    }
+}
+
+impl Access {
+    /// Positioned reads with blocks of 64KiB and a cache of 4MiB per pack.
+    pub fn positioned_read() -> Self {
+        Access::PositionedRead {
+            block_size: 64 * 1024,
+            capacity: 64,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Storage {
+    Data(crate::mmap::Data),
+    Read(Arc<file::positioned::Reader>),
+}
+
+impl Storage {
+    fn len(&self) -> usize {
+        match self {
+            Storage::Data(data) => data.len(),
+            Storage::Read(reader) => reader.len(),
+        }
+    }
+
+    /// Return the bytes in `range`, or `None` if they are out of bounds or couldn't be read.
+    fn get(&self, range: std::ops::Range<usize>) -> Option<Cow<'_, [u8]>> {
+        self.read(range).ok()
+    }
+
+    /// Return the bytes in `range`, or an error if they are out of bounds or couldn't be read.
+    fn read(&self, range: std::ops::Range<usize>) -> std::io::Result<Cow<'_, [u8]>> {
+        let out_of_bounds = || std::io::Error::from(std::io::ErrorKind::UnexpectedEof);
+        match self {
+            Storage::Data(data) => data.get(range).map(Cow::Borrowed).ok_or_else(out_of_bounds),
+            Storage::Read(reader) => {
+                let mut buf = vec![0; range.end.checked_sub(range.start).ok_or_else(out_of_bounds)?];
+                reader.read_exact_at(range.start, &mut buf)?;
+                Ok(Cow::Owned(buf))
+            }
+        }
+    }
+}
+
 /// A pack data file
+///
+/// Clones are cheap as they share the underlying data.
+#[derive(Clone)]
 pub struct File {
-    data: Mmap,
+    storage: Storage,
     path: std::path::PathBuf,
     /// A value to represent this pack uniquely when used with cache lookup, or a way to identify this pack by its location on disk.
     /// The same location on disk should yield the same id.
@@ -92,7 +157,7 @@ impl File {
     }
     /// The length of all mapped data, including the pack header and the pack trailer
     pub fn data_len(&self) -> usize {
-        self.data.len()
+        self.storage.len()
     }
     /// The kind of hash we use internally.
     pub fn object_hash(&self) -> git_hash::Kind {
@@ -100,19 +165,47 @@ impl File {
     }
     /// The position of the byte one past the last pack entry, or in other terms, the first byte of the trailing hash.
     pub fn pack_end(&self) -> usize {
-        self.data.len() - self.hash_len
+        self.storage.len() - self.hash_len
     }
 
-    /// The path to the pack data file on disk
+    /// The path to the pack data file on disk.
+    ///
+    /// If this file was [created from bytes][File::from_bytes()], it's a synthetic path based on its checksum
+    /// which doesn't exist on disk. Packs [embedded in bundle files][crate::bundle_file::File::pack()] have the path
+    /// of their bundle.
     pub fn path(&self) -> &Path {
         &self.path
     }
 
-    /// Returns the pack data at the given slice if its range is contained in the mapped pack data
-    pub fn entry_slice(&self, slice: EntryRange) -> Option<&[u8]> {
+    /// Return true if our data is the entire content of the file at our [path][File::path()], which allows reading it from there.
+    pub(crate) fn is_entire_file(&self) -> bool {
+        match &self.storage {
+            Storage::Data(data) => data.is_entire_file(),
+            Storage::Read(_) => true,
+        }
+    }
+
+    /// Return true if this file was [created from bytes][File::from_bytes()] instead of being read from disk.
+    pub fn is_in_memory(&self) -> bool {
+        match &self.storage {
+            Storage::Data(data) => data.is_in_memory(),
+            Storage::Read(_) => false,
+        }
+    }
+
+    /// Return information about the file we were read from, or `None` if we were [created from bytes][File::from_bytes()].
+    pub fn file_info(&self) -> Option<crate::verify::FileInfo> {
+        (!self.is_in_memory()).then(|| crate::verify::FileInfo::from_path(&self.path, self.data_len()))
+    }
+
+    /// Returns the pack data at the given slice if its range is contained in the pack data.
+    ///
+    /// The data is only borrowed if the pack is [memory mapped][Access::MemoryMap] or held in memory, and `None` is
+    /// also returned if it couldn't be read.
+    pub fn entry_slice(&self, slice: EntryRange) -> Option<Cow<'_, [u8]>> {
         let entry_end: usize = slice.end.try_into().expect("end of pack fits into usize");
         let entry_start = slice.start as usize;
-        self.data.get(entry_start..entry_end)
+        self.storage.get(entry_start..entry_end)
     }
 
     /// Returns the CRC32 of the pack data indicated by `pack_offset` and the `size` of the mapped data.
@@ -122,11 +215,26 @@ impl File {
     ///
     /// # Panics
     ///
-    /// If `pack_offset` or `size` are pointing to a range outside of the mapped pack data.
+    /// If `pack_offset` or `size` are pointing to a range outside of the pack data, or if it couldn't be read.
+    /// Use [`try_entry_crc32()`][File::try_entry_crc32()] to handle this case.
     pub fn entry_crc32(&self, pack_offset: Offset, size: usize) -> u32 {
-        let pack_offset: usize = pack_offset.try_into().expect("pack_size fits into usize");
-        git_features::hash::crc32(&self.data[pack_offset..pack_offset + size])
+        self.try_entry_crc32(pack_offset, size)
+            .expect("entry to be within the readable pack data")
+    }
+
+    /// Like [`entry_crc32()`][File::entry_crc32()], but fail with [`Io`][decode_entry::Error::Io] if the pack data
+    /// in question is out of bounds or can't be read, for instance because the pack was truncated after it was opened.
+    pub fn try_entry_crc32(&self, pack_offset: Offset, size: usize) -> Result<u32, decode_entry::Error> {
+        let start: usize = pack_offset.try_into().expect("pack_size fits into usize");
+        self.storage
+            .read(start..start + size)
+            .map(|data| git_features::hash::crc32(&data))
+            .map_err(|source| decode_entry::Error::Io {
+                offset: pack_offset,
+                source,
+            })
     }
 }
 
-pub(crate) mod delta;
+///
+pub mod delta;
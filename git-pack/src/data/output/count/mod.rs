@@ -45,5 +45,7 @@ pub use objects_impl::{objects, objects_unthreaded};
 
 ///
 pub mod objects {
-    pub use super::objects_impl::{Error, ObjectExpansion, Options, Outcome, Result};
+    pub use super::objects_impl::{
+        Error, ObjectExpansion, Options, Outcome, PathFilter, PathMatch, Result, SecondsSinceUnixEpoch,
+    };
 }
@@ -12,7 +12,7 @@ pub(in crate::data::output::count::objects_impl) mod reduce;
 mod util;
 
 mod types;
-pub use types::{Error, ObjectExpansion, Options, Outcome};
+pub use types::{Error, ObjectExpansion, Options, Outcome, PathFilter, PathMatch, SecondsSinceUnixEpoch};
 
 mod tree;
 
@@ -33,6 +33,8 @@ pub type Result<E1, E2> = std::result::Result<(Vec<output::Count>, Outcome), Err
 ///   * a way to obtain progress information
 /// * `should_interrupt`
 ///  * A flag that is set to true if the operation should stop
+/// * `commit_graph`
+///   * If set, generation numbers are obtained from it to apply the [generation cutoff][Options::cutoff_generation].
 /// * `options`
 ///   * more configuration
 pub fn objects<Find, Iter, IterErr, Oid>(
@@ -40,10 +42,14 @@ pub fn objects<Find, Iter, IterErr, Oid>(
     objects_ids: Iter,
     progress: impl Progress,
     should_interrupt: &AtomicBool,
+    commit_graph: Option<&git_commitgraph::Graph>,
     Options {
         thread_limit,
         input_object_expansion,
         chunk_size,
+        cutoff_time,
+        cutoff_generation,
+        path_filter,
     }: Options,
 ) -> Result<find::existing::Error<Find::Error>, IterErr>
 where
@@ -65,6 +71,12 @@ where
         size: chunk_size,
     };
     let seen_objs = dashmap::DashSet::<ObjectId, git_hashtable::hash::Builder>::default();
+    let cutoff = expand::Cutoff {
+        time: cutoff_time,
+        generation: cutoff_generation,
+        commit_graph,
+    };
+    let path_filter = path_filter.as_ref();
     let progress = Arc::new(parking_lot::Mutex::new(progress));
 
     parallel::in_parallel(
@@ -92,6 +104,8 @@ where
                 expand::this(
                     &db,
                     input_object_expansion,
+                    cutoff,
+                    path_filter,
                     seen_objs,
                     oids,
                     buf1,
@@ -107,12 +121,22 @@ where
 }
 
 /// Like [`objects()`] but using a single thread only to mostly save on the otherwise required overhead.
+///
+/// Note that [`thread_limit`][Options::thread_limit] and [`chunk_size`][Options::chunk_size] are ignored.
 pub fn objects_unthreaded<Find, IterErr, Oid>(
     db: Find,
     object_ids: impl Iterator<Item = std::result::Result<Oid, IterErr>>,
     mut progress: impl Progress,
     should_interrupt: &AtomicBool,
-    input_object_expansion: ObjectExpansion,
+    commit_graph: Option<&git_commitgraph::Graph>,
+    Options {
+        input_object_expansion,
+        cutoff_time,
+        cutoff_generation,
+        path_filter,
+        thread_limit: _,
+        chunk_size: _,
+    }: Options,
 ) -> Result<find::existing::Error<Find::Error>, IterErr>
 where
     Find: crate::Find,
@@ -125,6 +149,12 @@ where
     expand::this(
         &db,
         input_object_expansion,
+        expand::Cutoff {
+            time: cutoff_time,
+            generation: cutoff_generation,
+            commit_graph,
+        },
+        path_filter.as_ref(),
         &seen_objs,
         object_ids,
         &mut buf1,
@@ -144,7 +174,7 @@ mod expand {
 
     use super::{
         tree,
-        types::{Error, ObjectExpansion, Outcome},
+        types::{Error, ObjectExpansion, Outcome, PathFilter, SecondsSinceUnixEpoch},
         util,
     };
     use crate::{
@@ -152,10 +182,45 @@ mod expand {
         find, FindExt,
     };
 
+    /// Determines which commits are too old to be expanded.
+    #[derive(Clone, Copy)]
+    pub struct Cutoff<'a> {
+        pub time: Option<SecondsSinceUnixEpoch>,
+        pub generation: Option<u32>,
+        pub commit_graph: Option<&'a git_commitgraph::Graph>,
+    }
+
+    impl Cutoff<'_> {
+        fn is_set(&self) -> bool {
+            self.time.is_some() || self.generation.is_some()
+        }
+
+        /// Return `true` if the commit with `id` and `data` is below the cutoff and should be pruned.
+        fn prunes(&self, id: &oid, data: &[u8]) -> Result<bool, git_object::decode::Error> {
+            if let Some((generation, graph)) = self.generation.zip(self.commit_graph) {
+                if graph.commit_by_id(id).map_or(false, |c| c.generation() < generation) {
+                    return Ok(true);
+                }
+            }
+            Ok(match self.time {
+                Some(time) => {
+                    CommitRefIter::from_bytes(data)
+                        .committer()?
+                        .time
+                        .seconds_since_unix_epoch
+                        < time
+                }
+                None => false,
+            })
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn this<Find, IterErr, Oid>(
         db: &Find,
         input_object_expansion: ObjectExpansion,
+        cutoff: Cutoff<'_>,
+        path_filter: Option<&PathFilter>,
         seen_objs: &impl util::InsertImmutable<ObjectId>,
         oids: impl IntoIterator<Item = std::result::Result<Oid, IterErr>>,
         buf1: &mut Vec<u8>,
@@ -175,8 +240,9 @@ mod expand {
         let mut tree_traversal_state = git_traverse::tree::breadthfirst::State::default();
         let mut tree_diff_state = git_diff::tree::State::default();
         let mut parent_commit_ids = Vec::new();
-        let mut traverse_delegate = tree::traverse::AllUnseen::new(seen_objs);
-        let mut changes_delegate = tree::changes::AllNew::new(seen_objs);
+        let revisited_trees = tree::traverse::RevisitedTrees::default();
+        let mut traverse_delegate = tree::traverse::AllUnseen::new(seen_objs, path_filter, &revisited_trees);
+        let mut changes_delegate = tree::changes::AllNew::new(seen_objs, path_filter);
         let mut outcome = Outcome::default();
 
         let stats = &mut outcome;
@@ -196,6 +262,10 @@ mod expand {
                     let mut id = id.to_owned();
 
                     loop {
+                        if obj.kind == Commit && cutoff.prunes(&id, obj.data).map_err(Error::CommitDecode)? {
+                            stats.pruned_commits += 1;
+                            break;
+                        }
                         push_obj_count_unique(&mut out, seen_objs, &id, location, progress, stats, false);
                         match obj.kind {
                             Tree | Blob => break,
@@ -232,6 +302,22 @@ mod expand {
                                     git_object::TreeRefIter::from_bytes(obj.data)
                                 };
 
+                                if cutoff.is_set() {
+                                    let mut idx = 0;
+                                    while let Some(parent_id) = parent_commit_ids.get(idx).copied() {
+                                        let (parent_commit_obj, _location) = db.find(parent_id, buf2)?;
+                                        if cutoff
+                                            .prunes(&parent_id, parent_commit_obj.data)
+                                            .map_err(Error::CommitDecode)?
+                                        {
+                                            stats.pruned_commits += 1;
+                                            parent_commit_ids.remove(idx);
+                                        } else {
+                                            idx += 1;
+                                        }
+                                    }
+                                }
+
                                 let objects = if parent_commit_ids.is_empty() {
                                     traverse_delegate.clear();
                                     git_traverse::tree::breadthfirst(
@@ -241,9 +327,11 @@ mod expand {
                                             stats.decoded_objects += 1;
                                             match db.find(oid, buf).ok() {
                                                 Some((obj, location)) => {
-                                                    progress.inc();
-                                                    stats.expanded_objects += 1;
-                                                    out.push(output::Count::from_data(oid, location));
+                                                    if !revisited_trees.take(oid) {
+                                                        progress.inc();
+                                                        stats.expanded_objects += 1;
+                                                        out.push(output::Count::from_data(oid, location));
+                                                    }
                                                     obj.try_into_tree_iter()
                                                 }
                                                 None => None,
@@ -252,6 +340,10 @@ mod expand {
                                         &mut traverse_delegate,
                                     )
                                     .map_err(Error::TreeTraverse)?;
+                                    stats.excluded_trees += traverse_delegate.excluded_trees;
+                                    stats.excluded_blobs += traverse_delegate.excluded_blobs;
+                                    stats.skipped_gitlinks += traverse_delegate.skipped_gitlinks;
+                                    stats.ambiguous_paths += traverse_delegate.ambiguities.count;
                                     &traverse_delegate.non_trees
                                 } else {
                                     for commit_id in &parent_commit_ids {
@@ -291,11 +383,18 @@ mod expand {
                                                 &mut changes_delegate,
                                             )
                                             .map_err(Error::TreeChanges)?;
+                                        stats.excluded_trees += changes_delegate.excluded_trees;
+                                        stats.excluded_blobs += changes_delegate.excluded_blobs;
+                                        stats.skipped_gitlinks += changes_delegate.skipped_gitlinks;
+                                        stats.ambiguous_paths += changes_delegate.ambiguities.count;
                                     }
                                     &changes_delegate.objects
                                 };
                                 for id in objects.iter() {
-                                    out.push(id_to_count(db, buf2, id, progress, stats, allow_pack_lookups));
+                                    out.push(
+                                        id_to_count(db, buf2, id, progress, stats, allow_pack_lookups)
+                                            .map_err(|err| Error::FindExisting(find::existing::Error::Find(err)))?,
+                                    );
                                 }
                                 break;
                             }
@@ -307,6 +406,10 @@ mod expand {
                     let mut id = id;
                     let mut obj = (obj, location);
                     loop {
+                        if obj.0.kind == Commit && cutoff.prunes(&id, obj.0.data).map_err(Error::CommitDecode)? {
+                            stats.pruned_commits += 1;
+                            break;
+                        }
                         push_obj_count_unique(&mut out, seen_objs, &id, obj.1.clone(), progress, stats, false);
                         match obj.0.kind {
                             Tree => {
@@ -318,9 +421,11 @@ mod expand {
                                         stats.decoded_objects += 1;
                                         match db.find(oid, buf).ok() {
                                             Some((obj, location)) => {
-                                                progress.inc();
-                                                stats.expanded_objects += 1;
-                                                out.push(output::Count::from_data(oid, location));
+                                                if !revisited_trees.take(oid) {
+                                                    progress.inc();
+                                                    stats.expanded_objects += 1;
+                                                    out.push(output::Count::from_data(oid, location));
+                                                }
                                                 obj.try_into_tree_iter()
                                             }
                                             None => None,
@@ -329,8 +434,15 @@ mod expand {
                                     &mut traverse_delegate,
                                 )
                                 .map_err(Error::TreeTraverse)?;
+                                stats.excluded_trees += traverse_delegate.excluded_trees;
+                                stats.excluded_blobs += traverse_delegate.excluded_blobs;
+                                stats.skipped_gitlinks += traverse_delegate.skipped_gitlinks;
+                                stats.ambiguous_paths += traverse_delegate.ambiguities.count;
                                 for id in traverse_delegate.non_trees.iter() {
-                                    out.push(id_to_count(db, buf1, id, progress, stats, allow_pack_lookups));
+                                    out.push(
+                                        id_to_count(db, buf1, id, progress, stats, allow_pack_lookups)
+                                            .map_err(|err| Error::FindExisting(find::existing::Error::Find(err)))?,
+                                    );
                                 }
                                 break;
                             }
@@ -390,16 +502,16 @@ mod expand {
         progress: &mut impl Progress,
         statistics: &mut Outcome,
         allow_pack_lookups: bool,
-    ) -> output::Count {
+    ) -> Result<output::Count, Find::Error> {
         progress.inc();
         statistics.expanded_objects += 1;
-        output::Count {
+        Ok(output::Count {
             id: id.to_owned(),
             entry_pack_location: if allow_pack_lookups {
-                PackLocation::LookedUp(db.location_by_oid(id, buf))
+                PackLocation::LookedUp(db.location_by_oid(id, buf)?)
             } else {
                 PackLocation::NotLookedUp
             },
-        }
+        })
     }
 }
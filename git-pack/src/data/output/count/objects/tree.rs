@@ -1,30 +1,135 @@
+use crate::data::output::count::objects_impl::types::PathFilter;
+
+/// Keeps track of the path of the entry currently visited during tree traversals and diffs.
+#[derive(Default)]
+pub struct Path {
+    current: git_object::bstr::BString,
+    deque: std::collections::VecDeque<git_object::bstr::BString>,
+}
+
+impl Path {
+    pub fn as_bstr(&self) -> &git_object::bstr::BStr {
+        self.current.as_ref()
+    }
+
+    pub fn clear(&mut self) {
+        self.current.clear();
+        self.deque.clear();
+    }
+
+    pub fn pop_front_tracked_path_and_set_current(&mut self) {
+        self.current = self
+            .deque
+            .pop_front()
+            .expect("every call is matched with push_back_tracked_path_component");
+    }
+
+    pub fn push_back_tracked_path_component(&mut self, component: &git_object::bstr::BStr) {
+        self.push_path_component(component);
+        self.deque.push_back(self.current.clone());
+    }
+
+    pub fn push_path_component(&mut self, component: &git_object::bstr::BStr) {
+        use git_object::bstr::ByteVec;
+        if !self.current.is_empty() {
+            self.current.push(b'/');
+        }
+        self.current.push_str(component);
+    }
+
+    pub fn pop_path_component(&mut self) {
+        use git_object::bstr::ByteSlice;
+        match self.current.rfind_byte(b'/') {
+            Some(pos) => self.current.resize(pos, 0),
+            None => self.current.clear(),
+        }
+    }
+}
+
+/// Counts entries of the same tree which lead to or match the same path of a path filter as a previous entry, which
+/// is possible if the filter ignores case or unicode normalization.
+#[derive(Default)]
+pub struct Ambiguities {
+    pub count: usize,
+    /// The indices of the path filter prefixes that entries of the current tree lead to or match.
+    prefixes_in_current_tree: Vec<usize>,
+}
+
+impl Ambiguities {
+    pub fn clear(&mut self) {
+        self.count = 0;
+        self.prefixes_in_current_tree.clear();
+    }
+
+    /// Call whenever the entries of the next tree are about to be visited.
+    pub fn next_tree(&mut self) {
+        self.prefixes_in_current_tree.clear();
+    }
+
+    pub fn check(&mut self, filter: &PathFilter, path: &git_object::bstr::BStr) {
+        if !(filter.ignores_case() || filter.ignores_normalization()) {
+            return;
+        }
+        match filter.matching_portion(path) {
+            Some((idx, portion)) if portion.len() == path.len() => {
+                if self.prefixes_in_current_tree.contains(&idx) {
+                    self.count += 1;
+                } else {
+                    self.prefixes_in_current_tree.push(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 pub mod changes {
     use git_diff::tree::{
         visit::{Action, Change},
         Visit,
     };
     use git_hash::ObjectId;
-    use git_object::{bstr::BStr, tree::EntryMode};
+    use git_object::bstr::BStr;
 
-    use crate::data::output::count::objects_impl::util::InsertImmutable;
+    use crate::data::output::count::objects_impl::{
+        types::{PathFilter, PathMatch},
+        util::InsertImmutable,
+    };
 
     pub struct AllNew<'a, H> {
         pub objects: Vec<ObjectId>,
+        pub excluded_trees: usize,
+        pub excluded_blobs: usize,
+        pub skipped_gitlinks: usize,
+        pub ambiguities: super::Ambiguities,
         all_seen: &'a H,
+        filter: Option<&'a PathFilter>,
+        path: super::Path,
     }
 
     impl<'a, H> AllNew<'a, H>
     where
         H: InsertImmutable<ObjectId>,
     {
-        pub fn new(all_seen: &'a H) -> Self {
+        pub fn new(all_seen: &'a H, filter: Option<&'a PathFilter>) -> Self {
             AllNew {
                 objects: Default::default(),
+                excluded_trees: 0,
+                excluded_blobs: 0,
+                skipped_gitlinks: 0,
+                ambiguities: Default::default(),
                 all_seen,
+                filter,
+                path: Default::default(),
             }
         }
         pub fn clear(&mut self) {
             self.objects.clear();
+            self.excluded_trees = 0;
+            self.excluded_blobs = 0;
+            self.skipped_gitlinks = 0;
+            self.ambiguities.clear();
+            self.path.clear();
         }
     }
 
@@ -32,25 +137,58 @@ pub mod changes {
     where
         H: InsertImmutable<ObjectId>,
     {
-        fn pop_front_tracked_path_and_set_current(&mut self) {}
+        fn pop_front_tracked_path_and_set_current(&mut self) {
+            if self.filter.is_some() {
+                self.path.pop_front_tracked_path_and_set_current();
+                self.ambiguities.next_tree();
+            }
+        }
 
-        fn push_back_tracked_path_component(&mut self, _component: &BStr) {}
+        fn push_back_tracked_path_component(&mut self, component: &BStr) {
+            if self.filter.is_some() {
+                self.path.push_back_tracked_path_component(component);
+            }
+        }
 
-        fn push_path_component(&mut self, _component: &BStr) {}
+        fn push_path_component(&mut self, component: &BStr) {
+            if self.filter.is_some() {
+                self.path.push_path_component(component);
+            }
+        }
 
-        fn pop_path_component(&mut self) {}
+        fn pop_path_component(&mut self) {
+            if self.filter.is_some() {
+                self.path.pop_path_component();
+            }
+        }
 
         fn visit(&mut self, change: Change) -> Action {
             match change {
                 Change::Addition { oid, entry_mode } | Change::Modification { oid, entry_mode, .. } => {
-                    if entry_mode == EntryMode::Commit {
+                    if entry_mode.is_commit() {
+                        self.skipped_gitlinks += 1;
                         return Action::Continue;
                     }
+                    if let Some(filter) = self.filter {
+                        match (filter.classify(self.path.as_bstr()), entry_mode.is_tree()) {
+                            (PathMatch::Included, _) | (PathMatch::Parent, true) => {}
+                            (_, true) => {
+                                self.excluded_trees += 1;
+                                return Action::Continue;
+                            }
+                            (_, false) => {
+                                self.excluded_blobs += 1;
+                                return Action::Continue;
+                            }
+                        }
+                        self.ambiguities.check(filter, self.path.as_bstr());
+                    }
                     let inserted = self.all_seen.insert(oid);
                     if inserted {
                         self.objects.push(oid);
                     }
                 }
+                Change::SubmoduleChanged { .. } => self.skipped_gitlinks += 1,
                 Change::Deletion { .. } => {}
             };
             Action::Continue
@@ -59,32 +197,82 @@ pub mod changes {
 }
 
 pub mod traverse {
+    use std::cell::RefCell;
+
     use git_hash::ObjectId;
-    use git_object::{
-        bstr::BStr,
-        tree::{EntryMode, EntryRef},
-    };
+    use git_object::{bstr::BStr, tree::EntryRef};
     use git_traverse::tree::{visit::Action, Visit};
 
-    use crate::data::output::count::objects_impl::util::InsertImmutable;
+    use crate::data::output::count::objects_impl::{
+        types::{PathFilter, PathMatch},
+        util::InsertImmutable,
+    };
+
+    /// Trees which were seen before but have to be entered again, as they may lead to included paths not seen yet,
+    /// along with the amount of times they are entered again. These must not be added again when entering them.
+    #[derive(Default)]
+    pub struct RevisitedTrees(RefCell<git_hashtable::HashMap<ObjectId, usize>>);
+
+    impl RevisitedTrees {
+        fn insert(&self, id: ObjectId) {
+            *self.0.borrow_mut().entry(id).or_default() += 1;
+        }
+
+        /// Return `true` if the tree with `id` is entered again and thus shouldn't be added.
+        pub fn take(&self, id: &git_hash::oid) -> bool {
+            let mut map = self.0.borrow_mut();
+            match map.get_mut(id) {
+                Some(count) => {
+                    *count -= 1;
+                    if *count == 0 {
+                        map.remove(id);
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
+    }
 
     pub struct AllUnseen<'a, H> {
         pub non_trees: Vec<ObjectId>,
+        pub excluded_trees: usize,
+        pub excluded_blobs: usize,
+        pub skipped_gitlinks: usize,
+        pub ambiguities: super::Ambiguities,
         all_seen: &'a H,
+        filter: Option<&'a PathFilter>,
+        path: super::Path,
+        /// Trees whose entire contents were added already, which don't have to be entered again.
+        fully_included_trees: git_hashtable::HashSet<ObjectId>,
+        revisited_trees: &'a RevisitedTrees,
     }
 
     impl<'a, H> AllUnseen<'a, H>
     where
         H: InsertImmutable<ObjectId>,
     {
-        pub fn new(all_seen: &'a H) -> Self {
+        pub fn new(all_seen: &'a H, filter: Option<&'a PathFilter>, revisited_trees: &'a RevisitedTrees) -> Self {
             AllUnseen {
                 non_trees: Default::default(),
+                excluded_trees: 0,
+                excluded_blobs: 0,
+                skipped_gitlinks: 0,
+                ambiguities: Default::default(),
                 all_seen,
+                filter,
+                path: Default::default(),
+                fully_included_trees: Default::default(),
+                revisited_trees,
             }
         }
         pub fn clear(&mut self) {
             self.non_trees.clear();
+            self.excluded_trees = 0;
+            self.excluded_blobs = 0;
+            self.skipped_gitlinks = 0;
+            self.ambiguities.clear();
+            self.path.clear();
         }
     }
 
@@ -92,27 +280,72 @@ pub mod traverse {
     where
         H: InsertImmutable<ObjectId>,
     {
-        fn pop_front_tracked_path_and_set_current(&mut self) {}
+        fn pop_front_tracked_path_and_set_current(&mut self) {
+            if self.filter.is_some() {
+                self.path.pop_front_tracked_path_and_set_current();
+                self.ambiguities.next_tree();
+            }
+        }
 
-        fn push_back_tracked_path_component(&mut self, _component: &BStr) {}
+        fn push_back_tracked_path_component(&mut self, component: &BStr) {
+            if self.filter.is_some() {
+                self.path.push_back_tracked_path_component(component);
+            }
+        }
 
-        fn push_path_component(&mut self, _component: &BStr) {}
+        fn push_path_component(&mut self, component: &BStr) {
+            if self.filter.is_some() {
+                self.path.push_path_component(component);
+            }
+        }
 
-        fn pop_path_component(&mut self) {}
+        fn pop_path_component(&mut self) {
+            if self.filter.is_some() {
+                self.path.pop_path_component();
+            }
+        }
 
         fn visit_tree(&mut self, entry: &EntryRef<'_>) -> Action {
-            let inserted = self.all_seen.insert(entry.oid.to_owned());
-            if inserted {
-                Action::Continue
-            } else {
-                Action::Skip
+            let filter = match self.filter {
+                Some(filter) => filter,
+                None => {
+                    let inserted = self.all_seen.insert(entry.oid.to_owned());
+                    return if inserted { Action::Continue } else { Action::Skip };
+                }
+            };
+            match filter.classify(self.path.as_bstr()) {
+                PathMatch::Excluded => {
+                    self.excluded_trees += 1;
+                    return Action::Skip;
+                }
+                PathMatch::Included => {
+                    self.ambiguities.check(filter, self.path.as_bstr());
+                    if !self.fully_included_trees.insert(entry.oid.to_owned()) {
+                        return Action::Skip;
+                    }
+                }
+                PathMatch::Parent => self.ambiguities.check(filter, self.path.as_bstr()),
             }
+            // A tree seen before may have been partially included, so we have to enter it again without adding it.
+            if !self.all_seen.insert(entry.oid.to_owned()) {
+                self.revisited_trees.insert(entry.oid.to_owned());
+            }
+            Action::Continue
+        }
+
+        fn visit_gitlink(&mut self, _entry: &EntryRef<'_>) -> Action {
+            // commits of submodules live in other repositories
+            self.skipped_gitlinks += 1;
+            Action::Continue
         }
 
         fn visit_nontree(&mut self, entry: &EntryRef<'_>) -> Action {
-            if entry.mode == EntryMode::Commit {
-                // links don't have a representation
-                return Action::Continue;
+            if let Some(filter) = self.filter {
+                if filter.classify(self.path.as_bstr()) != PathMatch::Included {
+                    self.excluded_blobs += 1;
+                    return Action::Continue;
+                }
+                self.ambiguities.check(filter, self.path.as_bstr());
             }
             let inserted = self.all_seen.insert(entry.oid.to_owned());
             if inserted {
@@ -1,3 +1,5 @@
+use git_object::bstr::{BStr, BString, ByteSlice};
+
 /// Information gathered during the run of [`iter_from_objects()`][super::objects()].
 #[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -12,6 +14,22 @@ pub struct Outcome {
     pub decoded_objects: usize,
     /// The total amount of encountered objects. Should be `expanded_objects + input_objects`.
     pub total_objects: usize,
+    /// The amount of commits that were not expanded as they were below the [time][Options::cutoff_time] or
+    /// [generation][Options::cutoff_generation] cutoff.
+    pub pruned_commits: usize,
+    /// The amount of tree entries pointing to trees which were not entered as their path is outside of the
+    /// [path filter][Options::path_filter].
+    pub excluded_trees: usize,
+    /// The amount of tree entries pointing to blobs or symlinks which were not added as their path is outside of the
+    /// [path filter][Options::path_filter].
+    pub excluded_blobs: usize,
+    /// The amount of tree entries pointing to commits of submodules, which were skipped as these live in other repositories.
+    pub skipped_gitlinks: usize,
+    /// The amount of tree entries which lead to or match the same path of a [path filter][Options::path_filter] ignoring case
+    /// or unicode normalization as another entry of the same tree, like `readme` after `README`. These are included nonetheless.
+    ///
+    /// Only entries that are encountered are counted, so trees skipped as they were seen before aren't checked again.
+    pub ambiguous_paths: usize,
 }
 
 impl Outcome {
@@ -22,12 +40,22 @@ impl Outcome {
             decoded_objects,
             expanded_objects,
             total_objects,
+            pruned_commits,
+            excluded_trees,
+            excluded_blobs,
+            skipped_gitlinks,
+            ambiguous_paths,
         }: Self,
     ) {
         self.input_objects += input_objects;
         self.decoded_objects += decoded_objects;
         self.expanded_objects += expanded_objects;
         self.total_objects += total_objects;
+        self.pruned_commits += pruned_commits;
+        self.excluded_trees += excluded_trees;
+        self.excluded_blobs += excluded_blobs;
+        self.skipped_gitlinks += skipped_gitlinks;
+        self.ambiguous_paths += ambiguous_paths;
     }
 }
 
@@ -58,8 +86,186 @@ impl Default for ObjectExpansion {
     }
 }
 
+/// A filter restricting the trees and blobs reachable from input objects to those at or below a set of paths,
+/// similar to the pathspec in `git rev-list --objects -- <path>...`.
+///
+/// Paths are relative to the root tree, use `/` as separator and match whole components only, so `a/b` matches
+/// `a/b` and `a/b/c`, but not `a/bc`. Trees leading to any of the paths are kept as well to be able to reach them.
+///
+/// By default, paths must match exactly. On case-insensitive filesystems it can be useful to [ignore case][PathFilter::with_ignore_case()]
+/// and [unicode normalization][PathFilter::with_ignore_normalization()] instead, which only affects matching and never the
+/// paths stored in trees. Note that this makes it possible for differently spelled entries of the same tree, like `README` and
+/// `readme`, to match the same path, in which case all of them match. Use [`matching_portion()`][PathFilter::matching_portion()]
+/// to detect this.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathFilter {
+    prefixes: Vec<BString>,
+    ignore_case: bool,
+    ignore_normalization: bool,
+}
+
+/// How a path relates to a [`PathFilter`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PathMatch {
+    /// The path and everything below it is included.
+    Included,
+    /// The path isn't included itself, but leads to paths that are.
+    Parent,
+    /// Neither the path nor anything below it is included.
+    Excluded,
+}
+
+/// How a path and a prefix of a [`PathFilter`] overlap.
+enum Overlap {
+    /// The path is at or below the prefix, and the leading portion of the path of the given length matches it.
+    PathAtOrBelow(usize),
+    /// The prefix is below the path.
+    PrefixBelow,
+}
+
+/// Initialization
+impl PathFilter {
+    /// Create a new instance matching all paths at or below any of the given `prefixes`.
+    ///
+    /// Leading and trailing slashes are ignored, and an empty prefix matches all paths.
+    pub fn new(prefixes: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        let mut prefixes: Vec<BString> = prefixes
+            .into_iter()
+            .map(|prefix| {
+                let prefix = prefix.into();
+                let start = prefix.iter().position(|b| *b != b'/').unwrap_or(prefix.len());
+                let end = prefix.iter().rposition(|b| *b != b'/').map_or(start, |pos| pos + 1);
+                prefix[start..end].into()
+            })
+            .collect();
+        prefixes.sort();
+        prefixes.dedup();
+        PathFilter {
+            prefixes,
+            ignore_case: false,
+            ignore_normalization: false,
+        }
+    }
+
+    /// If `toggle` is true, path components match if they are equal when ignoring case, so `Docs` matches `docs` and `DOCS`.
+    ///
+    /// Case is folded on the fly while comparing, for ASCII as well as for other unicode characters as long as both
+    /// components are valid UTF-8.
+    pub fn with_ignore_case(mut self, toggle: bool) -> Self {
+        self.ignore_case = toggle;
+        self
+    }
+
+    /// If `toggle` is true, path components match if they are equal after [canonical decomposition](https://unicode.org/reports/tr15/),
+    /// so a precomposed `é` (NFC) as typically used on Linux and Windows matches an `e` followed by a combining accent (NFD)
+    /// as produced by some filesystems on MacOS.
+    pub fn with_ignore_normalization(mut self, toggle: bool) -> Self {
+        self.ignore_normalization = toggle;
+        self
+    }
+}
+
+/// Access
+impl PathFilter {
+    /// The normalized prefixes of all paths to include.
+    pub fn prefixes(&self) -> &[BString] {
+        &self.prefixes
+    }
+
+    /// Return `true` if case is ignored when matching paths.
+    pub fn ignores_case(&self) -> bool {
+        self.ignore_case
+    }
+
+    /// Return `true` if unicode normalization is ignored when matching paths.
+    pub fn ignores_normalization(&self) -> bool {
+        self.ignore_normalization
+    }
+
+    /// Return `true` if `path` is at or below one of our prefixes.
+    pub fn is_included(&self, path: &BStr) -> bool {
+        self.classify(path) == PathMatch::Included
+    }
+
+    /// Return how `path` relates to our prefixes.
+    pub fn classify(&self, path: &BStr) -> PathMatch {
+        let mut res = PathMatch::Excluded;
+        for prefix in &self.prefixes {
+            if prefix.is_empty() {
+                return PathMatch::Included;
+            }
+            match self.overlap(path, prefix) {
+                Some(Overlap::PathAtOrBelow(_)) => return PathMatch::Included,
+                Some(Overlap::PrefixBelow) => res = PathMatch::Parent,
+                None => {}
+            }
+        }
+        res
+    }
+
+    /// Return the index into our [prefixes][PathFilter::prefixes()] of the first non-empty prefix that `path` is at or below
+    /// or leads to, along with the portion of `path` matching the prefix. That's `path` itself if it leads to the prefix,
+    /// or its leading components otherwise.
+    ///
+    /// When ignoring case or unicode normalization, differently spelled portions for the same prefix indicate that the
+    /// prefix is ambiguous as it matches multiple paths.
+    pub fn matching_portion<'a>(&self, path: &'a BStr) -> Option<(usize, &'a BStr)> {
+        self.prefixes.iter().enumerate().find_map(|(idx, prefix)| {
+            if prefix.is_empty() {
+                return None;
+            }
+            match self.overlap(path, prefix)? {
+                Overlap::PathAtOrBelow(len) => Some((idx, path[..len].as_bstr())),
+                Overlap::PrefixBelow => Some((idx, path)),
+            }
+        })
+    }
+
+    /// Compare the components of `path` and `prefix` pairwise until one of them runs out.
+    fn overlap(&self, path: &[u8], prefix: &[u8]) -> Option<Overlap> {
+        let mut path_components = path.split(|b| *b == b'/');
+        let mut len = 0;
+        for prefix_component in prefix.split(|b| *b == b'/') {
+            match path_components.next() {
+                Some(component) if self.component_eq(component, prefix_component) => {
+                    len += component.len() + usize::from(len != 0);
+                }
+                Some(_) => return None,
+                None => return Some(Overlap::PrefixBelow),
+            }
+        }
+        Some(Overlap::PathAtOrBelow(len))
+    }
+
+    fn component_eq(&self, a: &[u8], b: &[u8]) -> bool {
+        if a == b {
+            return true;
+        }
+        match (a.to_str(), b.to_str()) {
+            (Ok(a), Ok(b)) if !(a.is_ascii() && b.is_ascii()) => {
+                use unicode_normalization::UnicodeNormalization;
+                match (self.ignore_case, self.ignore_normalization) {
+                    (true, true) => lowercase(a).nfd().eq(lowercase(b).nfd()),
+                    (true, false) => lowercase(a).eq(lowercase(b)),
+                    (false, true) => a.nfd().eq(b.nfd()),
+                    (false, false) => false,
+                }
+            }
+            _ => self.ignore_case && a.eq_ignore_ascii_case(b),
+        }
+    }
+}
+
+fn lowercase(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(char::to_lowercase)
+}
+
+/// The amount of seconds since unix epoch, the same value obtained by any `git_date::Time` structure and the way git counts time.
+pub type SecondsSinceUnixEpoch = i64;
+
 /// Configuration options for the pack generation functions provided in [this module][crate::data::output].
-#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Options {
     /// The amount of threads to use at most when resolving the pack. If `None`, all logical cores are used.
@@ -70,6 +276,23 @@ pub struct Options {
     pub chunk_size: usize,
     /// The way input objects are handled
     pub input_object_expansion: ObjectExpansion,
+    /// If set, commits with a committer time older than the given time will not be expanded nor added, pruning the walk
+    /// at this point. Parents below the cutoff aren't used for comparison either, so if all parents of a commit are pruned
+    /// it will be treated like a root commit to assure all of its objects are still contained.
+    ///
+    /// Only effective if [`input_object_expansion`][Options::input_object_expansion] isn't [`AsIs`][ObjectExpansion::AsIs].
+    pub cutoff_time: Option<SecondsSinceUnixEpoch>,
+    /// Like [`cutoff_time`][Options::cutoff_time], but prunes commits whose generation number is lower than the given one.
+    ///
+    /// Generation numbers are obtained from the commit-graph passed alongside these options, and commits not contained in it
+    /// are never pruned by generation.
+    pub cutoff_generation: Option<u32>,
+    /// If set, only trees and blobs at or below the paths of the filter are added, along with the trees needed to reach them,
+    /// while all commits and tags are kept. Entries outside of the filter are not entered and counted in
+    /// [`Outcome::excluded_trees`] and [`Outcome::excluded_blobs`].
+    ///
+    /// Only effective if [`input_object_expansion`][Options::input_object_expansion] isn't [`AsIs`][ObjectExpansion::AsIs].
+    pub path_filter: Option<PathFilter>,
 }
 
 impl Default for Options {
@@ -78,6 +301,9 @@ impl Default for Options {
             thread_limit: None,
             chunk_size: 10,
             input_object_expansion: Default::default(),
+            cutoff_time: None,
+            cutoff_generation: None,
+            path_filter: None,
         }
     }
 }
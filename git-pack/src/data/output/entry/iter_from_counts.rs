@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, sync::Arc};
+use std::{cmp::Ordering, collections::VecDeque, sync::Arc};
 
 use git_features::{parallel, parallel::SequenceId, progress::Progress};
 
@@ -43,6 +43,8 @@ pub fn iter_from_counts<Find>(
         allow_thin_pack,
         thread_limit,
         chunk_size,
+        delta_mode,
+        compression_level,
     }: Options,
 ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error<Find::Error>>>
        + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error<Find::Error>>>
@@ -77,7 +79,11 @@ where
                         use crate::data::output::count::PackLocation::*;
                         match count.entry_pack_location {
                             LookedUp(_) => continue,
-                            NotLookedUp => count.entry_pack_location = LookedUp(db.location_by_oid(count.id, buf)),
+                            // Objects whose location can't be obtained are looked up again to create their entry,
+                            // which is when errors are reported.
+                            NotLookedUp => {
+                                count.entry_pack_location = LookedUp(db.location_by_oid(count.id, buf).ok().flatten())
+                            }
                         }
                     }
                     progress.lock().inc_by(chunk_size);
@@ -149,13 +155,15 @@ where
             let counts = Arc::clone(&counts);
             move |(chunk_id, chunk_range): (SequenceId, std::ops::Range<usize>), (buf, progress)| {
                 let mut out = Vec::new();
+                let chunk_start = chunk_range.start;
                 let chunk = &counts[chunk_range];
                 let mut stats = Outcome::default();
                 let mut pack_offsets_to_id = None;
+                let mut window = VecDeque::new();
                 progress.init(Some(chunk.len()), git_features::progress::count("objects"));
 
-                for count in chunk.iter() {
-                    out.push(match count
+                for (index_in_chunk, count) in chunk.iter().enumerate() {
+                    let (mut entry, mut source) = match count
                         .entry_pack_location
                         .as_ref()
                         .and_then(|l| db.entry_by_location(l).map(|pe| (l, pe)))
@@ -173,58 +181,101 @@ where
                             .clone();
                             let base_index_offset = pack_range.start;
                             let counts_in_pack = &counts[pack_range];
-                            match output::Entry::from_pack_entry(
-                                pack_entry,
-                                count,
-                                counts_in_pack,
-                                base_index_offset,
-                                allow_thin_pack.then(|| {
-                                    |pack_id, base_offset| {
-                                        let (cached_pack_id, cache) = pack_offsets_to_id.get_or_insert_with(|| {
-                                            db.pack_offsets_and_oid(pack_id)
-                                                .map(|mut v| {
-                                                    v.sort_by_key(|e| e.0);
-                                                    (pack_id, v)
-                                                })
-                                                .expect("pack used for counts is still available")
-                                        });
-                                        debug_assert_eq!(*cached_pack_id, pack_id);
-                                        stats.ref_delta_objects += 1;
-                                        cache
-                                            .binary_search_by_key(&base_offset, |e| e.0)
-                                            .ok()
-                                            .map(|idx| cache[idx].1)
+                            let copy_deltas = delta_mode != DeltaMode::None;
+                            let copied = (copy_deltas || !is_delta(&pack_entry, count))
+                                .then(|| {
+                                    output::Entry::from_pack_entry(
+                                        pack_entry,
+                                        count,
+                                        counts_in_pack,
+                                        base_index_offset,
+                                        allow_thin_pack.then(|| {
+                                            |pack_id, base_offset| {
+                                                let (cached_pack_id, cache) =
+                                                    pack_offsets_to_id.get_or_insert_with(|| {
+                                                        db.pack_offsets_and_oid(pack_id)
+                                                            .map(|mut v| {
+                                                                v.sort_by_key(|e| e.0);
+                                                                (pack_id, v)
+                                                            })
+                                                            .expect("pack used for counts is still available")
+                                                    });
+                                                debug_assert_eq!(*cached_pack_id, pack_id);
+                                                stats.ref_delta_objects += 1;
+                                                cache
+                                                    .binary_search_by_key(&base_offset, |e| e.0)
+                                                    .ok()
+                                                    .map(|idx| cache[idx].1)
+                                            }
+                                        }),
+                                        version,
+                                    )
+                                })
+                                .flatten();
+                            match copied {
+                                Some(entry) => (entry?, Source::CopiedFromPack),
+                                None => from_odb(&db, count, buf, compression_level)?,
+                            }
+                        }
+                        None => from_odb(&db, count, buf, compression_level)?,
+                    };
+
+                    if let DeltaMode::Compute {
+                        window: window_size,
+                        max_depth,
+                    } = delta_mode
+                    {
+                        if window_size > 0 && matches!(entry.kind, output::entry::Kind::Base(_)) && !entry.is_invalid()
+                        {
+                            if let Some((obj, _location)) = db.try_find(count.id, buf).map_err(Error::FindExisting)? {
+                                let (kind, data) = (obj.kind, obj.data.to_vec());
+                                let mut depth = 0;
+                                for candidate in window
+                                    .iter()
+                                    .filter(|c: &&Candidate| c.kind == kind && c.depth < max_depth)
+                                {
+                                    let delta = match crate::data::delta::encode(&candidate.data, &data, data.len() / 2)
+                                    {
+                                        Some(delta) => delta,
+                                        None => continue,
+                                    };
+                                    let delta_entry = output::Entry::from_delta(
+                                        count,
+                                        chunk_start + candidate.index_in_chunk,
+                                        &delta,
+                                        compression_level,
+                                    )?;
+                                    if delta_entry.compressed_data.len() < entry.compressed_data.len() {
+                                        entry = delta_entry;
+                                        source = Source::Computed;
+                                        depth = candidate.depth + 1;
                                     }
-                                }),
-                                version,
-                            ) {
-                                Some(entry) => {
-                                    stats.objects_copied_from_pack += 1;
-                                    entry
                                 }
-                                None => match db.try_find(count.id, buf).map_err(Error::FindExisting)? {
-                                    Some((obj, _location)) => {
-                                        stats.decoded_and_recompressed_objects += 1;
-                                        output::Entry::from_data(count, &obj)
-                                    }
-                                    None => {
-                                        stats.missing_objects += 1;
-                                        Ok(output::Entry::invalid())
-                                    }
-                                },
+                                if window.len() == window_size {
+                                    window.pop_front();
+                                }
+                                window.push_back(Candidate {
+                                    index_in_chunk,
+                                    kind,
+                                    data,
+                                    depth,
+                                });
                             }
                         }
-                        None => match db.try_find(count.id, buf).map_err(Error::FindExisting)? {
-                            Some((obj, _location)) => {
-                                stats.decoded_and_recompressed_objects += 1;
-                                output::Entry::from_data(count, &obj)
-                            }
-                            None => {
-                                stats.missing_objects += 1;
-                                Ok(output::Entry::invalid())
+                    }
+
+                    match source {
+                        Source::CopiedFromPack => {
+                            stats.objects_copied_from_pack += 1;
+                            if !matches!(entry.kind, output::entry::Kind::Base(_)) {
+                                stats.reused_deltas += 1;
                             }
-                        },
-                    }?);
+                        }
+                        Source::Recompressed => stats.decoded_and_recompressed_objects += 1,
+                        Source::Computed => stats.computed_deltas += 1,
+                        Source::Missing => stats.missing_objects += 1,
+                    }
+                    out.push(entry);
                     progress.inc();
                 }
                 Ok((chunk_id, out, stats))
@@ -234,6 +285,47 @@ where
     )
 }
 
+/// Where an entry came from, to keep statistics.
+enum Source {
+    CopiedFromPack,
+    Recompressed,
+    Computed,
+    Missing,
+}
+
+/// An object which can serve as base for the deltas of the objects following it in the same chunk.
+struct Candidate {
+    index_in_chunk: usize,
+    kind: git_object::Kind,
+    data: Vec<u8>,
+    /// The length of the chain of computed deltas this object is at the end of, or 0 if it is a base object.
+    depth: usize,
+}
+
+fn is_delta(pack_entry: &crate::find::Entry, count: &output::Count) -> bool {
+    crate::data::Entry::from_bytes(&pack_entry.data, 0, count.id.as_slice().len())
+        .header
+        .is_delta()
+}
+
+fn from_odb<Find>(
+    db: &Find,
+    count: &output::Count,
+    buf: &mut Vec<u8>,
+    compression_level: u32,
+) -> Result<(output::Entry, Source), Error<Find::Error>>
+where
+    Find: crate::Find,
+{
+    Ok(match db.try_find(count.id, buf).map_err(Error::FindExisting)? {
+        Some((obj, _location)) => (
+            output::Entry::from_data_with_level(count, &obj, compression_level)?,
+            Source::Recompressed,
+        ),
+        None => (output::Entry::invalid(), Source::Missing),
+    })
+}
+
 mod util {
     #[derive(Clone)]
     pub struct ChunkRanges {
@@ -310,7 +402,7 @@ mod reduce {
 }
 
 mod types {
-    use crate::data::output::entry;
+    use crate::data::{output, output::entry};
 
     /// Information gathered during the run of [`iter_from_counts()`][super::iter_from_counts()].
     #[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -325,6 +417,11 @@ mod types {
         pub objects_copied_from_pack: usize,
         /// The amount of objects that ref to their base as ref-delta, an indication for a thin back being created.
         pub ref_delta_objects: usize,
+        /// The amount of deltas copied from packs, which are also counted in `objects_copied_from_pack`.
+        /// It's always 0 with [`DeltaMode::None`].
+        pub reused_deltas: usize,
+        /// The amount of deltas computed against preceding objects with [`DeltaMode::Compute`].
+        pub computed_deltas: usize,
     }
 
     impl Outcome {
@@ -335,12 +432,16 @@ mod types {
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                reused_deltas,
+                computed_deltas,
             }: Self,
         ) {
             self.decoded_and_recompressed_objects += decoded_objects;
             self.missing_objects += missing_objects;
             self.objects_copied_from_pack += objects_copied_from_pack;
             self.ref_delta_objects += ref_delta_objects;
+            self.reused_deltas += reused_deltas;
+            self.computed_deltas += computed_deltas;
         }
     }
 
@@ -355,6 +456,30 @@ mod types {
         PackCopyAndBaseObjects,
     }
 
+    /// How to produce deltas, trading time spent for the size of the pack.
+    #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+    pub enum DeltaMode {
+        /// Write all objects as base objects, decompressing and recompressing deltas found in packs.
+        /// This is fast, but produces the largest packs.
+        None,
+        /// Copy deltas from packs verbatim if their base is part of the output as well, and write all other objects
+        /// as base objects.
+        ReuseExisting,
+        /// Like [`ReuseExisting`][DeltaMode::ReuseExisting], but try to turn all objects which would be written as base
+        /// objects into deltas against up to `window` preceding objects of the same kind, building chains of up to `max_depth`
+        /// computed deltas. This is slowest, but produces the smallest packs.
+        ///
+        /// Note that bases are only searched for within the chunk an object is processed in,
+        /// see [`Options::chunk_size`].
+        Compute {
+            /// The amount of preceding objects to try as base for each object.
+            window: usize,
+            /// The longest chain of computed deltas to build.
+            max_depth: usize,
+        },
+    }
+
     /// Configuration options for the pack generation functions provided in [this module][crate::data::output].
     #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
     #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -375,6 +500,10 @@ mod types {
         pub chunk_size: usize,
         /// The pack data version to produce for each entry
         pub version: crate::data::Version,
+        /// How to produce deltas.
+        pub delta_mode: DeltaMode,
+        /// The zlib compression level from 0 (none) to 9 (best) for all objects that are compressed, i.e. not copied from packs.
+        pub compression_level: u32,
     }
 
     impl Default for Options {
@@ -385,6 +514,8 @@ mod types {
                 allow_thin_pack: false,
                 chunk_size: 10,
                 version: Default::default(),
+                delta_mode: DeltaMode::ReuseExisting,
+                compression_level: output::entry::DEFAULT_COMPRESSION_LEVEL,
             }
         }
     }
@@ -402,4 +533,4 @@ mod types {
         NewEntry(#[from] entry::Error),
     }
 }
-pub use types::{Error, Mode, Options, Outcome};
+pub use types::{DeltaMode, Error, Mode, Options, Outcome};
@@ -31,6 +31,9 @@ pub enum Kind {
     },
 }
 
+/// The zlib compression level used by [`output::Entry::from_data()`], which favors speed.
+pub const DEFAULT_COMPRESSION_LEVEL: u32 = 1;
+
 /// The error returned by [`output::Entry::from_data()`].
 #[allow(missing_docs)]
 #[derive(Debug, thiserror::Error)]
@@ -128,21 +131,35 @@ impl output::Entry {
 
     /// Create a new instance from the given `oid` and its corresponding git `obj`ect data.
     pub fn from_data(count: &output::Count, obj: &git_object::Data<'_>) -> Result<Self, Error> {
+        Self::from_data_with_level(count, obj, DEFAULT_COMPRESSION_LEVEL)
+    }
+
+    /// Like [`from_data()`][Self::from_data()], but compress `obj` with the given zlib `compression_level` from 0 to 9.
+    pub fn from_data_with_level(
+        count: &output::Count,
+        obj: &git_object::Data<'_>,
+        compression_level: u32,
+    ) -> Result<Self, Error> {
         Ok(output::Entry {
             id: count.id.to_owned(),
             kind: Kind::Base(obj.kind),
             decompressed_size: obj.data.len(),
-            compressed_data: {
-                let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
-                if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
-                    match err.kind() {
-                        std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
-                        err => unreachable!("Should never see other errors than zlib, but got {:?}", err,),
-                    }
-                };
-                out.flush()?;
-                out.into_inner()
-            },
+            compressed_data: deflate(obj.data, compression_level)?,
+        })
+    }
+
+    /// Create a new instance from a `delta` computed against the object at `object_index`, compressed with `compression_level`.
+    pub(crate) fn from_delta(
+        count: &output::Count,
+        object_index: usize,
+        delta: &[u8],
+        compression_level: u32,
+    ) -> Result<Self, Error> {
+        Ok(output::Entry {
+            id: count.id.to_owned(),
+            kind: Kind::DeltaRef { object_index },
+            decompressed_size: delta.len(),
+            compressed_data: deflate(delta, compression_level)?,
         })
     }
 
@@ -179,3 +196,15 @@ impl output::Entry {
         }
     }
 }
+
+fn deflate(data: &[u8], compression_level: u32) -> Result<Vec<u8>, Error> {
+    let mut out = git_features::zlib::stream::deflate::Write::with_level(Vec::new(), compression_level);
+    if let Err(err) = std::io::copy(&mut &*data, &mut out) {
+        match err.kind() {
+            std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
+            err => unreachable!("Should never see other errors than zlib, but got {:?}", err,),
+        }
+    };
+    out.flush()?;
+    Ok(out.into_inner())
+}
@@ -1,3 +1,32 @@
+/// A coarse classification of errors occurring when finding objects, to allow telling a missing object apart from
+/// a damaged or inaccessible object database without matching on nested error types.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The requested object doesn't exist.
+    NotFound,
+    /// The requested object doesn't exist locally, but is expected to be provided by the promisor remote of a partial clone.
+    Promised,
+    /// The object exists, but its data or the data it depends on is damaged or missing.
+    Corrupt,
+    /// The object is larger than the configured limit, or its delta chain is deeper than allowed.
+    LimitExceeded,
+    /// Object data could not be accessed on disk.
+    Io,
+    /// Any other error, like an object of an unexpected kind.
+    Other,
+}
+
+/// Implemented by errors of object lookups to [classify][ErrorKind] them.
+pub trait Classify {
+    /// Return the kind of this error.
+    fn kind(&self) -> ErrorKind;
+
+    /// Return `true` if the requested object doesn't exist, as opposed to failing to obtain it.
+    fn is_not_found(&self) -> bool {
+        self.kind() == ErrorKind::NotFound
+    }
+}
+
 ///
 pub mod existing {
     use git_hash::ObjectId;
@@ -11,6 +40,34 @@ pub mod existing {
         #[error("An object with id {} could not be found", .oid)]
         NotFound { oid: ObjectId },
     }
+
+    impl<T: std::error::Error + 'static> Error<T> {
+        /// Return `true` if the object doesn't exist.
+        pub fn is_not_found(&self) -> bool {
+            matches!(self, Error::NotFound { .. })
+        }
+
+        /// Return the kind of this error, which for lookup errors is determined by the underlying error.
+        pub fn kind(&self) -> crate::find::ErrorKind
+        where
+            T: crate::find::Classify,
+        {
+            use crate::find::ErrorKind;
+            match self {
+                Error::Find(err) => err.kind(),
+                Error::NotFound { .. } => ErrorKind::NotFound,
+            }
+        }
+    }
+
+    impl<T> crate::find::Classify for Error<T>
+    where
+        T: crate::find::Classify + std::error::Error + 'static,
+    {
+        fn kind(&self) -> crate::find::ErrorKind {
+            Error::kind(self)
+        }
+    }
 }
 
 ///
@@ -30,6 +87,36 @@ pub mod existing_object {
         #[error("Expected object of kind {} something else", .expected)]
         ObjectKind { expected: git_object::Kind },
     }
+
+    impl<T: std::error::Error + 'static> Error<T> {
+        /// Return `true` if the object doesn't exist.
+        pub fn is_not_found(&self) -> bool {
+            matches!(self, Error::NotFound { .. })
+        }
+
+        /// Return the kind of this error, which for lookup errors is determined by the underlying error.
+        pub fn kind(&self) -> crate::find::ErrorKind
+        where
+            T: crate::find::Classify,
+        {
+            use crate::find::ErrorKind;
+            match self {
+                Error::Find(err) => err.kind(),
+                Error::NotFound { .. } => ErrorKind::NotFound,
+                Error::Decode(_) => ErrorKind::Corrupt,
+                Error::ObjectKind { .. } => ErrorKind::Other,
+            }
+        }
+    }
+
+    impl<T> crate::find::Classify for Error<T>
+    where
+        T: crate::find::Classify + std::error::Error + 'static,
+    {
+        fn kind(&self) -> crate::find::ErrorKind {
+            Error::kind(self)
+        }
+    }
 }
 
 ///
@@ -47,6 +134,35 @@ pub mod existing_iter {
         #[error("Expected object of kind {} something else", .expected)]
         ObjectKind { expected: git_object::Kind },
     }
+
+    impl<T: std::error::Error + 'static> Error<T> {
+        /// Return `true` if the object doesn't exist.
+        pub fn is_not_found(&self) -> bool {
+            matches!(self, Error::NotFound { .. })
+        }
+
+        /// Return the kind of this error, which for lookup errors is determined by the underlying error.
+        pub fn kind(&self) -> crate::find::ErrorKind
+        where
+            T: crate::find::Classify,
+        {
+            use crate::find::ErrorKind;
+            match self {
+                Error::Find(err) => err.kind(),
+                Error::NotFound { .. } => ErrorKind::NotFound,
+                Error::ObjectKind { .. } => ErrorKind::Other,
+            }
+        }
+    }
+
+    impl<T> crate::find::Classify for Error<T>
+    where
+        T: crate::find::Classify + std::error::Error + 'static,
+    {
+        fn kind(&self) -> crate::find::ErrorKind {
+            Error::kind(self)
+        }
+    }
 }
 
 /// An Entry in a pack providing access to its data.
@@ -45,8 +45,13 @@ pub trait Find {
     /// Find the packs location where an object with `id` can be found in the database, or `None` if there is no pack
     /// holding the object.
     ///
-    /// _Note_ that this is always None if the object isn't packed.
-    fn location_by_oid(&self, id: impl AsRef<git_hash::oid>, buf: &mut Vec<u8>) -> Option<data::entry::Location>;
+    /// _Note_ that this is always None if the object isn't packed. Errors occur if the object can't be decompressed,
+    /// for instance as it exceeds the configured size limit.
+    fn location_by_oid(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<data::entry::Location>, Self::Error>;
 
     /// Obtain a vector of all offsets, in index order, along with their object id.
     fn pack_offsets_and_oid(&self, pack_id: u32) -> Option<Vec<(data::Offset, git_hash::ObjectId)>>;
@@ -181,7 +186,11 @@ mod find_impls {
             (*self).try_find_cached(id, buffer, pack_cache)
         }
 
-        fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<data::entry::Location> {
+        fn location_by_oid(
+            &self,
+            id: impl AsRef<oid>,
+            buf: &mut Vec<u8>,
+        ) -> Result<Option<data::entry::Location>, Self::Error> {
             (*self).location_by_oid(id, buf)
         }
 
@@ -213,7 +222,11 @@ mod find_impls {
             self.deref().try_find_cached(id, buffer, pack_cache)
         }
 
-        fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<data::entry::Location> {
+        fn location_by_oid(
+            &self,
+            id: impl AsRef<oid>,
+            buf: &mut Vec<u8>,
+        ) -> Result<Option<data::entry::Location>, Self::Error> {
             self.deref().location_by_oid(id, buf)
         }
 
@@ -245,7 +258,11 @@ mod find_impls {
             self.deref().try_find_cached(id, buffer, pack_cache)
         }
 
-        fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<data::entry::Location> {
+        fn location_by_oid(
+            &self,
+            id: impl AsRef<oid>,
+            buf: &mut Vec<u8>,
+        ) -> Result<Option<data::entry::Location>, Self::Error> {
             self.deref().location_by_oid(id, buf)
         }
 
@@ -277,7 +294,11 @@ mod find_impls {
             self.deref().try_find_cached(id, buffer, pack_cache)
         }
 
-        fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<data::entry::Location> {
+        fn location_by_oid(
+            &self,
+            id: impl AsRef<oid>,
+            buf: &mut Vec<u8>,
+        ) -> Result<Option<data::entry::Location>, Self::Error> {
             self.deref().location_by_oid(id, buf)
         }
 
@@ -26,6 +26,22 @@ pub struct Entry {
     pub crc32: Option<u32>,
 }
 
+///
+pub mod entry_ranges {
+    /// Returned by [`index::File::entry_ranges()`][crate::index::File::entry_ranges()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The entry at pack offset {offset} would end at {end} and thus be empty, which happens if its offset is duplicated in the index")]
+        EmptyEntry {
+            offset: crate::data::Offset,
+            end: crate::data::Offset,
+        },
+        #[error(transparent)]
+        Io(#[from] crate::data::decode_entry::Error),
+    }
+}
+
 /// Iteration and access
 impl index::File {
     fn iter_v1(&self) -> impl Iterator<Item = Entry> + '_ {
@@ -122,6 +138,22 @@ impl index::File {
         lookup(id, &self.fan, |idx| self.oid_at_index(idx))
     }
 
+    /// Return the entry in `pack` of the object with `id` for use as delta base, or `None` if it isn't contained in this index.
+    ///
+    /// If the entry can't be read, `None` is returned as well while the error is stored in `failed`, as otherwise
+    /// it would seem as if the base couldn't be found.
+    pub(crate) fn base_entry(
+        &self,
+        pack: &data::File,
+        id: &git_hash::oid,
+        failed: &std::cell::RefCell<Option<data::decode_entry::Error>>,
+    ) -> Option<data::Entry> {
+        let index = self.lookup(id)?;
+        pack.try_entry(self.pack_offset_at_index(index))
+            .map_err(|err| *failed.borrow_mut() = Some(err))
+            .ok()
+    }
+
     /// Given a `prefix`, find an object that matches it uniquely within this index and return `Some(Ok(entry_index))`.
     /// If there is more than one object matching the object `Some(Err(())` is returned.
     ///
@@ -154,6 +186,73 @@ impl index::File {
         }
     }
 
+    /// Return the ranges of bytes in `pack` which are needed to decode the object identified by `id`, which are the pack entry
+    /// of the object itself along with the entries of all of its delta bases, or `None` if the object isn't in this index.
+    ///
+    /// The ranges are sorted by offset and adjacent ranges are merged, so that serving only these bytes is enough to
+    /// [decode][data::File::decode_entry()] the object.
+    /// Ref-delta bases that aren't in this index, as in thin packs, can't be followed and have to be obtained by other means.
+    /// Note that all offsets of this index are sorted to learn where entries end, which is best amortized over multiple calls
+    /// by callers who care.
+    ///
+    /// `None` is also returned if an entry of `pack` refers to a base at an offset that isn't in this index, which means
+    /// `pack` doesn't belong to it or is corrupt. If an entry would be empty as the next entry starts at the same offset,
+    /// or if it starts past the end of the pack, [`EmptyEntry`][entry_ranges::Error::EmptyEntry] is returned.
+    pub fn entry_ranges(
+        &self,
+        pack: &data::File,
+        id: impl AsRef<git_hash::oid>,
+    ) -> Result<Option<Vec<data::EntryRange>>, entry_ranges::Error> {
+        let sorted_offsets = self.sorted_offsets();
+        let mut ranges: Vec<data::EntryRange> = Vec::new();
+        let mut offset = match self.lookup(id) {
+            Some(index) => self.pack_offset_at_index(index),
+            None => return Ok(None),
+        };
+        loop {
+            // Use the first of duplicate offsets so that the entry is consistently seen as empty.
+            let pos = sorted_offsets.partition_point(|other| *other < offset);
+            if sorted_offsets.get(pos) != Some(&offset) {
+                return Ok(None);
+            }
+            let end = sorted_offsets
+                .get(pos + 1)
+                .copied()
+                .unwrap_or(pack.pack_end() as data::Offset);
+            if end <= offset {
+                return Err(entry_ranges::Error::EmptyEntry { offset, end });
+            }
+            ranges.push(offset..end);
+            let entry = pack.try_entry(offset)?;
+            offset = match entry.header {
+                data::entry::Header::OfsDelta { base_distance } => {
+                    match data::entry::Header::verified_base_pack_offset(offset, base_distance) {
+                        Some(base_offset) => base_offset,
+                        None => return Ok(None),
+                    }
+                }
+                data::entry::Header::RefDelta { base_id } => match self.lookup(base_id) {
+                    Some(base_index) => self.pack_offset_at_index(base_index),
+                    None => break,
+                },
+                _ => break,
+            };
+            if ranges.iter().any(|range| range.start == offset) {
+                // Only crafted packs can have cycles, which can't be decoded anyway.
+                break;
+            }
+        }
+        ranges.sort_by_key(|range| range.start);
+        let mut merged: Vec<data::EntryRange> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => merged.push(range),
+            }
+        }
+        Ok(Some(merged))
+    }
+
     /// Return a vector of ascending offsets into our respective pack data file.
     ///
     /// Useful to control an iteration over all pack entries in a cache-friendly way.
@@ -2,7 +2,9 @@ use std::{mem::size_of, path::Path};
 
 use crate::index::{self, Version, FAN_LEN, V2_SIGNATURE};
 
-/// Returned by [`index::File::at()`].
+/// Returned by [`index::File::at()`] and [`index::File::from_bytes()`].
+///
+/// Each variant carries the path of the index file, which is synthetic for files [held in memory][index::File::from_bytes()].
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -11,12 +13,38 @@ pub enum Error {
         source: std::io::Error,
         path: std::path::PathBuf,
     },
-    #[error("{message}")]
-    Corrupt { message: String },
-    #[error("Unsupported index version: {version})")]
-    UnsupportedVersion { version: u32 },
+    #[error("Pack index file at '{path}' with {size} bytes is too small for even an empty index")]
+    TooSmall { size: usize, path: std::path::PathBuf },
+    #[error("Pack index file at '{path}' has unsupported version {version}")]
+    UnsupportedVersion { version: u32, path: std::path::PathBuf },
+    #[error("Pack index file at '{path}' with {size} bytes is too small for {num_objects} objects, which need at least {expected_size} bytes")]
+    TooSmallForObjects {
+        size: usize,
+        num_objects: u32,
+        expected_size: usize,
+        path: std::path::PathBuf,
+    },
+    #[error("Pack index file at '{path}' with {size} bytes is too large for {num_objects} objects, which need at most {max_size} bytes")]
+    TooLargeForObjects {
+        size: usize,
+        num_objects: u32,
+        max_size: usize,
+        path: std::path::PathBuf,
+    },
+    #[error("Pack index file at '{path}' with {size} bytes was opened for {object_hash} hashes of {} bytes, but its size implies hashes of {implied_hash_len} bytes", .object_hash.len_in_bytes())]
+    ObjectHashMismatch {
+        object_hash: git_hash::Kind,
+        implied_hash_len: usize,
+        size: usize,
+        path: std::path::PathBuf,
+    },
 }
 
+/// The lengths of hashes in bytes that git uses for objects, SHA-1 and SHA-256, even if not all of them are supported.
+///
+/// They are used to tell if an index was written for a different kind of hash than it was opened with.
+const KNOWN_HASH_LENGTHS: [usize; 2] = [20, 32];
+
 const N32_SIZE: usize = size_of::<u32>();
 
 /// Instantiation
@@ -29,19 +57,41 @@ impl index::File {
         Self::at_inner(path.as_ref(), object_hash)
     }
 
+    /// Instantiate an index file from `data` held in memory, for example after receiving it over the network.
+    ///
+    /// As there is no path on disk, [`path()`][index::File::path()] returns a synthetic path based on the pack checksum
+    /// stored in the index, so that it matches the path of the in-memory pack it belongs to apart from the extension.
+    /// See [`at()`][index::File::at()] for more information on `object_hash`.
+    pub fn from_bytes(data: Vec<u8>, object_hash: git_hash::Kind) -> Result<index::File, Error> {
+        Self::from_data(crate::mmap::Data::InMemory(data.into()), None, object_hash)
+    }
+
     fn at_inner(path: &Path, object_hash: git_hash::Kind) -> Result<index::File, Error> {
         let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
             source,
             path: path.to_owned(),
         })?;
+        Self::from_data(crate::mmap::Data::Mapped(data.into()), Some(path), object_hash)
+    }
+
+    fn from_data(
+        data: crate::mmap::Data,
+        path: Option<&Path>,
+        object_hash: git_hash::Kind,
+    ) -> Result<index::File, Error> {
         let idx_len = data.len();
         let hash_len = object_hash.len_in_bytes();
+        let path = match path {
+            Some(path) => path.to_owned(),
+            None => crate::mmap::in_memory_path(
+                &data[idx_len.saturating_sub(hash_len * 2)..idx_len.saturating_sub(hash_len)],
+                "idx",
+            ),
+        };
 
         let footer_size = hash_len * 2;
         if idx_len < FAN_LEN * N32_SIZE + footer_size {
-            return Err(Error::Corrupt {
-                message: format!("Pack index of size {} is too small for even an empty index", idx_len),
-            });
+            return Err(Error::TooSmall { size: idx_len, path });
         }
         let (kind, fan, num_objects) = {
             let (kind, d) = {
@@ -57,7 +107,7 @@ impl index::File {
                     let (vd, dr) = d.split_at(N32_SIZE);
                     let version = crate::read_u32(vd);
                     if version != Version::V2 as u32 {
-                        return Err(Error::UnsupportedVersion { version });
+                        return Err(Error::UnsupportedVersion { version, path });
                     }
                     dr
                 } else {
@@ -70,9 +120,45 @@ impl index::File {
 
             (kind, fan, num_objects)
         };
+        let size_range = |hash_len: usize| size_range(kind, num_objects, hash_len);
+        let (expected_size, max_size) = size_range(hash_len);
+        if !(expected_size..=max_size).contains(&idx_len) {
+            if let Some(implied_hash_len) =
+                KNOWN_HASH_LENGTHS
+                    .iter()
+                    .copied()
+                    .filter(|len| *len != hash_len)
+                    .find(|len| {
+                        let (min, max) = size_range(*len);
+                        (min..=max).contains(&idx_len)
+                    })
+            {
+                return Err(Error::ObjectHashMismatch {
+                    object_hash,
+                    implied_hash_len,
+                    size: idx_len,
+                    path,
+                });
+            }
+            return Err(if idx_len < expected_size {
+                Error::TooSmallForObjects {
+                    size: idx_len,
+                    num_objects,
+                    expected_size,
+                    path,
+                }
+            } else {
+                Error::TooLargeForObjects {
+                    size: idx_len,
+                    num_objects,
+                    max_size,
+                    path,
+                }
+            });
+        }
         Ok(index::File {
             data,
-            path: path.to_owned(),
+            path,
             version: kind,
             num_objects,
             fan,
@@ -82,6 +168,27 @@ impl index::File {
     }
 }
 
+/// Return the smallest and largest size in bytes of an index of `version` with `num_objects` and hashes of `hash_len` bytes,
+/// which differ only by the table of large offsets in V2 that has one entry per object except for the first, at most.
+fn size_range(version: Version, num_objects: u32, hash_len: usize) -> (usize, usize) {
+    let num_objects = num_objects as usize;
+    let footer_size = hash_len * 2;
+    match version {
+        Version::V1 => {
+            let size = FAN_LEN * N32_SIZE + num_objects * (N32_SIZE + hash_len) + footer_size;
+            (size, size)
+        }
+        Version::V2 => {
+            let min_size = V2_SIGNATURE.len()
+                + N32_SIZE
+                + FAN_LEN * N32_SIZE
+                + num_objects * (hash_len + N32_SIZE * 2)
+                + footer_size;
+            (min_size, min_size + num_objects.saturating_sub(1) * 8)
+        }
+    }
+}
+
 fn read_fan(d: &[u8]) -> ([u32; FAN_LEN], usize) {
     let mut fan = [0; FAN_LEN];
     for (c, f) in d.chunks(N32_SIZE).zip(fan.iter_mut()) {
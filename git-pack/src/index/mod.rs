@@ -73,8 +73,6 @@ macro_rules! izip {
     };
 }
 
-use memmap2::Mmap;
-
 /// The version of an index file
 #[derive(PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -109,8 +107,11 @@ pub type EntryIndex = u32;
 const FAN_LEN: usize = 256;
 
 /// A representation of a pack index file
+///
+/// Clones are cheap as they share the underlying data.
+#[derive(Clone)]
 pub struct File {
-    data: Mmap,
+    data: crate::mmap::Data,
     path: std::path::PathBuf,
     version: Version,
     num_objects: u32,
@@ -125,10 +126,21 @@ impl File {
     pub fn version(&self) -> Version {
         self.version
     }
-    /// The path of the opened index file
+    /// The path of the opened index file.
+    ///
+    /// If this file was [created from bytes][File::from_bytes()], it's a synthetic path based on its checksum
+    /// which doesn't exist on disk.
     pub fn path(&self) -> &std::path::Path {
         &self.path
     }
+    /// Return true if this file was [created from bytes][File::from_bytes()] instead of being read from disk.
+    pub fn is_in_memory(&self) -> bool {
+        self.data.is_in_memory()
+    }
+    /// Return information about the file we were read from, or `None` if we were [created from bytes][File::from_bytes()].
+    pub fn file_info(&self) -> Option<crate::verify::FileInfo> {
+        (!self.is_in_memory()).then(|| crate::verify::FileInfo::from_path(&self.path, self.data.len()))
+    }
     /// The amount of objects stored in the pack and index, as one past the highest entry index.
     pub fn num_objects(&self) -> EntryIndex {
         self.num_objects
@@ -144,12 +156,14 @@ const V2_SIGNATURE: &[u8] = b"\xfftOc";
 pub mod init;
 
 pub(crate) mod access;
-pub use access::Entry;
+pub use access::{entry_ranges, Entry};
 
 ///
 pub mod traverse;
 mod util;
 ///
+pub mod verbatim_reusable;
+///
 pub mod verify;
 ///
 pub mod write;
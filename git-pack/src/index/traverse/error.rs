@@ -18,17 +18,17 @@ pub enum Error<E: std::error::Error + Send + Sync + 'static> {
         offset: u64,
         source: crate::data::decode_entry::Error,
     },
+    #[error("The delta chain of object {id} at offset {offset} is longer than the amount of objects in the pack, which means it has a cycle")]
+    DeltaChainCycle { id: git_hash::ObjectId, offset: u64 },
     #[error("The packfiles checksum didn't match the index file checksum: expected {expected}, got {actual}")]
     PackMismatch {
         expected: git_hash::ObjectId,
         actual: git_hash::ObjectId,
     },
-    #[error("The hash of {kind} object at offset {offset} didn't match the checksum in the index file: expected {expected}, got {actual}")]
-    PackObjectMismatch {
-        expected: git_hash::ObjectId,
-        actual: git_hash::ObjectId,
-        offset: u64,
-        kind: git_object::Kind,
+    #[error("The hash of {count} object(s) in the pack didn't match their object id in the index file")]
+    IndexOidMismatch {
+        count: u32,
+        first: Vec<index::traverse::IndexOidMismatch>,
     },
     #[error(
         "The CRC32 of {kind} object at offset {offset} didn't match the checksum in the index file: expected {expected}, got {actual}"
@@ -39,6 +39,16 @@ pub enum Error<E: std::error::Error + Send + Sync + 'static> {
         offset: u64,
         kind: git_object::Kind,
     },
+    #[error("Partition {index} is out of bounds as there are only {count} partitions")]
+    InvalidPartition { index: u32, count: u32 },
+    #[error("The pack at '{}' was changed on disk while it was traversed", .path.display())]
+    PackMutatedDuringVerification { path: std::path::PathBuf },
+    #[error("The entry at offset {offset} occupies {entry_size} bytes, but only {available_size} bytes are available until the next entry")]
+    EntryOverlap {
+        offset: u64,
+        entry_size: u64,
+        available_size: u64,
+    },
     #[error("Interrupted")]
     Interrupted,
 }
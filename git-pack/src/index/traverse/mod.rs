@@ -11,11 +11,20 @@ pub mod with_index;
 pub mod with_lookup;
 use reduce::Reducer;
 
+mod streaming;
+
+///
+pub mod plan;
+pub use plan::Plan;
+
 mod error;
 pub use error::Error;
 
 mod types;
-pub use types::{Algorithm, SafetyCheck, Statistics};
+pub use types::{
+    Algorithm, EntryBoundaryMismatch, EntryInfo, IndexOidMismatch, Partition, SafetyCheck, Statistics, Storage, Tally,
+    MAX_REPORTED_ENTRY_BOUNDARY_MISMATCHES, MAX_REPORTED_INDEX_OID_MISMATCHES,
+};
 
 /// Traversal options for [`index::File::traverse()`].
 #[derive(Debug, Clone)]
@@ -29,6 +38,30 @@ pub struct Options<F> {
     pub check: SafetyCheck,
     /// A function to create a pack cache
     pub make_pack_lookup_cache: F,
+    /// If `Some`, fail if any object or delta would decompress to more than the given amount of bytes.
+    ///
+    /// Defaults to [`DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE`][crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE].
+    pub max_decompressed_object_size: Option<u64>,
+    /// If `Some`, only process the entries within the given partition of the pack, while still resolving delta bases
+    /// outside of it.
+    ///
+    /// This always uses the [`Lookup`][Algorithm::Lookup] algorithm, ignoring [`traversal`][Options::traversal].
+    /// Note that the checksums of the entire index and pack files are still verified as per [`check`][Options::check],
+    /// which can be skipped with [`SafetyCheck::SkipFileChecksumVerification`] to avoid reading the whole pack on each shard.
+    pub partition: Option<Partition>,
+    /// If `true`, fail with [`Error::PackMutatedDuringVerification`] if the pack data file on disk was changed while it was
+    /// traversed, as detected by comparing its size, modification time and, where available, inode and change time before and
+    /// after the traversal.
+    ///
+    /// This protects long-running traversals from reporting a mix of old and new data as success if the pack is rewritten
+    /// in place concurrently. Packs that are held in memory aren't checked. Defaults to `false`.
+    pub paranoid: bool,
+    /// If `true`, fail with [`Error::EntryOverlap`] if the compressed data of an entry extends past the offset of the entry
+    /// following it, instead of merely counting it in [`Statistics::entry_overlaps`].
+    ///
+    /// Entry boundaries are only checked if entries are traversed by offset, i.e. with [`Algorithm::Lookup`] or within a
+    /// [`partition`][Options::partition]. Defaults to `false`.
+    pub strict_entry_boundaries: bool,
 }
 
 impl Default for Options<fn() -> crate::cache::Never> {
@@ -38,30 +71,118 @@ impl Default for Options<fn() -> crate::cache::Never> {
             traversal: Default::default(),
             thread_limit: None,
             make_pack_lookup_cache: || crate::cache::Never,
+            max_decompressed_object_size: Some(crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+            partition: None,
+            paranoid: false,
+            strict_entry_boundaries: false,
         }
     }
 }
 
 /// The outcome of the [`traverse()`][index::File::traverse()] method.
-pub struct Outcome<P> {
+pub struct Outcome {
     /// The checksum obtained when hashing the file, which matched the checksum contained within the file.
+    ///
+    /// If file checksums aren't verified as per [`SafetyCheck`], it's the checksum stored in the index file.
     pub actual_index_checksum: git_hash::ObjectId,
     /// The statistics obtaine during traversal.
     pub statistics: Statistics,
-    /// The input progress to allow reuse.
-    pub progress: P,
+    /// The partition of the pack that was traversed, or `None` if it was traversed entirely.
+    pub partition: Option<Partition>,
+    /// The amount of times a delta base had to be decoded again as it wasn't cached anymore, or `None` if the algorithm
+    /// doesn't track it.
+    ///
+    /// Only [`Algorithm::Streaming`] tracks it.
+    pub num_redecoded_bases: Option<u64>,
+    /// The statistics gathered by the processors of all threads, which is always empty if the processor doesn't receive a
+    /// [`Tally`] like the one of [`traverse_with_info()`][index::File::traverse_with_info()].
+    pub tally: Tally,
+}
+
+///
+pub mod merge {
+    /// Returned by [`Outcome::merge()`][super::Outcome::merge()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("There were no outcomes to merge")]
+        Empty,
+        #[error("Outcomes of different packs can't be merged: expected index checksum {expected}, got {actual}")]
+        ChecksumMismatch {
+            expected: git_hash::ObjectId,
+            actual: git_hash::ObjectId,
+        },
+        #[error("The outcomes to merge don't cover each of {count} partition(s) exactly once")]
+        IncompletePartitions { count: u32 },
+    }
+}
+
+impl Outcome {
+    /// Combine the `outcomes` of traversing each [partition][Options::partition] of the same pack into one,
+    /// as if the pack was traversed entirely.
+    ///
+    /// All outcomes must have the same index checksum, and each partition must be present exactly once.
+    pub fn merge(outcomes: impl IntoIterator<Item = Outcome>) -> Result<Outcome, merge::Error> {
+        let outcomes: Vec<_> = outcomes.into_iter().collect();
+        let count = outcomes
+            .first()
+            .ok_or(merge::Error::Empty)?
+            .partition
+            .map_or(1, |p| p.count);
+        if outcomes.len() != count as usize {
+            return Err(merge::Error::IncompletePartitions { count });
+        }
+        let mut seen = vec![false; outcomes.len()];
+        let mut outcomes = outcomes.into_iter();
+        let mut merged = outcomes.next().expect("checked non-empty");
+        let mut mark_seen = |partition: Option<Partition>| {
+            let index = match partition {
+                Some(p) if p.count == count && p.is_valid() => p.index as usize,
+                None if count == 1 => 0,
+                _ => return Err(merge::Error::IncompletePartitions { count }),
+            };
+            if std::mem::replace(&mut seen[index], true) {
+                return Err(merge::Error::IncompletePartitions { count });
+            }
+            Ok(())
+        };
+        mark_seen(merged.partition)?;
+        for outcome in outcomes {
+            if outcome.actual_index_checksum != merged.actual_index_checksum {
+                return Err(merge::Error::ChecksumMismatch {
+                    expected: merged.actual_index_checksum,
+                    actual: outcome.actual_index_checksum,
+                });
+            }
+            mark_seen(outcome.partition)?;
+            merged.statistics.merge(&outcome.statistics);
+            merged.tally.merge(outcome.tally);
+            merged.num_redecoded_bases = merged
+                .num_redecoded_bases
+                .zip(outcome.num_redecoded_bases)
+                .map(|(lhs, rhs)| lhs + rhs);
+        }
+        merged.partition = None;
+        Ok(merged)
+    }
 }
 
 /// Traversal of pack data files using an index file
 impl index::File {
     /// Iterate through all _decoded objects_ in the given `pack` and handle them with a `Processor`.
-    /// The return value is (pack-checksum, [`Outcome`], `progress`), thus the pack traversal will always verify
+    /// The return value is the pack-checksum along with statistics as [`Outcome`], thus the pack traversal will always verify
     /// the whole packs checksum to assure it was correct. In case of bit-rod, the operation will abort early without
     /// verifying all objects using the [interrupt mechanism][git_features::interrupt] mechanism.
     ///
+    /// `progress` is only borrowed and remains usable by the caller, no matter whether the traversal succeeds or fails.
+    ///
+    /// With object checksums enabled in [`check`][SafetyCheck], each object is hashed and compared to the object id in the index
+    /// no matter the algorithm. Objects whose hash doesn't match aren't passed to the `Processor`, but are recorded in
+    /// [`Statistics::index_oid_mismatches`] without aborting the traversal.
+    ///
     /// # Algorithms
     ///
-    /// Using the [`Options::traversal`] field one can chose between two algorithms providing different tradeoffs. Both invoke
+    /// Using the [`Options::traversal`] field one can chose between three algorithms providing different tradeoffs. All invoke
     /// `new_processor()` to create functions receiving decoded objects, their object kind, index entry and a progress instance to provide
     /// progress information.
     ///
@@ -71,13 +192,88 @@ impl index::File {
     /// * [`Algorithm::Lookup`] uses a cache created by `new_cache()` to avoid having to re-compute all bases of a delta-chain while
     ///   decoding objects.
     ///   One could also call [`traverse_with_lookup()`][index::File::traverse_with_lookup()] directly.
+    /// * [`Algorithm::Streaming`] walks the pack from front to back on a single thread with a memory-capped cache of its own,
+    ///   rendering `new_cache()` unused. It's the only algorithm whose memory usage doesn't grow with the amount of objects.
     ///
     /// Use [`thread_limit`][Options::thread_limit] to further control parallelism and [`check`][SafetyCheck] to define how much the passed
     /// objects shall be verified beforehand.
+    ///
+    /// Use [`traverse_with_info()`][index::File::traverse_with_info()] to also learn how each object is stored in the pack.
     pub fn traverse<P, C, Processor, E, F>(
         &self,
         pack: &crate::data::File,
-        progress: P,
+        progress: &mut P,
+        should_interrupt: &AtomicBool,
+        new_processor: impl Fn() -> Processor + Send + Clone,
+        options: Options<F>,
+    ) -> Result<Outcome, Error<E>>
+    where
+        P: Progress,
+        C: crate::cache::DecodeEntry,
+        E: std::error::Error + Send + Sync + 'static,
+        Processor: FnMut(
+            git_object::Kind,
+            &[u8],
+            &index::Entry,
+            &mut <P::SubProgress as Progress>::SubProgress,
+        ) -> Result<(), E>,
+        F: Fn() -> C + Send + Clone,
+    {
+        self.traverse_with_info_inner(
+            pack,
+            progress,
+            should_interrupt,
+            move || {
+                let mut processor = new_processor();
+                move |kind,
+                      data: &[u8],
+                      entry: &index::Entry,
+                      _info: &EntryInfo,
+                      _tally: &mut Tally,
+                      progress: &mut <P::SubProgress as Progress>::SubProgress| {
+                    processor(kind, data, entry, progress)
+                }
+            },
+            options,
+            false,
+        )
+    }
+
+    /// Like [`traverse()`][index::File::traverse()], but `new_processor()` creates functions which additionally receive
+    /// an [`EntryInfo`] about the way the object is stored in the pack, like its storage kind and compressed size, and
+    /// a [`Tally`] of the current thread to gather statistics in, which is merged into [`Outcome::tally`].
+    pub fn traverse_with_info<P, C, Processor, E, F>(
+        &self,
+        pack: &crate::data::File,
+        progress: &mut P,
+        should_interrupt: &AtomicBool,
+        new_processor: impl Fn() -> Processor + Send + Clone,
+        options: Options<F>,
+    ) -> Result<Outcome, Error<E>>
+    where
+        P: Progress,
+        C: crate::cache::DecodeEntry,
+        E: std::error::Error + Send + Sync + 'static,
+        Processor: FnMut(
+            git_object::Kind,
+            &[u8],
+            &index::Entry,
+            &EntryInfo,
+            &mut Tally,
+            &mut <P::SubProgress as Progress>::SubProgress,
+        ) -> Result<(), E>,
+        F: Fn() -> C + Send + Clone,
+    {
+        self.traverse_with_info_inner(pack, progress, should_interrupt, new_processor, options, true)
+    }
+
+    /// Like [`traverse_with_info()`][index::File::traverse_with_info()], but only determine the
+    /// [`chain_length`][EntryInfo::chain_length] of entries traversed by [`Algorithm::Lookup`] if `with_chain_lengths`
+    /// is set, as it has to be obtained by following the delta chain of each entry. Otherwise it's always 0.
+    pub(crate) fn traverse_with_info_inner<P, C, Processor, E, F>(
+        &self,
+        pack: &crate::data::File,
+        progress: &mut P,
         should_interrupt: &AtomicBool,
         new_processor: impl Fn() -> Processor + Send + Clone,
         Options {
@@ -85,8 +281,13 @@ impl index::File {
             thread_limit,
             check,
             make_pack_lookup_cache,
+            max_decompressed_object_size,
+            partition,
+            paranoid,
+            strict_entry_boundaries,
         }: Options<F>,
-    ) -> Result<Outcome<P>, Error<E>>
+        with_chain_lengths: bool,
+    ) -> Result<Outcome, Error<E>>
     where
         P: Progress,
         C: crate::cache::DecodeEntry,
@@ -95,30 +296,53 @@ impl index::File {
             git_object::Kind,
             &[u8],
             &index::Entry,
+            &EntryInfo,
+            &mut Tally,
             &mut <P::SubProgress as Progress>::SubProgress,
         ) -> Result<(), E>,
         F: Fn() -> C + Send + Clone,
     {
-        match traversal {
-            Algorithm::Lookup => self.traverse_with_lookup(
-                new_processor,
+        fail_if_pack_changed(pack, paranoid, || match traversal {
+            Algorithm::DeltaTreeLookup if partition.is_none() => self.traverse_with_index_and_info(
                 pack,
+                new_processor,
                 progress,
                 should_interrupt,
-                with_lookup::Options {
-                    thread_limit,
+                crate::index::traverse::with_index::Options {
                     check,
-                    make_pack_lookup_cache,
+                    thread_limit,
+                    max_decompressed_object_size,
                 },
             ),
-            Algorithm::DeltaTreeLookup => self.traverse_with_index(
-                pack,
+            Algorithm::Streaming {
+                cache_memory_cap_in_bytes,
+            } if partition.is_none() => self.traverse_streaming_and_info(
                 new_processor,
+                pack,
                 progress,
                 should_interrupt,
-                crate::index::traverse::with_index::Options { check, thread_limit },
+                check,
+                max_decompressed_object_size,
+                cache_memory_cap_in_bytes,
             ),
-        }
+            Algorithm::Lookup | Algorithm::DeltaTreeLookup | Algorithm::Streaming { .. } => self
+                .traverse_with_lookup_and_info(
+                    new_processor,
+                    pack,
+                    progress,
+                    should_interrupt,
+                    with_lookup::Options {
+                        thread_limit,
+                        check,
+                        make_pack_lookup_cache,
+                        max_decompressed_object_size,
+                        partition,
+                        strict_entry_boundaries,
+                    },
+                    false,
+                    with_chain_lengths,
+                ),
+        })
     }
 
     fn possibly_verify<E>(
@@ -157,79 +381,173 @@ impl index::File {
         pack: &crate::data::File,
         cache: &mut C,
         buf: &mut Vec<u8>,
+        tally: &mut Tally,
         progress: &mut P,
         index_entry: &crate::index::Entry,
-        processor: &mut impl FnMut(git_object::Kind, &[u8], &index::Entry, &mut P) -> Result<(), E>,
-    ) -> Result<crate::data::decode_entry::Outcome, Error<E>>
+        processor: &mut impl FnMut(git_object::Kind, &[u8], &index::Entry, &EntryInfo, &mut Tally, &mut P) -> Result<(), E>,
+        max_decompressed_object_size: Option<u64>,
+        next_pack_offset: u64,
+        strict_entry_boundaries: bool,
+        with_chain_lengths: bool,
+    ) -> Result<
+        (
+            crate::data::decode_entry::Outcome,
+            Option<IndexOidMismatch>,
+            Option<EntryBoundaryMismatch>,
+        ),
+        Error<E>,
+    >
     where
         C: crate::cache::DecodeEntry,
         P: Progress,
         E: std::error::Error + Send + Sync + 'static,
     {
-        let pack_entry = pack.entry(index_entry.pack_offset);
+        let pack_decode_error = |source| Error::PackDecode {
+            source,
+            id: index_entry.oid,
+            offset: index_entry.pack_offset,
+        };
+        let pack_entry = pack.try_entry(index_entry.pack_offset).map_err(pack_decode_error)?;
         let pack_entry_data_offset = pack_entry.data_offset;
+        let storage = Storage::from_entry(&pack_entry);
+        let decompressed_size = pack_entry.decompressed_size;
+        let chain_length = if with_chain_lengths {
+            self.delta_chain_length(pack, &pack_entry, || index_entry.oid)?
+        } else {
+            0
+        };
+        let unreadable_base = std::cell::RefCell::new(None);
         let entry_stats = pack
-            .decode_entry(
+            .decode_entry_with_limit(
                 pack_entry,
                 buf,
                 |id, _| {
-                    self.lookup(id)
-                        .map(|index| crate::data::ResolvedBase::InPack(pack.entry(self.pack_offset_at_index(index))))
+                    self.base_entry(pack, id, &unreadable_base)
+                        .map(crate::data::ResolvedBase::InPack)
                 },
                 cache,
+                max_decompressed_object_size,
+                true,
             )
-            .map_err(|e| Error::PackDecode {
-                source: e,
-                id: index_entry.oid,
-                offset: index_entry.pack_offset,
-            })?;
+            .map_err(|err| pack_decode_error(unreadable_base.take().unwrap_or(err)))?;
         let object_kind = entry_stats.kind;
         let header_size = (pack_entry_data_offset - index_entry.pack_offset) as usize;
         let entry_len = header_size + entry_stats.compressed_size;
+        let available_size = next_pack_offset - index_entry.pack_offset;
+        let boundary_mismatch = (entry_len as u64 != available_size).then(|| EntryBoundaryMismatch {
+            pack_offset: index_entry.pack_offset,
+            entry_size: entry_len as u64,
+            available_size,
+        });
+        if let Some(overlap) = boundary_mismatch.filter(|m| strict_entry_boundaries && m.is_overlap()) {
+            return Err(Error::EntryOverlap {
+                offset: overlap.pack_offset,
+                entry_size: overlap.entry_size,
+                available_size: overlap.available_size,
+            });
+        }
+        let info = EntryInfo {
+            storage,
+            header_size: header_size as u32,
+            compressed_size: entry_stats.compressed_size as u64,
+            decompressed_size,
+            chain_length,
+        };
 
-        process_entry(
+        let mismatch = process_entry(
             check,
             object_kind,
             buf,
+            tally,
             progress,
             index_entry,
-            || pack.entry_crc32(index_entry.pack_offset, entry_len),
+            &info,
+            || pack.try_entry_crc32(index_entry.pack_offset, entry_len),
             processor,
         )?;
-        Ok(entry_stats)
+        Ok((entry_stats, mismatch, boundary_mismatch))
+    }
+
+    /// Return the amount of deltas to apply to obtain the object of `entry`, independently of any cache used while decoding it.
+    ///
+    /// `id` is only called to obtain the id of `entry` in case of an error.
+    fn delta_chain_length<E>(
+        &self,
+        pack: &crate::data::File,
+        entry: &crate::data::Entry,
+        id: impl FnOnce() -> git_hash::ObjectId,
+    ) -> Result<u32, Error<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        use crate::data::entry::Header;
+        let mut chain_length = 0;
+        let mut base = entry.clone();
+        loop {
+            let base_offset = match base.header {
+                Header::OfsDelta { base_distance } => base.base_pack_offset(base_distance),
+                Header::RefDelta { base_id } => match self.lookup(base_id) {
+                    Some(index) => self.pack_offset_at_index(index),
+                    None => return Ok(chain_length + 1),
+                },
+                Header::Commit | Header::Tree | Header::Blob | Header::Tag => return Ok(chain_length),
+            };
+            chain_length += 1;
+            // A chain visiting more entries than there are in the pack has to visit one of them twice.
+            if chain_length >= self.num_objects() {
+                return Err(Error::DeltaChainCycle {
+                    id: id(),
+                    offset: entry.pack_offset(),
+                });
+            }
+            base = match pack.try_entry(base_offset) {
+                Ok(base) => base,
+                Err(source) => {
+                    return Err(Error::PackDecode {
+                        id: id(),
+                        offset: entry.pack_offset(),
+                        source,
+                    })
+                }
+            };
+        }
     }
 }
 
+/// Verify the entry as per `check` and pass it to `processor`, unless the object id in the index doesn't match the object hash
+/// which is returned instead.
 #[allow(clippy::too_many_arguments)]
 fn process_entry<P, E>(
     check: SafetyCheck,
     object_kind: git_object::Kind,
     decompressed: &[u8],
+    tally: &mut Tally,
     progress: &mut P,
     index_entry: &crate::index::Entry,
-    pack_entry_crc32: impl FnOnce() -> u32,
-    processor: &mut impl FnMut(git_object::Kind, &[u8], &index::Entry, &mut P) -> Result<(), E>,
-) -> Result<(), Error<E>>
+    info: &EntryInfo,
+    pack_entry_crc32: impl FnOnce() -> Result<u32, crate::data::decode_entry::Error>,
+    processor: &mut impl FnMut(git_object::Kind, &[u8], &index::Entry, &EntryInfo, &mut Tally, &mut P) -> Result<(), E>,
+) -> Result<Option<IndexOidMismatch>, Error<E>>
 where
     P: Progress,
     E: std::error::Error + Send + Sync + 'static,
 {
+    let mut mismatch = None;
     if check.object_checksum() {
-        let mut hasher = git_features::hash::hasher(index_entry.oid.kind());
-        hasher.update(&git_object::encode::loose_header(object_kind, decompressed.len()));
-        hasher.update(decompressed);
-
-        let actual_oid = git_hash::ObjectId::from(hasher.digest());
+        let actual_oid = git_object::compute_hash(object_kind, decompressed, index_entry.oid.kind());
         if actual_oid != index_entry.oid {
-            return Err(Error::PackObjectMismatch {
-                actual: actual_oid,
-                expected: index_entry.oid,
-                offset: index_entry.pack_offset,
-                kind: object_kind,
+            mismatch = Some(IndexOidMismatch {
+                index_oid: index_entry.oid,
+                actual_oid,
+                pack_offset: index_entry.pack_offset,
             });
         }
         if let Some(desired_crc32) = index_entry.crc32 {
-            let actual_crc32 = pack_entry_crc32();
+            let actual_crc32 = pack_entry_crc32().map_err(|source| Error::PackDecode {
+                id: index_entry.oid,
+                offset: index_entry.pack_offset,
+                source,
+            })?;
             if actual_crc32 != desired_crc32 {
                 return Err(Error::Crc32Mismatch {
                     actual: actual_crc32,
@@ -240,5 +558,59 @@ where
             }
         }
     }
-    processor(object_kind, decompressed, index_entry, progress).map_err(Error::Processor)
+    if mismatch.is_some() {
+        return Ok(mismatch);
+    }
+    processor(object_kind, decompressed, index_entry, info, tally, progress).map_err(Error::Processor)?;
+    Ok(None)
+}
+
+/// Run `traverse()` and fail with [`Error::PackMutatedDuringVerification`] instead of returning its result if `paranoid` is set
+/// and the file backing `pack` changed on disk in the mean time.
+pub(crate) fn fail_if_pack_changed<T, E>(
+    pack: &crate::data::File,
+    paranoid: bool,
+    traverse: impl FnOnce() -> Result<T, Error<E>>,
+) -> Result<T, Error<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if !paranoid || pack.is_in_memory() {
+        return traverse();
+    }
+    let before = FileState::at(pack.path());
+    let res = traverse();
+    if before.is_none() || before != FileState::at(pack.path()) {
+        return Err(Error::PackMutatedDuringVerification {
+            path: pack.path().to_owned(),
+        });
+    }
+    res
+}
+
+/// The properties of a file which change if it is written to or replaced.
+#[derive(PartialEq, Eq)]
+struct FileState {
+    len: u64,
+    modified: Option<std::time::SystemTime>,
+    #[cfg(unix)]
+    inode: u64,
+    #[cfg(unix)]
+    changed: (i64, i64),
+}
+
+impl FileState {
+    fn at(path: &std::path::Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        #[cfg(unix)]
+        use std::os::unix::fs::MetadataExt;
+        Some(FileState {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+            #[cfg(unix)]
+            inode: metadata.ino(),
+            #[cfg(unix)]
+            changed: (metadata.ctime(), metadata.ctime_nsec()),
+        })
+    }
 }
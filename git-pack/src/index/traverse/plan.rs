@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, HashMap};
+
+use git_features::{parallel, progress};
+
+use crate::{
+    data,
+    index::{self, traverse::Algorithm, util},
+};
+
+/// Returned by [`index::File::traverse_plan()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] data::decode_entry::Error),
+    #[error("The delta chain of the entry at offset {offset} is longer than the amount of objects in the pack, which means it has a cycle")]
+    DeltaChainCycle { offset: data::Offset },
+}
+
+/// The work a [traversal][index::File::traverse()] would do, as computed by [`index::File::traverse_plan()`]
+/// from the headers of pack entries without decompressing them.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plan {
+    /// The algorithm that would be used, which differs from [`Options::traversal`][super::Options::traversal] if a
+    /// [partition][super::Options::partition] is set.
+    pub algorithm: Algorithm,
+    /// The amount of entries that would be processed.
+    pub num_entries: u32,
+    /// The amount of entries that would be processed and aren't deltas against another entry in the pack.
+    ///
+    /// These are the roots of the delta trees that are resolved by [`Algorithm::DeltaTreeLookup`].
+    pub num_roots: u32,
+    /// The length of the longest delta chain among the entries that would be processed.
+    pub max_chain_length: u32,
+    /// The average length of the delta chains of the entries that would be processed.
+    pub average_chain_length: f32,
+    /// The amount of threads that would be used.
+    pub num_threads: usize,
+    /// The amount of entries handed to a thread at a time, or `None` if the work isn't split into chunks of entries.
+    ///
+    /// Only [`Algorithm::Lookup`] splits the work into chunks, whereas [`Algorithm::DeltaTreeLookup`] hands out entire
+    /// delta trees and [`Algorithm::Streaming`] processes all entries on a single thread.
+    pub chunk_size: Option<usize>,
+    /// The amount of entries each thread would process if the work was handed out to the threads in turn.
+    ///
+    /// This is an estimate as threads actually pick up new work as soon as they are done with the previous one.
+    pub entries_per_thread: Vec<usize>,
+    /// The sum of the decompressed sizes stored in the headers of the entries that would be processed.
+    ///
+    /// For deltas, this is the size of the delta instructions and not the size of the object they produce.
+    pub decompressed_entries_size: u64,
+}
+
+impl index::File {
+    /// Compute the [`Plan`] of [traversing][index::File::traverse()] `pack` with `options` by reading the index and the
+    /// headers of pack entries only, without decompressing or verifying anything.
+    ///
+    /// Only [`traversal`][super::Options::traversal], [`thread_limit`][super::Options::thread_limit] and
+    /// [`partition`][super::Options::partition] affect the plan. An invalid partition yields a plan without entries.
+    pub fn traverse_plan<F>(&self, pack: &data::File, options: &super::Options<F>) -> Result<Plan, Error> {
+        let algorithm = if options.partition.is_some() {
+            Algorithm::Lookup
+        } else {
+            options.traversal
+        };
+        let mut index_entries = util::index_entries_sorted_by_offset_ascending(self, progress::Discard);
+        if let Some(partition) = options.partition {
+            let offsets = partition.pack_offsets(pack.pack_end() as u64);
+            index_entries.retain(|entry| offsets.contains(&entry.pack_offset));
+        }
+
+        let mut chains = HashMap::new();
+        let mut entries_per_root = BTreeMap::<data::Offset, usize>::new();
+        let (mut max_chain_length, mut total_chain_length, mut decompressed_entries_size) = (0, 0u64, 0);
+        for entry in &index_entries {
+            let (chain_length, root) = self.chain_length_and_root(pack, entry.pack_offset, &mut chains)?;
+            max_chain_length = max_chain_length.max(chain_length);
+            total_chain_length += chain_length as u64;
+            decompressed_entries_size += pack.try_entry(entry.pack_offset)?.decompressed_size;
+            *entries_per_root.entry(root).or_default() += 1;
+        }
+        let num_roots = index_entries
+            .iter()
+            .filter(|entry| chains[&entry.pack_offset].1 == entry.pack_offset)
+            .count();
+
+        let (num_threads, chunk_size, units) = match algorithm {
+            Algorithm::Lookup => {
+                let (chunk_size, thread_limit, available_cores) = parallel::optimize_chunk_size_and_thread_limit(
+                    1000,
+                    Some(index_entries.len()),
+                    options.thread_limit,
+                    None,
+                );
+                let num_threads =
+                    if parallel::num_threads(thread_limit) > 1 && index_entries.len() > chunk_size * available_cores {
+                        parallel::num_threads(thread_limit)
+                    } else {
+                        1
+                    };
+                let chunks = index_entries.chunks(chunk_size).map(|chunk| chunk.len()).collect();
+                (num_threads, Some(chunk_size), chunks)
+            }
+            Algorithm::DeltaTreeLookup => (
+                parallel::num_threads(options.thread_limit),
+                None,
+                entries_per_root.into_values().collect(),
+            ),
+            Algorithm::Streaming { .. } => (1, None, vec![index_entries.len()]),
+        };
+        let mut entries_per_thread = vec![0; num_threads];
+        for (unit_index, count) in units.into_iter().enumerate() {
+            entries_per_thread[unit_index % num_threads] += count;
+        }
+
+        Ok(Plan {
+            algorithm,
+            num_entries: index_entries.len() as u32,
+            num_roots: num_roots as u32,
+            max_chain_length,
+            average_chain_length: if index_entries.is_empty() {
+                0.0
+            } else {
+                total_chain_length as f32 / index_entries.len() as f32
+            },
+            num_threads,
+            chunk_size,
+            entries_per_thread,
+            decompressed_entries_size,
+        })
+    }
+
+    /// Return the length of the delta chain of the entry at `pack_offset` along with the offset of the entry at its end,
+    /// remembering both for all entries along the chain in `chains` to not follow them again.
+    ///
+    /// Ref-deltas whose base isn't in the pack are their own root with a chain length of 1.
+    fn chain_length_and_root(
+        &self,
+        pack: &data::File,
+        mut pack_offset: data::Offset,
+        chains: &mut HashMap<data::Offset, (u32, data::Offset)>,
+    ) -> Result<(u32, data::Offset), Error> {
+        use crate::data::entry::Header;
+        let first_offset = pack_offset;
+        let mut deltas = Vec::new();
+        let mut chain = loop {
+            if let Some(chain) = chains.get(&pack_offset) {
+                break *chain;
+            }
+            // A chain visiting more entries than there are in the pack has to visit one of them twice.
+            if deltas.len() >= self.num_objects() as usize {
+                return Err(Error::DeltaChainCycle { offset: first_offset });
+            }
+            let entry = pack.try_entry(pack_offset)?;
+            let base_offset = match entry.header {
+                Header::OfsDelta { base_distance } => entry.base_pack_offset(base_distance),
+                Header::RefDelta { base_id } => match self.lookup(base_id) {
+                    Some(index) => self.pack_offset_at_index(index),
+                    None => break (1, pack_offset),
+                },
+                Header::Commit | Header::Tree | Header::Blob | Header::Tag => break (0, pack_offset),
+            };
+            deltas.push(pack_offset);
+            pack_offset = base_offset;
+        };
+        chains.insert(pack_offset, chain);
+        for delta_offset in deltas.into_iter().rev() {
+            chain.0 += 1;
+            chains.insert(delta_offset, chain);
+        }
+        Ok(chain)
+    }
+}
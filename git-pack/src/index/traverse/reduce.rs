@@ -33,6 +33,7 @@ pub struct Reducer<'a, P, E> {
     then: Instant,
     entries_seen: usize,
     stats: traverse::Statistics,
+    tally: traverse::Tally,
     should_interrupt: &'a AtomicBool,
     _error: std::marker::PhantomData<E>,
 }
@@ -58,6 +59,7 @@ where
             entries_seen: 0,
             should_interrupt,
             stats,
+            tally: Default::default(),
             _error: Default::default(),
         }
     }
@@ -68,13 +70,23 @@ where
     P: Progress,
     E: std::error::Error + Send + Sync + 'static,
 {
-    type Input = Result<Vec<data::decode_entry::Outcome>, traverse::Error<E>>;
+    type Input = Result<
+        (
+            Vec<(
+                data::decode_entry::Outcome,
+                Option<traverse::IndexOidMismatch>,
+                Option<traverse::EntryBoundaryMismatch>,
+            )>,
+            traverse::Tally,
+        ),
+        traverse::Error<E>,
+    >;
     type FeedProduce = ();
-    type Output = traverse::Statistics;
+    type Output = (traverse::Statistics, traverse::Tally);
     type Error = traverse::Error<E>;
 
     fn feed(&mut self, input: Self::Input) -> Result<(), Self::Error> {
-        let chunk_stats: Vec<_> = match input {
+        let (chunk_stats, chunk_tally) = match input {
             Err(err @ traverse::Error::PackDecode { .. }) if !self.check.fatal_decode_error() => {
                 lock(&self.progress).info(format!("Ignoring decode error: {}", err));
                 return Ok(());
@@ -82,10 +94,17 @@ where
             res => res,
         }?;
         self.entries_seen += chunk_stats.len();
+        self.tally.merge(chunk_tally);
 
         let chunk_total = chunk_stats.into_iter().fold(
             data::decode_entry::Outcome::default_from_kind(git_object::Kind::Tree),
-            |mut total, stats| {
+            |mut total, (stats, mismatch, boundary_mismatch)| {
+                if let Some(mismatch) = mismatch {
+                    self.stats.record_index_oid_mismatch(mismatch);
+                }
+                if let Some(mismatch) = boundary_mismatch {
+                    self.stats.record_entry_boundary_mismatch(mismatch);
+                }
                 *self.stats.objects_per_chain_length.entry(stats.num_deltas).or_insert(0) += 1;
                 self.stats.total_decompressed_entries_size += stats.decompressed_size;
                 self.stats.total_compressed_entries_size += stats.compressed_size as u64;
@@ -113,6 +132,8 @@ where
 
     fn finalize(mut self) -> Result<Self::Output, Self::Error> {
         div_decode_result(&mut self.stats.average, self.entries_seen as usize);
+        self.stats.keep_first_index_oid_mismatches();
+        self.stats.keep_first_entry_boundary_mismatches();
 
         let elapsed_s = self.then.elapsed().as_secs_f32();
         let objects_per_second = (self.entries_seen as f32 / elapsed_s) as u32;
@@ -124,6 +145,6 @@ where
             objects_per_second,
             bytesize::ByteSize(self.stats.average.object_size * objects_per_second as u64)
         ));
-        Ok(self.stats)
+        Ok((self.stats, self.tally))
     }
 }
@@ -0,0 +1,379 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryInto,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::{
+    parallel::{self, Reduce},
+    progress::{self, Progress},
+    threading::{lock, Mutable, OwnShared},
+};
+
+use super::{
+    EntryInfo, Error, IndexOidMismatch, Outcome, Reducer, SafetyCheck, Storage, Tally,
+    MAX_REPORTED_INDEX_OID_MISMATCHES,
+};
+use crate::{cache::DecodeEntry, data, index};
+
+/// The pack offset of the first entry, right after the pack header.
+const FIRST_ENTRY_OFFSET: data::Offset = 12;
+
+/// The amount of decoded entries to collect before passing their statistics to the reducer.
+const CHUNK_SIZE: usize = 1000;
+
+/// Streaming traversal
+impl index::File {
+    /// Visit all entries of `pack` in the order in which they are stored, looking up each of them in the index by the hash
+    /// of its object, and keep decoded objects in a cache of up to `cache_memory_cap_in_bytes` to resolve deltas against them.
+    ///
+    /// For more details, see the documentation on [`Algorithm::Streaming`][super::Algorithm::Streaming].
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn traverse_streaming_and_info<P, Processor, E>(
+        &self,
+        new_processor: impl FnOnce() -> Processor + Send,
+        pack: &crate::data::File,
+        progress: &mut P,
+        should_interrupt: &AtomicBool,
+        check: SafetyCheck,
+        max_decompressed_object_size: Option<u64>,
+        cache_memory_cap_in_bytes: usize,
+    ) -> Result<Outcome, Error<E>>
+    where
+        P: Progress,
+        E: std::error::Error + Send + Sync + 'static,
+        Processor: FnMut(
+            git_object::Kind,
+            &[u8],
+            &index::Entry,
+            &EntryInfo,
+            &mut Tally,
+            &mut <P::SubProgress as Progress>::SubProgress,
+        ) -> Result<(), E>,
+    {
+        let (verify_result, traversal_result) = parallel::join(
+            {
+                let pack_progress = progress.add_child_with_id(
+                    format!(
+                        "hash pack '{}'",
+                        pack.path().file_name().expect("pack has filename").to_string_lossy()
+                    ),
+                    *b"PTHP", /* Pack Traverse Hash Pack bytes */
+                );
+                let index_progress = progress.add_child_with_id(
+                    format!(
+                        "hash index '{}'",
+                        self.path.file_name().expect("index has filename").to_string_lossy()
+                    ),
+                    *b"PTHI", /* Pack Traverse Hash Index bytes */
+                );
+                move || {
+                    let res = self.possibly_verify(pack, check, pack_progress, index_progress, should_interrupt);
+                    if res.is_err() {
+                        should_interrupt.store(true, Ordering::SeqCst);
+                    }
+                    res
+                }
+            },
+            || {
+                let reduce_progress = OwnShared::new(Mutable::new({
+                    let mut p = progress.add_child_with_id("decode objects", *b"PTRO"); /* Pack Traverse Resolve Objects */
+                    p.init(Some(pack.num_objects() as usize), progress::count("objects"));
+                    p
+                }));
+                let mut entry_progress =
+                    lock(&reduce_progress).add_child_with_id("thread 0", git_features::progress::UNKNOWN);
+                entry_progress.init(Some(pack.num_objects() as usize), progress::count("objects"));
+                let mut reducer: Reducer<'_, _, E> =
+                    Reducer::from_progress(reduce_progress, pack.data_len(), check, should_interrupt);
+
+                let mut processor = new_processor();
+                let mut tally = Tally::default();
+                let mut cache = Cache::new(cache_memory_cap_in_bytes);
+                let mut buf = Vec::with_capacity(2048);
+                let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+                let mut num_redecoded_bases = 0;
+                let mut num_index_oid_mismatches = 0;
+                let mut first_index_oid_mismatches = Vec::new();
+                let mut num_delta_result_size_mismatches = 0;
+
+                let mut pack_offset = FIRST_ENTRY_OFFSET;
+                while pack_offset < pack.pack_end() as u64 {
+                    let pack_entry = pack.try_entry(pack_offset).map_err(|source| Error::PackDecode {
+                        id: self.oid_at_pack_offset(pack_offset),
+                        offset: pack_offset,
+                        source,
+                    })?;
+                    let header_size = pack_entry.header_size();
+                    let storage = Storage::from_entry(&pack_entry);
+                    let chain_length =
+                        self.delta_chain_length(pack, &pack_entry, || self.oid_at_pack_offset(pack_offset))?;
+
+                    let misses_before = cache.misses;
+                    let unreadable_base = std::cell::RefCell::new(None);
+                    let result = pack
+                        .decode_entry_with_limit(
+                            pack_entry.clone(),
+                            &mut buf,
+                            |id, _| {
+                                self.base_entry(pack, id, &unreadable_base)
+                                    .map(data::ResolvedBase::InPack)
+                            },
+                            &mut cache,
+                            max_decompressed_object_size,
+                            true,
+                        )
+                        .map_err(|err| unreadable_base.take().unwrap_or(err));
+                    // The lookup of the entry itself always misses, but each further miss means a base is decoded again.
+                    num_redecoded_bases += (cache.misses - misses_before).saturating_sub(1);
+                    entry_progress.inc();
+
+                    let mut entry_stats = match result {
+                        Ok(entry_stats) => entry_stats,
+                        Err(source) => {
+                            let is_delta_result_size_mismatch =
+                                matches!(source, data::decode_entry::Error::DeltaResultSizeMismatch { .. });
+                            let err = Error::PackDecode {
+                                id: self.oid_at_pack_offset(pack_offset),
+                                offset: pack_offset,
+                                source,
+                            };
+                            if check.fatal_decode_error() {
+                                return Err(err);
+                            }
+                            entry_progress.info(format!("Ignoring decode error: {}", err));
+                            if is_delta_result_size_mismatch {
+                                num_delta_result_size_mismatches += 1;
+                            }
+                            let compressed_size = skip_entry(pack, &pack_entry, &mut buf, max_decompressed_object_size)
+                                .map_err(|source| Error::PackDecode {
+                                    id: self.oid_at_pack_offset(pack_offset),
+                                    offset: pack_offset,
+                                    source,
+                                })?;
+                            pack_offset += (header_size + compressed_size) as u64;
+                            continue;
+                        }
+                    };
+                    if !pack_entry.header.is_delta() {
+                        // Deltas are put into the cache while decoding them, but their bases are not.
+                        cache.put(
+                            pack.id,
+                            pack_entry.data_offset,
+                            &buf,
+                            entry_stats.kind,
+                            entry_stats.compressed_size,
+                        );
+                    }
+
+                    let entry_len = header_size + entry_stats.compressed_size;
+                    let actual_oid = git_object::compute_hash(entry_stats.kind, &buf, self.object_hash);
+                    let index_entry = self
+                        .lookup(actual_oid)
+                        .filter(|index| self.pack_offset_at_index(*index) == pack_offset)
+                        .map(|index| index::Entry {
+                            oid: actual_oid,
+                            pack_offset,
+                            crc32: self.crc32_at_index(index),
+                        });
+                    match index_entry {
+                        Some(index_entry) => {
+                            if let Some(desired_crc32) = index_entry.crc32.filter(|_| check.object_checksum()) {
+                                let actual_crc32 = pack.try_entry_crc32(pack_offset, entry_len).map_err(|source| {
+                                    Error::PackDecode {
+                                        id: index_entry.oid,
+                                        offset: pack_offset,
+                                        source,
+                                    }
+                                })?;
+                                if actual_crc32 != desired_crc32 {
+                                    return Err(Error::Crc32Mismatch {
+                                        actual: actual_crc32,
+                                        expected: desired_crc32,
+                                        offset: pack_offset,
+                                        kind: entry_stats.kind,
+                                    });
+                                }
+                            }
+                            let info = EntryInfo {
+                                storage,
+                                header_size: header_size as u32,
+                                compressed_size: entry_stats.compressed_size as u64,
+                                decompressed_size: pack_entry.decompressed_size,
+                                chain_length,
+                            };
+                            processor(
+                                entry_stats.kind,
+                                &buf,
+                                &index_entry,
+                                &info,
+                                &mut tally,
+                                &mut entry_progress,
+                            )
+                            .map_err(Error::Processor)?;
+                        }
+                        None => {
+                            num_index_oid_mismatches += 1;
+                            // Entries are visited by ascending offset, so the first ones are those with the lowest offsets.
+                            if first_index_oid_mismatches.len() < MAX_REPORTED_INDEX_OID_MISMATCHES {
+                                first_index_oid_mismatches.push((pack_offset, actual_oid));
+                            }
+                        }
+                    }
+
+                    // Cache hits shorten the amount of deltas applied, but statistics are about the pack itself.
+                    entry_stats.num_deltas = chain_length;
+                    chunk.push((entry_stats, None, None));
+                    if chunk.len() == CHUNK_SIZE {
+                        reducer.feed(Ok((
+                            std::mem::replace(&mut chunk, Vec::with_capacity(CHUNK_SIZE)),
+                            std::mem::take(&mut tally),
+                        )))?;
+                    }
+                    pack_offset += entry_len as u64;
+                }
+                reducer.feed(Ok((chunk, tally)))?;
+
+                let (mut statistics, tally) = reducer.finalize()?;
+                for (pack_offset, actual_oid) in first_index_oid_mismatches {
+                    statistics.record_index_oid_mismatch(IndexOidMismatch {
+                        index_oid: self.oid_at_pack_offset(pack_offset),
+                        actual_oid,
+                        pack_offset,
+                    });
+                }
+                statistics.index_oid_mismatches = num_index_oid_mismatches;
+                statistics.delta_result_size_mismatches = num_delta_result_size_mismatches;
+                statistics.keep_first_index_oid_mismatches();
+                Ok((statistics, tally, num_redecoded_bases))
+            },
+        );
+        let actual_index_checksum = verify_result?;
+        let (statistics, tally, num_redecoded_bases) = traversal_result?;
+        Ok(Outcome {
+            actual_index_checksum,
+            statistics,
+            partition: None,
+            num_redecoded_bases: Some(num_redecoded_bases),
+            tally,
+        })
+    }
+
+    /// Return the id of the index entry at `pack_offset`, or the null id if there is none.
+    ///
+    /// This searches all entries and should only be used in exceptional cases.
+    fn oid_at_pack_offset(&self, pack_offset: data::Offset) -> git_hash::ObjectId {
+        self.iter()
+            .find(|entry| entry.pack_offset == pack_offset)
+            .map_or_else(|| git_hash::ObjectId::null(self.object_hash), |entry| entry.oid)
+    }
+}
+
+/// Decompress only the data of `entry` itself to learn how many compressed bytes it takes, which are returned.
+fn skip_entry(
+    pack: &crate::data::File,
+    entry: &data::Entry,
+    buf: &mut Vec<u8>,
+    max_decompressed_object_size: Option<u64>,
+) -> Result<usize, data::decode_entry::Error> {
+    data::decode_entry::check_size_limit(
+        entry.pack_offset(),
+        entry.decompressed_size,
+        max_decompressed_object_size,
+    )?;
+    buf.resize(
+        entry
+            .decompressed_size
+            .try_into()
+            .expect("size representable by machine"),
+        0,
+    );
+    pack.decompress_entry(entry, buf)
+}
+
+/// A least-recently-used cache of decoded objects by their data offset in a single pack, which evicts objects once their data
+/// would use more than `memory_cap_in_bytes`.
+struct Cache {
+    objects: HashMap<data::Offset, CachedObject>,
+    /// The data offsets of all objects by the time they were last used, the least recently used first.
+    offsets_by_use: BTreeMap<u64, data::Offset>,
+    time: u64,
+    memory_in_bytes: usize,
+    memory_cap_in_bytes: usize,
+    /// The amount of times an object wasn't found.
+    misses: u64,
+}
+
+struct CachedObject {
+    data: Vec<u8>,
+    kind: git_object::Kind,
+    compressed_size: usize,
+    last_used: u64,
+}
+
+impl Cache {
+    fn new(memory_cap_in_bytes: usize) -> Self {
+        Cache {
+            objects: HashMap::new(),
+            offsets_by_use: BTreeMap::new(),
+            time: 0,
+            memory_in_bytes: 0,
+            memory_cap_in_bytes,
+            misses: 0,
+        }
+    }
+
+    fn remove(&mut self, offset: data::Offset) {
+        if let Some(object) = self.objects.remove(&offset) {
+            self.offsets_by_use.remove(&object.last_used);
+            self.memory_in_bytes -= object.data.len();
+        }
+    }
+}
+
+impl DecodeEntry for Cache {
+    fn put(&mut self, _pack_id: u32, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
+        self.remove(offset);
+        if data.len() > self.memory_cap_in_bytes {
+            return;
+        }
+        while self.memory_in_bytes + data.len() > self.memory_cap_in_bytes {
+            let least_recently_used = *self
+                .offsets_by_use
+                .values()
+                .next()
+                .expect("objects in cache if memory is used");
+            self.remove(least_recently_used);
+        }
+        self.time += 1;
+        self.offsets_by_use.insert(self.time, offset);
+        self.memory_in_bytes += data.len();
+        self.objects.insert(
+            offset,
+            CachedObject {
+                data: data.to_owned(),
+                kind,
+                compressed_size,
+                last_used: self.time,
+            },
+        );
+    }
+
+    fn get(&mut self, _pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)> {
+        match self.objects.get_mut(&offset) {
+            Some(object) => {
+                self.time += 1;
+                self.offsets_by_use.remove(&object.last_used);
+                self.offsets_by_use.insert(self.time, offset);
+                object.last_used = self.time;
+                out.clear();
+                out.extend_from_slice(&object.data);
+                Some((object.kind, object.compressed_size))
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+}
@@ -27,6 +27,152 @@ pub struct Statistics {
     pub num_tags: u32,
     /// The amount of objects encountered that where blobs
     pub num_blobs: u32,
+    /// The amount of index entries whose object id didn't match the hash of the object found at their offset in the pack.
+    ///
+    /// Only set if object checksums are verified as part of the [safety checks][SafetyCheck].
+    pub index_oid_mismatches: u32,
+    /// Up to [`MAX_REPORTED_INDEX_OID_MISMATCHES`] of all `index_oid_mismatches`, those with the lowest pack offset first.
+    pub first_index_oid_mismatches: Vec<IndexOidMismatch>,
+    /// The amount of deltas which produced less than the result size declared in their header, and whose objects were skipped.
+    ///
+    /// These are only counted if decode errors aren't fatal as per the [safety checks][SafetyCheck], as otherwise the traversal
+    /// fails on the first one. [`Algorithm::DeltaTreeLookup`] always fails on them.
+    pub delta_result_size_mismatches: u32,
+    /// The amount of entries whose compressed data extends past the offset of the entry following it, or past the end of the pack.
+    ///
+    /// Entry boundaries are only checked if entries are traversed by offset, i.e. with [`Algorithm::Lookup`] or within a
+    /// [`Partition`]. Unless [`strict_entry_boundaries`][super::Options::strict_entry_boundaries] is set, these entries
+    /// are decoded nonetheless.
+    pub entry_overlaps: u32,
+    /// The amount of bytes between entries that aren't used by any entry known to the index, as the compressed data of
+    /// the entry before them ends early.
+    ///
+    /// Only set if entry boundaries are checked, see [`entry_overlaps`][Statistics::entry_overlaps].
+    pub entry_slack_bytes: u64,
+    /// Up to [`MAX_REPORTED_ENTRY_BOUNDARY_MISMATCHES`] of all overlapping entries and entries followed by slack,
+    /// those with the lowest pack offset first.
+    pub first_entry_boundary_mismatches: Vec<EntryBoundaryMismatch>,
+}
+
+/// The maximum amount of [mismatches][IndexOidMismatch] to keep in [`Statistics::first_index_oid_mismatches`].
+pub const MAX_REPORTED_INDEX_OID_MISMATCHES: usize = 10;
+
+/// An index entry whose object id doesn't match the hash of the object at its offset in the pack, as happens if the index
+/// was built for a different pack even though the CRC32 of the entry matches.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexOidMismatch {
+    /// The object id as stored in the index.
+    pub index_oid: git_hash::ObjectId,
+    /// The object id obtained by hashing the object in the pack.
+    pub actual_oid: git_hash::ObjectId,
+    /// The offset of the object in the pack.
+    pub pack_offset: u64,
+}
+
+/// The maximum amount of [mismatches][EntryBoundaryMismatch] to keep in [`Statistics::first_entry_boundary_mismatches`].
+pub const MAX_REPORTED_ENTRY_BOUNDARY_MISMATCHES: usize = 10;
+
+/// A pack entry whose size, including its header and the compressed bytes consumed while inflating it, doesn't match
+/// the space available until the next entry, or the end of the pack.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntryBoundaryMismatch {
+    /// The offset of the entry in the pack.
+    pub pack_offset: u64,
+    /// The amount of bytes the entry actually occupies.
+    pub entry_size: u64,
+    /// The amount of bytes between the start of the entry and the start of the next one.
+    pub available_size: u64,
+}
+
+impl EntryBoundaryMismatch {
+    /// Return `true` if the entry extends into the next entry, or `false` if there is slack after it.
+    pub fn is_overlap(&self) -> bool {
+        self.entry_size > self.available_size
+    }
+
+    /// The amount of unused bytes following the entry, or 0 if it [overlaps][Self::is_overlap()] with the next one.
+    pub fn slack(&self) -> u64 {
+        self.available_size.saturating_sub(self.entry_size)
+    }
+}
+
+impl Statistics {
+    pub(crate) fn record_entry_boundary_mismatch(&mut self, mismatch: EntryBoundaryMismatch) {
+        if mismatch.is_overlap() {
+            self.entry_overlaps += 1;
+        }
+        self.entry_slack_bytes += mismatch.slack();
+        self.first_entry_boundary_mismatches.push(mismatch);
+        if self.first_entry_boundary_mismatches.len() > MAX_REPORTED_ENTRY_BOUNDARY_MISMATCHES * 2 {
+            self.keep_first_entry_boundary_mismatches();
+        }
+    }
+
+    pub(crate) fn keep_first_entry_boundary_mismatches(&mut self) {
+        self.first_entry_boundary_mismatches.sort_by_key(|m| m.pack_offset);
+        self.first_entry_boundary_mismatches
+            .truncate(MAX_REPORTED_ENTRY_BOUNDARY_MISMATCHES);
+    }
+
+    pub(crate) fn record_index_oid_mismatch(&mut self, mismatch: IndexOidMismatch) {
+        self.index_oid_mismatches += 1;
+        self.first_index_oid_mismatches.push(mismatch);
+        if self.first_index_oid_mismatches.len() > MAX_REPORTED_INDEX_OID_MISMATCHES * 2 {
+            self.keep_first_index_oid_mismatches();
+        }
+    }
+
+    pub(crate) fn keep_first_index_oid_mismatches(&mut self) {
+        self.first_index_oid_mismatches.sort_by_key(|m| m.pack_offset);
+        self.first_index_oid_mismatches
+            .truncate(MAX_REPORTED_INDEX_OID_MISMATCHES);
+    }
+}
+
+impl Statistics {
+    /// The amount of objects these statistics were gathered from.
+    pub fn num_objects(&self) -> u64 {
+        self.objects_per_chain_length.values().map(|count| *count as u64).sum()
+    }
+
+    /// Add the statistics in `other` to ours, as if the objects of both were traversed together.
+    ///
+    /// The `pack_size` is kept as both are expected to refer to the same pack, and averages are weighted
+    /// by the amount of objects.
+    pub fn merge(&mut self, other: &Statistics) {
+        let (lhs_objects, rhs_objects) = (self.num_objects(), other.num_objects());
+        let total_objects = lhs_objects + rhs_objects;
+        if total_objects != 0 {
+            let weighted = |lhs: u64, rhs: u64| (lhs * lhs_objects + rhs * rhs_objects) / total_objects;
+            let (lhs, rhs) = (&mut self.average, &other.average);
+            lhs.num_deltas = weighted(lhs.num_deltas as u64, rhs.num_deltas as u64) as u32;
+            lhs.decompressed_size = weighted(lhs.decompressed_size, rhs.decompressed_size);
+            lhs.compressed_size = weighted(lhs.compressed_size as u64, rhs.compressed_size as u64) as usize;
+            lhs.object_size = weighted(lhs.object_size, rhs.object_size);
+        }
+        for (chain_length, count) in &other.objects_per_chain_length {
+            *self.objects_per_chain_length.entry(*chain_length).or_insert(0) += count;
+        }
+        self.total_compressed_entries_size += other.total_compressed_entries_size;
+        self.total_decompressed_entries_size += other.total_decompressed_entries_size;
+        self.total_object_size += other.total_object_size;
+        self.num_commits += other.num_commits;
+        self.num_trees += other.num_trees;
+        self.num_tags += other.num_tags;
+        self.num_blobs += other.num_blobs;
+        self.index_oid_mismatches += other.index_oid_mismatches;
+        self.first_index_oid_mismatches
+            .extend(other.first_index_oid_mismatches.iter().copied());
+        self.keep_first_index_oid_mismatches();
+        self.delta_result_size_mismatches += other.delta_result_size_mismatches;
+        self.entry_overlaps += other.entry_overlaps;
+        self.entry_slack_bytes += other.entry_slack_bytes;
+        self.first_entry_boundary_mismatches
+            .extend(other.first_entry_boundary_mismatches.iter().copied());
+        self.keep_first_entry_boundary_mismatches();
+    }
 }
 
 impl Default for Statistics {
@@ -42,7 +188,138 @@ impl Default for Statistics {
             num_commits: 0,
             num_trees: 0,
             num_tags: 0,
+            index_oid_mismatches: 0,
+            first_index_oid_mismatches: Vec::new(),
+            delta_result_size_mismatches: 0,
+            entry_overlaps: 0,
+            entry_slack_bytes: 0,
+            first_entry_boundary_mismatches: Vec::new(),
+        }
+    }
+}
+
+/// One of `count` deterministic slices of a pack by the offsets of its entries, to allow traversing a pack in parts,
+/// for instance on multiple machines, and [merge][super::Outcome::merge()] the outcomes afterwards.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Partition {
+    /// The zero-based index of the partition to traverse, which must be smaller than `count`.
+    pub index: u32,
+    /// The amount of partitions the pack is split into.
+    pub count: u32,
+}
+
+impl Partition {
+    /// Return `true` if `index` is smaller than `count`.
+    pub fn is_valid(&self) -> bool {
+        self.index < self.count
+    }
+
+    /// Return the range of offsets of the entries belonging to this partition in a pack whose entries end at `pack_end`.
+    ///
+    /// The ranges of all partitions with the same `count` don't overlap and together cover the entire pack.
+    pub fn pack_offsets(&self, pack_end: u64) -> std::ops::Range<u64> {
+        let bound = |index: u32| (pack_end as u128 * index as u128 / self.count as u128) as u64;
+        bound(self.index)..bound(self.index + 1)
+    }
+}
+
+/// The way an object is stored in a pack.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Storage {
+    /// The object is stored as a whole and isn't a delta.
+    Base,
+    /// The object is a delta against the base object at `base_pack_offset` in the same pack.
+    OfsDelta {
+        /// The pack offset of the base object.
+        base_pack_offset: crate::data::Offset,
+    },
+    /// The object is a delta against the object with `base_id`.
+    RefDelta {
+        /// The id of the base object.
+        base_id: git_hash::ObjectId,
+    },
+}
+
+impl Storage {
+    pub(crate) fn from_entry(entry: &crate::data::Entry) -> Self {
+        use crate::data::entry::Header;
+        match entry.header {
+            Header::OfsDelta { base_distance } => Storage::OfsDelta {
+                base_pack_offset: entry.base_pack_offset(base_distance),
+            },
+            Header::RefDelta { base_id } => Storage::RefDelta { base_id },
+            Header::Commit | Header::Tree | Header::Blob | Header::Tag => Storage::Base,
+        }
+    }
+}
+
+/// Information about how an object is stored in the pack, as passed to the processor of
+/// [`traverse_with_info()`][crate::index::File::traverse_with_info()].
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntryInfo {
+    /// Whether the object is stored as base or as delta, along with the delta's base.
+    pub storage: Storage,
+    /// The size of the entry header in bytes.
+    pub header_size: u32,
+    /// The size of the compressed entry data in bytes, not including the header.
+    pub compressed_size: u64,
+    /// The size of the decompressed entry data in bytes, which for deltas is the size of the delta itself, not of the object.
+    pub decompressed_size: u64,
+    /// The amount of deltas to apply to obtain the object, 0 for base objects.
+    pub chain_length: u32,
+}
+
+impl EntryInfo {
+    /// The amount of bytes the entry occupies in the pack, including its header.
+    pub fn size_in_pack(&self) -> u64 {
+        self.header_size as u64 + self.compressed_size
+    }
+}
+
+/// Statistics gathered by the processor of [`traverse_with_info()`][crate::index::File::traverse_with_info()] on the thread
+/// it runs on, which are merged into [`Outcome::tally`][super::Outcome::tally] once the traversal is done.
+///
+/// As each thread has its own instance, processors can count at will without atomics, locks or progress updates, leaving
+/// the progress instance for reporting progress.
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tally {
+    /// Counters by a name chosen by the processor.
+    pub counters: BTreeMap<String, u64>,
+    /// Messages about noteworthy findings which didn't fail the processor, in the order they were made on each thread.
+    pub warnings: Vec<String>,
+}
+
+impl Tally {
+    /// Add `count` to the counter with the given `name`, creating it if needed.
+    pub fn add(&mut self, name: &str, count: u64) {
+        match self.counters.get_mut(name) {
+            Some(counter) => *counter += count,
+            None => {
+                self.counters.insert(name.to_owned(), count);
+            }
+        }
+    }
+
+    /// Return the value of the counter with the given `name`, which is 0 if it was never added to.
+    pub fn count(&self, name: &str) -> u64 {
+        self.counters.get(name).copied().unwrap_or_default()
+    }
+
+    /// Record a `message` about a finding that didn't fail the processor.
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    /// Add all counters and warnings of `other` to our own.
+    pub fn merge(&mut self, other: Tally) {
+        for (name, count) in other.counters {
+            *self.counters.entry(name).or_insert(0) += count;
         }
+        self.warnings.extend(other.warnings);
     }
 }
 
@@ -103,6 +380,20 @@ pub enum Algorithm {
     ///
     /// This option may be well suited for big packs in memory-starved system that support memory mapping.
     Lookup,
+    /// Walk the pack from front to back on a single thread, finding each entry in the index by the hash of its object,
+    /// and keep the most recently used objects in a cache whose object data uses at most `cache_memory_cap_in_bytes`.
+    ///
+    /// Unlike the other algorithms, this one doesn't collect all index entries upfront, so its memory usage stays the same
+    /// no matter how many objects there are. This comes at the expense of compute, as delta bases which were evicted from the
+    /// cache are decoded again, as counted in [`Outcome::num_redecoded_bases`][super::Outcome::num_redecoded_bases].
+    /// As the pack isn't traversed in parallel, only file checksums are computed on other threads.
+    ///
+    /// As object ids are obtained by hashing objects, this is always done, and objects that aren't found in the index at their
+    /// offset are counted as [mismatch][Statistics::index_oid_mismatches] no matter the [`SafetyCheck`].
+    Streaming {
+        /// The maximum amount of bytes of decoded objects to keep in the cache.
+        cache_memory_cap_in_bytes: usize,
+    },
 }
 
 impl Default for Algorithm {
@@ -5,17 +5,34 @@ use git_features::{parallel, progress::Progress};
 use super::Error;
 use crate::{
     cache::delta::traverse,
-    index::{self, traverse::Outcome, util::index_entries_sorted_by_offset_ascending},
+    index::{
+        self,
+        traverse::{EntryInfo, Outcome, Storage, Tally},
+        util::index_entries_sorted_by_offset_ascending,
+    },
 };
 
 /// Traversal options for [`traverse_with_index()`][index::File::traverse_with_index()]
-#[derive(Default)]
 pub struct Options {
     /// If `Some`, only use the given amount of threads. Otherwise, the amount of threads to use will be selected based on
     /// the amount of available logical cores.
     pub thread_limit: Option<usize>,
     /// The kinds of safety checks to perform.
     pub check: crate::index::traverse::SafetyCheck,
+    /// If `Some`, fail if any object or delta would decompress to more than the given amount of bytes.
+    ///
+    /// Defaults to [`DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE`][crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE].
+    pub max_decompressed_object_size: Option<u64>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            thread_limit: None,
+            check: Default::default(),
+            max_decompressed_object_size: Some(crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+        }
+    }
 }
 
 /// Traversal with index
@@ -28,10 +45,10 @@ impl index::File {
         &self,
         pack: &crate::data::File,
         new_processor: impl Fn() -> Processor + Send + Clone,
-        mut progress: P,
+        progress: &mut P,
         should_interrupt: &AtomicBool,
-        Options { check, thread_limit }: Options,
-    ) -> Result<Outcome<P>, Error<E>>
+        options: Options,
+    ) -> Result<Outcome, Error<E>>
     where
         P: Progress,
         Processor: FnMut(
@@ -41,19 +58,62 @@ impl index::File {
             &mut <P::SubProgress as Progress>::SubProgress,
         ) -> Result<(), E>,
         E: std::error::Error + Send + Sync + 'static,
+    {
+        self.traverse_with_index_and_info(
+            pack,
+            move || {
+                let mut processor = new_processor();
+                move |kind,
+                      data: &[u8],
+                      entry: &index::Entry,
+                      _info: &EntryInfo,
+                      _tally: &mut Tally,
+                      progress: &mut <P::SubProgress as Progress>::SubProgress| {
+                    processor(kind, data, entry, progress)
+                }
+            },
+            progress,
+            should_interrupt,
+            options,
+        )
+    }
+
+    pub(crate) fn traverse_with_index_and_info<P, Processor, E>(
+        &self,
+        pack: &crate::data::File,
+        new_processor: impl Fn() -> Processor + Send + Clone,
+        progress: &mut P,
+        should_interrupt: &AtomicBool,
+        Options {
+            check,
+            thread_limit,
+            max_decompressed_object_size,
+        }: Options,
+    ) -> Result<Outcome, Error<E>>
+    where
+        P: Progress,
+        Processor: FnMut(
+            git_object::Kind,
+            &[u8],
+            &index::Entry,
+            &EntryInfo,
+            &mut Tally,
+            &mut <P::SubProgress as Progress>::SubProgress,
+        ) -> Result<(), E>,
+        E: std::error::Error + Send + Sync + 'static,
     {
         let (verify_result, traversal_result) = parallel::join(
             {
                 let pack_progress = progress.add_child_with_id(
                     format!(
-                        "Hash of pack '{}'",
+                        "hash pack '{}'",
                         pack.path().file_name().expect("pack has filename").to_string_lossy()
                     ),
                     *b"PTHP", /* Pack Traverse Hash Pack bytes */
                 );
                 let index_progress = progress.add_child_with_id(
                     format!(
-                        "Hash of index '{}'",
+                        "hash index '{}'",
                         self.path.file_name().expect("index has filename").to_string_lossy()
                     ),
                     *b"PTHI", /* Pack Traverse Hash Index bytes */
@@ -71,26 +131,45 @@ impl index::File {
                     self,
                     progress.add_child_with_id("collecting sorted index", *b"PTCE"),
                 ); /* Pack Traverse Collect sorted Entries */
-                let tree = crate::cache::delta::Tree::from_offsets_in_pack(
-                    pack.path(),
-                    sorted_entries.into_iter().map(Entry::from),
-                    |e| e.index_entry.pack_offset,
-                    |id| self.lookup(id).map(|idx| self.pack_offset_at_index(idx)),
-                    progress.add_child_with_id("indexing", *b"PTDI"), /* Pack Traverse Delta Index creation */
-                    should_interrupt,
-                    self.object_hash,
-                )?;
-                let mut outcome = digest_statistics(tree.traverse(
-                    |slice, out| pack.entry_slice(slice).map(|entry| out.copy_from_slice(entry)),
+                let entries = sorted_entries.into_iter().map(Entry::from);
+                let get_pack_offset = |e: &Entry| e.index_entry.pack_offset;
+                let resolve_in_pack_id = |id: &git_hash::oid| self.lookup(id).map(|idx| self.pack_offset_at_index(idx));
+                let indexing_progress = progress.add_child_with_id("indexing", *b"PTDI"); /* Pack Traverse Delta Index creation */
+                let tree = if !pack.is_entire_file() {
+                    crate::cache::delta::Tree::from_offsets_in_pack_reader(
+                        std::io::Cursor::new(
+                            pack.entry_slice(0..pack.data_len() as u64)
+                                .expect("the entire pack is in bounds"),
+                        ),
+                        entries,
+                        get_pack_offset,
+                        resolve_in_pack_id,
+                        indexing_progress,
+                        should_interrupt,
+                        self.object_hash,
+                    )?
+                } else {
+                    crate::cache::delta::Tree::from_offsets_in_pack(
+                        pack.path(),
+                        entries,
+                        get_pack_offset,
+                        resolve_in_pack_id,
+                        indexing_progress,
+                        should_interrupt,
+                        self.object_hash,
+                    )?
+                };
+                let (tree_outcome, thread_tallies) = tree.traverse_with_thread_states(
+                    |slice, out| pack.entry_slice(slice).map(|entry| out.copy_from_slice(&entry)),
                     pack.pack_end() as u64,
-                    new_processor,
+                    move || (new_processor(), Tally::default()),
                     |data,
                      progress,
                      traverse::Context {
                          entry: pack_entry,
                          entry_end,
                          decompressed: bytes,
-                         state: ref mut processor,
+                         state: (ref mut processor, ref mut tally),
                          level,
                      }| {
                         let object_kind = pack_entry.header.as_kind().expect("non-delta object");
@@ -99,47 +178,79 @@ impl index::File {
                         data.object_kind = object_kind;
                         data.compressed_size = entry_end - pack_entry.data_offset;
                         data.object_size = bytes.len() as u64;
+                        // The entry we are given carries the kind of the resolved object, so obtain the original header.
+                        let stored_entry =
+                            pack.try_entry(data.index_entry.pack_offset)
+                                .map_err(|source| Error::PackDecode {
+                                    id: data.index_entry.oid,
+                                    offset: data.index_entry.pack_offset,
+                                    source,
+                                })?;
+                        let info = EntryInfo {
+                            storage: Storage::from_entry(&stored_entry),
+                            header_size: stored_entry.header_size() as u32,
+                            compressed_size: data.compressed_size,
+                            decompressed_size: stored_entry.decompressed_size,
+                            chain_length: level as u32,
+                        };
                         let result = crate::index::traverse::process_entry(
                             check,
                             object_kind,
                             bytes,
+                            tally,
                             progress,
                             &data.index_entry,
+                            &info,
                             || {
                                 // TODO: Fix this - we overwrite the header of 'data' which also changes the computed entry size,
                                 // causing index and pack to seemingly mismatch. This is surprising, and should be done differently.
                                 // debug_assert_eq!(&data.index_entry.pack_offset, &pack_entry.pack_offset());
-                                git_features::hash::crc32(
-                                    pack.entry_slice(data.index_entry.pack_offset..entry_end)
-                                        .expect("slice pointing into the pack (by now data is verified)"),
+                                pack.try_entry_crc32(
+                                    data.index_entry.pack_offset,
+                                    (entry_end - data.index_entry.pack_offset) as usize,
                                 )
                             },
                             processor,
                         );
                         match result {
+                            Ok(mismatch) => {
+                                data.index_oid_mismatch = mismatch;
+                                Ok(())
+                            }
                             Err(err @ Error::PackDecode { .. }) if !check.fatal_decode_error() => {
                                 progress.info(format!("Ignoring decode error: {}", err));
                                 Ok(())
                             }
-                            res => res,
+                            Err(err) => Err(err),
                         }
                     },
+                    |(_processor, tally)| tally,
                     crate::cache::delta::traverse::Options {
-                        object_progress: progress.add_child_with_id("Resolving", *b"PTRO"), /* Pack Traverse Resolve Objects */
-                        size_progress: progress.add_child_with_id("Decoding", *b"PTDB"), /* Pack Traverse Decode Bytes */
+                        object_progress: progress.add_child_with_id("decode objects", *b"PTRO"), /* Pack Traverse Resolve Objects */
+                        size_progress: progress.add_child_with_id("decode bytes", *b"PTDB"), /* Pack Traverse Decode Bytes */
                         thread_limit,
                         should_interrupt,
                         object_hash: self.object_hash,
+                        max_decompressed_object_size,
                     },
-                )?);
-                outcome.pack_size = pack.data_len() as u64;
-                Ok(outcome)
+                )?;
+                let mut statistics = digest_statistics(tree_outcome);
+                statistics.pack_size = pack.data_len() as u64;
+                let tally = thread_tallies.into_iter().fold(Tally::default(), |mut acc, tally| {
+                    acc.merge(tally);
+                    acc
+                });
+                Ok((statistics, tally))
             },
         );
+        let actual_index_checksum = verify_result?;
+        let (statistics, tally) = traversal_result?;
         Ok(Outcome {
-            actual_index_checksum: verify_result?,
-            statistics: traversal_result?,
-            progress,
+            actual_index_checksum,
+            statistics,
+            partition: None,
+            num_redecoded_bases: None,
+            tally,
         })
     }
 }
@@ -151,6 +262,7 @@ struct Entry {
     decompressed_size: u64,
     compressed_size: u64,
     level: u16,
+    index_oid_mismatch: Option<index::traverse::IndexOidMismatch>,
 }
 
 impl From<crate::index::Entry> for Entry {
@@ -162,23 +274,26 @@ impl From<crate::index::Entry> for Entry {
             object_size: 0,
             decompressed_size: 0,
             compressed_size: 0,
+            index_oid_mismatch: None,
         }
     }
 }
 
 fn digest_statistics(traverse::Outcome { roots, children }: traverse::Outcome<Entry>) -> index::traverse::Statistics {
     let mut res = index::traverse::Statistics::default();
-    let average = &mut res.average;
     for item in roots.iter().chain(children.iter()) {
         res.total_compressed_entries_size += item.data.compressed_size;
         res.total_decompressed_entries_size += item.data.decompressed_size;
         res.total_object_size += item.data.object_size;
         *res.objects_per_chain_length.entry(item.data.level as u32).or_insert(0) += 1;
 
-        average.decompressed_size += item.data.decompressed_size;
-        average.compressed_size += item.data.compressed_size as usize;
-        average.object_size += item.data.object_size;
-        average.num_deltas += item.data.level as u32;
+        res.average.decompressed_size += item.data.decompressed_size;
+        res.average.compressed_size += item.data.compressed_size as usize;
+        res.average.object_size += item.data.object_size;
+        res.average.num_deltas += item.data.level as u32;
+        if let Some(mismatch) = item.data.index_oid_mismatch {
+            res.record_index_oid_mismatch(mismatch);
+        }
         use git_object::Kind::*;
         match item.data.object_kind {
             Blob => res.num_blobs += 1,
@@ -189,10 +304,13 @@ fn digest_statistics(traverse::Outcome { roots, children }: traverse::Outcome<En
     }
 
     let num_nodes = roots.len() + children.len();
-    average.decompressed_size /= num_nodes as u64;
-    average.compressed_size /= num_nodes;
-    average.object_size /= num_nodes as u64;
-    average.num_deltas /= num_nodes as u32;
+    if num_nodes != 0 {
+        res.average.decompressed_size /= num_nodes as u64;
+        res.average.compressed_size /= num_nodes;
+        res.average.object_size /= num_nodes as u64;
+        res.average.num_deltas /= num_nodes as u32;
+    }
+    res.keep_first_index_oid_mismatches();
 
     res
 }
@@ -1,11 +1,11 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 use git_features::{
     parallel::{self, in_parallel_if},
     progress::{self, unit, Progress},
 };
 
-use super::{Error, Reducer};
+use super::{EntryBoundaryMismatch, EntryInfo, Error, IndexOidMismatch, Reducer, Tally};
 use crate::{data, index, index::util};
 
 /// Traversal options for [`traverse()`][crate::index::File::traverse_with_lookup()]
@@ -17,6 +17,16 @@ pub struct Options<F> {
     pub check: crate::index::traverse::SafetyCheck,
     /// A function to create a pack cache
     pub make_pack_lookup_cache: F,
+    /// If `Some`, fail if any object or delta would decompress to more than the given amount of bytes.
+    ///
+    /// Defaults to [`DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE`][crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE].
+    pub max_decompressed_object_size: Option<u64>,
+    /// If `Some`, only process the entries within the given partition of the pack, while still resolving delta bases
+    /// outside of it.
+    pub partition: Option<crate::index::traverse::Partition>,
+    /// If `true`, fail with [`Error::EntryOverlap`] if the compressed data of an entry extends past the offset of the entry
+    /// following it, instead of merely counting it in the [statistics][crate::index::traverse::Statistics::entry_overlaps].
+    pub strict_entry_boundaries: bool,
 }
 
 impl Default for Options<fn() -> crate::cache::Never> {
@@ -25,6 +35,9 @@ impl Default for Options<fn() -> crate::cache::Never> {
             check: Default::default(),
             thread_limit: None,
             make_pack_lookup_cache: || crate::cache::Never,
+            max_decompressed_object_size: Some(crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+            partition: None,
+            strict_entry_boundaries: false,
         }
     }
 }
@@ -43,14 +56,64 @@ impl index::File {
         &self,
         new_processor: impl Fn() -> Processor + Send + Clone,
         pack: &crate::data::File,
-        mut progress: P,
+        progress: &mut P,
+        should_interrupt: &AtomicBool,
+        options: Options<F>,
+    ) -> Result<Outcome, Error<E>>
+    where
+        P: Progress,
+        C: crate::cache::DecodeEntry,
+        E: std::error::Error + Send + Sync + 'static,
+        Processor: FnMut(
+            git_object::Kind,
+            &[u8],
+            &index::Entry,
+            &mut <P::SubProgress as Progress>::SubProgress,
+        ) -> Result<(), E>,
+        F: Fn() -> C + Send + Clone,
+    {
+        self.traverse_with_lookup_and_info(
+            move || {
+                let mut processor = new_processor();
+                move |kind,
+                      data: &[u8],
+                      entry: &index::Entry,
+                      _info: &EntryInfo,
+                      _tally: &mut Tally,
+                      progress: &mut <P::SubProgress as Progress>::SubProgress| {
+                    processor(kind, data, entry, progress)
+                }
+            },
+            pack,
+            progress,
+            should_interrupt,
+            options,
+            false,
+            false,
+        )
+    }
+
+    /// If `skip_unresolved_ref_deltas` is set, entries whose delta chain ends in a ref-delta base that isn't in this pack
+    /// aren't decoded and processed, but skipped.
+    /// The [`chain_length`][EntryInfo::chain_length] passed to processors is only determined if `with_chain_lengths` is set.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn traverse_with_lookup_and_info<P, C, Processor, E, F>(
+        &self,
+        new_processor: impl Fn() -> Processor + Send + Clone,
+        pack: &crate::data::File,
+        progress: &mut P,
         should_interrupt: &AtomicBool,
         Options {
             thread_limit,
             check,
             make_pack_lookup_cache,
+            max_decompressed_object_size,
+            partition,
+            strict_entry_boundaries,
         }: Options<F>,
-    ) -> Result<Outcome<P>, Error<E>>
+        skip_unresolved_ref_deltas: bool,
+        with_chain_lengths: bool,
+    ) -> Result<Outcome, Error<E>>
     where
         P: Progress,
         C: crate::cache::DecodeEntry,
@@ -59,22 +122,31 @@ impl index::File {
             git_object::Kind,
             &[u8],
             &index::Entry,
+            &EntryInfo,
+            &mut Tally,
             &mut <P::SubProgress as Progress>::SubProgress,
         ) -> Result<(), E>,
         F: Fn() -> C + Send + Clone,
     {
+        if let Some(partition) = partition.filter(|p| !p.is_valid()) {
+            return Err(Error::InvalidPartition {
+                index: partition.index,
+                count: partition.count,
+            });
+        }
+        let delta_result_size_mismatches = AtomicU32::new(0);
         let (verify_result, traversal_result) = parallel::join(
             {
                 let pack_progress = progress.add_child_with_id(
                     format!(
-                        "Hash of pack '{}'",
+                        "hash pack '{}'",
                         pack.path().file_name().expect("pack has filename").to_string_lossy()
                     ),
                     *b"PTHP", /* Pack Traverse Hash Pack bytes */
                 );
                 let index_progress = progress.add_child_with_id(
                     format!(
-                        "Hash of index '{}'",
+                        "hash index '{}'",
                         self.path.file_name().expect("index has filename").to_string_lossy()
                     ),
                     *b"PTHI", /* Pack Traverse Hash Index bytes */
@@ -88,18 +160,34 @@ impl index::File {
                 }
             },
             || {
-                let index_entries = util::index_entries_sorted_by_offset_ascending(
+                let all_index_entries = util::index_entries_sorted_by_offset_ascending(
                     self,
                     progress.add_child_with_id("collecting sorted index", *b"PTCE"),
                 ); /* Pack Traverse Collect sorted Entries */
+                let index_entries = match partition {
+                    Some(partition) => {
+                        let offsets = partition.pack_offsets(pack.pack_end() as u64);
+                        let start = all_index_entries.partition_point(|entry| entry.pack_offset < offsets.start);
+                        let end = all_index_entries.partition_point(|entry| entry.pack_offset < offsets.end);
+                        &all_index_entries[start..end]
+                    }
+                    None => &all_index_entries[..],
+                };
+                // Entries end where the next one in the entire pack starts, even if it's outside of our partition.
+                let next_pack_offset = |pack_offset: u64| {
+                    let next = all_index_entries.partition_point(|entry| entry.pack_offset <= pack_offset);
+                    all_index_entries
+                        .get(next)
+                        .map_or(pack.pack_end() as u64, |entry| entry.pack_offset)
+                };
 
                 let (chunk_size, thread_limit, available_cores) =
                     parallel::optimize_chunk_size_and_thread_limit(1000, Some(index_entries.len()), thread_limit, None);
                 let there_are_enough_entries_to_process = || index_entries.len() > chunk_size * available_cores;
                 let input_chunks = index_entries.chunks(chunk_size.max(chunk_size));
                 let reduce_progress = OwnShared::new(Mutable::new({
-                    let mut p = progress.add_child_with_id("Traversing", *b"PTRO"); /* Pack Traverse Resolve Objects */
-                    p.init(Some(self.num_objects() as usize), progress::count("objects"));
+                    let mut p = progress.add_child_with_id("decode objects", *b"PTRO"); /* Pack Traverse Resolve Objects */
+                    p.init(Some(index_entries.len()), progress::count("objects"));
                     p
                 }));
                 let state_per_thread = {
@@ -109,6 +197,7 @@ impl index::File {
                             make_pack_lookup_cache(),
                             new_processor(),
                             Vec::with_capacity(2048), // decode buffer
+                            Tally::default(),
                             lock(&reduce_progress)
                                 .add_child_with_id(format!("thread {}", index), git_features::progress::UNKNOWN), // per thread progress
                         )
@@ -121,8 +210,18 @@ impl index::File {
                     thread_limit,
                     state_per_thread,
                     |entries: &[index::Entry],
-                     (cache, ref mut processor, buf, progress)|
-                     -> Result<Vec<data::decode_entry::Outcome>, Error<_>> {
+                     (cache, ref mut processor, buf, tally, progress)|
+                     -> Result<
+                        (
+                            Vec<(
+                                data::decode_entry::Outcome,
+                                Option<IndexOidMismatch>,
+                                Option<EntryBoundaryMismatch>,
+                            )>,
+                            Tally,
+                        ),
+                        Error<_>,
+                    > {
                         progress.init(
                             Some(entries.len()),
                             Some(unit::dynamic(unit::Human::new(
@@ -138,30 +237,51 @@ impl index::File {
                                 pack,
                                 cache,
                                 buf,
+                                tally,
                                 progress,
                                 index_entry,
                                 processor,
+                                max_decompressed_object_size,
+                                next_pack_offset(index_entry.pack_offset),
+                                strict_entry_boundaries,
+                                with_chain_lengths,
                             );
                             progress.inc();
                             let stat = match result {
+                                Err(Error::PackDecode {
+                                    source: data::decode_entry::Error::DeltaBaseUnresolved(_),
+                                    ..
+                                }) if skip_unresolved_ref_deltas => continue,
                                 Err(err @ Error::PackDecode { .. }) if !check.fatal_decode_error() => {
                                     progress.info(format!("Ignoring decode error: {}", err));
+                                    if let Error::PackDecode {
+                                        source: data::decode_entry::Error::DeltaResultSizeMismatch { .. },
+                                        ..
+                                    } = err
+                                    {
+                                        delta_result_size_mismatches.fetch_add(1, Ordering::Relaxed);
+                                    }
                                     continue;
                                 }
                                 res => res,
                             }?;
                             stats.push(stat);
                         }
-                        Ok(stats)
+                        Ok((stats, std::mem::take(tally)))
                     },
                     Reducer::from_progress(reduce_progress, pack.data_len(), check, should_interrupt),
                 )
             },
         );
+        let actual_index_checksum = verify_result?;
+        let (mut statistics, tally) = traversal_result?;
+        statistics.delta_result_size_mismatches = delta_result_size_mismatches.into_inner();
         Ok(Outcome {
-            actual_index_checksum: verify_result?,
-            statistics: traversal_result?,
-            progress,
+            actual_index_checksum,
+            statistics,
+            partition,
+            num_redecoded_bases: None,
+            tally,
         })
     }
 }
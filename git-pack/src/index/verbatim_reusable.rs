@@ -0,0 +1,123 @@
+use git_hash::ObjectId;
+
+use crate::{data, index};
+
+/// Returned by [`index::File::verbatim_reusable()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The pack at '{}' declares {pack_objects} objects in its header, but its index at '{}' has {index_objects} entries", .pack_path.display(), .index_path.display())]
+    ObjectCountMismatch {
+        pack_objects: u32,
+        index_objects: u32,
+        pack_path: std::path::PathBuf,
+        index_path: std::path::PathBuf,
+    },
+    #[error(transparent)]
+    Io(#[from] data::decode_entry::Error),
+}
+
+/// A contiguous range of entries in a pack which can be copied verbatim.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    /// The pack offset of the first entry of the range, and the pack offset one past the last byte of its last entry.
+    pub pack_offsets: std::ops::Range<data::Offset>,
+    /// The amount of entries in the range.
+    pub num_objects: u32,
+}
+
+/// The result of [`index::File::verbatim_reusable()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plan {
+    /// The maximal ranges of entries which are wanted along with all of their delta bases, ordered by pack offset.
+    pub ranges: Vec<Range>,
+    /// The amount of entries in the pack.
+    pub num_objects: u32,
+    /// The amount of wanted objects which are contained in the pack.
+    pub num_wanted: u32,
+}
+
+impl Plan {
+    /// The amount of entries which can be copied verbatim, across all [ranges][Plan::ranges].
+    pub fn num_reusable_objects(&self) -> u32 {
+        self.ranges.iter().map(|r| r.num_objects).sum()
+    }
+
+    /// The amount of bytes which can be copied verbatim, across all [ranges][Plan::ranges].
+    pub fn num_reusable_bytes(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|r| r.pack_offsets.end - r.pack_offsets.start)
+            .sum()
+    }
+}
+
+impl index::File {
+    /// Determine which entries of `pack`, the pack of this index, could be copied verbatim into a pack containing the
+    /// objects in `wants`, which must be sorted, and return them as maximal ranges of consecutive entries.
+    ///
+    /// An entry is reusable if it is wanted and, if it is a delta, its base entry is reusable too, so entries
+    /// whose delta chain leaves the want set or the pack are not, nor are ref-deltas whose base is stored after them.
+    /// Note that offset-deltas copied as part of a range refer to their base by distance, which needs adjustment
+    /// if their base ends up in an earlier range that isn't written at the same distance.
+    pub fn verbatim_reusable(&self, pack: &data::File, wants: &[ObjectId]) -> Result<Plan, Error> {
+        debug_assert!(wants.windows(2).all(|w| w[0] <= w[1]), "wants must be sorted");
+        if pack.num_objects() != self.num_objects() {
+            return Err(Error::ObjectCountMismatch {
+                pack_objects: pack.num_objects(),
+                index_objects: self.num_objects(),
+                pack_path: pack.path().to_owned(),
+                index_path: self.path().to_owned(),
+            });
+        }
+        let mut entries: Vec<_> = self.iter().map(|e| (e.pack_offset, e.oid)).collect();
+        entries.sort_by_key(|e| e.0);
+        let position_of = |offset: data::Offset| entries.binary_search_by_key(&offset, |e| e.0).ok();
+
+        let mut reusable = vec![false; entries.len()];
+        let mut plan = Plan {
+            num_objects: self.num_objects(),
+            ..Default::default()
+        };
+        let pack_end = pack.pack_end() as data::Offset;
+        for (idx, (offset, id)) in entries.iter().enumerate() {
+            if wants.binary_search(id).is_err() {
+                continue;
+            }
+            plan.num_wanted += 1;
+            let entry = pack.try_entry(*offset)?;
+            let base_is_reusable = |base_offset: Option<data::Offset>| {
+                base_offset
+                    .and_then(position_of)
+                    .map_or(false, |position| reusable[position])
+            };
+            reusable[idx] = match entry.header {
+                data::entry::Header::OfsDelta { base_distance } => {
+                    base_is_reusable(Some(entry.base_pack_offset(base_distance)))
+                }
+                data::entry::Header::RefDelta { base_id } => {
+                    base_is_reusable(self.lookup(base_id).map(|base_idx| self.pack_offset_at_index(base_idx)))
+                }
+                _ => true,
+            };
+            if !reusable[idx] {
+                continue;
+            }
+
+            let end = entries.get(idx + 1).map_or(pack_end, |e| e.0);
+            match plan.ranges.last_mut() {
+                Some(range) if range.pack_offsets.end == *offset => {
+                    range.pack_offsets.end = end;
+                    range.num_objects += 1;
+                }
+                _ => plan.ranges.push(Range {
+                    pack_offsets: *offset..end,
+                    num_objects: 1,
+                }),
+            }
+        }
+        Ok(plan)
+    }
+}
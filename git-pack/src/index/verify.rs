@@ -1,7 +1,6 @@
 use std::sync::atomic::AtomicBool;
 
 use git_features::progress::Progress;
-use git_object::{bstr::ByteSlice, WriteTo};
 
 use crate::index;
 
@@ -15,6 +14,19 @@ pub mod integrity {
     pub enum Error {
         #[error("The fan at index {index} is out of order as it's larger then the following value.")]
         Fan { index: usize },
+        #[error("The entries {first} and {second} both start at pack offset {offset}, so the CRC32 of one of them covers no bytes")]
+        DuplicateOffset {
+            offset: u64,
+            first: git_hash::ObjectId,
+            second: git_hash::ObjectId,
+        },
+        #[error("The pack at '{}' declares {pack_objects} objects in its header, but its index at '{}' has {index_objects} entries", .pack_path.display(), .index_path.display())]
+        ObjectCountMismatch {
+            pack_objects: u32,
+            index_objects: u32,
+            pack_path: std::path::PathBuf,
+            index_path: std::path::PathBuf,
+        },
         #[error("{kind} object {id} could not be decoded")]
         ObjectDecode {
             source: git_object::decode::Error,
@@ -28,16 +40,73 @@ pub mod integrity {
             expected: BString,
             actual: BString,
         },
+        #[error("The ref-delta {entry_oid} at offset {offset} refers to base object {base_oid} which isn't contained in the pack")]
+        MissingDeltaBase {
+            entry_oid: git_hash::ObjectId,
+            base_oid: git_hash::ObjectId,
+            offset: u64,
+        },
     }
 
     /// Returned by [`index::File::verify_integrity()`][crate::index::File::verify_integrity()].
-    pub struct Outcome<P> {
+    pub struct Outcome {
         /// The computed checksum of the index which matched the stored one.
         pub actual_index_checksum: git_hash::ObjectId,
         /// The packs traversal outcome, if one was provided
         pub pack_traverse_statistics: Option<crate::index::traverse::Statistics>,
-        /// The provided progress instance.
-        pub progress: P,
+        /// All trees with entries that have problems as selected by [`Options::tree_checks`], sorted by object id.
+        pub tree_issues: Vec<TreeIssues>,
+        /// Information about how objects were decoded as per [`Options::verify_mode`].
+        pub decode_statistics: DecodeStatistics,
+        /// The ids of all ref-delta bases that aren't contained in the pack, sorted and without duplicates.
+        ///
+        /// It can only be non-empty if [`Options::keep_going`] is set.
+        pub missing_delta_bases: Vec<git_hash::ObjectId>,
+        /// The amount of objects that caused a warning of each class, like trees with [non-canonical entry modes][git_object::verify::WarningClass::NonCanonicalTreeEntryMode]
+        /// or [tree issues][Outcome::tree_issues], with classes that didn't occur being absent.
+        ///
+        /// Objects with more than one warning of the same class are counted once for that class.
+        pub warnings_by_class: WarningsByClass,
+        /// Information about the index file that was verified, or `None` if it was created from bytes.
+        pub index_file: Option<crate::verify::FileInfo>,
+        /// Information about the pack data file that was verified, or `None` if no pack was provided or if it was created from bytes.
+        pub pack_file: Option<crate::verify::FileInfo>,
+    }
+
+    /// Counts of objects by the way they were checked when decoding them as per [`Options::verify_mode`].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+    pub struct DecodeStatistics {
+        /// The amount of trees whose serialization was [found to be canonical][git_object::TreeRef::is_canonical()] by a
+        /// cheap scan, which proves they decode and re-encode without change.
+        pub fast_path: usize,
+        /// The amount of objects that had to be decoded, and re-encoded if the verify mode demands it.
+        pub slow_path: usize,
+    }
+
+    impl std::ops::AddAssign for DecodeStatistics {
+        fn add_assign(&mut self, rhs: Self) {
+            self.fast_path += rhs.fast_path;
+            self.slow_path += rhs.slow_path;
+        }
+    }
+
+    /// The amount of objects per class of warning, as found in [`Outcome::warnings_by_class`].
+    pub type WarningsByClass = std::collections::BTreeMap<git_object::verify::WarningClass, u64>;
+
+    /// Add the counts of `other` to the ones in `warnings`.
+    pub fn merge_warnings_by_class(warnings: &mut WarningsByClass, other: &WarningsByClass) {
+        for (class, count) in other {
+            *warnings.entry(*class).or_insert(0) += count;
+        }
+    }
+
+    /// A tree whose entries have problems that `git fsck` would report.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+    pub struct TreeIssues {
+        /// The id of the tree.
+        pub id: git_hash::ObjectId,
+        /// The problems found in its entries.
+        pub issues: Vec<git_object::tree::check::Issue>,
     }
 
     /// Additional options to define how the integrity should be verified.
@@ -51,6 +120,32 @@ pub mod integrity {
         pub thread_limit: Option<usize>,
         /// A function to create a pack cache
         pub make_pack_lookup_cache: F,
+        /// If `Some`, fail if any object or delta would decompress to more than the given amount of bytes.
+        ///
+        /// Defaults to [`DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE`][crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE].
+        pub max_decompressed_object_size: Option<u64>,
+        /// The problems with tree entries to look for if trees are decoded as per `verify_mode`.
+        ///
+        /// Trees with such problems are collected in [`Outcome::tree_issues`] instead of failing the verification.
+        /// Defaults to being lenient, which doesn't check for any of them.
+        pub tree_checks: git_object::tree::check::Options,
+        /// If `true`, ref-delta entries whose base object isn't contained in the pack, along with all entries depending on them,
+        /// are skipped instead of failing the verification with [`MissingDeltaBase`][Error::MissingDeltaBase].
+        /// The ids of all missing bases are collected in [`Outcome::missing_delta_bases`] instead.
+        ///
+        /// Note that the [`Lookup`][crate::index::traverse::Algorithm::Lookup] algorithm is used if there are missing bases.
+        /// Defaults to `false`.
+        pub keep_going: bool,
+        /// If `true`, fail with [`PackMutatedDuringVerification`][crate::index::traverse::Error::PackMutatedDuringVerification]
+        /// if the pack data file was changed on disk during the verification, see
+        /// [`traverse::Options::paranoid`][crate::index::traverse::Options::paranoid].
+        /// Defaults to `false`.
+        pub paranoid: bool,
+        /// If `true`, fail with [`EntryOverlap`][crate::index::traverse::Error::EntryOverlap] if the compressed data of an
+        /// entry extends into the next one, see
+        /// [`traverse::Options::strict_entry_boundaries`][crate::index::traverse::Options::strict_entry_boundaries].
+        /// Defaults to `false`.
+        pub strict_entry_boundaries: bool,
     }
 
     impl Default for Options<fn() -> crate::cache::Never> {
@@ -60,6 +155,11 @@ pub mod integrity {
                 traversal: Default::default(),
                 thread_limit: None,
                 make_pack_lookup_cache: || crate::cache::Never,
+                max_decompressed_object_size: Some(crate::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+                tree_checks: Default::default(),
+                keep_going: false,
+                paranoid: false,
+                strict_entry_boundaries: false,
             }
         }
     }
@@ -90,6 +190,40 @@ impl Default for Mode {
     }
 }
 
+/// Buffers to be reused by each thread for all entries it verifies, to avoid allocating them per entry.
+#[derive(Default)]
+struct Scratch {
+    /// Receives the problems found in a tree.
+    tree_issues: Vec<git_object::tree::check::Issue>,
+}
+
+/// The name of the [tally][index::traverse::Tally] counter for [`integrity::DecodeStatistics::fast_path`].
+const FAST_PATH: &str = "verify.decode.fast-path";
+/// The name of the [tally][index::traverse::Tally] counter for [`integrity::DecodeStatistics::slow_path`].
+const SLOW_PATH: &str = "verify.decode.slow-path";
+
+/// The name of the [tally][index::traverse::Tally] counter for objects with warnings of `class`.
+fn warning_counter(class: git_object::verify::WarningClass) -> String {
+    format!("verify.warning.{}", class)
+}
+
+fn warnings_by_class(tally: &index::traverse::Tally) -> integrity::WarningsByClass {
+    git_object::verify::WarningClass::ALL
+        .iter()
+        .map(|class| (*class, tally.count(&warning_counter(*class))))
+        .filter(|(_, count)| *count != 0)
+        .collect()
+}
+
+impl From<&index::traverse::Tally> for integrity::DecodeStatistics {
+    fn from(tally: &index::traverse::Tally) -> Self {
+        integrity::DecodeStatistics {
+            fast_path: tally.count(FAST_PATH) as usize,
+            slow_path: tally.count(SLOW_PATH) as usize,
+        }
+    }
+}
+
 /// Information to allow verifying the integrity of an index with the help of its corresponding pack.
 pub struct PackContext<'a, F> {
     /// The pack data file itself.
@@ -122,6 +256,15 @@ impl index::File {
         progress: impl Progress,
         should_interrupt: &AtomicBool,
     ) -> Result<git_hash::ObjectId, checksum::Error> {
+        if self.is_in_memory() {
+            return crate::verify::checksum_of_data(
+                &self.data,
+                self.index_checksum(),
+                self.object_hash,
+                progress,
+                should_interrupt,
+            );
+        }
         crate::verify::checksum_on_disk_or_mmap(
             self.path(),
             &self.data,
@@ -133,11 +276,19 @@ impl index::File {
     }
 
     /// The most thorough validation of integrity of both index file and the corresponding pack data file, if provided.
-    /// Returns the checksum of the index file, the traversal outcome and the given progress if the integrity check is successful.
+    /// Returns the checksum of the index file and the traversal outcome if the integrity check is successful.
+    ///
+    /// The index is validated structurally first, which fails with [`Fan`][integrity::Error::Fan] if the fan-out table
+    /// isn't ascending, and with [`DuplicateOffset`][integrity::Error::DuplicateOffset] if two entries start at the same offset.
     ///
     /// If `pack` is provided, it is expected (and validated to be) the pack belonging to this index.
+    /// Before anything else, the amount of objects declared in its header must match the amount of entries in this index,
+    /// or [`ObjectCountMismatch`][integrity::Error::ObjectCountMismatch] is returned.
     /// It will be used to validate internal integrity of the pack before checking each objects integrity
     /// is indeed as advertised via its SHA1 as stored in this index, as well as the CRC32 hash.
+    /// Object hashes are verified for every entry independently of the [`Mode`] and [traversal algorithm][index::traverse::Algorithm],
+    /// and all entries are checked before failing with [`IndexOidMismatch`][index::traverse::Error::IndexOidMismatch] if any
+    /// of them doesn't match.
     /// The last member of the Option is a function returning an implementation of [`crate::cache::DecodeEntry`] to be used if
     /// the [`index::traverse::Algorithm`] is `Lookup`.
     /// To set this to `None`, use `None::<(_, _, _, fn() -> crate::cache::Never)>`.
@@ -145,16 +296,20 @@ impl index::File {
     /// The `thread_limit` optionally specifies the amount of threads to be used for the [pack traversal][index::File::traverse()].
     /// `make_cache` is only used in case a `pack` is specified, use existing implementations in the [`crate::cache`] module.
     ///
-    /// # Tradeoffs
+    /// Ref-delta entries must have their base object in the same pack, which is validated by scanning all entry headers
+    /// before the traversal, failing with [`MissingDeltaBase`][integrity::Error::MissingDeltaBase] for the first entry
+    /// that doesn't unless [`keep_going`][integrity::Options::keep_going] is set.
     ///
-    /// The given `progress` is inevitably consumed if there is an error, which is a tradeoff chosen to easily allow using `?` in the
-    /// error case.
+    /// `progress` is only borrowed, which keeps it available to the caller even if an error is returned.
+    /// Its children are identified by stable ids: `PTHP` and `PTHI` for hashing the pack and index bytes, and `PTRO` for
+    /// decoding objects with one child per thread, along with `PTCE`, `PTDI` and `PTDB` for the steps specific to
+    /// the [traversal algorithm][index::traverse::Algorithm]. Without `pack`, only `PTHI` is created.
     pub fn verify_integrity<P, C, F>(
         &self,
         pack: Option<PackContext<'_, F>>,
-        mut progress: P,
+        progress: &mut P,
         should_interrupt: &AtomicBool,
-    ) -> Result<integrity::Outcome<P>, index::traverse::Error<index::verify::integrity::Error>>
+    ) -> Result<integrity::Outcome, index::traverse::Error<index::verify::integrity::Error>>
     where
         P: Progress,
         C: crate::cache::DecodeEntry,
@@ -165,6 +320,13 @@ impl index::File {
                 index: first_invalid,
             }));
         }
+        if let Some((offset, first, second)) = self.first_duplicate_offset() {
+            return Err(index::traverse::Error::Processor(integrity::Error::DuplicateOffset {
+                offset,
+                first,
+                second,
+            }));
+        }
 
         match pack {
             Some(PackContext {
@@ -175,34 +337,123 @@ impl index::File {
                         traversal,
                         thread_limit,
                         make_pack_lookup_cache,
+                        max_decompressed_object_size,
+                        tree_checks,
+                        keep_going,
+                        paranoid,
+                        strict_entry_boundaries,
                     },
-            }) => self
-                .traverse(
-                    pack,
-                    progress,
-                    should_interrupt,
-                    || {
-                        let mut encode_buf = Vec::with_capacity(2048);
-                        move |kind, data, index_entry, progress| {
-                            Self::verify_entry(verify_mode, &mut encode_buf, kind, data, index_entry, progress)
-                        }
-                    },
-                    index::traverse::Options {
-                        traversal,
-                        thread_limit,
-                        check: index::traverse::SafetyCheck::All,
-                        make_pack_lookup_cache,
-                    },
-                )
-                .map(|o| integrity::Outcome {
-                    actual_index_checksum: o.actual_index_checksum,
-                    pack_traverse_statistics: Some(o.statistics),
-                    progress: o.progress,
-                }),
+            }) => {
+                if pack.num_objects() != self.num_objects() {
+                    return Err(index::traverse::Error::Processor(
+                        integrity::Error::ObjectCountMismatch {
+                            pack_objects: pack.num_objects(),
+                            index_objects: self.num_objects(),
+                            pack_path: pack.path().to_owned(),
+                            index_path: self.path().to_owned(),
+                        },
+                    ));
+                }
+                let missing_bases = self.ref_deltas_with_missing_base(pack)?;
+                if let Some((entry, base_oid)) = missing_bases.first().filter(|_| !keep_going) {
+                    return Err(index::traverse::Error::Processor(integrity::Error::MissingDeltaBase {
+                        entry_oid: entry.oid,
+                        base_oid: *base_oid,
+                        offset: entry.pack_offset,
+                    }));
+                }
+                let tree_issues = std::sync::Mutex::new(Vec::new());
+                let new_processor = || {
+                    let mut scratch = Scratch::default();
+                    let tree_issues = &tree_issues;
+                    move |kind,
+                          data: &[u8],
+                          index_entry: &index::Entry,
+                          _info: &index::traverse::EntryInfo,
+                          tally: &mut index::traverse::Tally,
+                          _progress: &mut <P::SubProgress as Progress>::SubProgress| {
+                        Self::verify_entry(
+                            verify_mode,
+                            tree_checks,
+                            &mut scratch,
+                            kind,
+                            data,
+                            index_entry,
+                            tree_issues,
+                            tally,
+                        )
+                    }
+                };
+                let check = index::traverse::SafetyCheck::All;
+                let outcome = if missing_bases.is_empty() {
+                    self.traverse_with_info_inner(
+                        pack,
+                        progress,
+                        should_interrupt,
+                        new_processor,
+                        index::traverse::Options {
+                            traversal,
+                            thread_limit,
+                            check,
+                            make_pack_lookup_cache,
+                            max_decompressed_object_size,
+                            partition: None,
+                            paranoid,
+                            strict_entry_boundaries,
+                        },
+                        false,
+                    )
+                } else {
+                    index::traverse::fail_if_pack_changed(pack, paranoid, || {
+                        self.traverse_with_lookup_and_info(
+                            new_processor,
+                            pack,
+                            progress,
+                            should_interrupt,
+                            index::traverse::with_lookup::Options {
+                                thread_limit,
+                                check,
+                                make_pack_lookup_cache,
+                                max_decompressed_object_size,
+                                partition: None,
+                                strict_entry_boundaries,
+                            },
+                            true,
+                            false,
+                        )
+                    })
+                };
+                outcome.and_then(|o| {
+                    if o.statistics.index_oid_mismatches != 0 {
+                        return Err(index::traverse::Error::IndexOidMismatch {
+                            count: o.statistics.index_oid_mismatches,
+                            first: o.statistics.first_index_oid_mismatches,
+                        });
+                    }
+                    let mut tree_issues = tree_issues.into_inner().expect("no panic while holding the lock");
+                    tree_issues.sort();
+                    let mut missing_delta_bases: Vec<_> = missing_bases.into_iter().map(|(_, base)| base).collect();
+                    missing_delta_bases.sort();
+                    missing_delta_bases.dedup();
+                    for warning in &o.tally.warnings {
+                        progress.info(warning.clone());
+                    }
+                    Ok(integrity::Outcome {
+                        actual_index_checksum: o.actual_index_checksum,
+                        pack_traverse_statistics: Some(o.statistics),
+                        tree_issues,
+                        decode_statistics: (&o.tally).into(),
+                        missing_delta_bases,
+                        warnings_by_class: warnings_by_class(&o.tally),
+                        index_file: self.file_info(),
+                        pack_file: pack.file_info(),
+                    })
+                })
+            }
             None => self
                 .verify_checksum(
                     progress.add_child_with_id(
-                        "Sha1 of index",
+                        "hash index",
                         *b"PTHI", /* Pack Traverse Hash Index bytes (semantically the same as in branch above) */
                     ),
                     should_interrupt,
@@ -211,55 +462,178 @@ impl index::File {
                 .map(|id| integrity::Outcome {
                     actual_index_checksum: id,
                     pack_traverse_statistics: None,
-                    progress,
+                    tree_issues: Vec::new(),
+                    decode_statistics: Default::default(),
+                    missing_delta_bases: Vec::new(),
+                    warnings_by_class: Default::default(),
+                    index_file: self.file_info(),
+                    pack_file: None,
                 }),
         }
     }
 
+    /// Return the ids of all base objects of ref-delta entries in `pack` which aren't contained in this index,
+    /// sorted and without duplicates.
+    ///
+    /// Only the entry headers are read, which is much cheaper than decoding objects as part of a traversal.
+    /// Fails with [`PackDecode`][index::traverse::Error::PackDecode] if an entry header can't be read.
+    pub fn missing_ref_delta_bases(
+        &self,
+        pack: &crate::data::File,
+    ) -> Result<Vec<git_hash::ObjectId>, index::traverse::Error<integrity::Error>> {
+        let mut bases: Vec<_> = self
+            .ref_deltas_with_missing_base(pack)?
+            .into_iter()
+            .map(|(_, base)| base)
+            .collect();
+        bases.sort();
+        bases.dedup();
+        Ok(bases)
+    }
+
+    /// Return the lowest pack offset that more than one entry starts at, along with the ids of the first two of these entries,
+    /// or `None` if all offsets are unique.
+    ///
+    /// Pack entries can't overlap, so such an index is corrupt, and entries would seemingly be empty when deriving their size
+    /// from the offset of the next entry.
+    pub fn first_duplicate_offset(&self) -> Option<(crate::data::Offset, git_hash::ObjectId, git_hash::ObjectId)> {
+        let offset = self
+            .sorted_offsets()
+            .windows(2)
+            .find_map(|pair| (pair[0] == pair[1]).then(|| pair[0]))?;
+        let mut ids = self
+            .iter()
+            .filter_map(|entry| (entry.pack_offset == offset).then(|| entry.oid));
+        Some((offset, ids.next()?, ids.next()?))
+    }
+
+    /// Return all ref-delta entries in index order along with the id of their base object if it isn't contained in this index.
+    fn ref_deltas_with_missing_base(
+        &self,
+        pack: &crate::data::File,
+    ) -> Result<Vec<(index::Entry, git_hash::ObjectId)>, index::traverse::Error<integrity::Error>> {
+        let mut out = Vec::new();
+        for entry in self.iter() {
+            let header = pack
+                .try_entry(entry.pack_offset)
+                .map_err(|source| index::traverse::Error::PackDecode {
+                    id: entry.oid,
+                    offset: entry.pack_offset,
+                    source,
+                })?
+                .header;
+            match header {
+                crate::data::entry::Header::RefDelta { base_id } if self.lookup(base_id).is_none() => {
+                    out.push((entry, base_id))
+                }
+                _ => {}
+            }
+        }
+        Ok(out)
+    }
+
+    /// Like [`verify_integrity()`][index::File::verify_integrity()], but takes `progress` by value and returns it along with the
+    /// outcome, which means it is lost if there is an error.
+    #[deprecated(
+        note = "use `verify_integrity()` which only borrows `progress`, this method will be removed in the next release"
+    )]
+    pub fn verify_integrity_with_owned_progress<P, C, F>(
+        &self,
+        pack: Option<PackContext<'_, F>>,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(integrity::Outcome, P), index::traverse::Error<index::verify::integrity::Error>>
+    where
+        P: Progress,
+        C: crate::cache::DecodeEntry,
+        F: Fn() -> C + Send + Clone,
+    {
+        self.verify_integrity(pack, &mut progress, should_interrupt)
+            .map(|outcome| (outcome, progress))
+    }
+
     #[allow(clippy::too_many_arguments)]
-    fn verify_entry<P>(
+    fn verify_entry(
         verify_mode: Mode,
-        encode_buf: &mut Vec<u8>,
+        tree_checks: git_object::tree::check::Options,
+        Scratch { tree_issues: issues }: &mut Scratch,
         object_kind: git_object::Kind,
         buf: &[u8],
         index_entry: &index::Entry,
-        progress: &mut P,
-    ) -> Result<(), integrity::Error>
-    where
-        P: Progress,
-    {
+        tree_issues: &std::sync::Mutex<Vec<integrity::TreeIssues>>,
+        tally: &mut index::traverse::Tally,
+    ) -> Result<(), integrity::Error> {
         if let Mode::HashCrc32Decode | Mode::HashCrc32DecodeEncode = verify_mode {
             use git_object::Kind::*;
+            if object_kind == Tree && tree_checks != git_object::tree::check::Options::default() {
+                issues.clear();
+                git_object::TreeRef::check(buf, tree_checks, issues).map_err(|err| integrity::Error::ObjectDecode {
+                    source: err,
+                    kind: object_kind,
+                    id: index_entry.oid,
+                })?;
+                if !issues.is_empty() {
+                    let mut kinds: Vec<_> = issues.iter().map(|issue| issue.kind).collect();
+                    kinds.sort();
+                    kinds.dedup();
+                    for kind in kinds {
+                        tally.add(&warning_counter(kind.into()), 1);
+                    }
+                    // Trees like these can't be decoded or re-encoded without change, so there is nothing more to learn.
+                    tree_issues
+                        .lock()
+                        .expect("no panic while holding the lock")
+                        .push(integrity::TreeIssues {
+                            id: index_entry.oid,
+                            issues: std::mem::take(issues),
+                        });
+                    return Ok(());
+                }
+            }
+            if object_kind == Tree && git_object::TreeRef::is_canonical(buf) {
+                tally.add(FAST_PATH, 1);
+                return Ok(());
+            }
             match object_kind {
                 Tree | Commit | Tag => {
-                    let object = git_object::ObjectRef::from_bytes(object_kind, buf).map_err(|err| {
-                        integrity::Error::ObjectDecode {
-                            source: err,
-                            kind: object_kind,
-                            id: index_entry.oid,
+                    tally.add(SLOW_PATH, 1);
+                    let res = match verify_mode {
+                        Mode::HashCrc32DecodeEncode => git_object::verify::roundtrip(object_kind, buf),
+                        _ => git_object::ObjectRef::from_bytes(object_kind, buf)
+                            .map(|_| ())
+                            .map_err(Into::into),
+                    };
+                    match res {
+                        Ok(()) => {}
+                        Err(git_object::verify::roundtrip::Error::Decode(err)) => {
+                            return Err(integrity::Error::ObjectDecode {
+                                source: err,
+                                kind: object_kind,
+                                id: index_entry.oid,
+                            })
+                        }
+                        Err(git_object::verify::roundtrip::Error::Mismatch {
+                            normalization: Some(normalization),
+                            ..
+                        }) => {
+                            tally.add(&warning_counter(normalization.into()), 1);
+                            tally.warn(format!(
+                                "{} object {} would be cleaned up during re-serialization, {}",
+                                object_kind, index_entry.oid, normalization
+                            ));
                         }
-                    })?;
-                    if let Mode::HashCrc32DecodeEncode = verify_mode {
-                        encode_buf.clear();
-                        object
-                            .write_to(&mut *encode_buf)
-                            .expect("writing to a memory buffer never fails");
-                        if encode_buf.as_slice() != buf {
-                            let mut should_return_error = true;
-                            if let git_object::Kind::Tree = object_kind {
-                                if buf.as_bstr().find(b"100664").is_some() || buf.as_bstr().find(b"100640").is_some() {
-                                    progress.info(format!("Tree object {} would be cleaned up during re-serialization, replacing mode '100664|100640' with '100644'", index_entry.oid));
-                                    should_return_error = false
-                                }
-                            }
-                            if should_return_error {
-                                return Err(integrity::Error::ObjectEncodeMismatch {
-                                    kind: object_kind,
-                                    id: index_entry.oid,
-                                    expected: buf.into(),
-                                    actual: encode_buf.clone().into(),
-                                });
-                            }
+                        Err(git_object::verify::roundtrip::Error::Mismatch {
+                            kind,
+                            expected,
+                            actual,
+                            normalization: None,
+                        }) => {
+                            return Err(integrity::Error::ObjectEncodeMismatch {
+                                kind,
+                                id: index_entry.oid,
+                                expected,
+                                actual,
+                            })
                         }
                     }
                 }
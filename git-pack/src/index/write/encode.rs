@@ -78,10 +78,9 @@ pub(crate) fn write_to(
     out.write_all(pack_hash.as_slice())?;
 
     let bytes_written_without_trailer = out.bytes;
-    let mut out = out.inner.into_inner()?;
-    let index_hash: git_hash::ObjectId = out.hash.digest().into();
-    out.inner.write_all(index_hash.as_slice())?;
-    out.inner.flush()?;
+    let (mut out, index_hash) = out.inner.into_inner()?.into_inner_and_digest();
+    out.write_all(index_hash.as_slice())?;
+    out.flush()?;
 
     progress.inc();
     progress.show_throughput_with(
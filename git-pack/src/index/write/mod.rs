@@ -36,6 +36,8 @@ impl crate::index::File {
     ///
     /// * `kind` is the version of pack index to produce, use [`crate::index::Version::default()`] if in doubt.
     /// * `tread_limit` is used for a parallel tree traversal for obtaining object hashes with optimal performance.
+    /// * `max_decompressed_object_size`, if `Some`, fails the operation if any object or delta would decompress to more than
+    ///    the given amount of bytes when resolving deltas.
     /// * `root_progress` is the top-level progress to stay informed about the progress of this potentially long-running
     ///    computation.
     /// * `object_hash` defines what kind of object hash we write into the index file.
@@ -55,6 +57,7 @@ impl crate::index::File {
         make_resolver: F,
         entries: impl Iterator<Item = Result<crate::data::input::Entry, crate::data::input::Error>>,
         thread_limit: Option<usize>,
+        max_decompressed_object_size: Option<u64>,
         mut root_progress: impl Progress,
         out: impl io::Write,
         should_interrupt: &AtomicBool,
@@ -173,6 +176,7 @@ impl crate::index::File {
                     thread_limit,
                     should_interrupt,
                     object_hash,
+                    max_decompressed_object_size,
                 },
             )?;
             root_progress.inc();
@@ -221,14 +225,6 @@ impl crate::index::File {
 }
 
 fn modify_base(entry: &mut TreeEntry, pack_entry: &crate::data::Entry, decompressed: &[u8], hash: git_hash::Kind) {
-    fn compute_hash(kind: git_object::Kind, bytes: &[u8], object_hash: git_hash::Kind) -> git_hash::ObjectId {
-        let mut hasher = git_features::hash::hasher(object_hash);
-        hasher.update(&git_object::encode::loose_header(kind, bytes.len()));
-        hasher.update(bytes);
-        git_hash::ObjectId::from(hasher.digest())
-    }
-
     let object_kind = pack_entry.header.as_kind().expect("base object as source of iteration");
-    let id = compute_hash(object_kind, decompressed, hash);
-    entry.id = id;
+    entry.id = git_object::compute_hash(object_kind, decompressed, hash);
 }
@@ -18,6 +18,9 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
+///
+pub mod bitmap;
+
 ///
 pub mod bundle;
 /// A bundle of pack data and the corresponding pack index
@@ -28,6 +31,9 @@ pub struct Bundle {
     pub index: crate::index::File,
 }
 
+///
+pub mod bundle_file;
+
 ///
 pub mod find;
 
@@ -49,7 +55,11 @@ pub mod multi_index;
 pub mod verify;
 
 mod mmap {
-    use std::path::Path;
+    use std::{
+        ops::{Deref, Range},
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
 
     pub fn read_only(path: &Path) -> std::io::Result<memmap2::Mmap> {
         let file = std::fs::File::open(path)?;
@@ -59,6 +69,59 @@ mod mmap {
             memmap2::Mmap::map(&file)
         }
     }
+
+    /// The bytes backing an index or pack data file, shared among all of its clones.
+    #[derive(Clone)]
+    pub enum Data {
+        /// A memory map of a file on disk.
+        Mapped(Arc<memmap2::Mmap>),
+        /// Bytes which were handed to us and never existed on disk.
+        InMemory(Arc<Vec<u8>>),
+        /// A portion of other data, like a pack embedded in a git bundle file.
+        Slice { data: Box<Data>, range: Range<usize> },
+    }
+
+    impl Data {
+        pub fn is_in_memory(&self) -> bool {
+            match self {
+                Data::Mapped(_) => false,
+                Data::InMemory(_) => true,
+                Data::Slice { data, .. } => data.is_in_memory(),
+            }
+        }
+
+        pub fn is_entire_file(&self) -> bool {
+            matches!(self, Data::Mapped(_))
+        }
+    }
+
+    impl AsRef<[u8]> for Data {
+        fn as_ref(&self) -> &[u8] {
+            self
+        }
+    }
+
+    impl Deref for Data {
+        type Target = [u8];
+
+        fn deref(&self) -> &Self::Target {
+            match self {
+                Data::Mapped(map) => map,
+                Data::InMemory(bytes) => bytes,
+                Data::Slice { data, range } => &data[range.clone()],
+            }
+        }
+    }
+
+    /// Produce a path for in-memory files which is unique as it is based on the `checksum` of the file's content.
+    /// It isn't expected to exist on disk.
+    pub fn in_memory_path(checksum: &[u8], extension: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "<memory>/pack-{}.{}",
+            git_hash::oid::from_bytes_unchecked(checksum),
+            extension
+        ))
+    }
 }
 
 use std::convert::TryInto;
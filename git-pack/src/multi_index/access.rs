@@ -34,6 +34,10 @@ impl File {
     pub fn path(&self) -> &Path {
         &self.path
     }
+    /// Return information about the multi-index file we were loaded from.
+    pub fn file_info(&self) -> crate::verify::FileInfo {
+        crate::verify::FileInfo::from_path(&self.path, self.data.len())
+    }
     /// Returns the amount of indices stored in this multi-index file. It's the same as [File::index_names().len()][File::index_names()],
     /// and returned as one past the highest known index.
     pub fn num_indices(&self) -> PackIndex {
@@ -14,24 +14,50 @@ mod error {
             source: std::io::Error,
             path: std::path::PathBuf,
         },
-        #[error("{message}")]
-        Corrupt { message: &'static str },
-        #[error("Unsupported multi-index version: {version})")]
-        UnsupportedVersion { version: u8 },
-        #[error("Unsupported hash kind: {kind})")]
-        UnsupportedObjectHash { kind: u8 },
-        #[error(transparent)]
-        ChunkFileDecode(#[from] git_chunk::file::decode::Error),
-        #[error(transparent)]
-        MissingChunk(#[from] git_chunk::file::index::offset_by_kind::Error),
-        #[error(transparent)]
-        FileTooLarge(#[from] git_chunk::file::index::data_by_kind::Error),
-        #[error("The multi-pack fan doesn't have the correct size of 256 * 4 bytes")]
-        MultiPackFanSize,
-        #[error(transparent)]
-        PackNames(#[from] chunk::index_names::decode::Error),
-        #[error("multi-index chunk {:?} has invalid size: {message}", String::from_utf8_lossy(.id))]
-        InvalidChunkSize { id: git_chunk::Id, message: &'static str },
+        #[error("Multi-index file at '{path}' with {size} bytes is too small for even an empty multi-index")]
+        TooSmall { size: usize, path: std::path::PathBuf },
+        #[error("Multi-index file at '{path}' doesn't start with the 'MIDX' signature, but with {actual:?}")]
+        InvalidSignature {
+            actual: git_object::bstr::BString,
+            path: std::path::PathBuf,
+        },
+        #[error("{message} in multi-index file at '{path}'")]
+        Corrupt {
+            message: &'static str,
+            path: std::path::PathBuf,
+        },
+        #[error("Multi-index file at '{path}' has unsupported version {version}")]
+        UnsupportedVersion { version: u8, path: std::path::PathBuf },
+        #[error("Multi-index file at '{path}' uses unsupported hash kind {kind}")]
+        UnsupportedObjectHash { kind: u8, path: std::path::PathBuf },
+        #[error("The table of contents of multi-index file at '{}' is invalid: {err}", .path.display())]
+        ChunkFileDecode {
+            err: git_chunk::file::decode::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{err} of multi-index file at '{}'", .path.display())]
+        MissingChunk {
+            err: git_chunk::file::index::offset_by_kind::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{err} in multi-index file at '{}'", .path.display())]
+        FileTooLarge {
+            err: git_chunk::file::index::data_by_kind::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("The multi-pack fan of multi-index file at '{path}' doesn't have the correct size of 256 * 4 bytes")]
+        MultiPackFanSize { path: std::path::PathBuf },
+        #[error("The pack names of multi-index file at '{path}' are invalid")]
+        PackNames {
+            source: chunk::index_names::decode::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("multi-index chunk {:?} in file at '{}' has invalid size: {message}", String::from_utf8_lossy(.id), .path.display())]
+        InvalidChunkSize {
+            id: git_chunk::Id,
+            message: &'static str,
+            path: std::path::PathBuf,
+        },
     }
 }
 
@@ -61,27 +87,37 @@ impl TryFrom<&Path> for File {
                 + chunk::fanout::SIZE
                 + TRAILER_LEN
         {
-            return Err(Error::Corrupt {
-                message: "multi-index file is truncated and too short",
+            return Err(Error::TooSmall {
+                size: data.len(),
+                path: path.to_owned(),
             });
         }
 
         let (version, object_hash, num_chunks, num_indices) = {
             let (signature, data) = data.split_at(4);
             if signature != Self::SIGNATURE {
-                return Err(Error::Corrupt {
-                    message: "Invalid signature",
+                return Err(Error::InvalidSignature {
+                    actual: signature.into(),
+                    path: path.to_owned(),
                 });
             }
             let (version, data) = data.split_at(1);
             let version = match version[0] {
                 1 => Version::V1,
-                version => return Err(Error::UnsupportedVersion { version }),
+                version => {
+                    return Err(Error::UnsupportedVersion {
+                        version,
+                        path: path.to_owned(),
+                    })
+                }
             };
 
             let (object_hash, data) = data.split_at(1);
-            let object_hash = git_hash::Kind::try_from(object_hash[0])
-                .map_err(|unknown| Error::UnsupportedObjectHash { kind: unknown })?;
+            let object_hash =
+                git_hash::Kind::try_from(object_hash[0]).map_err(|unknown| Error::UnsupportedObjectHash {
+                    kind: unknown,
+                    path: path.to_owned(),
+                })?;
             let (num_chunks, data) = data.split_at(1);
             let num_chunks = num_chunks[0];
 
@@ -93,38 +129,67 @@ impl TryFrom<&Path> for File {
             (version, object_hash, num_chunks, num_indices)
         };
 
-        let chunks = git_chunk::file::Index::from_bytes(&data, Self::HEADER_LEN, num_chunks as u32)?;
+        let chunks = git_chunk::file::Index::from_bytes(&data, Self::HEADER_LEN, num_chunks as u32).map_err(|err| {
+            Error::ChunkFileDecode {
+                err,
+                path: path.to_owned(),
+            }
+        })?;
+        let missing_chunk = |err| Error::MissingChunk {
+            err,
+            path: path.to_owned(),
+        };
+        let data_by_id = |id| {
+            chunks.data_by_id(&data, id).map_err(|err| match err {
+                git_chunk::file::index::data_by_kind::Error::NotFound(err) => missing_chunk(err),
+                err => Error::FileTooLarge {
+                    err,
+                    path: path.to_owned(),
+                },
+            })
+        };
 
-        let index_names = chunks.data_by_id(&data, chunk::index_names::ID)?;
-        let index_names = chunk::index_names::from_bytes(index_names, num_indices)?;
+        let index_names = data_by_id(chunk::index_names::ID)?;
+        let index_names =
+            chunk::index_names::from_bytes(index_names, num_indices).map_err(|source| Error::PackNames {
+                source,
+                path: path.to_owned(),
+            })?;
 
-        let fan = chunks.data_by_id(&data, chunk::fanout::ID)?;
-        let fan = chunk::fanout::from_bytes(fan).ok_or(Error::MultiPackFanSize)?;
+        let fan = data_by_id(chunk::fanout::ID)?;
+        let fan = chunk::fanout::from_bytes(fan).ok_or_else(|| Error::MultiPackFanSize { path: path.to_owned() })?;
         let num_objects = fan[255];
 
-        let lookup = chunks.validated_usize_offset_by_id(chunk::lookup::ID, |offset| {
-            chunk::lookup::is_valid(&offset, object_hash, num_objects)
-                .then(|| offset)
-                .ok_or(Error::InvalidChunkSize {
-                    id: chunk::lookup::ID,
-                    message: "The chunk with alphabetically ordered object ids doesn't have the correct size",
-                })
-        })??;
-        let offsets = chunks.validated_usize_offset_by_id(chunk::offsets::ID, |offset| {
-            chunk::offsets::is_valid(&offset, num_objects)
-                .then(|| offset)
-                .ok_or(Error::InvalidChunkSize {
-                    id: chunk::offsets::ID,
-                    message: "The chunk with offsets into the pack doesn't have the correct size",
-                })
-        })??;
+        let lookup = chunks
+            .validated_usize_offset_by_id(chunk::lookup::ID, |offset| {
+                chunk::lookup::is_valid(&offset, object_hash, num_objects)
+                    .then(|| offset)
+                    .ok_or_else(|| Error::InvalidChunkSize {
+                        id: chunk::lookup::ID,
+                        message: "The chunk with alphabetically ordered object ids doesn't have the correct size",
+                        path: path.to_owned(),
+                    })
+            })
+            .map_err(missing_chunk)??;
+        let offsets = chunks
+            .validated_usize_offset_by_id(chunk::offsets::ID, |offset| {
+                chunk::offsets::is_valid(&offset, num_objects)
+                    .then(|| offset)
+                    .ok_or_else(|| Error::InvalidChunkSize {
+                        id: chunk::offsets::ID,
+                        message: "The chunk with offsets into the pack doesn't have the correct size",
+                        path: path.to_owned(),
+                    })
+            })
+            .map_err(missing_chunk)??;
         let large_offsets = chunks
             .validated_usize_offset_by_id(chunk::large_offsets::ID, |offset| {
                 chunk::large_offsets::is_valid(&offset)
                     .then(|| offset)
-                    .ok_or(Error::InvalidChunkSize {
+                    .ok_or_else(|| Error::InvalidChunkSize {
                         id: chunk::large_offsets::ID,
                         message: "The chunk with large offsets into the pack doesn't have the correct size",
+                        path: path.to_owned(),
                     })
             })
             .ok()
@@ -135,7 +200,8 @@ impl TryFrom<&Path> for File {
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Corrupt {
                 message:
-                    "Trailing checksum didn't have the expected size or there were unknown bytes after the checksum.",
+                    "Trailing checksum didn't have the expected size or there were unknown bytes after the checksum",
+                path: path.to_owned(),
             });
         }
 
@@ -39,13 +39,23 @@ pub mod integrity {
     }
 
     /// Returned by [`multi_index::File::verify_integrity()`][crate::multi_index::File::verify_integrity()].
-    pub struct Outcome<P> {
+    pub struct Outcome {
         /// The computed checksum of the multi-index which matched the stored one.
         pub actual_index_checksum: git_hash::ObjectId,
         /// The for each entry in [`index_names()`][super::File::index_names()] provide the corresponding pack traversal outcome.
         pub pack_traverse_statistics: Vec<crate::index::traverse::Statistics>,
-        /// The provided progress instance.
-        pub progress: P,
+        /// All trees with problematic entries in all packs, sorted by object id.
+        pub tree_issues: Vec<crate::index::verify::integrity::TreeIssues>,
+        /// Information about how objects were decoded, for all packs combined.
+        pub decode_statistics: crate::index::verify::integrity::DecodeStatistics,
+        /// The ids of all ref-delta bases missing in their pack, for all packs combined, sorted and without duplicates.
+        pub missing_delta_bases: Vec<git_hash::ObjectId>,
+        /// The amount of objects that caused a warning of each class, for all packs combined.
+        pub warnings_by_class: crate::index::verify::integrity::WarningsByClass,
+        /// Information about the multi-index file that was verified.
+        pub index_file: crate::verify::FileInfo,
+        /// For each entry in [`index_names()`][super::File::index_names()], information about the corresponding pack data file.
+        pub pack_files: Vec<Option<crate::verify::FileInfo>>,
     }
 }
 
@@ -78,9 +88,9 @@ impl File {
     /// Instead we only validate the contents of the multi-index itself.
     pub fn verify_integrity_fast<P>(
         &self,
-        progress: P,
+        progress: &mut P,
         should_interrupt: &AtomicBool,
-    ) -> Result<(git_hash::ObjectId, P), integrity::Error>
+    ) -> Result<git_hash::ObjectId, integrity::Error>
     where
         P: Progress,
     {
@@ -94,7 +104,7 @@ impl File {
             index::traverse::Error::Processor(err) => err,
             _ => unreachable!("BUG: no other error type is possible"),
         })
-        .map(|o| (o.actual_index_checksum, o.progress))
+        .map(|o| o.actual_index_checksum)
     }
 
     /// Similar to [`crate::Bundle::verify_integrity()`] but checks all contained indices and their packs.
@@ -102,10 +112,10 @@ impl File {
     /// Note that it's considered a failure if an index doesn't have a corresponding pack.
     pub fn verify_integrity<C, P, F>(
         &self,
-        progress: P,
+        progress: &mut P,
         should_interrupt: &AtomicBool,
         options: index::verify::integrity::Options<F>,
-    ) -> Result<integrity::Outcome<P>, index::traverse::Error<integrity::Error>>
+    ) -> Result<integrity::Outcome, index::traverse::Error<integrity::Error>>
     where
         P: Progress,
         C: crate::cache::DecodeEntry,
@@ -116,11 +126,11 @@ impl File {
 
     fn verify_integrity_inner<C, P, F>(
         &self,
-        mut progress: P,
+        progress: &mut P,
         should_interrupt: &AtomicBool,
         deep_check: bool,
         options: index::verify::integrity::Options<F>,
-    ) -> Result<integrity::Outcome<P>, index::traverse::Error<integrity::Error>>
+    ) -> Result<integrity::Outcome, index::traverse::Error<integrity::Error>>
     where
         P: Progress,
         C: crate::cache::DecodeEntry,
@@ -130,7 +140,16 @@ impl File {
 
         let actual_index_checksum = self
             .verify_checksum(
-                progress.add_child_with_id(format!("{}: checksum", self.path.display()), *b"MVCK"), /* Multiindex Verify ChecKsum */
+                progress.add_child_with_id(
+                    format!(
+                        "hash multi-index '{}'",
+                        self.path
+                            .file_name()
+                            .expect("multi-index has filename")
+                            .to_string_lossy()
+                    ),
+                    *b"MVCK", /* Multiindex Verify ChecKsum */
+                ),
                 should_interrupt,
             )
             .map_err(integrity::Error::from)
@@ -147,6 +166,11 @@ impl File {
         }
 
         let mut pack_traverse_statistics = Vec::new();
+        let mut tree_issues = Vec::new();
+        let mut decode_statistics = crate::index::verify::integrity::DecodeStatistics::default();
+        let mut missing_delta_bases = Vec::new();
+        let mut warnings_by_class = crate::index::verify::integrity::WarningsByClass::default();
+        let mut pack_files = Vec::new();
 
         let operation_start = Instant::now();
         let mut total_objects_checked = 0;
@@ -254,7 +278,12 @@ impl File {
                 let crate::bundle::verify::integrity::Outcome {
                     actual_index_checksum: _,
                     pack_traverse_outcome,
-                    progress: returned_progress,
+                    tree_issues: pack_tree_issues,
+                    decode_statistics: pack_decode_statistics,
+                    missing_delta_bases: pack_missing_delta_bases,
+                    warnings_by_class: pack_warnings_by_class,
+                    index_file: _,
+                    pack_file,
                 } = bundle
                     .verify_integrity(progress, should_interrupt, options.clone())
                     .map_err(|err| {
@@ -265,34 +294,43 @@ impl File {
                             Tree(err) => Tree(err),
                             TreeTraversal(err) => TreeTraversal(err),
                             PackDecode { id, offset, source } => PackDecode { id, offset, source },
+                            DeltaChainCycle { id, offset } => DeltaChainCycle { id, offset },
                             PackMismatch { expected, actual } => PackMismatch { expected, actual },
-                            PackObjectMismatch {
+                            IndexOidMismatch { count, first } => IndexOidMismatch { count, first },
+                            Crc32Mismatch {
                                 expected,
                                 actual,
                                 offset,
                                 kind,
-                            } => PackObjectMismatch {
+                            } => Crc32Mismatch {
                                 expected,
                                 actual,
                                 offset,
                                 kind,
                             },
-                            Crc32Mismatch {
-                                expected,
-                                actual,
+                            InvalidPartition { index, count } => InvalidPartition { index, count },
+                            PackMutatedDuringVerification { path } => PackMutatedDuringVerification { path },
+                            EntryOverlap {
                                 offset,
-                                kind,
-                            } => Crc32Mismatch {
-                                expected,
-                                actual,
+                                entry_size,
+                                available_size,
+                            } => EntryOverlap {
                                 offset,
-                                kind,
+                                entry_size,
+                                available_size,
                             },
                             Interrupted => Interrupted,
                         }
                     })?;
-                progress = returned_progress;
                 pack_traverse_statistics.push(pack_traverse_outcome);
+                tree_issues.extend(pack_tree_issues);
+                decode_statistics += pack_decode_statistics;
+                missing_delta_bases.extend(pack_missing_delta_bases);
+                crate::index::verify::integrity::merge_warnings_by_class(
+                    &mut warnings_by_class,
+                    &pack_warnings_by_class,
+                );
+                pack_files.push(pack_file);
             }
         }
 
@@ -304,10 +342,18 @@ impl File {
         progress.set_name("Validating multi-pack");
         progress.show_throughput(operation_start);
 
+        tree_issues.sort();
+        missing_delta_bases.sort();
+        missing_delta_bases.dedup();
         Ok(integrity::Outcome {
             actual_index_checksum,
             pack_traverse_statistics,
-            progress,
+            tree_issues,
+            decode_statistics,
+            missing_delta_bases,
+            warnings_by_class,
+            index_file: self.file_info(),
+            pack_files,
         })
     }
 }
@@ -1,4 +1,7 @@
-use std::{path::Path, sync::atomic::AtomicBool};
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use git_features::progress::Progress;
 
@@ -10,6 +13,8 @@ pub mod checksum {
     pub enum Error {
         #[error("Interrupted by user")]
         Interrupted,
+        #[error("Could not read file to compute its checksum")]
+        Io(#[from] std::io::Error),
         #[error("index checksum mismatch: expected {expected}, got {actual}")]
         Mismatch {
             expected: git_hash::ObjectId,
@@ -18,6 +23,33 @@ pub mod checksum {
     }
 }
 
+/// Information about a file on disk that was verified, to be able to tell which file an outcome refers to.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileInfo {
+    /// The path at which the file was opened.
+    pub path: PathBuf,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The time of the last modification of the file in seconds since the unix epoch, if it could be obtained.
+    pub modified: Option<u64>,
+}
+
+impl FileInfo {
+    /// Create a new instance for the file at `path` with the given `size`, and query its modification time.
+    pub(crate) fn from_path(path: &Path, size: usize) -> Self {
+        FileInfo {
+            path: path.to_owned(),
+            size: size as u64,
+            modified: std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+        }
+    }
+}
+
 /// Returns the `index` at which the following `index + 1` value is not an increment over the value at `index`.
 pub fn fan(data: &[u32]) -> Option<usize> {
     data.windows(2)
@@ -45,15 +77,38 @@ pub fn checksum_on_disk_or_mmap(
         should_interrupt,
     ) {
         Ok(id) => id,
-        Err(err) if err.kind() == std::io::ErrorKind::Interrupted => return Err(checksum::Error::Interrupted),
-        Err(_io_err) => {
-            let start = std::time::Instant::now();
-            let mut hasher = git_features::hash::hasher(object_hash);
-            hasher.update(&data[..data_len_without_trailer]);
-            progress.inc_by(data_len_without_trailer);
-            progress.show_throughput(start);
-            git_hash::ObjectId::from(hasher.digest())
-        }
+        Err(_) if should_interrupt.load(Ordering::Relaxed) => return Err(checksum::Error::Interrupted),
+        Err(_io_err) => return checksum_of_data(data, expected, object_hash, progress, should_interrupt),
+    };
+
+    if actual == expected {
+        Ok(actual)
+    } else {
+        Err(checksum::Error::Mismatch { actual, expected })
+    }
+}
+
+/// Calculate the hash of the given kind over the first `data_len` bytes of the file at `data_path` except for its trailing hash,
+/// without any fallback if it can't be read.
+pub fn checksum_on_disk(
+    data_path: &Path,
+    data_len: usize,
+    expected: git_hash::ObjectId,
+    object_hash: git_hash::Kind,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+) -> Result<git_hash::ObjectId, checksum::Error> {
+    let data_len_without_trailer = data_len - object_hash.len_in_bytes();
+    let actual = match git_features::hash::bytes_of_file(
+        data_path,
+        data_len_without_trailer,
+        object_hash,
+        &mut progress,
+        should_interrupt,
+    ) {
+        Ok(id) => id,
+        Err(_) if should_interrupt.load(Ordering::Relaxed) => return Err(checksum::Error::Interrupted),
+        Err(err) => return Err(err.into()),
     };
 
     if actual == expected {
@@ -62,3 +117,30 @@ pub fn checksum_on_disk_or_mmap(
         Err(checksum::Error::Mismatch { actual, expected })
     }
 }
+
+/// Calculate the hash of the given kind over all of `data` except for its trailing hash, without any disk access.
+/// This is useful for files that only exist in memory.
+/// `Ok(desired_hash)` or `Err(Some(actual_hash))` is returned if the hash matches or mismatches.
+pub fn checksum_of_data(
+    data: &[u8],
+    expected: git_hash::ObjectId,
+    object_hash: git_hash::Kind,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+) -> Result<git_hash::ObjectId, checksum::Error> {
+    let data_len_without_trailer = data.len() - object_hash.len_in_bytes();
+    let actual = git_features::hash::bytes(
+        &data[..data_len_without_trailer],
+        data_len_without_trailer,
+        object_hash,
+        &mut progress,
+        should_interrupt,
+    )
+    .map_err(|_reading_from_memory_only_fails_on_interrupt| checksum::Error::Interrupted)?;
+
+    if actual == expected {
+        Ok(actual)
+    } else {
+        Err(checksum::Error::Mismatch { actual, expected })
+    }
+}
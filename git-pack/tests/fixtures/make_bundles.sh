@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  for round in 1 2 3; do
+    echo "$round" > file
+    git add file
+    git commit -qm "commit $round"
+  done
+  git tag -m "an annotated tag" v1
+  git branch other HEAD~1
+
+  git bundle create -q ../complete.bundle --all
+  git bundle create -q ../incremental.bundle main~1..main
+  git bundle create -q --version=3 ../v3.bundle main
+
+  for bundle in complete incremental v3; do
+    git bundle list-heads ../$bundle.bundle > ../$bundle.heads
+  done
+  git rev-parse main~1 > ../incremental.prerequisite
+)
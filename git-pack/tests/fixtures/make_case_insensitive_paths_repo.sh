@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+# paths which are the same on case-insensitive filesystems, but distinct in trees
+mkdir Docs docs other
+echo upper > README
+echo lower > readme
+echo a > Docs/a
+echo b > docs/b
+echo o > other/o
+# 'cafe' with a combining acute accent (NFD), as some filesystems on MacOS write it
+nfd_cafe=$(printf 'cafe\xcc\x81')
+mkdir "$nfd_cafe"
+echo c > "$nfd_cafe/c"
+git add . && git commit -q -m one
+
+git rev-list main > commits
@@ -0,0 +1,5 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git pack-objects -q .git/objects/pack/pack </dev/null >/dev/null
@@ -0,0 +1,40 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo a > a && echo b > b && echo c > c
+git add . && git commit -q -m one
+git repack -adq
+
+pack_dir=.git/objects/pack
+idx=$(echo $pack_dir/*.idx)
+pack=${idx%.idx}.pack
+
+# a V2 index consists of an 8 byte header, a 1024 byte fan-out table, and a table each for ids, crc32s and 4 byte offsets.
+num_objects=$(od -An -tu4 --endian=big -j $((8 + 255 * 4)) -N4 "$idx" | tr -d ' ')
+offsets_start=$((8 + 1024 + num_objects * (20 + 4)))
+
+# find the entry with the smallest offset and the one following it in the pack
+sorted=$(od -An -v -tu4 --endian=big -w4 -j $offsets_start -N $((num_objects * 4)) "$idx" | awk '{ print $1, NR - 1 }' | sort -n)
+first_offset=$(echo "$sorted" | sed -n 1p | cut -d' ' -f1)
+first_index=$(echo "$sorted" | sed -n 1p | cut -d' ' -f2)
+second_offset=$(echo "$sorted" | sed -n 2p | cut -d' ' -f1)
+second_index=$(echo "$sorted" | sed -n 2p | cut -d' ' -f2)
+
+# let the second entry point to the first one, so the first entry seemingly covers zero bytes
+cp "$idx" duplicate-offset.idx
+cp "$pack" duplicate-offset.pack
+printf "$(printf '%08x' "$first_offset" | sed 's/../\\x&/g')" \
+  | dd of=duplicate-offset.idx bs=1 seek=$((offsets_start + second_index * 4)) conv=notrunc status=none
+
+# fix the trailing checksum so the index is only corrupt structurally
+head -c -20 duplicate-offset.idx > duplicate-offset.idx.tmp
+sha1sum duplicate-offset.idx.tmp | cut -d' ' -f1 | xxd -r -p >> duplicate-offset.idx.tmp
+mv duplicate-offset.idx.tmp duplicate-offset.idx
+
+id_at() {
+  od -An -v -tx1 -j $((8 + 1024 + $1 * 20)) -N20 "$idx" | tr -d ' \n'
+}
+echo "$first_offset $second_offset" > offsets
+echo "$(id_at $first_index) $(id_at $second_index)" > ids
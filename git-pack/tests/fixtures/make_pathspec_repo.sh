@@ -0,0 +1,36 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir -p services/api/v1 services/web docs lib/core lib/other
+echo a > services/api/a
+echo b > services/api/v1/b
+echo not-api > services/apiary
+echo w > services/web/w
+echo d > docs/d
+echo core > lib/core/c
+echo other > lib/other/o
+# an identical copy of 'lib' to have the same trees at included and excluded paths
+cp -R lib shared
+git add . && git commit -q -m one
+
+echo a2 > services/api/a
+echo w2 > services/web/w
+echo d2 > docs/d
+echo core2 > lib/core/c
+cp lib/core/c shared/core/c
+git commit -q -am two
+
+# a blob under an included path which is also present in an excluded path
+mkdir services/api/v2
+cp docs/d services/api/v2/d
+echo core3 > lib/core/c
+cp lib/core/c shared/core/c
+git add . && git commit -q -m three
+
+# every commit changes all paths so that 'git rev-list' doesn't simplify history
+git rev-list --objects main -- services/api | cut -c1-40 > services-api.objects
+git rev-list --objects main -- shared lib/core | cut -c1-40 > shared-lib-core.objects
+git rev-list main > commits
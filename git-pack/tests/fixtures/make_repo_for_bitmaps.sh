@@ -0,0 +1,32 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+function commit() {
+  local name=$1
+  mkdir -p "dir/$name"
+  echo "$name" > "dir/$name/file"
+  git add .
+  git commit -qm "$name"
+}
+
+for name in a b c d e; do commit $name; done
+git tag -a -m "annotated" v1
+git update-index --add --cacheinfo 160000,1111111111111111111111111111111111111111,submodule
+git commit -qm "submodule added"
+
+git checkout -q -b other main~2
+for name in f g h; do commit $name; done
+git tag lightweight
+
+git checkout -q main
+for name in i j; do commit $name; done
+git merge -q --no-ff -m "merge other" other
+for name in k l m n; do commit $name; done
+git tag -a -m "tag of a tree" tree-tag main^{tree}
+
+git repack -adbq
+mv .git/objects/pack/*.bitmap git.bitmap
+git for-each-ref --format='%(objectname)' > tips
@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir dir
+touch f dir/f
+git add f dir/f
+git update-index --add --cacheinfo 160000,1111111111111111111111111111111111111111,changed
+git update-index --add --cacheinfo 160000,2222222222222222222222222222222222222222,dir/removed
+git commit -qm 'files and submodules added'
+
+echo f > f
+git add f
+git update-index --cacheinfo 160000,3333333333333333333333333333333333333333,changed
+git update-index --force-remove dir/removed
+git update-index --add --cacheinfo 160000,4444444444444444444444444444444444444444,dir/added
+git commit -qm 'submodules added, changed and removed'
+
+git rev-list --objects main | cut -c1-40 > objects
+git rev-list main > commits
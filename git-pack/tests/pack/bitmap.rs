@@ -0,0 +1,175 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::AtomicBool,
+};
+
+use git_features::progress;
+use git_pack::bitmap;
+
+fn pack_idx_path(repo: &Path) -> crate::Result<PathBuf> {
+    Ok(std::fs::read_dir(repo.join(".git/objects/pack"))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .find(|p| p.extension().map_or(false, |ext| ext == "idx"))
+        .expect("one pack"))
+}
+
+fn tips(repo: &Path) -> crate::Result<Vec<git_hash::ObjectId>> {
+    Ok(std::fs::read_to_string(repo.join("tips"))?
+        .lines()
+        .map(|line| git_hash::ObjectId::from_hex(line.as_bytes()))
+        .collect::<Result<_, _>>()?)
+}
+
+fn git(repo: &Path, args: &[&str]) -> crate::Result<String> {
+    let out = Command::new("git").args(args).current_dir(repo).output()?;
+    assert!(
+        out.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(String::from_utf8(out.stdout)?)
+}
+
+/// Positions of all objects in pack order, i.e. sorted by pack offset.
+fn pack_order(index: &git_pack::index::File) -> Vec<git_hash::ObjectId> {
+    let mut entries: Vec<_> = index.iter().collect();
+    entries.sort_by_key(|e| e.pack_offset);
+    entries.into_iter().map(|e| e.oid).collect()
+}
+
+fn set_bits(bitmap: &git_bitmap::ewah::Vec) -> BTreeSet<usize> {
+    let mut bits = BTreeSet::new();
+    bitmap.for_each_set_bit(|bit| {
+        bits.insert(bit);
+        Some(())
+    });
+    bits
+}
+
+fn reachable_by_git(
+    repo: &Path,
+    commit: &git_hash::oid,
+    pack_order: &[git_hash::ObjectId],
+) -> crate::Result<BTreeSet<usize>> {
+    Ok(git(repo, &["rev-list", "--objects", &commit.to_string()])?
+        .lines()
+        .map(|line| {
+            let id = git_hash::ObjectId::from_hex(&line.as_bytes()[..40]).expect("valid hex");
+            pack_order.iter().position(|o| *o == id).expect("all objects in pack")
+        })
+        .collect())
+}
+
+fn assert_bitmaps_match_git(file: &bitmap::File, repo: &Path, index: &git_pack::index::File) -> crate::Result {
+    let pack_order = pack_order(index);
+    assert_eq!(
+        file.pack_checksum(),
+        index.pack_checksum(),
+        "the bitmap belongs to the pack"
+    );
+    for (entry_index, entry) in file.entries().iter().enumerate() {
+        let commit = index.oid_at_index(entry.index_position);
+        assert_eq!(file.lookup(entry.index_position), Some(entry_index));
+        assert_eq!(
+            set_bits(&file.reachable_at(entry_index)),
+            reachable_by_git(repo, commit, &pack_order)?,
+            "reachable objects of {} match",
+            commit
+        );
+    }
+    for kind in [
+        git_object::Kind::Commit,
+        git_object::Kind::Tree,
+        git_object::Kind::Blob,
+        git_object::Kind::Tag,
+    ] {
+        let expected: BTreeSet<_> = git(
+            repo,
+            &[
+                "cat-file",
+                "--batch-all-objects",
+                "--batch-check=%(objectname) %(objecttype)",
+            ],
+        )?
+        .lines()
+        .filter(|line| line.split(' ').nth(1) == Some(kind.to_string().as_str()))
+        .map(|line| {
+            let id = git_hash::ObjectId::from_hex(&line.as_bytes()[..40]).expect("valid hex");
+            pack_order.iter().position(|o| *o == id).expect("all objects in pack")
+        })
+        .collect();
+        assert_eq!(set_bits(file.kind_bitmap(kind)), expected, "{} bitmap matches", kind);
+    }
+    Ok(())
+}
+
+#[test]
+fn bitmaps_written_by_git_can_be_read() -> crate::Result {
+    let repo = git_testtools::scripted_fixture_repo_read_only("make_repo_for_bitmaps.sh")?;
+    let index = git_pack::index::File::at(pack_idx_path(&repo)?, git_hash::Kind::Sha1)?;
+    let file = bitmap::File::at(repo.join("git.bitmap"), git_hash::Kind::Sha1)?;
+
+    assert_eq!(file.version(), bitmap::Version::V1);
+    assert_ne!(file.flags() & bitmap::FLAG_FULL_DAG, 0);
+    assert!(file.num_entries() > 0, "git selects at least the tips");
+    assert_bitmaps_match_git(&file, &repo, &index)
+}
+
+#[test]
+fn write_from_bundle_is_understood_by_git_and_round_trips() -> crate::Result {
+    let repo = git_testtools::scripted_fixture_repo_writable("make_repo_for_bitmaps.sh")?;
+    let repo = repo.path();
+    let index_path = pack_idx_path(repo)?;
+    let bundle = git_pack::Bundle::at(&index_path, git_hash::Kind::Sha1)?;
+    let bitmap_path = index_path.with_extension("bitmap");
+
+    for (sample_interval, expected_num_bitmaps) in [(0, 3), (3, 8)] {
+        let outcome = bitmap::File::write_from_bundle(
+            &bundle,
+            tips(repo)?,
+            std::fs::File::create(&bitmap_path)?,
+            progress::Discard,
+            &AtomicBool::new(false),
+            bitmap::write::Options { sample_interval },
+        )?;
+        assert_eq!(
+            outcome.num_bitmaps,
+            expected_num_bitmaps,
+            "peeled tips are deduplicated, tags of trees don't count, and samples are taken from {} commits",
+            git(repo, &["rev-list", "--count", "--all"])?.trim()
+        );
+
+        let file = bitmap::File::at(&bitmap_path, git_hash::Kind::Sha1)?;
+        assert_eq!(file.num_entries(), outcome.num_bitmaps);
+        assert_eq!(file.checksum(), outcome.bitmap_checksum);
+        let data = std::fs::read(&bitmap_path)?;
+        assert_eq!(
+            git_features::hash::bytes_of_file(
+                &bitmap_path,
+                data.len() - 20,
+                git_hash::Kind::Sha1,
+                &mut progress::Discard,
+                &AtomicBool::new(false)
+            )?,
+            outcome.bitmap_checksum,
+            "the trailer is the checksum of everything before it"
+        );
+        assert_bitmaps_match_git(&file, repo, &bundle.index)?;
+
+        for args in [
+            &["rev-list", "--count", "--all"][..],
+            &["rev-list", "--count", "--objects", "--all"],
+            &["rev-list", "--count", "main"],
+            &["rev-list", "--count", "--objects", "other"],
+        ] {
+            let mut with_bitmap = vec!["rev-list", "--use-bitmap-index"];
+            with_bitmap.extend_from_slice(&args[1..]);
+            assert_eq!(git(repo, &with_bitmap)?, git(repo, args)?, "{:?}", args);
+        }
+        git(repo, &["rev-list", "--test-bitmap", "main"])?;
+    }
+    Ok(())
+}
@@ -75,6 +75,45 @@ mod locate {
     }
 }
 
+mod at_with_warnings {
+    use git_odb::pack;
+
+    use crate::{
+        fixture_path,
+        pack::{PACK_FOR_INDEX_V2, SMALL_PACK, SMALL_PACK_INDEX},
+    };
+
+    #[test]
+    fn matching_pack_and_index_have_no_warnings() -> Result<(), Box<dyn std::error::Error>> {
+        let (_bundle, warnings) = pack::Bundle::at_with_warnings(fixture_path(SMALL_PACK), git_hash::Kind::Sha1)?;
+        assert!(warnings.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn object_count_mismatches_are_warnings() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let index_path = dir.path().join("pack-mismatch.idx");
+        std::fs::copy(fixture_path(SMALL_PACK_INDEX), &index_path)?;
+        std::fs::copy(fixture_path(PACK_FOR_INDEX_V2), index_path.with_extension("pack"))?;
+
+        let (bundle, warnings) = pack::Bundle::at_with_warnings(&index_path, git_hash::Kind::Sha1)?;
+        assert_eq!(
+            warnings,
+            vec![pack::bundle::init::Warning::ObjectCountMismatch {
+                pack_objects: 30,
+                index_objects: 42
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "The pack declares 30 objects in its header, but its index has 42 entries"
+        );
+        assert_eq!(bundle.pack.num_objects(), 30, "the bundle can still be used");
+        Ok(())
+    }
+}
+
 mod write_to_directory {
     use std::{fs, path::Path, sync::atomic::AtomicBool};
 
@@ -142,6 +181,31 @@ mod write_to_directory {
         Ok(())
     }
 
+    #[test]
+    fn a_failure_to_install_the_index_leaves_nothing_behind() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let pack_hash = expected_outcome()?.index.data_hash;
+        let index_dir = dir.path().join(format!("pack-{}.idx", pack_hash));
+        fs::create_dir(&index_dir)?;
+        fs::write(index_dir.join("file"), b"")?;
+
+        let err = write_pack(Some(&dir), SMALL_PACK).expect_err("the index can't replace a directory");
+        let err = err.downcast_ref::<pack::bundle::write::Error>().expect("a write error");
+        assert!(
+            matches!(err, pack::bundle::write::Error::Install(err) if err.installed().is_empty()),
+            "the pack was removed again"
+        );
+        let entries = fs::read_dir(&dir)?
+            .map(|e| e.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            entries,
+            vec![index_dir],
+            "neither the pack, nor the keep file or temporary files are left"
+        );
+        Ok(())
+    }
+
     fn file_name(entry: &fs::DirEntry) -> String {
         entry.path().file_name().unwrap().to_str().unwrap().to_owned()
     }
@@ -164,8 +228,123 @@ mod write_to_directory {
                 iteration_mode: pack::data::input::Mode::Verify,
                 index_version: pack::index::Version::V2,
                 object_hash: git_hash::Kind::Sha1,
+                max_decompressed_object_size: None,
+                durability: Default::default(),
             },
         )
         .map_err(Into::into)
     }
 }
+
+mod install {
+    use std::{fs, path::Path};
+
+    use git_odb::pack::{self, bundle::install};
+    use tempfile::TempDir;
+
+    use crate::hex_to_id;
+
+    const PACK_HASH: &str = "0f3ea84cd1bba10c2a03d736a460635082833e59";
+
+    fn temp_files(dir: &Path, rev: bool) -> std::io::Result<install::Files> {
+        let tmp = dir.join("tmp");
+        fs::create_dir_all(&tmp)?;
+        let write = |name: &str| {
+            let path = tmp.join(name);
+            fs::write(&path, name).map(|_| path)
+        };
+        Ok(install::Files {
+            data: write("data")?,
+            index: write("index")?,
+            rev: rev.then(|| write("rev")).transpose()?,
+        })
+    }
+
+    fn destination(dir: &Path, extension: &str) -> std::path::PathBuf {
+        dir.join(format!("pack-{}.{}", PACK_HASH, extension))
+    }
+
+    #[test]
+    fn moves_all_files_into_place() -> Result<(), Box<dyn std::error::Error>> {
+        for durability in [install::Durability::Relaxed, install::Durability::Fsync] {
+            let dir = TempDir::new()?;
+            let files = temp_files(dir.path(), true)?;
+            let outcome = pack::bundle::install(dir.path(), &hex_to_id(PACK_HASH), files.clone(), durability)?;
+            assert_eq!(
+                outcome,
+                install::Outcome {
+                    data_path: destination(dir.path(), "pack"),
+                    index_path: destination(dir.path(), "idx"),
+                    rev_path: Some(destination(dir.path(), "rev")),
+                }
+            );
+            assert_eq!(fs::read(&outcome.data_path)?, b"data");
+            assert_eq!(fs::read(&outcome.index_path)?, b"index");
+            assert_eq!(fs::read(outcome.rev_path.expect("set"))?, b"rev");
+            for tmp in [files.data, files.index, files.rev.expect("set")] {
+                assert!(!tmp.exists(), "temporary files were moved");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn a_failure_to_move_the_index_removes_the_moved_files_again() -> Result<(), Box<dyn std::error::Error>> {
+        for durability in [install::Durability::Relaxed, install::Durability::Fsync] {
+            let dir = TempDir::new()?;
+            let files = temp_files(dir.path(), true)?;
+            fs::create_dir(destination(dir.path(), "idx"))?;
+            fs::write(destination(dir.path(), "idx").join("file"), b"")?;
+
+            let err = pack::bundle::install(dir.path(), &hex_to_id(PACK_HASH), files.clone(), durability)
+                .expect_err("the index can't replace a non-empty directory");
+            assert!(matches!(err, install::Error::Rename { ref to, .. } if *to == destination(dir.path(), "idx")));
+            assert!(err.installed().is_empty(), "everything could be removed");
+            assert!(!destination(dir.path(), "pack").exists(), "the pack was removed");
+            assert!(
+                !destination(dir.path(), "rev").exists(),
+                "the reverse index was removed"
+            );
+            assert!(files.index.is_file(), "the index remains at its temporary location");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn a_failure_to_move_the_pack_moves_nothing() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let files = temp_files(dir.path(), false)?;
+        fs::create_dir(destination(dir.path(), "pack"))?;
+        fs::write(destination(dir.path(), "pack").join("file"), b"")?;
+
+        let err = pack::bundle::install(
+            dir.path(),
+            &hex_to_id(PACK_HASH),
+            files.clone(),
+            install::Durability::default(),
+        )
+        .expect_err("the pack can't replace a non-empty directory");
+        assert!(err.installed().is_empty());
+        assert!(!destination(dir.path(), "idx").exists(), "the index wasn't moved");
+        assert!(files.data.is_file() && files.index.is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn files_that_existed_before_are_kept_on_failure() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let files = temp_files(dir.path(), false)?;
+        fs::write(destination(dir.path(), "pack"), b"existing")?;
+        fs::create_dir(destination(dir.path(), "idx"))?;
+        fs::write(destination(dir.path(), "idx").join("file"), b"")?;
+
+        pack::bundle::install(dir.path(), &hex_to_id(PACK_HASH), files, install::Durability::default())
+            .expect_err("the index can't replace a non-empty directory");
+        assert_eq!(
+            fs::read(destination(dir.path(), "pack"))?,
+            b"data",
+            "the pack with the same name was replaced, but is not removed as it existed before"
+        );
+        Ok(())
+    }
+}
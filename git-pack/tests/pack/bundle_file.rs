@@ -0,0 +1,202 @@
+use std::{path::Path, process::Command, sync::atomic::AtomicBool};
+
+use bstr::ByteSlice;
+use git_features::progress;
+use git_odb::pack::{self, bundle_file};
+
+use crate::{hex_to_id, scripted_fixture_repo_read_only};
+
+fn fixture(name: &str) -> crate::Result<bundle_file::File> {
+    Ok(bundle_file::File::at(
+        scripted_fixture_repo_read_only("make_bundles.sh")?.join(name),
+    )?)
+}
+
+/// Render the references of `header` like `git bundle list-heads`.
+fn list_heads(header: &bundle_file::Header) -> String {
+    header
+        .refs
+        .iter()
+        .map(|r| format!("{} {}\n", r.target, r.name))
+        .collect()
+}
+
+fn git_bundle_verify(repo: &Path, bundle: &Path) -> crate::Result<bool> {
+    Ok(Command::new("git")
+        .args(["bundle", "verify", "-q"])
+        .arg(bundle)
+        .current_dir(repo)
+        .output()?
+        .status
+        .success())
+}
+
+#[test]
+fn headers_written_by_git_can_be_read() -> crate::Result {
+    let dir = scripted_fixture_repo_read_only("make_bundles.sh")?;
+    for (name, version, num_prerequisites) in [
+        ("complete", bundle_file::Version::V2, 0),
+        ("incremental", bundle_file::Version::V2, 1),
+        ("v3", bundle_file::Version::V3, 0),
+    ] {
+        let bundle = fixture(&format!("{}.bundle", name))?;
+        let header = bundle.header();
+        assert_eq!(header.version, version, "{}", name);
+        assert_eq!(header.object_hash, git_hash::Kind::Sha1);
+        assert_eq!(header.prerequisites.len(), num_prerequisites, "{}", name);
+        assert_eq!(
+            list_heads(header),
+            std::fs::read_to_string(dir.join(format!("{}.heads", name)))?,
+            "{}: references are the same as seen by git",
+            name
+        );
+        assert_eq!(
+            &bundle.pack_data()[..4],
+            b"PACK",
+            "{}: the pack follows the header",
+            name
+        );
+    }
+
+    let incremental = fixture("incremental.bundle")?;
+    let prerequisite = &incremental.header().prerequisites[0];
+    assert_eq!(
+        prerequisite.id,
+        hex_to_id(std::fs::read_to_string(dir.join("incremental.prerequisite"))?.trim())
+    );
+    assert_eq!(prerequisite.comment, "commit 2");
+
+    let v3 = fixture("v3.bundle")?;
+    assert_eq!(
+        v3.header().capabilities,
+        vec![bundle_file::Capability {
+            name: "object-format".into(),
+            value: Some("sha1".into()),
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn embedded_packs_can_be_used_and_indexed_directly() -> crate::Result {
+    for name in ["complete.bundle", "incremental.bundle", "v3.bundle"] {
+        let bundle = fixture(name)?;
+        let pack = bundle.pack()?;
+        assert_eq!(pack.path(), bundle.path(), "the pack is identified by its bundle");
+        assert_eq!(pack.data_len(), bundle.pack_data().len());
+        pack.verify_checksum(progress::Discard, &AtomicBool::new(false))?;
+        assert_eq!(pack.streaming_iter()?.count(), pack.num_objects() as usize);
+
+        let dir = tempfile::tempdir()?;
+        let outcome = pack::Bundle::write_to_directory(
+            bundle.pack_data(),
+            Some(dir.path()),
+            progress::Discard,
+            &AtomicBool::new(false),
+            None,
+            Default::default(),
+        )?;
+        let indexed = outcome.to_bundle().expect("written to directory")?;
+        assert_eq!(indexed.index.num_objects(), pack.num_objects(), "{}", name);
+    }
+    Ok(())
+}
+
+#[test]
+fn written_bundles_are_identical_to_the_ones_of_git_and_verify_with_git() -> crate::Result {
+    let dir = scripted_fixture_repo_read_only("make_bundles.sh")?;
+    let out_dir = tempfile::tempdir()?;
+    for name in ["complete.bundle", "incremental.bundle", "v3.bundle"] {
+        let bundle = fixture(name)?;
+        let mut out = Vec::new();
+        let pack_len = bundle_file::write(bundle.header(), bundle.pack_data(), &mut out)?;
+        assert_eq!(pack_len as usize, bundle.pack_data().len());
+        assert_eq!(
+            out.as_bstr(),
+            std::fs::read(bundle.path())?.as_bstr(),
+            "{}: round-trips exactly",
+            name
+        );
+
+        let written = bundle_file::File::from_bytes(out.clone())?;
+        assert_eq!(written.header(), bundle.header());
+        assert!(written.pack()?.is_in_memory());
+
+        let path = out_dir.path().join(name);
+        std::fs::write(&path, &out)?;
+        assert!(
+            git_bundle_verify(&dir.join("repo"), &path)?,
+            "{}: git accepts our bundle",
+            name
+        );
+    }
+
+    let complete = fixture("complete.bundle")?;
+    let mut header = bundle_file::Header::new(complete.header().refs.clone(), Vec::new(), git_hash::Kind::Sha1);
+    assert_eq!(
+        header.version,
+        bundle_file::Version::V2,
+        "SHA1 bundles are written in the most compatible version"
+    );
+    header.version = bundle_file::Version::V3;
+    let path = out_dir.path().join("v3-from-v2.bundle");
+    bundle_file::write(&header, complete.pack_data(), std::fs::File::create(&path)?)?;
+    assert_eq!(
+        bundle_file::File::at(&path)?.header().capabilities.len(),
+        1,
+        "the object format is added"
+    );
+    assert!(git_bundle_verify(&dir.join("repo"), &path)?);
+    Ok(())
+}
+
+#[test]
+fn invalid_headers_and_bundles_without_refs_are_rejected() {
+    for (input, expected) in [
+        (&b"# v4 git bundle\n\nPACK"[..], "Expected a bundle signature"),
+        (b"# v2 git bundle\n", "The header ended"),
+        (
+            b"# v2 git bundle\n@object-format=sha1\n\n",
+            "Capabilities are only allowed",
+        ),
+        (
+            b"# v3 git bundle\n@object-format=sha256\n\n",
+            "Object format \"sha256\"",
+        ),
+        (b"# v2 git bundle\n-abc\n\n", "Prerequisite line"),
+        (
+            b"# v2 git bundle\n0000000000000000000000000000000000000000\n\n",
+            "Reference line",
+        ),
+    ] {
+        let err = bundle_file::Header::from_bytes(input).expect_err("invalid");
+        assert!(err.to_string().starts_with(expected), "{:?}: {}", input.as_bstr(), err);
+    }
+
+    let header = bundle_file::Header::new(Vec::new(), Vec::new(), git_hash::Kind::Sha1);
+    assert!(matches!(
+        bundle_file::write(&header, &b""[..], Vec::new()),
+        Err(bundle_file::write::Error::NoRefs)
+    ));
+}
+
+#[test]
+fn v3_headers_must_declare_the_object_format_they_are_written_with() -> crate::Result {
+    let v3 = fixture("v3.bundle")?;
+    for declared in [Some("sha256"), Some(""), None] {
+        let mut header = v3.header().clone();
+        header.capabilities[0].value = declared.map(Into::into);
+        assert!(
+            matches!(
+                bundle_file::write(&header, &b""[..], Vec::new()),
+                Err(bundle_file::write::Error::ObjectFormatMismatch {
+                    declared: ref actual,
+                    object_hash: git_hash::Kind::Sha1,
+                }) if actual.as_ref().map(|name| name.as_bstr()) == declared.map(|name| name.as_bytes().as_bstr())
+            ),
+            "{:?} doesn't name the object hash",
+            declared
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+mod spec {
+    use git_pack::cache::{spec, Spec};
+
+    #[test]
+    fn valid() {
+        for (input, expected) in [
+            ("never", Spec::Never),
+            ("lru-mem=1000", Spec::LruMemory { bytes: 1000 }),
+            ("lru-mem=1b", Spec::LruMemory { bytes: 1 }),
+            ("lru-mem=64k", Spec::LruMemory { bytes: 64 * 1024 }),
+            (
+                "lru-mem=512MB",
+                Spec::LruMemory {
+                    bytes: 512 * 1024 * 1024,
+                },
+            ),
+            ("lru-mem=2MiB", Spec::LruMemory { bytes: 2 * 1024 * 1024 }),
+            (
+                "lru-mem=1GB",
+                Spec::LruMemory {
+                    bytes: 1024 * 1024 * 1024,
+                },
+            ),
+            ("lru-entries=10000", Spec::LruEntries { count: 10000 }),
+        ] {
+            let actual: Spec = input.parse().expect("valid");
+            assert_eq!(actual, expected, "{}", input);
+            assert_eq!(
+                actual.to_string().parse::<Spec>().expect("valid"),
+                actual,
+                "the display version round-trips"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        for (input, expected) in [
+            (
+                "lru",
+                "Unknown pack cache specification 'lru', valid ones are 'never', 'lru-mem=<size>' like 'lru-mem=512MB' and 'lru-entries=<count>' like 'lru-entries=10000'",
+            ),
+            (
+                "never=1",
+                "Unknown pack cache specification 'never=1', valid ones are 'never', 'lru-mem=<size>' like 'lru-mem=512MB' and 'lru-entries=<count>' like 'lru-entries=10000'",
+            ),
+            (
+                "lru-mem=512XB",
+                "Could not parse '512XB' in pack cache specification 'lru-mem=512XB' as size in bytes, like '512MB'",
+            ),
+            (
+                "lru-mem=",
+                "Could not parse '' in pack cache specification 'lru-mem=' as size in bytes, like '512MB'",
+            ),
+            (
+                "lru-entries=many",
+                "Could not parse 'many' in pack cache specification 'lru-entries=many' as amount of entries, like '10000'",
+            ),
+            (
+                "lru-entries=0",
+                "The pack cache of specification 'lru-entries=0' must be able to hold at least one byte or entry",
+            ),
+        ] {
+            let err = input.parse::<Spec>().unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+        assert!(matches!("lru-mem=0MB".parse::<Spec>(), Err(spec::Error::Zero { .. })));
+    }
+
+    #[test]
+    fn caches_are_created_according_to_their_spec() -> crate::Result {
+        let mut out = Vec::new();
+        let mut cache = git_pack::cache::from_spec("never")?;
+        cache.put(0, 0, b"data", git_object::Kind::Blob, 4);
+        assert_eq!(cache.get(0, 0, &mut out), None, "never caches anything");
+
+        let mut cache = git_pack::cache::from_spec("lru-entries=1")?;
+        cache.put(0, 0, b"first", git_object::Kind::Blob, 5);
+        cache.put(0, 1, b"second", git_object::Kind::Tree, 6);
+        assert_eq!(
+            cache.get(0, 0, &mut out),
+            None,
+            "the least recently used entry was evicted"
+        );
+        assert_eq!(cache.get(0, 1, &mut out), Some((git_object::Kind::Tree, 6)));
+        assert_eq!(out, b"second");
+
+        let mut cache = git_pack::cache::from_spec("lru-mem=10")?;
+        cache.put(0, 0, b"first", git_object::Kind::Blob, 5);
+        cache.put(0, 1, b"second", git_object::Kind::Tree, 6);
+        assert_eq!(cache.get(0, 0, &mut out), None, "both entries don't fit into 10 bytes");
+        assert_eq!(cache.get(0, 1, &mut out), Some((git_object::Kind::Tree, 6)));
+        Ok(())
+    }
+}
+
+#[test]
+fn verify_integrity_with_cache_from_spec() -> crate::Result {
+    use std::sync::atomic::AtomicBool;
+
+    use git_features::progress;
+    use git_pack::{cache::Spec, index};
+
+    use crate::{fixture_path, pack::INDEX_V2, pack::PACK_FOR_INDEX_V2};
+
+    let idx = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+    let pack = git_pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+    for spec in ["never", "lru-mem=1MB", "lru-entries=10"] {
+        let spec: Spec = spec.parse()?;
+        let outcome = idx.verify_integrity(
+            Some(index::verify::PackContext {
+                data: &pack,
+                options: index::verify::integrity::Options {
+                    verify_mode: Default::default(),
+                    traversal: index::traverse::Algorithm::Lookup,
+                    thread_limit: None,
+                    make_pack_lookup_cache: move || spec.to_cache(),
+                    max_decompressed_object_size: None,
+                    tree_checks: Default::default(),
+                    keep_going: false,
+                    paranoid: false,
+                    strict_entry_boundaries: false,
+                },
+            }),
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+        )?;
+        assert_eq!(outcome.actual_index_checksum, idx.index_checksum(), "{}", spec);
+    }
+    Ok(())
+}
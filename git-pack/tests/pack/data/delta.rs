@@ -0,0 +1,185 @@
+use git_pack::data::delta::{apply, apply::Error, apply_partially, decode_header_size};
+
+const BASE: &[u8] = b"hello world, this is the base object";
+
+fn copy(offset: u8, size: u8) -> [u8; 3] {
+    [0b1001_0001, offset, size]
+}
+
+#[test]
+fn copies_and_inserts_produce_the_declared_result() {
+    let mut instructions = copy(0, 5).to_vec();
+    instructions.extend_from_slice(&[3, b'!', b'!', b'!']);
+    let mut target = vec![0; 8];
+    apply(BASE, &mut target, &instructions).expect("valid delta");
+    assert_eq!(target, b"hello!!!");
+}
+
+#[test]
+fn copy_beyond_the_base_is_an_error() {
+    let mut target = vec![0; 10];
+    assert_eq!(
+        apply(BASE, &mut target, &copy(30, 10)),
+        Err(Error::CopyOutOfBounds {
+            offset: 30,
+            size: 10,
+            base_size: BASE.len()
+        })
+    );
+}
+
+#[test]
+fn producing_more_than_declared_is_an_error() {
+    let mut target = vec![0; 4];
+    assert_eq!(
+        apply(BASE, &mut target, &copy(0, 5)),
+        Err(Error::ResultTooLarge {
+            produced: 5,
+            declared: 4
+        })
+    );
+}
+
+#[test]
+fn producing_less_than_declared_is_an_error() {
+    let mut target = vec![0; 6];
+    assert_eq!(
+        apply(BASE, &mut target, &copy(0, 5)),
+        Err(Error::ResultTooSmall {
+            produced: 5,
+            declared: 6
+        })
+    );
+}
+
+#[test]
+fn producing_less_than_declared_is_reported_when_applying_partially() {
+    let mut target = vec![0; 6];
+    assert_eq!(apply_partially(BASE, &mut target, &copy(0, 5)), Ok(5));
+    assert_eq!(&target[..5], b"hello");
+    assert_eq!(
+        apply_partially(&BASE[..4], &mut target, &copy(0, 5)),
+        Err(Error::CopyOutOfBounds {
+            offset: 0,
+            size: 5,
+            base_size: 4
+        }),
+        "all other errors are still detected"
+    );
+}
+
+#[test]
+fn truncated_instructions_are_an_error() {
+    let mut target = vec![0; 5];
+    assert_eq!(
+        apply(BASE, &mut target, &copy(0, 5)[..2]),
+        Err(Error::TruncatedInstructions { instruction_offset: 2 })
+    );
+    assert_eq!(
+        apply(BASE, &mut target, &[5, b'a', b'b']),
+        Err(Error::TruncatedInstructions { instruction_offset: 1 })
+    );
+}
+
+#[test]
+fn command_zero_is_an_error() {
+    let mut target = vec![0; 5];
+    assert_eq!(
+        apply(BASE, &mut target, &[0]),
+        Err(Error::UnsupportedCommand { instruction_offset: 0 })
+    );
+}
+
+#[test]
+fn overlong_header_sizes_do_not_overflow() {
+    let (size, consumed) = decode_header_size(&[0xff; 12]);
+    assert_eq!(consumed, 12);
+    assert_eq!(size, u64::MAX, "excessive sizes are caught by size limits later");
+}
+
+#[test]
+fn arbitrary_instructions_never_panic() {
+    // A simple xorshift generator keeps this deterministic while exploring plenty of malformed instruction streams.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut target = Vec::new();
+    for _ in 0..10_000 {
+        let len = (next() % 32) as usize;
+        let instructions: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+        target.resize((next() % 128) as usize, 0);
+        if apply(BASE, &mut target, &instructions).is_ok() {
+            assert!(!instructions.is_empty() || target.is_empty());
+        }
+        let _ = decode_header_size(&instructions);
+    }
+}
+
+mod encode {
+    use git_pack::data::delta::{apply, decode_header_size, encode};
+
+    fn round_trip(base: &[u8], target: &[u8]) -> usize {
+        let delta = encode(base, target, usize::MAX).expect("no size limit");
+        let (base_size, consumed) = decode_header_size(&delta);
+        assert_eq!(base_size as usize, base.len());
+        let (target_size, consumed_target) = decode_header_size(&delta[consumed..]);
+        assert_eq!(target_size as usize, target.len());
+        let mut actual = vec![0; target.len()];
+        apply(base, &mut actual, &delta[consumed + consumed_target..]).expect("valid delta");
+        assert_eq!(actual, target);
+        delta.len()
+    }
+
+    fn lines(range: std::ops::Range<usize>) -> Vec<u8> {
+        range.map(|n| format!("line {}\n", n)).collect::<String>().into_bytes()
+    }
+
+    #[test]
+    fn applying_the_delta_produces_the_target() {
+        let base = lines(0..1000);
+        for target in [
+            lines(0..1000),
+            lines(0..500),
+            lines(500..1000),
+            [lines(0..300), b"inserted\n".to_vec(), lines(300..1000)].concat(),
+            [lines(0..300), lines(400..1000)].concat(),
+            [lines(900..1000), lines(0..900)].concat(),
+            b"completely different".to_vec(),
+            Vec::new(),
+        ] {
+            round_trip(&base, &target);
+        }
+        round_trip(b"", b"tiny");
+        round_trip(b"tiny", b"");
+    }
+
+    #[test]
+    fn similar_objects_have_small_deltas() {
+        let base = lines(0..1000);
+        let target = [lines(0..300), b"inserted\n".to_vec(), lines(300..1000)].concat();
+        let delta_size = round_trip(&base, &target);
+        assert!(
+            delta_size < 40,
+            "two copies and an insert, but got {} bytes",
+            delta_size
+        );
+    }
+
+    #[test]
+    fn large_copies_are_split() {
+        let base = vec![42; 0x100_0000 + 10];
+        round_trip(&base, &base);
+    }
+
+    #[test]
+    fn deltas_larger_than_the_limit_are_not_produced() {
+        let base = lines(0..100);
+        let target = lines(100..200);
+        assert!(encode(&base, &target, 100).is_none());
+        assert!(encode(&base, &base, 100).is_some());
+    }
+}
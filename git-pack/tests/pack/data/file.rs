@@ -94,6 +94,95 @@ mod decode_entry {
         );
     }
 
+    #[test]
+    fn size_limit_applies_to_objects_and_deltas() {
+        let p = pack_at(SMALL_PACK);
+        let decode = |offset: u64, limit: u64| {
+            p.decode_entry_with_limit(
+                p.entry(offset),
+                &mut Vec::new(),
+                |_, _| unreachable!("no ref deltas here"),
+                &mut cache::Never,
+                Some(limit),
+                true,
+            )
+        };
+        assert!(decode(1968, 187).is_ok(), "the limit is inclusive");
+        assert!(matches!(
+            decode(1968, 186),
+            Err(git_pack::data::decode_entry::Error::SizeLimitExceeded {
+                pack_offset: 1968,
+                size: 187,
+                limit: 186
+            })
+        ));
+
+        assert!(decode(3033, 2381).is_ok());
+        assert!(
+            matches!(
+                decode(3033, 173),
+                Err(git_pack::data::decode_entry::Error::SizeLimitExceeded { limit: 173, .. })
+            ),
+            "base objects of deltas count as well, even if the result itself is small enough"
+        );
+    }
+
+    #[test]
+    fn deltas_producing_less_than_their_declared_result_size_are_detected() -> crate::Result {
+        use std::io::Write;
+
+        use git_pack::data::entry::Header;
+
+        fn zlib(data: &[u8]) -> std::io::Result<Vec<u8>> {
+            let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+            out.write_all(data)?;
+            out.flush()?;
+            Ok(out.into_inner())
+        }
+
+        let mut pack = b"PACK".to_vec();
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        let base_offset = pack.len() as u64;
+        Header::Blob.write_to(5, &mut pack)?;
+        pack.extend_from_slice(&zlib(b"hello")?);
+        let delta_offset = pack.len() as u64;
+        // Declare a base of 5 bytes and a result of 6 bytes, but only copy the 5 bytes of the base.
+        let delta = [5, 6, 0b1001_0001, 0, 5];
+        Header::OfsDelta {
+            base_distance: delta_offset - base_offset,
+        }
+        .write_to(delta.len() as u64, &mut pack)?;
+        pack.extend_from_slice(&zlib(&delta)?);
+        pack.extend_from_slice(&[0; 20]);
+        let p = git_pack::data::File::from_bytes(pack, git_hash::Kind::Sha1)?;
+
+        let decode = |verify_delta_result_size: bool| {
+            p.decode_entry_with_limit(
+                p.entry(delta_offset),
+                &mut Vec::new(),
+                |_, _| unreachable!("no ref deltas here"),
+                &mut cache::Never,
+                None,
+                verify_delta_result_size,
+            )
+        };
+        assert!(matches!(
+            decode(true),
+            Err(git_pack::data::decode_entry::Error::DeltaResultSizeMismatch {
+                pack_offset,
+                declared: 6,
+                actual: 5
+            }) if pack_offset == delta_offset
+        ));
+        assert_eq!(
+            decode(false)?.object_size,
+            6,
+            "without verification, the declared size is trusted"
+        );
+        Ok(())
+    }
+
     fn decode_entry_at_offset(offset: u64) -> Vec<u8> {
         fn resolve_with_panic(_oid: &git_hash::oid, _out: &mut Vec<u8>) -> Option<ResolvedBase> {
             panic!("should not want to resolve an id here")
@@ -108,6 +197,45 @@ mod decode_entry {
     }
 }
 
+mod decode_header {
+    use git_odb::pack::{self, cache};
+
+    use crate::{fixture_path, pack::PACKS_AND_INDICES};
+
+    #[test]
+    fn matches_the_decoded_object_for_all_entries() -> crate::Result {
+        for (index_path, data_path) in PACKS_AND_INDICES {
+            let index = pack::index::File::at(fixture_path(index_path), git_hash::Kind::Sha1)?;
+            let pack = pack::data::File::at(fixture_path(data_path), git_hash::Kind::Sha1)?;
+            let mut buf = Vec::new();
+            let mut saw_delta = false;
+            for entry in index.iter() {
+                let header = pack.decode_header(pack.entry(entry.pack_offset), |id| {
+                    index.lookup(id).map(|idx| {
+                        pack::data::decode_header::ResolvedBase::InPack(pack.entry(index.pack_offset_at_index(idx)))
+                    })
+                })?;
+                let outcome = pack.decode_entry(
+                    pack.entry(entry.pack_offset),
+                    &mut buf,
+                    |id, _| {
+                        index
+                            .lookup(id)
+                            .map(|idx| pack::data::ResolvedBase::InPack(pack.entry(index.pack_offset_at_index(idx))))
+                    },
+                    &mut cache::Never,
+                )?;
+                assert_eq!(header.kind, outcome.kind, "{}", entry.oid);
+                assert_eq!(header.object_size, buf.len() as u64, "{}", entry.oid);
+                assert_eq!(header.num_deltas, outcome.num_deltas, "{}", entry.oid);
+                saw_delta |= header.num_deltas > 0;
+            }
+            assert!(saw_delta, "the fixture contains deltas to exercise the delta path");
+        }
+        Ok(())
+    }
+}
+
 mod decompress_entry {
     use git_object::bstr::ByteSlice;
 
@@ -163,3 +291,251 @@ mod decompress_entry {
         buf
     }
 }
+
+mod init {
+    use git_odb::pack;
+
+    fn write_pack(data: &[u8]) -> std::io::Result<(tempfile::TempDir, std::path::PathBuf)> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("pack-broken.pack");
+        std::fs::write(&path, data)?;
+        Ok((dir, path))
+    }
+
+    fn open_err(path: &std::path::Path) -> pack::data::init::Error {
+        pack::data::File::at(path, git_hash::Kind::Sha1)
+            .map(|_| ())
+            .expect_err("file is invalid")
+    }
+
+    #[test]
+    fn errors_mention_the_path_and_the_problem() -> crate::Result {
+        let (_dir, path) = write_pack(b"PACK")?;
+        assert_eq!(
+            open_err(&path).to_string(),
+            format!(
+                "Pack file at '{}' with 4 bytes is too small for even an empty pack",
+                path.display()
+            )
+        );
+
+        let (_dir, path) = write_pack(&[b"JUNK".as_ref(), &[0u8; 28][..]].concat())?;
+        assert_eq!(
+            open_err(&path).to_string(),
+            format!(
+                "Pack file at '{}' doesn't start with the 'PACK' signature, but with \"JUNK\"",
+                path.display()
+            )
+        );
+
+        let (_dir, path) = write_pack(&[b"PACK".as_ref(), &4u32.to_be_bytes()[..], &[0u8; 24][..]].concat())?;
+        assert_eq!(
+            open_err(&path).to_string(),
+            format!("Pack file at '{}' has unsupported version 4", path.display())
+        );
+
+        let missing = path.with_file_name("missing.pack");
+        let err = open_err(&missing);
+        assert_eq!(
+            err.to_string(),
+            format!("Could not open pack file at '{}'", missing.display())
+        );
+        assert!(matches!(err, pack::data::init::Error::Io { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn in_memory_files_have_synthetic_paths_in_errors() {
+        let err = pack::data::File::from_bytes(vec![0; 32], git_hash::Kind::Sha1)
+            .map(|_| ())
+            .expect_err("invalid signature");
+        assert_eq!(
+            err.to_string(),
+            "Pack file at '<memory>/pack-0000000000000000000000000000000000000000.pack' doesn't start with the 'PACK' signature, but with \"\\0\\0\\0\\0\""
+        );
+    }
+}
+
+mod positioned_read {
+    use std::{convert::Infallible, sync::atomic::AtomicBool};
+
+    use git_features::progress;
+    use git_odb::pack::{
+        self, cache,
+        data::Access,
+        index::traverse::{Algorithm, SafetyCheck},
+    };
+
+    use crate::{
+        fixture_path,
+        pack::{SMALL_PACK, SMALL_PACK_INDEX, V2_PACKS_AND_INDICES},
+    };
+
+    fn decode_all(
+        index: &pack::index::File,
+        pack: &pack::data::File,
+    ) -> Result<Vec<(pack::data::decode_entry::Outcome, Vec<u8>, u32)>, pack::data::decode_entry::Error> {
+        let mut entries: Vec<_> = index.iter().collect();
+        entries.sort_by_key(|e| e.pack_offset);
+        let mut out = Vec::new();
+        for (idx, entry) in entries.iter().enumerate() {
+            let mut buf = Vec::new();
+            let outcome = pack.decode_entry(
+                pack.try_entry(entry.pack_offset)?,
+                &mut buf,
+                |id, _| {
+                    index
+                        .lookup(id)
+                        .map(|idx| pack::data::ResolvedBase::InPack(pack.entry(index.pack_offset_at_index(idx))))
+                },
+                &mut cache::Never,
+            )?;
+            let entry_end = entries
+                .get(idx + 1)
+                .map_or(pack.pack_end() as u64, |next| next.pack_offset);
+            let crc32 = pack.entry_crc32(entry.pack_offset, (entry_end - entry.pack_offset) as usize);
+            out.push((outcome, buf, crc32));
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn tiny_uncached_blocks_decode_the_same_as_memory_maps() -> crate::Result {
+        for (index_path, data_path) in V2_PACKS_AND_INDICES {
+            let index = pack::index::File::at(fixture_path(index_path), git_hash::Kind::Sha1)?;
+            let mapped = pack::data::File::at(fixture_path(data_path), git_hash::Kind::Sha1)?;
+            let expected = decode_all(&index, &mapped)?;
+            // Blocks of a single byte without cache simulate a slow reader which only ever returns a little data at a time.
+            for (block_size, capacity) in [(1, 0), (7, 1), (4096, 2), (1024 * 1024, 64)] {
+                let pack = pack::data::File::at_with_access(
+                    fixture_path(data_path),
+                    git_hash::Kind::Sha1,
+                    Access::PositionedRead { block_size, capacity },
+                )?;
+                assert_eq!(pack.data_len(), mapped.data_len());
+                assert_eq!(pack.checksum(), mapped.checksum());
+                assert_eq!(
+                    pack.verify_checksum(git_features::progress::Discard, &Default::default())?,
+                    mapped.checksum()
+                );
+                assert!(
+                    decode_all(&index, &pack)? == expected,
+                    "objects, their compressed sizes and CRC32 match with blocks of {} bytes",
+                    block_size
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_packs_fail_with_io_errors() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("pack.pack");
+        std::fs::copy(fixture_path(SMALL_PACK), &path)?;
+        let pack = pack::data::File::at_with_access(
+            &path,
+            git_hash::Kind::Sha1,
+            Access::PositionedRead {
+                block_size: 64,
+                capacity: 0,
+            },
+        )?;
+        let commit = pack.try_entry(1968)?;
+
+        std::fs::OpenOptions::new().write(true).open(&path)?.set_len(1000)?;
+        let err = pack
+            .try_entry(3033)
+            .expect_err("the entry header is past the end of the file");
+        assert!(matches!(err, pack::data::decode_entry::Error::Io { offset: 3033, .. }));
+        assert_eq!(err.kind(), pack::find::ErrorKind::Io);
+
+        let err = pack
+            .decode_entry(commit, &mut Vec::new(), |_, _| None, &mut cache::Never)
+            .expect_err("entry data is past the end of the file");
+        assert!(matches!(err, pack::data::decode_entry::Error::Io { .. }));
+        assert!(pack.entry_slice(1968..2000).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_packs_fail_traversal_and_verification_with_io_errors() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("pack.pack");
+        std::fs::copy(fixture_path(SMALL_PACK), &path)?;
+        let index = pack::index::File::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+        let pack = pack::data::File::at_with_access(
+            &path,
+            git_hash::Kind::Sha1,
+            Access::PositionedRead {
+                block_size: 64,
+                capacity: 0,
+            },
+        )?;
+        std::fs::OpenOptions::new().write(true).open(&path)?.set_len(1000)?;
+
+        for traversal in [
+            Algorithm::Lookup,
+            Algorithm::DeltaTreeLookup,
+            Algorithm::Streaming {
+                cache_memory_cap_in_bytes: 64 * 1024,
+            },
+        ] {
+            let err = index
+                .traverse(
+                    &pack,
+                    &mut progress::Discard,
+                    &AtomicBool::new(false),
+                    || |_kind, _data: &[u8], _entry: &pack::index::Entry, _progress: &mut _| Ok::<_, Infallible>(()),
+                    pack::index::traverse::Options {
+                        traversal,
+                        check: SafetyCheck::SkipFileChecksumVerification,
+                        ..Default::default()
+                    },
+                )
+                .map(|_| ())
+                .expect_err("entries past the end of the file can't be read");
+            assert!(
+                matches!(
+                    err,
+                    pack::index::traverse::Error::PackDecode {
+                        source: pack::data::decode_entry::Error::Io { .. },
+                        ..
+                    } | pack::index::traverse::Error::Tree(_)
+                ),
+                "{:?}: {:?}",
+                traversal,
+                err
+            );
+
+            let err = index
+                .verify_integrity(
+                    Some(pack::index::verify::PackContext {
+                        data: &pack,
+                        options: pack::index::verify::integrity::Options {
+                            traversal,
+                            ..Default::default()
+                        },
+                    }),
+                    &mut progress::Discard,
+                    &AtomicBool::new(false),
+                )
+                .map(|_| ())
+                .expect_err("the truncated pack can't be verified");
+            assert!(
+                matches!(
+                    err,
+                    pack::index::traverse::Error::VerifyChecksum(_)
+                        | pack::index::traverse::Error::PackDecode {
+                            source: pack::data::decode_entry::Error::Io { .. },
+                            ..
+                        }
+                ),
+                "{:?}: {:?}",
+                traversal,
+                err
+            );
+        }
+        Ok(())
+    }
+}
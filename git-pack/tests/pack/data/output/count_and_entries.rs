@@ -1,4 +1,4 @@
-use std::{convert::Infallible, sync::atomic::AtomicBool};
+use std::{collections::HashSet, convert::Infallible, sync::atomic::AtomicBool};
 
 use git_features::{
     parallel::{reduce::Finalize, InOrderIter},
@@ -116,12 +116,19 @@ fn traversals() -> crate::Result {
                     expanded_objects: 0,
                     decoded_objects: 16,
                     total_objects: 16,
+                    pruned_commits: 0,
+                    excluded_trees: 0,
+                    excluded_blobs: 0,
+                    skipped_gitlinks: 0,
+                    ambiguous_paths: 0,
                 },
                 output::entry::iter_from_counts::Outcome {
                     decoded_and_recompressed_objects: 0,
                     missing_objects: 0,
                     objects_copied_from_pack: 16,
                     ref_delta_objects: 0,
+                    reused_deltas: 0,
+                    computed_deltas: 0,
                 },
                 hex_to_id("b920bbb055e1efb9080592a409d3975738b6efb3"),
                 None,
@@ -149,12 +156,19 @@ fn traversals() -> crate::Result {
                     expanded_objects: 102,
                     decoded_objects: 18,
                     total_objects: 103,
+                    pruned_commits: 0,
+                    excluded_trees: 0,
+                    excluded_blobs: 0,
+                    skipped_gitlinks: 0,
+                    ambiguous_paths: 0,
                 },
                 output::entry::iter_from_counts::Outcome {
                     decoded_and_recompressed_objects: 0,
                     missing_objects: 0,
                     objects_copied_from_pack: 103,
                     ref_delta_objects: 74,
+                    reused_deltas: 79,
+                    computed_deltas: 0,
                 },
                 hex_to_id("25114bd8820b393c402cd53ad8ec7f6a84bb0633"),
                 Some(hex_to_id("29ab9797aff1ca826afb699680356695d19c5acb")),
@@ -182,12 +196,19 @@ fn traversals() -> crate::Result {
                     expanded_objects: 102,
                     decoded_objects: 18,
                     total_objects: 103,
+                    pruned_commits: 0,
+                    excluded_trees: 0,
+                    excluded_blobs: 0,
+                    skipped_gitlinks: 0,
+                    ambiguous_paths: 0,
                 },
                 output::entry::iter_from_counts::Outcome {
                     decoded_and_recompressed_objects: 74,
                     missing_objects: 0,
                     objects_copied_from_pack: 29,
                     ref_delta_objects: 0,
+                    reused_deltas: 5,
+                    computed_deltas: 0,
                 },
                 hex_to_id("d83d42128e40957c5174920189a0390b5a70f446"),
                 None,
@@ -203,12 +224,19 @@ fn traversals() -> crate::Result {
                     expanded_objects: 852,
                     decoded_objects: 57,
                     total_objects: 868,
+                    pruned_commits: 0,
+                    excluded_trees: 0,
+                    excluded_blobs: 0,
+                    skipped_gitlinks: 0,
+                    ambiguous_paths: 0,
                 },
                 output::entry::iter_from_counts::Outcome {
                     decoded_and_recompressed_objects: 0,
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    reused_deltas: 542,
+                    computed_deltas: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -224,12 +252,19 @@ fn traversals() -> crate::Result {
                     expanded_objects: 866,
                     decoded_objects: 208,
                     total_objects: 868,
+                    pruned_commits: 0,
+                    excluded_trees: 0,
+                    excluded_blobs: 0,
+                    skipped_gitlinks: 0,
+                    ambiguous_paths: 0,
                 },
                 output::entry::iter_from_counts::Outcome {
                     decoded_and_recompressed_objects: 0,
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    reused_deltas: 542,
+                    computed_deltas: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -265,6 +300,7 @@ fn traversals() -> crate::Result {
                     .map(Ok::<_, Infallible>),
                 progress::Discard,
                 &AtomicBool::new(false),
+                None,
                 count::objects::Options {
                     input_object_expansion: expansion_mode,
                     thread_limit: deterministic_count_needs_single_thread,
@@ -314,19 +350,456 @@ fn traversals() -> crate::Result {
                 "two different ways of counting, still the same in the end"
             );
 
-            write_and_verify(db.clone(), entries, expected_pack_hash, expected_thin_pack_hash)?;
+            write_and_verify(db.clone(), entries, Some(expected_pack_hash), expected_thin_pack_hash)?;
         }
     }
 
     Ok(())
 }
 
+#[test]
+fn pruned_walks_contain_all_objects_of_commits_above_the_cutoff() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let graph = git_commitgraph::Graph::from_info_dir(db.store_ref().path().join("info"))?;
+    let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+    let commits = commit::Ancestors::new(Some(head), commit::ancestors::State::default(), {
+        let db = db.clone();
+        move |oid, buf| db.find_commit_iter(oid, buf).map(|t| t.0)
+    })
+    .map(Result::unwrap)
+    .collect::<Vec<_>>();
+    assert_eq!(commits.len(), 15);
+
+    let cutoff_generation = 10;
+    let commits_above_cutoff: Vec<_> = commits
+        .iter()
+        .filter(|id| {
+            graph
+                .commit_by_id(id)
+                .expect("all commits are in the graph")
+                .generation()
+                >= cutoff_generation
+        })
+        .copied()
+        .collect();
+    assert_eq!(commits_above_cutoff.len(), 6);
+
+    let count = |commits: &[git_hash::ObjectId], options: count::objects::Options| {
+        output::count::objects_unthreaded(
+            db.clone(),
+            commits.iter().copied().map(Ok::<_, Infallible>),
+            progress::Discard,
+            &AtomicBool::new(false),
+            Some(&graph),
+            options,
+        )
+        .map(|(counts, stats)| (counts.into_iter().map(|c| c.id).collect::<HashSet<_>>(), stats))
+    };
+    let (expected, _) = count(
+        &commits_above_cutoff,
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+            ..Default::default()
+        },
+    )?;
+
+    for expansion in [
+        count::objects::ObjectExpansion::TreeContents,
+        count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+    ] {
+        let (actual, stats) = count(
+            &commits,
+            count::objects::Options {
+                input_object_expansion: expansion,
+                cutoff_generation: Some(cutoff_generation),
+                ..Default::default()
+            },
+        )?;
+        assert!(
+            actual.is_superset(&expected),
+            "{:?}: all objects reachable from commits above the cutoff are present",
+            expansion
+        );
+        assert!(
+            commits
+                .iter()
+                .filter(|id| !commits_above_cutoff.contains(id))
+                .all(|id| !actual.contains(id)),
+            "{:?}: commits below the cutoff are not included",
+            expansion
+        );
+        assert_eq!(
+            stats.pruned_commits,
+            match expansion {
+                count::objects::ObjectExpansion::TreeContents => 9,
+                _ => 9 + 1,
+            },
+            "{:?}: input commits are pruned, and the parent of the oldest commit above the cutoff is pruned as well",
+            expansion
+        );
+    }
+
+    let commit_time = db
+        .find_commit(head, &mut Vec::new())?
+        .0
+        .committer
+        .time
+        .seconds_since_unix_epoch;
+    for (cutoff_time, expected_pruned_commits) in [(commit_time, 0), (commit_time + 1, commits.len())] {
+        let (_, stats) = count(
+            &commits,
+            count::objects::Options {
+                input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+                cutoff_time: Some(cutoff_time),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            stats.pruned_commits, expected_pruned_commits,
+            "all commits have the same time, so only commits strictly older than the cutoff are pruned"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn path_filters_restrict_trees_and_blobs_like_git_rev_list() -> crate::Result {
+    let repo = git_testtools::scripted_fixture_repo_read_only("make_pathspec_repo.sh")?;
+    let db = std::sync::Arc::new(git_odb::Store::at_opts(
+        repo.join(".git/objects"),
+        Vec::new(),
+        Default::default(),
+    )?)
+    .to_cache_arc();
+    let ids_in = |name: &str| -> crate::Result<HashSet<git_hash::ObjectId>> {
+        std::fs::read_to_string(repo.join(name))?
+            .lines()
+            .map(|hex| Ok(git_hash::ObjectId::from_hex(hex.as_bytes())?))
+            .collect()
+    };
+    let commits = ids_in("commits")?;
+    let count = |expansion, prefixes: &[&str]| {
+        output::count::objects_unthreaded(
+            db.clone(),
+            commits.iter().copied().map(Ok::<_, Infallible>),
+            progress::Discard,
+            &AtomicBool::new(false),
+            None,
+            count::objects::Options {
+                input_object_expansion: expansion,
+                path_filter: Some(count::objects::PathFilter::new(prefixes.iter().copied())),
+                ..Default::default()
+            },
+        )
+        .map(|(counts, stats)| {
+            let ids: HashSet<_> = counts.iter().map(|c| c.id).collect();
+            assert_eq!(ids.len(), counts.len(), "objects are never added twice");
+            (ids, stats)
+        })
+    };
+
+    for expansion in [
+        count::objects::ObjectExpansion::TreeContents,
+        count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+    ] {
+        let (actual, stats) = count(expansion, &["services/api/"])?;
+        assert_eq!(
+            actual,
+            ids_in("services-api.objects")?,
+            "{:?}: commits, the trees leading to the path and everything below it, which excludes 'services/apiary'",
+            expansion
+        );
+        assert!(stats.excluded_trees > 0, "{:?}", expansion);
+        assert!(stats.excluded_blobs > 0, "{:?}", expansion);
+
+        let (actual, _) = count(expansion, &["shared", "lib/core"])?;
+        let expected = ids_in("shared-lib-core.objects")?;
+        let only_ours: Vec<_> = actual.difference(&expected).collect();
+        assert!(actual.is_superset(&expected), "{:?}", expansion);
+        assert_eq!(
+            only_ours.len(),
+            2,
+            "{:?}: git doesn't enter 'shared' as it saw the identical 'lib' tree before, missing 'shared/other' and its blob",
+            expansion
+        );
+    }
+
+    let (everything, stats) = count(count::objects::ObjectExpansion::TreeContents, &[""])?;
+    assert_eq!(
+        (stats.excluded_trees, stats.excluded_blobs),
+        (0, 0),
+        "an empty prefix includes everything"
+    );
+    let (unfiltered, _) = output::count::objects_unthreaded(
+        db.clone(),
+        commits.iter().copied().map(Ok::<_, Infallible>),
+        progress::Discard,
+        &AtomicBool::new(false),
+        None,
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(everything, unfiltered.into_iter().map(|c| c.id).collect::<HashSet<_>>());
+    Ok(())
+}
+
+#[test]
+fn path_filters_can_ignore_case_and_unicode_normalization() -> crate::Result {
+    let repo = git_testtools::scripted_fixture_repo_read_only("make_case_insensitive_paths_repo.sh")?;
+    let db = std::sync::Arc::new(git_odb::Store::at_opts(
+        repo.join(".git/objects"),
+        Vec::new(),
+        Default::default(),
+    )?)
+    .to_cache_arc();
+    let commits: Vec<_> = std::fs::read_to_string(repo.join("commits"))?
+        .lines()
+        .map(|hex| git_hash::ObjectId::from_hex(hex.as_bytes()))
+        .collect::<Result<_, _>>()?;
+    let count = |expansion, filter: count::objects::PathFilter| {
+        output::count::objects_unthreaded(
+            db.clone(),
+            commits.iter().copied().map(Ok::<_, Infallible>),
+            progress::Discard,
+            &AtomicBool::new(false),
+            None,
+            count::objects::Options {
+                input_object_expansion: expansion,
+                path_filter: Some(filter),
+                ..Default::default()
+            },
+        )
+        .map(|(counts, stats)| (counts.len(), stats))
+    };
+    let commit_and_root_tree = 2;
+
+    for expansion in [
+        count::objects::ObjectExpansion::TreeContents,
+        count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+    ] {
+        let (num_objects, stats) = count(expansion, count::objects::PathFilter::new(Some("README")))?;
+        assert_eq!(
+            num_objects,
+            commit_and_root_tree + 1,
+            "{:?}: exact by default",
+            expansion
+        );
+        assert_eq!(stats.ambiguous_paths, 0, "{:?}: nothing can be ambiguous", expansion);
+
+        let (num_objects, stats) = count(
+            expansion,
+            count::objects::PathFilter::new(Some("Readme")).with_ignore_case(true),
+        )?;
+        assert_eq!(
+            num_objects,
+            commit_and_root_tree + 2,
+            "{:?}: both 'README' and 'readme' match",
+            expansion
+        );
+        assert_eq!(
+            stats.ambiguous_paths, 1,
+            "{:?}: the second match is ambiguous",
+            expansion
+        );
+
+        let (num_objects, stats) = count(
+            expansion,
+            count::objects::PathFilter::new(Some("DOCS")).with_ignore_case(true),
+        )?;
+        assert_eq!(
+            num_objects,
+            commit_and_root_tree + 4,
+            "{:?}: the trees 'Docs' and 'docs' and their blobs",
+            expansion
+        );
+        assert_eq!(stats.ambiguous_paths, 1, "{:?}", expansion);
+
+        let nfc_cafe = "caf\u{e9}/c";
+        let (num_objects, _) = count(
+            expansion,
+            count::objects::PathFilter::new(Some(nfc_cafe)).with_ignore_case(true),
+        )?;
+        assert_eq!(
+            num_objects, commit_and_root_tree,
+            "{:?}: the path is stored decomposed, so case folding alone doesn't match",
+            expansion
+        );
+        let (num_objects, stats) = count(
+            expansion,
+            count::objects::PathFilter::new(Some(nfc_cafe.to_uppercase()))
+                .with_ignore_normalization(true)
+                .with_ignore_case(true),
+        )?;
+        assert_eq!(
+            num_objects,
+            commit_and_root_tree + 2,
+            "{:?}: the tree and its blob",
+            expansion
+        );
+        assert_eq!(stats.ambiguous_paths, 0, "{:?}", expansion);
+    }
+    Ok(())
+}
+
+#[test]
+fn path_filter_matching_portion() {
+    let filter = count::objects::PathFilter::new(["a/b", "Docs"]).with_ignore_case(true);
+    assert_eq!(
+        filter.matching_portion("a".into()),
+        Some((1, "a".into())),
+        "leading to a prefix"
+    );
+    assert_eq!(
+        filter.matching_portion("A/B/c".into()),
+        Some((1, "A/B".into())),
+        "below a prefix"
+    );
+    assert_eq!(filter.matching_portion("docs/x".into()), Some((0, "docs".into())));
+    assert_eq!(
+        filter.matching_portion("a/bc".into()),
+        None,
+        "only whole components match"
+    );
+    assert_eq!(
+        filter.prefixes(),
+        ["Docs", "a/b"],
+        "prefixes are kept as given, as case is only ignored when matching"
+    );
+    assert_eq!(
+        filter.classify("A".into()),
+        count::objects::PathMatch::Parent,
+        "classification ignores case as well"
+    );
+}
+
+#[test]
+fn gitlinks_are_skipped_and_counted() -> crate::Result {
+    let repo = git_testtools::scripted_fixture_repo_read_only("make_repo_with_gitlinks.sh")?;
+    let db = std::sync::Arc::new(git_odb::Store::at_opts(
+        repo.join(".git/objects"),
+        Vec::new(),
+        Default::default(),
+    )?)
+    .to_cache_arc();
+    let ids_in = |name: &str| -> crate::Result<HashSet<git_hash::ObjectId>> {
+        std::fs::read_to_string(repo.join(name))?
+            .lines()
+            .map(|hex| Ok(git_hash::ObjectId::from_hex(hex.as_bytes())?))
+            .collect()
+    };
+    let commits = ids_in("commits")?;
+    let expected = ids_in("objects")?;
+
+    for (expansion, expected_skipped_gitlinks) in [
+        // both trees of both commits are traversed
+        (count::objects::ObjectExpansion::TreeContents, 4),
+        // the tree of the first commit is traversed, and the second commit adds one and changes another gitlink
+        (count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor, 4),
+    ] {
+        let (counts, stats) = output::count::objects_unthreaded(
+            db.clone(),
+            commits.iter().copied().map(Ok::<_, Infallible>),
+            progress::Discard,
+            &AtomicBool::new(false),
+            None,
+            count::objects::Options {
+                input_object_expansion: expansion,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            counts.iter().map(|c| c.id).collect::<HashSet<_>>(),
+            expected,
+            "{:?}: commits of submodules are never looked up, just like 'git rev-list --objects' doesn't list them",
+            expansion
+        );
+        assert_eq!(stats.skipped_gitlinks, expected_skipped_gitlinks, "{:?}", expansion);
+    }
+    Ok(())
+}
+
+#[test]
+fn delta_modes_trade_time_for_pack_size() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+    let (counts, _) = output::count::objects(
+        db.clone(),
+        std::iter::once(Ok::<_, Infallible>(head)),
+        progress::Discard,
+        &AtomicBool::new(false),
+        None,
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+            thread_limit: Some(1),
+            ..Default::default()
+        },
+    )?;
+
+    let mut pack_sizes = Vec::new();
+    let compression_level = 1;
+    for delta_mode in [
+        entry::iter_from_counts::DeltaMode::None,
+        entry::iter_from_counts::DeltaMode::ReuseExisting,
+        entry::iter_from_counts::DeltaMode::Compute {
+            window: 10,
+            max_depth: 50,
+        },
+    ] {
+        let mut entries_iter = output::entry::iter_from_counts(
+            counts.clone(),
+            db.clone(),
+            progress::Discard,
+            output::entry::iter_from_counts::Options {
+                thread_limit: Some(1),
+                delta_mode,
+                compression_level,
+                ..Default::default()
+            },
+        );
+        let entries: Vec<_> = InOrderIter::from(entries_iter.by_ref())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        assert_eq!(entries.len(), counts.len());
+        let stats = entries_iter.finalize()?;
+        assert_eq!(stats.missing_objects, 0);
+        match delta_mode {
+            entry::iter_from_counts::DeltaMode::None => {
+                assert_eq!(stats.reused_deltas, 0, "no delta is copied");
+                assert_eq!(stats.computed_deltas, 0);
+                assert!(
+                    entries.iter().all(|e| matches!(e.kind, entry::Kind::Base(_))),
+                    "all objects are written as base objects"
+                );
+            }
+            entry::iter_from_counts::DeltaMode::ReuseExisting => {
+                assert!(stats.reused_deltas > 0, "some deltas have their base in the pack");
+                assert_eq!(stats.computed_deltas, 0);
+            }
+            entry::iter_from_counts::DeltaMode::Compute { .. } => {
+                assert!(stats.reused_deltas > 0, "existing deltas are still reused");
+                assert!(stats.computed_deltas > 0, "similar objects are delta-compressed");
+            }
+        }
+        pack_sizes.push(write_and_verify(db.clone(), entries, None, None)?);
+    }
+    assert!(
+        pack_sizes[2] < pack_sizes[1] && pack_sizes[1] < pack_sizes[0],
+        "with the same compression level, computing deltas yields smaller packs than reusing them, which is smaller than not using them: {:?}",
+        pack_sizes
+    );
+    Ok(())
+}
+
 #[test]
 fn empty_pack_is_allowed() {
     write_and_verify(
         db(DbKind::DeterministicGeneratedContent).unwrap(),
         vec![],
-        hex_to_id("029d08823bd8a8eab510ad6ac75c823cfd3ed31e"),
+        Some(hex_to_id("029d08823bd8a8eab510ad6ac75c823cfd3ed31e")),
         None,
     )
     .unwrap();
@@ -335,9 +808,9 @@ fn empty_pack_is_allowed() {
 fn write_and_verify(
     db: git_odb::HandleArc,
     entries: Vec<output::Entry>,
-    expected_pack_hash: git_hash::ObjectId,
+    expected_pack_hash: Option<git_hash::ObjectId>,
     expected_thin_pack_hash: Option<git_hash::ObjectId>,
-) -> crate::Result {
+) -> crate::Result<u64> {
     let tmp_dir = tempfile::TempDir::new()?;
     let pack_file_path = tmp_dir.path().join("new.pack");
     let mut pack_file = std::fs::OpenOptions::new()
@@ -380,7 +853,9 @@ fn write_and_verify(
         "the trailer of the pack matches the actually written trailer"
     );
 
-    assert_eq!(hash, expected_pack_hash, "pack hashes are stable if the input is");
+    if let Some(expected_pack_hash) = expected_pack_hash {
+        assert_eq!(hash, expected_pack_hash, "pack hashes are stable if the input is");
+    }
 
     // Re-generate the index from the pack for validation.
     let object_hash = git_hash::Kind::Sha1; // TODO: parameterize this
@@ -409,15 +884,20 @@ fn write_and_verify(
     }
 
     bundle.verify_integrity(
-        progress::Discard,
+        &mut progress::Discard,
         &should_interrupt,
         git_pack::index::verify::integrity::Options {
             verify_mode: pack::index::verify::Mode::HashCrc32DecodeEncode,
             traversal: pack::index::traverse::Algorithm::Lookup,
             make_pack_lookup_cache: || pack::cache::Never,
             thread_limit: None,
+            max_decompressed_object_size: None,
+            tree_checks: Default::default(),
+            keep_going: false,
+            paranoid: false,
+            strict_entry_boundaries: false,
         },
     )?;
 
-    Ok(())
+    Ok(num_written_bytes)
 }
@@ -153,6 +153,7 @@ mod version {
                     move || Ok(resolve),
                     pack_iter,
                     None,
+                    None,
                     progress::Discard,
                     &mut actual,
                     &AtomicBool::new(false),
@@ -265,7 +266,7 @@ fn traverse_with_index_and_forward_ref_deltas() {
                     Ok::<_, std::io::Error>(())
                 }
             },
-            progress::Discard,
+            &mut progress::Discard,
             &AtomicBool::new(false),
             index::traverse::with_index::Options::default(),
         )
@@ -277,11 +278,14 @@ use git_features::progress;
 use git_pack::{cache, data::decode_entry::Outcome, index};
 use maplit::btreemap;
 
-use crate::pack::{INDEX_V2, PACK_FOR_INDEX_V2};
+use crate::pack::{INDEX_V2, PACK_FOR_INDEX_V2, V2_PACKS_AND_INDICES};
 
 static ALGORITHMS: &[index::traverse::Algorithm] = &[
     index::traverse::Algorithm::Lookup,
     index::traverse::Algorithm::DeltaTreeLookup,
+    index::traverse::Algorithm::Streaming {
+        cache_memory_cap_in_bytes: 64 * 1024,
+    },
 ];
 
 static MODES: &[index::verify::Mode] = &[
@@ -321,6 +325,12 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
                 num_tags: 0,
                 num_trees: 15,
                 pack_size: 51875,
+                index_oid_mismatches: 0,
+                first_index_oid_mismatches: Vec::new(),
+                delta_result_size_mismatches: 0,
+                entry_overlaps: 0,
+                entry_slack_bytes: 0,
+                first_entry_boundary_mismatches: Vec::new(),
             },
         ),
         (
@@ -346,6 +356,12 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
                 num_tags: 0,
                 num_trees: 2,
                 pack_size: 49113,
+                index_oid_mismatches: 0,
+                first_index_oid_mismatches: Vec::new(),
+                delta_result_size_mismatches: 0,
+                entry_overlaps: 0,
+                entry_slack_bytes: 0,
+                first_entry_boundary_mismatches: Vec::new(),
             },
         ),
         (
@@ -372,6 +388,12 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
                 num_tags: 0,
                 num_trees: 14,
                 pack_size: 3732,
+                index_oid_mismatches: 0,
+                first_index_oid_mismatches: Vec::new(),
+                delta_result_size_mismatches: 0,
+                entry_overlaps: 0,
+                entry_slack_bytes: 0,
+                first_entry_boundary_mismatches: Vec::new(),
             },
         ),
     ] {
@@ -390,10 +412,15 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
                                 verify_mode: *mode,
                                 traversal: *algo,
                                 make_pack_lookup_cache: || cache::Never,
-                                thread_limit: None
+                                thread_limit: None,
+                                max_decompressed_object_size: None,
+                                tree_checks: Default::default(),
+                                keep_going: false,
+                                paranoid: false,
+                                strict_entry_boundaries: false,
                             }
                         }),
-                        progress::Discard,
+                        &mut progress::Discard,
                         &AtomicBool::new(false)
                     )
                     .map(|o| (o.actual_index_checksum, o.pack_traverse_statistics))?,
@@ -492,7 +519,7 @@ fn iter() -> Result<(), Box<dyn std::error::Error>> {
         assert_eq!(
             idx.verify_integrity(
                 None::<git_pack::index::verify::PackContext<'_, fn() -> cache::Never>>,
-                progress::Discard,
+                &mut progress::Discard,
                 &AtomicBool::new(false)
             )
             .map(|o| (o.actual_index_checksum, o.pack_traverse_statistics))?,
@@ -504,3 +531,1645 @@ fn iter() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[test]
+fn verify_integrity_of_in_memory_files() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::from_bytes(std::fs::read(fixture_path(SMALL_PACK_INDEX))?, git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::from_bytes(std::fs::read(fixture_path(SMALL_PACK))?, git_hash::Kind::Sha1)?;
+    assert!(idx.is_in_memory() && pack.is_in_memory());
+    assert_eq!(
+        idx.path().file_name().expect("synthetic file name"),
+        "pack-0f3ea84cd1bba10c2a03d736a460635082833e59.idx",
+        "the name is derived from the pack checksum stored in the index"
+    );
+    assert_eq!(
+        idx.path().with_extension("pack"),
+        pack.path(),
+        "an in-memory index is named like the in-memory pack it belongs to"
+    );
+    assert!(!idx.path().exists(), "in-memory files are not on disk");
+
+    let on_disk = index::File::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+    let on_disk_pack = pack::data::File::at(fixture_path(SMALL_PACK), git_hash::Kind::Sha1)?;
+    for algo in ALGORITHMS {
+        let verify = |idx: &index::File, pack: &pack::data::File| {
+            idx.verify_integrity(
+                Some(git_pack::index::verify::PackContext {
+                    data: pack,
+                    options: git_pack::index::verify::integrity::Options {
+                        verify_mode: index::verify::Mode::HashCrc32DecodeEncode,
+                        traversal: *algo,
+                        make_pack_lookup_cache: || cache::Never,
+                        thread_limit: None,
+                        max_decompressed_object_size: None,
+                        tree_checks: Default::default(),
+                        keep_going: false,
+                        paranoid: false,
+                        strict_entry_boundaries: false,
+                    },
+                }),
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+            )
+            .map(|o| (o.actual_index_checksum, o.pack_traverse_statistics))
+        };
+        assert_eq!(
+            verify(&idx, &pack)?,
+            verify(&on_disk, &on_disk_pack)?,
+            "{:?}: memory-backed files verify just like the ones on disk",
+            algo
+        );
+    }
+    assert_eq!(
+        pack.streaming_iter()?.count(),
+        idx.num_objects() as usize,
+        "streaming works without a file on disk"
+    );
+    Ok(())
+}
+
+#[test]
+fn entry_ranges_suffice_to_decode_objects() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+    let pack_data = std::fs::read(fixture_path(SMALL_PACK))?;
+    let pack = pack::data::File::from_bytes(pack_data.clone(), git_hash::Kind::Sha1)?;
+    const PACK_HEADER_LEN: usize = 12;
+
+    let (mut expected, mut actual) = (Vec::new(), Vec::new());
+    let mut num_deltas = 0;
+    for entry in idx.iter() {
+        let ranges = idx.entry_ranges(&pack, entry.oid)?.expect("object is in index");
+        assert!(
+            ranges.windows(2).all(|w| w[0].end < w[1].start),
+            "ranges are sorted and adjacent ones are merged"
+        );
+        assert!(ranges.iter().any(|range| range.contains(&entry.pack_offset)));
+        if pack.entry(entry.pack_offset).header.is_delta() {
+            num_deltas += 1;
+        }
+
+        // Only keep the pack header and the bytes of the ranges, everything else is garbage.
+        let mut sparse_data = vec![0xff; pack_data.len()];
+        sparse_data[..PACK_HEADER_LEN].copy_from_slice(&pack_data[..PACK_HEADER_LEN]);
+        for range in &ranges {
+            let range = range.start as usize..range.end as usize;
+            sparse_data[range.clone()].copy_from_slice(&pack_data[range]);
+        }
+        let sparse = pack::data::File::from_bytes(sparse_data, git_hash::Kind::Sha1)?;
+
+        let decode = |pack: &pack::data::File, out: &mut Vec<u8>| {
+            pack.decode_entry(
+                pack.entry(entry.pack_offset),
+                out,
+                |id, _| {
+                    idx.lookup(id)
+                        .map(|i| pack::data::ResolvedBase::InPack(pack.entry(idx.pack_offset_at_index(i))))
+                },
+                &mut cache::Never,
+            )
+            .map(|outcome| outcome.kind)
+        };
+        let expected_kind = decode(&pack, &mut expected)?;
+        assert_eq!(decode(&sparse, &mut actual)?, expected_kind);
+        assert_eq!(actual, expected, "{} decodes from its ranges alone", entry.oid);
+    }
+    assert!(num_deltas > 0, "the fixture contains delta objects");
+    assert_eq!(
+        idx.entry_ranges(&pack, hex_to_id("ffffffffffffffffffffffffffffffffffffffff"))?,
+        None,
+        "objects that aren't in the index have no ranges"
+    );
+    Ok(())
+}
+
+#[test]
+fn verbatim_reusable_ranges_contain_wanted_entries_along_with_their_bases() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(SMALL_PACK), git_hash::Kind::Sha1)?;
+    let mut all: Vec<_> = idx.iter().map(|e| e.oid).collect();
+    all.sort();
+
+    let plan = idx.verbatim_reusable(&pack, &all)?;
+    assert_eq!(
+        plan.ranges,
+        vec![pack::index::verbatim_reusable::Range {
+            pack_offsets: 12..pack.pack_end() as u64,
+            num_objects: idx.num_objects(),
+        }],
+        "everything is reusable if everything is wanted"
+    );
+    assert_eq!(plan.num_wanted, idx.num_objects());
+    assert_eq!(plan.num_reusable_objects(), idx.num_objects());
+
+    let plan = idx.verbatim_reusable(&pack, &[])?;
+    assert!(plan.ranges.is_empty());
+    assert_eq!(plan.num_wanted, 0);
+    assert_eq!(plan.num_objects, idx.num_objects());
+
+    let (delta_offset, base_offset) = idx
+        .iter()
+        .find_map(|e| match pack.entry(e.pack_offset).header {
+            pack::data::entry::Header::OfsDelta { base_distance } => {
+                Some((e.pack_offset, e.pack_offset - base_distance))
+            }
+            _ => None,
+        })
+        .expect("the fixture has offset deltas");
+    let base_id = idx
+        .iter()
+        .find(|e| e.pack_offset == base_offset)
+        .expect("base is in pack")
+        .oid;
+    let wants: Vec<_> = all.iter().copied().filter(|id| *id != base_id).collect();
+    let plan = idx.verbatim_reusable(&pack, &wants)?;
+    assert_eq!(plan.num_wanted, idx.num_objects() - 1);
+    assert!(
+        plan.num_reusable_objects() < plan.num_wanted,
+        "the delta of the unwanted base can't be reused either"
+    );
+    for offset in [base_offset, delta_offset] {
+        assert!(
+            !plan.ranges.iter().any(|r| r.pack_offsets.contains(&offset)),
+            "neither the base nor its delta are in any range"
+        );
+    }
+    assert!(
+        plan.ranges
+            .windows(2)
+            .all(|w| w[0].pack_offsets.end < w[1].pack_offsets.start),
+        "ranges are maximal"
+    );
+    Ok(())
+}
+
+#[test]
+fn verify_integrity_reports_index_oids_not_matching_the_pack() -> Result<(), Box<dyn std::error::Error>> {
+    // The index belongs to the pack as far as checksums and CRC32 go, but two of its object ids were altered.
+    let idx = index::File::at(
+        fixture_path("objects/pack-with-mismatched-index/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx"),
+        git_hash::Kind::Sha1,
+    )?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+    let expected_mismatches = vec![
+        index::traverse::IndexOidMismatch {
+            index_oid: hex_to_id("6674d310d179400358d581f9725cbd4a2c32e3c0"),
+            actual_oid: hex_to_id("6674d310d179400358d581f9725cbd4a2c32e3bf"),
+            pack_offset: 810,
+        },
+        index::traverse::IndexOidMismatch {
+            index_oid: hex_to_id("1a480b442042edd4a6bacae41bf4113727e7a131"),
+            actual_oid: hex_to_id("1a480b442042edd4a6bacae41bf4113727e7a130"),
+            pack_offset: 50227,
+        },
+    ];
+
+    // The same progress is used throughout as it remains with us even if verification fails.
+    let mut progress = progress::Discard;
+    for algo in ALGORITHMS {
+        for mode in MODES {
+            let err = idx
+                .verify_integrity(
+                    Some(git_pack::index::verify::PackContext {
+                        data: &pack,
+                        options: git_pack::index::verify::integrity::Options {
+                            verify_mode: *mode,
+                            traversal: *algo,
+                            make_pack_lookup_cache: || cache::Never,
+                            thread_limit: None,
+                            max_decompressed_object_size: None,
+                            tree_checks: Default::default(),
+                            keep_going: false,
+                            paranoid: false,
+                            strict_entry_boundaries: false,
+                        },
+                    }),
+                    &mut progress,
+                    &AtomicBool::new(false),
+                )
+                .map(|_| ())
+                .expect_err("mismatches are detected");
+            match err {
+                index::traverse::Error::IndexOidMismatch { count, first } => {
+                    assert_eq!(count, 2, "{:?} -> {:?}", algo, mode);
+                    assert_eq!(first, expected_mismatches, "{:?} -> {:?}", algo, mode);
+                }
+                err => panic!("{:?} -> {:?}: unexpected error {:?}", algo, mode, err),
+            }
+        }
+
+        let processed = AtomicUsize::default();
+        let outcome = idx.traverse(
+            &pack,
+            &mut progress,
+            &AtomicBool::new(false),
+            || {
+                |_, _, _, _| {
+                    processed.fetch_add(1, Ordering::SeqCst);
+                    Ok::<_, std::io::Error>(())
+                }
+            },
+            index::traverse::Options {
+                traversal: *algo,
+                thread_limit: None,
+                check: index::traverse::SafetyCheck::All,
+                make_pack_lookup_cache: || cache::Never,
+                max_decompressed_object_size: None,
+                partition: None,
+                paranoid: false,
+                strict_entry_boundaries: false,
+            },
+        )?;
+        assert_eq!(outcome.statistics.index_oid_mismatches, 2);
+        assert_eq!(outcome.statistics.first_index_oid_mismatches, expected_mismatches);
+        assert_eq!(
+            processed.load(Ordering::SeqCst),
+            idx.num_objects() as usize - 2,
+            "{:?}: mismatching objects aren't passed to the processor",
+            algo
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn traverse_fails_in_paranoid_mode_if_the_pack_changes_during_traversal() -> Result<(), Box<dyn std::error::Error>> {
+    for algo in ALGORITHMS {
+        for paranoid in [false, true] {
+            let dir = tempfile::tempdir()?;
+            let pack_path = dir.path().join("pack.pack");
+            std::fs::copy(fixture_path(PACK_FOR_INDEX_V2), &pack_path)?;
+            let idx = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+            let pack = pack::data::File::at(&pack_path, git_hash::Kind::Sha1)?;
+
+            let mutated = AtomicBool::new(false);
+            let res = idx.traverse(
+                &pack,
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+                || {
+                    |_, _, _, _| {
+                        if !mutated.swap(true, Ordering::SeqCst) {
+                            use std::io::Write;
+                            std::fs::OpenOptions::new()
+                                .append(true)
+                                .open(&pack_path)?
+                                .write_all(b"concurrent write")?;
+                        }
+                        Ok::<_, std::io::Error>(())
+                    }
+                },
+                index::traverse::Options {
+                    traversal: *algo,
+                    thread_limit: None,
+                    check: index::traverse::SafetyCheck::SkipFileChecksumVerification,
+                    make_pack_lookup_cache: || cache::Never,
+                    max_decompressed_object_size: None,
+                    partition: None,
+                    paranoid,
+                    strict_entry_boundaries: false,
+                },
+            );
+            assert!(mutated.load(Ordering::SeqCst));
+            if paranoid {
+                assert!(
+                    matches!(
+                        res,
+                        Err(index::traverse::Error::PackMutatedDuringVerification { ref path }) if path == &pack_path
+                    ),
+                    "{:?}: the change is detected",
+                    algo
+                );
+            } else {
+                assert!(res.is_ok(), "{:?}: the change goes unnoticed", algo);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn traverse_with_info_matches_git_verify_pack() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+    let oid_by_offset: std::collections::HashMap<_, _> = idx.iter().map(|e| (e.pack_offset, e.oid)).collect();
+    let expected: Vec<_> = std::fs::read_to_string(fixture_path(
+        "verify-pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.txt",
+    ))?
+    .lines()
+    .filter(|line| line.split(' ').next().map_or(false, |oid| oid.len() == 40))
+    .map(ToOwned::to_owned)
+    .collect();
+
+    for algo in ALGORITHMS {
+        let entries = std::sync::Mutex::new(Vec::new());
+        idx.traverse_with_info(
+            &pack,
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+            || {
+                |kind,
+                 _data: &[u8],
+                 entry: &index::Entry,
+                 info: &index::traverse::EntryInfo,
+                 _tally: &mut _,
+                 _progress| {
+                    entries
+                        .lock()
+                        .unwrap()
+                        .push((entry.pack_offset, entry.oid, kind, *info));
+                    Ok::<_, std::io::Error>(())
+                }
+            },
+            index::traverse::Options {
+                traversal: *algo,
+                thread_limit: None,
+                check: index::traverse::SafetyCheck::All,
+                make_pack_lookup_cache: || cache::Never,
+                max_decompressed_object_size: None,
+                partition: None,
+                paranoid: false,
+                strict_entry_boundaries: false,
+            },
+        )?;
+        let mut entries = entries.into_inner().unwrap();
+        entries.sort_by_key(|e| e.0);
+        let actual: Vec<_> = entries
+            .into_iter()
+            .map(|(pack_offset, oid, kind, info)| {
+                let mut line = format!(
+                    "{} {:<6} {} {} {}",
+                    oid,
+                    kind.to_string(),
+                    info.decompressed_size,
+                    info.size_in_pack(),
+                    pack_offset
+                );
+                let base = match info.storage {
+                    index::traverse::Storage::Base => None,
+                    index::traverse::Storage::OfsDelta { base_pack_offset } => Some(oid_by_offset[&base_pack_offset]),
+                    index::traverse::Storage::RefDelta { base_id } => Some(base_id),
+                };
+                if let Some(base) = base {
+                    line.push_str(&format!(" {} {}", info.chain_length, base));
+                } else {
+                    assert_eq!(info.chain_length, 0, "base objects have no delta chain");
+                }
+                line
+            })
+            .collect();
+        assert_eq!(actual, expected, "{:?}", algo);
+    }
+    Ok(())
+}
+
+#[test]
+fn traversing_all_partitions_and_merging_them_is_like_traversing_the_whole_pack(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+    let traverse = |partition: Option<index::traverse::Partition>| {
+        idx.traverse(
+            &pack,
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+            || |_, _, _, _| Ok::<_, std::io::Error>(()),
+            index::traverse::Options {
+                traversal: index::traverse::Algorithm::DeltaTreeLookup,
+                thread_limit: None,
+                check: index::traverse::SafetyCheck::SkipFileChecksumVerification,
+                make_pack_lookup_cache: || cache::Never,
+                max_decompressed_object_size: None,
+                partition,
+                paranoid: false,
+                strict_entry_boundaries: false,
+            },
+        )
+    };
+    let whole = traverse(None)?;
+
+    let count = 3;
+    let partitions = (0..count)
+        .map(|index| {
+            let partition = index::traverse::Partition { index, count };
+            let outcome = traverse(Some(partition))?;
+            assert_eq!(outcome.partition, Some(partition));
+            assert!(
+                outcome.statistics.num_objects() < idx.num_objects() as u64,
+                "each partition only sees a part of the pack"
+            );
+            Ok(outcome)
+        })
+        .collect::<Result<Vec<_>, index::traverse::Error<std::io::Error>>>()?;
+
+    let merged = index::traverse::Outcome::merge(partitions)?;
+    assert_eq!(merged.partition, None);
+    assert_eq!(merged.actual_index_checksum, whole.actual_index_checksum);
+    let (actual, expected) = (&merged.statistics, &whole.statistics);
+    assert_eq!(actual.num_objects(), idx.num_objects() as u64);
+    assert_eq!(actual.objects_per_chain_length, expected.objects_per_chain_length);
+    assert_eq!(
+        actual.total_compressed_entries_size,
+        expected.total_compressed_entries_size
+    );
+    assert_eq!(
+        actual.total_decompressed_entries_size,
+        expected.total_decompressed_entries_size
+    );
+    assert_eq!(actual.total_object_size, expected.total_object_size);
+    assert_eq!(
+        (actual.num_commits, actual.num_trees, actual.num_tags, actual.num_blobs),
+        (
+            expected.num_commits,
+            expected.num_trees,
+            expected.num_tags,
+            expected.num_blobs
+        )
+    );
+
+    assert!(matches!(
+        index::traverse::Outcome::merge(None),
+        Err(index::traverse::merge::Error::Empty)
+    ));
+    let incomplete = (0..count - 1)
+        .map(|index| traverse(Some(index::traverse::Partition { index, count })))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(matches!(
+        index::traverse::Outcome::merge(incomplete),
+        Err(index::traverse::merge::Error::IncompletePartitions { count: 3 })
+    ));
+
+    assert!(matches!(
+        traverse(Some(index::traverse::Partition { index: count, count })),
+        Err(index::traverse::Error::InvalidPartition { index: 3, count: 3 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn traverse_plan_agrees_with_the_statistics_of_the_traversal() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+    let options = |traversal, partition| index::traverse::Options {
+        traversal,
+        thread_limit: Some(2),
+        check: index::traverse::SafetyCheck::SkipFileChecksumVerification,
+        make_pack_lookup_cache: || cache::Never,
+        max_decompressed_object_size: None,
+        partition,
+        paranoid: false,
+        strict_entry_boundaries: false,
+    };
+    let stats = idx
+        .traverse(
+            &pack,
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+            || |_, _, _, _| Ok::<_, std::io::Error>(()),
+            options(index::traverse::Algorithm::DeltaTreeLookup, None),
+        )?
+        .statistics;
+    let num_deltas: u64 = stats
+        .objects_per_chain_length
+        .iter()
+        .map(|(chain_length, count)| *chain_length as u64 * *count as u64)
+        .sum();
+
+    for algo in ALGORITHMS {
+        let plan = idx.traverse_plan(&pack, &options(*algo, None))?;
+        assert_eq!(plan.algorithm, *algo);
+        assert_eq!(plan.num_entries, idx.num_objects());
+        assert_eq!(plan.num_roots, stats.objects_per_chain_length[&0]);
+        assert_eq!(
+            plan.max_chain_length,
+            *stats.objects_per_chain_length.keys().max().expect("non-empty")
+        );
+        assert_eq!(
+            plan.average_chain_length,
+            num_deltas as f32 / idx.num_objects() as f32,
+            "{:?}",
+            algo
+        );
+        assert_eq!(plan.decompressed_entries_size, stats.total_decompressed_entries_size);
+        assert_eq!(plan.entries_per_thread.len(), plan.num_threads);
+        assert_eq!(
+            plan.entries_per_thread.iter().sum::<usize>(),
+            idx.num_objects() as usize,
+            "{:?}: each entry is handed to exactly one thread",
+            algo
+        );
+        assert_eq!(plan.chunk_size.is_some(), *algo == index::traverse::Algorithm::Lookup);
+    }
+
+    let count = 3;
+    let num_entries: u32 = (0..count)
+        .map(|index| {
+            let plan = idx
+                .traverse_plan(
+                    &pack,
+                    &options(
+                        index::traverse::Algorithm::DeltaTreeLookup,
+                        Some(index::traverse::Partition { index, count }),
+                    ),
+                )
+                .expect("readable pack");
+            assert_eq!(
+                plan.algorithm,
+                index::traverse::Algorithm::Lookup,
+                "partitions are always traversed by lookup"
+            );
+            plan.num_entries
+        })
+        .sum();
+    assert_eq!(
+        num_entries,
+        idx.num_objects(),
+        "partitions plan for all entries together"
+    );
+    assert_eq!(
+        idx.traverse_plan(
+            &pack,
+            &options(
+                index::traverse::Algorithm::Lookup,
+                Some(index::traverse::Partition { index: count, count })
+            )
+        )?
+        .num_entries,
+        0,
+        "invalid partitions have nothing to do"
+    );
+    Ok(())
+}
+
+#[test]
+fn tallies_of_all_threads_are_merged_without_sharing_state_between_them() -> Result<(), Box<dyn std::error::Error>> {
+    for (index_path, data_path) in V2_PACKS_AND_INDICES {
+        let idx = index::File::at(fixture_path(index_path), git_hash::Kind::Sha1)?;
+        let pack = pack::data::File::at(fixture_path(data_path), git_hash::Kind::Sha1)?;
+        let mut expected = None;
+        for algo in ALGORITHMS {
+            for thread_limit in [Some(1), Some(4), None] {
+                // Repeat to give threads a chance to interleave differently.
+                for _round in 0..10 {
+                    let outcome = idx.traverse_with_info(
+                        &pack,
+                        &mut progress::Discard,
+                        &AtomicBool::new(false),
+                        || {
+                            |kind: object::Kind,
+                             data: &[u8],
+                             entry: &index::Entry,
+                             _info: &index::traverse::EntryInfo,
+                             tally: &mut index::traverse::Tally,
+                             _progress: &mut _| {
+                                tally.add(&kind.to_string(), 1);
+                                tally.add("bytes", data.len() as u64);
+                                if kind == object::Kind::Tag {
+                                    tally.warn(format!("found tag {}", entry.oid));
+                                }
+                                Ok::<_, std::io::Error>(())
+                            }
+                        },
+                        index::traverse::Options {
+                            traversal: *algo,
+                            thread_limit,
+                            check: index::traverse::SafetyCheck::All,
+                            make_pack_lookup_cache: || cache::Never,
+                            max_decompressed_object_size: None,
+                            partition: None,
+                            paranoid: false,
+                            strict_entry_boundaries: false,
+                        },
+                    )?;
+                    let stats = &outcome.statistics;
+                    let mut tally = outcome.tally;
+                    assert_eq!(tally.count("commit"), stats.num_commits as u64);
+                    assert_eq!(tally.count("tree"), stats.num_trees as u64);
+                    assert_eq!(tally.count("blob"), stats.num_blobs as u64);
+                    assert_eq!(tally.count("tag"), stats.num_tags as u64);
+                    assert_eq!(tally.count("bytes"), stats.total_object_size);
+                    assert_eq!(tally.warnings.len(), stats.num_tags as usize);
+                    tally.warnings.sort();
+                    match &expected {
+                        None => expected = Some(tally),
+                        Some(expected) => assert_eq!(
+                            &tally, expected,
+                            "{:?} with {:?} threads: the outcome doesn't depend on the traversal",
+                            algo, thread_limit
+                        ),
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn streaming_traversal_decodes_bases_again_only_if_they_are_not_cached() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+    let traverse = |traversal: index::traverse::Algorithm| {
+        let ids = std::sync::Mutex::new(Vec::new());
+        let outcome = {
+            let ids = &ids;
+            idx.traverse(
+                &pack,
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+                move || {
+                    move |_, _: &[u8], entry: &index::Entry, _: &mut _| {
+                        ids.lock().expect("no panic").push(entry.oid);
+                        Ok::<_, std::io::Error>(())
+                    }
+                },
+                index::traverse::Options {
+                    traversal,
+                    thread_limit: Some(1),
+                    check: index::traverse::SafetyCheck::All,
+                    make_pack_lookup_cache: || cache::Never,
+                    max_decompressed_object_size: None,
+                    partition: None,
+                    paranoid: false,
+                    strict_entry_boundaries: false,
+                },
+            )
+        };
+        outcome.map(|outcome| (outcome, ids.into_inner().expect("no panic")))
+    };
+
+    let (lookup, mut expected_ids) = traverse(index::traverse::Algorithm::Lookup)?;
+    assert_eq!(lookup.num_redecoded_bases, None, "only tracked when streaming");
+    expected_ids.sort();
+    for (cache_memory_cap_in_bytes, expected_redecoded_bases) in [(0, 32), (1024 * 1024, 0)] {
+        let (streaming, mut ids) = traverse(index::traverse::Algorithm::Streaming {
+            cache_memory_cap_in_bytes,
+        })?;
+        assert_eq!(
+            streaming.num_redecoded_bases,
+            Some(expected_redecoded_bases),
+            "without cache, the 12 deltas decode 32 bases along their chains, otherwise bases are cached as they precede their deltas"
+        );
+        assert_eq!(streaming.actual_index_checksum, lookup.actual_index_checksum);
+        assert_eq!(streaming.statistics, lookup.statistics);
+        ids.sort();
+        assert_eq!(ids, expected_ids, "all objects are processed");
+    }
+    Ok(())
+}
+
+#[test]
+fn verify_integrity_fails_early_if_pack_and_index_disagree_on_the_object_count(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(crate::pack::PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+    assert_ne!(pack.num_objects(), idx.num_objects());
+
+    let err = idx
+        .verify_integrity(
+            Some(git_pack::index::verify::PackContext {
+                data: &pack,
+                options: git_pack::index::verify::integrity::Options::default(),
+            }),
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+        )
+        .map(|_| ())
+        .expect_err("the object counts don't match");
+    match err {
+        index::traverse::Error::Processor(index::verify::integrity::Error::ObjectCountMismatch {
+            pack_objects,
+            index_objects,
+            pack_path,
+            index_path,
+        }) => {
+            assert_eq!(pack_objects, 30);
+            assert_eq!(index_objects, 42);
+            assert_eq!(pack_path, pack.path());
+            assert_eq!(index_path, idx.path());
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+    Ok(())
+}
+
+#[test]
+fn duplicate_offsets_are_detected_instead_of_yielding_empty_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let repo = crate::scripted_fixture_repo_read_only("make_pack_with_duplicate_offsets.sh")?;
+    let idx = index::File::at(repo.join("duplicate-offset.idx"), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(repo.join("duplicate-offset.pack"), git_hash::Kind::Sha1)?;
+    let offset: u64 = std::fs::read_to_string(repo.join("offsets"))?
+        .split_whitespace()
+        .next()
+        .expect("first offset")
+        .parse()?;
+    let mut ids: Vec<_> = std::fs::read_to_string(repo.join("ids"))?
+        .split_whitespace()
+        .map(|hex| hex_to_id(hex))
+        .collect();
+    ids.sort();
+
+    assert_eq!(
+        idx.first_duplicate_offset(),
+        Some((offset, ids[0], ids[1])),
+        "both entries are named, in the order of their ids"
+    );
+    assert_eq!(
+        idx.verify_checksum(progress::Discard, &AtomicBool::new(false))?,
+        idx.index_checksum(),
+        "the index is only corrupt structurally"
+    );
+
+    let assert_duplicate_offset_error = |pack: Option<&pack::data::File>, traversal| {
+        let err = idx
+            .verify_integrity(
+                pack.map(|pack| git_pack::index::verify::PackContext {
+                    data: pack,
+                    options: git_pack::index::verify::integrity::Options {
+                        traversal,
+                        ..Default::default()
+                    },
+                }),
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+            )
+            .map(|_| ())
+            .expect_err("duplicate offsets are detected");
+        match err {
+            index::traverse::Error::Processor(index::verify::integrity::Error::DuplicateOffset {
+                offset: actual_offset,
+                first,
+                second,
+            }) => {
+                assert_eq!(actual_offset, offset);
+                assert_eq!([first, second], [ids[0], ids[1]]);
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+    };
+    assert_duplicate_offset_error(None, Default::default());
+    for algo in ALGORITHMS {
+        assert_duplicate_offset_error(Some(&pack), *algo);
+    }
+
+    for id in &ids {
+        match idx.entry_ranges(&pack, id) {
+            Err(index::entry_ranges::Error::EmptyEntry { offset: start, end }) => {
+                assert_eq!((start, end), (offset, offset), "the entry would cover zero bytes");
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+
+    for algo in &[
+        index::traverse::Algorithm::Lookup,
+        index::traverse::Algorithm::DeltaTreeLookup,
+    ] {
+        let res = idx.traverse(
+            &pack,
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+            || |_, _, _, _| Ok::<_, std::io::Error>(()),
+            index::traverse::Options {
+                traversal: *algo,
+                ..Default::default()
+            },
+        );
+        assert!(
+            res.is_err(),
+            "{:?}: traversals based on index offsets fail instead of panicking",
+            algo
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn canonical_trees_take_the_fast_path_and_agree_with_decoding_and_re_encoding() -> Result<(), Box<dyn std::error::Error>>
+{
+    use git_object::WriteTo;
+
+    fn decodes_and_encodes_identically(data: &[u8]) -> bool {
+        object::TreeRef::from_bytes(data).map_or(false, |tree| {
+            let mut buf = Vec::new();
+            tree.write_to(&mut buf).is_ok() && buf == data
+        })
+    }
+
+    /// Return each entry of `tree` including its mode, name and id.
+    fn raw_entries(mut tree: &[u8]) -> Vec<&[u8]> {
+        let mut out = Vec::new();
+        while let Some(nul) = tree.iter().position(|b| *b == 0) {
+            let (entry, rest) = tree.split_at(nul + 1 + SHA1_SIZE);
+            out.push(entry);
+            tree = rest;
+        }
+        out
+    }
+
+    let pack_dir = crate::scripted_fixture_repo_read_only("make_pack_gen_repo.sh")?
+        .join(".git")
+        .join("objects")
+        .join("pack");
+    let index_path = std::fs::read_dir(&pack_dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|path| path.extension().map_or(false, |ext| ext == "idx"))
+        .expect("a pack was created by garbage collection");
+    let bundle = git_pack::Bundle::at(&index_path, git_hash::Kind::Sha1)?;
+
+    let mut buf = Vec::new();
+    let (mut num_trees, mut num_commits_and_tags) = (0, 0);
+    for entry in bundle.index.iter() {
+        let (object, _location) = bundle
+            .find(entry.oid, &mut buf, &mut cache::Never)?
+            .expect("object is in pack");
+        match object.kind {
+            object::Kind::Tree => num_trees += 1,
+            object::Kind::Commit | object::Kind::Tag => {
+                num_commits_and_tags += 1;
+                continue;
+            }
+            object::Kind::Blob => continue,
+        }
+        let tree = object.data.to_owned();
+        assert!(object::TreeRef::is_canonical(&tree), "git writes canonical trees");
+        assert!(decodes_and_encodes_identically(&tree));
+
+        let entries = raw_entries(&tree);
+        let mut variants = vec![tree[..tree.len() - 1].to_vec(), [entries[0], &tree[..]].concat()];
+        if entries.len() > 1 {
+            variants.push([entries[1], entries[0], &entries[2..].concat()[..]].concat());
+        }
+        for (pos, entry) in entries.iter().enumerate() {
+            let mode_len = entry
+                .iter()
+                .position(|b| *b == b' ')
+                .expect("mode is followed by space");
+            let mode: &[u8] = match &entry[..mode_len] {
+                b"40000" => b"040000",
+                _ => b"100664",
+            };
+            variants.push(
+                [
+                    &entries[..pos].concat()[..],
+                    mode,
+                    &entry[mode_len..],
+                    &entries[pos + 1..].concat()[..],
+                ]
+                .concat(),
+            );
+        }
+        for variant in variants {
+            assert!(
+                !object::TreeRef::is_canonical(&variant),
+                "{}: altered trees are never canonical",
+                entry.oid
+            );
+        }
+    }
+    assert!(num_trees > 0 && num_commits_and_tags > 0);
+
+    for mode in [
+        index::verify::Mode::HashCrc32Decode,
+        index::verify::Mode::HashCrc32DecodeEncode,
+    ] {
+        let outcome = bundle.verify_integrity(
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+            git_pack::index::verify::integrity::Options {
+                verify_mode: mode,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            outcome.decode_statistics,
+            index::verify::integrity::DecodeStatistics {
+                fast_path: num_trees,
+                slow_path: num_commits_and_tags,
+            },
+            "{:?}: only commits and tags need to be decoded",
+            mode
+        );
+    }
+
+    let outcome = bundle.verify_integrity(
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+        git_pack::index::verify::integrity::Options {
+            verify_mode: index::verify::Mode::HashCrc32,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.decode_statistics,
+        Default::default(),
+        "nothing is decoded if only hashes are checked"
+    );
+    Ok(())
+}
+
+#[test]
+fn verify_integrity_outcome_identifies_the_files_on_disk() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(SMALL_PACK), git_hash::Kind::Sha1)?;
+    let outcome = idx.verify_integrity(
+        Some(git_pack::index::verify::PackContext {
+            data: &pack,
+            options: Default::default(),
+        }),
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+    )?;
+    let index_file = outcome.index_file.expect("index is on disk");
+    assert_eq!(index_file.path, fixture_path(SMALL_PACK_INDEX));
+    assert_eq!(
+        index_file.size,
+        std::fs::metadata(fixture_path(SMALL_PACK_INDEX))?.len()
+    );
+    assert!(index_file.modified.is_some());
+    let pack_file = outcome.pack_file.expect("pack is on disk");
+    assert_eq!(pack_file.path, fixture_path(SMALL_PACK));
+    assert_eq!(pack_file.size, std::fs::metadata(fixture_path(SMALL_PACK))?.len());
+
+    let outcome = idx.verify_integrity(
+        None::<git_pack::index::verify::PackContext<'_, fn() -> cache::Never>>,
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+    )?;
+    assert!(outcome.index_file.is_some());
+    assert!(outcome.pack_file.is_none(), "there is no pack to describe");
+
+    let idx = index::File::from_bytes(std::fs::read(fixture_path(SMALL_PACK_INDEX))?, git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::from_bytes(std::fs::read(fixture_path(SMALL_PACK))?, git_hash::Kind::Sha1)?;
+    let outcome = idx.verify_integrity(
+        Some(git_pack::index::verify::PackContext {
+            data: &pack,
+            options: Default::default(),
+        }),
+        &mut progress::Discard,
+        &AtomicBool::new(false),
+    )?;
+    assert!(
+        outcome.index_file.is_none() && outcome.pack_file.is_none(),
+        "memory-backed files have no file information"
+    );
+    Ok(())
+}
+
+mod recording_progress {
+    use std::sync::{Arc, Mutex};
+
+    use git_features::progress::{self, Progress, Unit};
+
+    /// What was passed to [`Progress::init()`] of a child, along with the name it was created with.
+    #[derive(Debug, Clone)]
+    pub struct Init {
+        pub name: String,
+        pub max: Option<usize>,
+        pub unit: Option<String>,
+    }
+
+    /// A progress implementation which records each call to `init()` of itself and all of its children.
+    #[derive(Clone, Default)]
+    pub struct Recorder {
+        name: String,
+        step: usize,
+        pub inits: Arc<Mutex<Vec<Init>>>,
+    }
+
+    impl Recorder {
+        /// The last initialization of the child whose name starts with `prefix`.
+        pub fn init_of(&self, prefix: &str) -> Option<Init> {
+            self.inits
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .find(|init| init.name.starts_with(prefix))
+                .cloned()
+        }
+    }
+
+    fn render(unit: Unit) -> String {
+        let mut buf = String::new();
+        unit.as_display_value().display_unit(&mut buf, 0).ok();
+        buf
+    }
+
+    impl Progress for Recorder {
+        type SubProgress = Recorder;
+
+        fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+            Recorder {
+                name: name.into(),
+                step: 0,
+                inits: self.inits.clone(),
+            }
+        }
+
+        fn add_child_with_id(&mut self, name: impl Into<String>, _id: [u8; 4]) -> Self::SubProgress {
+            self.add_child(name)
+        }
+
+        fn init(&mut self, max: Option<usize>, unit: Option<Unit>) {
+            self.inits.lock().unwrap().push(Init {
+                name: self.name.clone(),
+                max,
+                unit: unit.map(render),
+            });
+        }
+
+        fn set(&mut self, step: usize) {
+            self.step = step;
+        }
+
+        fn step(&self) -> usize {
+            self.step
+        }
+
+        fn inc_by(&mut self, step: usize) {
+            self.step += step;
+        }
+
+        fn set_name(&mut self, name: impl Into<String>) {
+            self.name = name.into();
+        }
+
+        fn name(&self) -> Option<String> {
+            Some(self.name.clone())
+        }
+
+        fn id(&self) -> [u8; 4] {
+            progress::UNKNOWN
+        }
+
+        fn message(&mut self, _level: progress::MessageLevel, _message: impl Into<String>) {}
+    }
+}
+
+#[test]
+fn verify_integrity_reports_bytes_for_hashing_and_objects_for_decoding() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(SMALL_PACK), git_hash::Kind::Sha1)?;
+    let bytes_unit = {
+        let mut buf = String::new();
+        progress::bytes()
+            .expect("bytes unit")
+            .as_display_value()
+            .display_unit(&mut buf, 0)?;
+        buf
+    };
+    for algo in ALGORITHMS {
+        let mut progress = recording_progress::Recorder::default();
+        idx.verify_integrity(
+            Some(git_pack::index::verify::PackContext {
+                data: &pack,
+                options: git_pack::index::verify::integrity::Options {
+                    traversal: *algo,
+                    ..Default::default()
+                },
+            }),
+            &mut progress,
+            &AtomicBool::new(false),
+        )?;
+
+        let hash_index = progress.init_of("hash index").expect("index is hashed");
+        assert_eq!(
+            hash_index.max,
+            Some(std::fs::metadata(fixture_path(SMALL_PACK_INDEX))?.len() as usize - SHA1_SIZE),
+            "{:?}",
+            algo
+        );
+        assert_eq!(hash_index.unit.as_deref(), Some(bytes_unit.as_str()));
+        let hash_pack = progress.init_of("hash pack").expect("pack is hashed");
+        assert_eq!(hash_pack.max, Some(pack.data_len() - SHA1_SIZE), "{:?}", algo);
+        assert_eq!(hash_pack.unit.as_deref(), Some(bytes_unit.as_str()));
+        let decode = progress.init_of("decode objects").expect("objects are decoded");
+        assert_eq!(decode.max, Some(idx.num_objects() as usize), "{:?}", algo);
+        assert_eq!(decode.unit.as_deref(), Some("objects"), "{:?}", algo);
+    }
+
+    let mut progress = recording_progress::Recorder::default();
+    idx.verify_integrity(
+        None::<git_pack::index::verify::PackContext<'_, fn() -> cache::Never>>,
+        &mut progress,
+        &AtomicBool::new(false),
+    )?;
+    let hash_index = progress.init_of("hash index").expect("index is hashed without a pack");
+    assert_eq!(
+        hash_index.max,
+        Some(std::fs::metadata(fixture_path(SMALL_PACK_INDEX))?.len() as usize - SHA1_SIZE)
+    );
+    assert_eq!(hash_index.unit.as_deref(), Some(bytes_unit.as_str()));
+    assert!(progress.init_of("decode objects").is_none(), "nothing is decoded");
+    Ok(())
+}
+
+#[test]
+fn verify_integrity_reports_ref_delta_bases_missing_in_the_pack() -> Result<(), Box<dyn std::error::Error>> {
+    // A blob, a ref-delta on top of it, a ref-delta whose base isn't in the pack, and an ofs-delta on top of that.
+    let idx = index::File::at(
+        fixture_path("objects/pack-with-missing-ref-delta-base/pack-1ded35d80f3092404f51827242a8a50c3c3d6031.idx"),
+        git_hash::Kind::Sha1,
+    )?;
+    let pack = pack::data::File::at(idx.path().with_extension("pack"), git_hash::Kind::Sha1)?;
+    let missing_base = hex_to_id("6a579cb661476b885b5b24afa3041e306b3353de");
+    assert_eq!(idx.missing_ref_delta_bases(&pack)?, vec![missing_base]);
+
+    let small_pack = pack::data::File::at(fixture_path(SMALL_PACK), git_hash::Kind::Sha1)?;
+    let small_pack_index = index::File::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+    assert!(
+        small_pack_index.missing_ref_delta_bases(&small_pack)?.is_empty(),
+        "packs on disk are self-contained"
+    );
+
+    let options = |traversal, keep_going| git_pack::index::verify::integrity::Options {
+        traversal,
+        keep_going,
+        ..Default::default()
+    };
+    for algo in ALGORITHMS {
+        let err = idx
+            .verify_integrity(
+                Some(git_pack::index::verify::PackContext {
+                    data: &pack,
+                    options: options(*algo, false),
+                }),
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+            )
+            .map(|_| ())
+            .expect_err("missing bases are detected before traversing");
+        match err {
+            index::traverse::Error::Processor(index::verify::integrity::Error::MissingDeltaBase {
+                entry_oid,
+                base_oid,
+                offset,
+            }) => {
+                assert_eq!(entry_oid, hex_to_id("53a3da6bda9e5f32212f314ea5f81f911658489d"));
+                assert_eq!(base_oid, missing_base);
+                assert_eq!(offset, 108);
+            }
+            err => panic!("{:?}: unexpected error {:?}", algo, err),
+        }
+
+        let outcome = idx.verify_integrity(
+            Some(git_pack::index::verify::PackContext {
+                data: &pack,
+                options: options(*algo, true),
+            }),
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+        )?;
+        assert_eq!(outcome.missing_delta_bases, vec![missing_base]);
+        assert_eq!(
+            outcome.pack_traverse_statistics.expect("pack is set").num_blobs,
+            2,
+            "{:?}: objects depending on the missing base are skipped, all others are verified",
+            algo
+        );
+    }
+    Ok(())
+}
+
+/// Return a pack with the given blobs and its index, with `slack` unused bytes after the first blob, and a trailer of
+/// `trailer_len` bytes that is expected to be as long as a hash.
+fn pack_of_blobs_with_slack(
+    blobs: &[&[u8]],
+    slack: usize,
+    trailer_len: usize,
+) -> Result<(index::File, pack::data::File), Box<dyn std::error::Error>> {
+    use std::io::Write;
+    let mut pack = b"PACK".to_vec();
+    pack.extend_from_slice(&2u32.to_be_bytes());
+    pack.extend_from_slice(&(blobs.len() as u32).to_be_bytes());
+    let mut entries = Vec::new();
+    for (blob_index, blob) in blobs.iter().enumerate() {
+        let offset = pack.len() as u32;
+        pack::data::entry::Header::Blob.write_to(blob.len() as u64, &mut pack)?;
+        let mut deflate = git_features::zlib::stream::deflate::Write::new(Vec::new());
+        deflate.write_all(blob)?;
+        deflate.flush()?;
+        pack.extend_from_slice(&deflate.into_inner());
+        if blob_index == 0 {
+            pack.extend(std::iter::repeat(0).take(slack));
+        }
+        let mut hasher = git_features::hash::hasher(git_hash::Kind::Sha1);
+        hasher.update(&git_object::encode::loose_header(object::Kind::Blob, blob.len() as u64));
+        hasher.update(blob);
+        entries.push((git_hash::ObjectId::from(hasher.digest()), offset));
+    }
+    pack.extend(std::iter::repeat(0).take(trailer_len));
+
+    entries.sort();
+    let mut idx = b"\xfftOc".to_vec();
+    idx.extend_from_slice(&2u32.to_be_bytes());
+    for first_byte in 0..=255u8 {
+        let count = entries.iter().filter(|(id, _)| id.as_bytes()[0] <= first_byte).count() as u32;
+        idx.extend_from_slice(&count.to_be_bytes());
+    }
+    for (id, _) in &entries {
+        idx.extend_from_slice(id.as_bytes());
+    }
+    for _ in &entries {
+        idx.extend_from_slice(&0u32.to_be_bytes());
+    }
+    for (_, offset) in &entries {
+        idx.extend_from_slice(&offset.to_be_bytes());
+    }
+    idx.extend(std::iter::repeat(0).take(SHA1_SIZE * 2));
+    Ok((
+        index::File::from_bytes(idx, git_hash::Kind::Sha1)?,
+        pack::data::File::from_bytes(pack, git_hash::Kind::Sha1)?,
+    ))
+}
+
+#[test]
+fn traversal_by_offset_reports_slack_and_overlaps_between_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let traverse = |idx: &index::File, pack: &pack::data::File, strict_entry_boundaries: bool| {
+        idx.traverse(
+            pack,
+            &mut progress::Discard,
+            &AtomicBool::new(false),
+            || |_, _, _, _| Ok::<_, std::io::Error>(()),
+            index::traverse::Options {
+                traversal: index::traverse::Algorithm::Lookup,
+                check: index::traverse::SafetyCheck::SkipFileAndObjectChecksumVerification,
+                strict_entry_boundaries,
+                ..Default::default()
+            },
+        )
+    };
+
+    let (idx, pack) = pack_of_blobs_with_slack(&[b"first", b"second"], 3, SHA1_SIZE)?;
+    let stats = traverse(&idx, &pack, true)?.statistics;
+    assert_eq!(stats.num_blobs, 2, "slack doesn't prevent decoding");
+    assert_eq!(stats.entry_overlaps, 0);
+    assert_eq!(stats.entry_slack_bytes, 3);
+    let first = pack.entry(12);
+    let first_size = (first.header_size() + pack.decompress_entry(&first, &mut [0; 5])?) as u64;
+    assert_eq!(
+        stats.first_entry_boundary_mismatches,
+        vec![index::traverse::EntryBoundaryMismatch {
+            pack_offset: 12,
+            entry_size: first_size,
+            available_size: first_size + 3,
+        }]
+    );
+    assert_eq!(stats.first_entry_boundary_mismatches[0].slack(), 3);
+
+    // The last entry extends into the trailer, which is too short.
+    let (idx, pack) = pack_of_blobs_with_slack(&[b"first", b"second"], 0, SHA1_SIZE - 4)?;
+    let stats = traverse(&idx, &pack, false)?.statistics;
+    assert_eq!(
+        stats.num_blobs, 2,
+        "overlapping entries are decoded unless boundaries are strict"
+    );
+    assert_eq!(stats.entry_overlaps, 1);
+    assert_eq!(stats.entry_slack_bytes, 0);
+    let overlap = stats.first_entry_boundary_mismatches[0];
+    assert!(overlap.is_overlap());
+    assert_eq!(overlap.entry_size - overlap.available_size, 4);
+
+    match traverse(&idx, &pack, true) {
+        Err(index::traverse::Error::EntryOverlap {
+            offset,
+            entry_size,
+            available_size,
+        }) => {
+            assert_eq!(offset, overlap.pack_offset);
+            assert_eq!(entry_size, overlap.entry_size);
+            assert_eq!(available_size, overlap.available_size);
+        }
+        res => panic!("expected an overlap error, got {:?}", res.map(|o| o.statistics)),
+    }
+    Ok(())
+}
+
+mod allocations {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+        sync::atomic::AtomicBool,
+    };
+
+    use git_features::progress;
+    use git_pack::{cache, index};
+
+    /// Counts allocations and reallocations per thread, so tests running concurrently don't affect each other.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn count_one() {
+        ALLOCATIONS.try_with(|count| count.set(count.get() + 1)).ok();
+    }
+
+    fn allocations() -> usize {
+        ALLOCATIONS.with(Cell::get)
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            count_one();
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            count_one();
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn decoding_and_checking_objects_reuses_buffers_across_entries() -> crate::Result {
+        let pack_dir = crate::scripted_fixture_repo_read_only("make_pack_gen_repo.sh")?
+            .join(".git")
+            .join("objects")
+            .join("pack");
+        let index_path = std::fs::read_dir(&pack_dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .find(|path| path.extension().map_or(false, |ext| ext == "idx"))
+            .expect("a pack was created by garbage collection");
+        let bundle = git_pack::Bundle::at(&index_path, git_hash::Kind::Sha1)?;
+
+        let mut buf = Vec::new();
+        let mut num_decoded_objects = 0;
+        for entry in bundle.index.iter() {
+            let (object, _location) = bundle
+                .find(entry.oid, &mut buf, &mut cache::Never)?
+                .expect("object is in pack");
+            if object.kind != git_object::Kind::Blob {
+                num_decoded_objects += 1;
+            }
+        }
+
+        // Traversing with a single thread happens on the current thread, which is where allocations are counted.
+        let allocations_during_verification = |verify_mode, tree_checks| -> crate::Result<usize> {
+            let before = allocations();
+            bundle.verify_integrity(
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+                index::verify::integrity::Options {
+                    verify_mode,
+                    traversal: index::traverse::Algorithm::Lookup,
+                    thread_limit: Some(1),
+                    tree_checks,
+                    ..Default::default()
+                },
+            )?;
+            Ok(allocations() - before)
+        };
+        let without_decoding = allocations_during_verification(index::verify::Mode::HashCrc32, Default::default())?;
+        let with_decoding_and_checks = allocations_during_verification(
+            index::verify::Mode::HashCrc32DecodeEncode,
+            git_object::tree::check::Options::strict(),
+        )?;
+
+        let allocations_for_decoding = with_decoding_and_checks.saturating_sub(without_decoding);
+        assert!(
+            allocations_for_decoding * 10 < num_decoded_objects * 7,
+            "decoding, checking and re-encoding {} objects took {} allocations, but should take at least 30% fewer than one per object",
+            num_decoded_objects,
+            allocations_for_decoding
+        );
+        Ok(())
+    }
+}
+
+mod init {
+    use git_pack::index;
+
+    use crate::{fixture_path, pack::INDEX_V2};
+
+    fn open_err(data: &[u8]) -> crate::Result<(index::init::Error, std::path::PathBuf)> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("pack-broken.idx");
+        std::fs::write(&path, data)?;
+        let err = index::File::at(&path, git_hash::Kind::Sha1)
+            .map(|_| ())
+            .expect_err("file is invalid");
+        Ok((err, path))
+    }
+
+    #[test]
+    fn errors_mention_the_path_and_the_problem() -> crate::Result {
+        let (err, path) = open_err(&[0; 100])?;
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Pack index file at '{}' with 100 bytes is too small for even an empty index",
+                path.display()
+            )
+        );
+
+        let (err, path) = open_err(&[b"\xfftOc".as_ref(), &3u32.to_be_bytes()[..], &[0u8; 1100][..]].concat())?;
+        assert_eq!(
+            err.to_string(),
+            format!("Pack index file at '{}' has unsupported version 3", path.display())
+        );
+
+        let valid = std::fs::read(fixture_path(INDEX_V2))?;
+        let num_objects = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?.num_objects();
+        let (err, path) = open_err(&valid[..valid.len() - 100])?;
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Pack index file at '{}' with {} bytes is too small for {} objects, which need at least {} bytes",
+                path.display(),
+                valid.len() - 100,
+                num_objects,
+                valid.len()
+            )
+        );
+
+        let missing = path.with_file_name("missing.idx");
+        let err = index::File::at(&missing, git_hash::Kind::Sha1)
+            .map(|_| ())
+            .expect_err("file doesn't exist");
+        assert_eq!(
+            err.to_string(),
+            format!("Could not open pack index file at '{}'", missing.display())
+        );
+        Ok(())
+    }
+
+    /// Rewrite the SHA-1 index `data` as if it was written for hashes of `hash_len` bytes, padding all hashes with zeroes.
+    fn with_hash_len(data: &[u8], num_objects: usize, version: index::Version, hash_len: usize) -> Vec<u8> {
+        const SHA1_LEN: usize = 20;
+        let pad = |hash: &[u8]| [hash, &vec![0; hash_len - SHA1_LEN]].concat();
+        let trailer = data[data.len() - SHA1_LEN * 2..]
+            .chunks(SHA1_LEN)
+            .flat_map(pad)
+            .collect::<Vec<_>>();
+        let body = match version {
+            index::Version::V1 => {
+                let (fan, entries) = data[..data.len() - SHA1_LEN * 2].split_at(256 * 4);
+                let entries = entries
+                    .chunks(4 + SHA1_LEN)
+                    .flat_map(|entry| [&entry[..4], &pad(&entry[4..])].concat());
+                fan.iter().copied().chain(entries).collect::<Vec<_>>()
+            }
+            index::Version::V2 => {
+                let (header_and_fan, rest) = data[..data.len() - SHA1_LEN * 2].split_at(8 + 256 * 4);
+                let (names, tables) = rest.split_at(num_objects * SHA1_LEN);
+                let names = names.chunks(SHA1_LEN).flat_map(pad);
+                header_and_fan
+                    .iter()
+                    .copied()
+                    .chain(names)
+                    .chain(tables.iter().copied())
+                    .collect()
+            }
+        };
+        [body, trailer].concat()
+    }
+
+    #[test]
+    fn indices_written_for_longer_hashes_are_detected_by_their_size() -> crate::Result {
+        for (fixture, version) in [
+            (crate::pack::INDEX_V1, index::Version::V1),
+            (INDEX_V2, index::Version::V2),
+        ] {
+            let valid = std::fs::read(fixture_path(fixture))?;
+            let num_objects = index::File::at(fixture_path(fixture), git_hash::Kind::Sha1)?.num_objects() as usize;
+            let sha256_layout = with_hash_len(&valid, num_objects, version, 32);
+            assert_eq!(
+                with_hash_len(&valid, num_objects, version, 20),
+                valid,
+                "without padding, the layout doesn't change"
+            );
+
+            let (err, path) = open_err(&sha256_layout)?;
+            assert!(
+                matches!(
+                    err,
+                    index::init::Error::ObjectHashMismatch {
+                        object_hash: git_hash::Kind::Sha1,
+                        implied_hash_len: 32,
+                        size,
+                        ..
+                    } if size == sha256_layout.len()
+                ),
+                "{:?}",
+                err
+            );
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Pack index file at '{}' with {} bytes was opened for SHA1 hashes of 20 bytes, but its size implies hashes of 32 bytes",
+                    path.display(),
+                    sha256_layout.len()
+                )
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_bytes_beyond_the_largest_offset_table_are_an_error() -> crate::Result {
+        let valid = std::fs::read(fixture_path(INDEX_V2))?;
+        let num_objects = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?.num_objects() as usize;
+        let too_large = [&valid[..], &vec![0; num_objects * 8]].concat();
+        let (err, path) = open_err(&too_large)?;
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Pack index file at '{}' with {} bytes is too large for {} objects, which need at most {} bytes",
+                path.display(),
+                too_large.len(),
+                num_objects,
+                valid.len() + (num_objects - 1) * 8
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn in_memory_files_have_synthetic_paths_in_errors() {
+        let err = index::File::from_bytes(vec![0; 100], git_hash::Kind::Sha1)
+            .map(|_| ())
+            .expect_err("too small");
+        assert_eq!(
+            err.to_string(),
+            "Pack index file at '<memory>/pack-0000000000000000000000000000000000000000.idx' with 100 bytes is too small for even an empty index"
+        );
+    }
+}
+
+#[test]
+fn empty_pack_can_be_iterated_traversed_and_verified() -> Result<(), Box<dyn std::error::Error>> {
+    let repo = crate::scripted_fixture_repo_read_only("make_empty_pack.sh")?;
+    let index_path = std::fs::read_dir(repo.join(".git/objects/pack"))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .find(|p| p.extension().map_or(false, |ext| ext == "idx"))
+        .expect("one index");
+    let idx = index::File::at(&index_path, git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(index_path.with_extension("pack"), git_hash::Kind::Sha1)?;
+
+    assert_eq!(idx.num_objects(), 0);
+    assert_eq!(pack.num_objects(), 0);
+    assert_eq!(idx.iter().count(), 0);
+    assert!(idx.sorted_offsets().is_empty());
+    assert_eq!(idx.lookup(hex_to_id("ffffffffffffffffffffffffffffffffffffffff")), None);
+    assert_eq!(
+        pack.verify_checksum(progress::Discard, &AtomicBool::new(false))?,
+        idx.pack_checksum(),
+        "the trailer of an empty pack is validated like any other"
+    );
+
+    for algo in ALGORITHMS {
+        for mode in MODES {
+            let outcome = idx.verify_integrity(
+                Some(git_pack::index::verify::PackContext {
+                    data: &pack,
+                    options: git_pack::index::verify::integrity::Options {
+                        verify_mode: *mode,
+                        traversal: *algo,
+                        make_pack_lookup_cache: || cache::Never,
+                        thread_limit: None,
+                        max_decompressed_object_size: None,
+                        tree_checks: Default::default(),
+                        keep_going: false,
+                        paranoid: false,
+                        strict_entry_boundaries: false,
+                    },
+                }),
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+            )?;
+            assert_eq!(outcome.actual_index_checksum, idx.index_checksum());
+            let stats = outcome.pack_traverse_statistics.expect("pack was traversed");
+            assert_eq!(stats.num_objects(), 0, "{:?} -> {:?}", algo, mode);
+            let Outcome {
+                num_deltas,
+                decompressed_size,
+                compressed_size,
+                object_size,
+                ..
+            } = stats.average;
+            assert_eq!(
+                (num_deltas, decompressed_size, compressed_size, object_size),
+                (0, 0, 0, 0),
+                "averages of nothing are zero instead of nonsensical"
+            );
+            assert_eq!(
+                stats.num_commits + stats.num_trees + stats.num_blobs + stats.num_tags,
+                0
+            );
+        }
+    }
+    Ok(())
+}
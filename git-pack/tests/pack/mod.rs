@@ -30,7 +30,11 @@ pub fn fixup(v: Vec<u8>) -> Vec<u8> {
     v.replace(b"\r\n", "\n")
 }
 
+mod bitmap;
 mod bundle;
+mod bundle_file;
+#[cfg(feature = "pack-cache-lru-dynamic")]
+mod cache;
 mod data;
 mod index;
 mod iter;
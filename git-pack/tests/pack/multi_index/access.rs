@@ -97,3 +97,40 @@ fn general() {
     }
     assert_eq!(count, file.num_objects());
 }
+
+#[test]
+fn init_errors_mention_the_path_and_the_problem() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("multi-pack-index");
+    let open_err = |data: &[u8]| -> crate::Result<String> {
+        std::fs::write(&path, data)?;
+        Ok(git_pack::multi_index::File::at(&path)
+            .map(|_| ())
+            .expect_err("file is invalid")
+            .to_string())
+    };
+
+    assert_eq!(
+        open_err(b"MIDX")?,
+        format!(
+            "Multi-index file at '{}' with 4 bytes is too small for even an empty multi-index",
+            path.display()
+        )
+    );
+    assert_eq!(
+        open_err(&[b"JUNK".as_ref(), &[0u8; 1200][..]].concat())?,
+        format!(
+            "Multi-index file at '{}' doesn't start with the 'MIDX' signature, but with \"JUNK\"",
+            path.display()
+        )
+    );
+    assert_eq!(
+        open_err(&[b"MIDX".as_ref(), &[2u8; 1200][..]].concat())?,
+        format!("Multi-index file at '{}' has unsupported version 2", path.display())
+    );
+    assert_eq!(
+        open_err(&[b"MIDX\x01\x07".as_ref(), &[0u8; 1200][..]].concat())?,
+        format!("Multi-index file at '{}' uses unsupported hash kind 7", path.display())
+    );
+    Ok(())
+}
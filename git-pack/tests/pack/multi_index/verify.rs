@@ -19,7 +19,7 @@ fn checksum() -> crate::Result {
 fn integrity() {
     let (file, _) = multi_index();
     let outcome = file
-        .verify_integrity(progress::Discard, &AtomicBool::new(false), Default::default())
+        .verify_integrity(&mut progress::Discard, &AtomicBool::new(false), Default::default())
         .unwrap();
     assert_eq!(outcome.actual_index_checksum, file.checksum());
     assert_eq!(
@@ -46,7 +46,13 @@ fn integrity() {
             num_commits: 16,
             num_trees: 40,
             num_tags: 1,
-            num_blobs: 811
+            num_blobs: 811,
+            index_oid_mismatches: 0,
+            first_index_oid_mismatches: Vec::new(),
+            delta_result_size_mismatches: 0,
+            entry_overlaps: 0,
+            entry_slack_bytes: 0,
+            first_entry_boundary_mismatches: Vec::new(),
         }]
     );
 }
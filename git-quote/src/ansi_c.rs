@@ -28,6 +28,39 @@ use std::{borrow::Cow, io::Read};
 
 use bstr::{BStr, BString, ByteSlice};
 
+/// Quote the given `input` in ansi-c style like `git` does for paths in its output, the inverse of [`undo()`].
+///
+/// The `input` is returned unaltered if it contains no control characters, double quotes, backslashes or non-ASCII bytes.
+/// Otherwise it is surrounded by double quotes, with typical escapes like `\t` used where possible and octal escapes otherwise,
+/// similar to `git` with `core.quotePath` enabled.
+pub fn quote(input: &BStr) -> Cow<'_, BStr> {
+    fn needs_quoting(b: u8) -> bool {
+        b < 0x20 || b == b'"' || b == b'\\' || b >= 0x7f
+    }
+    if !input.iter().copied().any(needs_quoting) {
+        return input.into();
+    }
+    let mut out = BString::from(Vec::with_capacity(input.len() + 2));
+    out.push(b'"');
+    for &b in input.iter() {
+        match b {
+            b'\n' => out.extend_from_slice(b"\\n"),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            b'\t' => out.extend_from_slice(b"\\t"),
+            7 => out.extend_from_slice(b"\\a"),
+            8 => out.extend_from_slice(b"\\b"),
+            0xb => out.extend_from_slice(b"\\v"),
+            0xc => out.extend_from_slice(b"\\f"),
+            b'"' => out.extend_from_slice(b"\\\""),
+            b'\\' => out.extend_from_slice(b"\\\\"),
+            b if needs_quoting(b) => out.extend_from_slice(format!("\\{:03o}", b).as_bytes()),
+            b => out.push(b),
+        }
+    }
+    out.push(b'"');
+    out.into()
+}
+
 /// Unquote the given ansi-c quoted `input` string, returning it and all of the consumed bytes.
 ///
 /// The `input` is returned unaltered if it doesn't start with a `"` character to indicate
@@ -1,4 +1,46 @@
 mod ansi_c {
+    mod quote {
+        use bstr::ByteSlice;
+        use git_quote::ansi_c;
+
+        macro_rules! test {
+            ($name:ident, $input:literal, $expected:literal) => {
+                #[test]
+                fn $name() {
+                    let input = $input.as_bytes().as_bstr();
+                    let quoted = ansi_c::quote(input);
+                    assert_eq!(quoted.as_ref(), $expected.as_bytes().as_bstr());
+                    assert_eq!(
+                        ansi_c::undo(quoted.as_ref())
+                            .expect("quoted output is valid")
+                            .0
+                            .as_ref(),
+                        input,
+                        "quoting can be undone"
+                    );
+                }
+            };
+        }
+
+        test!(plain_remains_unchanged, "hello there/file.ext", "hello there/file.ext");
+        test!(typical_escapes, b"a\n\r\tb", r#""a\n\r\tb""#);
+        test!(untypical_escapes, b"\x07\x08\x0c\x0b", r#""\a\b\f\v""#);
+        test!(literal_escape_and_double_quote, br#""\"#, r#""\"\\""#);
+        test!(
+            non_ascii_and_other_control_bytes_by_number,
+            "濱野\x01\x7f",
+            r#""\346\277\261\351\207\216\001\177""#
+        );
+
+        #[test]
+        fn unchanged_input_is_borrowed() {
+            assert!(matches!(
+                ansi_c::quote(b"file".as_bstr()),
+                std::borrow::Cow::Borrowed(_)
+            ));
+        }
+    }
+
     mod undo {
         use bstr::ByteSlice;
         use git_quote::ansi_c;
@@ -66,38 +66,77 @@ impl file::Store {
         self.find_one_with_verified_input(partial.try_into()?, packed)
     }
 
+    /// Find all references matching `partial` in the order of precedence used by [`file::Store::try_find()`], which
+    /// returns only the first of them.
+    ///
+    /// More than one reference matches if `partial` is ambiguous, like `v1.2` if both `refs/tags/v1.2` and
+    /// `refs/heads/v1.2` exist. Each reference is returned only once.
+    pub fn try_find_all<'a, Name, E>(&self, partial: Name) -> Result<Vec<Reference>, Error>
+    where
+        Name: TryInto<&'a PartialNameRef, Error = E>,
+        Error: From<E>,
+    {
+        let packed = self.assure_packed_refs_uptodate()?;
+        let mut out = Vec::<Reference>::new();
+        self.find_with_verified_input(partial.try_into()?, packed.as_ref().map(|b| &***b), |r| {
+            if !out.iter().any(|existing| existing.name == r.name) {
+                out.push(r);
+            }
+            true
+        })?;
+        Ok(out)
+    }
+
     pub(crate) fn find_one_with_verified_input(
         &self,
         partial_name: &PartialNameRef,
         packed: Option<&packed::Buffer>,
     ) -> Result<Option<Reference>, Error> {
+        let mut found = None;
+        self.find_with_verified_input(partial_name, packed, |r| {
+            found = Some(r);
+            false
+        })?;
+        Ok(found)
+    }
+
+    /// Call `on_match` with each reference matching `partial_name` in order of precedence until it returns `false`.
+    fn find_with_verified_input(
+        &self,
+        partial_name: &PartialNameRef,
+        packed: Option<&packed::Buffer>,
+        mut on_match: impl FnMut(Reference) -> bool,
+    ) -> Result<(), Error> {
         let mut buf = BString::default();
         if partial_name.looks_like_full_name() {
             if let Some(r) = self.find_inner("", partial_name, None, Transform::None, &mut buf)? {
-                return Ok(Some(r));
+                if !on_match(r) {
+                    return Ok(());
+                }
             }
         }
 
         for inbetween in &["", "tags", "heads", "remotes"] {
-            match self.find_inner(inbetween, partial_name, packed, Transform::EnforceRefsPrefix, &mut buf) {
-                Ok(Some(r)) => return Ok(Some(r)),
-                Ok(None) => {
-                    continue;
+            if let Some(r) = self.find_inner(inbetween, partial_name, packed, Transform::EnforceRefsPrefix, &mut buf)? {
+                if !on_match(r) {
+                    return Ok(());
                 }
-                Err(err) => return Err(err),
             }
         }
-        self.find_inner(
+        if let Some(r) = self.find_inner(
             "remotes",
             partial_name
                 .to_owned()
                 .join("HEAD")
                 .expect("HEAD is valid name")
                 .as_ref(),
-            None,
+            packed,
             Transform::EnforceRefsPrefix,
             &mut buf,
-        )
+        )? {
+            on_match(r);
+        }
+        Ok(())
     }
 
     fn find_inner(
@@ -131,13 +131,19 @@ pub mod decode {
         }
     }
 
+    /// Reflogs were written by many tools over time, so malformed signatures are decoded leniently instead of rejecting the line.
+    fn signature<'a, E>(i: &'a [u8]) -> IResult<&'a [u8], git_actor::SignatureRef<'a>, E> {
+        let (i, signature, _issues) = git_actor::signature::decode_lenient(i);
+        Ok((i, signature))
+    }
+
     fn one<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(bytes: &'a [u8]) -> IResult<&[u8], LineRef<'a>, E> {
         let (i, (old, new, signature, message_sep, message)) = context(
             "<old-hexsha> <new-hexsha> <name> <<email>> <timestamp> <tz>\\t<message>",
             tuple((
                 context("<old-hexsha>", terminated(hex_hash, tag(b" "))),
                 context("<new-hexsha>", terminated(hex_hash, tag(b" "))),
-                context("<name> <<email>> <timestamp>", git_actor::signature::decode),
+                context("<name> <<email>> <timestamp>", signature),
                 opt(tag(b"\t")),
                 context("<optional message>", message),
             )),
@@ -228,7 +234,8 @@ pub mod decode {
                                 seconds_since_unix_epoch: 1234567890,
                                 offset_in_seconds: 0,
                                 sign: Sign::Minus
-                            }
+                            },
+                            raw_time: None,
                         },
                         message: b"".as_bstr(),
                     }
@@ -255,6 +262,7 @@ pub mod decode {
                             offset_in_seconds: 28800,
                             sign: Sign::Plus,
                         },
+                        raw_time: None,
                     },
                     message: b"pull --ff-only: Fast-forward".as_bstr(),
                 };
@@ -267,6 +275,21 @@ pub mod decode {
             }
         }
 
+        #[test]
+        fn entry_with_malformed_signature_is_decoded_leniently() {
+            let line = b"0000000000000000000000000000000000000000 0000000000000000000000000000000000000000 name <foo@example.com 1234567890\thello";
+            let (remainder, parsed) = one::<nom::error::Error<_>>(line).expect("parse single line");
+            assert!(remainder.is_empty());
+            assert_eq!(parsed.signature.name, b"name".as_bstr());
+            assert_eq!(
+                parsed.signature.email,
+                b"foo@example.com 1234567890".as_bstr(),
+                "the email extends up to the message"
+            );
+            assert_eq!(parsed.signature.time, Time::default(), "time is defaulted");
+            assert_eq!(parsed.message, b"hello".as_bstr());
+        }
+
         #[test]
         fn two_lines_in_a_row_with_and_without_newline() {
             let lines = b"0000000000000000000000000000000000000000 0000000000000000000000000000000000000000 one <foo@example.com> 1234567890 -0000\t\n0000000000000000000000000000000000000000 0000000000000000000000000000000000000000 two <foo@example.com> 1234567890 -0000\thello";
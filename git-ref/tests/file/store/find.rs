@@ -90,6 +90,56 @@ mod existing {
     }
 }
 
+mod all {
+    use crate::file::{store, store_at};
+
+    #[test]
+    fn ambiguous_names_yield_all_matches_in_order_of_precedence() -> crate::Result {
+        let store = store()?;
+        for (partial_name, expected) in [
+            ("dt1", &["refs/tags/dt1", "refs/heads/dt1"][..]),
+            ("d1", &["refs/d1", "refs/heads/d1"]),
+            ("origin", &["refs/remotes/origin/HEAD"]),
+            ("main", &["refs/heads/main"]),
+            ("refs/heads/main", &["refs/heads/main"]),
+            ("foobar", &[]),
+        ] {
+            let names: Vec<_> = store
+                .try_find_all(partial_name)?
+                .into_iter()
+                .map(|r| r.name.as_bstr().to_owned())
+                .collect();
+            assert_eq!(names, expected, "{}", partial_name);
+        }
+        assert_eq!(
+            store.try_find("dt1")?.expect("exists").name.as_bstr(),
+            "refs/tags/dt1",
+            "the first match is the one returned by a normal lookup"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn packed_refs_are_considered_as_well() -> crate::Result {
+        let store = store_at("make_packed_refs_for_lookup_rules.sh")?;
+        let names: Vec<_> = store
+            .try_find_all("head-or-tag")?
+            .into_iter()
+            .map(|r| r.name.as_bstr().to_owned())
+            .collect();
+        assert_eq!(names, ["refs/tags/head-or-tag", "refs/heads/head-or-tag"]);
+
+        let r = store.try_find_all("origin")?;
+        assert_eq!(r.len(), 1);
+        assert_eq!(
+            r[0].name.as_bstr(),
+            "refs/remotes/origin/HEAD",
+            "the remote HEAD may be packed"
+        );
+        Ok(())
+    }
+}
+
 mod loose {
     use crate::file::store;
 
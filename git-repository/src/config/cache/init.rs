@@ -147,6 +147,7 @@ impl Cache {
         let ignore_case = config_bool(&config, "core.ignoreCase", false, lenient_config)?;
         let use_multi_pack_index = config_bool(&config, "core.multiPackIndex", true, lenient_config)?;
         let object_kind_hint = util::disambiguate_hint(&config);
+        let ambiguous_refs_hint = util::ambiguous_refs_hint(&config, lenient_config)?;
         let (pack_cache_bytes, object_cache_bytes) =
             util::parse_object_caches(&config, lenient_config, filter_config_section)?;
         // NOTE: When adding a new initial cache, consider adjusting `reread_values_and_clear_caches()` as well.
@@ -155,6 +156,7 @@ impl Cache {
             use_multi_pack_index,
             object_hash,
             object_kind_hint,
+            ambiguous_refs_hint,
             pack_cache_bytes,
             object_cache_bytes,
             reflog,
@@ -199,11 +201,13 @@ impl Cache {
         use util::config_bool;
         let ignore_case = config_bool(config, "core.ignoreCase", false, self.lenient_config)?;
         let object_kind_hint = util::disambiguate_hint(config);
+        let ambiguous_refs_hint = util::ambiguous_refs_hint(config, self.lenient_config)?;
         let reflog = util::query_refupdates(config, self.lenient_config)?;
 
         self.hex_len = hex_len;
         self.ignore_case = ignore_case;
         self.object_kind_hint = object_kind_hint;
+        self.ambiguous_refs_hint = ambiguous_refs_hint;
         self.reflog = reflog;
 
         self.user_agent = Default::default();
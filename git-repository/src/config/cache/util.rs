@@ -1,7 +1,10 @@
 use std::convert::TryFrom;
 
 use super::Error;
-use crate::{bstr::ByteSlice, revision::spec::parse::ObjectKindHint};
+use crate::{
+    bstr::ByteSlice,
+    revision::spec::parse::{AmbiguousRefsHint, ObjectKindHint},
+};
 
 pub(crate) fn interpolate_context<'a>(
     git_install_dir: Option<&'a std::path::Path>,
@@ -181,3 +184,14 @@ pub(crate) fn disambiguate_hint(config: &git_config::File<'static>) -> Option<Ob
         })
     })
 }
+
+pub(crate) fn ambiguous_refs_hint(
+    config: &git_config::File<'static>,
+    lenient: bool,
+) -> Result<AmbiguousRefsHint, Error> {
+    Ok(if config_bool(config, "core.warnAmbiguousRefs", true, lenient)? {
+        AmbiguousRefsHint::Warn
+    } else {
+        AmbiguousRefsHint::Ignore
+    })
+}
@@ -205,6 +205,8 @@ pub(crate) struct Cache {
     filter_config_section: fn(&git_config::file::Metadata) -> bool,
     /// The object kind to pick if a prefix is ambiguous.
     pub object_kind_hint: Option<spec::parse::ObjectKindHint>,
+    /// What to do if a reference name matches more than one reference.
+    pub ambiguous_refs_hint: spec::parse::AmbiguousRefsHint,
     /// If true, we are on a case-insensitive file system.
     pub ignore_case: bool,
     /// If true, we should default what's possible if something is misconfigured, on case by case basis, to be more resilient.
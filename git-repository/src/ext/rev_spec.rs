@@ -14,6 +14,8 @@ impl RevSpecExt for git_revision::Spec {
             inner: self,
             first_ref: None,
             second_ref: None,
+            verified: true,
+            ambiguous_refs: Vec::new(),
             repo,
         }
     }
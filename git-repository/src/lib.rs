@@ -133,6 +133,7 @@ use git_features::threading::OwnShared;
 pub use git_features::{parallel, progress, progress::Progress, threading};
 pub use git_glob as glob;
 pub use git_hash as hash;
+pub use git_hashtable as hashtable;
 #[doc(inline)]
 pub use git_index as index;
 pub use git_lock as lock;
@@ -297,6 +298,12 @@ pub mod worktree;
 
 pub mod revision;
 
+///
+pub mod shallow;
+
+///
+pub mod objects;
+
 ///
 pub mod remote;
 
@@ -20,6 +20,48 @@ mod error {
 
 pub use error::Error;
 
+///
+pub mod parent {
+    use git_hash::ObjectId;
+
+    use crate::object;
+
+    /// The error returned by [`Commit::nth_parent()`][crate::Commit::nth_parent()] and [`Commit::ancestor()`][crate::Commit::ancestor()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Commit {id} has {available} parents and the parent at index {desired} is out of range")]
+        ParentOutOfRange {
+            id: ObjectId,
+            desired: usize,
+            available: usize,
+        },
+        #[error("Commit {id} has {available} ancestors along the first parent and ancestor number {desired} is out of range")]
+        AncestorOutOfRange {
+            id: ObjectId,
+            desired: usize,
+            available: usize,
+        },
+        #[error("Could not find parent {parent_id} referenced by commit {id}")]
+        FindParent {
+            id: ObjectId,
+            parent_id: ObjectId,
+            source: object::find::existing::Error,
+        },
+        #[error("Parent {parent_id} referenced by commit {id} was a {actual}, but should have been a commit")]
+        ParentKind {
+            id: ObjectId,
+            parent_id: ObjectId,
+            actual: git_object::Kind,
+        },
+        #[error("Commit {id} could not be decoded to obtain its parents")]
+        Decode {
+            id: ObjectId,
+            source: git_object::decode::Error,
+        },
+    }
+}
+
 impl<'repo> Commit<'repo> {
     /// Create an owned instance of this object, copying our data in the process.
     pub fn detached(&self) -> ObjectDetached {
@@ -99,8 +141,29 @@ impl<'repo> Commit<'repo> {
             .map(|s| s.trim())
     }
 
+    /// Return the commits author with name and email mapped according to `mailmap`, which is typically obtained with
+    /// [`Repository::open_mailmap()`][crate::Repository::open_mailmap()].
+    ///
+    /// Fields that are not mapped are borrowed from this commit.
+    pub fn author_resolved(
+        &self,
+        mailmap: &crate::mailmap::Snapshot,
+    ) -> Result<crate::mailmap::snapshot::Signature<'_>, git_object::decode::Error> {
+        self.author().map(|author| mailmap.resolve_cow(author))
+    }
+
+    /// Return the commits committer with name and email mapped according to `mailmap`, which is typically obtained with
+    /// [`Repository::open_mailmap()`][crate::Repository::open_mailmap()].
+    ///
+    /// Fields that are not mapped are borrowed from this commit.
+    pub fn committer_resolved(
+        &self,
+        mailmap: &crate::mailmap::Snapshot,
+    ) -> Result<crate::mailmap::snapshot::Signature<'_>, git_object::decode::Error> {
+        self.committer().map(|committer| mailmap.resolve_cow(committer))
+    }
+
     /// Decode this commits parent ids on the fly without allocating.
-    // TODO: tests
     pub fn parent_ids(&self) -> impl Iterator<Item = crate::Id<'repo>> + '_ {
         use crate::ext::ObjectIdExt;
         let repo = self.repo;
@@ -109,6 +172,102 @@ impl<'repo> Commit<'repo> {
             .map(move |id| id.attach(repo))
     }
 
+    /// Find and return the parent at the zero-based `index`, as listed in this commit.
+    ///
+    /// Index `0` is the first parent, which is typically the commit that was checked out when a merge was made.
+    /// Out-of-range indices, missing parent objects and parents that aren't commits are reported as errors.
+    pub fn nth_parent(&self, index: usize) -> Result<Commit<'repo>, parent::Error> {
+        use git_object::commit::ref_iter::Token;
+        let mut available = 0;
+        for token in git_object::CommitRefIter::from_bytes(&self.data) {
+            match token.map_err(|err| parent::Error::Decode {
+                id: self.id,
+                source: err,
+            })? {
+                Token::Tree { .. } => continue,
+                Token::Parent { id: parent_id } => {
+                    if available == index {
+                        return self
+                            .repo
+                            .find_object(parent_id)
+                            .map_err(|err| parent::Error::FindParent {
+                                id: self.id,
+                                parent_id,
+                                source: err,
+                            })?
+                            .try_into_commit()
+                            .map_err(|err| parent::Error::ParentKind {
+                                id: self.id,
+                                parent_id,
+                                actual: err.actual,
+                            });
+                    }
+                    available += 1;
+                }
+                _ => break,
+            }
+        }
+        Err(parent::Error::ParentOutOfRange {
+            id: self.id,
+            desired: index,
+            available,
+        })
+    }
+
+    /// Follow the first parent `n` times and return the commit we arrive at, with `0` returning a copy of this commit.
+    ///
+    /// This is the equivalent of `<commit>~<n>` in `git rev-parse`.
+    pub fn ancestor(&self, n: usize) -> Result<Commit<'repo>, parent::Error> {
+        let mut current: Option<Commit<'repo>> = None;
+        for available in 0..n {
+            match current.as_ref().unwrap_or(self).nth_parent(0) {
+                Ok(parent) => current = Some(parent),
+                Err(parent::Error::ParentOutOfRange { .. }) => {
+                    return Err(parent::Error::AncestorOutOfRange {
+                        id: self.id,
+                        desired: n,
+                        available,
+                    })
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(current.unwrap_or_else(|| {
+            let mut data = self.repo.free_buf();
+            data.extend_from_slice(&self.data);
+            Commit {
+                id: self.id,
+                data,
+                repo: self.repo,
+            }
+        }))
+    }
+
+    /// Interpret this commit as stash entry, like `stash@{0}`, and return the commit that was checked out when the changes
+    /// were stashed, which is its first parent.
+    ///
+    /// Stash entries store the state of the worktree in their own tree, while their parents hold the base, the state of the
+    /// index and, if untracked files were stashed, the untracked files.
+    pub fn stash_base(&self) -> Result<Commit<'repo>, parent::Error> {
+        self.nth_parent(0)
+    }
+
+    /// Interpret this commit as stash entry and return the commit whose tree is the state of the index when the changes were
+    /// stashed, which is its second parent.
+    pub fn stash_index(&self) -> Result<Commit<'repo>, parent::Error> {
+        self.nth_parent(1)
+    }
+
+    /// Interpret this commit as stash entry and return the commit whose tree holds the stashed untracked files, which is its
+    /// third parent, or `None` if untracked files weren't stashed.
+    pub fn stash_untracked(&self) -> Result<Option<Commit<'repo>>, parent::Error> {
+        match self.nth_parent(2) {
+            Ok(commit) => Ok(Some(commit)),
+            Err(parent::Error::ParentOutOfRange { .. }) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Parse the commit and return the the tree object it points to.
     pub fn tree(&self) -> Result<Tree<'repo>, Error> {
         match self.tree_id()?.object()?.try_into_tree() {
@@ -17,6 +17,8 @@ pub mod conversion {
 
 ///
 pub mod find {
+    pub use git_odb::find::{Classify, ErrorKind};
+
     /// Indicate that an error occoured when trying to find an object.
     pub type Error = git_odb::store::find::Error;
 
@@ -32,6 +32,15 @@ pub enum Event<'old, 'new> {
         /// The object id after the modification.
         id: Id<'new>,
     },
+    /// A gitlink entry, i.e. a submodule, now points to a different commit.
+    ///
+    /// Note that the commits typically live in the submodule's repository, not in this one.
+    SubmoduleChanged {
+        /// The commit the submodule pointed to before the change.
+        old_commit: git_hash::ObjectId,
+        /// The commit the submodule points to after the change.
+        new_commit: git_hash::ObjectId,
+    },
 }
 
 impl<'old, 'new> Event<'old, 'new> {
@@ -143,6 +143,7 @@ where
                 previous_id: previous_oid.attach(self.repo),
                 id: oid.attach(self.other_repo),
             },
+            SubmoduleChanged { old_commit, new_commit } => change::Event::SubmoduleChanged { old_commit, new_commit },
         };
         match (self.visit)(Change {
             event,
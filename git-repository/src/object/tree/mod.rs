@@ -94,6 +94,21 @@ pub mod diff;
 ///
 pub mod traverse;
 
+///
+pub mod find_paths {
+    /// The error returned by [`Repository::find_paths_of()`][crate::Repository::find_paths_of()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindExistingObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+    }
+}
+
 ///
 mod iter;
 pub use iter::EntryRef;
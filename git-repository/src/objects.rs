@@ -0,0 +1,160 @@
+///
+pub mod verify {
+    use git_odb::pack;
+
+    /// The error returned by [`Repository::verify_objects()`][crate::Repository::verify_objects()].
+    pub type Error = git_odb::store::verify::integrity::Error;
+
+    /// The combined integrity information of all loose object databases and packs, as returned by
+    /// [`Repository::verify_objects()`][crate::Repository::verify_objects()].
+    pub type Outcome = git_odb::store::verify::integrity::Outcome;
+
+    /// The default amount of bytes each thread may use to cache decoded delta bases, see
+    /// [`Options::pack_cache_bytes_per_thread`].
+    pub const DEFAULT_PACK_CACHE_BYTES_PER_THREAD: usize = 16 * 1024 * 1024;
+
+    /// Options for use in [`Repository::verify_objects()`][crate::Repository::verify_objects()].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Options {
+        /// How thoroughly to verify packed objects, which by default decodes and re-encodes all objects that aren't blobs.
+        pub mode: pack::index::verify::Mode,
+        /// The way to traverse packs, which by default is [`Lookup`][pack::index::traverse::Algorithm::Lookup] as it starts
+        /// right away and needs no additional memory.
+        pub algorithm: pack::index::traverse::Algorithm,
+        /// The amount of threads to use if `Some(N)`, with `None` or `Some(0)` using all logical cores.
+        pub thread_limit: Option<usize>,
+        /// The amount of bytes each thread may use to cache decoded delta bases, which is why the memory used by all caches
+        /// grows with the amount of threads. `0` disables the cache.
+        ///
+        /// Defaults to [`DEFAULT_PACK_CACHE_BYTES_PER_THREAD`], and is only used with the `max-performance-safe` feature.
+        pub pack_cache_bytes_per_thread: usize,
+        /// If `Some`, fail if any object or delta would decompress to more than the given amount of bytes.
+        ///
+        /// Defaults to [`DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE`][pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE].
+        pub max_decompressed_object_size: Option<u64>,
+        /// The problems with tree entries to look for and report in [`Outcome::tree_issues`], which by default are none.
+        pub tree_checks: git_object::tree::check::Options,
+        /// If `true`, skip ref-delta entries whose base isn't in their pack and report the bases in
+        /// [`Outcome::missing_delta_bases`] instead of failing.
+        pub keep_going: bool,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                mode: Default::default(),
+                algorithm: pack::index::traverse::Algorithm::Lookup,
+                thread_limit: None,
+                pack_cache_bytes_per_thread: DEFAULT_PACK_CACHE_BYTES_PER_THREAD,
+                max_decompressed_object_size: Some(pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+                tree_checks: Default::default(),
+                keep_going: false,
+            }
+        }
+    }
+
+    impl Options {
+        /// Convert these options into the ones used by the object database, which need a function to create the cache of
+        /// each thread.
+        pub fn into_integrity_options(
+            self,
+        ) -> pack::index::verify::integrity::Options<impl Fn() -> Box<git_odb::cache::PackCache> + Send + Clone>
+        {
+            let Options {
+                mode,
+                algorithm,
+                thread_limit,
+                pack_cache_bytes_per_thread,
+                max_decompressed_object_size,
+                tree_checks,
+                keep_going,
+            } = self;
+            pack::index::verify::integrity::Options {
+                verify_mode: mode,
+                traversal: algorithm,
+                thread_limit,
+                make_pack_lookup_cache: move || make_pack_cache(pack_cache_bytes_per_thread),
+                max_decompressed_object_size,
+                tree_checks,
+                keep_going,
+                paranoid: false,
+                strict_entry_boundaries: false,
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "max-performance-safe"), allow(unused_variables))]
+    fn make_pack_cache(bytes: usize) -> Box<git_odb::cache::PackCache> {
+        #[cfg(feature = "max-performance-safe")]
+        if bytes != 0 {
+            return Box::new(pack::cache::lru::MemoryCappedHashmap::new(bytes));
+        }
+        Box::new(pack::cache::Never)
+    }
+}
+
+///
+pub mod iter {
+    use crate::Object;
+
+    /// The error returned by [`Repository::objects_iter()`][crate::Repository::objects_iter()] and its iterator.
+    pub type Error = git_odb::store::iter_with_data::Error;
+
+    /// Options for use in [`Repository::objects_iter()`][crate::Repository::objects_iter()].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Options {
+        /// If `Some`, only return objects of the given kinds.
+        pub kinds: Option<Vec<git_object::Kind>>,
+        /// The maximum amount of bytes of decoded objects to keep in memory to resolve deltas against them, which defaults
+        /// to 64MB.
+        pub pack_cache_bytes: usize,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                kinds: None,
+                pack_cache_bytes: git_odb::store::iter_with_data::Options::default().pack_cache_memory_cap_in_bytes,
+            }
+        }
+    }
+
+    impl From<Options> for git_odb::store::iter_with_data::Options {
+        fn from(
+            Options {
+                kinds,
+                pack_cache_bytes,
+            }: Options,
+        ) -> Self {
+            git_odb::store::iter_with_data::Options {
+                kinds,
+                pack_cache_memory_cap_in_bytes: pack_cache_bytes,
+                ..Default::default()
+            }
+        }
+    }
+
+    /// An iterator over all objects of a repository along with their data, returned by
+    /// [`Repository::objects_iter()`][crate::Repository::objects_iter()].
+    ///
+    /// It stops with an error once the [interrupt flag][crate::interrupt::IS_INTERRUPTED] is set.
+    pub struct Iter<'repo> {
+        pub(crate) inner: git_odb::store::iter_with_data::AllObjectsWithData<'static>,
+        pub(crate) repo: &'repo crate::Repository,
+    }
+
+    impl<'repo> Iterator for Iter<'repo> {
+        type Item = Result<Object<'repo>, Error>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.inner.next().map(|res| {
+                res.map(|(id, kind, data)| {
+                    let mut buf = self.repo.free_buf();
+                    buf.clear();
+                    buf.extend_from_slice(&data);
+                    Object::from_data(id, kind, buf, self.repo)
+                })
+            })
+        }
+    }
+}
@@ -9,6 +9,13 @@ use crate::{bstr::BString, config, permission, Permissions};
 /// The environment variables `GIT_REPLACE_REF_BASE` and `GIT_NO_REPLACE_OBJECTS` are mapped to `gitoxide.objects.replaceRefBase`
 /// and `gitoxide.objects.noReplace` respectively and then interpreted exactly as their environment variable counterparts.
 ///
+/// ### Object directories
+///
+/// Like in git, `GIT_OBJECT_DIRECTORY` replaces the `objects` directory of the repository, and `GIT_ALTERNATE_OBJECT_DIRECTORIES`
+/// provides additional object directories separated by `:` (or `;` on Windows) which are used as alternates, see
+/// [`Store::extra_alternates()`][git_odb::Store::extra_alternates()]. Both are subject to the [`objects`][crate::permissions::Environment::objects]
+/// permission, with [`Forbid`][git_sec::Permission::Forbid] failing the operation if one of them is set.
+///
 /// Use [Permissions] to control which environment variables can be read, and config-overrides to control these values programmatically.
 #[derive(Clone)]
 pub struct Options {
@@ -21,6 +28,7 @@ pub struct Options {
     pub(crate) lossy_config: Option<bool>,
     pub(crate) lenient_config: bool,
     pub(crate) bail_if_untrusted: bool,
+    pub(crate) untrusted_object_directories: git_sec::Permission,
     pub(crate) api_config_overrides: Vec<BString>,
     pub(crate) cli_config_overrides: Vec<BString>,
     /// Internal to pass an already obtained CWD on to where it may also be used. This avoids the CWD being queried more than once per repo.
@@ -13,6 +13,7 @@ impl Default for Options {
             lossy_config: None,
             lenient_config: true,
             bail_if_untrusted: false,
+            untrusted_object_directories: git_sec::Permission::Deny,
             api_config_overrides: Vec::new(),
             cli_config_overrides: Vec::new(),
             current_dir: None,
@@ -100,6 +101,17 @@ impl Options {
         self
     }
 
+    /// Determine what to do with object directories, including alternates, which aren't owned by the current user.
+    ///
+    /// By default, with [`Deny`][git_sec::Permission::Deny], they are skipped and recorded as
+    /// [warnings][git_odb::Store::warnings()], while [`Forbid`][git_sec::Permission::Forbid] fails when they are
+    /// encountered and [`Allow`][git_sec::Permission::Allow] uses them like any other.
+    /// The `objects` directory is classified using the trust level of the `.git` directory, see [`with()`][Self::with()].
+    pub fn untrusted_object_directories(mut self, permission: git_sec::Permission) -> Self {
+        self.untrusted_object_directories = permission;
+        self
+    }
+
     /// Set the filter which determines if a configuration section can be used to read values from,
     /// hence it returns true if it is eligible.
     ///
@@ -145,6 +157,7 @@ impl git_sec::trust::DefaultForLevel for Options {
                 filter_config_section: Some(config::section::is_trusted),
                 lossy_config: None,
                 bail_if_untrusted: false,
+                untrusted_object_directories: git_sec::Permission::Deny,
                 lenient_config: true,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
@@ -156,6 +169,7 @@ impl git_sec::trust::DefaultForLevel for Options {
                 git_dir_trust: git_sec::Trust::Reduced.into(),
                 filter_config_section: Some(config::section::is_trusted),
                 bail_if_untrusted: false,
+                untrusted_object_directories: git_sec::Permission::Deny,
                 lenient_config: true,
                 lossy_config: None,
                 api_config_overrides: Vec::new(),
@@ -116,6 +116,7 @@ impl ThreadSafeRepository {
             lossy_config,
             lenient_config,
             bail_if_untrusted,
+            untrusted_object_directories,
             permissions: Permissions { ref env, config },
             ref api_config_overrides,
             ref cli_config_overrides,
@@ -153,6 +154,20 @@ impl ThreadSafeRepository {
         let home = std::env::var_os("HOME")
             .map(PathBuf::from)
             .and_then(|home| env.home.check_opt(home));
+        let objects_dir = std::env::var_os("GIT_OBJECT_DIRECTORY")
+            .map(|dir| env.objects.check(current_dir.join(dir)))
+            .transpose()?
+            .flatten();
+        let extra_alternates: Vec<_> = std::env::var_os("GIT_ALTERNATE_OBJECT_DIRECTORIES")
+            .map(|dirs| env.objects.check(PathBuf::from(dirs)))
+            .transpose()?
+            .flatten()
+            .map(|dirs| {
+                std::env::split_paths(&dirs)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
 
         let mut filter_config_section = filter_config_section.unwrap_or(config::section::is_trusted);
         let config = config::Cache::from_stage_one(
@@ -219,15 +234,25 @@ impl ThreadSafeRepository {
             })
             .unwrap_or_default();
 
+        let trust_policy = git_odb::store::init::TrustPolicy {
+            // The objects directory inherits the trust of the `.git` directory unless it was overridden.
+            objects_directory: objects_dir.is_none().then(|| git_dir_trust),
+            reduced: untrusted_object_directories,
+            ..Default::default()
+        };
         Ok(ThreadSafeRepository {
             objects: OwnShared::new(git_odb::Store::at_opts(
-                common_dir_ref.join("objects"),
+                objects_dir.unwrap_or_else(|| common_dir_ref.join("objects")),
                 replacements,
                 git_odb::store::init::Options {
                     slots: object_store_slots,
                     object_hash: config.object_hash,
                     use_multi_pack_index: config.use_multi_pack_index,
                     current_dir: current_dir.to_owned().into(),
+                    strict: false,
+                    extra_alternates,
+                    trust_policy,
+                    pack_access: Default::default(),
                 },
             )?),
             common_dir,
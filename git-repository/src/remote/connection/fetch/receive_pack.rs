@@ -138,6 +138,8 @@ where
             index_version: config::pack_index_version(repo)?,
             iteration_mode: git_pack::data::input::Mode::Verify,
             object_hash: con.remote.repo.object_hash(),
+            max_decompressed_object_size: Some(git_pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+            durability: Default::default(),
         };
 
         let mut write_pack_bundle = if matches!(self.dry_run, fetch::DryRun::No) {
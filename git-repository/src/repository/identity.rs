@@ -24,6 +24,7 @@ impl crate::Repository {
                     .or(p.author.time)
                     .unwrap_or_else(git_date::Time::now_local_or_utc)
             },
+            raw_time: None,
         }
     }
 
@@ -51,6 +52,7 @@ impl crate::Repository {
                 .or(p.user.email.as_ref())
                 .map(|v| v.as_ref())?,
             time: p.committer.time.unwrap_or_else(git_date::Time::now_local_or_utc),
+            raw_time: None,
         }
         .into()
     }
@@ -79,6 +81,7 @@ impl crate::Repository {
             name: p.author.name.as_ref().or(p.user.name.as_ref()).map(|v| v.as_ref())?,
             email: p.author.email.as_ref().or(p.user.email.as_ref()).map(|v| v.as_ref())?,
             time: p.author.time.unwrap_or_else(git_date::Time::now_local_or_utc),
+            raw_time: None,
         }
         .into()
     }
@@ -26,10 +26,12 @@ mod impls;
 mod init;
 mod location;
 mod object;
+mod objects;
 pub(crate) mod permissions;
 mod reference;
 mod remote;
 mod revision;
+mod shallow;
 mod snapshots;
 mod state;
 mod thread_safe;
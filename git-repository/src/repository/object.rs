@@ -6,6 +6,7 @@ use git_ref::{
     transaction::{LogChange, PreviousValue, RefLog},
     FullName,
 };
+use smallvec::SmallVec;
 
 use crate::{commit, ext::ObjectIdExt, object, tag, Id, Object, Reference, Tree};
 
@@ -148,13 +149,19 @@ impl crate::Repository {
         // TODO: possibly use CommitRef to save a few allocations (but will have to allocate for object ids anyway.
         //       This can be made vastly more efficient though if we wanted to, so we lie in the API
         let reference = reference.try_into()?;
+        let mut parent_ids = SmallVec::<[ObjectId; 1]>::new();
+        for id in parents.into_iter().map(Into::into) {
+            if !parent_ids.contains(&id) {
+                parent_ids.push(id);
+            }
+        }
         let commit = git_object::Commit {
             message: message.as_ref().into(),
             tree: tree.into(),
             author: author.into().to_owned(),
             committer: committer.into().to_owned(),
             encoding: None,
-            parents: parents.into_iter().map(|id| id.into()).collect(),
+            parents: parent_ids,
             extra_headers: Default::default(),
         };
 
@@ -193,8 +200,9 @@ impl crate::Repository {
     /// that `HEAD` points to if it is not detached. For this reason, detached head states cannot be created unless the `HEAD` is detached
     /// already. The reflog will be written as canonical git would do, like `<operation> (<detail>): <summary>`.
     ///
-    /// The first parent id in `parents` is expected to be the current target of `reference` and the operation will fail if it is not.
-    /// If there is no parent, the `reference` is expected to not exist yet.
+    /// The first parent id in `parents` is expected to be the current target of `reference` and the operation will fail if it is not,
+    /// which prevents losing commits made concurrently. If there is no parent, the `reference` is expected to not exist yet.
+    /// Duplicate parents are ignored, just like `git commit-tree` does.
     ///
     /// The method fails immediately if a `reference` lock can't be acquired.
     pub fn commit<Name, E>(
@@ -222,4 +230,25 @@ impl crate::Repository {
             .expect("always present")
             .into_tree()
     }
+
+    /// Find all paths at which the objects with `blob_ids` are located within `root_tree` using a single traversal, and return
+    /// each id in the order given along with its paths in traversal order.
+    ///
+    /// `root_tree` may also be a commit or tag, which is peeled to its tree. Ids that aren't present in the tree have no paths.
+    pub fn find_paths_of(
+        &self,
+        blob_ids: &[ObjectId],
+        root_tree: impl Into<ObjectId>,
+    ) -> Result<Vec<(ObjectId, Vec<git_object::bstr::BString>)>, object::tree::find_paths::Error> {
+        let tree = self.find_object(root_tree)?.peel_to_tree()?;
+        let mut finder = git_traverse::tree::PathFinder::new(blob_ids.iter().copied());
+        tree.traverse().breadthfirst(&mut finder)?;
+        Ok(blob_ids
+            .iter()
+            .map(|id| {
+                let paths = finder.paths_of(id).expect("every id was queried").to_vec();
+                (*id, paths)
+            })
+            .collect())
+    }
 }
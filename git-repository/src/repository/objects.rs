@@ -0,0 +1,70 @@
+use std::sync::atomic::AtomicBool;
+
+use git_features::progress::Progress;
+
+use crate::objects::{iter, verify};
+
+impl crate::Repository {
+    /// Return metrics about the object database, like the amount of loaded indices and packs and the amount of objects they
+    /// contain, along with how often it was refreshed.
+    pub fn objects_metrics(&self) -> git_odb::store::Metrics {
+        self.objects.store_ref().metrics()
+    }
+
+    /// Check the integrity of all loose and packed objects, including the ones in alternates, as configured by `options`,
+    /// and return the combined outcome.
+    ///
+    /// `progress` is only borrowed and remains usable even if an error is returned. Set `should_interrupt` to stop early,
+    /// for instance by passing [`IS_INTERRUPTED`][crate::interrupt::IS_INTERRUPTED].
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let repo = git_repository::open(".")?;
+    /// let outcome = repo.verify_objects(
+    ///     Default::default(),
+    ///     &mut git_repository::progress::Discard,
+    ///     &git_repository::interrupt::IS_INTERRUPTED,
+    /// )?;
+    /// assert!(outcome.tree_issues.is_empty());
+    /// # Ok(()) }
+    /// ```
+    pub fn verify_objects(
+        &self,
+        options: verify::Options,
+        progress: &mut impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<verify::Outcome, verify::Error> {
+        self.objects
+            .store_ref()
+            .verify_integrity(progress, should_interrupt, options.into_integrity_options())
+    }
+
+    /// Return an iterator over all objects of the object database along with their data as configured by `options`,
+    /// starting with the objects of each pack in the order they are stored in, followed by all loose objects.
+    ///
+    /// This is much faster than looking up each id returned by [`objects.iter()`][git_odb::store::Handle::iter()], and stops
+    /// with an error once the [interrupt flag][crate::interrupt::IS_INTERRUPTED] is set.
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use git_repository::objs::Kind;
+    /// let repo = git_repository::open(".")?;
+    /// let options = git_repository::objects::iter::Options {
+    ///     kinds: Some(vec![Kind::Commit]),
+    ///     ..Default::default()
+    /// };
+    /// for commit in repo.objects_iter(options)? {
+    ///     println!("{}", commit?.id);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn objects_iter(&self, options: iter::Options) -> Result<iter::Iter<'_>, git_odb::store::load_index::Error> {
+        Ok(iter::Iter {
+            inner: self
+                .objects
+                .store_ref()
+                .iter_with_data(&crate::interrupt::IS_INTERRUPTED, options.into())?,
+            repo: self,
+        })
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{bstr::BStr, revision, Id};
+use crate::{bstr::BStr, ext::RevSpecExt, revision, Id};
 
 /// Methods for resolving revisions by spec or working with the commit graph.
 impl crate::Repository {
@@ -14,6 +14,28 @@ impl crate::Repository {
             self,
             revision::spec::parse::Options {
                 object_kind_hint: self.config.object_kind_hint,
+                ambiguous_refs_hint: self.config.ambiguous_refs_hint,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Like [`rev_parse()`][Self::rev_parse()], but full hex ids of objects that don't exist in the object database resolve
+    /// as they are instead of failing, which is useful if these objects are yet to be fetched.
+    ///
+    /// Use [`Spec::is_verified()`][revision::Spec::is_verified()] to learn if all objects of the spec are present locally.
+    /// Abbreviated ids still have to match objects in the object database.
+    pub fn rev_parse_allow_unverified<'a>(
+        &self,
+        spec: impl Into<&'a BStr>,
+    ) -> Result<revision::Spec<'_>, revision::spec::parse::Error> {
+        revision::Spec::from_bstr(
+            spec,
+            self,
+            revision::spec::parse::Options {
+                object_kind_hint: self.config.object_kind_hint,
+                ambiguous_refs_hint: self.config.ambiguous_refs_hint,
+                allow_unverified_full_hex_ids: true,
                 ..Default::default()
             },
         )
@@ -30,6 +52,49 @@ impl crate::Repository {
             .ok_or(revision::spec::parse::single::Error::RangedRev { spec: spec.into() })
     }
 
+    /// Parse all `specs` and combine them into a single specification of [tips][git_revision::Spec::Tips] similar to how
+    /// `git rev-list` interprets its arguments, so `a..b ^c d` includes commits reachable from `b` and `d`, but not from `a` or `c`.
+    ///
+    /// A `--not` in `specs` inverts the meaning of all specifications following it, hence `a --not b` is the same as `a ^b`.
+    /// Note that only the references of the first specification are retained, but ambiguous references of all of them are.
+    pub fn rev_parse_tips<'a>(
+        &self,
+        specs: impl IntoIterator<Item = impl Into<&'a BStr>>,
+    ) -> Result<revision::Spec<'_>, revision::spec::tips::Error> {
+        let mut out: Option<revision::Spec<'_>> = None;
+        let mut invert = false;
+        for spec in specs {
+            let spec = spec.into();
+            if spec == "--not" {
+                invert = !invert;
+                continue;
+            }
+            let mut spec = self.rev_parse(spec)?.into_tips()?;
+            if invert {
+                spec.invert_tips();
+            }
+            match out.as_mut() {
+                Some(out) => match &mut out.inner {
+                    git_revision::Spec::Tips { include, exclude } => {
+                        let (other_include, other_exclude) = spec.inner.to_tips().expect("always tips");
+                        include.extend(other_include);
+                        exclude.extend(other_exclude);
+                        out.ambiguous_refs.extend(spec.ambiguous_refs);
+                    }
+                    _ => unreachable!("BUG: only tips are stored"),
+                },
+                None => out = Some(spec),
+            }
+        }
+        Ok(out.unwrap_or_else(|| {
+            git_revision::Spec::Tips {
+                include: Vec::new(),
+                exclude: Vec::new(),
+            }
+            .attach(self)
+        }))
+    }
+
     /// Create the baseline for a revision walk by initializing it with the `tips` to start iterating on.
     ///
     /// It can be configured further before starting the actual walk.
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use git_hash::ObjectId;
+
+use crate::bstr::ByteSlice;
+
+impl crate::Repository {
+    /// Return the path to the file listing the commits at the boundary of a shallow clone, which exists only if the repository
+    /// [is shallow][crate::Repository::is_shallow()].
+    pub fn shallow_file(&self) -> PathBuf {
+        self.common_dir().join("shallow")
+    }
+
+    /// Return `true` if this repository is a shallow clone, with the history of some commits being cut off.
+    ///
+    /// See [`shallow_commits()`][crate::Repository::shallow_commits()] to learn at which commits history ends.
+    pub fn is_shallow(&self) -> bool {
+        std::fs::metadata(self.shallow_file()).map_or(false, |m| m.is_file() && m.len() > 0)
+    }
+
+    /// Return the commits at the boundary of a shallow clone, sorted, whose parents are not present in the repository
+    /// and have to be treated as if they didn't exist, or `None` if this repository isn't shallow.
+    ///
+    /// Traversals created with [`rev_walk()`][crate::Repository::rev_walk()] already treat these commits as having no parents.
+    pub fn shallow_commits(&self) -> Result<Option<Vec<ObjectId>>, crate::shallow::open::Error> {
+        let path = self.shallow_file();
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(crate::shallow::open::Error::Io { source, path }),
+        };
+        let mut commits = data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                ObjectId::from_hex(line.trim()).map_err(|source| crate::shallow::open::Error::DecodeHash {
+                    source,
+                    line_number: idx + 1,
+                    path: path.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if commits.is_empty() {
+            return Ok(None);
+        }
+        commits.sort();
+        commits.dedup();
+        Ok(Some(commits))
+    }
+}
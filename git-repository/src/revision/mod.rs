@@ -23,5 +23,9 @@ pub struct Spec<'repo> {
     pub(crate) first_ref: Option<git_ref::Reference>,
     /// The second name of a reference as seen while parsing a `RevSpec`, for completeness.
     pub(crate) second_ref: Option<git_ref::Reference>,
+    /// If `false`, at least one of the objects wasn't looked up in the object database and may not exist.
+    pub(crate) verified: bool,
+    /// All reference names which matched more than one reference.
+    pub(crate) ambiguous_refs: Vec<spec::parse::AmbiguousRef>,
     pub(crate) repo: &'repo crate::Repository,
 }
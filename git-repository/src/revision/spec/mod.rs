@@ -2,6 +2,8 @@ use crate::{ext::ReferenceExt, revision::Spec, Id, Reference};
 
 ///
 pub mod parse;
+///
+pub mod tips;
 
 mod impls {
     use std::ops::{Deref, DerefMut};
@@ -40,6 +42,8 @@ impl<'repo> Spec<'repo> {
             repo: id.repo,
             first_ref: None,
             second_ref: None,
+            verified: true,
+            ambiguous_refs: Vec::new(),
         }
     }
 }
@@ -75,6 +79,21 @@ impl<'repo> Spec<'repo> {
         self.second_ref.as_ref()
     }
 
+    /// Return `true` if all objects of this spec were found in the object database, or `false` if at least one of them was
+    /// a full hex id which was accepted without checking for its existence due to
+    /// [`Options::allow_unverified_full_hex_ids`][crate::revision::spec::parse::Options::allow_unverified_full_hex_ids].
+    pub fn is_verified(&self) -> bool {
+        self.verified
+    }
+
+    /// Return all reference names of the rev-spec which matched more than one reference, along with the references they matched.
+    ///
+    /// The first matching reference was used, which is worth a warning as it might not be the one the user meant.
+    /// This is only recorded if ambiguous references aren't [ignored][crate::revision::spec::parse::AmbiguousRefsHint::Ignore].
+    pub fn ambiguous_refs(&self) -> &[crate::revision::spec::parse::AmbiguousRef] {
+        &self.ambiguous_refs
+    }
+
     /// Return the single included object represented by this instance, or `None` if it is a range of any kind.
     pub fn single(&self) -> Option<Id<'repo>> {
         match self.inner {
@@ -84,7 +103,8 @@ impl<'repo> Spec<'repo> {
             git_revision::Spec::Exclude(_)
             | git_revision::Spec::Range { .. }
             | git_revision::Spec::Merge { .. }
-            | git_revision::Spec::IncludeOnlyParents { .. } => None,
+            | git_revision::Spec::IncludeOnlyParents { .. }
+            | git_revision::Spec::Tips { .. } => None,
         }
     }
 }
@@ -26,6 +26,8 @@ impl<'repo> Delegate<'repo> {
             err: Vec::new(),
             prefix: Default::default(),
             last_call_was_disambiguate_prefix: Default::default(),
+            unverified: Default::default(),
+            ambiguous_refs: Vec::new(),
             opts,
             repo,
         }
@@ -98,6 +100,7 @@ impl<'repo> Delegate<'repo> {
                 },
                 IncludeReachableFromParents => git_revision::Spec::IncludeOnlyParents(first.expect("set by parser")),
                 ExcludeReachableFromParents => git_revision::Spec::ExcludeParents(first.expect("set by parser")),
+                IncludeAndExcludeTips => unreachable!("BUG: the parser never produces sets of tips"),
             }
         }
 
@@ -106,6 +109,8 @@ impl<'repo> Delegate<'repo> {
             first_ref: self.refs[0].take(),
             second_ref: self.refs[1].take(),
             inner: kind_to_spec(self.kind, range),
+            verified: !self.unverified.iter().any(|unverified| *unverified),
+            ambiguous_refs: self.ambiguous_refs,
             repo: self.repo,
         })
     }
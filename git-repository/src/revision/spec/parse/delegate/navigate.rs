@@ -28,60 +28,46 @@ impl<'repo> delegate::Navigate for Delegate<'repo> {
         let repo = self.repo;
 
         for obj in objs.iter() {
-            match kind {
-                Traversal::NthParent(num) => {
-                    match self.repo.find_object(*obj).map_err(Error::from).and_then(|obj| {
-                        obj.try_into_commit().map_err(|err| {
-                            let object::try_into::Error { actual, expected, id } = err;
-                            Error::ObjectKind {
-                                oid: id.attach(repo).shorten_or_id(),
-                                actual,
-                                expected,
-                            }
-                        })
-                    }) {
-                        Ok(commit) => match commit.parent_ids().nth(num.saturating_sub(1)) {
-                            Some(id) => replacements.push((commit.id, id.detach())),
-                            None => errors.push((
-                                commit.id,
-                                Error::ParentOutOfRange {
-                                    oid: commit.id().shorten_or_id(),
-                                    desired: num,
-                                    available: commit.parent_ids().count(),
-                                },
-                            )),
-                        },
-                        Err(err) => errors.push((*obj, err)),
+            let commit = match repo.find_object(*obj).map_err(Error::from).and_then(|obj| {
+                obj.try_into_commit().map_err(|err| {
+                    let object::try_into::Error { actual, expected, id } = err;
+                    Error::ObjectKind {
+                        oid: id.attach(repo).shorten_or_id(),
+                        actual,
+                        expected,
                     }
+                })
+            }) {
+                Ok(commit) => commit,
+                Err(err) => {
+                    errors.push((*obj, err));
+                    continue;
                 }
-                Traversal::NthAncestor(num) => {
-                    let id = obj.attach(repo);
-                    match id
-                        .ancestors()
-                        .first_parent_only()
-                        .all()
-                        .expect("cannot fail without sorting")
-                        .skip(num)
-                        .filter_map(Result::ok)
-                        .next()
-                    {
-                        Some(id) => replacements.push((*obj, id.detach())),
-                        None => errors.push((
-                            *obj,
+            };
+            let (res, desired) = match kind {
+                Traversal::NthParent(num) => (commit.nth_parent(num.saturating_sub(1)), num),
+                Traversal::NthAncestor(num) => (commit.ancestor(num), num),
+            };
+            match res {
+                Ok(target) => replacements.push((commit.id, target.id)),
+                Err(err) => errors.push((
+                    commit.id,
+                    match err {
+                        object::commit::parent::Error::ParentOutOfRange { available, .. } => Error::ParentOutOfRange {
+                            oid: commit.id().shorten_or_id(),
+                            desired,
+                            available,
+                        },
+                        object::commit::parent::Error::AncestorOutOfRange { available, .. } => {
                             Error::AncestorOutOfRange {
-                                oid: id.shorten_or_id(),
-                                desired: num,
-                                available: id
-                                    .ancestors()
-                                    .first_parent_only()
-                                    .all()
-                                    .expect("cannot fail without sorting")
-                                    .skip(1)
-                                    .count(),
-                            },
-                        )),
-                    }
-                }
+                                oid: commit.id().shorten_or_id(),
+                                desired,
+                                available,
+                            }
+                        }
+                        err => err.into(),
+                    },
+                )),
             }
         }
 
@@ -10,7 +10,7 @@ use crate::{
     bstr::{BStr, BString, ByteSlice},
     ext::ReferenceExt,
     object,
-    revision::spec::parse::{Delegate, Error, RefsHint},
+    revision::spec::parse::{AmbiguousRef, AmbiguousRefsHint, Delegate, Error, RefsHint},
 };
 
 impl<'repo> delegate::Revision for Delegate<'repo> {
@@ -19,14 +19,18 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
         if !self.err.is_empty() && self.refs[self.idx].is_some() {
             return None;
         }
-        match self.repo.refs.find(name) {
+        let res = match self.opts.ambiguous_refs_hint {
+            AmbiguousRefsHint::Ignore => self.repo.refs.find(name).map_err(Into::into),
+            AmbiguousRefsHint::Warn | AmbiguousRefsHint::Fail => self.find_ref_and_check_ambiguity(name),
+        };
+        match res {
             Ok(r) => {
                 assert!(self.refs[self.idx].is_none(), "BUG: cannot set the same ref twice");
                 self.refs[self.idx] = Some(r);
                 Some(())
             }
             Err(err) => {
-                self.err.push(err.into());
+                self.err.push(err);
                 None
             }
         }
@@ -54,6 +58,17 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
                 self.err.push(object::find::existing::Error::Find(err).into());
                 None
             }
+            Ok(None)
+                if self.opts.allow_unverified_full_hex_ids
+                    && prefix.hex_len() == prefix.as_oid().kind().len_in_hex() =>
+            {
+                assert!(self.objs[self.idx].is_none(), "BUG: cannot set the same prefix twice");
+                // There is nothing to disambiguate or peel if we don't have the object, so subsequent transformations fail naturally.
+                self.last_call_was_disambiguate_prefix[self.idx] = false;
+                self.unverified[self.idx] = true;
+                self.objs[self.idx] = Some(std::iter::once(prefix.as_oid().to_owned()).collect());
+                Some(())
+            }
             Ok(None) => {
                 self.err.push(Error::PrefixNotFound { prefix });
                 None
@@ -224,3 +239,38 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
         None
     }
 }
+
+impl<'repo> Delegate<'repo> {
+    /// Find the reference with the highest precedence matching `name`, and record or fail if there are more depending on
+    /// our options.
+    fn find_ref_and_check_ambiguity(&mut self, name: &BStr) -> Result<git_ref::Reference, Error> {
+        use git_ref::file::find;
+        let partial_name: &git_ref::PartialNameRef = name
+            .try_into()
+            .map_err(|err| find::existing::Error::Find(find::Error::RefnameValidation(err)))?;
+        let mut refs = self
+            .repo
+            .refs
+            .try_find_all(partial_name)
+            .map_err(find::existing::Error::Find)?
+            .into_iter();
+        let first = refs.next().ok_or_else(|| find::existing::Error::NotFound {
+            name: partial_name.to_partial_path().to_owned(),
+        })?;
+        let others: Vec<_> = refs.map(|r| r.name).collect();
+        if !others.is_empty() {
+            let candidates = std::iter::once(first.name.clone()).chain(others).collect();
+            if self.opts.ambiguous_refs_hint == AmbiguousRefsHint::Fail {
+                return Err(Error::AmbiguousRefName {
+                    name: name.to_owned(),
+                    candidates,
+                });
+            }
+            self.ambiguous_refs.push(AmbiguousRef {
+                name: name.to_owned(),
+                candidates,
+            });
+        }
+        Ok(first)
+    }
+}
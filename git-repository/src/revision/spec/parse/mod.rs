@@ -6,7 +6,7 @@ use git_revision::spec::parse;
 use crate::{bstr::BStr, revision::Spec, Repository};
 
 mod types;
-pub use types::{Error, ObjectKindHint, Options, RefsHint};
+pub use types::{AmbiguousRef, AmbiguousRefsHint, Error, ObjectKindHint, Options, RefsHint};
 
 ///
 pub mod single {
@@ -54,6 +54,10 @@ struct Delegate<'repo> {
     prefix: [Option<git_hash::Prefix>; 2],
     /// If true, we didn't try to do any other transformation which might have helped with disambiguation.
     last_call_was_disambiguate_prefix: [bool; 2],
+    /// If true, the object at the respective position was taken from a full hex id without checking for its existence.
+    unverified: [bool; 2],
+    /// All reference names that matched more than one reference.
+    ambiguous_refs: Vec<AmbiguousRef>,
 
     repo: &'repo Repository,
 }
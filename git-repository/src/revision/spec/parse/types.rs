@@ -20,6 +20,41 @@ pub enum RefsHint {
     Fail,
 }
 
+/// A hint to know what to do if a reference name matches more than one reference, like `v1.2` matching both
+/// `refs/tags/v1.2` and `refs/heads/v1.2`.
+///
+/// In any case, references are looked up in the order of precedence documented in `git help revisions`, that is
+/// the name as is, then prefixed with `refs/`, `refs/tags/`, `refs/heads/`, `refs/remotes/` and finally `refs/remotes/<name>/HEAD`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AmbiguousRefsHint {
+    /// Use the first matching reference without checking if there are more.
+    ///
+    /// This is what `core.warnAmbiguousRefs = false` does in git, and it's the fastest option.
+    Ignore,
+    /// Use the first matching reference, but record all matching references in
+    /// [`Spec::ambiguous_refs()`][crate::revision::Spec::ambiguous_refs()] so they can be presented as warning.
+    ///
+    /// This is the default.
+    Warn,
+    /// If more than one reference matches, fail with [`Error::AmbiguousRefName`] instead of silently picking the first one.
+    Fail,
+}
+
+impl Default for AmbiguousRefsHint {
+    fn default() -> Self {
+        AmbiguousRefsHint::Warn
+    }
+}
+
+/// A reference name in a rev-spec which matched more than one reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousRef {
+    /// The name as it was given in the rev-spec, like `v1.2`.
+    pub name: BString,
+    /// The full names of all matching references in order of precedence, with the first one being the one that was used.
+    pub candidates: Vec<git_ref::FullName>,
+}
+
 /// A hint to know which object kind to prefer if multiple objects match a prefix.
 ///
 /// This disambiguation mechanism is applied only if there is no disambiguation hints in the spec itself.
@@ -54,6 +89,14 @@ pub struct Options {
     /// If `None`, the rev-spec itself must disambiguate the object by drilling down to desired kinds or applying
     /// other disambiguating transformations.
     pub object_kind_hint: Option<ObjectKindHint>,
+    /// If `true`, object ids of full length that don't exist in the object database resolve as they are instead of failing
+    /// with [`Error::PrefixNotFound`], yielding a spec that [isn't verified][crate::revision::Spec::is_verified()].
+    ///
+    /// This is useful if objects are about to be fetched from a remote, for example. Abbreviated ids are always
+    /// disambiguated against the object database.
+    pub allow_unverified_full_hex_ids: bool,
+    /// What to do if a reference name matches more than one reference.
+    pub ambiguous_refs_hint: AmbiguousRefsHint,
 }
 
 /// The error returned by [`crate::Repository::rev_parse()`].
@@ -136,6 +179,13 @@ pub enum Error {
         /// The reference matching the prefix.
         reference: git_ref::Reference,
     },
+    #[error("Refname {name:?} is ambiguous. Candidates are:\n{}", candidates.iter().map(|name| format!("\t{name}")).collect::<Vec<_>>().join("\n"))]
+    AmbiguousRefName {
+        /// The name as given in the rev-spec.
+        name: BString,
+        /// The full names of all matching references in order of precedence.
+        candidates: Vec<git_ref::FullName>,
+    },
     #[error(transparent)]
     IdFromHex(#[from] git_hash::decode::Error),
     #[error(transparent)]
@@ -173,6 +223,10 @@ pub enum Error {
     },
     #[error(transparent)]
     Traverse(#[from] git_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    Parent(#[from] object::commit::parent::Error),
     #[error("Spec does not contain a single object id")]
     SingleNotFound,
 }
@@ -0,0 +1,76 @@
+use git_hash::ObjectId;
+
+use crate::{ext::ObjectIdExt, object, revision, revision::Spec};
+
+/// The error returned by [`Spec::into_tips()`], [`Spec::rev_walk()`] and [`Repository::rev_parse_tips()`][crate::Repository::rev_parse_tips()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] revision::spec::parse::Error),
+    #[error("Cannot turn '{theirs}...{ours}' into tips as computing merge-bases isn't supported yet")]
+    MergeBaseUnsupported { theirs: ObjectId, ours: ObjectId },
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] object::peel::to_kind::Error),
+}
+
+/// Conversion
+impl<'repo> Spec<'repo> {
+    /// Turn this instance into one holding [tips][git_revision::Spec::Tips] of included and excluded commits.
+    ///
+    /// Parents are looked up for `a^@` and `a^!`, whereas `a...b` isn't supported as it would require a merge-base.
+    pub fn into_tips(mut self) -> Result<Self, Error> {
+        let (include, exclude) = match self.inner.to_tips() {
+            Some(tips) => tips,
+            None => match self.inner {
+                git_revision::Spec::Merge { theirs, ours } => return Err(Error::MergeBaseUnsupported { theirs, ours }),
+                git_revision::Spec::IncludeOnlyParents(id) => (self.parents_of(id)?, Vec::new()),
+                git_revision::Spec::ExcludeParents(id) => (vec![id], self.parents_of(id)?),
+                git_revision::Spec::Include(_)
+                | git_revision::Spec::Exclude(_)
+                | git_revision::Spec::Range { .. }
+                | git_revision::Spec::Tips { .. } => unreachable!("BUG: these can always be turned into tips"),
+            },
+        };
+        self.inner = git_revision::Spec::Tips { include, exclude };
+        Ok(self)
+    }
+
+    /// Create a platform to traverse all commits included by this specification, after [turning it into tips][Spec::into_tips()].
+    ///
+    /// All tips are peeled to commits first, which fails if that isn't possible.
+    pub fn rev_walk(self) -> Result<revision::walk::Platform<'repo>, Error> {
+        let repo = self.repo;
+        let (include, exclude) = self
+            .into_tips()?
+            .inner
+            .to_tips()
+            .expect("tips can always be obtained from tips");
+        let peel = |ids: Vec<ObjectId>| -> Result<Vec<ObjectId>, Error> {
+            ids.into_iter()
+                .map(|id| -> Result<_, Error> { Ok(id.attach(repo).object()?.peel_to_kind(object::Kind::Commit)?.id) })
+                .collect()
+        };
+        Ok(repo.rev_walk(peel(include)?).hide(peel(exclude)?))
+    }
+
+    /// Swap included and excluded tips, which is what `--not` does to all specifications that follow it.
+    pub(crate) fn invert_tips(&mut self) {
+        if let git_revision::Spec::Tips { include, exclude } = &mut self.inner {
+            std::mem::swap(include, exclude);
+        }
+    }
+
+    fn parents_of(&self, id: ObjectId) -> Result<Vec<ObjectId>, Error> {
+        Ok(id
+            .attach(self.repo)
+            .object()?
+            .peel_to_kind(object::Kind::Commit)?
+            .into_commit()
+            .parent_ids()
+            .map(|id| id.detach())
+            .collect())
+    }
+}
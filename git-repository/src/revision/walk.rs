@@ -1,4 +1,7 @@
-use git_hash::ObjectId;
+use std::{cell::Cell, collections::HashSet, rc::Rc};
+
+use git_hash::{oid, ObjectId};
+use git_object::bstr::ByteSlice;
 use git_odb::FindExt;
 
 use crate::{revision, Repository};
@@ -8,6 +11,7 @@ use crate::{revision, Repository};
 pub struct Platform<'repo> {
     pub(crate) repo: &'repo Repository,
     pub(crate) tips: Vec<ObjectId>,
+    pub(crate) hidden: Vec<ObjectId>,
     pub(crate) sorting: git_traverse::commit::Sorting,
     pub(crate) parents: git_traverse::commit::Parents,
 }
@@ -17,6 +21,7 @@ impl<'repo> Platform<'repo> {
         revision::walk::Platform {
             repo,
             tips: tips.into_iter().map(Into::into).collect(),
+            hidden: Vec::new(),
             sorting: Default::default(),
             parents: Default::default(),
         }
@@ -36,40 +41,135 @@ impl<'repo> Platform<'repo> {
         self.parents = git_traverse::commit::Parents::First;
         self
     }
+
+    /// Don't return any commit reachable from the given `tips`, similar to `^tip` or `--not tip` in `git rev-list`.
+    pub fn hide(mut self, tips: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.hidden.extend(tips.into_iter().map(Into::into));
+        self
+    }
+}
+
+///
+pub mod error {
+    /// The error returned by [`Platform::all()`][super::Platform::all()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        AncestorIter(#[from] git_traverse::commit::ancestors::Error),
+        #[error(transparent)]
+        ShallowCommits(#[from] crate::shallow::open::Error),
+    }
 }
+pub use error::Error;
 
 /// Produce the iterator
 impl<'repo> Platform<'repo> {
     /// Return an iterator to traverse all commits reachable as configured by the [Platform].
     ///
+    /// In shallow clones, the commits at the [shallow boundary][Repository::shallow_commits()] are returned as if they had
+    /// no parents, which is recorded in [`Walk::is_shallow`][revision::Walk::is_shallow].
+    ///
     /// # Performance
     ///
     /// It's highly recommended to set an [`object cache`][Repository::object_cache_size()] on the parent repo
     /// to greatly speed up performance if the returned id is supposed to be looked up right after.
-    pub fn all(self) -> Result<revision::Walk<'repo>, git_traverse::commit::ancestors::Error> {
+    ///
+    /// Note that [hidden][Platform::hide()] commits are fully traversed before the first commit is returned.
+    pub fn all(self) -> Result<revision::Walk<'repo>, Error> {
         let Platform {
             repo,
             tips,
+            hidden,
             sorting,
             parents,
         } = self;
+        let shallow_commits = Rc::new(repo.shallow_commits()?.unwrap_or_default());
+        let clamped = Rc::new(Cell::new(false));
+        let hidden = reachable_from(repo, hidden, &shallow_commits, &clamped)?;
         Ok(revision::Walk {
             repo,
             inner: Box::new(
-                git_traverse::commit::Ancestors::new(
+                git_traverse::commit::Ancestors::filtered(
                     tips,
                     git_traverse::commit::ancestors::State::default(),
-                    move |oid, buf| repo.objects.find_commit_iter(oid, buf),
+                    {
+                        let clamped = Rc::clone(&clamped);
+                        move |oid, buf| find_commit_iter_in_shallow_graph(repo, &shallow_commits, &clamped, oid, buf)
+                    },
+                    move |id| !hidden.contains(&id.to_owned()),
                 )
                 .sorting(sorting)?
                 .parents(parents),
             ),
+            clamped,
             is_shallow: None,
             error_on_missing_commit: false,
         })
     }
 }
 
+/// Find the commit `id` like `find_commit_iter()` does, but if it's one of the sorted `shallow_commits`, return it without
+/// its parents as these aren't present in the repository, and set `clamped` accordingly.
+fn find_commit_iter_in_shallow_graph<'a>(
+    repo: &Repository,
+    shallow_commits: &[ObjectId],
+    clamped: &Cell<bool>,
+    id: &oid,
+    buf: &'a mut Vec<u8>,
+) -> Result<git_object::CommitRefIter<'a>, git_odb::find::existing_iter::Error<git_odb::store::find::Error>> {
+    if shallow_commits.binary_search_by(|c| c.as_ref().cmp(id)).is_err() {
+        return repo.objects.find_commit_iter(id, buf);
+    }
+    // Once found, the buffer holds exactly the commit data.
+    repo.objects.find_commit_iter(id, buf)?;
+    let without_parents = {
+        let mut out = Vec::with_capacity(buf.len());
+        let mut in_headers = true;
+        for line in buf.lines_with_terminator() {
+            if line == b"\n" {
+                in_headers = false;
+            }
+            if in_headers && line.starts_with(b"parent ") {
+                clamped.set(true);
+                continue;
+            }
+            out.extend_from_slice(line);
+        }
+        out
+    };
+    *buf = without_parents;
+    Ok(git_object::CommitRefIter::from_bytes(buf))
+}
+
+/// Return all commits reachable from `tips`, ignoring missing commits as they are expected in shallow clones, and not
+/// traversing past the `shallow_commits`.
+fn reachable_from(
+    repo: &Repository,
+    tips: Vec<ObjectId>,
+    shallow_commits: &[ObjectId],
+    clamped: &Cell<bool>,
+) -> Result<HashSet<ObjectId>, git_traverse::commit::ancestors::Error> {
+    let mut out = HashSet::new();
+    if tips.is_empty() {
+        return Ok(out);
+    }
+    for id in
+        git_traverse::commit::Ancestors::new(tips, git_traverse::commit::ancestors::State::default(), |oid, buf| {
+            find_commit_iter_in_shallow_graph(repo, shallow_commits, clamped, oid, buf)
+        })
+    {
+        match id {
+            Ok(id) => {
+                out.insert(id);
+            }
+            Err(git_traverse::commit::ancestors::Error::FindExisting { .. }) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(out)
+}
+
 pub(crate) mod iter {
     use crate::{ext::ObjectIdExt, Id};
 
@@ -79,10 +179,13 @@ pub(crate) mod iter {
         pub(crate) inner:
             Box<dyn Iterator<Item = Result<git_hash::ObjectId, git_traverse::commit::ancestors::Error>> + 'repo>,
         pub(crate) error_on_missing_commit: bool,
-        // TODO: tests
-        /// After iteration this flag is true if the iteration was stopped prematurely due to missing parent commits.
+        /// Set if parents of commits at the shallow boundary were ignored.
+        pub(crate) clamped: std::rc::Rc<std::cell::Cell<bool>>,
+        /// After iteration this flag is true if the commit graph is shallow, either because commits at the
+        /// [shallow boundary][crate::Repository::shallow_commits()] were treated as having no parents, or because
+        /// the iteration was stopped prematurely due to missing parent commits.
         /// Note that this flag won't be `Some` if any iteration error occurs, which is the case if
-        /// [`error_on_missing_commit()`][Walk::error_on_missing_commit()] was called.
+        /// [`error_on_missing_commit()`][Walk::error_on_missing_commit()] was called and a commit is missing.
         ///
         /// This happens if a repository is a shallow clone.
         /// Note that this value is `None` as long as the iteration isn't complete.
@@ -108,7 +211,7 @@ pub(crate) mod iter {
         fn next(&mut self) -> Option<Self::Item> {
             match self.inner.next() {
                 None => {
-                    self.is_shallow = Some(false);
+                    self.is_shallow = Some(self.clamped.get());
                     None
                 }
                 Some(Ok(oid)) => Some(Ok(oid.attach(self.repo))),
@@ -0,0 +1,19 @@
+///
+pub mod open {
+    /// The error returned by [`Repository::shallow_commits()`][crate::Repository::shallow_commits()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open the shallow file at '{}'", .path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("Line {line_number} of the shallow file at '{}' isn't a full object id", .path.display())]
+        DecodeHash {
+            source: git_hash::decode::Error,
+            line_number: usize,
+            path: std::path::PathBuf,
+        },
+    }
+}
@@ -0,0 +1,18 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q --object-format=sha1 sha1
+(cd sha1
+  git commit -q --allow-empty -m "first"
+)
+
+git init -q --object-format=sha256 sha256
+(cd sha256
+  git commit -q --allow-empty -m "first"
+)
+
+git init -q sha256-without-format-version
+(cd sha256-without-format-version
+  git config core.repositoryFormatVersion 0
+  git config extensions.objectFormat sha256
+)
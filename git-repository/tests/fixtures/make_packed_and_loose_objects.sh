@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+echo packed > file
+git add file
+git commit -q -m c1
+mkdir dir
+echo packed > dir/file
+git add dir
+git commit -q -m c2
+git repack -adq
+
+echo loose >> file
+git commit -q -am c3
@@ -396,3 +396,47 @@ git init new
 (cd new
   baseline '@{1}'
 )
+
+git init ambiguous_branch_and_tag
+(
+  cd ambiguous_branch_and_tag
+  tick
+  git commit --allow-empty -q -m tagged
+  git tag v1.2
+  tick
+  git commit --allow-empty -q -m branched
+  git branch v1.2
+
+  baseline "v1.2"               # the tag takes precedence over the branch
+  baseline "heads/v1.2"         # unambiguous
+  baseline "v1.2..heads/v1.2"   # only the first name is ambiguous
+  baseline "main"               # not ambiguous at all
+)
+
+git clone -q ambiguous_branch_and_tag ambiguous_branch_and_tag_without_warnings
+(
+  cd ambiguous_branch_and_tag_without_warnings
+  git branch v1.2 origin/v1.2
+  git config core.warnAmbiguousRefs false
+)
+
+git init hidden_tip_with_missing_parent
+(
+  cd hidden_tip_with_missing_parent
+  tick
+  git commit --allow-empty -q -m missing
+  missing=$(git rev-parse @)
+  tick
+  git commit --allow-empty -q -m x
+  git branch x
+  git checkout -q --orphan y
+  tick
+  git commit --allow-empty -q -m y1
+  git branch y1
+  tick
+  git commit --allow-empty -q -m y2
+  git branch y2
+  tick
+  git branch w $(git commit-tree -p y2 -p y1 -m w "$(git write-tree)")
+  rm .git/objects/${missing:0:2}/${missing:2}
+)
@@ -0,0 +1,13 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  for msg in c1 c2 c3 c4; do
+    echo "$msg" > file
+    git add file
+    git commit -q -m "$msg"
+  done
+)
+
+git clone -q --depth 2 "file://$PWD/base" shallow
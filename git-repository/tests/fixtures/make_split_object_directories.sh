@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git commit -q --allow-empty -m first
+)
+
+mkdir objects
+mv repo/.git/objects objects/main
+mkdir -p repo/.git/objects/info repo/.git/objects/pack
+
+mkdir objects/extra
+(cd repo
+  GIT_OBJECT_DIRECTORY=../objects/extra GIT_ALTERNATE_OBJECT_DIRECTORIES=../objects/main git commit -q --allow-empty -m second
+)
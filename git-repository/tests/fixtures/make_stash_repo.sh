@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo base > file
+git add file && git commit -q -m base
+
+echo staged > file
+git add file
+echo worktree > file
+git stash push -q -m "tracked changes only"
+
+echo staged-again > file
+git add file
+echo untracked > untracked
+git stash push -q --include-untracked -m "with untracked files"
+
+for spec in stash stash@{0} stash@{1} stash@{0}^1 stash@{0}^2 stash@{0}^3 stash@{1}^1 stash@{1}^2 stash@{0}^{tree} stash@{0}^3^{tree}; do
+  echo "$spec $(git rev-parse "$spec")"
+done > rev-parse.baseline
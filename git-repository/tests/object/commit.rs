@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 
+use git_repository as git;
 use git_testtools::hex_to_id;
 
 use crate::basic_repo;
@@ -34,3 +35,133 @@ fn decode() -> crate::Result {
     assert_eq!(commit.decode()?.message, "c2\n");
     Ok(())
 }
+
+#[test]
+fn author_and_committer_resolved() -> crate::Result {
+    let repo = basic_repo()?;
+    let commit = repo.head_commit()?;
+    let mailmap = git::mailmap::Snapshot::from_bytes(b"Mapped Author <mapped@example.com> <author@example.com>");
+
+    let author = commit.author_resolved(&mailmap)?;
+    assert_eq!(*author.name, "Mapped Author");
+    assert_eq!(*author.email, "mapped@example.com");
+    assert_eq!(author.time, commit.author()?.time);
+
+    let committer = commit.committer_resolved(&mailmap)?;
+    assert_eq!(
+        committer,
+        git::mailmap::snapshot::Signature::from(commit.committer()?),
+        "unmapped signatures stay the same"
+    );
+    assert!(
+        matches!(committer.name, std::borrow::Cow::Borrowed(_)),
+        "nothing is copied"
+    );
+    Ok(())
+}
+
+mod parents {
+    use git_repository as git;
+    use git_testtools::hex_to_id;
+
+    fn repo(name: &str) -> crate::Result<git::Repository> {
+        crate::named_subrepo_opts("make_rev_spec_parse_repos.sh", name, crate::restricted())
+    }
+
+    fn commit<'repo>(repo: &'repo git::Repository, spec: &str) -> crate::Result<git::Commit<'repo>> {
+        Ok(repo.rev_parse_single(spec)?.object()?.into_commit())
+    }
+
+    #[test]
+    fn nth_parent_of_octopus_merge() -> crate::Result {
+        let repo = repo("complex_graph")?;
+        let b = commit(&repo, "b")?;
+        assert_eq!(b.parent_ids().count(), 3, "b is an octopus merge");
+        for (index, spec) in ["b^1", "b^2", "b^3"].into_iter().enumerate() {
+            assert_eq!(b.nth_parent(index)?.id, commit(&repo, spec)?.id);
+        }
+        assert_eq!(b.nth_parent(2)?.nth_parent(1)?.id, commit(&repo, "j")?.id);
+        Ok(())
+    }
+
+    #[test]
+    fn nth_parent_out_of_range() -> crate::Result {
+        let repo = repo("complex_graph")?;
+        let b = commit(&repo, "b")?;
+        let err = b.nth_parent(3).unwrap_err();
+        assert!(matches!(
+            err,
+            git::object::commit::parent::Error::ParentOutOfRange {
+                desired: 3,
+                available: 3,
+                ..
+            }
+        ));
+        assert_eq!(
+            commit(&repo, "g")?.nth_parent(0).unwrap_err().to_string(),
+            format!(
+                "Commit {} has 0 parents and the parent at index 0 is out of range",
+                commit(&repo, "g")?.id
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ancestor() -> crate::Result {
+        let repo = repo("complex_graph")?;
+        let a = commit(&repo, "a")?;
+        assert_eq!(a.ancestor(0)?.id, a.id, "zero steps yields the commit itself");
+        assert_eq!(a.ancestor(1)?.id, commit(&repo, "b")?.id);
+        assert_eq!(a.ancestor(2)?.id, commit(&repo, "d")?.id);
+        assert_eq!(a.ancestor(3)?.id, hex_to_id("9f9eac6bd1cd4b4cc6a494f044b28c985a22972b"));
+        assert!(matches!(
+            a.ancestor(42).unwrap_err(),
+            git::object::commit::parent::Error::AncestorOutOfRange {
+                desired: 42,
+                available: 3,
+                ..
+            }
+        ));
+        Ok(())
+    }
+}
+
+mod stash {
+    use git_repository as git;
+
+    fn commit<'repo>(repo: &'repo git::Repository, spec: &str) -> crate::Result<git::Commit<'repo>> {
+        Ok(repo.rev_parse_single(spec)?.object()?.into_commit())
+    }
+
+    #[test]
+    fn entries_expose_base_index_and_untracked_files() -> crate::Result {
+        let repo = crate::named_repo("make_stash_repo.sh")?;
+        let with_untracked = commit(&repo, "stash@{0}")?;
+        assert_eq!(with_untracked.stash_base()?.id, commit(&repo, "main")?.id);
+        assert_eq!(with_untracked.stash_index()?.id, commit(&repo, "stash@{0}^2")?.id);
+        let untracked = with_untracked.stash_untracked()?.expect("untracked files were stashed");
+        assert_eq!(
+            untracked
+                .tree()?
+                .lookup_entry_by_path("untracked")?
+                .map(|entry| entry.mode()),
+            Some(git::objs::tree::EntryMode::Blob)
+        );
+        assert!(
+            untracked.tree()?.lookup_entry_by_path("file")?.is_none(),
+            "tracked files aren't part of the untracked tree"
+        );
+
+        let tracked_only = commit(&repo, "stash@{1}")?;
+        assert_eq!(tracked_only.stash_base()?.id, commit(&repo, "main")?.id);
+        assert_eq!(tracked_only.stash_index()?.id, commit(&repo, "stash@{1}^2")?.id);
+        assert!(tracked_only.stash_untracked()?.is_none());
+        assert_ne!(
+            tracked_only.tree_id()?,
+            tracked_only.stash_index()?.tree_id()?,
+            "the worktree and the index were stashed with different changes"
+        );
+        Ok(())
+    }
+}
@@ -27,7 +27,9 @@ mod diff {
                         assert_eq!(previous_id.object().unwrap().data.as_bstr(), "a\n");
                         assert_eq!(id.object().unwrap().data.as_bstr(), "a\na1\n");
                     }
-                    Event::Deletion { .. } | Event::Addition { .. } => unreachable!("only modification is expected"),
+                    Event::Deletion { .. } | Event::Addition { .. } | Event::SubmoduleChanged { .. } => {
+                        unreachable!("only modification is expected")
+                    }
                 };
 
                 let diff = change.event.diff().expect("changed file").expect("objects available");
@@ -50,7 +50,8 @@ fn author_and_committer_and_fallback() {
                     seconds_since_unix_epoch: 42,
                     offset_in_seconds: 1800,
                     sign: git_date::time::Sign::Plus
-                }
+                },
+                raw_time: None,
             }),
             "the only parsesable marker time we know right now, indicating time parse success"
         );
@@ -2,9 +2,11 @@ use git_repository::Repository;
 
 mod config;
 mod object;
+mod objects;
 mod open;
 mod reference;
 mod remote;
+mod shallow;
 mod state;
 mod worktree;
 
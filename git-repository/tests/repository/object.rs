@@ -115,6 +115,17 @@ mod find {
         );
         Ok(())
     }
+
+    #[test]
+    fn missing_objects_are_classified_as_not_found() -> crate::Result {
+        let repo = basic_repo()?;
+        let err = repo
+            .find_object(git::hash::ObjectId::null(repo.object_hash()))
+            .unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.kind(), git::object::find::ErrorKind::NotFound);
+        Ok(())
+    }
 }
 
 mod tag {
@@ -301,6 +312,151 @@ mod commit {
         );
         Ok(())
     }
+
+    fn git_log(repo: &git::Repository) -> crate::Result<Vec<String>> {
+        let out = std::process::Command::new("git")
+            .args(["log", "--format=%H %P %s", "HEAD"])
+            .current_dir(repo.work_dir().expect("non-bare"))
+            .output()?;
+        assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+        Ok(String::from_utf8(out.stdout)?.lines().map(ToOwned::to_owned).collect())
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn duplicate_parents_are_ignored_and_git_sees_the_commit() -> crate::Result {
+        let _env = freeze_time();
+        let (repo, _keep) = crate::repo_rw_opts("make_basic_repo.sh", restricted_and_git())?;
+        let parent = repo.head_id()?.detach();
+        let tree_id = repo.find_object(parent)?.into_commit().tree_id()?.detach();
+
+        let commit_id = repo.commit("HEAD", "duplicates\n\nbody", tree_id, [parent, parent, parent])?;
+        let commit = commit_id.object()?.into_commit();
+        assert_eq!(
+            commit.parent_ids().map(|id| id.detach()).collect::<Vec<_>>(),
+            vec![parent],
+            "it's not a merge commit"
+        );
+        assert_eq!(
+            repo.head()?
+                .log_iter()
+                .rev()?
+                .expect("log present")
+                .next()
+                .expect("one line")?
+                .message,
+            "commit: duplicates"
+        );
+
+        let log = git_log(&repo)?;
+        assert_eq!(log.len(), 3, "the commit is on top of the two existing ones");
+        assert_eq!(log[0], format!("{} {} duplicates", commit_id, parent));
+        Ok(())
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn parents_not_matching_the_reference_cause_failure_to_not_lose_commits() -> crate::Result {
+        let _env = freeze_time();
+        let (repo, _keep) = crate::repo_rw_opts("make_basic_repo.sh", restricted_and_git())?;
+        let head_commit = repo.head_id()?.object()?.into_commit();
+        let stale_parent = head_commit.nth_parent(0)?.id;
+        let tree_id = head_commit.tree_id()?.detach();
+
+        let err = repo
+            .commit("HEAD", "based on an outdated parent", tree_id, Some(stale_parent))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains(&format!("{}", stale_parent)),
+            "the unexpected previous value is mentioned: {}",
+            err
+        );
+        assert_eq!(repo.head_id()?, head_commit.id, "HEAD wasn't changed");
+        Ok(())
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn detached_head_is_updated_without_affecting_branches() -> crate::Result {
+        use git::refs::{
+            transaction::{Change, LogChange, PreviousValue, RefEdit},
+            Target,
+        };
+        let _env = freeze_time();
+        let (repo, _keep) = crate::repo_rw_opts("make_basic_repo.sh", restricted_and_git())?;
+        let parent = repo.head_id()?.detach();
+        repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                expected: PreviousValue::Any,
+                new: Target::Peeled(parent),
+            },
+            name: "HEAD".try_into()?,
+            deref: false,
+        })?;
+        assert!(repo.head()?.is_detached());
+        let tree_id = repo.find_object(parent)?.into_commit().tree_id()?.detach();
+
+        let commit_id = repo.commit("HEAD", "on detached head", tree_id, Some(parent))?;
+        let head = repo.head()?;
+        assert!(head.is_detached(), "HEAD stays detached");
+        assert_eq!(repo.head_id()?, commit_id);
+        assert_eq!(
+            repo.find_reference("main")?.id(),
+            parent,
+            "the previously checked out branch remains unchanged"
+        );
+        assert_eq!(git_log(&repo)?[0], format!("{} {} on detached head", commit_id, parent));
+        Ok(())
+    }
+}
+
+mod find_paths_of {
+    use git_repository::objs::{
+        tree::{Entry, EntryMode},
+        Tree,
+    };
+
+    use crate::repository::object::empty_bare_repo;
+
+    #[test]
+    fn blobs_at_multiple_paths_and_not_at_all() -> crate::Result {
+        let (_tmp, repo) = empty_bare_repo()?;
+        let blob = repo.write_blob("content")?.detach();
+        let missing = repo.write_blob("not in the tree")?.detach();
+        let subtree = repo
+            .write_object(&Tree {
+                entries: vec![Entry {
+                    mode: EntryMode::Blob,
+                    filename: "b".into(),
+                    oid: blob,
+                }],
+            })?
+            .detach();
+        let root = repo
+            .write_object(&Tree {
+                entries: vec![
+                    Entry {
+                        mode: EntryMode::Blob,
+                        filename: "a".into(),
+                        oid: blob,
+                    },
+                    Entry {
+                        mode: EntryMode::Tree,
+                        filename: "dir".into(),
+                        oid: subtree,
+                    },
+                ],
+            })?
+            .detach();
+
+        assert_eq!(
+            repo.find_paths_of(&[missing, blob], root)?,
+            vec![(missing, vec![]), (blob, vec!["a".into(), "dir/b".into()])],
+            "ids are returned in the order given, with all of their paths"
+        );
+        Ok(())
+    }
 }
 
 fn empty_bare_repo() -> crate::Result<(tempfile::TempDir, git::Repository)> {
@@ -0,0 +1,87 @@
+use std::{collections::BTreeSet, sync::atomic::AtomicBool};
+
+use git_repository as git;
+
+fn repo() -> crate::Result<git::Repository> {
+    crate::named_repo("make_packed_and_loose_objects.sh")
+}
+
+#[test]
+fn metrics_reflect_the_loaded_packs_and_loose_databases() -> crate::Result {
+    let repo = repo()?;
+    repo.head_commit()?;
+    let metrics = repo.objects_metrics();
+    assert_eq!(metrics.known_packs, 1, "all but the last commit were repacked");
+    assert_eq!(metrics.loose_dbs, 1);
+    assert_eq!(metrics.orphaned_indices, 0);
+    Ok(())
+}
+
+#[test]
+fn verify_objects_with_default_options_checks_packs_and_loose_objects() -> crate::Result {
+    let repo = repo()?;
+    let outcome = repo.verify_objects(Default::default(), &mut git::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.index_statistics.len(), 1);
+    assert_eq!(outcome.loose_object_stores.len(), 1);
+    assert_eq!(
+        outcome.loose_object_stores[0].statistics.num_objects, 3,
+        "a commit, its tree and the changed blob"
+    );
+    assert!(outcome.tree_issues.is_empty());
+    assert!(outcome.missing_delta_bases.is_empty());
+    Ok(())
+}
+
+#[test]
+fn verify_objects_without_pack_cache_yields_the_same_statistics() -> crate::Result {
+    let repo = repo()?;
+    let verify = |options| {
+        repo.verify_objects(options, &mut git::progress::Discard, &AtomicBool::default())
+            .map(|outcome| outcome.index_statistics)
+    };
+    assert_eq!(
+        verify(git::objects::verify::Options {
+            pack_cache_bytes_per_thread: 0,
+            thread_limit: Some(1),
+            ..Default::default()
+        })?,
+        verify(Default::default())?
+    );
+    Ok(())
+}
+
+#[test]
+fn objects_iter_returns_all_objects_with_their_data() -> crate::Result {
+    let repo = repo()?;
+    let mut ids = BTreeSet::new();
+    for object in repo.objects_iter(Default::default())? {
+        let object = object?;
+        let expected = repo.find_object(object.id)?;
+        assert_eq!(object.kind, expected.kind);
+        assert_eq!(object.data, expected.data);
+        ids.insert(object.id);
+    }
+    let expected: BTreeSet<_> = repo.objects.iter()?.collect::<Result<_, _>>()?;
+    assert_eq!(ids, expected);
+    Ok(())
+}
+
+#[test]
+fn objects_iter_can_be_limited_to_certain_kinds() -> crate::Result {
+    let repo = repo()?;
+    let commits = repo
+        .objects_iter(git::objects::iter::Options {
+            kinds: Some(vec![git::objs::Kind::Commit]),
+            ..Default::default()
+        })?
+        .map(|object| object.map(|object| object.id))
+        .collect::<Result<BTreeSet<_>, _>>()?;
+    let expected = repo
+        .head_commit()?
+        .ancestors()
+        .all()?
+        .map(|id| id.map(|id| id.detach()))
+        .collect::<Result<BTreeSet<_>, _>>()?;
+    assert_eq!(commits, expected);
+    Ok(())
+}
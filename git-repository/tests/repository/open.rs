@@ -226,6 +226,166 @@ mod with_overrides {
     }
 }
 
+mod object_directories_from_environment {
+    use std::path::{Path, PathBuf};
+
+    use git_repository as git;
+    use git_sec::Permission;
+    use git_testtools::Env;
+    use serial_test::serial;
+
+    /// The `main` object directory has the first commit and its tree, and `extra` has the second commit only,
+    /// while the objects directory of the repository is empty.
+    fn fixture() -> crate::Result<PathBuf> {
+        Ok(
+            std::env::current_dir()?.join(git_testtools::scripted_fixture_repo_read_only(
+                "make_split_object_directories.sh",
+            )?),
+        )
+    }
+
+    fn open(root: &Path, objects: Permission) -> Result<git::Repository, git::open::Error> {
+        let mut opts = git::open::Options::isolated();
+        opts.permissions.env.objects = objects;
+        git::ThreadSafeRepository::open_opts(root.join("repo"), opts).map(|repo| repo.to_thread_local())
+    }
+
+    fn head_ref_target(repo: &git::Repository) -> crate::Result<git::ObjectId> {
+        Ok(repo
+            .head_ref()?
+            .expect("head is a branch")
+            .target()
+            .try_id()
+            .expect("direct reference")
+            .to_owned())
+    }
+
+    fn join(dirs: &[PathBuf]) -> String {
+        std::env::join_paths(dirs)
+            .expect("no separators in paths")
+            .into_string()
+            .expect("valid unicode")
+    }
+
+    #[test]
+    #[serial]
+    fn objects_directory_and_alternates_are_used() -> crate::Result {
+        let root = fixture()?;
+        let main = root.join("objects").join("main");
+        let extra = root.join("objects").join("extra");
+        let _env = Env::new()
+            .set("GIT_OBJECT_DIRECTORY", extra.to_str().expect("valid unicode"))
+            .set("GIT_ALTERNATE_OBJECT_DIRECTORIES", join(&[main.clone()]));
+
+        let repo = open(&root, Permission::Allow)?;
+        assert_eq!(repo.objects.store_ref().path(), extra);
+        assert_eq!(repo.objects.store_ref().extra_alternates(), [main]);
+
+        let commit = repo.find_object(head_ref_target(&repo)?)?.try_into_commit()?;
+        assert_eq!(commit.message()?.summary().as_ref(), "second");
+        let parent_id = commit.parent_ids().next().expect("one parent");
+        let parent = parent_id.object()?.try_into_commit()?;
+        assert_eq!(parent.message()?.summary().as_ref(), "first");
+        parent.tree()?;
+        assert_eq!(repo.objects.store_ref().metrics().extra_alternates, 1);
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn multiple_alternates_can_be_given() -> crate::Result {
+        let root = fixture()?;
+        let main = root.join("objects").join("main");
+        let extra = root.join("objects").join("extra");
+        let _env = Env::new()
+            .unset("GIT_OBJECT_DIRECTORY")
+            .set("GIT_ALTERNATE_OBJECT_DIRECTORIES", join(&[extra.clone(), main.clone()]));
+
+        let repo = open(&root, Permission::Allow)?;
+        assert_eq!(repo.objects.store_ref().extra_alternates(), [extra, main]);
+        let commit = repo.find_object(head_ref_target(&repo)?)?.try_into_commit()?;
+        let parent_id = commit.parent_ids().next().expect("one parent");
+        assert!(repo.try_find_object(parent_id)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn permissions_are_respected() -> crate::Result {
+        let root = fixture()?;
+        let main = root.join("objects").join("main");
+        let extra = root.join("objects").join("extra");
+        let _env = Env::new()
+            .set("GIT_OBJECT_DIRECTORY", extra.to_str().expect("valid unicode"))
+            .set("GIT_ALTERNATE_OBJECT_DIRECTORIES", join(&[main]));
+
+        let repo = open(&root, Permission::Deny)?;
+        assert!(repo.objects.store_ref().extra_alternates().is_empty());
+        assert!(
+            repo.try_find_object(head_ref_target(&repo)?)?.is_none(),
+            "the environment is ignored, and the objects directory of the repository is empty"
+        );
+
+        assert!(
+            matches!(
+                open(&root, Permission::Forbid),
+                Err(git::open::Error::EnvironmentAccessDenied(_))
+            ),
+            "forbidden variables that are set fail the operation"
+        );
+        Ok(())
+    }
+}
+
+mod untrusted_object_directories {
+    use git_repository as git;
+    use git_sec::{Permission, Trust};
+
+    use crate::util::restricted;
+
+    fn open(opts: git::open::Options) -> crate::Result<git::Repository> {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        Ok(opts.open(repo_path)?.to_thread_local())
+    }
+
+    #[test]
+    fn the_objects_directory_inherits_the_trust_of_the_git_dir() -> crate::Result {
+        let repo = open(restricted().with(Trust::Reduced))?;
+        assert!(
+            repo.head_commit().is_err(),
+            "objects of untrusted repositories are not used by default"
+        );
+        let store = repo.objects.store_ref();
+        assert_eq!(store.metrics().untrusted_object_directories, 1);
+        let dirs = store.object_directories();
+        assert_eq!(dirs.len(), 1);
+        assert_eq!(dirs[0].trust, Trust::Reduced);
+        assert!(!dirs[0].used);
+
+        let repo = open(
+            restricted()
+                .with(Trust::Reduced)
+                .untrusted_object_directories(Permission::Allow),
+        )?;
+        repo.head_commit()?;
+
+        assert!(
+            open(
+                restricted()
+                    .with(Trust::Reduced)
+                    .untrusted_object_directories(Permission::Forbid)
+            )
+            .is_err(),
+            "the objects directory is classified when opening the repository, which fails"
+        );
+
+        let repo = open(restricted().with(Trust::Full))?;
+        repo.head_commit()?;
+        assert_eq!(repo.objects.store_ref().metrics().untrusted_object_directories, 0);
+        Ok(())
+    }
+}
+
 mod worktree {
     use git_repository::open;
 
@@ -326,3 +486,50 @@ mod worktree {
         Ok(())
     }
 }
+
+mod object_format {
+    use git_repository as git;
+
+    use crate::util::named_subrepo_opts;
+
+    fn open(name: &str) -> crate::Result<git::Repository> {
+        named_subrepo_opts("make_object_format_repos.sh", name, git::open::Options::isolated())
+    }
+
+    #[test]
+    fn sha1_is_passed_down_to_the_object_store() -> crate::Result {
+        let repo = open("sha1")?;
+        assert_eq!(repo.object_hash(), git_hash::Kind::Sha1);
+        assert_eq!(repo.objects.store_ref().object_hash(), git_hash::Kind::Sha1);
+        assert!(
+            repo.head_commit().is_ok(),
+            "objects can be read with the configured hash"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sha256_is_rejected_as_unsupported() -> crate::Result {
+        let err = open("sha256").unwrap_err();
+        let err = err
+            .downcast_ref::<git::open::Error>()
+            .expect("the open error is passed through");
+        assert!(
+            matches!(
+                err,
+                git::open::Error::Config(git::config::Error::UnsupportedObjectFormat { name }) if name == "sha256"
+            ),
+            "{:?}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn object_format_extension_is_ignored_without_repository_format_version_1() -> crate::Result {
+        let repo = open("sha256-without-format-version")?;
+        assert_eq!(repo.object_hash(), git_hash::Kind::Sha1);
+        assert_eq!(repo.objects.store_ref().object_hash(), git_hash::Kind::Sha1);
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{named_subrepo_opts, restricted};
+
+#[test]
+fn non_shallow_repositories_have_no_shallow_commits() -> crate::Result {
+    let repo = named_subrepo_opts("make_shallow_repo.sh", "base", restricted())?;
+    assert!(!repo.is_shallow());
+    assert_eq!(repo.shallow_commits()?, None);
+    Ok(())
+}
+
+#[test]
+fn shallow_commits_are_parsed() -> crate::Result {
+    let repo = named_subrepo_opts("make_shallow_repo.sh", "shallow", restricted())?;
+    assert!(repo.is_shallow());
+    let head = repo.head_commit()?;
+    let parent = head.parent_ids().next().expect("second commit is present");
+    assert_eq!(
+        repo.shallow_commits()?,
+        Some(vec![parent.detach()]),
+        "the parent of HEAD is the only commit at the boundary"
+    );
+    Ok(())
+}
+
+mod traverse {
+    use crate::{named_subrepo_opts, restricted};
+
+    #[test]
+    fn boundary_commits_are_treated_as_parentless() -> crate::Result {
+        let repo = named_subrepo_opts("make_shallow_repo.sh", "shallow", restricted())?;
+        let head = repo.head_id()?;
+        let mut walk = head.ancestors().all()?;
+        let messages = walk
+            .by_ref()
+            .map(|id| -> crate::Result<_> { Ok(id?.object()?.into_commit().message_raw()?.to_owned()) })
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            messages,
+            ["c4\n", "c3\n"],
+            "only the commits present in the clone are returned"
+        );
+        assert_eq!(
+            walk.is_shallow,
+            Some(true),
+            "the traversal stopped at the shallow boundary"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn non_shallow_traversals_are_not_clamped() -> crate::Result {
+        let repo = named_subrepo_opts("make_shallow_repo.sh", "base", restricted())?;
+        let mut walk = repo.head_id()?.ancestors().all()?.error_on_missing_commit();
+        assert_eq!(walk.by_ref().count(), 4);
+        assert_eq!(walk.is_shallow, Some(false));
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_commits_stop_at_the_boundary_as_well() -> crate::Result {
+        let repo = named_subrepo_opts("make_shallow_repo.sh", "shallow", restricted())?;
+        let head = repo.head_commit()?;
+        let parent = head.parent_ids().next().expect("present");
+        let walk = repo.rev_walk(Some(head.id)).hide(Some(parent));
+        let ids = walk.all()?.error_on_missing_commit().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            ids,
+            [head.id],
+            "hiding the boundary commit doesn't fail while looking for its parents"
+        );
+        Ok(())
+    }
+}
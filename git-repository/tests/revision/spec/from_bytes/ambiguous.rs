@@ -1,7 +1,7 @@
 use git_repository::{
     prelude::{ObjectIdExt, RevSpecExt},
     revision::{
-        spec::parse::{Options, RefsHint},
+        spec::parse::{AmbiguousRefsHint, Error, Options, RefsHint},
         Spec,
     },
 };
@@ -146,6 +146,8 @@ fn opts_ref_hint(hint: RefsHint) -> Options {
     Options {
         refs_hint: hint,
         object_kind_hint: None,
+        allow_unverified_full_hex_ids: false,
+        ambiguous_refs_hint: Default::default(),
     }
 }
 
@@ -277,3 +279,117 @@ fn repository_local_disambiguation_hints_are_overridden_by_specific_ones() {
         "spec overrides overrule the configuration value, which makes this particular object ambiguous between tree and tag"
     );
 }
+
+mod refs {
+    use super::*;
+
+    fn ambiguous_names(spec: &Spec<'_>) -> Vec<(String, Vec<String>)> {
+        spec.ambiguous_refs()
+            .iter()
+            .map(|r| {
+                (
+                    r.name.to_string(),
+                    r.candidates.iter().map(|name| name.as_bstr().to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tags_take_precedence_over_branches_of_the_same_name_and_the_ambiguity_is_recorded() {
+        let repo = repo("ambiguous_branch_and_tag").unwrap();
+        let spec = rev_parse("v1.2", &repo).unwrap();
+        assert_eq!(
+            spec.first_reference().map(|r| r.name.as_bstr().to_string()),
+            Some("refs/tags/v1.2".into())
+        );
+        assert_eq!(
+            ambiguous_names(&spec),
+            vec![(
+                "v1.2".to_string(),
+                vec!["refs/tags/v1.2".to_string(), "refs/heads/v1.2".into()]
+            )]
+        );
+
+        for unambiguous in ["heads/v1.2", "main"] {
+            let spec = rev_parse(unambiguous, &repo).unwrap();
+            assert!(spec.ambiguous_refs().is_empty(), "{unambiguous}");
+        }
+
+        let spec = rev_parse("v1.2..heads/v1.2", &repo).unwrap();
+        assert_eq!(
+            ambiguous_names(&spec)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            ["v1.2"],
+            "only the first name of the range is ambiguous"
+        );
+
+        let spec = repo.rev_parse_tips(["heads/v1.2", "v1.2"]).unwrap();
+        assert_eq!(
+            spec.ambiguous_refs().len(),
+            1,
+            "ambiguities of all specs are retained when combining them into tips"
+        );
+    }
+
+    #[test]
+    fn ambiguity_can_be_ignored_or_turned_into_an_error() {
+        let repo = repo("ambiguous_branch_and_tag").unwrap();
+        let spec = parse_spec_opts(
+            "v1.2",
+            &repo,
+            Options {
+                ambiguous_refs_hint: AmbiguousRefsHint::Ignore,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            spec.first_reference().map(|r| r.name.as_bstr().to_string()),
+            Some("refs/tags/v1.2".into()),
+            "the precedence is the same"
+        );
+        assert!(spec.ambiguous_refs().is_empty(), "but it's not checked for ambiguity");
+
+        let err = parse_spec_no_baseline_opts(
+            "v1.2",
+            &repo,
+            Options {
+                ambiguous_refs_hint: AmbiguousRefsHint::Fail,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::AmbiguousRefName { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Refname \"v1.2\" is ambiguous. Candidates are:\n\trefs/tags/v1.2\n\trefs/heads/v1.2"
+        );
+
+        assert!(
+            parse_spec_no_baseline_opts(
+                "heads/v1.2",
+                &repo,
+                Options {
+                    ambiguous_refs_hint: AmbiguousRefsHint::Fail,
+                    ..Default::default()
+                },
+            )
+            .is_ok(),
+            "unambiguous names are fine"
+        );
+    }
+
+    #[test]
+    fn core_warn_ambiguous_refs_can_turn_off_the_ambiguity_check() {
+        let repo = repo("ambiguous_branch_and_tag_without_warnings").unwrap();
+        let spec = repo.rev_parse("v1.2").unwrap();
+        assert_eq!(
+            spec.first_reference().map(|r| r.name.as_bstr().to_string()),
+            Some("refs/tags/v1.2".into())
+        );
+        assert!(spec.ambiguous_refs().is_empty());
+    }
+}
@@ -132,3 +132,40 @@ fn empty_tree_as_full_name() {
         Spec::from_id(hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbee4904").attach(&repo))
     );
 }
+
+#[test]
+fn full_hex_ids_of_missing_objects_resolve_unverified_on_request() {
+    let repo = repo("complex_graph").unwrap();
+    let missing = "1111111111111111111111111111111111111111";
+    assert!(matches!(
+        parse_spec_no_baseline(missing, &repo).unwrap_err(),
+        git_repository::revision::spec::parse::Error::Multi { current, .. }
+            if matches!(current.downcast_ref(), Some(git_repository::revision::spec::parse::Error::PrefixNotFound { .. }))
+    ));
+
+    let spec = repo.rev_parse_allow_unverified(missing).unwrap();
+    assert_eq!(*spec, git_revision::Spec::Include(hex_to_id(missing)));
+    assert!(!spec.is_verified());
+
+    let spec = repo
+        .rev_parse_allow_unverified(format!("main..{}", missing).as_str())
+        .unwrap();
+    assert!(!spec.is_verified(), "a single missing object suffices");
+
+    assert!(
+        repo.rev_parse_allow_unverified("4b825dc642cb6eb9a060e54bf8d69288fbee4904")
+            .unwrap()
+            .is_verified(),
+        "the empty tree is always known"
+    );
+    assert!(repo.rev_parse_allow_unverified("main").unwrap().is_verified());
+    assert!(
+        repo.rev_parse_allow_unverified("1111111").is_err(),
+        "abbreviated ids still need to be found"
+    );
+    assert!(
+        repo.rev_parse_allow_unverified(format!("{}^{{tree}}", missing).as_str())
+            .is_err(),
+        "transformations need the object"
+    );
+}
@@ -83,3 +83,31 @@ fn by_date_is_planned_until_git_date_crate_is_implements_parsing() {
         Error::Planned { .. }
     ));
 }
+
+mod stash {
+    use git_repository as git;
+
+    fn baseline(repo: &git::Repository) -> Vec<(String, git::ObjectId)> {
+        std::fs::read_to_string(repo.work_dir().expect("non-bare").join("rev-parse.baseline"))
+            .expect("baseline present")
+            .lines()
+            .map(|line| {
+                let (spec, hex) = line.split_once(' ').expect("spec and id");
+                (
+                    spec.to_owned(),
+                    git::ObjectId::from_hex(hex.as_bytes()).expect("valid id"),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn entries_and_their_parents_resolve_like_git() -> crate::Result {
+        let repo = crate::named_repo("make_stash_repo.sh")?;
+        for (spec, expected) in baseline(&repo) {
+            let actual = repo.rev_parse_single(spec.as_str())?;
+            assert_eq!(actual, expected, "{}", spec);
+        }
+        Ok(())
+    }
+}
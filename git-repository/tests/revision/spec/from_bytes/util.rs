@@ -28,7 +28,9 @@ static BASELINE: Lazy<HashMap<PathBuf, HashMap<BString, Option<git_revision::Spe
             Kind::ExcludeReachable | Kind::IncludeReachable => 1,
             Kind::RangeBetween => 2,
             Kind::ReachableToMergeBase => 3,
-            Kind::IncludeReachableFromParents | Kind::ExcludeReachableFromParents => return None,
+            Kind::IncludeReachableFromParents | Kind::ExcludeReachableFromParents | Kind::IncludeAndExcludeTips => {
+                return None
+            }
         })
     }
     fn object_id_of_next(lines: &mut std::iter::Peekable<bstr::Lines<'_>>) -> git_hash::ObjectId {
@@ -169,13 +171,14 @@ fn compare_with_baseline(
     spec: &str,
     expectation: BaselineExpectation,
 ) {
-    let actual = res.as_deref().ok().copied();
+    let actual = res.as_deref().ok().cloned();
     let spec: BString = spec.into();
-    let expected = *BASELINE
+    let expected = BASELINE
         .get(repo.work_dir().unwrap_or_else(|| repo.git_dir()))
         .unwrap_or_else(|| panic!("No baseline for {:?}", repo))
         .get(&spec)
-        .unwrap_or_else(|| panic!("'{}' revspec not found in git baseline", spec));
+        .unwrap_or_else(|| panic!("'{}' revspec not found in git baseline", spec))
+        .clone();
     match expectation {
         BaselineExpectation::Same => {
             assert_eq!(
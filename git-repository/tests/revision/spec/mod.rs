@@ -1 +1,2 @@
 mod from_bytes;
+mod tips;
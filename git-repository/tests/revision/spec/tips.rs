@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use git_repository as git;
+
+use crate::revision::spec::from_bytes::repo;
+
+fn id(repo: &git::Repository, spec: &str) -> git_hash::ObjectId {
+    repo.rev_parse_single(spec).expect("valid spec").detach()
+}
+
+fn tips(include: Vec<git_hash::ObjectId>, exclude: Vec<git_hash::ObjectId>) -> git_revision::Spec {
+    git_revision::Spec::Tips { include, exclude }
+}
+
+#[test]
+fn multiple_specs_are_combined_into_included_and_excluded_tips() -> crate::Result {
+    let repo = &repo("complex_graph")?;
+    let (a, b, c, d) = (id(repo, "a"), id(repo, "b"), id(repo, "c"), id(repo, "d"));
+
+    assert_eq!(repo.rev_parse_tips(["a", "^b"])?.detach(), tips(vec![a], vec![b]));
+    assert_eq!(
+        repo.rev_parse_tips(["b..a", "c", "^d"])?.detach(),
+        tips(vec![a, c], vec![b, d])
+    );
+    assert_eq!(repo.rev_parse_tips(Vec::<&str>::new())?.detach(), tips(vec![], vec![]));
+    Ok(())
+}
+
+#[test]
+fn not_inverts_all_following_specs() -> crate::Result {
+    let repo = &repo("complex_graph")?;
+    let (a, b, c, d) = (id(repo, "a"), id(repo, "b"), id(repo, "c"), id(repo, "d"));
+
+    assert_eq!(
+        repo.rev_parse_tips(["a", "--not", "b", "^c", "--not", "d"])?.detach(),
+        tips(vec![a, c, d], vec![b])
+    );
+    assert_eq!(
+        repo.rev_parse_tips(["a", "--not", "b"])?.detach(),
+        repo.rev_parse_tips(["a", "^b"])?.detach()
+    );
+    Ok(())
+}
+
+#[test]
+fn parents_are_looked_up_but_merge_bases_are_unsupported() -> crate::Result {
+    let repo = &repo("complex_graph")?;
+    let (a, a1, a2) = (id(repo, "a"), id(repo, "a^1"), id(repo, "a^2"));
+
+    assert_eq!(repo.rev_parse("a^@")?.into_tips()?.detach(), tips(vec![a1, a2], vec![]));
+    assert_eq!(
+        repo.rev_parse("a^!")?.into_tips()?.detach(),
+        tips(vec![a], vec![a1, a2])
+    );
+    assert!(matches!(
+        repo.rev_parse_tips(["a...b"]).unwrap_err(),
+        git::revision::spec::tips::Error::MergeBaseUnsupported { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn rev_walk_excludes_commits_reachable_from_excluded_tips() -> crate::Result {
+    let repo = &repo("complex_graph")?;
+    let reachable_from = |spec: &str| -> crate::Result<HashSet<git_hash::ObjectId>> {
+        Ok(repo
+            .rev_parse_single(spec)?
+            .ancestors()
+            .all()?
+            .map(|id| id.map(|id| id.detach()))
+            .collect::<Result<_, _>>()?)
+    };
+
+    let actual = repo
+        .rev_parse_tips(["a", "^b"])?
+        .rev_walk()?
+        .all()?
+        .map(|id| id.map(|id| id.detach()))
+        .collect::<Result<HashSet<_>, _>>()?;
+    let expected: HashSet<_> = reachable_from("a")?
+        .difference(&reachable_from("b")?)
+        .copied()
+        .collect();
+    assert!(
+        !expected.is_empty(),
+        "the graph must have commits reachable only from a"
+    );
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn rev_walk_keeps_hiding_commits_after_a_missing_hidden_commit() -> crate::Result {
+    let repo = &repo("hidden_tip_with_missing_parent")?;
+    let (w, x, y2) = (id(repo, "w"), id(repo, "x"), id(repo, "y2"));
+
+    let actual = repo
+        .rev_walk(Some(w))
+        .hide([x, y2])
+        .all()?
+        .map(|id| id.map(|id| id.detach()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        actual,
+        vec![w],
+        "the parent of x is missing, which must not prevent y1 from being hidden as well"
+    );
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use git_hash::ObjectId;
+
 use crate::Spec;
 
 /// How to interpret a revision specification, or `revspec`.
@@ -17,6 +19,11 @@ pub enum Kind {
     IncludeReachableFromParents,
     /// Exclude every commit of all parents of `a`, but not `a` itself. Example: `a^!`.
     ExcludeReachableFromParents,
+    /// Include commits reachable from a set of tips, but exclude those reachable from another set of tips.
+    /// Example: `a b ^c` or `a b --not c` when passing multiple specifications.
+    ///
+    /// Note that this kind is never produced when parsing a single specification.
+    IncludeAndExcludeTips,
 }
 
 impl Default for Kind {
@@ -35,8 +42,24 @@ impl Spec {
             Spec::Merge { .. } => Kind::ReachableToMergeBase,
             Spec::IncludeOnlyParents { .. } => Kind::IncludeReachableFromParents,
             Spec::ExcludeParents { .. } => Kind::ExcludeReachableFromParents,
+            Spec::Tips { .. } => Kind::IncludeAndExcludeTips,
         }
     }
+
+    /// Return the tips whose reachable commits are included and excluded respectively as `(include, exclude)`,
+    /// or `None` if this isn't possible without access to the commit graph.
+    ///
+    /// The latter is the case for [merges][Spec::Merge] which require a merge-base, as well as
+    /// for specifications [including][Spec::IncludeOnlyParents] or [excluding][Spec::ExcludeParents] parents.
+    pub fn to_tips(&self) -> Option<(Vec<ObjectId>, Vec<ObjectId>)> {
+        Some(match self {
+            Spec::Include(oid) => (vec![*oid], Vec::new()),
+            Spec::Exclude(oid) => (Vec::new(), vec![*oid]),
+            Spec::Range { from, to } => (vec![*to], vec![*from]),
+            Spec::Tips { include, exclude } => (include.clone(), exclude.clone()),
+            Spec::Merge { .. } | Spec::IncludeOnlyParents(_) | Spec::ExcludeParents(_) => return None,
+        })
+    }
 }
 
 mod _impls {
@@ -53,6 +76,18 @@ mod _impls {
                 Spec::Merge { theirs, ours } => write!(f, "{theirs}...{ours}"),
                 Spec::IncludeOnlyParents(from_exclusive) => write!(f, "{from_exclusive}^@"),
                 Spec::ExcludeParents(oid) => write!(f, "{oid}^!"),
+                Spec::Tips { include, exclude } => {
+                    let mut sep = "";
+                    for oid in include {
+                        write!(f, "{sep}{oid}")?;
+                        sep = " ";
+                    }
+                    for oid in exclude {
+                        write!(f, "{sep}^{oid}")?;
+                        sep = " ";
+                    }
+                    Ok(())
+                }
             }
         }
     }
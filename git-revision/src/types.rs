@@ -2,7 +2,7 @@
 ///
 /// Note that all [object ids][git_hash::ObjectId] should be a committish, but don't have to be.
 /// Unless the field name contains `_exclusive`, the respective objects are included in the set.
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub enum Spec {
     /// Include commits reachable from this revision, i.e. `a` and its ancestors.
@@ -45,4 +45,14 @@ pub enum Spec {
         /// Exclude the parents of this object, but not the object itself.
         git_hash::ObjectId,
     ),
+    /// Include commits reachable from any of the `include` tips, but not those reachable from any of the `exclude` tips.
+    /// Example: `a b ^c` or `a --not c`, as multiple specifications passed to `git rev-list`.
+    ///
+    /// The equivalent to [crate::spec::Kind::IncludeAndExcludeTips], but with data.
+    Tips {
+        /// The tips from which to include all reachable commits.
+        include: Vec<git_hash::ObjectId>,
+        /// The tips from which to exclude all reachable commits.
+        exclude: Vec<git_hash::ObjectId>,
+    },
 }
@@ -63,3 +63,23 @@ fn exclude_parents() {
         "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa^!"
     );
 }
+
+#[test]
+fn tips() {
+    assert_eq!(
+        git_revision::Spec::Tips {
+            include: vec![oid(), oid2()],
+            exclude: vec![oid()]
+        }
+        .to_string(),
+        "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb ^aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+    );
+    assert_eq!(
+        git_revision::Spec::Tips {
+            include: Vec::new(),
+            exclude: vec![oid2()]
+        }
+        .to_string(),
+        "^bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"
+    );
+}
@@ -42,7 +42,7 @@ pub enum Sorting {
     /// As the query is usually repeated with different cutoff dates, this search mode benefits greatly from an object cache.
     ByCommitTimeNewestFirstCutoffOlderThan {
         /// The amount of seconds since unix epoch, the same value obtained by any `git_date::Time` structure and the way git counts time.
-        time_in_seconds_since_epoch: u32,
+        time_in_seconds_since_epoch: i64,
     },
 }
 
@@ -79,7 +79,7 @@ pub mod ancestors {
         ObjectDecode(#[from] git_object::decode::Error),
     }
 
-    type TimeInSeconds = u32;
+    type TimeInSeconds = i64;
 
     /// The state used and potentially shared by multiple graph traversals.
     #[derive(Default, Clone)]
@@ -251,7 +251,7 @@ pub mod ancestors {
 
     impl Sorting {
         /// If not topo sort, provide the cutoff date if present.
-        fn cutoff_time(&self) -> Option<u32> {
+        fn cutoff_time(&self) -> Option<TimeInSeconds> {
             match self {
                 Sorting::ByCommitTimeNewestFirstCutoffOlderThan {
                     time_in_seconds_since_epoch,
@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 
 use git_hash::ObjectId;
+use git_object::bstr::{BStr, BString, ByteVec};
 
 /// The error is part of the item returned by the [`traverse()`][impl_::traverse()] function.
 #[derive(Debug, thiserror::Error)]
@@ -12,22 +13,83 @@ pub enum Error {
     Cancelled,
     #[error(transparent)]
     ObjectDecode(#[from] git_object::decode::Error),
+    #[error("The tree at '{path}' is nested deeper than the maximum of {max} levels")]
+    DepthLimitExceeded { path: BString, max: usize },
+    #[error("The path of the tree at '{path}' is longer than the maximum of {max} bytes")]
+    PathLengthLimitExceeded { path: BString, max: usize },
+}
+
+/// Limits which are enforced before descending into a tree, to protect against maliciously crafted trees that are nested
+/// extremely deeply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum amount of components in the path of a tree to descend into, with trees in the root tree having a depth of 1.
+    pub max_depth: usize,
+    /// The maximum length in bytes of the path of a tree to descend into, including the `/` separating its components.
+    pub max_path_len: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_depth: 4096,
+            max_path_len: 64 * 1024,
+        }
+    }
+}
+
+impl Limits {
+    fn check(&self, depth: usize, path: &BStr) -> Result<(), Error> {
+        if depth > self.max_depth {
+            return Err(Error::DepthLimitExceeded {
+                path: path.to_owned(),
+                max: self.max_depth,
+            });
+        }
+        if path.len() > self.max_path_len {
+            return Err(Error::PathLengthLimitExceeded {
+                path: path.to_owned(),
+                max: self.max_path_len,
+            });
+        }
+        Ok(())
+    }
 }
 
 /// The state used and potentially shared by multiple tree traversals.
 #[derive(Default, Clone)]
 pub struct State {
-    next: VecDeque<ObjectId>,
+    /// The trees to visit next along with their depth and path.
+    next: VecDeque<(ObjectId, usize, BString)>,
+    /// The depth of the tree whose entries are currently visited.
+    depth: usize,
+    /// The path of the tree whose entries are currently visited.
+    path: BString,
     buf: Vec<u8>,
+    /// The limits to enforce during traversal.
+    pub limits: Limits,
 }
 
 impl State {
     fn clear(&mut self) {
         self.next.clear();
+        self.depth = 0;
+        self.path.clear();
         self.buf.clear();
     }
 }
 
+/// Return the path of an entry named `filename` in the tree at `parent`.
+fn child_path(parent: &BStr, filename: &BStr) -> BString {
+    let mut path = BString::from(Vec::with_capacity(parent.len() + 1 + filename.len()));
+    if !parent.is_empty() {
+        path.push_str(parent);
+        path.push_byte(b'/');
+    }
+    path.push_str(filename);
+    path
+}
+
 pub(crate) mod impl_ {
     use std::borrow::BorrowMut;
 
@@ -49,6 +111,9 @@ pub(crate) mod impl_ {
     ///    be considered an errors as all objects in the tree DAG should be present in the database. Hence [`Error::NotFound`] should
     ///    be escalated into a more specific error if its encountered by the caller.
     /// * `delegate` - A way to observe entries and control the iteration while allowing the optimizer to let you pay only for what you use.
+    ///
+    /// The traversal uses a queue instead of recursion, and fails if a tree to descend into exceeds the [limits][super::Limits]
+    /// configured in `state`.
     pub fn traverse<StateMut, Find, V>(
         root: TreeRefIter<'_>,
         mut state: StateMut,
@@ -74,15 +139,24 @@ pub(crate) mod impl_ {
                         match action {
                             Skip => {}
                             Continue => {
+                                let (depth, path) =
+                                    (state.depth + 1, super::child_path(state.path.as_ref(), entry.filename));
+                                state.limits.check(depth, path.as_ref())?;
                                 delegate.pop_path_component();
                                 delegate.push_back_tracked_path_component(entry.filename);
-                                state.next.push_back(entry.oid.to_owned())
+                                state.next.push_back((entry.oid.to_owned(), depth, path))
                             }
                             Cancel => {
                                 return Err(Error::Cancelled);
                             }
                         }
                     }
+                    EntryMode::Commit => {
+                        delegate.push_path_component(entry.filename);
+                        if delegate.visit_gitlink(&entry).cancelled() {
+                            return Err(Error::Cancelled);
+                        }
+                    }
                     _non_tree => {
                         delegate.push_path_component(entry.filename);
                         if delegate.visit_nontree(&entry).cancelled() {
@@ -93,7 +167,9 @@ pub(crate) mod impl_ {
                 delegate.pop_path_component();
             }
             match state.next.pop_front() {
-                Some(oid) => {
+                Some((oid, depth, path)) => {
+                    state.depth = depth;
+                    state.path = path;
                     delegate.pop_front_tracked_path_and_set_current();
                     match find(&oid, &mut state.buf) {
                         Some(tree_iter) => tree = tree_iter,
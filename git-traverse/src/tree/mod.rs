@@ -25,6 +25,15 @@ pub trait Visit {
     ///
     /// The implementation may use the current path to learn where in the tree the change is located.
     fn visit_nontree(&mut self, entry: &git_object::tree::EntryRef<'_>) -> visit::Action;
+
+    /// Observe a tree entry that is a [gitlink][git_object::tree::EntryKind::Gitlink], the commit of a submodule, and return
+    /// an instruction whether to continue or not. Its object lives in another repository and can't be looked up in ours.
+    /// [`Action::Skip`][visit::Action::Skip] has no effect here.
+    ///
+    /// By default, it is passed to [`visit_nontree()`][Visit::visit_nontree()].
+    fn visit_gitlink(&mut self, entry: &git_object::tree::EntryRef<'_>) -> visit::Action {
+        self.visit_nontree(entry)
+    }
 }
 
 /// A [Visit][Visit] implementation to record every observed change and keep track of the changed paths.
@@ -36,6 +45,19 @@ pub struct Recorder {
     pub records: Vec<recorder::Entry>,
 }
 
+/// A [Visit][Visit] implementation to find all paths at which the objects with any of the given ids are located.
+///
+/// Paths are only allocated for entries that match, which keeps traversing large trees cheap.
+#[derive(Clone, Debug, Default)]
+pub struct PathFinder {
+    path_deque: VecDeque<BString>,
+    path: BString,
+    /// The ids to look for, sorted for fast lookup.
+    ids: Vec<git_hash::ObjectId>,
+    /// The paths found for each id in `ids`, at the same index.
+    paths: Vec<Vec<BString>>,
+}
+
 ///
 pub mod visit {
     /// What to do after an entry was [recorded][super::Visit::visit_tree()].
@@ -60,6 +82,9 @@ pub mod visit {
 ///
 pub mod recorder;
 
+///
+pub mod path_finder;
+
 ///
 pub mod breadthfirst;
 pub use breadthfirst::impl_::traverse as breadthfirst;
@@ -0,0 +1,89 @@
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    tree,
+};
+
+use crate::tree::{visit::Action, PathFinder, Visit};
+
+impl PathFinder {
+    /// Create a new instance to find the paths of all objects with the given `ids`.
+    pub fn new(ids: impl IntoIterator<Item = ObjectId>) -> Self {
+        let mut ids: Vec<_> = ids.into_iter().collect();
+        ids.sort();
+        ids.dedup();
+        PathFinder {
+            paths: vec![Vec::new(); ids.len()],
+            ids,
+            ..Default::default()
+        }
+    }
+
+    /// Return the paths at which `id` was found, in traversal order, or `None` if `id` wasn't one of the ids we were looking for.
+    ///
+    /// The returned list is empty if `id` wasn't found in the traversed tree.
+    pub fn paths_of(&self, id: &git_hash::oid) -> Option<&[BString]> {
+        self.ids
+            .binary_search_by(|probe| probe.as_ref().cmp(id))
+            .ok()
+            .map(|idx| self.paths[idx].as_slice())
+    }
+
+    /// Consume this instance and return each id we were looking for along with all paths it was found at, sorted by id.
+    pub fn into_paths(self) -> Vec<(ObjectId, Vec<BString>)> {
+        self.ids.into_iter().zip(self.paths).collect()
+    }
+
+    fn record_if_wanted(&mut self, entry: &tree::EntryRef<'_>) {
+        if let Ok(idx) = self.ids.binary_search_by(|probe| probe.as_ref().cmp(entry.oid)) {
+            self.paths[idx].push(self.path.clone());
+        }
+    }
+
+    fn pop_element(&mut self) {
+        if let Some(pos) = self.path.rfind_byte(b'/') {
+            self.path.resize(pos, 0);
+        } else {
+            self.path.clear();
+        }
+    }
+
+    fn push_element(&mut self, name: &BStr) {
+        if !self.path.is_empty() {
+            self.path.push(b'/');
+        }
+        self.path.push_str(name);
+    }
+}
+
+impl Visit for PathFinder {
+    fn pop_front_tracked_path_and_set_current(&mut self) {
+        self.path = self
+            .path_deque
+            .pop_front()
+            .expect("every call is matched with push_tracked_path_component");
+    }
+
+    fn push_back_tracked_path_component(&mut self, component: &BStr) {
+        self.push_element(component);
+        self.path_deque.push_back(self.path.clone());
+    }
+
+    fn push_path_component(&mut self, component: &BStr) {
+        self.push_element(component);
+    }
+
+    fn pop_path_component(&mut self) {
+        self.pop_element();
+    }
+
+    fn visit_tree(&mut self, entry: &tree::EntryRef<'_>) -> Action {
+        self.record_if_wanted(entry);
+        Action::Continue
+    }
+
+    fn visit_nontree(&mut self, entry: &tree::EntryRef<'_>) -> Action {
+        self.record_if_wanted(entry);
+        Action::Continue
+    }
+}
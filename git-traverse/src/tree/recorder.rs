@@ -21,6 +21,11 @@ pub struct Entry {
 }
 
 impl Entry {
+    /// The kind of object the entry points to, which for [gitlinks][tree::EntryKind::Gitlink] isn't in our object database.
+    pub fn kind(&self) -> tree::EntryKind {
+        self.mode.kind()
+    }
+
     fn new(entry: &tree::EntryRef<'_>, filepath: BString) -> Self {
         Entry {
             filepath,
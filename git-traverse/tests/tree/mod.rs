@@ -95,3 +95,243 @@ fn basic_nesting() -> crate::Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn gitlinks_are_passed_to_their_own_method() -> crate::Result<()> {
+    use git_object::{
+        tree::{Entry, EntryKind, EntryMode, EntryRef},
+        Tree, TreeRefIter, WriteTo,
+    };
+
+    #[derive(Default)]
+    struct Gitlinks {
+        recorder: tree::Recorder,
+        gitlinks: Vec<git_hash::ObjectId>,
+    }
+
+    impl tree::Visit for Gitlinks {
+        fn pop_front_tracked_path_and_set_current(&mut self) {
+            self.recorder.pop_front_tracked_path_and_set_current()
+        }
+        fn push_back_tracked_path_component(&mut self, component: &git_object::bstr::BStr) {
+            self.recorder.push_back_tracked_path_component(component)
+        }
+        fn push_path_component(&mut self, component: &git_object::bstr::BStr) {
+            self.recorder.push_path_component(component)
+        }
+        fn pop_path_component(&mut self) {
+            self.recorder.pop_path_component()
+        }
+        fn visit_tree(&mut self, entry: &EntryRef<'_>) -> tree::visit::Action {
+            self.recorder.visit_tree(entry)
+        }
+        fn visit_nontree(&mut self, entry: &EntryRef<'_>) -> tree::visit::Action {
+            assert!(!entry.mode.is_commit(), "gitlinks are never passed here");
+            self.recorder.visit_nontree(entry)
+        }
+        fn visit_gitlink(&mut self, entry: &EntryRef<'_>) -> tree::visit::Action {
+            self.gitlinks.push(entry.oid.to_owned());
+            tree::visit::Action::Continue
+        }
+    }
+
+    let submodule_commit = hex_to_id("1111111111111111111111111111111111111111");
+    let tree = Tree {
+        entries: vec![
+            Entry {
+                mode: EntryMode::Blob,
+                filename: "file".into(),
+                oid: hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+            },
+            Entry {
+                mode: EntryMode::Commit,
+                filename: "submodule".into(),
+                oid: submodule_commit,
+            },
+        ],
+    };
+    let mut data = Vec::new();
+    tree.write_to(&mut data)?;
+    fn no_trees<'a>(_id: &git_hash::oid, _buf: &'a mut Vec<u8>) -> Option<TreeRefIter<'a>> {
+        unreachable!("there are no nested trees")
+    }
+
+    let mut delegate = Gitlinks::default();
+    git_traverse::tree::breadthfirst(
+        TreeRefIter::from_bytes(&data),
+        tree::breadthfirst::State::default(),
+        no_trees,
+        &mut delegate,
+    )?;
+    assert_eq!(delegate.gitlinks, vec![submodule_commit]);
+    assert_eq!(delegate.recorder.records.len(), 1, "only the file was recorded");
+
+    let mut recorder = tree::Recorder::default();
+    git_traverse::tree::breadthfirst(
+        TreeRefIter::from_bytes(&data),
+        tree::breadthfirst::State::default(),
+        no_trees,
+        &mut recorder,
+    )?;
+    assert_eq!(
+        recorder.records.iter().map(|e| e.kind()).collect::<Vec<_>>(),
+        vec![EntryKind::Blob, EntryKind::Gitlink],
+        "by default, gitlinks are passed to visit_nontree() and can be told apart by their kind"
+    );
+    Ok(())
+}
+
+#[test]
+fn path_finder() -> crate::Result<()> {
+    let db = db()?;
+    let mut buf = Vec::new();
+    let mut buf2 = Vec::new();
+    let mut commit = db
+        .find_commit_iter(hex_to_id("85df34aa34848b8138b2b3dcff5fb5c2b734e0ce"), &mut buf)?
+        .0;
+    let empty_blob = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    let subtree = hex_to_id("5805b676e247eb9a8046ad0c4d249cd2fb2513df");
+    let missing = hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+    let mut finder = tree::PathFinder::new([missing, empty_blob, subtree]);
+    git_traverse::tree::breadthfirst(
+        db.find_tree_iter(commit.tree_id().expect("a tree is available in a commit"), &mut buf2)?
+            .0,
+        tree::breadthfirst::State::default(),
+        |oid, buf| db.find_tree_iter(oid, buf).ok().map(|t| t.0),
+        &mut finder,
+    )?;
+
+    assert_eq!(
+        finder.paths_of(&empty_blob).expect("queried"),
+        ["a", "b", "c", "d/a", "e/b", "f/c", "f/z", "f/d/x"]
+            .iter()
+            .map(|p| (*p).into())
+            .collect::<Vec<git_object::bstr::BString>>(),
+        "the same blob can be found at many paths"
+    );
+    assert_eq!(
+        finder.paths_of(&subtree).expect("queried"),
+        &["f/d".into()] as &[git_object::bstr::BString]
+    );
+    assert!(finder.paths_of(&missing).expect("queried").is_empty());
+    assert!(finder
+        .paths_of(&hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5392"))
+        .is_none());
+    assert_eq!(finder.into_paths().len(), 3);
+    Ok(())
+}
+
+mod limits {
+    use std::collections::HashMap;
+
+    use git_hash::ObjectId;
+    use git_object::{
+        bstr::BString,
+        tree::{Entry, EntryMode},
+        Tree, TreeRefIter, WriteTo,
+    };
+    use git_odb::Write;
+    use git_traverse::tree;
+
+    /// Synthesize `depth` trees named `name` nested in one another below a root tree, with the innermost one containing a file,
+    /// and return the id of the root tree along with the data of all trees by their id.
+    fn deeply_nested_trees(depth: usize, name: &str) -> crate::Result<(ObjectId, HashMap<ObjectId, Vec<u8>>)> {
+        let mut trees = HashMap::new();
+        let mut tree = Tree {
+            entries: vec![Entry {
+                mode: EntryMode::Blob,
+                filename: "file".into(),
+                oid: ObjectId::null(git_hash::Kind::Sha1),
+            }],
+        };
+        for _ in 0..depth {
+            let id = git_odb::sink(git_hash::Kind::Sha1).write(&tree)?;
+            let mut data = Vec::new();
+            tree.write_to(&mut data)?;
+            trees.insert(id, data);
+            tree = Tree {
+                entries: vec![Entry {
+                    mode: EntryMode::Tree,
+                    filename: name.into(),
+                    oid: id,
+                }],
+            };
+        }
+        let root_id = git_odb::sink(git_hash::Kind::Sha1).write(&tree)?;
+        let mut data = Vec::new();
+        tree.write_to(&mut data)?;
+        trees.insert(root_id, data);
+        Ok((root_id, trees))
+    }
+
+    fn traverse(
+        depth: usize,
+        name: &str,
+        state: tree::breadthfirst::State,
+    ) -> crate::Result<Result<tree::Recorder, tree::breadthfirst::Error>> {
+        let (root_id, trees) = deeply_nested_trees(depth, name)?;
+        let mut recorder = tree::Recorder::default();
+        let res = git_traverse::tree::breadthfirst(
+            TreeRefIter::from_bytes(&trees[&root_id]),
+            state,
+            |oid, buf| {
+                buf.clear();
+                buf.extend_from_slice(trees.get(&oid.to_owned())?);
+                Some(TreeRefIter::from_bytes(buf))
+            },
+            &mut recorder,
+        );
+        Ok(res.map(|()| recorder))
+    }
+
+    fn path(components: usize, name: &str) -> BString {
+        vec![name; components].join("/").into()
+    }
+
+    #[test]
+    fn trees_nested_up_to_the_maximum_depth_are_traversed() -> crate::Result {
+        let recorder = traverse(4096, "d", Default::default())?.expect("within limits");
+        assert_eq!(recorder.records.len(), 4096 + 1, "all trees and the file");
+        assert_eq!(
+            recorder.records.last().expect("present").filepath,
+            format!("{}/file", path(4096, "d"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn trees_nested_deeper_than_the_maximum_depth_fail_with_their_path() -> crate::Result {
+        match traverse(100_000, "d", Default::default())? {
+            Err(tree::breadthfirst::Error::DepthLimitExceeded { path: actual, max }) => {
+                assert_eq!(max, 4096);
+                assert_eq!(actual, path(4097, "d"), "the path of the first tree that is too deep");
+            }
+            _ => unreachable!("the default limits protect against extremely deep trees"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn limits_are_configurable() -> crate::Result {
+        let mut state = tree::breadthfirst::State::default();
+        state.limits.max_path_len = 10;
+        match traverse(3, "abc", state.clone())? {
+            Err(tree::breadthfirst::Error::PathLengthLimitExceeded { path: actual, max }) => {
+                assert_eq!(max, 10);
+                assert_eq!(actual, path(3, "abc"), "'abc/abc/abc' is 11 bytes long");
+            }
+            _ => unreachable!("the path of the innermost tree is too long"),
+        }
+
+        state.limits = tree::breadthfirst::Limits {
+            max_depth: 1,
+            max_path_len: usize::MAX,
+        };
+        assert!(matches!(
+            traverse(2, "d", state.clone())?,
+            Err(tree::breadthfirst::Error::DepthLimitExceeded { max: 1, .. })
+        ));
+        assert!(traverse(1, "d", state)?.is_ok());
+        Ok(())
+    }
+}
@@ -49,6 +49,9 @@ where
     #[cfg_attr(not(feature = "serde1"), allow(clippy::single_match))]
     match output_statistics {
         Some(OutputFormat::Human) => drop(print_human_output(&mut out, &stats)),
+        Some(OutputFormat::Porcelain { .. }) => {
+            anyhow::bail!("Porcelain output isn't supported for commit-graph statistics")
+        }
         #[cfg(feature = "serde1")]
         Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, &stats)?,
         _ => {}
@@ -23,7 +23,8 @@ pub fn estimate_hours(
             let change_in_minutes = (next
                 .time
                 .seconds_since_unix_epoch
-                .saturating_sub(cur.time.seconds_since_unix_epoch)) as f32
+                .saturating_sub(cur.time.seconds_since_unix_epoch)
+                .max(0)) as f32
                 / MINUTES_PER_HOUR;
             if change_in_minutes < MAX_COMMIT_DIFFERENCE_IN_MINUTES {
                 hours + change_in_minutes as f32 / MINUTES_PER_HOUR
@@ -115,6 +115,7 @@ where
                                 name,
                                 email,
                                 time: author.time,
+                                raw_time: None,
                             },
                         ));
                     }
@@ -240,6 +241,7 @@ where
                                                         }
                                                     }
                                                 },
+                                                SubmoduleChanged { .. } => {}
                                             }
                                             Ok::<_, Infallible>(Default::default())
                                         })?;
@@ -1,15 +1,22 @@
-use std::path::Path;
+use std::{path::Path, sync::atomic::AtomicBool};
 
 use git_repository as git;
 
-use crate::index::{parse_file, Options};
+use crate::{
+    bail_if_interrupted,
+    index::{parse_file, Options},
+};
 
 pub fn entries(
     index_path: impl AsRef<Path>,
     mut out: impl std::io::Write,
     Options { object_hash, format }: Options,
+    should_interrupt: &AtomicBool,
 ) -> anyhow::Result<()> {
     use crate::OutputFormat::*;
+    if let Porcelain { .. } = format {
+        anyhow::bail!("Porcelain output isn't supported for listing index entries");
+    }
     let file = parse_file(index_path, object_hash)?;
 
     #[cfg(feature = "serde1")]
@@ -19,8 +26,10 @@ pub fn entries(
 
     let mut entries = file.entries().iter().peekable();
     while let Some(entry) = entries.next() {
+        bail_if_interrupted(should_interrupt)?;
         match format {
             Human => to_human(&mut out, &file, entry)?,
+            Porcelain { .. } => unreachable!("rejected before parsing the index"),
             #[cfg(feature = "serde1")]
             Json => to_json(&mut out, &file, entry, entries.peek().is_none())?,
         }
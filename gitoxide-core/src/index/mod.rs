@@ -71,6 +71,9 @@ pub fn information(
         Human => {
             anyhow::bail!("Cannot print information using 'human' format.")
         }
+        Porcelain { .. } => {
+            anyhow::bail!("Cannot print information using 'porcelain' format.")
+        }
         #[cfg(feature = "serde1")]
         Json => {
             let info = information::Collection::try_from_file(parse_file(index_path, object_hash)?, extension_details)?;
@@ -32,9 +32,19 @@
 
 use std::str::FromStr;
 
+use git_repository as git;
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub enum OutputFormat {
     Human,
+    /// Stable, machine-readable output with one record per line and fields separated by tabs, in an order documented
+    /// by each command.
+    Porcelain {
+        /// If true, terminate records with NUL instead of a newline, which allows the last field to contain tabs and newlines.
+        ///
+        /// This is why fields that may contain those, like paths, are always last.
+        nul_terminated: bool,
+    },
     #[cfg(feature = "serde1")]
     Json,
 }
@@ -43,6 +53,8 @@ impl OutputFormat {
     pub fn variants() -> &'static [&'static str] {
         &[
             "human",
+            "porcelain",
+            "porcelain-z",
             #[cfg(feature = "serde1")]
             "json",
         ]
@@ -56,6 +68,8 @@ impl FromStr for OutputFormat {
         let s_lc = s.to_ascii_lowercase();
         Ok(match s_lc.as_str() {
             "human" => OutputFormat::Human,
+            "porcelain" => OutputFormat::Porcelain { nul_terminated: false },
+            "porcelain-z" => OutputFormat::Porcelain { nul_terminated: true },
             #[cfg(feature = "serde1")]
             "json" => OutputFormat::Json,
             _ => return Err(format!("Invalid output format: '{}'", s)),
@@ -63,6 +77,156 @@ impl FromStr for OutputFormat {
     }
 }
 
+/// Writes the records of [`OutputFormat::Porcelain`].
+pub(crate) struct Records<W> {
+    out: W,
+    terminator: u8,
+}
+
+impl<W: std::io::Write> Records<W> {
+    pub fn new(out: W, nul_terminated: bool) -> Self {
+        Records {
+            out,
+            terminator: if nul_terminated { 0 } else { b'\n' },
+        }
+    }
+
+    /// Write one record made of `fields`, separated by tabs.
+    pub fn write(&mut self, fields: &[&dyn std::fmt::Display]) -> std::io::Result<()> {
+        for (index, field) in fields.iter().enumerate() {
+            if index != 0 {
+                self.out.write_all(b"\t")?;
+            }
+            write!(self.out, "{}", field)?;
+        }
+        self.out.write_all(&[self.terminator])
+    }
+}
+
+/// The error returned by long-running operations if they noticed that they should stop early.
+///
+/// Use [`is_interrupted()`] to detect it, for instance to exit with a distinct exit code.
+#[derive(Debug, thiserror::Error)]
+#[error("Interrupted")]
+pub struct Interrupted;
+
+/// Return `true` if `err` or one of its sources is [`Interrupted`].
+pub fn is_interrupted(err: &anyhow::Error) -> bool {
+    err.chain().any(|err| err.is::<Interrupted>())
+}
+
+/// Fail with [`Interrupted`] if `should_interrupt` is set.
+pub(crate) fn bail_if_interrupted(should_interrupt: &std::sync::atomic::AtomicBool) -> Result<(), Interrupted> {
+    if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+        Err(Interrupted)
+    } else {
+        Ok(())
+    }
+}
+
+/// Call `f` with `out` and return its result, but if it fails while `format` is [`OutputFormat::Json`], first write the error
+/// to `out` as final JSON object `{"error": {"message": …, "kind": …, "sources": […]}}` so JSON consumers can parse failures too.
+///
+/// The `kind` is one of `not-found`, `promised`, `corrupt`, `limit-exceeded`, `io`, `invalid-spec`, `interrupted` or `other`.
+/// The error is returned nonetheless to be reported on stderr and to determine the exit code.
+#[cfg_attr(not(feature = "serde1"), allow(unused_variables))]
+pub fn with_json_error<W, T>(
+    format: OutputFormat,
+    mut out: W,
+    f: impl FnOnce(&mut W) -> anyhow::Result<T>,
+) -> anyhow::Result<T>
+where
+    W: std::io::Write,
+{
+    let res = f(&mut out);
+    #[cfg(feature = "serde1")]
+    if let (OutputFormat::Json, Err(err)) = (format, &res) {
+        #[derive(serde::Serialize)]
+        struct Error {
+            message: String,
+            kind: &'static str,
+            sources: Vec<String>,
+        }
+        #[derive(serde::Serialize)]
+        struct Report {
+            error: Error,
+        }
+        let report = Report {
+            error: Error {
+                message: err.to_string(),
+                kind: error_kind(err),
+                sources: err.chain().skip(1).map(ToString::to_string).collect(),
+            },
+        };
+        // The original error is more important than failing to report it.
+        serde_json::to_writer_pretty(&mut out, &report)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writeln!(out))
+            .ok();
+    }
+    res
+}
+
+/// Classify `err` by the first of its sources that we know, for consumption by machines.
+#[cfg(feature = "serde1")]
+fn error_kind(err: &anyhow::Error) -> &'static str {
+    for err in err.chain() {
+        if err.is::<Interrupted>() {
+            return "interrupted";
+        } else if let Some(err) = err.downcast_ref::<git::revision::spec::parse::Error>() {
+            return spec_error_kind(err);
+        } else if let Some(err) = err.downcast_ref::<git::revision::spec::parse::single::Error>() {
+            return match err {
+                git::revision::spec::parse::single::Error::Parse(err) => spec_error_kind(err),
+                git::revision::spec::parse::single::Error::RangedRev { .. } => "invalid-spec",
+            };
+        } else if let Some(err) = err.downcast_ref::<repository::revision::resolve::SingleError>() {
+            return match err {
+                repository::revision::resolve::SingleError::NotFound { .. } => "not-found",
+                repository::revision::resolve::SingleError::SpecCount { .. }
+                | repository::revision::resolve::SingleError::Range { .. } => "invalid-spec",
+            };
+        } else if let Some(err) = err.downcast_ref::<git::object::find::existing::Error>() {
+            return find_error_kind(err.kind());
+        } else if let Some(err) = err.downcast_ref::<git::object::find::Error>() {
+            return find_error_kind(err.kind());
+        } else if err.is::<std::io::Error>() {
+            return "io";
+        }
+    }
+    "other"
+}
+
+fn find_error_kind(kind: git::object::find::ErrorKind) -> &'static str {
+    use git::object::find::ErrorKind;
+    match kind {
+        ErrorKind::NotFound => "not-found",
+        ErrorKind::Promised => "promised",
+        ErrorKind::Corrupt => "corrupt",
+        ErrorKind::LimitExceeded => "limit-exceeded",
+        ErrorKind::Io => "io",
+        ErrorKind::Other => "other",
+    }
+}
+
+/// Classify a rev-spec parse error like [`error_kind()`] would.
+fn spec_error_kind(err: &git::revision::spec::parse::Error) -> &'static str {
+    use git::revision::spec::parse::Error as SpecError;
+    match err {
+        SpecError::FindObject(err) => find_error_kind(err.kind()),
+        SpecError::FindReference(git::refs::file::find::existing::Error::NotFound { .. })
+        | SpecError::FindHead(git::reference::find::existing::Error::NotFound)
+        | SpecError::PrefixNotFound { .. }
+        | SpecError::PathNotFound { .. }
+        | SpecError::NoRegexMatch { .. }
+        | SpecError::NoRegexMatchAllRefs { .. } => "not-found",
+        SpecError::Multi { current, .. } => current
+            .downcast_ref::<SpecError>()
+            .map_or("invalid-spec", spec_error_kind),
+        _ => "invalid-spec",
+    }
+}
+
 pub mod net;
 
 pub mod commitgraph;
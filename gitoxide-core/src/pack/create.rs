@@ -62,6 +62,40 @@ impl From<ObjectExpansion> for pack::data::output::count::objects::ObjectExpansi
     }
 }
 
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Deltas {
+    None,
+    Reuse,
+    Compute,
+}
+
+impl Deltas {
+    pub fn variants() -> &'static [&'static str] {
+        &["none", "reuse", "compute"]
+    }
+}
+
+impl Default for Deltas {
+    fn default() -> Self {
+        Deltas::Reuse
+    }
+}
+
+impl FromStr for Deltas {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Deltas::*;
+        let slc = s.to_ascii_lowercase();
+        Ok(match slc.as_str() {
+            "none" => None,
+            "reuse" => Reuse,
+            "compute" => Compute,
+            _ => return Err("invalid value".into()),
+        })
+    }
+}
+
 /// A general purpose context for many operations provided here
 pub struct Context<W> {
     /// The way input objects should be handled
@@ -80,6 +114,10 @@ pub struct Context<W> {
     pub thread_limit: Option<usize>,
     /// If set, statistics about the operation will be written to the output stream.
     pub statistics: Option<OutputFormat>,
+    /// How to produce deltas, trading time for the size of the pack.
+    pub delta_mode: pack::data::output::entry::iter_from_counts::DeltaMode,
+    /// The zlib compression level from 0 to 9 for objects which aren't copied from existing packs.
+    pub compression_level: u32,
     /// The size of the cache storing fully decoded delta objects. This can greatly speed up pack decoding by reducing the length of delta
     /// chains. Note that caches also incur a cost and poorly used caches may reduce overall performance.
     /// This is a total, shared among all threads if `thread_limit` permits.
@@ -108,6 +146,8 @@ pub fn create<W, P>(
         thin,
         thread_limit,
         statistics,
+        delta_mode,
+        compression_level,
         pack_cache_size_in_bytes,
         object_cache_size_in_bytes,
         mut out,
@@ -118,6 +158,9 @@ where
     P: Progress,
     P::SubProgress: 'static,
 {
+    if let Some(OutputFormat::Porcelain { .. }) = statistics {
+        return Err(anyhow!("Porcelain output isn't supported for pack creation statistics"));
+    }
     let repo = git::discover(repository_path)?.into_sync();
     progress.init(Some(2), progress::steps());
     let tips = tips.into_iter();
@@ -215,10 +258,12 @@ where
                 input,
                 progress,
                 &interrupt::IS_INTERRUPTED,
+                None,
                 pack::data::output::count::objects::Options {
                     thread_limit,
                     chunk_size,
                     input_object_expansion,
+                    ..Default::default()
                 },
             )?
         } else {
@@ -227,7 +272,11 @@ where
                 input,
                 progress,
                 &interrupt::IS_INTERRUPTED,
-                input_object_expansion,
+                None,
+                pack::data::output::count::objects::Options {
+                    input_object_expansion,
+                    ..Default::default()
+                },
             )?
         };
         stats.counts = count_stats;
@@ -249,6 +298,8 @@ where
                 allow_thin_pack: thin,
                 chunk_size,
                 version: Default::default(),
+                delta_mode,
+                compression_level,
             },
         ))
     };
@@ -315,6 +366,7 @@ where
 fn print(stats: Statistics, format: OutputFormat, out: impl std::io::Write) -> anyhow::Result<()> {
     match format {
         OutputFormat::Human => human_output(stats, out).map_err(Into::into),
+        OutputFormat::Porcelain { .. } => unreachable!("rejected before creating the pack"),
         #[cfg(feature = "serde1")]
         OutputFormat::Json => serde_json::to_writer_pretty(out, &stats).map_err(Into::into),
     }
@@ -328,6 +380,7 @@ fn human_output(
                 expanded_objects,
                 decoded_objects,
                 total_objects,
+                ..
             },
         entries:
             pack::data::output::entry::iter_from_counts::Outcome {
@@ -335,6 +388,8 @@ fn human_output(
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                reused_deltas,
+                computed_deltas,
             },
     }: Statistics,
     mut out: impl std::io::Write,
@@ -355,9 +410,11 @@ fn human_output(
     #[rustfmt::skip]
     writeln!(
         out,
-        "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
+        "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
         "decoded and recompressed", decoded_and_recompressed_objects,
         "pack-to-pack copies", objects_copied_from_pack,
+        "reused deltas", reused_deltas,
+        "computed deltas", computed_deltas,
         "ref-delta-objects", ref_delta_objects,
         "missing objects", missing_objects,
         width = width
@@ -135,13 +135,17 @@ pub struct Context {
     pub verify: bool,
     pub should_interrupt: Arc<AtomicBool>,
     pub object_hash: git_repository::hash::Kind,
+    /// If set, create pack caches according to this specification instead of using a small LRU cache.
+    ///
+    /// Each thread creates its own cache, so the memory budget of the specification applies per thread.
+    pub pack_cache: Option<pack::cache::Spec>,
 }
 
 pub fn pack_or_pack_index(
     pack_path: impl AsRef<Path>,
     object_path: Option<impl AsRef<Path>>,
     check: SafetyCheck,
-    progress: impl Progress,
+    mut progress: impl Progress,
     Context {
         thread_limit,
         delete_pack,
@@ -149,17 +153,21 @@ pub fn pack_or_pack_index(
         verify,
         should_interrupt,
         object_hash,
+        pack_cache,
     }: Context,
 ) -> Result<()> {
     use anyhow::Context;
 
     let path = pack_path.as_ref();
-    let bundle = pack::Bundle::at(path, object_hash).with_context(|| {
+    let (bundle, warnings) = pack::Bundle::at_with_warnings(path, object_hash).with_context(|| {
         format!(
             "Could not find .idx or .pack file from given file at '{}'",
             path.display()
         )
     })?;
+    for warning in warnings {
+        progress.fail(format!("{}", warning));
+    }
 
     if !object_path.as_ref().map(|p| p.as_ref().is_dir()).unwrap_or(true) {
         return Err(anyhow!(
@@ -182,11 +190,11 @@ pub fn pack_or_pack_index(
             }
         });
 
-    let pack::index::traverse::Outcome{mut progress, ..} = bundle
+    bundle
         .index
         .traverse(
             &bundle.pack,
-            progress,
+            &mut progress,
             &should_interrupt,
             {
                 let object_path = object_path.map(|p| p.as_ref().to_owned());
@@ -226,7 +234,16 @@ pub fn pack_or_pack_index(
                 traversal: algorithm,
                 thread_limit,
                 check: check.into(),
-                make_pack_lookup_cache:             pack::cache::lru::StaticLinkedList::<64>::default,
+                make_pack_lookup_cache: move || -> Box<dyn pack::cache::DecodeEntry + Send> {
+                    match pack_cache {
+                        Some(spec) => spec.to_cache(),
+                        None => Box::new(pack::cache::lru::StaticLinkedList::<64>::default()),
+                    }
+                },
+                max_decompressed_object_size: Some(pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+                partition: None,
+                paranoid: false,
+                strict_entry_boundaries: false,
             },
         )
         .with_context(|| "Failed to explode the entire pack - some loose objects may have been created nonetheless")?;
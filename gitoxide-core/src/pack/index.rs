@@ -86,11 +86,16 @@ where
     P::SubProgress: 'static,
 {
     use anyhow::Context;
+    if let OutputFormat::Porcelain { .. } = ctx.format {
+        anyhow::bail!("Porcelain output isn't supported for creating indices");
+    }
     let options = pack::bundle::write::Options {
         thread_limit: ctx.thread_limit,
         iteration_mode: ctx.iteration_mode.into(),
         index_version: pack::index::Version::default(),
         object_hash: ctx.object_hash,
+        max_decompressed_object_size: Some(pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+        durability: Default::default(),
     };
     let out = ctx.out;
     let format = ctx.format;
@@ -121,6 +126,7 @@ where
     .with_context(|| "Failed to write pack and index")?;
     match format {
         OutputFormat::Human => drop(human_output(out, res)),
+        OutputFormat::Porcelain { .. } => unreachable!("rejected before writing the index"),
         #[cfg(feature = "serde1")]
         OutputFormat::Json => serde_json::to_writer_pretty(out, &res)?,
     };
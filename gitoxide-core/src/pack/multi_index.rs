@@ -8,8 +8,12 @@ use crate::OutputFormat;
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
-pub fn verify(multi_index_path: PathBuf, progress: impl Progress, should_interrupt: &AtomicBool) -> anyhow::Result<()> {
-    git::odb::pack::multi_index::File::at(multi_index_path)?.verify_integrity_fast(progress, should_interrupt)?;
+pub fn verify(
+    multi_index_path: PathBuf,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    git::odb::pack::multi_index::File::at(multi_index_path)?.verify_integrity_fast(&mut progress, should_interrupt)?;
     Ok(())
 }
 
@@ -56,8 +60,8 @@ pub fn info(
     out: impl std::io::Write,
     mut err: impl std::io::Write,
 ) -> anyhow::Result<()> {
-    if format == OutputFormat::Human {
-        writeln!(err, "Defaulting to JSON as human format isn't implemented").ok();
+    if matches!(format, OutputFormat::Human | OutputFormat::Porcelain { .. }) {
+        writeln!(err, "Defaulting to JSON as no other format is implemented").ok();
     }
     #[cfg(feature = "serde1")]
     {
@@ -359,11 +359,19 @@ fn receive_pack_blocking<W: io::Write>(
     progress: impl Progress,
     refs: &[Ref],
 ) -> io::Result<()> {
+    if let OutputFormat::Porcelain { .. } = ctx.format {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "Porcelain output isn't supported for receiving packs",
+        ));
+    }
     let options = pack::bundle::write::Options {
         thread_limit: ctx.thread_limit,
         index_version: pack::index::Version::V2,
         iteration_mode: pack::data::input::Mode::Verify,
         object_hash: ctx.object_hash,
+        max_decompressed_object_size: Some(pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+        durability: Default::default(),
     };
     let outcome =
         pack::Bundle::write_to_directory(input, directory.take(), progress, &ctx.should_interrupt, None, options)
@@ -375,6 +383,7 @@ fn receive_pack_blocking<W: io::Write>(
 
     match ctx.format {
         OutputFormat::Human => drop(print(&mut ctx.out, outcome, refs)),
+        OutputFormat::Porcelain { .. } => unreachable!("rejected before writing the pack"),
         #[cfg(feature = "serde1")]
         OutputFormat::Json => {
             serde_json::to_writer_pretty(&mut ctx.out, &JsonOutcome::from_outcome_and_refs(outcome, refs))?
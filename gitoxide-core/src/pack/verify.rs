@@ -4,14 +4,14 @@ use anyhow::{anyhow, Context as AnyhowContext, Result};
 use bytesize::ByteSize;
 use git_repository as git;
 use git_repository::{
-    object, odb,
+    odb,
     odb::{pack, pack::index},
     Progress,
 };
 pub use index::verify::Mode;
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
-use crate::OutputFormat;
+use crate::{OutputFormat, Records};
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub enum Algorithm {
@@ -47,6 +47,49 @@ impl From<Algorithm> for index::traverse::Algorithm {
     }
 }
 
+/// Options to control how statistics are printed in human-readable output.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// If true, print sizes in binary units like MiB and GiB and separate the thousands of counts.
+    ///
+    /// It's off by default to not change the output for scripts that parse it.
+    pub human_units: bool,
+}
+
+impl Options {
+    fn size(&self, bytes: u64) -> String {
+        if !self.human_units {
+            return ByteSize(bytes).to_string();
+        }
+        if bytes < 1024 {
+            return format!("{} B", bytes);
+        }
+        let mut value = bytes as f64 / 1024.0;
+        let mut units = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"].iter().peekable();
+        let mut unit = units.next().expect("non-empty");
+        while value >= 1024.0 && units.peek().is_some() {
+            value /= 1024.0;
+            unit = units.next().expect("peeked");
+        }
+        format!("{:.1} {}", value, unit)
+    }
+
+    pub(crate) fn count(&self, count: impl Into<u64>) -> String {
+        let count = count.into().to_string();
+        if !self.human_units {
+            return count;
+        }
+        let mut out = String::with_capacity(count.len() + count.len() / 3);
+        for (idx, digit) in count.chars().enumerate() {
+            if idx != 0 && (count.len() - idx) % 3 == 0 {
+                out.push(',');
+            }
+            out.push(digit);
+        }
+        out
+    }
+}
+
 /// A general purpose context for many operations provided here
 pub struct Context<'a, W1: io::Write, W2: io::Write> {
     /// If set, provide statistics to `out` in the given format
@@ -63,30 +106,61 @@ pub struct Context<'a, W1: io::Write, W2: io::Write> {
     pub algorithm: Algorithm,
     pub should_interrupt: &'a AtomicBool,
     pub object_hash: git::hash::Kind,
+    /// How to print statistics in human-readable output.
+    pub statistics_options: Options,
+    /// If set, don't verify anything but print the work that verifying an index and its pack would do instead.
+    pub plan_only: bool,
+    /// If set, create pack caches according to this specification instead of choosing one based on `algorithm`.
+    ///
+    /// Each thread creates its own cache, so the memory budget of the specification applies per thread.
+    /// It's ignored when printing statistics, which need to see entire delta chains.
+    pub pack_cache: Option<pack::cache::Spec>,
 }
 
-enum EitherCache<const SIZE: usize> {
-    Left(pack::cache::Never),
-    Right(pack::cache::lru::StaticLinkedList<SIZE>),
+/// The kind of file passed to [`file()`], as determined by its extension or name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// A pack data file with the `.pack` extension.
+    Pack,
+    /// A pack index file with the `.idx` extension.
+    Index,
+    /// A multi-pack index named `multi-pack-index` or with the `.midx` extension.
+    MultiIndex,
 }
 
-impl<const SIZE: usize> pack::cache::DecodeEntry for EitherCache<SIZE> {
-    fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: object::Kind, compressed_size: usize) {
-        match self {
-            EitherCache::Left(v) => v.put(pack_id, offset, data, kind, compressed_size),
-            EitherCache::Right(v) => v.put(pack_id, offset, data, kind, compressed_size),
+impl FileKind {
+    /// Determine the kind of file at `path` by its extension or name, or `None` if it's unknown.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("pack") => Some(FileKind::Pack),
+            Some("idx") => Some(FileKind::Index),
+            Some("midx") => Some(FileKind::MultiIndex),
+            _ => (path.file_name()? == "multi-pack-index").then(|| FileKind::MultiIndex),
         }
     }
+}
 
-    fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(object::Kind, usize)> {
-        match self {
-            EitherCache::Left(v) => v.get(pack_id, offset, out),
-            EitherCache::Right(v) => v.get(pack_id, offset, out),
-        }
-    }
+/// The combined outcome of [`file()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The kind of the file which was passed.
+    pub kind: FileKind,
+    /// The checksum of the pack index or multi-pack index, if one was verified.
+    pub index_checksum: Option<git::ObjectId>,
+    /// The checksum of the pack data file, if one was verified.
+    pub pack_checksum: Option<git::ObjectId>,
+    /// The path of the sibling that couldn't be opened, which limited verification to the checksum of the given file.
+    pub missing_sibling: Option<std::path::PathBuf>,
+    /// If `true`, all objects were traversed as the index and its pack were available together.
+    pub objects_verified: bool,
 }
 
-pub fn pack_or_pack_index<W1, W2>(
+/// Verify the `.idx`, `.pack` or multi-pack index file at `path`, using its sibling if it can be found.
+///
+/// An index is paired with the pack of the same name, and a pack with its index if there is one, to verify all objects
+/// they contain. Without a sibling, only the checksum of the given file is verified. A multi-pack index is verified along
+/// with all packs it refers to.
+pub fn file<W1, W2>(
     path: impl AsRef<Path>,
     mut progress: impl Progress,
     Context {
@@ -98,34 +172,80 @@ pub fn pack_or_pack_index<W1, W2>(
         algorithm,
         should_interrupt,
         object_hash,
+        statistics_options,
+        plan_only,
+        pack_cache,
     }: Context<'_, W1, W2>,
-) -> Result<()>
+) -> Result<Outcome>
 where
     W1: io::Write,
     W2: io::Write,
 {
     let path = path.as_ref();
-    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let kind = FileKind::from_path(path).ok_or_else(|| {
+        anyhow!(
+            "Cannot determine the kind of file at '{}', expecting the extensions 'idx', 'pack' or 'midx', or the name 'multi-pack-index'",
+            path.display()
+        )
+    })?;
     const CACHE_SIZE: usize = 64;
-    let cache = || -> EitherCache<CACHE_SIZE> {
-        if matches!(algorithm, Algorithm::LessMemory) {
-            if output_statistics.is_some() {
-                // turn off acceleration as we need to see entire chains all the time
-                EitherCache::Left(pack::cache::Never)
-            } else {
-                EitherCache::Right(pack::cache::lru::StaticLinkedList::<CACHE_SIZE>::default())
+    let cache = move || -> Box<dyn pack::cache::DecodeEntry + Send> {
+        if output_statistics.is_some() {
+            // turn off acceleration as we need to see entire chains all the time
+            return Box::new(pack::cache::Never);
+        }
+        match pack_cache {
+            Some(spec) => spec.to_cache(),
+            None if matches!(algorithm, Algorithm::LessMemory) => {
+                Box::new(pack::cache::lru::StaticLinkedList::<CACHE_SIZE>::default())
             }
-        } else {
-            EitherCache::Left(pack::cache::Never)
+            None => Box::new(pack::cache::Never),
         }
     };
-    let res = match ext {
-        "pack" => {
+    if plan_only && kind != FileKind::Index {
+        return Err(anyhow!(
+            "Only the verification of pack index files can be planned, got '{}'",
+            path.display()
+        ));
+    }
+    let integrity_options = git::odb::pack::index::verify::integrity::Options {
+        verify_mode: mode,
+        traversal: algorithm.into(),
+        make_pack_lookup_cache: cache,
+        thread_limit,
+        max_decompressed_object_size: Some(git::odb::pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+        tree_checks: Default::default(),
+        keep_going: false,
+        paranoid: false,
+        strict_entry_boundaries: false,
+    };
+    let mut outcome = Outcome {
+        kind,
+        index_checksum: None,
+        pack_checksum: None,
+        missing_sibling: None,
+        objects_verified: false,
+    };
+    let (idx, pack) = match kind {
+        FileKind::Pack => {
             let pack = odb::pack::data::File::at(path, object_hash).with_context(|| "Could not open pack file")?;
-            pack.verify_checksum(progress.add_child("Sha1 of pack"), should_interrupt)
-                .map(|id| (id, None))?
+            let index_path = path.with_extension("idx");
+            if !index_path.is_file() {
+                writeln!(
+                    err,
+                    "Could not find matching index file at '{}' - only pack file will be verified",
+                    index_path.display()
+                )
+                .ok();
+                outcome.pack_checksum = Some(pack.verify_checksum(progress.add_child("hash pack"), should_interrupt)?);
+                outcome.missing_sibling = Some(index_path);
+                return Ok(outcome);
+            }
+            let idx = odb::pack::index::File::at(&index_path, object_hash)
+                .with_context(|| format!("Could not open matching pack index file at '{}'", index_path.display()))?;
+            (idx, Some(pack))
         }
-        "idx" => {
+        FileKind::Index => {
             let idx =
                 odb::pack::index::File::at(path, object_hash).with_context(|| "Could not open pack index file")?;
             let packfile_path = path.with_extension("pack");
@@ -138,79 +258,212 @@ where
                         e
                     )
                     .ok();
+                    outcome.missing_sibling = Some(packfile_path.clone());
                     e
                 })
                 .ok();
 
-            idx.verify_integrity(
-                pack.as_ref().map(|p| git::odb::pack::index::verify::PackContext {
-                    data: p,
-                    options: git::odb::pack::index::verify::integrity::Options {
-                        verify_mode: mode,
-                        traversal: algorithm.into(),
-                        make_pack_lookup_cache: cache,
-                        thread_limit
-                    }
-                }),
-                progress,
-                should_interrupt,
-            )
-            .map(|o| (o.actual_index_checksum, o.pack_traverse_statistics))
-            .with_context(|| "Verification failure")?
-        }
-        "" => {
-            match path.file_name() {
-                Some(file_name) if file_name == "multi-pack-index" => {
-                    let multi_index = git::odb::pack::multi_index::File::at(path)?;
-                    let res = multi_index.verify_integrity(progress, should_interrupt, git::odb::pack::index::verify::integrity::Options{
-                        verify_mode: mode,
+            if plan_only {
+                let pack = pack.ok_or_else(|| anyhow!("Planning the verification requires the pack file"))?;
+                let plan = idx.traverse_plan(
+                    &pack,
+                    &index::traverse::Options {
                         traversal: algorithm.into(),
                         thread_limit,
-                        make_pack_lookup_cache: cache
-                    })?;
-                    match output_statistics {
-                        Some(OutputFormat::Human) => {
-                            for (index_name, stats) in multi_index.index_names().iter().zip(res.pack_traverse_statistics) {
-                                writeln!(out, "{}", index_name.display()).ok();
-                                drop(print_statistics(&mut out, &stats));
-                            }
-                        },
-                        #[cfg(feature = "serde1")]
-                        Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, &multi_index.index_names().iter().zip(res.pack_traverse_statistics).collect::<Vec<_>>())?,
-                        _ => {}
-                    };
-                    return Ok(())
-                },
-                _ => return Err(anyhow!(
-                        "Cannot determine data type on path without extension '{}', expecting default extensions 'idx' and 'pack'",
-                        path.display()
-                    ))
+                        ..Default::default()
+                    },
+                )?;
+                print_plan(out, &plan, output_statistics.unwrap_or(OutputFormat::Human))?;
+                return Ok(outcome);
             }
+            (idx, pack)
+        }
+        FileKind::MultiIndex => {
+            let multi_index = git::odb::pack::multi_index::File::at(path)?;
+            let res = multi_index.verify_integrity(&mut progress, should_interrupt, integrity_options)?;
+            outcome.index_checksum = Some(res.actual_index_checksum);
+            outcome.objects_verified = true;
+            match output_statistics {
+                Some(OutputFormat::Human) => {
+                    for ((index_name, stats), pack_file) in multi_index
+                        .index_names()
+                        .iter()
+                        .zip(res.pack_traverse_statistics)
+                        .zip(res.pack_files)
+                    {
+                        writeln!(out, "{}", pack_section_header(pack_file, index_name)).ok();
+                        drop(print_statistics(&mut out, &stats, statistics_options));
+                    }
+                }
+                Some(OutputFormat::Porcelain { nul_terminated }) => {
+                    let mut records = Records::new(&mut out, nul_terminated);
+                    for (index_name, stats) in multi_index.index_names().iter().zip(res.pack_traverse_statistics) {
+                        print_statistics_porcelain(&mut records, &stats, &[&index_name.display()])?;
+                    }
+                }
+                #[cfg(feature = "serde1")]
+                Some(OutputFormat::Json) => serde_json::to_writer_pretty(
+                    out,
+                    &multi_index
+                        .index_names()
+                        .iter()
+                        .zip(res.pack_traverse_statistics)
+                        .collect::<Vec<_>>(),
+                )?,
+                _ => {}
+            };
+            return Ok(outcome);
         }
-        ext => return Err(anyhow!("Unknown extension {:?}, expecting 'idx' or 'pack'", ext)),
     };
-    if let Some(stats) = res.1.as_ref() {
+
+    let res = idx
+        .verify_integrity(
+            pack.as_ref().map(|p| git::odb::pack::index::verify::PackContext {
+                data: p,
+                options: integrity_options,
+            }),
+            &mut progress,
+            should_interrupt,
+        )
+        .with_context(|| "Verification failure")?;
+    outcome.index_checksum = Some(res.actual_index_checksum);
+    outcome.pack_checksum = pack.as_ref().map(|pack| pack.checksum());
+    outcome.objects_verified = pack.is_some();
+    if let Some(stats) = res.pack_traverse_statistics.as_ref() {
         #[cfg_attr(not(feature = "serde1"), allow(clippy::single_match))]
         match output_statistics {
-            Some(OutputFormat::Human) => drop(print_statistics(&mut out, stats)),
+            Some(OutputFormat::Human) => drop(print_statistics(&mut out, stats, statistics_options)),
+            Some(OutputFormat::Porcelain { nul_terminated }) => {
+                print_statistics_porcelain(&mut Records::new(&mut out, nul_terminated), stats, &[])?
+            }
             #[cfg(feature = "serde1")]
             Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, stats)?,
             _ => {}
         };
     }
+    Ok(outcome)
+}
+
+/// Print the work verifying an index would do.
+///
+/// Porcelain output has one record per value with the columns `<name>` and `<value>`, in this order:
+/// `algorithm`, `num_entries`, `num_roots`, `max_chain_length`, `average_chain_length`, `num_threads`,
+/// `chunk_size` (or `none`), `entries_per_thread` as comma-separated counts and `decompressed_entries_size` in bytes.
+fn print_plan(mut out: impl io::Write, plan: &index::traverse::Plan, format: OutputFormat) -> Result<()> {
+    let entries_per_thread = plan
+        .entries_per_thread
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    let chunk_size = plan
+        .chunk_size
+        .map_or_else(|| "none".to_string(), |size| size.to_string());
+    match format {
+        OutputFormat::Human => {
+            let width = 30;
+            #[rustfmt::skip]
+            writeln!(
+                out,
+                "{:<width$} {:?}\n{:<width$} {}\n{:<width$} {}\n{:<width$} {}\n{:<width$} {:.2}\n{:<width$} {}\n{:<width$} {}\n{:<width$} {}\n{:<width$} {}",
+                "algorithm:", plan.algorithm,
+                "entries:", plan.num_entries,
+                "delta roots:", plan.num_roots,
+                "max delta chain length:", plan.max_chain_length,
+                "average delta chain length:", plan.average_chain_length,
+                "threads:", plan.num_threads,
+                "chunk size:", chunk_size,
+                "entries per thread:", entries_per_thread,
+                "decompressed entries size [B]:", plan.decompressed_entries_size,
+                width = width
+            )?;
+        }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(&mut out, nul_terminated);
+            records.write(&[&"algorithm", &format_args!("{:?}", plan.algorithm)])?;
+            records.write(&[&"num_entries", &plan.num_entries])?;
+            records.write(&[&"num_roots", &plan.num_roots])?;
+            records.write(&[&"max_chain_length", &plan.max_chain_length])?;
+            records.write(&[&"average_chain_length", &plan.average_chain_length])?;
+            records.write(&[&"num_threads", &plan.num_threads])?;
+            records.write(&[&"chunk_size", &chunk_size])?;
+            records.write(&[&"entries_per_thread", &entries_per_thread])?;
+            records.write(&[&"decompressed_entries_size", &plan.decompressed_entries_size])?;
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, plan)?,
+    }
     Ok(())
 }
 
-fn print_statistics(out: &mut impl io::Write, stats: &index::traverse::Statistics) -> io::Result<()> {
+/// Write one record per statistic with the columns `<name>` and `<value>`, followed by the `trailing` fields.
+///
+/// Records are written in this order, with sizes in bytes:
+/// `num_objects_at_chain_length_<n>` for each chain length, `average_delta_chain_length`, `average_decompressed_entry_size`,
+/// `average_compressed_entry_size`, `average_object_size`, `total_compressed_entries_size`, `total_decompressed_entries_size`,
+/// `total_object_size`, `pack_size`, `num_trees`, `num_blobs`, `num_commits`, `num_tags`, `index_oid_mismatches`
+/// and `delta_result_size_mismatches`.
+pub(crate) fn print_statistics_porcelain(
+    records: &mut Records<impl io::Write>,
+    stats: &index::traverse::Statistics,
+    trailing: &[&dyn std::fmt::Display],
+) -> io::Result<()> {
+    let mut write = |name: &dyn std::fmt::Display, value: &dyn std::fmt::Display| {
+        let mut fields: Vec<&dyn std::fmt::Display> = vec![name, value];
+        fields.extend_from_slice(trailing);
+        records.write(&fields)
+    };
+    for (chain_length, object_count) in &stats.objects_per_chain_length {
+        write(
+            &format_args!("num_objects_at_chain_length_{}", chain_length),
+            object_count,
+        )?;
+    }
+    let average = &stats.average;
+    write(&"average_delta_chain_length", &average.num_deltas)?;
+    write(&"average_decompressed_entry_size", &average.decompressed_size)?;
+    write(&"average_compressed_entry_size", &average.compressed_size)?;
+    write(&"average_object_size", &average.object_size)?;
+    write(&"total_compressed_entries_size", &stats.total_compressed_entries_size)?;
+    write(
+        &"total_decompressed_entries_size",
+        &stats.total_decompressed_entries_size,
+    )?;
+    write(&"total_object_size", &stats.total_object_size)?;
+    write(&"pack_size", &stats.pack_size)?;
+    write(&"num_trees", &stats.num_trees)?;
+    write(&"num_blobs", &stats.num_blobs)?;
+    write(&"num_commits", &stats.num_commits)?;
+    write(&"num_tags", &stats.num_tags)?;
+    write(&"index_oid_mismatches", &stats.index_oid_mismatches)?;
+    write(&"delta_result_size_mismatches", &stats.delta_result_size_mismatches)
+}
+
+/// The name of the pack file whose statistics follow, or the path of its index if the pack isn't on disk.
+pub(crate) fn pack_section_header(pack_file: Option<pack::verify::FileInfo>, index_path: &Path) -> String {
+    match pack_file {
+        Some(pack_file) => pack_file.path.file_name().map_or_else(
+            || pack_file.path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        ),
+        None => index_path.display().to_string(),
+    }
+}
+
+pub(crate) fn print_statistics(
+    out: &mut impl io::Write,
+    stats: &index::traverse::Statistics,
+    options: Options,
+) -> io::Result<()> {
     writeln!(out, "objects per delta chain length")?;
     let mut chain_length_to_object: Vec<_> = stats.objects_per_chain_length.iter().map(|(a, b)| (*a, *b)).collect();
     chain_length_to_object.sort_by_key(|e| e.0);
     let mut total_object_count = 0;
     for (chain_length, object_count) in chain_length_to_object.into_iter() {
         total_object_count += object_count;
-        writeln!(out, "\t{:>2}: {}", chain_length, object_count)?;
+        writeln!(out, "\t{:>2}: {}", chain_length, options.count(object_count))?;
     }
-    writeln!(out, "\t->: {}", total_object_count)?;
+    writeln!(out, "\t->: {}", options.count(total_object_count))?;
 
     let pack::data::decode_entry::Outcome {
         kind: _,
@@ -222,35 +475,48 @@ fn print_statistics(out: &mut impl io::Write, stats: &index::traverse::Statistic
 
     let width = 30;
     writeln!(out, "\naverages")?;
-    #[rustfmt::skip]
-    writeln!(
-        out,
-        "\t{:<width$} {};\n\t{:<width$} {};\n\t{:<width$} {};\n\t{:<width$} {};",
-        "delta chain length:", num_deltas,
-        "decompressed entry [B]:", decompressed_size,
-        "compressed entry [B]:", compressed_size,
-        "decompressed object size [B]:", object_size,
-        width = width
-    )?;
+    if options.human_units {
+        #[rustfmt::skip]
+        writeln!(
+            out,
+            "\t{:<width$} {};\n\t{:<width$} {};\n\t{:<width$} {};\n\t{:<width$} {};",
+            "delta chain length:", num_deltas,
+            "decompressed entry:", options.size(decompressed_size),
+            "compressed entry:", options.size(compressed_size as u64),
+            "decompressed object size:", options.size(object_size),
+            width = width
+        )?;
+    } else {
+        #[rustfmt::skip]
+        writeln!(
+            out,
+            "\t{:<width$} {};\n\t{:<width$} {};\n\t{:<width$} {};\n\t{:<width$} {};",
+            "delta chain length:", num_deltas,
+            "decompressed entry [B]:", decompressed_size,
+            "compressed entry [B]:", compressed_size,
+            "decompressed object size [B]:", object_size,
+            width = width
+        )?;
+    }
 
     writeln!(out, "\ncompression")?;
     #[rustfmt::skip]
     writeln!(
         out, "\t{:<width$}: {}\n\t{:<width$}: {}\n\t{:<width$}: {}\n\t{:<width$}: {}",
-        "compressed entries size", ByteSize(stats.total_compressed_entries_size),
-        "decompressed entries size", ByteSize(stats.total_decompressed_entries_size),
-        "total object size", ByteSize(stats.total_object_size),
-        "pack size", ByteSize(stats.pack_size),
+        "compressed entries size", options.size(stats.total_compressed_entries_size),
+        "decompressed entries size", options.size(stats.total_decompressed_entries_size),
+        "total object size", options.size(stats.total_object_size),
+        "pack size", options.size(stats.pack_size),
         width = width
     )?;
     #[rustfmt::skip]
     writeln!(
         out,
         "\n\t{:<width$}: {}\n\t{:<width$}: {}\n\t{:<width$}: {}\n\t{:<width$}: {}",
-        "num trees", stats.num_trees,
-        "num blobs", stats.num_blobs,
-        "num commits", stats.num_commits,
-        "num tags", stats.num_tags,
+        "num trees", options.count(stats.num_trees),
+        "num blobs", options.count(stats.num_blobs),
+        "num commits", options.count(stats.num_commits),
+        "num tags", options.count(stats.num_tags),
         width = width
     )?;
     let compression_ratio = stats.total_decompressed_entries_size as f64 / stats.total_compressed_entries_size as f64;
@@ -265,5 +531,25 @@ fn print_statistics(out: &mut impl io::Write, stats: &index::traverse::Statistic
         "pack overhead", (1.0 - (stats.total_compressed_entries_size as f64 / stats.pack_size as f64)) * 100.0,
         width = width
     )?;
+    if !stats.first_entry_boundary_mismatches.is_empty() {
+        #[rustfmt::skip]
+        writeln!(
+            out,
+            "\nentry boundaries\n\t{:<width$}: {}\n\t{:<width$}: {}",
+            "overlapping entries", options.count(stats.entry_overlaps),
+            "slack", options.size(stats.entry_slack_bytes),
+            width = width
+        )?;
+        for mismatch in &stats.first_entry_boundary_mismatches {
+            writeln!(
+                out,
+                "\t{:>width$}: {} of {} available bytes used",
+                mismatch.pack_offset,
+                mismatch.entry_size,
+                mismatch.available_size,
+                width = width
+            )?;
+        }
+    }
     Ok(())
 }
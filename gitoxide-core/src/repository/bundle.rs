@@ -0,0 +1,301 @@
+use std::{collections::HashSet, path::Path, sync::atomic::AtomicBool};
+
+use anyhow::{anyhow, bail};
+use git_repository as git;
+use git_repository::{
+    bstr::ByteSlice,
+    odb::pack::{self, bundle_file},
+    prelude::{Finalize, Find, FindExt, ReferenceExt},
+    Progress,
+};
+
+use crate::{bail_if_interrupted, OutputFormat, Records};
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+pub mod create {
+    use git_repository::odb::pack::bundle_file;
+
+    pub struct Options {
+        /// The version of the bundle to write, or `None` to use the most compatible one.
+        pub version: Option<bundle_file::Version>,
+        /// Revisions whose history is left out of the bundle, making the parents of the commits in the bundle
+        /// that are reachable from them its prerequisites.
+        pub exclude: Vec<String>,
+        pub thread_limit: Option<usize>,
+    }
+}
+
+/// Write a bundle file to `path` which advertises the references named in `refs` and contains all objects reachable
+/// from them, like `git bundle create`.
+///
+/// If `refs` is empty, `HEAD` and all references are advertised, like `git bundle create <file> --all`.
+/// Commits reachable from the [excluded revisions][create::Options::exclude] are left out, making the bundle
+/// depend on them as prerequisites.
+pub fn create<P>(
+    repo: git::Repository,
+    path: &Path,
+    refs: Vec<String>,
+    mut progress: P,
+    should_interrupt: &AtomicBool,
+    create::Options {
+        version,
+        exclude,
+        thread_limit,
+    }: create::Options,
+) -> anyhow::Result<()>
+where
+    P: Progress,
+    P::SubProgress: 'static,
+{
+    let refs = if refs.is_empty() {
+        let mut refs = vec![repo.find_reference("HEAD")?];
+        let platform = repo.references()?;
+        for reference in platform.all()? {
+            refs.push(reference.map_err(|err| anyhow!(err))?.detach().attach(&repo));
+        }
+        refs
+    } else {
+        refs.iter()
+            .map(|name| repo.find_reference(name.as_str()))
+            .collect::<Result<_, _>>()?
+    };
+    let mut advertised = Vec::<bundle_file::Ref>::new();
+    let mut tips = Vec::new();
+    for reference in refs {
+        let name = reference.name().as_bstr().to_owned();
+        if advertised.iter().any(|r| r.name == name) {
+            continue;
+        }
+        let target = match reference.target() {
+            git::refs::TargetRef::Peeled(id) => id.to_owned(),
+            git::refs::TargetRef::Symbolic(_) => reference.into_fully_peeled_id()?.detach(),
+        };
+        let peeled = repo.find_object(target)?.peel_tags_to_end()?;
+        if peeled.kind == git::objs::Kind::Commit {
+            tips.push(peeled.id);
+        }
+        advertised.push(bundle_file::Ref { name, target });
+    }
+    let hidden = exclude
+        .iter()
+        .map(|spec| -> anyhow::Result<_> {
+            Ok(repo
+                .rev_parse_single(spec.as_str())?
+                .object()?
+                .peel_to_kind(git::objs::Kind::Commit)?
+                .id)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    progress.init(Some(3), git::progress::steps());
+    progress.set_name("traversing");
+    let mut commits = Vec::new();
+    for commit in repo.rev_walk(tips).hide(hidden).all()? {
+        bail_if_interrupted(should_interrupt)?;
+        commits.push(commit?.detach());
+    }
+    let included: HashSet<_> = commits.iter().copied().collect();
+    let mut prerequisites = Vec::<bundle_file::Prerequisite>::new();
+    for id in &commits {
+        for parent in repo.find_object(*id)?.try_into_commit()?.parent_ids() {
+            let parent = parent.detach();
+            if included.contains(&parent) || prerequisites.iter().any(|p| p.id == parent) {
+                continue;
+            }
+            let comment = repo
+                .find_object(parent)?
+                .try_into_commit()?
+                .message_raw_sloppy()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .into();
+            prerequisites.push(bundle_file::Prerequisite { id: parent, comment });
+        }
+    }
+
+    progress.inc();
+    progress.set_name("counting");
+    let mut handle = repo.objects.clone().into_arc()?;
+    handle.prevent_pack_unload();
+    let inputs = advertised
+        .iter()
+        .map(|r| r.target)
+        .chain(commits)
+        .map(Ok::<_, std::convert::Infallible>);
+    let (counts, _) = pack::data::output::count::objects_unthreaded(
+        handle.clone(),
+        inputs,
+        progress.add_child("counting"),
+        should_interrupt,
+        None,
+        pack::data::output::count::objects::Options {
+            input_object_expansion: pack::data::output::count::objects::ObjectExpansion::TreeContents,
+            ..Default::default()
+        },
+    )?;
+    let counts: Vec<_> = counts
+        .into_iter()
+        .filter(|count| !prerequisites.iter().any(|p| p.id == count.id))
+        .collect();
+
+    progress.inc();
+    progress.set_name("writing");
+    let num_objects = counts.len();
+    let mut entries = git::parallel::InOrderIter::from(pack::data::output::entry::iter_from_counts(
+        counts,
+        handle,
+        progress.add_child("creating entries"),
+        pack::data::output::entry::iter_from_counts::Options {
+            thread_limit,
+            ..Default::default()
+        },
+    ));
+    let mut pack_data = Vec::new();
+    let mut pack_writer = pack::data::output::bytes::FromEntriesIter::new(
+        entries.by_ref(),
+        &mut pack_data,
+        num_objects as u32,
+        pack::data::Version::V2,
+        repo.object_hash(),
+    );
+    for written in pack_writer.by_ref() {
+        bail_if_interrupted(should_interrupt)?;
+        written?;
+    }
+    entries.inner.finalize()?;
+
+    let mut header = bundle_file::Header::new(advertised, prerequisites, repo.object_hash());
+    if let Some(version) = version {
+        header.version = version;
+    }
+    bundle_file::write(
+        &header,
+        pack_data.as_slice(),
+        std::io::BufWriter::new(std::fs::File::create(path)?),
+    )?;
+    progress.inc();
+    Ok(())
+}
+
+pub mod verify {
+    use crate::OutputFormat;
+
+    pub struct Options {
+        pub format: OutputFormat,
+        pub thread_limit: Option<usize>,
+    }
+
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Outcome {
+        /// The header of the bundle with its references and prerequisites.
+        pub header: git_repository::odb::pack::bundle_file::Header,
+        /// The prerequisites of the bundle which don't exist in the repository.
+        pub missing_prerequisites: Vec<git_repository::ObjectId>,
+        /// The amount of objects in the pack of the bundle.
+        pub num_objects: u32,
+    }
+}
+
+/// Check that the bundle file at `path` can be read, that its pack is intact and that all of its prerequisites exist
+/// in `repo`, like `git bundle verify`, and write its references and prerequisites to `out`.
+///
+/// Fail if the bundle is corrupt or prerequisites are missing.
+///
+/// The porcelain format has one record per reference with the columns `ref`, `<id>` and `<name>`, and one record per
+/// prerequisite with the columns `prerequisite`, `<id>` and `present` or `missing`.
+pub fn verify(
+    repo: git::Repository,
+    path: &Path,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    mut out: impl std::io::Write,
+    verify::Options { format, thread_limit }: verify::Options,
+) -> anyhow::Result<()> {
+    let bundle = bundle_file::File::at(path)?;
+    let header = bundle.header().clone();
+    if header.object_hash != repo.object_hash() {
+        bail!(
+            "The bundle uses object format {}, but the repository uses {}",
+            header.object_hash,
+            repo.object_hash()
+        );
+    }
+    let missing_prerequisites: Vec<_> = header
+        .prerequisites
+        .iter()
+        .map(|p| p.id)
+        .filter(|id| !repo.objects.contains(id))
+        .collect();
+
+    progress.init(Some(2), git::progress::steps());
+    progress.set_name("indexing pack");
+    let pack_outcome = pack::Bundle::write_to_directory(
+        bundle.pack_data(),
+        None::<&Path>,
+        progress.add_child("indexing"),
+        should_interrupt,
+        Some(Box::new({
+            let repo = repo.clone();
+            move |oid, buf| repo.objects.find(oid, buf).ok()
+        })),
+        pack::bundle::write::Options {
+            thread_limit,
+            object_hash: header.object_hash,
+            ..Default::default()
+        },
+    )?;
+    progress.inc();
+
+    let outcome = verify::Outcome {
+        header,
+        missing_prerequisites,
+        num_objects: pack_outcome.index.num_objects,
+    };
+    match format {
+        OutputFormat::Human => {
+            let header = &outcome.header;
+            writeln!(out, "The bundle contains {} ref(s):", header.refs.len())?;
+            for r in &header.refs {
+                writeln!(out, "{} {}", r.target, r.name)?;
+            }
+            if header.prerequisites.is_empty() {
+                writeln!(out, "The bundle records a complete history.")?;
+            } else {
+                writeln!(out, "The bundle requires {} ref(s):", header.prerequisites.len())?;
+                for p in &header.prerequisites {
+                    let missing = outcome.missing_prerequisites.contains(&p.id);
+                    writeln!(out, "{} {}{}", p.id, p.comment, if missing { " (missing)" } else { "" })?;
+                }
+            }
+            writeln!(out, "The bundle contains {} object(s).", outcome.num_objects)?;
+        }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(&mut out, nul_terminated);
+            for r in &outcome.header.refs {
+                records.write(&[&"ref", &r.target, &r.name])?;
+            }
+            for p in &outcome.header.prerequisites {
+                let state = if outcome.missing_prerequisites.contains(&p.id) {
+                    "missing"
+                } else {
+                    "present"
+                };
+                records.write(&[&"prerequisite", &p.id, &state])?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &outcome)?;
+            writeln!(out)?;
+        }
+    }
+    if !outcome.missing_prerequisites.is_empty() {
+        bail!(
+            "The repository lacks {} prerequisite(s) of the bundle",
+            outcome.missing_prerequisites.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,450 @@
+use git_repository as git;
+
+use crate::OutputFormat;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If true, print a summary of inserted and deleted lines per file similar to `git diff --stat` instead of
+    /// the kind of change per file.
+    pub stats_only: bool,
+    /// If true, deleted and added files with the same content are paired up as renames.
+    pub find_renames: bool,
+    /// If set, only changes to files at or below the paths of the filter are listed, similar to `git diff <a> <b> -- <path>...`.
+    ///
+    /// If the filter ignores case or unicode normalization, a path may match differently spelled files like `README`
+    /// and `readme`, which are all listed and reported as ambiguity.
+    pub path_filter: Option<git::odb::pack::data::output::count::objects::PathFilter>,
+}
+
+pub use function::tree;
+
+pub(crate) mod function {
+    use std::{collections::HashMap, convert::Infallible};
+
+    use git_repository as git;
+    use git_repository::{
+        bstr::{BStr, BString, ByteSlice},
+        objs::tree::EntryMode,
+        ObjectId,
+    };
+
+    use super::Options;
+    use crate::{repository::path_filter::Spellings, OutputFormat};
+
+    /// Ambiguous paths of the path filter are reported to `err`.
+    pub fn tree(
+        mut repo: git::Repository,
+        old_treeish: &str,
+        new_treeish: &str,
+        mut out: impl std::io::Write,
+        err: impl std::io::Write,
+        Options {
+            format,
+            stats_only,
+            find_renames,
+            path_filter,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        repo.object_cache_size_if_unset(4 * 1024 * 1024);
+        let old = repo.rev_parse_single(old_treeish)?.object()?.peel_to_tree()?;
+        let new = repo.rev_parse_single(new_treeish)?.object()?.peel_to_tree()?;
+
+        let mut changes = Vec::new();
+        let mut spellings = Spellings::default();
+        old.changes()
+            .track_path()
+            .for_each_to_obtain_tree(&new, |change| -> Result<_, Infallible> {
+                use git::object::tree::diff::change::Event::*;
+                if let Some(filter) = &path_filter {
+                    if !filter.is_included(change.location) {
+                        return Ok(git::object::tree::diff::Action::Continue);
+                    }
+                    spellings.record(filter, change.location);
+                }
+                let path = change.location.to_owned();
+                match change.event {
+                    Addition { entry_mode, id } if entry_mode.is_no_tree() => changes.push(Change::Addition {
+                        path,
+                        mode: entry_mode,
+                        id: id.detach(),
+                    }),
+                    Deletion { entry_mode, id } if entry_mode.is_no_tree() => changes.push(Change::Deletion {
+                        path,
+                        mode: entry_mode,
+                        id: id.detach(),
+                    }),
+                    Modification {
+                        previous_entry_mode,
+                        previous_id,
+                        entry_mode,
+                        id,
+                    } if previous_entry_mode.is_no_tree() && entry_mode.is_no_tree() => {
+                        changes.push(Change::Modification {
+                            path,
+                            previous_mode: previous_entry_mode,
+                            previous_id: previous_id.detach(),
+                            mode: entry_mode,
+                            id: id.detach(),
+                        })
+                    }
+                    _ => {}
+                }
+                Ok(git::object::tree::diff::Action::Continue)
+            })?;
+        if let Some(filter) = &path_filter {
+            spellings.write_ambiguities(filter, err)?;
+        }
+        if find_renames {
+            changes = pair_exact_renames(changes);
+        }
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+
+        let stats = if stats_only {
+            changes
+                .iter()
+                .map(|change| line_counts(&repo, change))
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            Vec::new()
+        };
+
+        match format {
+            OutputFormat::Human => {
+                if stats_only {
+                    write_stats(&mut out, &changes, &stats)?;
+                } else {
+                    for change in &changes {
+                        match change {
+                            Change::Rename {
+                                previous_path, path, ..
+                            } => writeln!(out, "{}\t{}\t{}", change.status(), previous_path, path)?,
+                            _ => writeln!(out, "{}\t{}", change.status(), change.path())?,
+                        }
+                    }
+                }
+            }
+            OutputFormat::Porcelain { .. } => anyhow::bail!("Porcelain output isn't supported for diffs"),
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => {
+                let stats = stats.into_iter().map(Some).chain(std::iter::repeat(None));
+                serde_json::to_writer_pretty(
+                    &mut out,
+                    &changes
+                        .iter()
+                        .zip(stats)
+                        .map(|(change, counts)| json::Change::new(change, counts.flatten()))
+                        .collect::<Vec<_>>(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A change to a non-tree entry, with renames being detected after the fact.
+    enum Change {
+        Addition {
+            path: BString,
+            mode: EntryMode,
+            id: ObjectId,
+        },
+        Deletion {
+            path: BString,
+            mode: EntryMode,
+            id: ObjectId,
+        },
+        Modification {
+            path: BString,
+            previous_mode: EntryMode,
+            previous_id: ObjectId,
+            mode: EntryMode,
+            id: ObjectId,
+        },
+        /// A rename without changes to the content.
+        Rename {
+            previous_path: BString,
+            previous_mode: EntryMode,
+            path: BString,
+            mode: EntryMode,
+            id: ObjectId,
+        },
+    }
+
+    impl Change {
+        fn path(&self) -> &BStr {
+            match self {
+                Change::Addition { path, .. }
+                | Change::Deletion { path, .. }
+                | Change::Modification { path, .. }
+                | Change::Rename { path, .. } => path.as_ref(),
+            }
+        }
+
+        /// The status as shown by `git diff --name-status`.
+        fn status(&self) -> &'static str {
+            match self {
+                Change::Addition { .. } => "A",
+                Change::Deletion { .. } => "D",
+                Change::Modification {
+                    previous_mode, mode, ..
+                } if object_type(*previous_mode) != object_type(*mode) => "T",
+                Change::Modification { .. } => "M",
+                Change::Rename { .. } => "R100",
+            }
+        }
+
+        /// The previous and current version of the entry.
+        fn versions(&self) -> (Option<(EntryMode, ObjectId)>, Option<(EntryMode, ObjectId)>) {
+            match self {
+                Change::Addition { mode, id, .. } => (None, Some((*mode, *id))),
+                Change::Deletion { mode, id, .. } => (Some((*mode, *id)), None),
+                Change::Modification {
+                    previous_mode,
+                    previous_id,
+                    mode,
+                    id,
+                    ..
+                } => (Some((*previous_mode, *previous_id)), Some((*mode, *id))),
+                Change::Rename {
+                    previous_mode,
+                    mode,
+                    id,
+                    ..
+                } => (Some((*previous_mode, *id)), Some((*mode, *id))),
+            }
+        }
+    }
+
+    /// Changing between these types is a type change rather than a modification.
+    fn object_type(mode: EntryMode) -> u8 {
+        match mode {
+            EntryMode::Blob | EntryMode::BlobExecutable => 0,
+            EntryMode::Link => 1,
+            EntryMode::Commit => 2,
+            EntryMode::Tree => 3,
+        }
+    }
+
+    /// Turn each deletion and addition of the same object into a rename, with each addition being used at most once.
+    fn pair_exact_renames(changes: Vec<Change>) -> Vec<Change> {
+        let mut additions_by_id = HashMap::<_, Vec<_>>::new();
+        let mut others = Vec::new();
+        for change in changes {
+            match change {
+                Change::Addition { id, mode, path } if mode != EntryMode::Commit => {
+                    additions_by_id.entry(id).or_default().push((path, mode))
+                }
+                change => others.push(change),
+            }
+        }
+        for additions in additions_by_id.values_mut() {
+            // Pop from the back to pair additions with deletions in order of their paths.
+            additions.sort_by(|a, b| b.0.cmp(&a.0));
+        }
+        others.sort_by(|a, b| a.path().cmp(b.path()));
+
+        let mut out: Vec<_> = others
+            .into_iter()
+            .map(|change| match change {
+                Change::Deletion {
+                    path: previous_path,
+                    mode: previous_mode,
+                    id,
+                } => match additions_by_id.get_mut(&id).and_then(Vec::pop) {
+                    Some((path, mode)) => Change::Rename {
+                        previous_path,
+                        previous_mode,
+                        path,
+                        mode,
+                        id,
+                    },
+                    None => Change::Deletion {
+                        path: previous_path,
+                        mode: previous_mode,
+                        id,
+                    },
+                },
+                change => change,
+            })
+            .collect();
+        out.extend(additions_by_id.into_iter().flat_map(|(id, additions)| {
+            additions
+                .into_iter()
+                .map(move |(path, mode)| Change::Addition { path, mode, id })
+        }));
+        out
+    }
+
+    /// The amount of inserted and deleted lines.
+    #[derive(Default, Clone, Copy)]
+    struct LineCounts {
+        insertions: u32,
+        deletions: u32,
+    }
+
+    /// Count inserted and deleted lines of `change`, or return `None` if one of its versions is a binary blob or a submodule.
+    fn line_counts(repo: &git::Repository, change: &Change) -> anyhow::Result<Option<LineCounts>> {
+        let data = |version: Option<(EntryMode, ObjectId)>| -> anyhow::Result<Option<Vec<u8>>> {
+            Ok(match version {
+                None => Some(Vec::new()),
+                Some((EntryMode::Commit, _)) => None,
+                Some((_, id)) => {
+                    let object = repo.find_object(id)?;
+                    (!is_binary(&object.data)).then(|| object.detach().data)
+                }
+            })
+        };
+        let (previous, current) = change.versions();
+        if previous.map(|v| v.1) == current.map(|v| v.1) {
+            return Ok(Some(LineCounts::default()));
+        }
+        let (previous, current) = match (data(previous)?, data(current)?) {
+            (Some(previous), Some(current)) => (previous, current),
+            _ => return Ok(None),
+        };
+        let input = git::diff::blob::intern::InternedInput::new(previous.as_slice(), current.as_slice());
+        let counter = git::diff::blob::diff(
+            git::diff::blob::Algorithm::Myers,
+            &input,
+            git::diff::blob::sink::Counter::<()>::default(),
+        );
+        Ok(Some(LineCounts {
+            insertions: counter.insertions,
+            deletions: counter.removals,
+        }))
+    }
+
+    /// Like git, consider data binary if there is a null byte within the first 8000 bytes.
+    fn is_binary(data: &[u8]) -> bool {
+        data.iter().take(8000).any(|b| *b == 0)
+    }
+
+    fn write_stats(
+        out: &mut impl std::io::Write,
+        changes: &[Change],
+        stats: &[Option<LineCounts>],
+    ) -> std::io::Result<()> {
+        const MAX_GRAPH_WIDTH: u32 = 50;
+        let names: Vec<_> = changes
+            .iter()
+            .map(|change| match change {
+                Change::Rename {
+                    previous_path, path, ..
+                } => format!("{} => {}", previous_path, path),
+                _ => change.path().to_str_lossy().into_owned(),
+            })
+            .collect();
+        let totals: Vec<_> = stats
+            .iter()
+            .map(|counts| counts.map(|c| c.insertions + c.deletions))
+            .collect();
+        let name_width = names.iter().map(|n| n.chars().count()).max().unwrap_or_default();
+        let total_width = totals
+            .iter()
+            .map(|t| t.map_or(3, |t| t.to_string().len()))
+            .max()
+            .unwrap_or_default();
+        let max_total = totals.iter().flatten().copied().max().unwrap_or_default();
+        let scale = |n: u32| {
+            if max_total <= MAX_GRAPH_WIDTH || n == 0 {
+                n
+            } else {
+                ((n as u64 * MAX_GRAPH_WIDTH as u64 / max_total as u64) as u32).max(1)
+            }
+        };
+
+        let (mut insertions, mut deletions) = (0, 0);
+        for ((name, total), counts) in names.iter().zip(&totals).zip(stats) {
+            match (total, counts) {
+                (Some(total), Some(counts)) => {
+                    insertions += counts.insertions;
+                    deletions += counts.deletions;
+                    writeln!(
+                        out,
+                        " {:<name_width$} | {:>total_width$} {}{}",
+                        name,
+                        total,
+                        "+".repeat(scale(counts.insertions) as usize),
+                        "-".repeat(scale(counts.deletions) as usize),
+                        name_width = name_width,
+                        total_width = total_width
+                    )?
+                }
+                _ => writeln!(
+                    out,
+                    " {:<name_width$} | {:>total_width$}",
+                    name,
+                    "Bin",
+                    name_width = name_width,
+                    total_width = total_width
+                )?,
+            }
+        }
+
+        let plural = |n: u32| if n == 1 { "" } else { "s" };
+        write!(out, " {} file{} changed", changes.len(), plural(changes.len() as u32))?;
+        if insertions != 0 || deletions == 0 {
+            write!(out, ", {} insertion{}(+)", insertions, plural(insertions))?;
+        }
+        if deletions != 0 || insertions == 0 {
+            write!(out, ", {} deletion{}(-)", deletions, plural(deletions))?;
+        }
+        writeln!(out)
+    }
+
+    #[cfg(feature = "serde1")]
+    mod json {
+        use git_repository::{bstr::ByteSlice, objs::tree::EntryMode, ObjectId};
+
+        use super::LineCounts;
+
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "kebab-case")]
+        pub struct Change {
+            change: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            previous_path: Option<String>,
+            path: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            previous_id: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            id: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            previous_mode: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            mode: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            insertions: Option<u32>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            deletions: Option<u32>,
+        }
+
+        impl Change {
+            pub fn new(change: &super::Change, counts: Option<LineCounts>) -> Self {
+                let (previous, current) = change.versions();
+                let id = |version: Option<(EntryMode, ObjectId)>| version.map(|(_, id)| id.to_string());
+                let mode = |version: Option<(EntryMode, ObjectId)>| {
+                    version.map(|(mode, _)| mode.as_bytes().to_str_lossy().into_owned())
+                };
+                Change {
+                    change: match change {
+                        super::Change::Addition { .. } => "addition",
+                        super::Change::Deletion { .. } => "deletion",
+                        super::Change::Modification { .. } => "modification",
+                        super::Change::Rename { .. } => "rename",
+                    },
+                    previous_path: match change {
+                        super::Change::Rename { previous_path, .. } => Some(previous_path.to_str_lossy().into_owned()),
+                        _ => None,
+                    },
+                    path: change.path().to_str_lossy().into_owned(),
+                    previous_id: id(previous),
+                    id: id(current),
+                    previous_mode: mode(previous),
+                    mode: mode(current),
+                    insertions: counts.map(|c| c.insertions),
+                    deletions: counts.map(|c| c.deletions),
+                }
+            }
+        }
+    }
+}
@@ -29,7 +29,7 @@ pub fn query(
     }: query::Options,
 ) -> anyhow::Result<()> {
     if format != OutputFormat::Human {
-        bail!("JSON output isn't implemented yet");
+        bail!("Only human output is supported at the moment");
     }
 
     let worktree = repo
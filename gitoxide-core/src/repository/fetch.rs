@@ -40,7 +40,7 @@ pub(crate) mod function {
         P::SubProgress: 'static,
     {
         if format != OutputFormat::Human {
-            bail!("JSON output isn't yet supported for fetching.");
+            bail!("Only human output is supported for fetching at the moment.");
         }
 
         let mut remote = crate::repository::remote::by_name_or_url(&repo, remote.as_deref())?;
@@ -34,8 +34,8 @@ pub fn entries(
     #[cfg_attr(not(feature = "serde1"), allow(unused_variables))] out: impl io::Write,
     mut err: impl io::Write,
 ) -> anyhow::Result<()> {
-    if format == OutputFormat::Human {
-        writeln!(err, "Defaulting to JSON as human format isn't implemented").ok();
+    if matches!(format, OutputFormat::Human | OutputFormat::Porcelain { .. }) {
+        writeln!(err, "Defaulting to JSON as no other format is implemented").ok();
     }
 
     let mut mailmap = git::mailmap::Snapshot::default();
@@ -12,9 +12,11 @@ pub fn init(directory: Option<PathBuf>) -> Result<git::discover::repository::Pat
     .with_context(|| "Repository initialization failed")
 }
 
+pub mod bundle;
 pub mod commit;
 pub mod config;
 mod credential;
+pub mod diff;
 pub use credential::function as credential;
 #[cfg(feature = "blocking-client")]
 pub mod clone;
@@ -28,6 +30,8 @@ pub use fetch::function::fetch;
 pub mod index;
 pub mod mailmap;
 pub mod odb;
+mod path_filter;
+pub mod refs;
 pub mod remote;
 pub mod revision;
 pub mod tree;
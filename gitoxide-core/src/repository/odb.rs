@@ -1,9 +1,8 @@
-use std::io;
+use std::{io, sync::atomic::AtomicBool};
 
-use anyhow::bail;
 use git_repository as git;
 
-use crate::OutputFormat;
+use crate::{bail_if_interrupted, Interrupted, OutputFormat, Records};
 
 mod info {
     use std::path::PathBuf;
@@ -27,7 +26,7 @@ pub fn info(
     out: impl io::Write,
     mut err: impl io::Write,
 ) -> anyhow::Result<()> {
-    if format == OutputFormat::Human {
+    if matches!(format, OutputFormat::Human | OutputFormat::Porcelain { .. }) {
         writeln!(err, "Only JSON is implemented - using that instead")?;
     }
 
@@ -45,18 +44,1077 @@ pub fn info(
         serde_json::to_writer_pretty(out, &stats)?;
     }
 
+    print_warnings(store.warnings().iter(), err)?;
     Ok(())
 }
 
-pub fn entries(repo: git::Repository, format: OutputFormat, mut out: impl io::Write) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("Only human output format is supported at the moment");
-    }
+/// List the ids of all objects in the object database of `repo`, one per line, until `should_interrupt` is set.
+///
+/// The porcelain format has one record per object with its id as only column.
+pub fn entries(
+    repo: git::Repository,
+    format: OutputFormat,
+    mut out: impl io::Write,
+    err: impl io::Write,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    let nul_terminated = match format {
+        OutputFormat::Human => false,
+        OutputFormat::Porcelain { nul_terminated } => nul_terminated,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => anyhow::bail!("Only human and porcelain output formats are supported at the moment"),
+    };
 
-    for object in repo.objects.iter()? {
+    let mut records = Records::new(&mut out, nul_terminated);
+    let mut objects = repo.objects.iter()?;
+    for object in objects.by_ref() {
+        bail_if_interrupted(should_interrupt)?;
         let object = object?;
-        writeln!(out, "{}", object)?;
+        records.write(&[&object])?;
+    }
+
+    print_warnings(
+        repo.objects.store_ref().warnings().iter().chain(objects.warnings()),
+        err,
+    )?;
+    Ok(())
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct ObjectPaths {
+    id: git::ObjectId,
+    paths: Vec<String>,
+}
+
+/// Print all paths at which the objects identified by `specs` can be found in the tree of `rev`.
+///
+/// The porcelain format has one record per path with the columns `<id>` and `<path>`, and none for objects that weren't found.
+pub fn find_paths(
+    repo: git::Repository,
+    specs: Vec<String>,
+    rev: String,
+    format: OutputFormat,
+    mut out: impl io::Write,
+) -> anyhow::Result<()> {
+    let ids = specs
+        .iter()
+        .map(|spec| Ok(repo.rev_parse_single(spec.as_str())?.detach()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let root = repo.rev_parse_single(rev.as_str())?.detach();
+    let objects: Vec<_> = repo
+        .find_paths_of(&ids, root)?
+        .into_iter()
+        .map(|(id, paths)| ObjectPaths {
+            id,
+            paths: paths.into_iter().map(|path| path.to_string()).collect(),
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Human => {
+            for ObjectPaths { id, paths } in objects {
+                if paths.is_empty() {
+                    writeln!(out, "{} not found in {}", id, rev)?;
+                }
+                for path in paths {
+                    writeln!(out, "{} {}", id, path)?;
+                }
+            }
+        }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(out, nul_terminated);
+            for ObjectPaths { id, paths } in objects {
+                for path in paths {
+                    records.write(&[&id, &path])?;
+                }
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &objects)?,
+    }
+    Ok(())
+}
+
+/// Write the objects identified by `specs` as loose objects into `destination`, or into the object database of `repo` if unset.
+///
+/// The porcelain format has a single record with the columns `<objects written>`, `<bytes written>`, `<objects skipped>`
+/// and `<destination>`.
+pub fn explode(
+    repo: git::Repository,
+    specs: impl Iterator<Item = String>,
+    destination: Option<std::path::PathBuf>,
+    mut progress: impl git::Progress,
+    should_interrupt: &AtomicBool,
+    format: OutputFormat,
+    mut out: impl io::Write,
+) -> anyhow::Result<()> {
+    let ids = specs
+        .map(|spec| Ok(repo.rev_parse_single(spec.as_str())?.detach()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let destination = match destination {
+        Some(destination) => {
+            std::fs::create_dir_all(&destination)?;
+            destination
+        }
+        None => repo.objects.store_ref().path().to_owned(),
+    };
+    let dest = git::odb::loose::Store::at(destination, repo.object_hash());
+    let outcome = repo
+        .objects
+        .export_loose(ids.into_iter(), &dest, &mut progress, should_interrupt)
+        .map_err(|err| match err {
+            git::odb::store::export::Error::Interrupted => anyhow::Error::from(Interrupted),
+            err => err.into(),
+        })?;
+
+    match format {
+        OutputFormat::Human => writeln!(
+            out,
+            "wrote {} objects ({} bytes) to '{}', skipped {} existing objects",
+            outcome.objects_written,
+            outcome.bytes_written,
+            dest.path().display(),
+            outcome.objects_skipped
+        )?,
+        OutputFormat::Porcelain { nul_terminated } => Records::new(out, nul_terminated).write(&[
+            &outcome.objects_written,
+            &outcome.bytes_written,
+            &outcome.objects_skipped,
+            &dest.path().display(),
+        ])?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &outcome)?,
+    }
+    Ok(())
+}
+
+pub mod verify_new_packs {
+    use crate::OutputFormat;
+
+    /// The options for [`verify_new_packs()`][super::verify_new_packs()].
+    pub struct Context {
+        pub format: OutputFormat,
+        /// If set, don't use more than this amount of threads.
+        /// Otherwise, usually use as many threads as there are logical cores.
+        pub thread_limit: Option<usize>,
+        pub verify_mode: crate::pack::verify::Mode,
+        pub algorithm: crate::pack::verify::Algorithm,
     }
 
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub(crate) struct VerifiedPack {
+        pub path: std::path::PathBuf,
+        pub size: u64,
+    }
+}
+
+/// Verify all packs in the object database of `repo` which were added since the packs recorded in `state_file` were verified,
+/// and record them in `state_file` once all of them verified successfully. If `state_file` doesn't exist, all packs are verified.
+///
+/// Packs with an index of their own are verified entirely, whereas only the checksum of packs which are solely accessible
+/// through a multi-pack index is verified.
+///
+/// The porcelain format has one record per verified pack with the columns `<pack path>` and `<size in bytes>`.
+pub fn verify_new_packs(
+    repo: git::Repository,
+    state_file: std::path::PathBuf,
+    mut progress: impl git::Progress,
+    should_interrupt: &AtomicBool,
+    mut out: impl io::Write,
+    verify_new_packs::Context {
+        format,
+        thread_limit,
+        verify_mode,
+        algorithm,
+    }: verify_new_packs::Context,
+) -> anyhow::Result<()> {
+    use anyhow::Context;
+    use git::odb::{pack, store::packs_since::Marker};
+
+    let marker = match std::fs::read(&state_file) {
+        Ok(data) => Marker::from_bytes(&data)
+            .with_context(|| format!("Could not parse the state file at '{}'", state_file.display()))?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Marker::default(),
+        Err(err) => return Err(err.into()),
+    };
+    let store = repo.objects.store_ref();
+    let outcome = store.packs_since(&marker)?;
+    progress.init(Some(outcome.packs.len()), git::progress::count("packs"));
+
+    let mut verified = Vec::with_capacity(outcome.packs.len());
+    for new_pack in outcome.packs {
+        bail_if_interrupted(should_interrupt)?;
+        let index_path = new_pack.path.with_extension("idx");
+        let mut pack_progress = progress.add_child(new_pack.path.display().to_string());
+        if index_path.is_file() {
+            pack::Bundle::at(&index_path, repo.object_hash())?.verify_integrity(
+                &mut pack_progress,
+                should_interrupt,
+                pack::index::verify::integrity::Options {
+                    verify_mode,
+                    traversal: algorithm.into(),
+                    thread_limit,
+                    make_pack_lookup_cache: || pack::cache::Never,
+                    max_decompressed_object_size: Some(pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE),
+                    tree_checks: git::objs::tree::check::Options::strict(),
+                    keep_going: false,
+                    paranoid: false,
+                    strict_entry_boundaries: false,
+                },
+            )?;
+        } else {
+            pack::data::File::at(&new_pack.path, repo.object_hash())?
+                .verify_checksum(&mut pack_progress, should_interrupt)?;
+        }
+        progress.inc();
+        verified.push(verify_new_packs::VerifiedPack {
+            path: new_pack.path,
+            size: new_pack.size,
+        });
+    }
+    std::fs::write(&state_file, outcome.marker.to_bytes())
+        .with_context(|| format!("Could not write the state file at '{}'", state_file.display()))?;
+
+    match format {
+        OutputFormat::Human => {
+            for pack in &verified {
+                writeln!(out, "verified {} ({} bytes)", pack.path.display(), pack.size)?;
+            }
+            writeln!(out, "verified {} new packs", verified.len())?;
+        }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(out, nul_terminated);
+            for pack in &verified {
+                records.write(&[&pack.path.display(), &pack.size])?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &verified)?,
+    }
+    Ok(())
+}
+
+pub mod size_histogram {
+    use crate::OutputFormat;
+
+    /// The options for [`size_histogram()`][super::size_histogram()].
+    pub struct Options {
+        pub format: OutputFormat,
+    }
+
+    /// The sizes of a group of objects or entries, bucketed by powers of two.
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Histogram {
+        /// The name of the group, which is an object kind or `compressed` for the size of entries on disk.
+        pub name: &'static str,
+        pub count: u64,
+        pub total_bytes: u64,
+        pub p50: u64,
+        pub p90: u64,
+        pub p99: u64,
+        pub max: u64,
+        /// The amount of sizes per bucket, where the bucket at index 0 counts empty objects and each bucket at index `n > 0`
+        /// counts sizes in the range `2^(n-1)..2^n`.
+        pub buckets: Vec<u64>,
+    }
+
+    /// Bucket index of `size`, see [`Histogram::buckets`].
+    pub(crate) fn bucket_of(size: u64) -> usize {
+        (64 - size.leading_zeros()) as usize
+    }
+
+    pub(crate) fn bucket_label(bucket: usize) -> String {
+        match bucket {
+            0 => "0".into(),
+            1 => "1".into(),
+            n => format!("{}-{}", 1u64 << (n - 1), (1u128 << n) - 1),
+        }
+    }
+
+    impl Histogram {
+        pub(crate) fn from_sizes(name: &'static str, mut sizes: Vec<u64>) -> Self {
+            let mut buckets = Vec::new();
+            for size in &sizes {
+                let bucket = bucket_of(*size);
+                if buckets.len() <= bucket {
+                    buckets.resize(bucket + 1, 0);
+                }
+                buckets[bucket] += 1;
+            }
+            let mut percentile = |p: usize| {
+                if sizes.is_empty() {
+                    return 0;
+                }
+                let rank = ((sizes.len() * p + 99) / 100).max(1) - 1;
+                *sizes.select_nth_unstable(rank).1
+            };
+            Histogram {
+                name,
+                p50: percentile(50),
+                p90: percentile(90),
+                p99: percentile(99),
+                count: sizes.len() as u64,
+                total_bytes: sizes.iter().sum(),
+                max: sizes.iter().copied().max().unwrap_or_default(),
+                buckets,
+            }
+        }
+    }
+}
+
+/// Print a histogram of the sizes of all objects in the object database of `repo` per object kind, along with one of the size
+/// of their compressed entries on disk, to learn how object sizes are distributed.
+///
+/// Sizes are obtained from the headers of pack entries and loose objects, and for deltified objects from the delta header,
+/// which makes this fast as objects don't have to be decoded.
+///
+/// The porcelain format has one record per non-empty bucket with the columns `<name>`, `<smallest size>`, `<largest size>`
+/// and `<count>`, where the name is the object kind or `compressed`.
+pub fn size_histogram(
+    repo: git::Repository,
+    mut progress: impl git::Progress,
+    should_interrupt: &AtomicBool,
+    mut out: impl io::Write,
+    size_histogram::Options { format }: size_histogram::Options,
+) -> anyhow::Result<()> {
+    use git::{
+        objs::Kind,
+        odb::{pack, store::structure::Record},
+    };
+
+    let store = repo.objects.store_ref();
+    let structure = store.structure()?;
+    let mut object_sizes: [Vec<u64>; 4] = Default::default();
+    let mut compressed_sizes = Vec::new();
+    let mut record = |kind: Kind, size: u64, compressed_size: u64| {
+        object_sizes[match kind {
+            Kind::Commit => 0,
+            Kind::Tree => 1,
+            Kind::Blob => 2,
+            Kind::Tag => 3,
+        }]
+        .push(size);
+        compressed_sizes.push(compressed_size);
+    };
+
+    progress.init(None, git::progress::count("objects"));
+    for record_or_db in &structure {
+        match record_or_db {
+            Record::LooseObjectDatabase { objects_directory, .. } => {
+                let db = git::odb::loose::Store::at(objects_directory, repo.object_hash());
+                for id in db.iter() {
+                    bail_if_interrupted(should_interrupt)?;
+                    if let Some(header) = db.try_header(id?)? {
+                        record(header.kind, header.size, header.compressed_size);
+                    }
+                    progress.inc();
+                }
+            }
+            Record::Index { path, .. } => {
+                let index = pack::index::File::at(path, repo.object_hash())?;
+                let data = pack::data::File::at(path.with_extension("pack"), repo.object_hash())?;
+                let resolve = |id: &git::hash::oid| index.lookup(id).map(|idx| index.pack_offset_at_index(idx));
+                record_pack_entries(
+                    &repo,
+                    &data,
+                    index.iter().map(|e| e.pack_offset).collect(),
+                    resolve,
+                    &mut record,
+                    &mut progress,
+                    should_interrupt,
+                )?;
+            }
+            Record::MultiIndex { path, .. } => {
+                let index = pack::multi_index::File::at(path)?;
+                let pack_dir = path.parent().expect("multi-index is in a directory");
+                for (pack_id, index_name) in index.index_names().iter().enumerate() {
+                    let pack_id = pack_id as pack::multi_index::PackIndex;
+                    let data =
+                        pack::data::File::at(pack_dir.join(index_name).with_extension("pack"), repo.object_hash())?;
+                    let offsets = index
+                        .iter()
+                        .filter(|e| e.pack_index == pack_id)
+                        .map(|e| e.pack_offset)
+                        .collect();
+                    let resolve = |id: &git::hash::oid| {
+                        index
+                            .lookup(id)
+                            .map(|idx| index.pack_id_and_pack_offset_at_index(idx))
+                            .and_then(|(id, offset)| (id == pack_id).then(|| offset))
+                    };
+                    record_pack_entries(
+                        &repo,
+                        &data,
+                        offsets,
+                        resolve,
+                        &mut record,
+                        &mut progress,
+                        should_interrupt,
+                    )?;
+                }
+            }
+            Record::Empty => {}
+        }
+    }
+
+    let [commit, tree, blob, tag] = object_sizes;
+    let histograms = vec![
+        size_histogram::Histogram::from_sizes("commit", commit),
+        size_histogram::Histogram::from_sizes("tree", tree),
+        size_histogram::Histogram::from_sizes("blob", blob),
+        size_histogram::Histogram::from_sizes("tag", tag),
+        size_histogram::Histogram::from_sizes("compressed", compressed_sizes),
+    ];
+
+    match format {
+        OutputFormat::Human => {
+            let num_buckets = histograms.iter().map(|h| h.buckets.len()).max().unwrap_or_default();
+            let mut rows = vec![std::iter::once("size".to_string())
+                .chain(histograms.iter().map(|h| h.name.to_string()))
+                .collect::<Vec<_>>()];
+            for bucket in 0..num_buckets {
+                rows.push(
+                    std::iter::once(size_histogram::bucket_label(bucket))
+                        .chain(
+                            histograms
+                                .iter()
+                                .map(|h| h.buckets.get(bucket).copied().unwrap_or_default().to_string()),
+                        )
+                        .collect(),
+                );
+            }
+            for name in ["count", "total", "p50", "p90", "p99", "max"] {
+                rows.push(
+                    std::iter::once(name.to_string())
+                        .chain(histograms.iter().map(|h| {
+                            match name {
+                                "count" => h.count,
+                                "total" => h.total_bytes,
+                                "p50" => h.p50,
+                                "p90" => h.p90,
+                                "p99" => h.p99,
+                                _ => h.max,
+                            }
+                            .to_string()
+                        }))
+                        .collect(),
+                );
+            }
+            let widths: Vec<_> = (0..rows[0].len())
+                .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or_default())
+                .collect();
+            for row in rows {
+                let mut line = format!("{:<width$}", row[0], width = widths[0]);
+                for (cell, width) in row.iter().zip(&widths).skip(1) {
+                    line.push_str(&format!("  {:>width$}", cell, width = width));
+                }
+                writeln!(out, "{}", line)?;
+            }
+        }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(out, nul_terminated);
+            for histogram in &histograms {
+                for (bucket, count) in histogram.buckets.iter().enumerate().filter(|(_, count)| **count != 0) {
+                    let (smallest, largest) = match bucket {
+                        0 => (0, 0),
+                        n => (1u64 << (n - 1), ((1u128 << n) - 1) as u64),
+                    };
+                    records.write(&[&histogram.name, &smallest, &largest, count])?;
+                }
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &histograms)?,
+    }
+    Ok(())
+}
+
+/// Record the object and entry sizes of all entries at `offsets` in `pack`, using `resolve` to find the offset of ref-delta bases.
+pub mod stats {
+    use crate::OutputFormat;
+
+    /// The options for [`stats()`][super::stats()].
+    pub struct Options {
+        pub format: OutputFormat,
+        /// If set, read all objects and print the decode statistics of each pack gathered while doing so.
+        pub runtime: bool,
+        /// If set, print how many of the objects reachable from this revision could be copied verbatim from each pack.
+        pub reuse: Option<String>,
+    }
+
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Statistics {
+        pub metrics: git_repository::odb::store::Metrics,
+        /// The decode statistics per pack, if they were gathered.
+        pub packs: Option<Vec<git_repository::odb::store::PackDecodeStatistics>>,
+        /// The potential reuse of packed objects, if it was determined.
+        pub reuse: Option<Reuse>,
+    }
+
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Reuse {
+        /// The amount of objects reachable from the revision.
+        pub num_wanted: usize,
+        /// The objects which could be copied verbatim from each pack.
+        pub packs: Vec<PackReuse>,
+    }
+
+    impl Reuse {
+        /// The amount of objects which could be copied verbatim, across all packs.
+        pub fn num_reusable_objects(&self) -> usize {
+            self.packs
+                .iter()
+                .map(|pack| pack.plan.num_reusable_objects() as usize)
+                .sum()
+        }
+
+        /// The percentage of wanted objects which could be copied verbatim.
+        pub fn percentage(&self) -> f32 {
+            if self.num_wanted == 0 {
+                return 0.0;
+            }
+            self.num_reusable_objects() as f32 / self.num_wanted as f32 * 100.0
+        }
+    }
+
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct PackReuse {
+        /// The path to the pack data file.
+        pub path: std::path::PathBuf,
+        pub plan: git_repository::odb::pack::index::verbatim_reusable::Plan,
+    }
+}
+
+/// Print the metrics of the object database of `repo`, and with `runtime` set, read all objects to print how many objects,
+/// deltas and delta-base cache hits and misses each pack saw, to learn how effective the pack cache is.
+/// With `reuse` set, print how many of the objects reachable from the given revision could be copied verbatim from
+/// existing packs when sending them, instead of being recompressed.
+///
+/// The porcelain format has one record per metric with the columns `<name>` and `<value>`, followed by one record per pack
+/// with the columns `pack`, `<path>`, `<objects>`, `<deltas>`, `<cache hits>`, `<cache misses>` and `<inflated bytes>`.
+/// Reuse is printed as one record per pack with the columns `reuse`, `<path>`, `<wanted objects>`, `<reusable objects>`,
+/// `<ranges>` and `<reusable bytes>`, followed by a record with the columns `reuse-total`, `<wanted objects>` and
+/// `<reusable objects>`.
+pub fn stats(
+    repo: git::Repository,
+    mut progress: impl git::Progress,
+    should_interrupt: &AtomicBool,
+    mut out: impl io::Write,
+    err: impl io::Write,
+    stats::Options { format, runtime, reuse }: stats::Options,
+) -> anyhow::Result<()> {
+    use git::odb::Find;
+
+    let packs = if runtime {
+        let mut objects = repo.objects.clone();
+        objects.collect_pack_decode_statistics(true);
+        let mut buf = Vec::new();
+        progress.init(None, git::progress::count("objects"));
+        for id in repo.objects.iter()? {
+            bail_if_interrupted(should_interrupt)?;
+            objects.try_find(id?, &mut buf)?;
+            progress.inc();
+        }
+        objects.pack_decode_statistics()
+    } else {
+        None
+    };
+    let reuse = reuse
+        .map(|spec| potential_reuse(&repo, &spec, &mut progress, should_interrupt))
+        .transpose()?;
+    let stats = stats::Statistics {
+        metrics: repo.objects.store_ref().metrics(),
+        packs,
+        reuse,
+    };
+
+    let git::odb::store::Metrics {
+        num_handles,
+        num_pinned_handles,
+        num_refreshes,
+        open_reachable_indices,
+        known_reachable_indices,
+        open_reachable_packs,
+        known_packs,
+        unused_slots,
+        unreachable_indices,
+        unreachable_packs,
+        loose_dbs,
+        extra_alternates,
+        orphaned_indices,
+        kept_packs,
+        promisor_packs,
+        untrusted_object_directories,
+    } = stats.metrics;
+    let metrics = [
+        ("num_handles", num_handles),
+        ("num_pinned_handles", num_pinned_handles),
+        ("num_refreshes", num_refreshes),
+        ("open_reachable_indices", open_reachable_indices),
+        ("known_reachable_indices", known_reachable_indices),
+        ("open_reachable_packs", open_reachable_packs),
+        ("known_packs", known_packs),
+        ("unused_slots", unused_slots),
+        ("unreachable_indices", unreachable_indices),
+        ("unreachable_packs", unreachable_packs),
+        ("loose_dbs", loose_dbs),
+        ("extra_alternates", extra_alternates),
+        ("orphaned_indices", orphaned_indices),
+        ("kept_packs", kept_packs),
+        ("promisor_packs", promisor_packs),
+        ("untrusted_object_directories", untrusted_object_directories),
+    ];
+    match format {
+        OutputFormat::Human => {
+            for (name, value) in metrics {
+                writeln!(out, "{}: {}", name.replace('_', " "), value)?;
+            }
+            for pack in stats.packs.iter().flatten() {
+                writeln!(out, "\n{}", pack.path.display())?;
+                writeln!(out, "  objects: {}", pack.objects)?;
+                writeln!(out, "  deltas: {}", pack.deltas)?;
+                writeln!(out, "  cache hits: {}", pack.cache_hits)?;
+                writeln!(out, "  cache misses: {}", pack.cache_misses)?;
+                writeln!(out, "  inflated: {}", bytesize::ByteSize(pack.inflated_bytes))?;
+            }
+            if let Some(reuse) = &stats.reuse {
+                for pack in &reuse.packs {
+                    writeln!(out, "\n{}", pack.path.display())?;
+                    writeln!(out, "  wanted objects: {}", pack.plan.num_wanted)?;
+                    writeln!(out, "  reusable objects: {}", pack.plan.num_reusable_objects())?;
+                    writeln!(out, "  reusable ranges: {}", pack.plan.ranges.len())?;
+                    writeln!(
+                        out,
+                        "  reusable bytes: {}",
+                        bytesize::ByteSize(pack.plan.num_reusable_bytes())
+                    )?;
+                }
+                writeln!(
+                    out,
+                    "\npotential reuse: {} of {} objects ({:.1}%)",
+                    reuse.num_reusable_objects(),
+                    reuse.num_wanted,
+                    reuse.percentage()
+                )?;
+            }
+        }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(&mut out, nul_terminated);
+            for (name, value) in metrics {
+                records.write(&[&name, &value])?;
+            }
+            for pack in stats.packs.iter().flatten() {
+                records.write(&[
+                    &"pack",
+                    &pack.path.display(),
+                    &pack.objects,
+                    &pack.deltas,
+                    &pack.cache_hits,
+                    &pack.cache_misses,
+                    &pack.inflated_bytes,
+                ])?;
+            }
+            if let Some(reuse) = &stats.reuse {
+                for pack in &reuse.packs {
+                    records.write(&[
+                        &"reuse",
+                        &pack.path.display(),
+                        &pack.plan.num_wanted,
+                        &pack.plan.num_reusable_objects(),
+                        &pack.plan.ranges.len(),
+                        &pack.plan.num_reusable_bytes(),
+                    ])?;
+                }
+                records.write(&[&"reuse-total", &reuse.num_wanted, &reuse.num_reusable_objects()])?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &stats)?,
+    }
+
+    print_warnings(repo.objects.store_ref().warnings().iter(), err)?;
+    Ok(())
+}
+
+pub mod reachability {
+    use std::time::SystemTime;
+
+    use crate::OutputFormat;
+
+    /// The options for [`reachability()`][super::reachability()].
+    pub struct Options {
+        pub format: OutputFormat,
+        /// If set, don't use more than this amount of threads to traverse trees.
+        /// Otherwise, usually use as many threads as there are logical cores.
+        /// A value of 0 is interpreted as no-limit
+        pub thread_limit: Option<usize>,
+        /// Unreachable objects modified at or after this time are recent, similar to `gc.pruneExpire`.
+        pub expire: SystemTime,
+    }
+
+    /// The amount of objects in one reachability class, along with the amount of bytes they occupy on disk.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Bucket {
+        pub count: u64,
+        pub bytes: u64,
+    }
+
+    /// All objects of an object database by reachability class.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Statistics {
+        /// Objects reachable from `HEAD` or any reference.
+        pub reachable: Bucket,
+        /// Unreachable objects modified at or after the expiry time, which pruning would keep.
+        pub recent_unreachable: Bucket,
+        /// Unreachable objects modified before the expiry time, which pruning would remove.
+        pub unreachable: Bucket,
+    }
+
+    impl Statistics {
+        pub(crate) fn record(&mut self, reachable: bool, modified: SystemTime, expire: SystemTime, bytes: u64) {
+            let bucket = if reachable {
+                &mut self.reachable
+            } else if modified >= expire {
+                &mut self.recent_unreachable
+            } else {
+                &mut self.unreachable
+            };
+            bucket.count += 1;
+            bucket.bytes += bytes;
+        }
+    }
+}
+
+/// Print how many objects of `repo`, and how many bytes on disk, are reachable from `HEAD` or any reference, unreachable but
+/// modified at or after `expire`, and unreachable and older, to learn what pruning unreachable objects would gain.
+///
+/// Reachable objects are found like in [`connectivity()`][super::verify::connectivity()] and are the only objects kept in memory.
+/// All other objects are classified while iterating the object database. Loose objects use the modification time of
+/// their file, and packed objects use the one of their pack as an approximation. Objects stored more than once are counted
+/// once per copy, and the bytes of packed objects are the size of their possibly deltified entry in the pack.
+///
+/// The porcelain format has one record per class with the columns `<class>`, `<objects>` and `<bytes>`, where the class
+/// is one of `reachable`, `recent-unreachable` or `unreachable`.
+pub fn reachability(
+    repo: git::Repository,
+    mut progress: impl git::Progress,
+    should_interrupt: &AtomicBool,
+    mut out: impl io::Write,
+    reachability::Options {
+        format,
+        thread_limit,
+        expire,
+    }: reachability::Options,
+) -> anyhow::Result<()> {
+    use git::{
+        odb::{pack, store::structure::Record},
+        Progress,
+    };
+
+    let (_, reachable) =
+        super::verify::connectivity_impl::walk(&repo, &mut progress, should_interrupt, thread_limit, false, false)?;
+
+    let mut stats = reachability::Statistics::default();
+    let mut object_progress = progress.add_child("classify");
+    object_progress.init(None, git::progress::count("objects"));
+    let hash_len = repo.object_hash().len_in_bytes() as u64;
+    for record_or_db in &repo.objects.store_ref().structure()? {
+        match record_or_db {
+            Record::LooseObjectDatabase { objects_directory, .. } => {
+                let db = git::odb::loose::Store::at(objects_directory, repo.object_hash());
+                for id in db.iter() {
+                    bail_if_interrupted(should_interrupt)?;
+                    let id = id?;
+                    let hex = id.to_hex().to_string();
+                    let metadata = match std::fs::metadata(objects_directory.join(&hex[..2]).join(&hex[2..])) {
+                        Ok(metadata) => metadata,
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                        Err(err) => return Err(err.into()),
+                    };
+                    stats.record(reachable.contains(&id), metadata.modified()?, expire, metadata.len());
+                    object_progress.inc();
+                }
+            }
+            Record::Index { path, .. } => {
+                let index = pack::index::File::at(path, repo.object_hash())?;
+                let pack = std::fs::metadata(path.with_extension("pack"))?;
+                let (modified, pack_end) = (pack.modified()?, pack.len().saturating_sub(hash_len));
+                let offsets = index.sorted_offsets();
+                for entry in index.iter() {
+                    bail_if_interrupted(should_interrupt)?;
+                    let bytes = entry_size(&offsets, entry.pack_offset, pack_end);
+                    stats.record(reachable.contains(&entry.oid), modified, expire, bytes);
+                    object_progress.inc();
+                }
+            }
+            Record::MultiIndex { path, .. } => {
+                let index = pack::multi_index::File::at(path)?;
+                let pack_dir = path.parent().expect("multi-index is in a directory");
+                let mut packs = Vec::with_capacity(index.index_names().len());
+                for index_name in index.index_names() {
+                    let pack = std::fs::metadata(pack_dir.join(index_name).with_extension("pack"))?;
+                    packs.push((pack.modified()?, pack.len().saturating_sub(hash_len), Vec::new()));
+                }
+                for entry in index.iter() {
+                    packs[entry.pack_index as usize].2.push(entry.pack_offset);
+                }
+                for (_, _, offsets) in &mut packs {
+                    offsets.sort_unstable();
+                }
+                for entry in index.iter() {
+                    bail_if_interrupted(should_interrupt)?;
+                    let (modified, pack_end, offsets) = &packs[entry.pack_index as usize];
+                    let bytes = entry_size(offsets, entry.pack_offset, *pack_end);
+                    stats.record(reachable.contains(&entry.oid), *modified, expire, bytes);
+                    object_progress.inc();
+                }
+            }
+            Record::Empty => {}
+        }
+    }
+    object_progress.show_throughput(std::time::Instant::now());
+
+    let classes = [
+        ("reachable", stats.reachable),
+        ("recent-unreachable", stats.recent_unreachable),
+        ("unreachable", stats.unreachable),
+    ];
+    match format {
+        OutputFormat::Human => {
+            for (name, bucket) in classes {
+                writeln!(
+                    out,
+                    "{:<18}  {:>10} objects  {:>14} bytes",
+                    name, bucket.count, bucket.bytes
+                )?;
+            }
+        }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(out, nul_terminated);
+            for (name, bucket) in classes {
+                records.write(&[&name, &bucket.count, &bucket.bytes])?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &stats)?,
+    }
+    Ok(())
+}
+
+pub mod prune {
+    use std::time::SystemTime;
+
+    use crate::OutputFormat;
+
+    /// The options for [`prune()`][super::prune()].
+    pub struct Options {
+        pub format: OutputFormat,
+        /// If set, don't use more than this amount of threads to traverse trees.
+        /// Otherwise, usually use as many threads as there are logical cores.
+        /// A value of 0 is interpreted as no-limit
+        pub thread_limit: Option<usize>,
+        /// Unreachable loose objects modified at or after this time are kept, similar to `gc.pruneExpire`.
+        pub expire: SystemTime,
+        /// If true, only print which objects would be deleted.
+        pub dry_run: bool,
+        /// If true, prune even if no object is reachable, or if reachable objects are missing.
+        pub force: bool,
+    }
+}
+
+/// Delete all loose objects of `repo` which are neither reachable from `HEAD`, any reference, their reflogs or the worktree
+/// index, nor modified at or after `expire`, and print their paths along with a summary.
+///
+/// Reachable objects are found like in [`connectivity()`][super::verify::connectivity()], and loose objects of alternates
+/// are never deleted. Nothing is deleted if reachable objects are missing, unless `force` is set.
+///
+/// The porcelain format has one record per deleted object with its path as only column.
+pub fn prune(
+    repo: git::Repository,
+    mut progress: impl git::Progress,
+    should_interrupt: &AtomicBool,
+    mut out: impl io::Write,
+    prune::Options {
+        format,
+        thread_limit,
+        expire,
+        dry_run,
+        force,
+    }: prune::Options,
+) -> anyhow::Result<()> {
+    let (walk, reachable) =
+        super::verify::connectivity_impl::walk(&repo, &mut progress, should_interrupt, thread_limit, true, true)?;
+    bail_if_interrupted(should_interrupt)?;
+    if !walk.missing.is_empty() && !force {
+        anyhow::bail!(
+            "Refusing to prune as {} reachable objects are missing, hiding what they refer to - use --force to prune anyway",
+            walk.missing.len()
+        )
+    }
+    let outcome = repo.objects.store_ref().prune_loose(
+        &reachable.into_existing(),
+        git::odb::store::prune::Options {
+            expire: std::time::SystemTime::now().duration_since(expire).unwrap_or_default(),
+            dry_run,
+            force,
+        },
+    )?;
+
+    match format {
+        OutputFormat::Human => {
+            for path in &outcome.deleted {
+                writeln!(out, "{}", path.display())?;
+            }
+            writeln!(
+                out,
+                "{} {} unreachable objects with {} bytes, and kept {} reachable and {} recent unreachable objects",
+                if dry_run { "Would delete" } else { "Deleted" },
+                outcome.deleted.len(),
+                outcome.reclaimed_bytes,
+                outcome.reachable,
+                outcome.recent_unreachable
+            )?;
+        }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(out, nul_terminated);
+            for path in &outcome.deleted {
+                records.write(&[&path.display()])?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct Summary {
+                dry_run: bool,
+                #[serde(flatten)]
+                outcome: git::odb::store::prune::Outcome,
+            }
+            serde_json::to_writer_pretty(out, &Summary { dry_run, outcome })?;
+        }
+    }
+    Ok(())
+}
+
+/// The size of the pack entry at `offset`, given the `sorted_offsets` of all entries in its pack which ends at `pack_end`.
+fn entry_size(sorted_offsets: &[u64], offset: u64, pack_end: u64) -> u64 {
+    let next_offset = match sorted_offsets.binary_search(&offset) {
+        Ok(idx) => sorted_offsets.get(idx + 1).copied(),
+        Err(_) => None,
+    };
+    next_offset.unwrap_or(pack_end).saturating_sub(offset)
+}
+
+/// Determine which of the objects reachable from `spec` could be copied verbatim from each pack of `repo`.
+fn potential_reuse(
+    repo: &git::Repository,
+    spec: &str,
+    progress: &mut impl git::Progress,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<stats::Reuse> {
+    use git::odb::{pack, store::structure::Record};
+
+    let tip = repo
+        .rev_parse_single(spec)?
+        .object()?
+        .peel_to_kind(git::objs::Kind::Commit)?
+        .id;
+    let mut commits = Vec::new();
+    for commit in repo.rev_walk(Some(tip)).all()? {
+        bail_if_interrupted(should_interrupt)?;
+        commits.push(commit?.detach());
+    }
+    let mut handle = repo.objects.clone().into_arc()?;
+    handle.prevent_pack_unload();
+    let (counts, _) = pack::data::output::count::objects_unthreaded(
+        handle,
+        commits.into_iter().map(Ok::<_, std::convert::Infallible>),
+        progress.add_child("counting"),
+        should_interrupt,
+        None,
+        pack::data::output::count::objects::Options {
+            input_object_expansion: pack::data::output::count::objects::ObjectExpansion::TreeContents,
+            ..Default::default()
+        },
+    )?;
+    let mut wants: Vec<_> = counts.into_iter().map(|count| count.id).collect();
+    wants.sort();
+    wants.dedup();
+
+    let mut packs = Vec::new();
+    let mut add_pack = |index_path: &std::path::Path| -> anyhow::Result<()> {
+        bail_if_interrupted(should_interrupt)?;
+        let index = pack::index::File::at(index_path, repo.object_hash())?;
+        let data = pack::data::File::at(index_path.with_extension("pack"), repo.object_hash())?;
+        packs.push(stats::PackReuse {
+            plan: index.verbatim_reusable(&data, &wants)?,
+            path: data.path().to_owned(),
+        });
+        Ok(())
+    };
+    for record in repo.objects.store_ref().structure()? {
+        match record {
+            Record::Index { path, .. } => add_pack(&path)?,
+            Record::MultiIndex { path, .. } => {
+                let index = pack::multi_index::File::at(&path)?;
+                let pack_dir = path.parent().expect("multi-index is in a directory");
+                for index_name in index.index_names() {
+                    add_pack(&pack_dir.join(index_name))?;
+                }
+            }
+            Record::LooseObjectDatabase { .. } | Record::Empty => {}
+        }
+    }
+    Ok(stats::Reuse {
+        num_wanted: wants.len(),
+        packs,
+    })
+}
+
+fn record_pack_entries(
+    repo: &git::Repository,
+    pack: &git::odb::pack::data::File,
+    mut offsets: Vec<u64>,
+    resolve: impl Fn(&git::hash::oid) -> Option<u64>,
+    record: &mut impl FnMut(git::objs::Kind, u64, u64),
+    progress: &mut impl git::Progress,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    use git::odb::pack::data::decode_header::ResolvedBase;
+
+    offsets.sort_unstable();
+    let pack_end = pack.pack_end() as u64;
+    for (idx, offset) in offsets.iter().enumerate() {
+        bail_if_interrupted(should_interrupt)?;
+        let next_offset = offsets.get(idx + 1).copied().unwrap_or(pack_end);
+        let unreadable_base = std::cell::RefCell::new(None);
+        let header = pack
+            .decode_header(pack.try_entry(*offset)?, |id| match resolve(id) {
+                Some(offset) => pack
+                    .try_entry(offset)
+                    .map_err(|err| *unreadable_base.borrow_mut() = Some(err))
+                    .ok()
+                    .map(ResolvedBase::InPack),
+                None => repo
+                    .try_find_object(id.to_owned())
+                    .ok()
+                    .flatten()
+                    .map(|obj| ResolvedBase::OutOfPack { kind: obj.kind }),
+            })
+            .map_err(|err| unreadable_base.take().unwrap_or(err))?;
+        record(header.kind, header.object_size, next_offset - offset);
+        progress.inc();
+    }
+    Ok(())
+}
+
+fn print_warnings<'a>(
+    warnings: impl Iterator<Item = &'a git::odb::store::Warning>,
+    mut err: impl io::Write,
+) -> io::Result<()> {
+    for warning in warnings {
+        writeln!(err, "WARNING: {}", warning)?;
+    }
     Ok(())
 }
@@ -0,0 +1,39 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use git::{
+    bstr::{BStr, BString},
+    odb::pack::data::output::count::objects::PathFilter,
+};
+use git_repository as git;
+
+/// Collects how the paths matching each prefix of a [`PathFilter`] are spelled, to be able to report prefixes which matched
+/// more than one path as case or unicode normalization were ignored.
+#[derive(Default)]
+pub(crate) struct Spellings(BTreeMap<usize, BTreeSet<BString>>);
+
+impl Spellings {
+    /// Record the portion of the included `path` that matched a prefix of `filter`, if it could match differently spelled paths.
+    pub fn record(&mut self, filter: &PathFilter, path: &BStr) {
+        if !(filter.ignores_case() || filter.ignores_normalization()) {
+            return;
+        }
+        if let Some((idx, portion)) = filter.matching_portion(path) {
+            let spellings = self.0.entry(idx).or_default();
+            if !spellings.contains(portion) {
+                spellings.insert(portion.to_owned());
+            }
+        }
+    }
+
+    /// Write a warning for each prefix of `filter` which matched more than one path to `err`.
+    pub fn write_ambiguities(&self, filter: &PathFilter, mut err: impl std::io::Write) -> std::io::Result<()> {
+        for (idx, spellings) in self.0.iter().filter(|(_, spellings)| spellings.len() > 1) {
+            write!(err, "WARNING: '{}' is ambiguous as it matches", filter.prefixes()[*idx])?;
+            for (num, spelling) in spellings.iter().enumerate() {
+                write!(err, "{} '{}'", if num == 0 { "" } else { "," }, spelling)?;
+            }
+            writeln!(err)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+pub mod verify {
+    use std::{collections::BTreeMap, sync::atomic::AtomicBool};
+
+    use anyhow::bail;
+    use git_repository as git;
+    use git_repository::{bstr::ByteSlice, Progress};
+
+    use crate::{bail_if_interrupted, OutputFormat, Records};
+
+    pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+    /// The maximum amount of symbolic references to follow, the same as git's `SYMREF_MAXDEPTH`.
+    const MAX_SYMBOLIC_DEPTH: usize = 5;
+
+    /// The reason a reference is considered dangling.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub enum Problem {
+        /// The object the reference points to doesn't exist.
+        MissingObject,
+        /// The reference points to an annotated tag whose chain of targets ends in an object that doesn't exist.
+        MissingTagTarget,
+        /// A symbolic reference points to a reference that doesn't exist.
+        MissingReference,
+        /// Symbolic references point at each other in a cycle, or the chain of them is too long to be followed.
+        SymbolicReferenceCycle,
+        /// `HEAD` or a branch doesn't point to a commit after peeling annotated tags.
+        NotACommit {
+            /// The kind of object pointed to instead.
+            actual: git::object::Kind,
+        },
+    }
+
+    impl std::fmt::Display for Problem {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Problem::MissingObject => f.write_str("missing object"),
+                Problem::MissingTagTarget => f.write_str("missing tag target"),
+                Problem::MissingReference => f.write_str("missing reference"),
+                Problem::SymbolicReferenceCycle => f.write_str("symbolic reference cycle"),
+                Problem::NotACommit { actual } => write!(f, "{} instead of commit", actual),
+            }
+        }
+    }
+
+    /// A reference which can't be resolved to an existing object of the expected kind.
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Dangling {
+        /// The full name of the reference.
+        pub name: String,
+        /// The object id or the name of the reference at which resolution stopped.
+        pub target: String,
+        /// What's wrong with the reference.
+        pub problem: Problem,
+    }
+
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    struct Summary {
+        references_checked: usize,
+        dangling: Vec<Dangling>,
+    }
+
+    /// Check that `HEAD` and all loose and packed references resolve to existing objects, that annotated tags can be peeled
+    /// and that `HEAD` and branches point to commits, writing all dangling references to `out` in the given `format`.
+    ///
+    /// Fail if there is at least one dangling reference.
+    ///
+    /// The porcelain format has one record per dangling reference with the columns `<problem>`, `<target>` and `<name>`.
+    pub fn references(
+        repo: git::Repository,
+        format: OutputFormat,
+        mut out: impl std::io::Write,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> anyhow::Result<()> {
+        let mut namespaces = BTreeMap::new();
+        let mut dangling = Vec::new();
+        let mut references_checked = 0;
+        let mut buf = Vec::new();
+
+        let platform = repo.references()?;
+        let head = repo.find_reference("HEAD")?;
+        for reference in std::iter::once(Ok(head)).chain(platform.all()?) {
+            bail_if_interrupted(should_interrupt)?;
+            let reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+            let name = reference.name().as_bstr().to_str_lossy().into_owned();
+            namespaces
+                .entry(namespace_of(&name).to_owned())
+                .or_insert_with_key(|namespace| {
+                    let mut p = progress.add_child(namespace.clone());
+                    p.init(None, git::progress::count("references"));
+                    p
+                })
+                .inc();
+            references_checked += 1;
+
+            if let Some((target, problem)) = problem_of(&repo, reference, &mut buf)? {
+                dangling.push(Dangling { name, target, problem });
+            }
+        }
+
+        let summary = Summary {
+            references_checked,
+            dangling,
+        };
+        match format {
+            OutputFormat::Human => {
+                for Dangling { name, target, problem } in &summary.dangling {
+                    writeln!(out, "dangling reference: {} -> {} ({})", name, target, problem)?;
+                }
+                writeln!(
+                    out,
+                    "checked {} references, {} dangling",
+                    summary.references_checked,
+                    summary.dangling.len()
+                )?;
+            }
+            OutputFormat::Porcelain { nul_terminated } => {
+                let mut records = Records::new(&mut out, nul_terminated);
+                for Dangling { name, target, problem } in &summary.dangling {
+                    records.write(&[problem, target, name])?;
+                }
+            }
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, &summary)?;
+                writeln!(out)?;
+            }
+        }
+        if !summary.dangling.is_empty() {
+            bail!("Found {} dangling reference(s)", summary.dangling.len());
+        }
+        Ok(())
+    }
+
+    /// Return the first two components of a reference `name`, like `refs/heads`, or the whole name if it has no more than two.
+    fn namespace_of(name: &str) -> &str {
+        match name.match_indices('/').nth(1) {
+            Some((pos, _)) => &name[..pos],
+            None => name,
+        }
+    }
+
+    /// Resolve `reference` to an object and check it, returning the target at which resolution stopped along with the problem
+    /// if there is one.
+    fn problem_of<'repo>(
+        repo: &'repo git::Repository,
+        mut reference: git::Reference<'repo>,
+        buf: &mut Vec<u8>,
+    ) -> anyhow::Result<Option<(String, Problem)>> {
+        use git::{odb::Find, refs::TargetRef};
+
+        let needs_commit = {
+            let name = reference.name().as_bstr();
+            name == "HEAD" || name.starts_with(b"refs/heads/")
+        };
+        let mut depth = 0;
+        let mut id = loop {
+            let target_name = match reference.target() {
+                TargetRef::Peeled(id) => break id.to_owned(),
+                TargetRef::Symbolic(name) => name.to_owned(),
+            };
+            depth += 1;
+            if depth > MAX_SYMBOLIC_DEPTH {
+                return Ok(Some((
+                    target_name.as_bstr().to_string(),
+                    Problem::SymbolicReferenceCycle,
+                )));
+            }
+            reference = match repo.try_find_reference(&target_name)? {
+                Some(reference) => reference,
+                None => return Ok(Some((target_name.as_bstr().to_string(), Problem::MissingReference))),
+            };
+        };
+
+        let mut missing = Problem::MissingObject;
+        loop {
+            match repo.objects.try_find(id, buf)? {
+                Some(obj) if obj.kind == git::object::Kind::Tag => {
+                    id = git::objs::TagRefIter::from_bytes(obj.data).target_id()?;
+                    missing = Problem::MissingTagTarget;
+                }
+                Some(obj) if needs_commit && obj.kind != git::object::Kind::Commit => {
+                    return Ok(Some((id.to_string(), Problem::NotACommit { actual: obj.kind })))
+                }
+                Some(_) => return Ok(None),
+                None => return Ok(Some((id.to_string(), missing))),
+            }
+        }
+    }
+}
@@ -9,7 +9,7 @@ mod refs_impl {
     };
 
     use super::by_name_or_url;
-    use crate::OutputFormat;
+    use crate::{OutputFormat, Records};
 
     pub mod refs {
         use git_repository::bstr::BString;
@@ -56,7 +56,7 @@ mod refs_impl {
         } = &kind
         {
             if format != OutputFormat::Human {
-                bail!("JSON output isn't yet supported for listing ref-mappings.");
+                bail!("Only human output is supported for listing ref-mappings at the moment.");
             }
             if !ref_specs.is_empty() {
                 remote.replace_refspecs(ref_specs.iter(), git::remote::Direction::Fetch)?;
@@ -97,6 +97,9 @@ mod refs_impl {
             refs::Kind::Remote => {
                 match format {
                     OutputFormat::Human => drop(print(out, &map.remote_refs)),
+                    OutputFormat::Porcelain { nul_terminated } => {
+                        print_porcelain(out, nul_terminated, &map.remote_refs)?
+                    }
                     #[cfg(feature = "serde1")]
                     OutputFormat::Json => serde_json::to_writer_pretty(
                         out,
@@ -293,6 +296,35 @@ mod refs_impl {
         }
         Ok(())
     }
+
+    /// Write one record per ref with the columns `<kind>`, `<object>`, `<peeled object or symref target>` and `<name>`.
+    ///
+    /// `<kind>` is one of `direct`, `peeled`, `symbolic` or `unborn`. The third column is empty for `direct` refs,
+    /// and `<object>` is empty for `unborn` ones.
+    fn print_porcelain(out: impl std::io::Write, nul_terminated: bool, refs: &[handshake::Ref]) -> std::io::Result<()> {
+        let mut records = Records::new(out, nul_terminated);
+        for r in refs {
+            match r {
+                handshake::Ref::Direct { full_ref_name, object } => {
+                    records.write(&[&"direct", object, &"", full_ref_name])?
+                }
+                handshake::Ref::Peeled {
+                    full_ref_name,
+                    tag,
+                    object,
+                } => records.write(&[&"peeled", tag, object, full_ref_name])?,
+                handshake::Ref::Symbolic {
+                    full_ref_name,
+                    target,
+                    object,
+                } => records.write(&[&"symbolic", object, target, full_ref_name])?,
+                handshake::Ref::Unborn { full_ref_name, target } => {
+                    records.write(&[&"unborn", &"", target, full_ref_name])?
+                }
+            }
+        }
+        Ok(())
+    }
 }
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use refs_impl::{refs, refs_fn as refs, JsonRef};
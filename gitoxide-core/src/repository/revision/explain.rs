@@ -220,6 +220,8 @@ impl<'a> delegate::Kind for Explain<'a> {
                 spec::Kind::ExcludeReachableFromParents => "exclude parents",
                 spec::Kind::IncludeReachable =>
                     unreachable!("BUG: 'single' mode is implied but cannot be set explicitly"),
+                spec::Kind::IncludeAndExcludeTips =>
+                    unreachable!("BUG: 'tips' mode is only used when combining multiple specifications"),
             }
         )
         .ok()
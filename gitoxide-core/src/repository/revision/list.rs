@@ -1,33 +1,28 @@
-use std::ffi::OsString;
+use std::{ffi::OsString, sync::atomic::AtomicBool};
 
-use anyhow::{bail, Context};
+use anyhow::bail;
 use git_repository as git;
-use git_repository::prelude::ObjectIdExt;
 
-use crate::OutputFormat;
+use crate::{bail_if_interrupted, OutputFormat};
 
 pub fn list(
     mut repo: git::Repository,
-    spec: OsString,
+    specs: Vec<OsString>,
     mut out: impl std::io::Write,
     format: OutputFormat,
+    should_interrupt: &AtomicBool,
 ) -> anyhow::Result<()> {
     if format != OutputFormat::Human {
         bail!("Only human output is currently supported");
     }
     repo.object_cache_size_if_unset(4 * 1024 * 1024);
 
-    let spec = git::path::os_str_into_bstr(&spec)?;
-    let id = repo
-        .rev_parse_single(spec)
-        .context("Only single revisions are currently supported")?;
-    let commit_id = id
-        .object()?
-        .peel_to_kind(git::object::Kind::Commit)
-        .context("Need commitish as starting point")?
-        .id
-        .attach(&repo);
-    for commit in commit_id.ancestors().all()? {
+    let specs = specs
+        .iter()
+        .map(|spec| git::path::os_str_into_bstr(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    for commit in repo.rev_parse_tips(specs)?.rev_walk()?.all()? {
+        bail_if_interrupted(should_interrupt)?;
         writeln!(out, "{}", commit?.to_hex())?;
     }
     Ok(())
@@ -1,8 +1,11 @@
 use anyhow::Context;
 use git_repository as git;
 
-use crate::OutputFormat;
+use crate::{OutputFormat, Records};
 
+/// Print the branches previously checked out in `repo`, with the most recent one last.
+///
+/// The porcelain format has one record per branch with the columns `<id>` and `<name>`.
 pub fn previous_branches(
     repo: git::Repository,
     mut out: impl std::io::Write,
@@ -18,6 +21,12 @@ pub fn previous_branches(
                 writeln!(out, "{} {}", id, name)?;
             }
         }
+        OutputFormat::Porcelain { nul_terminated } => {
+            let mut records = Records::new(&mut out, nul_terminated);
+            for (name, id) in branches {
+                records.write(&[&id, &name])?;
+            }
+        }
         #[cfg(feature = "serde1")]
         OutputFormat::Json => {
             serde_json::to_writer_pretty(&mut out, &branches)?;
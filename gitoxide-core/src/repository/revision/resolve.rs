@@ -1,9 +1,51 @@
+use git_repository as git;
+
 use crate::OutputFormat;
 
+/// Determines how many rev-specs are accepted and what they may resolve to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Resolve any amount of rev-specs, which may be ranges.
+    Normal,
+    /// Resolve exactly one rev-spec which must resolve to a single object, and print only its id on a single line,
+    /// similar to `git rev-parse --verify`.
+    Single,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Normal
+    }
+}
+
+/// The error returned if rev-specs are resolved in [`Mode::Single`].
+#[derive(Debug, thiserror::Error)]
+pub enum SingleError {
+    #[error("Exactly one rev-spec is needed, but got {count}")]
+    SpecCount { count: usize },
+    #[error("Rev-spec '{spec}' must resolve to a single object, but resolved to a range")]
+    Range { spec: git::bstr::BString },
+    #[error("Rev-spec '{spec}' didn't resolve to an object")]
+    NotFound {
+        spec: git::bstr::BString,
+        source: git::revision::spec::parse::Error,
+    },
+}
+
 pub struct Options {
     pub format: OutputFormat,
     pub explain: bool,
     pub cat_file: bool,
+    /// If true, all specs are combined into a single specification of included and excluded tips.
+    pub tips: bool,
+    /// If true, full hex ids of objects that aren't present locally resolve nonetheless and are marked accordingly.
+    pub allow_unverified: bool,
+    /// How many rev-specs to accept and what they may resolve to.
+    pub mode: Mode,
+    /// If true, print the shortest unambiguous prefix of the resolved id instead of the full id in [`Mode::Single`].
+    pub short: bool,
+    /// If true, fail if a reference name matches more than one reference instead of warning about it.
+    pub strict_refs: bool,
 }
 
 pub(crate) mod function {
@@ -12,21 +54,101 @@ pub(crate) mod function {
     use anyhow::Context;
     use git_repository as git;
 
-    use super::Options;
-    use crate::{repository::revision, OutputFormat};
+    use super::{Mode, Options, SingleError};
+    use crate::{repository::revision, OutputFormat, Records};
 
+    /// Resolve all `specs` and write them to `out` in the given format.
+    ///
+    /// The porcelain format has one record per spec with the columns `<resolved spec>`, `present` or `missing` depending on
+    /// whether the object exists locally, and the spec as given, or a single record with the resolved spec if `tips` is set.
+    ///
+    /// In [`Mode::Single`], the output is a single line with the resolved id in all formats but JSON, and failures are [`SingleError`]s.
+    ///
+    /// Reference names matching more than one reference are reported as warning to `err`, and as part of each record in JSON,
+    /// or fail the operation if `strict_refs` is set.
     pub fn resolve(
         mut repo: git::Repository,
         specs: Vec<OsString>,
         mut out: impl std::io::Write,
+        mut err: impl std::io::Write,
         Options {
             format,
             explain,
             cat_file,
+            tips,
+            allow_unverified,
+            mode,
+            short,
+            strict_refs,
         }: Options,
     ) -> anyhow::Result<()> {
         repo.object_cache_size_if_unset(1024 * 1024);
 
+        if mode == Mode::Single {
+            if explain || cat_file || tips {
+                anyhow::bail!(
+                    "Explanations, object contents and tips can't be combined with resolving a single rev-spec"
+                )
+            }
+            if specs.len() != 1 {
+                return Err(SingleError::SpecCount { count: specs.len() }.into());
+            }
+            let input = git::path::os_str_into_bstr(&specs[0])?;
+            let spec = if allow_unverified {
+                repo.rev_parse_allow_unverified(input)
+            } else {
+                repo.rev_parse(input)
+            }
+            .map_err(|err| match err {
+                err if crate::spec_error_kind(&err) == "not-found" => SingleError::NotFound {
+                    spec: input.into(),
+                    source: err,
+                }
+                .into(),
+                err => anyhow::Error::from(err),
+            })?;
+            check_ambiguous_refs(&spec, strict_refs, &mut err)?;
+            let id = spec.single().ok_or_else(|| SingleError::Range { spec: input.into() })?;
+            let id = if short {
+                id.shorten_or_id()
+            } else {
+                git::hash::Prefix::from(id.detach())
+            };
+            match format {
+                OutputFormat::Human => writeln!(out, "{id}")?,
+                OutputFormat::Porcelain { nul_terminated } => Records::new(&mut out, nul_terminated).write(&[&id])?,
+                #[cfg(feature = "serde1")]
+                OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &id.to_string())?,
+            }
+            return Ok(());
+        }
+
+        if tips {
+            let specs = specs
+                .iter()
+                .map(|spec| git::path::os_str_into_bstr(spec))
+                .collect::<Result<Vec<_>, _>>()?;
+            let spec = repo.rev_parse_tips(specs)?;
+            check_ambiguous_refs(&spec, strict_refs, &mut err)?;
+            let spec = spec.detach();
+            match format {
+                OutputFormat::Human => writeln!(out, "{spec}")?,
+                OutputFormat::Porcelain { nul_terminated } => Records::new(&mut out, nul_terminated).write(&[&spec])?,
+                #[cfg(feature = "serde1")]
+                OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &spec)?,
+            }
+            return Ok(());
+        }
+
+        let mut rev_parse = |spec: &git::bstr::BStr| -> anyhow::Result<_> {
+            let spec = if allow_unverified {
+                repo.rev_parse_allow_unverified(spec)
+            } else {
+                repo.rev_parse(spec)
+            }?;
+            check_ambiguous_refs(&spec, strict_refs, &mut err)?;
+            Ok(spec)
+        };
         match format {
             OutputFormat::Human => {
                 for spec in specs {
@@ -34,11 +156,27 @@ pub(crate) mod function {
                         return revision::explain(spec, out);
                     }
                     let spec = git::path::os_str_into_bstr(&spec)?;
-                    let spec = repo.rev_parse(spec)?;
+                    let spec = rev_parse(spec)?;
                     if cat_file {
                         return display_object(spec, out);
                     }
-                    writeln!(out, "{spec}", spec = spec.detach())?;
+                    if spec.is_verified() {
+                        writeln!(out, "{spec}", spec = spec.detach())?;
+                    } else {
+                        writeln!(out, "{spec} (not present locally)", spec = spec.detach())?;
+                    }
+                }
+            }
+            OutputFormat::Porcelain { nul_terminated } => {
+                if explain || cat_file {
+                    anyhow::bail!("Explanations and object contents are only for human consumption")
+                }
+                let mut records = Records::new(&mut out, nul_terminated);
+                for spec in specs {
+                    let input = git::path::os_str_into_bstr(&spec)?;
+                    let spec = rev_parse(input)?;
+                    let presence = if spec.is_verified() { "present" } else { "missing" };
+                    records.write(&[&spec.detach(), &presence, &input])?;
                 }
             }
             #[cfg(feature = "serde1")]
@@ -46,6 +184,19 @@ pub(crate) mod function {
                 if explain {
                     anyhow::bail!("Explanations are only for human consumption")
                 }
+                #[derive(serde::Serialize)]
+                struct AmbiguousRef {
+                    name: String,
+                    candidates: Vec<String>,
+                }
+                #[derive(serde::Serialize)]
+                struct Resolved {
+                    #[serde(flatten)]
+                    spec: git::revision::plumbing::Spec,
+                    exists: bool,
+                    #[serde(skip_serializing_if = "Vec::is_empty")]
+                    ambiguous_refs: Vec<AmbiguousRef>,
+                }
                 serde_json::to_writer_pretty(
                     &mut out,
                     &specs
@@ -53,8 +204,23 @@ pub(crate) mod function {
                         .map(|spec| {
                             git::path::os_str_into_bstr(&spec)
                                 .map_err(anyhow::Error::from)
-                                .and_then(|spec| repo.rev_parse(spec).map_err(Into::into))
-                                .map(|spec| spec.detach())
+                                .and_then(&mut rev_parse)
+                                .map(|spec| Resolved {
+                                    exists: spec.is_verified(),
+                                    ambiguous_refs: spec
+                                        .ambiguous_refs()
+                                        .iter()
+                                        .map(|r| AmbiguousRef {
+                                            name: r.name.to_string(),
+                                            candidates: r
+                                                .candidates
+                                                .iter()
+                                                .map(|name| name.as_bstr().to_string())
+                                                .collect(),
+                                        })
+                                        .collect(),
+                                    spec: spec.detach(),
+                                })
                         })
                         .collect::<Result<Vec<_>, _>>()?,
                 )?;
@@ -63,6 +229,25 @@ pub(crate) mod function {
         Ok(())
     }
 
+    /// Write a warning for each reference name of `spec` that matched more than one reference to `err`, or fail if `strict`.
+    fn check_ambiguous_refs(
+        spec: &git::revision::Spec<'_>,
+        strict: bool,
+        mut err: impl std::io::Write,
+    ) -> anyhow::Result<()> {
+        for ambiguous in spec.ambiguous_refs() {
+            if strict {
+                return Err(git::revision::spec::parse::Error::AmbiguousRefName {
+                    name: ambiguous.name.clone(),
+                    candidates: ambiguous.candidates.clone(),
+                }
+                .into());
+            }
+            writeln!(err, "warning: refname '{}' is ambiguous.", ambiguous.name)?;
+        }
+        Ok(())
+    }
+
     fn display_object(spec: git::revision::Spec<'_>, mut out: impl std::io::Write) -> anyhow::Result<()> {
         let id = spec.single().context("rev-spec must resolve to a single object")?;
         let object = id.object()?;
@@ -1,13 +1,22 @@
-use std::{borrow::Cow, io};
+use std::{io, sync::atomic::AtomicBool};
 
-use anyhow::bail;
+use git::{
+    bstr::{BString, ByteVec},
+    objs::tree::EntryMode,
+    odb::pack::data::output::count::objects::PathMatch,
+    prelude::FindExt,
+    Tree,
+};
 use git_repository as git;
-use git_repository::{prelude::ObjectIdExt, Tree};
 
-use crate::OutputFormat;
+use super::path_filter::Spellings;
+use crate::{bail_if_interrupted, OutputFormat};
 
-mod entries {
-    use std::collections::VecDeque;
+mod traverse {
+    use std::{
+        collections::VecDeque,
+        sync::atomic::{AtomicBool, Ordering},
+    };
 
     use git::{
         bstr::{BStr, BString},
@@ -17,8 +26,6 @@ mod entries {
     use git_repository as git;
     use git_repository::bstr::{ByteSlice, ByteVec};
 
-    use crate::repository::tree::format_entry;
-
     #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
     #[derive(Default)]
     pub struct Statistics {
@@ -36,17 +43,17 @@ mod entries {
     pub struct Traverse<'repo, 'a> {
         pub stats: Statistics,
         repo: Option<&'repo git::Repository>,
-        out: Option<&'a mut dyn std::io::Write>,
+        should_interrupt: &'a AtomicBool,
         path: BString,
         path_deque: VecDeque<BString>,
     }
 
     impl<'repo, 'a> Traverse<'repo, 'a> {
-        pub fn new(repo: Option<&'repo git::Repository>, out: Option<&'a mut dyn std::io::Write>) -> Self {
+        pub fn new(repo: Option<&'repo git::Repository>, should_interrupt: &'a AtomicBool) -> Self {
             Traverse {
                 stats: Default::default(),
                 repo,
-                out,
+                should_interrupt,
                 path: BString::default(),
                 path_deque: VecDeque::new(),
             }
@@ -87,18 +94,21 @@ mod entries {
         }
 
         fn visit_tree(&mut self, _entry: &EntryRef<'_>) -> Action {
+            if self.should_interrupt.load(Ordering::Relaxed) {
+                return Action::Cancel;
+            }
             self.stats.num_trees += 1;
             Action::Continue
         }
 
         fn visit_nontree(&mut self, entry: &EntryRef<'_>) -> Action {
             use git::objs::tree::EntryMode::*;
+            if self.should_interrupt.load(Ordering::Relaxed) {
+                return Action::Cancel;
+            }
             let size = self
                 .repo
                 .and_then(|repo| repo.find_object(entry.oid).map(|o| o.data.len()).ok());
-            if let Some(out) = &mut self.out {
-                format_entry(out, entry, self.path.as_bstr(), size).ok();
-            }
             if let Some(size) = size {
                 self.stats.num_bytes += size as u64;
             }
@@ -123,15 +133,18 @@ pub fn info(
     format: OutputFormat,
     out: impl io::Write,
     mut err: impl io::Write,
+    should_interrupt: &AtomicBool,
 ) -> anyhow::Result<()> {
-    if format == OutputFormat::Human {
+    if matches!(format, OutputFormat::Human | OutputFormat::Porcelain { .. }) {
         writeln!(err, "Only JSON is implemented - using that instead")?;
     }
 
     let tree = treeish_to_tree(treeish, &repo)?;
 
-    let mut delegate = entries::Traverse::new(extended.then(|| &repo), None);
-    tree.traverse().breadthfirst(&mut delegate)?;
+    let mut delegate = traverse::Traverse::new(extended.then(|| &repo), should_interrupt);
+    let res = tree.traverse().breadthfirst(&mut delegate);
+    bail_if_interrupted(should_interrupt)?;
+    res?;
 
     #[cfg(feature = "serde1")]
     {
@@ -142,69 +155,210 @@ pub fn info(
     Ok(())
 }
 
+pub mod entries {
+    use git_repository as git;
+
+    use crate::OutputFormat;
+
+    pub struct Options {
+        /// List the entries of all subtrees with their full path instead of the subtrees themselves, like `git ls-tree -r`.
+        pub recursive: bool,
+        /// Only print the path of each entry, like `git ls-tree --name-only`.
+        pub name_only: bool,
+        /// Also print the size of each blob, like `git ls-tree --long`. This is expensive as each blob is decoded entirely.
+        pub extended: bool,
+        pub format: OutputFormat,
+        /// If set, only list entries at or below the paths of the filter, entering the trees leading to them even if not
+        /// `recursive`, similar to `git ls-tree <tree> <path>...`.
+        ///
+        /// If the filter ignores case or unicode normalization, a path may match differently spelled entries like `README`
+        /// and `readme`, which are all listed and reported as ambiguity.
+        pub path_filter: Option<git::odb::pack::data::output::count::objects::PathFilter>,
+    }
+}
+
+/// Print the entries of the tree `treeish` resolves to, or of the tree at `HEAD`, in the format of `git ls-tree`.
+///
+/// Entries are written in tree order as they are encountered while walking the tree depth-first, without collecting them.
+/// Unusual paths are quoted like `git` does, unless each record is terminated with NUL in porcelain format similar to
+/// `git ls-tree -z`.
+/// Ambiguous paths of the path filter are reported to `err`.
 pub fn entries(
     repo: git::Repository,
     treeish: Option<&str>,
-    recursive: bool,
-    extended: bool,
-    format: OutputFormat,
-    mut out: impl io::Write,
+    options: entries::Options,
+    out: impl io::Write,
+    err: impl io::Write,
+    should_interrupt: &AtomicBool,
 ) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("Only human output format is supported at the moment");
+    let tree = treeish_to_tree(treeish, &repo)?;
+    let mut printer = Printer {
+        repo: &repo,
+        options: &options,
+        out,
+        path: BString::default(),
+        bufs: Vec::new(),
+        num_entries: 0,
+        spellings: Default::default(),
+        should_interrupt,
+    };
+    printer.start()?;
+    printer.tree(tree.id, 0)?;
+    printer.finish()?;
+    if let Some(filter) = &options.path_filter {
+        printer.spellings.write_ambiguities(filter, err)?;
     }
+    Ok(())
+}
 
-    let tree = treeish_to_tree(treeish, &repo)?;
+/// Writes tree entries to `out` as configured by `options` while walking trees depth-first.
+struct Printer<'repo, 'a, W> {
+    repo: &'repo git::Repository,
+    options: &'a entries::Options,
+    out: W,
+    /// The path of the current entry.
+    path: BString,
+    /// A buffer for tree data for each level of depth, reused for all trees at that depth.
+    bufs: Vec<Vec<u8>>,
+    num_entries: usize,
+    /// The spelling of paths matching the path filter.
+    spellings: Spellings,
+    should_interrupt: &'a AtomicBool,
+}
 
-    if recursive {
-        let mut delegate = entries::Traverse::new(extended.then(|| &repo), Some(&mut out));
-        tree.traverse().breadthfirst(&mut delegate)?;
-    } else {
-        for entry in tree.iter() {
+impl<'repo, 'a, W: io::Write> Printer<'repo, 'a, W> {
+    fn start(&mut self) -> io::Result<()> {
+        #[cfg(feature = "serde1")]
+        if self.options.format == OutputFormat::Json {
+            self.out.write_all(b"[")?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        #[cfg(feature = "serde1")]
+        if self.options.format == OutputFormat::Json {
+            if self.num_entries != 0 {
+                self.out.write_all(b"\n")?;
+            }
+            self.out.write_all(b"]\n")?;
+        }
+        Ok(())
+    }
+
+    fn tree(&mut self, id: git::ObjectId, depth: usize) -> anyhow::Result<()> {
+        if self.bufs.len() == depth {
+            self.bufs.push(Vec::new());
+        }
+        let mut buf = std::mem::take(&mut self.bufs[depth]);
+        let res = self.tree_entries(id, &mut buf, depth);
+        self.bufs[depth] = buf;
+        res
+    }
+
+    fn tree_entries(&mut self, id: git::ObjectId, buf: &mut Vec<u8>, depth: usize) -> anyhow::Result<()> {
+        let repo = self.repo;
+        for entry in repo.objects.find_tree_iter(id, buf)? {
+            bail_if_interrupted(self.should_interrupt)?;
             let entry = entry?;
-            format_entry(
-                &mut out,
-                &entry.inner,
-                entry.inner.filename,
-                extended
-                    .then(|| entry.id().object().map(|o| o.data.len()))
-                    .transpose()?,
-            )?;
+            let parent_len = self.path.len();
+            if parent_len != 0 {
+                self.path.push(b'/');
+            }
+            self.path.push_str(entry.filename);
+            let path_match = match &self.options.path_filter {
+                Some(filter) => {
+                    let path_match = filter.classify(self.path.as_ref());
+                    if path_match == PathMatch::Included {
+                        self.spellings.record(filter, self.path.as_ref());
+                    }
+                    path_match
+                }
+                None => PathMatch::Included,
+            };
+            let res = match path_match {
+                PathMatch::Included if self.options.recursive && entry.mode.is_tree() => {
+                    self.tree(entry.oid.to_owned(), depth + 1)
+                }
+                PathMatch::Included => self.entry(entry.mode, entry.oid),
+                PathMatch::Parent if entry.mode.is_tree() => self.tree(entry.oid.to_owned(), depth + 1),
+                PathMatch::Parent | PathMatch::Excluded => Ok(()),
+            };
+            self.path.truncate(parent_len);
+            res?;
         }
+        Ok(())
     }
 
-    Ok(())
+    fn entry(&mut self, mode: EntryMode, id: &git::oid) -> anyhow::Result<()> {
+        let kind = match mode {
+            EntryMode::Tree => "tree",
+            EntryMode::Commit => "commit",
+            EntryMode::Blob | EntryMode::BlobExecutable | EntryMode::Link => "blob",
+        };
+        let size = (self.options.extended && kind == "blob")
+            .then(|| self.repo.find_object(id).map(|obj| obj.data.len()))
+            .transpose()?;
+        let out = &mut self.out;
+        match self.options.format {
+            OutputFormat::Human | OutputFormat::Porcelain { .. } => {
+                if !self.options.name_only {
+                    write!(out, "{:06o} {} {}", mode as u16, kind, id)?;
+                    if self.options.extended {
+                        match size {
+                            Some(size) => write!(out, " {:>7}", size)?,
+                            None => write!(out, " {:>7}", "-")?,
+                        }
+                    }
+                    out.write_all(b"\t")?;
+                }
+                if matches!(self.options.format, OutputFormat::Porcelain { nul_terminated: true }) {
+                    out.write_all(&self.path)?;
+                    out.write_all(b"\0")?;
+                } else {
+                    out.write_all(&git_quote::ansi_c::quote(self.path.as_ref()))?;
+                    out.write_all(b"\n")?;
+                }
+            }
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => {
+                use git::bstr::ByteSlice;
+                out.write_all(if self.num_entries == 0 { b"\n  " } else { b",\n  " })?;
+                let name_only = self.options.name_only;
+                serde_json::to_writer(
+                    out,
+                    &JsonEntry {
+                        mode: (!name_only).then(|| format!("{:06o}", mode as u16)),
+                        kind: (!name_only).then(|| kind),
+                        id: (!name_only).then(|| id.to_string()),
+                        size,
+                        path: self.path.to_str_lossy(),
+                    },
+                )?;
+            }
+        }
+        self.num_entries += 1;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde1")]
+#[derive(serde::Serialize)]
+struct JsonEntry<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<usize>,
+    path: std::borrow::Cow<'a, str>,
 }
 
 fn treeish_to_tree<'repo>(treeish: Option<&str>, repo: &'repo git::Repository) -> anyhow::Result<Tree<'repo>> {
     Ok(match treeish {
-        Some(hex) => git::hash::ObjectId::from_hex(hex.as_bytes())
-            .map(|id| id.attach(repo))?
-            .object()?
-            .try_into_tree()?,
+        Some(spec) => repo.rev_parse_single(spec)?.object()?.peel_to_tree()?,
         None => repo.head()?.peel_to_commit_in_place()?.tree()?,
     })
 }
-
-fn format_entry(
-    mut out: impl io::Write,
-    entry: &git::objs::tree::EntryRef<'_>,
-    filename: &git::bstr::BStr,
-    size: Option<usize>,
-) -> std::io::Result<()> {
-    use git::objs::tree::EntryMode::*;
-    writeln!(
-        out,
-        "{} {}{} {}",
-        match entry.mode {
-            Tree => "TREE",
-            Blob => "BLOB",
-            BlobExecutable => " EXE",
-            Link => "LINK",
-            Commit => "SUBM",
-        },
-        entry.oid,
-        size.map(|s| Cow::Owned(format!(" {}", s))).unwrap_or_else(|| "".into()),
-        filename
-    )
-}
@@ -1,39 +1,138 @@
 use std::sync::atomic::AtomicBool;
 
 use git_repository as git;
-use git_repository::Progress;
+use git_repository::{bstr::ByteSlice, odb::pack, Progress};
 
-use crate::{pack, OutputFormat};
+use crate::OutputFormat;
 
 /// A general purpose context for many operations provided here
 pub struct Context {
     /// If set, provide statistics to `out` in the given format
     pub output_statistics: Option<OutputFormat>,
+    /// The format in which to print the summary of the verification to `out`.
+    pub format: OutputFormat,
     /// If set, don't use more than this amount of threads.
     /// Otherwise, usually use as many threads as there are logical cores.
     /// A value of 0 is interpreted as no-limit
     pub thread_limit: Option<usize>,
-    pub verify_mode: pack::verify::Mode,
-    pub algorithm: pack::verify::Algorithm,
+    pub verify_mode: crate::pack::verify::Mode,
+    pub algorithm: crate::pack::verify::Algorithm,
+    /// How to print statistics in human-readable output.
+    pub statistics_options: crate::pack::verify::Options,
 }
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
+/// The combined outcome of [`integrity()`], with each kind of problem mapping to its own [exit code][Status::exit_code()]
+/// so that monitoring can tell them apart.
+///
+/// If there is more than one problem, the one found first is reported, and object database problems are found before
+/// problems with references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub enum Status {
+    /// All objects could be verified and all references point to existing objects. Exit code `0`.
+    Ok,
+    /// The checksum of a pack, an index or an object didn't match its actual data. Exit code `2`.
+    ChecksumMismatch,
+    /// Objects in the object database could not be decoded or re-encoded. Exit code `3`.
+    UndecodableObjects,
+    /// References point to objects which don't exist in the object database, or deltas in packs refer to base objects
+    /// which aren't contained in them. Exit code `4`.
+    MissingObjects,
+    /// A pack index or multi-pack index is internally inconsistent, like being out of order. Exit code `5`.
+    InconsistentIndex,
+}
+
+impl Status {
+    /// The code to exit the process with to communicate this status.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Status::Ok => 0,
+            Status::ChecksumMismatch => 2,
+            Status::UndecodableObjects => 3,
+            Status::MissingObjects => 4,
+            Status::InconsistentIndex => 5,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::ChecksumMismatch => "checksum mismatch",
+            Status::UndecodableObjects => "undecodable objects",
+            Status::MissingObjects => "missing objects",
+            Status::InconsistentIndex => "inconsistent index",
+        }
+    }
+}
+
+/// A reference whose peeled target doesn't exist in the object database.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct MissingTarget {
+    /// The full name of the reference.
+    pub name: String,
+    /// The id of the object that couldn't be found, which may also be the target of an existing annotated tag.
+    pub id: git::ObjectId,
+}
+
+/// A problematic entry in a tree, as `git fsck` would report it. These are warnings which don't affect the [`Status`].
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct TreeIssue {
+    /// The id of the tree containing the entry.
+    pub id: git::ObjectId,
+    /// The identifier `git fsck` uses for the problem.
+    pub kind: &'static str,
+    /// The index of the entry in the tree.
+    pub entry_index: usize,
+    /// The name of the entry.
+    pub name: String,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct Summary {
+    status: Status,
+    exit_code: i32,
+    object_database_error: Option<String>,
+    tree_issues: Vec<TreeIssue>,
+    warnings_by_class: std::collections::BTreeMap<git::objs::verify::WarningClass, u64>,
+    orphaned_indices: Vec<std::path::PathBuf>,
+    references_checked: usize,
+    missing_targets: Vec<MissingTarget>,
+}
+
+/// Verify the object database and all references of `repo`, and write a summary to `out`.
+///
+/// `progress` receives a child `object database` with id `VIOD`, which has the children documented with
+/// [`verify_integrity()`][git::odb::Store::verify_integrity()].
 pub fn integrity(
     repo: git::Repository,
     mut out: impl std::io::Write,
-    progress: impl Progress,
+    mut progress: impl Progress,
     should_interrupt: &AtomicBool,
     Context {
         output_statistics,
+        format,
         thread_limit,
         verify_mode,
         algorithm,
+        statistics_options,
     }: Context,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Status> {
+    if matches!(format, OutputFormat::Porcelain { .. })
+        || matches!(output_statistics, Some(OutputFormat::Porcelain { .. }))
+    {
+        anyhow::bail!("Porcelain output isn't supported for verifying repositories");
+    }
+    let mut status = Status::Ok;
+    let mut object_database_error = None;
+    let mut tree_issues = Vec::new();
+    let mut warnings_by_class = Default::default();
+    let mut orphaned_indices = Vec::new();
     #[cfg_attr(not(feature = "serde1"), allow(unused))]
-    let mut outcome = repo.objects.store_ref().verify_integrity(
-        progress,
+    let mut odb_progress = progress.add_child_with_id("object database", *b"VIOD"); /* Verify Integrity Object Database */
+    let statistics = match repo.objects.store_ref().verify_integrity(
+        &mut odb_progress,
         should_interrupt,
         git_repository::odb::pack::index::verify::integrity::Options {
             verify_mode,
@@ -41,8 +140,52 @@ pub fn integrity(
             thread_limit,
             // TODO: a way to get the pack cache from a handle
             make_pack_lookup_cache: || git_repository::odb::pack::cache::Never,
+            max_decompressed_object_size: Some(
+                git_repository::odb::pack::data::decode_entry::DEFAULT_MAX_DECOMPRESSED_OBJECT_SIZE,
+            ),
+            tree_checks: git::objs::tree::check::Options::strict(),
+            keep_going: false,
+            paranoid: false,
+            strict_entry_boundaries: false,
         },
-    )?;
+    ) {
+        Ok(outcome) => {
+            tree_issues = outcome
+                .tree_issues
+                .into_iter()
+                .flat_map(|tree| {
+                    let id = tree.id;
+                    tree.issues.into_iter().map(move |issue| TreeIssue {
+                        id,
+                        kind: issue.kind.fsck_id(),
+                        entry_index: issue.entry_index,
+                        name: issue.name.to_str_lossy().into_owned(),
+                    })
+                })
+                .collect();
+            warnings_by_class = outcome.warnings_by_class;
+            orphaned_indices = outcome.orphaned_indices;
+            Some((outcome.index_statistics, outcome.loose_object_stores))
+        }
+        Err(err) => match status_of_store_error(&err) {
+            Some(problem) => {
+                status = problem;
+                odb_progress.fail(format!("{}", err));
+                object_database_error = Some(err.to_string());
+                None
+            }
+            None => return Err(err.into()),
+        },
+    };
+    for warning in repo.objects.store_ref().warnings() {
+        progress.fail(format!("Skipped: {}", warning));
+    }
+
+    let (references_checked, missing_targets) = missing_reference_targets(&repo)?;
+    if status == Status::Ok && !missing_targets.is_empty() {
+        status = Status::MissingObjects;
+    }
+
     if let Some(index) = repo.worktree().map(|wt| wt.index()).transpose()? {
         index.verify_integrity()?;
         index.verify_entries()?;
@@ -51,23 +194,799 @@ pub fn integrity(
             let objects = repo.objects;
             move |oid, buf: &mut Vec<u8>| objects.find_tree_iter(oid, buf).ok()
         })?;
-        outcome
-            .progress
-            .info(format!("Index at '{}' OK", index.path().display()));
+        progress.info(format!("Index at '{}' OK", index.path().display()));
+    }
+
+    let summary = Summary {
+        status,
+        exit_code: status.exit_code(),
+        object_database_error,
+        tree_issues,
+        warnings_by_class,
+        orphaned_indices,
+        references_checked,
+        missing_targets,
+    };
+    match format {
+        OutputFormat::Human => {
+            if let Some(err) = &summary.object_database_error {
+                writeln!(out, "object database: {}", err)?;
+            }
+            for TreeIssue {
+                id,
+                kind,
+                entry_index,
+                name,
+            } in &summary.tree_issues
+            {
+                writeln!(out, "tree {}: {}: entry {} named {:?}", id, kind, entry_index, name)?;
+            }
+            if !summary.warnings_by_class.is_empty() {
+                let mut warnings: Vec<_> = summary.warnings_by_class.iter().collect();
+                warnings.sort_by_key(|(class, _)| class.as_str());
+                writeln!(out, "objects with warnings:")?;
+                for (class, count) in warnings {
+                    writeln!(out, "{:>10} {}", count, class)?;
+                }
+            }
+            for path in &summary.orphaned_indices {
+                writeln!(out, "orphaned index: {} has no pack", path.display())?;
+            }
+            for MissingTarget { name, id } in &summary.missing_targets {
+                writeln!(out, "missing object: {} referenced by {}", id, name)?;
+            }
+            writeln!(
+                out,
+                "checked {} references, {} point to missing objects",
+                summary.references_checked,
+                summary.missing_targets.len()
+            )?;
+            writeln!(out, "status: {} (exit code {})", status.as_str(), summary.exit_code)?;
+        }
+        OutputFormat::Porcelain { .. } => unreachable!("rejected before verification"),
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &summary)?;
+            writeln!(out)?;
+        }
     }
     match output_statistics {
-        Some(OutputFormat::Human) => writeln!(out, "Human output is currently unsupported, use JSON instead")?,
+        Some(OutputFormat::Human) => {
+            if let Some((index_statistics, loose_object_stores)) = statistics {
+                use git::odb::store::verify::integrity::SingleOrMultiStatistics;
+                for index in index_statistics {
+                    let mut pack_files = index.pack_files.into_iter().chain(std::iter::repeat(None));
+                    match index.statistics {
+                        SingleOrMultiStatistics::Single(stats) => {
+                            writeln!(
+                                out,
+                                "\n{}",
+                                crate::pack::verify::pack_section_header(pack_files.next().flatten(), &index.path)
+                            )?;
+                            crate::pack::verify::print_statistics(&mut out, &stats, statistics_options)?
+                        }
+                        SingleOrMultiStatistics::Multi(stats) => {
+                            writeln!(out, "\n{}", index.path.display())?;
+                            for ((index_path, stats), pack_file) in stats.into_iter().zip(pack_files) {
+                                writeln!(
+                                    out,
+                                    "\n{}",
+                                    crate::pack::verify::pack_section_header(pack_file, &index_path)
+                                )?;
+                                crate::pack::verify::print_statistics(&mut out, &stats, statistics_options)?;
+                            }
+                        }
+                    }
+                }
+                for store in loose_object_stores {
+                    writeln!(
+                        out,
+                        "\nloose objects in {}: {}",
+                        store.path.display(),
+                        statistics_options.count(store.statistics.num_objects as u64)
+                    )?;
+                }
+            }
+        }
         #[cfg(feature = "serde1")]
         Some(OutputFormat::Json) => {
-            serde_json::to_writer_pretty(
+            if let Some((index_statistics, loose_object_stores)) = statistics {
+                serde_json::to_writer_pretty(
+                    out,
+                    &serde_json::json!({
+                        "index_statistics" : index_statistics,
+                        "loose_object-stores" : loose_object_stores
+                    }),
+                )?;
+            }
+        }
+        Some(OutputFormat::Porcelain { .. }) => unreachable!("rejected before verification"),
+        None => {}
+    }
+    Ok(status)
+}
+
+///
+pub mod connectivity {
+    use git_repository as git;
+
+    use crate::OutputFormat;
+
+    pub struct Options {
+        /// The format in which to print missing objects to `out`.
+        pub format: OutputFormat,
+        /// If set, don't use more than this amount of threads to traverse trees.
+        /// Otherwise, usually use as many threads as there are logical cores.
+        /// A value of 0 is interpreted as no-limit
+        pub thread_limit: Option<usize>,
+    }
+
+    /// Something that refers to a missing object.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub enum Referrer {
+        /// A reference, including `HEAD`, pointing to the missing object directly.
+        Reference { name: String },
+        /// An annotated tag whose target is missing.
+        Tag { id: git::ObjectId },
+        /// A commit whose tree or parent is missing.
+        Commit { id: git::ObjectId },
+        /// A tree whose entry named `name` points to the missing object.
+        Tree { id: git::ObjectId, name: String },
+        /// The worktree index, with an entry at `path` or a cached tree for the directory at `path` pointing to the missing object.
+        Index { path: String },
+    }
+
+    /// An object which is reachable from a reference but doesn't exist in the object database.
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Missing {
+        /// The id of the missing object.
+        pub id: git::ObjectId,
+        /// The kind of object its referrers expect, or `None` if only references point to it which don't imply a kind.
+        pub kind: Option<git::object::Kind>,
+        /// Everything referring to the missing object, sorted.
+        pub referrers: Vec<Referrer>,
+    }
+
+    /// The result of [`connectivity()`][super::connectivity()].
+    #[derive(Default)]
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Outcome {
+        /// The amount of references, including `HEAD`, whose reachable objects were checked.
+        pub references: usize,
+        /// The amount of commits which were found.
+        pub commits: usize,
+        /// The amount of trees which were found.
+        pub trees: usize,
+        /// The amount of blobs which were found.
+        pub blobs: usize,
+        /// All missing objects, sorted by kind and id.
+        pub missing: Vec<Missing>,
+        /// The amount of objects which are missing in a partial clone, but are expected to be provided by its promisor remote.
+        pub promised: usize,
+    }
+}
+
+/// Check that all objects reachable from `HEAD` and all references exist, similar to `git fsck --connectivity-only`,
+/// and write all missing objects along with everything referring to them to `out`.
+///
+/// Commits are traversed on the current thread, and the trees they refer to are traversed by up to `thread_limit` threads.
+/// Returns [`Status::MissingObjects`] if at least one object is missing, unless a partial clone expects it to be provided by its
+/// promisor remote.
+///
+/// `progress` receives a child `commits` with id `VCCO` and then a child `trees` with id `VCTR`.
+pub fn connectivity(
+    repo: git::Repository,
+    mut out: impl std::io::Write,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    connectivity::Options { format, thread_limit }: connectivity::Options,
+) -> anyhow::Result<Status> {
+    use connectivity::{Missing, Referrer};
+
+    if matches!(format, OutputFormat::Porcelain { .. }) {
+        anyhow::bail!("Porcelain output isn't supported for checking connectivity");
+    }
+    let (outcome, _seen) = connectivity_impl::walk(&repo, &mut progress, should_interrupt, thread_limit, false, false)?;
+    let status = if outcome.missing.is_empty() {
+        Status::Ok
+    } else {
+        Status::MissingObjects
+    };
+
+    match format {
+        OutputFormat::Human => {
+            for Missing { id, kind, referrers } in &outcome.missing {
+                write!(
+                    out,
+                    "missing {} {} referenced by ",
+                    kind.as_ref()
+                        .map_or(b"object".as_bstr(), |kind| kind.as_bytes().as_bstr()),
+                    id
+                )?;
+                for (index, referrer) in referrers.iter().enumerate() {
+                    if index != 0 {
+                        write!(out, ", ")?;
+                    }
+                    match referrer {
+                        Referrer::Reference { name } => write!(out, "reference {}", name),
+                        Referrer::Tag { id } => write!(out, "tag {}", id),
+                        Referrer::Commit { id } => write!(out, "commit {}", id),
+                        Referrer::Tree { id, name } => write!(out, "tree {} entry {:?}", id, name),
+                        Referrer::Index { path } => write!(out, "index entry {:?}", path),
+                    }?;
+                }
+                writeln!(out)?;
+            }
+            writeln!(
                 out,
-                &serde_json::json!({
-                    "index_statistics" : outcome.index_statistics,
-                    "loose_object-stores" : outcome.loose_object_stores
-                }),
+                "checked {} commits, {} trees and {} blobs reachable from {} references, {} are missing",
+                outcome.commits,
+                outcome.trees,
+                outcome.blobs,
+                outcome.references,
+                outcome.missing.len()
             )?;
+            if outcome.promised != 0 {
+                writeln!(
+                    out,
+                    "{} more objects are missing, but promised by the remote of this partial clone",
+                    outcome.promised
+                )?;
+            }
+            writeln!(out, "status: {} (exit code {})", status.as_str(), status.exit_code())?;
         }
-        None => {}
+        OutputFormat::Porcelain { .. } => unreachable!("rejected before checking"),
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct Summary {
+                status: Status,
+                exit_code: i32,
+                #[serde(flatten)]
+                outcome: connectivity::Outcome,
+            }
+            serde_json::to_writer_pretty(
+                &mut out,
+                &Summary {
+                    status,
+                    exit_code: status.exit_code(),
+                    outcome,
+                },
+            )?;
+            writeln!(out)?;
+        }
+    }
+    Ok(status)
+}
+
+pub(crate) mod connectivity_impl {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Mutex,
+        },
+    };
+
+    use anyhow::Context;
+    use git_repository as git;
+    use git_repository::{bstr::ByteSlice, object::Kind, Progress};
+
+    use super::connectivity::{Missing, Outcome, Referrer};
+
+    /// All objects seen so far, sharded by the first byte of their id to reduce lock contention,
+    /// mapped to `true` if they are missing.
+    pub struct Seen {
+        shards: Vec<Mutex<HashMap<git::ObjectId, bool>>>,
+    }
+
+    impl Default for Seen {
+        fn default() -> Self {
+            Seen {
+                shards: (0..256).map(|_| Default::default()).collect(),
+            }
+        }
+    }
+
+    impl Seen {
+        /// Return `true` if `id` wasn't seen before and `exists()`, and record the referrer if `id` is missing.
+        ///
+        /// The existence check happens while holding the lock so that no referrer of a missing object gets lost.
+        pub fn first_visit_of_existing(
+            &self,
+            id: git::ObjectId,
+            kind: Option<Kind>,
+            referrer: impl FnOnce() -> Referrer,
+            exists: impl FnOnce(&git::oid) -> bool,
+            missing: &MissingObjects,
+        ) -> bool {
+            let mut shard = self.shards[id.as_bytes()[0] as usize]
+                .lock()
+                .expect("no panic while holding the lock");
+            match shard.get(&id) {
+                Some(true) => {
+                    missing.record(id, kind, referrer());
+                    false
+                }
+                Some(false) => false,
+                None => {
+                    let exists = exists(&id);
+                    shard.insert(id, !exists);
+                    if !exists {
+                        missing.record(id, kind, referrer());
+                    }
+                    exists
+                }
+            }
+        }
+    }
+
+    impl Seen {
+        /// Return `true` if `id` was seen and exists.
+        pub fn contains(&self, id: &git::oid) -> bool {
+            let shard = self.shards[id.as_bytes()[0] as usize]
+                .lock()
+                .expect("no panic while holding the lock");
+            // Look up an owned id as `oid` doesn't hash like `ObjectId` with the standard hasher.
+            shard.get(&id.to_owned()) == Some(&false)
+        }
+
+        /// Return all objects that were seen and exist.
+        pub fn into_existing(self) -> git::hashtable::HashSet {
+            self.shards
+                .into_iter()
+                .flat_map(|shard| shard.into_inner().expect("no panic while holding the lock"))
+                .filter_map(|(id, is_missing)| (!is_missing).then(|| id))
+                .collect()
+        }
+    }
+
+    /// Find all objects reachable from `HEAD` and all references, traversing commits on the current thread and the trees they
+    /// refer to with up to `thread_limit` threads, and return the outcome along with all objects that were seen.
+    ///
+    /// If `reflogs` is true, all objects in the reflogs of `HEAD` and all references are traversed as well, without
+    /// counting them as references.
+    /// If `index` is true, all objects of the worktree index, i.e. the blobs of its entries and the trees of its valid
+    /// cache-tree extension, are traversed as well, without counting them as references.
+    pub fn walk(
+        repo: &git::Repository,
+        progress: &mut impl Progress,
+        should_interrupt: &AtomicBool,
+        thread_limit: Option<usize>,
+        reflogs: bool,
+        index: bool,
+    ) -> anyhow::Result<(Outcome, Seen)> {
+        use git::odb::Find;
+
+        let seen = Seen::default();
+        let missing = MissingObjects::default();
+        let mut outcome = Outcome::default();
+
+        let mut tips = Vec::new();
+        if let Some(id) = repo.head()?.id() {
+            tips.push((id.detach(), Referrer::Reference { name: "HEAD".into() }));
+        }
+        for reference in repo.references()?.all()? {
+            let reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+            if let Some(id) = reference.target().try_id() {
+                tips.push((
+                    id.to_owned(),
+                    Referrer::Reference {
+                        name: reference.name().as_bstr().to_str_lossy().into_owned(),
+                    },
+                ));
+            }
+        }
+        outcome.references = tips.len();
+        if reflogs {
+            let names = std::iter::once("HEAD".into()).chain(tips.iter().filter_map(|(_, referrer)| match referrer {
+                Referrer::Reference { name } if name != "HEAD" => Some(name.clone()),
+                _ => None,
+            }));
+            let mut reflog_tips = Vec::new();
+            for name in names {
+                let reference = match repo.try_find_reference(name.as_str())? {
+                    Some(reference) => reference,
+                    None => continue,
+                };
+                let mut log = reference.log_iter();
+                let lines = match log.all()? {
+                    Some(lines) => lines,
+                    None => continue,
+                };
+                let mut ids = Vec::new();
+                for line in lines {
+                    let line = line.with_context(|| format!("Could not parse the reflog of '{}'", name))?;
+                    ids.extend(
+                        [line.previous_oid(), line.new_oid()]
+                            .into_iter()
+                            .filter(|id| !id.is_null()),
+                    );
+                }
+                reflog_tips.extend(ids.into_iter().map(|id| {
+                    (
+                        id,
+                        Referrer::Reference {
+                            name: format!("{}@{{reflog}}", name),
+                        },
+                    )
+                }));
+            }
+            reflog_tips.sort_by(|a, b| a.0.cmp(&b.0));
+            reflog_tips.dedup_by(|a, b| a.0 == b.0);
+            tips.extend(reflog_tips);
+        }
+        if index && repo.index_path().is_file() {
+            let index = repo.open_index()?;
+            for entry in index.entries() {
+                if entry.mode == git::index::entry::Mode::COMMIT {
+                    // Submodule commits live in the object database of the submodule.
+                    continue;
+                }
+                tips.push((
+                    entry.id,
+                    Referrer::Index {
+                        path: entry.path(&index).to_str_lossy().into_owned(),
+                    },
+                ));
+            }
+            if let Some(tree) = index.tree() {
+                cache_tree_tips(tree, String::new(), &mut tips);
+            }
+        }
+
+        let mut buf = Vec::new();
+        let mut commits = Vec::new();
+        let mut trees = Vec::new();
+        for (mut id, mut referrer) in tips {
+            let mut kind = None;
+            loop {
+                let obj = match repo.objects.try_find(id, &mut buf)? {
+                    Some(obj) => obj,
+                    None => {
+                        missing.record(id, kind, referrer);
+                        break;
+                    }
+                };
+                if obj.kind != Kind::Tree
+                    && !seen.first_visit_of_existing(id, kind, || unreachable!(), |_| true, &missing)
+                {
+                    break;
+                }
+                match obj.kind {
+                    Kind::Tag => {
+                        let tag = git::objs::TagRef::from_bytes(obj.data)?;
+                        referrer = Referrer::Tag { id };
+                        kind = Some(tag.target_kind);
+                        id = tag.target();
+                    }
+                    Kind::Commit => {
+                        commits.push((id, obj.data.to_owned()));
+                        break;
+                    }
+                    Kind::Tree => {
+                        trees.push((id, referrer));
+                        break;
+                    }
+                    Kind::Blob => {
+                        outcome.blobs += 1;
+                        break;
+                    }
+                }
+            }
+        }
+
+        let shallow = repo.shallow_commits()?.unwrap_or_default();
+        let mut commit_progress = progress.add_child_with_id("commits", *b"VCCO"); /* Verify Connectivity COmmits */
+        commit_progress.init(None, git::progress::count("commits"));
+        let mut parents = Vec::new();
+        loop {
+            let (id, data) = match commits.pop() {
+                Some(commit) => commit,
+                None => match parents.pop() {
+                    Some((id, child)) => {
+                        let referrer = || Referrer::Commit { id: child };
+                        if !seen.first_visit_of_existing(
+                            id,
+                            Some(Kind::Commit),
+                            referrer,
+                            |id| repo.objects.contains(id),
+                            &missing,
+                        ) {
+                            continue;
+                        }
+                        match repo.objects.try_find(id, &mut buf)? {
+                            Some(obj) => (id, obj.data.to_owned()),
+                            None => {
+                                missing.record(id, Some(Kind::Commit), referrer());
+                                continue;
+                            }
+                        }
+                    }
+                    None => break,
+                },
+            };
+            if should_interrupt.load(Ordering::Relaxed) {
+                anyhow::bail!("Interrupted");
+            }
+            outcome.commits += 1;
+            commit_progress.inc();
+            let mut iter = git::objs::CommitRefIter::from_bytes(&data);
+            trees.push((iter.tree_id()?, Referrer::Commit { id }));
+            if shallow.binary_search(&id).is_err() {
+                parents.extend(iter.parent_ids().map(|parent| (parent, id)));
+            }
+        }
+        commit_progress.show_throughput(std::time::Instant::now());
+
+        let mut tree_progress = progress.add_child_with_id("trees", *b"VCTR"); /* Verify Connectivity TRees */
+        tree_progress.init(Some(trees.len()), git::progress::count("root trees"));
+        let objects = repo.objects.clone().into_arc()?;
+        let (chunk_size, thread_limit, _) =
+            git::parallel::optimize_chunk_size_and_thread_limit(16, Some(trees.len()), thread_limit, None);
+        let (num_trees, num_blobs) = git::parallel::in_parallel(
+            trees.chunks(chunk_size),
+            thread_limit,
+            move |_| (objects.clone(), Vec::new(), Vec::new()),
+            |chunk, (objects, buf, stack)| {
+                traverse_trees(chunk, objects, buf, stack, &seen, &missing, should_interrupt)
+            },
+            Reducer {
+                progress: &mut tree_progress,
+                trees: 0,
+                blobs: 0,
+            },
+        )?;
+        tree_progress.show_throughput(std::time::Instant::now());
+        outcome.trees += num_trees;
+        outcome.blobs += num_blobs;
+
+        let mut objects = repo.objects.clone();
+        objects.missing_object_policy = git::odb::store::MissingObjectPolicy::TreatAsPromised;
+        for (id, (kind, referrers)) in missing.into_sorted() {
+            match objects.try_find(id, &mut buf) {
+                Err(err) if err.kind() == git::odb::find::ErrorKind::Promised => outcome.promised += 1,
+                _ => outcome.missing.push(Missing { id, kind, referrers }),
+            }
+        }
+        outcome.missing.sort_by_key(|m| m.kind);
+        Ok((outcome, seen))
+    }
+
+    /// Add the ids of `tree` at `path` and all of its children to `tips`, skipping invalidated trees as they may not exist
+    /// in the object database.
+    fn cache_tree_tips(tree: &git::index::extension::Tree, path: String, tips: &mut Vec<(git::ObjectId, Referrer)>) {
+        for child in &tree.children {
+            let child_path = if path.is_empty() {
+                child.name.to_str_lossy().into_owned()
+            } else {
+                format!("{}/{}", path, child.name.to_str_lossy())
+            };
+            cache_tree_tips(child, child_path, tips);
+        }
+        if tree.num_entries.is_some() {
+            tips.push((tree.id, Referrer::Index { path }));
+        }
+    }
+
+    /// The kind expected by the referrers of a missing object, along with the referrers.
+    pub type KindAndReferrers = (Option<Kind>, Vec<Referrer>);
+
+    /// Missing objects along with the kind expected by their referrers.
+    #[derive(Default)]
+    pub struct MissingObjects(Mutex<BTreeMap<git::ObjectId, KindAndReferrers>>);
+
+    impl MissingObjects {
+        pub fn record(&self, id: git::ObjectId, kind: Option<Kind>, referrer: Referrer) {
+            let mut map = self.0.lock().expect("no panic while holding the lock");
+            let (expected_kind, referrers) = map.entry(id).or_default();
+            *expected_kind = expected_kind.or(kind);
+            referrers.push(referrer);
+        }
+
+        pub fn into_sorted(self) -> BTreeMap<git::ObjectId, KindAndReferrers> {
+            let mut map = self.0.into_inner().expect("no panic while holding the lock");
+            for (_, referrers) in map.values_mut() {
+                referrers.sort();
+                referrers.dedup();
+            }
+            map
+        }
+    }
+
+    /// Traverse all trees in `chunk` depth-first and return the amount of trees in `chunk`, along with the amount of trees
+    /// and blobs which were found.
+    pub fn traverse_trees<Find>(
+        chunk: &[(git::ObjectId, Referrer)],
+        objects: &Find,
+        buf: &mut Vec<u8>,
+        stack: &mut Vec<(git::ObjectId, Referrer)>,
+        seen: &Seen,
+        missing: &MissingObjects,
+        should_interrupt: &AtomicBool,
+    ) -> anyhow::Result<(usize, usize, usize)>
+    where
+        Find: git::odb::Find,
+        Find::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let (mut trees, mut blobs) = (0, 0);
+        stack.extend(chunk.iter().cloned());
+        while let Some((id, referrer)) = stack.pop() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                anyhow::bail!("Interrupted");
+            }
+            if !seen.first_visit_of_existing(
+                id,
+                Some(Kind::Tree),
+                || referrer.clone(),
+                |id| objects.contains(id),
+                missing,
+            ) {
+                continue;
+            }
+            let data = match objects.try_find(id, buf)? {
+                Some(obj) => obj.data,
+                None => {
+                    missing.record(id, Some(Kind::Tree), referrer);
+                    continue;
+                }
+            };
+            trees += 1;
+            for entry in git::objs::TreeRefIter::from_bytes(data) {
+                let entry = entry?;
+                let name = || entry.filename.to_str_lossy().into_owned();
+                match entry.mode {
+                    git::objs::tree::EntryMode::Tree => {
+                        stack.push((entry.oid.to_owned(), Referrer::Tree { id, name: name() }))
+                    }
+                    git::objs::tree::EntryMode::Blob
+                    | git::objs::tree::EntryMode::BlobExecutable
+                    | git::objs::tree::EntryMode::Link => {
+                        let referrer = || Referrer::Tree { id, name: name() };
+                        if seen.first_visit_of_existing(
+                            entry.oid.to_owned(),
+                            Some(Kind::Blob),
+                            referrer,
+                            |id| objects.contains(id),
+                            missing,
+                        ) {
+                            blobs += 1;
+                        }
+                    }
+                    git::objs::tree::EntryMode::Commit => {}
+                }
+            }
+        }
+        Ok((chunk.len(), trees, blobs))
+    }
+
+    /// Sums up the amount of trees and blobs found by each thread.
+    pub struct Reducer<P> {
+        pub progress: P,
+        pub trees: usize,
+        pub blobs: usize,
+    }
+
+    impl<P: Progress> git::parallel::Reduce for Reducer<&mut P> {
+        type Input = anyhow::Result<(usize, usize, usize)>;
+        type FeedProduce = ();
+        type Output = (usize, usize);
+        type Error = anyhow::Error;
+
+        fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+            let (roots, trees, blobs) = item?;
+            self.trees += trees;
+            self.blobs += blobs;
+            self.progress.inc_by(roots);
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Self::Error> {
+            Ok((self.trees, self.blobs))
+        }
+    }
+}
+
+/// Check that all direct references point to objects that exist, following annotated tags to their targets.
+fn missing_reference_targets(repo: &git::Repository) -> anyhow::Result<(usize, Vec<MissingTarget>)> {
+    use git::odb::Find;
+    let mut checked = 0;
+    let mut missing = Vec::new();
+    let mut buf = Vec::new();
+    let platform = repo.references()?;
+    for reference in platform.all()? {
+        let reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+        let mut id = match reference.target().try_id() {
+            Some(id) => id.to_owned(),
+            None => continue,
+        };
+        checked += 1;
+        loop {
+            match repo.objects.try_find(id, &mut buf)? {
+                Some(obj) if obj.kind == git::object::Kind::Tag => {
+                    id = git::objs::TagRefIter::from_bytes(obj.data).target_id()?;
+                }
+                Some(_) => break,
+                None => {
+                    missing.push(MissingTarget {
+                        name: reference.name().as_bstr().to_str_lossy().into_owned(),
+                        id,
+                    });
+                    break;
+                }
+            }
+        }
+    }
+    Ok((checked, missing))
+}
+
+/// Classify `err` as a problem with the data in the object database, or return `None` if it's an error preventing verification.
+fn status_of_store_error(err: &git::odb::store::verify::integrity::Error) -> Option<Status> {
+    use git::odb::{loose, store::verify::integrity::Error};
+    match err {
+        Error::IndexIntegrity(err) => status_of_traverse_error(err, |err| {
+            use pack::index::verify::integrity::Error;
+            Some(match err {
+                Error::Fan { .. } | Error::DuplicateOffset { .. } | Error::ObjectCountMismatch { .. } => {
+                    Status::InconsistentIndex
+                }
+                Error::ObjectDecode { .. } | Error::ObjectEncodeMismatch { .. } => Status::UndecodableObjects,
+                Error::MissingDeltaBase { .. } => Status::MissingObjects,
+            })
+        }),
+        Error::MultiIndexIntegrity(err) => status_of_traverse_error(err, |err| {
+            use pack::multi_index::verify::integrity::Error;
+            match err {
+                Error::MultiIndexChecksum(_) => Some(Status::ChecksumMismatch),
+                Error::IndexIntegrity(
+                    pack::index::verify::integrity::Error::Fan { .. }
+                    | pack::index::verify::integrity::Error::DuplicateOffset { .. }
+                    | pack::index::verify::integrity::Error::ObjectCountMismatch { .. },
+                ) => Some(Status::InconsistentIndex),
+                Error::IndexIntegrity(_) => Some(Status::UndecodableObjects),
+                Error::PackOffsetMismatch { .. }
+                | Error::UnexpectedObjectCount { .. }
+                | Error::OidNotFound { .. }
+                | Error::OutOfOrder { .. }
+                | Error::Fan { .. }
+                | Error::Empty => Some(Status::InconsistentIndex),
+                Error::BundleInit(_) | Error::Interrupted => None,
+            }
+        }),
+        Error::LooseObjectStoreIntegrity(err) => match err {
+            loose::verify::integrity::Error::ObjectDecode { .. }
+            | loose::verify::integrity::Error::ObjectEncodeMismatch { .. } => Some(Status::UndecodableObjects),
+            loose::verify::integrity::Error::ObjectHashMismatch { .. } => Some(Status::ChecksumMismatch),
+            loose::verify::integrity::Error::Retry | loose::verify::integrity::Error::Interrupted => None,
+        },
+        Error::IndexOpen(_)
+        | Error::MultiIndexOpen(_)
+        | Error::PackOpen(_)
+        | Error::InitializeODB(_)
+        | Error::NeedsRetryDueToChangeOnDisk => None,
+    }
+}
+
+fn status_of_traverse_error<E>(
+    err: &pack::index::traverse::Error<E>,
+    status_of_processor_error: impl FnOnce(&E) -> Option<Status>,
+) -> Option<Status>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    use pack::index::traverse::Error;
+    match err {
+        Error::VerifyChecksum(_)
+        | Error::PackMismatch { .. }
+        | Error::IndexOidMismatch { .. }
+        | Error::Crc32Mismatch { .. } => Some(Status::ChecksumMismatch),
+        Error::Tree(_)
+        | Error::TreeTraversal(_)
+        | Error::PackDecode { .. }
+        | Error::DeltaChainCycle { .. }
+        | Error::EntryOverlap { .. } => Some(Status::UndecodableObjects),
+        Error::Processor(err) => status_of_processor_error(err),
+        Error::InvalidPartition { .. } | Error::PackMutatedDuringVerification { .. } | Error::Interrupted => None,
     }
-    Ok(())
 }
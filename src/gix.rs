@@ -15,7 +15,10 @@ use anyhow::Result;
 
 #[cfg(feature = "pretty-cli")]
 fn main() -> Result<()> {
-    plumbing::main()
+    match plumbing::main()? {
+        0 => Ok(()),
+        code => std::process::exit(code),
+    }
 }
 
 #[cfg(not(feature = "pretty-cli"))]
@@ -15,7 +15,10 @@ use gitoxide_core::pack::verify;
 
 use crate::{
     plumbing::{
-        options::{commit, config, credential, exclude, free, index, mailmap, odb, revision, tree, Args, Subcommands},
+        options::{
+            bundle, commit, config, credential, diff, exclude, free, index, mailmap, odb, refs, revision, tree, Args,
+            Subcommands,
+        },
         show_progress,
     },
     shared::pretty::prepare_and_run,
@@ -50,7 +53,11 @@ pub mod async_util {
     }
 }
 
-pub fn main() -> Result<()> {
+/// The exit code shells report for processes terminated by `SIGINT`, used for all commands that were interrupted.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Run the program and return the code to exit the process with once everything was dropped.
+pub fn main() -> Result<i32> {
     let args: Args = Args::parse_from(git_repository::env::args_os());
     let thread_limit = args.threads;
     let verbose = args.verbose;
@@ -119,6 +126,7 @@ pub fn main() -> Result<()> {
         move || should_interrupt.store(true, Ordering::SeqCst)
     })?;
 
+    let mut verify_status = core::repository::verify::Status::Ok;
     match cmd {
         #[cfg(feature = "gitoxide-core-blocking-client")]
         Subcommands::Clone(crate::plumbing::options::clone::Platform {
@@ -335,7 +343,12 @@ pub fn main() -> Result<()> {
                     progress_keep_open,
                     None,
                     move |_progress, out, _err| {
-                        core::index::entries(index_path, out, core::index::Options { object_hash, format })
+                        core::index::entries(
+                            index_path,
+                            out,
+                            core::index::Options { object_hash, format },
+                            &should_interrupt,
+                        )
                     },
                 ),
                 free::index::Subcommands::Verify => prepare_and_run(
@@ -373,6 +386,10 @@ pub fn main() -> Result<()> {
                     counting_threads,
                     object_cache_size_mb,
                     output_directory,
+                    deltas,
+                    window,
+                    depth,
+                    compression_level,
                 } => {
                     let has_tips = !tips.is_empty();
                     prepare_and_run(
@@ -391,6 +408,18 @@ pub fn main() -> Result<()> {
                                 pack_cache_size_in_bytes: pack_cache_size_mb.unwrap_or(0) * 1_000_000,
                                 object_cache_size_in_bytes: object_cache_size_mb.unwrap_or(0) * 1_000_000,
                                 statistics: if statistics { Some(format) } else { None },
+                                delta_mode: {
+                                    use git_repository::odb::pack::data::output::entry::iter_from_counts::DeltaMode;
+                                    match deltas {
+                                        core::pack::create::Deltas::None => DeltaMode::None,
+                                        core::pack::create::Deltas::Reuse => DeltaMode::ReuseExisting,
+                                        core::pack::create::Deltas::Compute => DeltaMode::Compute {
+                                            window,
+                                            max_depth: depth,
+                                        },
+                                    }
+                                },
+                                compression_level,
                                 out,
                                 expansion: expansion.unwrap_or(if has_tips {
                                     core::pack::create::ObjectExpansion::TreeTraversal
@@ -467,6 +496,7 @@ pub fn main() -> Result<()> {
                     pack_path,
                     object_path,
                     verify,
+                    pack_cache,
                 } => prepare_and_run(
                     "pack-explode",
                     auto_verbose,
@@ -486,6 +516,7 @@ pub fn main() -> Result<()> {
                                 verify,
                                 should_interrupt,
                                 object_hash,
+                                pack_cache,
                             },
                         )
                     },
@@ -497,7 +528,10 @@ pub fn main() -> Result<()> {
                             decode,
                             re_encode,
                             statistics,
+                            human_units,
                         },
+                    plan_only,
+                    pack_cache,
                     path,
                 } => prepare_and_run(
                     "pack-verify",
@@ -506,22 +540,27 @@ pub fn main() -> Result<()> {
                     progress_keep_open,
                     verify::PROGRESS_RANGE,
                     move |progress, out, err| {
-                        let mode = verify_mode(decode, re_encode);
-                        let output_statistics = if statistics { Some(format) } else { None };
-                        verify::pack_or_pack_index(
-                            path,
-                            progress,
-                            verify::Context {
-                                output_statistics,
-                                out,
-                                err,
-                                thread_limit,
-                                mode,
-                                algorithm,
-                                should_interrupt: &should_interrupt,
-                                object_hash,
-                            },
-                        )
+                        core::with_json_error(format, out, |out| {
+                            let mode = verify_mode(decode, re_encode);
+                            let output_statistics = if statistics || plan_only { Some(format) } else { None };
+                            verify::file(
+                                path,
+                                progress,
+                                verify::Context {
+                                    output_statistics,
+                                    out,
+                                    err,
+                                    thread_limit,
+                                    mode,
+                                    algorithm,
+                                    should_interrupt: &should_interrupt,
+                                    object_hash,
+                                    statistics_options: verify::Options { human_units },
+                                    plan_only,
+                                    pack_cache,
+                                },
+                            )
+                        })
                     },
                 )
                 .map(|_| ()),
@@ -622,6 +661,7 @@ pub fn main() -> Result<()> {
                     algorithm,
                     decode,
                     re_encode,
+                    human_units,
                 },
         } => prepare_and_run(
             "verify",
@@ -637,22 +677,25 @@ pub fn main() -> Result<()> {
                     &should_interrupt,
                     core::repository::verify::Context {
                         output_statistics: statistics.then(|| format),
+                        format,
                         algorithm,
                         verify_mode: verify_mode(decode, re_encode),
                         thread_limit,
+                        statistics_options: core::pack::verify::Options { human_units },
                     },
                 )
             },
-        ),
+        )
+        .map(|status| verify_status = status),
         Subcommands::Revision(cmd) => match cmd {
-            revision::Subcommands::List { spec } => prepare_and_run(
+            revision::Subcommands::List { specs } => prepare_and_run(
                 "revision-list",
                 verbose,
                 progress,
                 progress_keep_open,
                 None,
                 move |_progress, out, _err| {
-                    core::repository::revision::list(repository(Mode::Lenient)?, spec, out, format)
+                    core::repository::revision::list(repository(Mode::Lenient)?, specs, out, format, &should_interrupt)
                 },
             ),
             revision::Subcommands::PreviousBranches => prepare_and_run(
@@ -677,23 +720,40 @@ pub fn main() -> Result<()> {
                 specs,
                 explain,
                 cat_file,
+                tips,
+                allow_unverified,
+                single,
+                short,
+                strict_refs,
             } => prepare_and_run(
                 "revision-parse",
                 verbose,
                 progress,
                 progress_keep_open,
                 None,
-                move |_progress, out, _err| {
-                    core::repository::revision::resolve(
-                        repository(Mode::Strict)?,
-                        specs,
-                        out,
-                        core::repository::revision::resolve::Options {
-                            format,
-                            explain,
-                            cat_file,
-                        },
-                    )
+                move |_progress, out, err| {
+                    core::with_json_error(format, out, |out| {
+                        core::repository::revision::resolve(
+                            repository(Mode::Strict)?,
+                            specs,
+                            out,
+                            err,
+                            core::repository::revision::resolve::Options {
+                                format,
+                                explain,
+                                cat_file,
+                                tips,
+                                allow_unverified,
+                                mode: if single {
+                                    core::repository::revision::resolve::Mode::Single
+                                } else {
+                                    core::repository::revision::resolve::Mode::Normal
+                                },
+                                short,
+                                strict_refs,
+                            },
+                        )
+                    })
                 },
             ),
         },
@@ -736,21 +796,29 @@ pub fn main() -> Result<()> {
             tree::Subcommands::Entries {
                 treeish,
                 recursive,
+                name_only,
                 extended,
+                paths,
             } => prepare_and_run(
                 "tree-entries",
                 verbose,
                 progress,
                 progress_keep_open,
                 None,
-                move |_progress, out, _err| {
+                move |_progress, out, err| {
                     core::repository::tree::entries(
                         repository(Mode::Strict)?,
                         treeish.as_deref(),
-                        recursive,
-                        extended,
-                        format,
+                        core::repository::tree::entries::Options {
+                            recursive,
+                            name_only,
+                            extended,
+                            format,
+                            path_filter: paths.into_filter(),
+                        },
                         out,
+                        err,
+                        &should_interrupt,
                     )
                 },
             ),
@@ -768,6 +836,7 @@ pub fn main() -> Result<()> {
                         format,
                         out,
                         err,
+                        &should_interrupt,
                     )
                 },
             ),
@@ -779,7 +848,11 @@ pub fn main() -> Result<()> {
                 progress,
                 progress_keep_open,
                 None,
-                move |_progress, out, _err| core::repository::odb::entries(repository(Mode::Strict)?, format, out),
+                move |_progress, out, err| {
+                    core::with_json_error(format, out, |out| {
+                        core::repository::odb::entries(repository(Mode::Strict)?, format, out, err, &should_interrupt)
+                    })
+                },
             ),
             odb::Subcommands::Info => prepare_and_run(
                 "odb-info",
@@ -787,7 +860,321 @@ pub fn main() -> Result<()> {
                 progress,
                 progress_keep_open,
                 None,
-                move |_progress, out, err| core::repository::odb::info(repository(Mode::Strict)?, format, out, err),
+                move |_progress, out, err| {
+                    core::with_json_error(format, out, |out| {
+                        core::repository::odb::info(repository(Mode::Strict)?, format, out, err)
+                    })
+                },
+            ),
+            odb::Subcommands::Connectivity => prepare_and_run(
+                "odb-connectivity",
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                core::repository::verify::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::repository::verify::connectivity(
+                        repository(Mode::Strict)?,
+                        out,
+                        progress,
+                        &should_interrupt,
+                        core::repository::verify::connectivity::Options { format, thread_limit },
+                    )
+                },
+            )
+            .map(|status| verify_status = status),
+            odb::Subcommands::Reachability { expire } => prepare_and_run(
+                "odb-reachability",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::repository::verify::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::with_json_error(format, out, |out| {
+                        let expire = git::date::parse(&expire, Some(std::time::SystemTime::now()))?
+                            .to_system_time()
+                            .with_context(|| format!("Expiry date {:?} is out of range", expire))?;
+                        core::repository::odb::reachability(
+                            repository(Mode::Strict)?,
+                            progress,
+                            &should_interrupt,
+                            out,
+                            core::repository::odb::reachability::Options {
+                                format,
+                                thread_limit,
+                                expire,
+                            },
+                        )
+                    })
+                },
+            ),
+            odb::Subcommands::Prune {
+                expire,
+                no_dry_run,
+                force,
+            } => prepare_and_run(
+                "odb-prune",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::repository::verify::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::with_json_error(format, out, |out| {
+                        let expire = git::date::parse(&expire, Some(std::time::SystemTime::now()))?
+                            .to_system_time()
+                            .with_context(|| format!("Expiry date {:?} is out of range", expire))?;
+                        core::repository::odb::prune(
+                            repository(Mode::Strict)?,
+                            progress,
+                            &should_interrupt,
+                            out,
+                            core::repository::odb::prune::Options {
+                                format,
+                                thread_limit,
+                                expire,
+                                dry_run: !no_dry_run,
+                                force,
+                            },
+                        )
+                    })
+                },
+            ),
+            odb::Subcommands::VerifyNewPacks {
+                state_file,
+                algorithm,
+                decode,
+            } => prepare_and_run(
+                "odb-verify-new-packs",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, out, _err| {
+                    core::with_json_error(format, out, |out| {
+                        core::repository::odb::verify_new_packs(
+                            repository(Mode::Strict)?,
+                            state_file,
+                            progress,
+                            &should_interrupt,
+                            out,
+                            core::repository::odb::verify_new_packs::Context {
+                                format,
+                                thread_limit,
+                                verify_mode: verify_mode(decode, false),
+                                algorithm,
+                            },
+                        )
+                    })
+                },
+            ),
+            odb::Subcommands::SizeHistogram => prepare_and_run(
+                "odb-size-histogram",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, out, _err| {
+                    core::with_json_error(format, out, |out| {
+                        core::repository::odb::size_histogram(
+                            repository(Mode::Strict)?,
+                            progress,
+                            &should_interrupt,
+                            out,
+                            core::repository::odb::size_histogram::Options { format },
+                        )
+                    })
+                },
+            ),
+            odb::Subcommands::Stats { runtime, reuse } => prepare_and_run(
+                "odb-stats",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, out, err| {
+                    core::with_json_error(format, out, |out| {
+                        core::repository::odb::stats(
+                            repository(Mode::Strict)?,
+                            progress,
+                            &should_interrupt,
+                            out,
+                            err,
+                            core::repository::odb::stats::Options { format, runtime, reuse },
+                        )
+                    })
+                },
+            ),
+            odb::Subcommands::FindPaths { rev, ids } => prepare_and_run(
+                "odb-find-paths",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::with_json_error(format, out, |out| {
+                        core::repository::odb::find_paths(repository(Mode::Strict)?, ids, rev, format, out)
+                    })
+                },
+            ),
+            odb::Subcommands::Explode { destination, specs } => prepare_and_run(
+                "odb-explode",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, out, _err| {
+                    core::with_json_error(format, out, |out| {
+                        use std::io::BufRead;
+                        core::repository::odb::explode(
+                            repository(Mode::Strict)?,
+                            if specs.is_empty() {
+                                Box::new(
+                                    stdin_or_bail()?
+                                        .lines()
+                                        .filter_map(Result::ok)
+                                        .map(|line| line.trim().to_owned())
+                                        .filter(|line| !line.is_empty()),
+                                ) as Box<dyn Iterator<Item = String>>
+                            } else {
+                                Box::new(specs.into_iter())
+                            },
+                            destination,
+                            progress,
+                            &should_interrupt,
+                            format,
+                            out,
+                        )
+                    })
+                },
+            ),
+            odb::Subcommands::Verify {
+                args:
+                    free::pack::VerifyOptions {
+                        statistics,
+                        algorithm,
+                        decode,
+                        re_encode,
+                        human_units,
+                    },
+            } => prepare_and_run(
+                "odb-verify",
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                core::repository::verify::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::repository::verify::integrity(
+                        repository(Mode::Strict)?,
+                        out,
+                        progress,
+                        &should_interrupt,
+                        core::repository::verify::Context {
+                            output_statistics: statistics.then(|| format),
+                            format,
+                            algorithm,
+                            verify_mode: verify_mode(decode, re_encode),
+                            thread_limit,
+                            statistics_options: core::pack::verify::Options { human_units },
+                        },
+                    )
+                },
+            )
+            .map(|status| verify_status = status),
+        },
+        Subcommands::Refs(cmd) => match cmd {
+            refs::Subcommands::Verify => prepare_and_run(
+                "refs-verify",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::repository::refs::verify::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::repository::refs::verify::references(
+                        repository(Mode::Strict)?,
+                        format,
+                        out,
+                        progress,
+                        &should_interrupt,
+                    )
+                },
+            ),
+        },
+        Subcommands::Diff(cmd) => match cmd {
+            diff::Subcommands::Tree {
+                stat,
+                find_renames,
+                paths,
+                old_treeish,
+                new_treeish,
+            } => prepare_and_run(
+                "diff-tree",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, err| {
+                    core::repository::diff::tree(
+                        repository(Mode::Strict)?,
+                        &old_treeish,
+                        &new_treeish,
+                        out,
+                        err,
+                        core::repository::diff::Options {
+                            format,
+                            stats_only: stat,
+                            find_renames,
+                            path_filter: paths.into_filter(),
+                        },
+                    )
+                },
+            ),
+        },
+        Subcommands::Bundle(cmd) => match cmd {
+            bundle::Subcommands::Create {
+                version,
+                exclude,
+                path,
+                refs,
+            } => prepare_and_run(
+                "bundle-create",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::repository::bundle::PROGRESS_RANGE,
+                move |progress, _out, _err| {
+                    core::repository::bundle::create(
+                        repository(Mode::Strict)?,
+                        &path,
+                        refs,
+                        progress,
+                        &should_interrupt,
+                        core::repository::bundle::create::Options {
+                            version: version.map(|version| match version {
+                                3 => git::odb::pack::bundle_file::Version::V3,
+                                _ => git::odb::pack::bundle_file::Version::V2,
+                            }),
+                            exclude,
+                            thread_limit,
+                        },
+                    )
+                },
+            ),
+            bundle::Subcommands::Verify { path } => prepare_and_run(
+                "bundle-verify",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::repository::bundle::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::repository::bundle::verify(
+                        repository(Mode::Strict)?,
+                        &path,
+                        progress,
+                        &should_interrupt,
+                        out,
+                        core::repository::bundle::verify::Options { format, thread_limit },
+                    )
+                },
             ),
         },
         Subcommands::Mailmap(cmd) => match cmd {
@@ -853,8 +1240,18 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
-    }?;
-    Ok(())
+    }
+    .map_err(exit_if_interrupted)?;
+    Ok(verify_status.exit_code())
+}
+
+/// Exit the process right away with [`INTERRUPTED_EXIT_CODE`] if `err` was caused by an interrupt, or return it.
+fn exit_if_interrupted(err: anyhow::Error) -> anyhow::Error {
+    if core::is_interrupted(&err) || git_repository::interrupt::is_triggered() {
+        eprintln!("Error: {:?}", err);
+        std::process::exit(INTERRUPTED_EXIT_CODE);
+    }
+    err
 }
 
 fn stdin_or_bail() -> Result<std::io::BufReader<std::io::Stdin>> {
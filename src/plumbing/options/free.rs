@@ -143,6 +143,29 @@ pub mod pack {
             /// If unset, no cache will be used.
             object_cache_size_mb: Option<usize>,
 
+            #[clap(
+                long,
+                possible_values(core::pack::create::Deltas::variants()),
+                default_value = "reuse"
+            )]
+            /// How to produce deltas.
+            ///
+            /// "none" writes all objects as base objects, "reuse" copies deltas from existing packs and "compute" additionally
+            /// tries to delta-compress all other objects against preceding ones.
+            deltas: core::pack::create::Deltas,
+
+            #[clap(long, default_value_t = 10)]
+            /// The amount of preceding objects to try as delta base when computing deltas.
+            window: usize,
+
+            #[clap(long, default_value_t = 50)]
+            /// The longest chain of computed deltas to build.
+            depth: usize,
+
+            #[clap(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(0..=9))]
+            /// The zlib compression level from 0 (none) to 9 (best) for objects which aren't copied from existing packs.
+            compression_level: u32,
+
             #[clap(long)]
             /// if set, delta-objects whose base object wouldn't be in the pack will not be recompressed as base object, but instead
             /// refer to its base object using its object id.
@@ -223,6 +246,12 @@ pub mod pack {
             #[clap(long)]
             sink_compress: bool,
 
+            /// The cache for decoded pack entries, one of 'never', 'lru-mem=<size>' like 'lru-mem=512MB' or 'lru-entries=<count>'.
+            ///
+            /// Each thread uses its own cache, so the budget is per thread. Defaults to a small cache of 64 entries.
+            #[clap(long)]
+            pack_cache: Option<git_repository::odb::pack::cache::Spec>,
+
             /// The '.pack' or '.idx' file to explode into loose objects
             pack_path: PathBuf,
 
@@ -230,11 +259,27 @@ pub mod pack {
             object_path: Option<PathBuf>,
         },
         /// Verify the integrity of a pack, index or multi-index file
+        ///
+        /// A '.pack' or '.idx' file is verified along with its sibling of the same name to check all objects, or by its
+        /// checksum alone if the sibling doesn't exist.
         Verify {
             #[clap(flatten)]
             args: VerifyOptions,
 
-            /// The '.pack', '.idx' or 'multi-pack-index' file to validate.
+            #[clap(long, conflicts_with_all(&["statistics", "decode", "re-encode"]))]
+            /// Don't verify anything, but print the work verifying a '.idx' file would do, based on the headers of its pack entries.
+            ///
+            /// This includes the amount of entries, their delta chains and how they would be distributed across threads.
+            plan_only: bool,
+
+            #[clap(long, conflicts_with("plan-only"))]
+            /// The cache for decoded pack entries, one of 'never', 'lru-mem=<size>' like 'lru-mem=512MB' or 'lru-entries=<count>'.
+            ///
+            /// Each thread uses its own cache, so the budget is per thread. It's not used with '--statistics' which needs
+            /// to see entire delta chains. Defaults to a small cache for the 'less-memory' algorithm only.
+            pack_cache: Option<git_repository::odb::pack::cache::Spec>,
+
+            /// The '.pack', '.idx', '.midx' or 'multi-pack-index' file to validate.
             path: PathBuf,
         },
     }
@@ -268,6 +313,12 @@ pub mod pack {
         /// This will reduce overall performance even more, as re-encoding requires to transform zero-copy objects into
         /// owned objects, causing plenty of allocation to occur.
         pub re_encode: bool,
+
+        #[clap(long, requires("statistics"))]
+        /// Print sizes in binary units like MiB and GiB and separate thousands in counts when printing statistics.
+        ///
+        /// This is off by default to keep the human-readable output stable for scripts that parse it.
+        pub human_units: bool,
     }
 
     ///
@@ -76,12 +76,21 @@ pub enum Subcommands {
     /// Interact with the object database.
     #[clap(subcommand)]
     Odb(odb::Subcommands),
+    /// Interact with references.
+    #[clap(subcommand)]
+    Refs(refs::Subcommands),
     /// Interact with tree objects.
     #[clap(subcommand)]
     Tree(tree::Subcommands),
     /// Interact with commit objects.
     #[clap(subcommand)]
     Commit(commit::Subcommands),
+    /// Compare trees of revisions.
+    #[clap(subcommand)]
+    Diff(diff::Subcommands),
+    /// Create and verify bundle files, which contain references along with the objects they need in a single file.
+    #[clap(subcommand)]
+    Bundle(bundle::Subcommands),
     /// Verify the integrity of the entire repository
     Verify {
         #[clap(flatten)]
@@ -117,6 +126,34 @@ pub enum Subcommands {
     Free(free::Subcommands),
 }
 
+/// Restrict the entries of trees to look at to the given paths.
+#[derive(Debug, clap::Parser)]
+pub struct PathFilterArgs {
+    /// Match paths while ignoring case, so `docs` matches `Docs` and `DOCS`, which is useful on case-insensitive filesystems.
+    ///
+    /// This only affects matching, and paths are always shown as they are stored. Paths matching differently spelled entries
+    /// of the same tree match all of them and are reported as ambiguous.
+    #[clap(long, short = 'i')]
+    pub ignore_case: bool,
+    /// Match paths while ignoring unicode normalization, so precomposed characters (NFC) match their decomposed form (NFD).
+    #[clap(long)]
+    pub ignore_normalization: bool,
+    /// Only consider entries at or below these paths, relative to the root of the tree.
+    #[clap(last = true, parse(try_from_os_str = git::env::os_str_to_bstring))]
+    pub paths: Vec<BString>,
+}
+
+impl PathFilterArgs {
+    /// Return a filter for our paths, or `None` if there are none.
+    pub fn into_filter(self) -> Option<git::odb::pack::data::output::count::objects::PathFilter> {
+        (!self.paths.is_empty()).then(|| {
+            git::odb::pack::data::output::count::objects::PathFilter::new(self.paths)
+                .with_ignore_case(self.ignore_case)
+                .with_ignore_normalization(self.ignore_normalization)
+        })
+    }
+}
+
 pub mod config {
     use git::bstr::BString;
     use git_repository as git;
@@ -229,42 +266,208 @@ pub mod mailmap {
 }
 
 pub mod odb {
+    use std::path::PathBuf;
+
+    use gitoxide_core as core;
+
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
         /// Print all object names.
         Entries,
         /// Provide general information about the object database.
         Info,
+        /// Verify all objects and check that all references point to existing objects.
+        ///
+        /// The exit code tells the kind of problem: 0 if everything is fine, 2 for checksum mismatches,
+        /// 3 for undecodable objects, 4 for references pointing to missing objects and 5 for inconsistent indices.
+        Verify {
+            #[clap(flatten)]
+            args: super::free::pack::VerifyOptions,
+        },
+        /// Check that all objects reachable from `HEAD` and all references exist, like `git fsck --connectivity-only`.
+        ///
+        /// Missing objects are listed along with the objects or references referring to them. The exit code is 4 if
+        /// objects are missing, and 0 otherwise.
+        Connectivity,
+        /// Print how many objects, and how many bytes on disk, are reachable, unreachable but recent, and unreachable and old.
+        ///
+        /// Objects are reachable from `HEAD` or any reference. Unreachable objects are recent if their loose object file, or
+        /// their pack for packed objects, was modified at or after the expiry date.
+        Reachability {
+            /// Unreachable objects modified at or after this date are recent, like `gc.pruneExpire`.
+            #[clap(long, default_value = "2 weeks ago", value_name = "DATE")]
+            expire: String,
+        },
+        /// Delete unreachable loose objects which were modified before the expiry date, like `git prune`.
+        ///
+        /// Objects are reachable from `HEAD`, any reference, their reflogs or the worktree index. By default, only the objects
+        /// that would be deleted are printed. Nothing is deleted if reachable objects are missing, unless `--force` is given.
+        Prune {
+            /// Unreachable loose objects modified at or after this date are kept, like `gc.pruneExpire`.
+            #[clap(long, default_value = "2 weeks ago", value_name = "DATE")]
+            expire: String,
+            /// Actually delete the objects instead of only printing which ones would be deleted.
+            #[clap(long)]
+            no_dry_run: bool,
+            /// Prune even if no object is reachable or reachable objects are missing, which would delete all loose objects
+            /// older than the expiry date or those only reachable through the missing objects.
+            #[clap(long)]
+            force: bool,
+        },
+        /// Verify only the packs which were added since the last invocation with the same state file, and record them there.
+        ///
+        /// This allows maintenance jobs to verify new packs as they arrive. The state file is only updated if all new packs
+        /// could be verified, so failed packs are verified again next time.
+        VerifyNewPacks {
+            /// The file to read the packs seen in the previous run from and to write all packs seen now to.
+            ///
+            /// All packs are verified if it doesn't exist yet.
+            #[clap(long, short = 's')]
+            state_file: PathBuf,
+            /// The algorithm used to verify packs. They differ in costs.
+            #[clap(
+                long,
+                short = 'a',
+                default_value = "less-time",
+                possible_values(core::pack::verify::Algorithm::variants())
+            )]
+            algorithm: core::pack::verify::Algorithm,
+            /// Decode and parse tags, commits and trees to validate their correctness beyond hashing correctly.
+            #[clap(long)]
+            decode: bool,
+        },
+        /// Print a histogram of object sizes per kind and of the sizes of their compressed entries on disk.
+        ///
+        /// Sizes are read from object headers without decoding objects, which is fast even for large object databases.
+        SizeHistogram,
+        /// Print the metrics of the object database, like the amount of known and open packs and indices.
+        Stats {
+            /// Read all objects and print per-pack decode statistics gathered while doing so, like delta-base cache hits and misses.
+            #[clap(long)]
+            runtime: bool,
+            /// Print how many objects reachable from the given revision could be copied verbatim from existing packs, without recompressing them.
+            #[clap(long, value_name = "REV")]
+            reuse: Option<String>,
+        },
+        /// Find all paths at which the given objects, usually blobs, are located in a tree.
+        FindPaths {
+            /// The commit or tree whose tree should be searched.
+            #[clap(long, default_value = "HEAD")]
+            rev: String,
+            /// The objects to find, as object ids or any other revision specification.
+            #[clap(required = true)]
+            ids: Vec<String>,
+        },
+        /// Write the given objects as loose objects, for inspection with tools that can't read packs.
+        ///
+        /// Objects that already exist as loose objects in the destination are skipped.
+        Explode {
+            /// The objects directory to write loose objects into, or the one of the repository if unset.
+            #[clap(long, short = 'd')]
+            destination: Option<PathBuf>,
+            /// The objects to write, as object ids or any other revision specification, or unset to read from stdin one per line.
+            specs: Vec<String>,
+        },
+    }
+}
+
+pub mod refs {
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Check that `HEAD` and all references resolve to existing objects of the expected kind, and list those that don't.
+        ///
+        /// This includes following symbolic references, peeling annotated tags and assuring `HEAD` and branches point to commits.
+        Verify,
     }
 }
 
 pub mod tree {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
-        /// Print entries in a given tree
+        /// Print entries in a given tree like `git ls-tree`.
+        ///
+        /// Use `--format porcelain-z` to terminate each entry with NUL, like `git ls-tree -z`.
         Entries {
-            /// Traverse the entire tree and its subtrees respectively, not only this tree.
+            /// Traverse the entire tree and its subtrees respectively, listing the entries of subtrees with their full path
+            /// instead of the subtrees themselves.
             #[clap(long, short = 'r')]
             recursive: bool,
 
-            /// Provide files size as well. This is expensive as the object is decoded entirely.
-            #[clap(long, short = 'e')]
+            /// Only print the path of each entry.
+            #[clap(long)]
+            name_only: bool,
+
+            /// Provide files size as well, like `git ls-tree --long`. This is expensive as the object is decoded entirely.
+            #[clap(long, short = 'e', visible_alias = "long")]
             extended: bool,
 
-            /// The tree to traverse, or the tree at `HEAD` if unspecified.
+            /// A revision specification of the tree to traverse, like `HEAD~1` or `main:src`, or the tree at `HEAD` if unspecified.
             treeish: Option<String>,
+
+            #[clap(flatten)]
+            paths: super::PathFilterArgs,
         },
         /// Provide information about a tree.
         Info {
             /// Provide files size as well. This is expensive as the object is decoded entirely.
             #[clap(long, short = 'e')]
             extended: bool,
-            /// The tree to traverse, or the tree at `HEAD` if unspecified.
+            /// A revision specification of the tree to traverse, or the tree at `HEAD` if unspecified.
             treeish: Option<String>,
         },
     }
 }
 
+pub mod diff {
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Print the paths that changed between two trees along with the kind of change, similar to `git diff --name-status`.
+        Tree {
+            /// Print the amount of inserted and deleted lines per file instead, similar to `git diff --stat`.
+            #[clap(long, short = 's')]
+            stat: bool,
+            /// Pair up deleted and added files with the same content as renames.
+            #[clap(long, short = 'M')]
+            find_renames: bool,
+            /// A revision specification of the tree to compare, like `HEAD~1` or `main^{tree}`.
+            old_treeish: String,
+            /// A revision specification of the tree to compare with.
+            new_treeish: String,
+            #[clap(flatten)]
+            paths: super::PathFilterArgs,
+        },
+    }
+}
+
+pub mod bundle {
+    use std::path::PathBuf;
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Write a bundle file with the given references and all objects reachable from them, like `git bundle create`.
+        Create {
+            /// The version of the bundle file format to write, defaulting to the most compatible one.
+            #[clap(long, possible_values(&["2", "3"]))]
+            version: Option<u8>,
+            /// A revision whose history to leave out of the bundle, making it a prerequisite for unbundling.
+            ///
+            /// May be given multiple times.
+            #[clap(long)]
+            exclude: Vec<String>,
+            /// The path to the bundle file to write.
+            path: PathBuf,
+            /// The names of the references to put into the bundle, or `HEAD` and all references if unset.
+            refs: Vec<String>,
+        },
+        /// Check that a bundle file is intact and that the repository has all of its prerequisites,
+        /// like `git bundle verify`.
+        Verify {
+            /// The path to the bundle file to verify.
+            path: PathBuf,
+        },
+    }
+}
+
 pub mod commit {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
@@ -323,9 +526,13 @@ pub mod revision {
     #[derive(Debug, clap::Subcommand)]
     #[clap(visible_alias = "rev", visible_alias = "r")]
     pub enum Subcommands {
-        /// List all commits reachable from the given rev-spec.
+        /// List all commits reachable from the given rev-specs, like `main ^origin/main` or `main --not origin/main`.
         #[clap(visible_alias = "l")]
-        List { spec: std::ffi::OsString },
+        List {
+            /// rev-specs like `@`, `^origin/main` or `a..b`, combined into a single set of included and excluded commits.
+            #[clap(required = true, allow_hyphen_values = true)]
+            specs: Vec<std::ffi::OsString>,
+        },
         /// Provide the revision specification like `@~1` to explain.
         #[clap(visible_alias = "e")]
         Explain { spec: std::ffi::OsString },
@@ -340,8 +547,26 @@ pub mod revision {
             /// Show the first resulting object similar to how `git cat-file` would, but don't show the resolved spec.
             #[clap(short = 'c', long, conflicts_with = "explain")]
             cat_file: bool,
+            /// Combine all rev-specs into a single specification of included and excluded tips, similar to `git rev-list`.
+            ///
+            /// This also allows to use `--not` to invert the meaning of all following rev-specs.
+            #[clap(short = 't', long, conflicts_with_all = &["explain", "cat-file"])]
+            tips: bool,
+            /// Resolve full hex ids of objects that don't exist locally instead of failing, and mark them as not present.
+            #[clap(short = 'u', long, conflicts_with = "tips")]
+            allow_unverified: bool,
+            /// Resolve exactly one rev-spec to a single object and print only its id, failing otherwise, similar to `git rev-parse --verify`.
+            #[clap(short = 's', long, conflicts_with_all = &["explain", "cat-file", "tips"])]
+            single: bool,
+            /// Print the shortest unambiguous prefix of the resolved id instead of the full id.
+            #[clap(long, requires = "single")]
+            short: bool,
+            /// Fail if a reference name matches more than one reference, like a branch and a tag of the same name,
+            /// instead of warning about it and using the one with the highest precedence.
+            #[clap(long)]
+            strict_refs: bool,
             /// rev-specs like `@`, `@~1` or `HEAD^2`.
-            #[clap(required = true)]
+            #[clap(required = true, allow_hyphen_values = true)]
             specs: Vec<std::ffi::OsString>,
         },
         /// Return the names and hashes of all previously checked-out branches.
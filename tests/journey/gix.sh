@@ -66,6 +66,431 @@ title "gix (with repository)"
         }
       )
       fi
+      (small-repo-in-sandbox
+      (with "a tree with a non-canonical entry mode"
+        { printf '100664 file\0'; printf "$(git rev-parse HEAD:a | sed 's/../\\x&/g')"; } | git hash-object -w --literally -t tree --stdin >/dev/null
+        it "counts it as warning without failing" && {
+          WITH_SNAPSHOT="$snapshot/success-with-warnings-format-human" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --format human verify --re-encode
+        }
+      )
+      )
+    )
+    (with "the 'refs verify' sub-command"
+      snapshot="$snapshot/refs-verify"
+      (with 'all references resolving to existing objects'
+        it "succeeds" && {
+          WITH_SNAPSHOT="$snapshot/success-format-human" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose refs verify
+        }
+      )
+      (with 'dangling references'
+        echo 0000000000000000000000000000000000000001 > .git/refs/heads/dangling
+        git rev-parse HEAD^{tree} > .git/refs/heads/tree
+        echo "ref: refs/heads/missing" > .git/refs/heads/symbolic
+        it "fails and lists all of them" && {
+          WITH_SNAPSHOT="$snapshot/dangling-format-human" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose refs verify
+        }
+        it "lists them as problem, target and name in porcelain format" && {
+          WITH_SNAPSHOT="$snapshot/dangling-format-porcelain" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose --format porcelain refs verify
+        }
+        rm .git/refs/heads/{dangling,tree,symbolic}
+      )
+    )
+    (with "the 'revision resolve' sub-command"
+      snapshot="$snapshot/revision-resolve"
+      (with 'porcelain output format'
+        it "lists the resolved spec, its presence and the input spec" && {
+          WITH_SNAPSHOT="$snapshot/porcelain-success" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --format porcelain rev parse --allow-unverified main dev annotated 0000000000000000000000000000000000000001
+        }
+      )
+      (with 'NUL-terminated porcelain output format'
+        it "terminates each record with NUL" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain-z rev parse main dev | tr '\0\t' '|:')" = "3f72b39ad1600e6dac63430c15e0d875e9d3f9d6:present:main|ee3c97678e89db4eab7420b04aef51758359f152:present:dev|"
+        }
+      )
+      (with '--single'
+        it "prints only the full id of the single object on a single line" && {
+          WITH_SNAPSHOT="$snapshot/single-success" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose rev parse --single main
+        }
+        it "terminates the line with a newline" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose rev parse --single main | wc -l | tr -d ' ')" = 1
+        }
+        it "prints the shortest unambiguous prefix if --short is set" && {
+          WITH_SNAPSHOT="$snapshot/single-short-success" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose rev parse --single --short main
+        }
+        it "rejects ranges" && {
+          WITH_SNAPSHOT="$snapshot/single-range-failure" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose rev parse --single main..dev
+        }
+        it "rejects more than one spec" && {
+          WITH_SNAPSHOT="$snapshot/single-multiple-specs-failure" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose rev parse --single main dev
+        }
+      )
+      (with 'a tag and a branch of the same name'
+        git tag dev main
+        it "resolves the tag and warns about the ambiguity" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose rev parse --single dev 2>&1 >/dev/null)" = "warning: refname 'dev' is ambiguous."
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose rev parse --single dev 2>/dev/null)" = "$(git rev-parse refs/tags/dev)"
+        }
+        it "fails if --strict-refs is set" && {
+          WITH_SNAPSHOT="$snapshot/ambiguous-ref-strict-failure" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose rev parse --strict-refs dev
+        }
+        git tag -d dev >/dev/null
+      )
+      if test "$kind" = "max" || test "$kind" = "max-pure"; then
+      (with "--format json"
+        it "reports a spec resolving to nothing in single mode as error object of its own kind" && {
+          WITH_SNAPSHOT="$snapshot/json-error-single-not-found" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose --format json rev parse --single does-not-exist
+        }
+        it "reports a malformed spec as error object" && {
+          WITH_SNAPSHOT="$snapshot/json-error-invalid-spec" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose --format json rev parse 'main^{'
+        }
+        it "reports a missing object as error object" && {
+          WITH_SNAPSHOT="$snapshot/json-error-not-found" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose --format json rev parse 0000000000000000000000000000000000000001
+        }
+      )
+      fi
+    )
+    (with "the 'odb entries' sub-command"
+      (with 'porcelain output format'
+        it "lists one object id per line" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb entries | grep -cE '^[0-9a-f]{40}$')" = 9
+        }
+      )
+    )
+    (with "the 'tree entries' sub-command"
+      (with 'a tree with nested directories and unusual file names'
+        git init -q ../ls-tree
+        (cd ../ls-tree
+          mkdir -p dir/sub && echo a > a && echo b > dir/b && echo c > dir/sub/c && echo x > "$(printf 'tab\tname')"
+          printf '#!/bin/sh\n' > exe && chmod +x exe && ln -s a link
+          git add . && git -c commit.gpgsign=false commit -q -m "first"
+          it "matches the output of 'git ls-tree'" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose tree entries HEAD)" = "$(git ls-tree HEAD)"
+          }
+          it "matches the output of 'git ls-tree -r'" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose tree entries -r HEAD)" = "$(git ls-tree -r HEAD)"
+          }
+          it "matches the output of 'git ls-tree -r --long' for a subtree" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose tree entries -r --long HEAD:dir)" = "$(git ls-tree -r --long HEAD:dir)"
+          }
+          it "matches the output of 'git ls-tree -r -z --name-only' in NUL-terminated porcelain format" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain-z tree entries -r --name-only HEAD | tr '\0\t' '|:')" = "$(git ls-tree -r -z --name-only HEAD | tr '\0\t' '|:')"
+          }
+          it "matches the output of 'git ls-tree -r' with paths" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose tree entries -r HEAD -- dir/sub a)" = "$(git ls-tree -r HEAD -- dir/sub a)"
+          }
+        )
+        rm -Rf ../ls-tree
+      )
+      (with 'a tree with paths differing only in case'
+        git init -q ../ls-tree-case
+        (cd ../ls-tree-case
+          mkdir Docs docs && echo upper > README && echo lower > readme && echo a > Docs/a && echo b > docs/b
+          git add . && git -c commit.gpgsign=false commit -q -m "first"
+          it "matches paths exactly by default" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose tree entries --name-only -r HEAD -- DOCS readme)" = "readme"
+          }
+          it "matches all differently spelled paths when ignoring case" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose tree entries --name-only -r --ignore-case HEAD -- DOCS 2>/dev/null | tr '\n' ' ')" = "Docs/a docs/b "
+          }
+          it "reports the ambiguity" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose tree entries --name-only -r --ignore-case HEAD -- readme 2>&1 >/dev/null)" = "WARNING: 'readme' is ambiguous as it matches 'README', 'readme'"
+          }
+        )
+        rm -Rf ../ls-tree-case
+      )
+    )
+    (with "the 'odb size-histogram' sub-command"
+      (with 'porcelain output format'
+        it "counts all objects by kind" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb size-histogram | awk -F'\t' '$1 != "compressed" { sum += $4 } END { print sum }')" = 9
+        }
+        it "counts the compressed entries of all objects" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb size-histogram | awk -F'\t' '$1 == "compressed" { sum += $4 } END { print sum }')" = 9
+        }
+      )
+    )
+    (with "the 'rev list' sub-command"
+      (with 'a shallow clone with depth 2'
+        git clone -q --depth 2 "file://$PWD" ../shallow-clone
+        (cd ../shallow-clone
+          it "lists exactly the commits present in the clone" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose rev list HEAD)" = "$(git rev-list HEAD)"
+          }
+          it "lists two commits" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose rev list HEAD | wc -l | tr -d ' ')" = 2
+          }
+        )
+        rm -Rf ../shallow-clone
+      )
+    )
+    (with "the 'odb verify' sub-command"
+      snapshot="$snapshot/odb-verify"
+      (with 'all references pointing to existing objects'
+        it "succeeds" && {
+          WITH_SNAPSHOT="$snapshot/success-format-human" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose odb verify
+        }
+      )
+      (with 'a reference pointing to a missing object'
+        echo 0000000000000000000000000000000000000001 > .git/refs/heads/dangling
+        it "fails with the exit code for missing objects" && {
+          WITH_SNAPSHOT="$snapshot/missing-object-format-human" \
+          expect_run 4 "$exe_plumbing" --no-verbose odb verify
+        }
+      )
+    )
+    (with "the 'odb connectivity' sub-command"
+      (with 'a repository with a blob removed from its loose objects'
+        git init -q ../connectivity
+        (cd ../connectivity
+          mkdir dir && echo a > a && echo b > dir/b
+          git add . && git -c commit.gpgsign=false commit -q -m "first"
+          it "succeeds while all objects are present" && {
+            expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose odb connectivity
+          }
+          blob="$(git rev-parse HEAD:dir/b)"
+          rm -f ".git/objects/${blob:0:2}/${blob:2}"
+          it "fails with the exit code for missing objects" && {
+            expect_run 4 "$exe_plumbing" --no-verbose odb connectivity
+          }
+          it "lists the missing blob along with the tree entry referring to it" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose odb connectivity | grep "^missing")" = "missing blob $blob referenced by tree $(git rev-parse HEAD:dir) entry \"b\""
+          }
+        )
+        rm -Rf ../connectivity
+      )
+      (with 'a blobless partial clone'
+        git init -q ../partial-source
+        (cd ../partial-source
+          mkdir dir && echo a > a && echo b > dir/b
+          git add . && git -c commit.gpgsign=false commit -q -m "first"
+          git config uploadpack.allowFilter true
+        )
+        git clone -q --bare --no-local --filter=blob:none "file://$PWD/../partial-source" ../partial.git
+        (cd ../partial.git
+          it "succeeds as the missing blobs are promised by the remote" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose odb connectivity | tail -n2 | head -n1)" = "2 more objects are missing, but promised by the remote of this partial clone"
+          }
+        )
+        rm -Rf ../partial-source ../partial.git
+      )
+    )
+    (with "the 'odb reachability' sub-command"
+      (with 'a repository with a recent and an old unreachable loose object'
+        git init -q ../reachability
+        (cd ../reachability
+          echo a > a && git add a && git -c commit.gpgsign=false commit -q -m "first"
+          old="$(echo old | git hash-object -w --stdin)"
+          touch -t 202001010000 ".git/objects/${old:0:2}/${old:2}"
+          echo recent | git hash-object -w --stdin >/dev/null
+          it "counts the commit, tree and blob as reachable" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb reachability | grep "^reachable" | cut -f2)" = 3
+          }
+          it "counts the object modified after the expiry date as recent" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb reachability | grep "^recent-unreachable" | cut -f2)" = 1
+          }
+          it "counts the object modified before the expiry date as unreachable" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb reachability | grep "^unreachable" | cut -f2)" = 1
+          }
+          it "considers all unreachable objects recent with an expiry date before them" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb reachability --expire 2019-01-01 | grep "^recent-unreachable" | cut -f2)" = 2
+          }
+        )
+        rm -Rf ../reachability
+      )
+    )
+    (with "the 'odb prune' sub-command"
+      (with 'a repository with an old unreachable loose object and an old commit only referenced by the reflog'
+        git init -q ../prune
+        (cd ../prune
+          echo a > a && git add a && git -c commit.gpgsign=false commit -q -m "first"
+          amended="$(git rev-parse HEAD)"
+          git -c commit.gpgsign=false commit -q --amend -m "amended"
+          old="$(echo old | git hash-object -w --stdin)"
+          touch -t 202001010000 .git/objects/*/*
+          it "only lists the unreachable object by default" && {
+            expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb prune)" = "./.git/objects/${old:0:2}/${old:2}"
+          }
+          it "doesn't delete anything by default" && {
+            expect_run $SUCCESSFULLY test -f ".git/objects/${old:0:2}/${old:2}"
+          }
+          it "deletes the unreachable object with --no-dry-run" && {
+            expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --format porcelain odb prune --no-dry-run
+          }
+          it "deleted the unreachable object but kept the commit in the reflog" && {
+            expect_run $SUCCESSFULLY test ! -e ".git/objects/${old:0:2}/${old:2}" -a -f ".git/objects/${amended:0:2}/${amended:2}"
+          }
+        )
+        rm -Rf ../prune
+      )
+      (with 'a repository whose reflog has an unparsable line'
+        git init -q ../prune
+        (cd ../prune
+          echo a > a && git add a && git -c commit.gpgsign=false commit -q -m "first"
+          amended="$(git rev-parse HEAD)"
+          git -c commit.gpgsign=false commit -q --amend -m "amended"
+          old="$(echo old | git hash-object -w --stdin)"
+          touch -t 202001010000 .git/objects/*/*
+          echo "not a reflog line" >> .git/logs/HEAD
+          it "fails to prune with --no-dry-run" && {
+            expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose --format porcelain odb prune --no-dry-run
+          }
+          it "deleted nothing" && {
+            expect_run $SUCCESSFULLY test -f ".git/objects/${old:0:2}/${old:2}" -a -f ".git/objects/${amended:0:2}/${amended:2}"
+          }
+        )
+        rm -Rf ../prune
+      )
+      (with 'a repository with an old blob and tree only referenced by the index'
+        git init -q ../prune
+        (cd ../prune
+          echo a > a && git add a && git -c commit.gpgsign=false commit -q -m "first"
+          mkdir dir && echo staged > dir/b && git add dir/b
+          staged="$(git rev-parse :dir/b)"
+          tree="$(git write-tree)"
+          touch -t 202001010000 .git/objects/*/*
+          it "prunes with --no-dry-run" && {
+            expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --format porcelain odb prune --no-dry-run
+          }
+          it "kept the staged blob and the cached tree" && {
+            expect_run $SUCCESSFULLY test -f ".git/objects/${staged:0:2}/${staged:2}" -a -f ".git/objects/${tree:0:2}/${tree:2}"
+          }
+        )
+        rm -Rf ../prune
+      )
+      (with 'a repository with a missing reachable object and an old unreachable loose object'
+        git init -q ../prune
+        (cd ../prune
+          echo a > a && git add a && git -c commit.gpgsign=false commit -q -m "first"
+          blob="$(git rev-parse HEAD:a)"
+          git rm -q --cached a
+          rm ".git/objects/${blob:0:2}/${blob:2}"
+          old="$(echo old | git hash-object -w --stdin)"
+          touch -t 202001010000 .git/objects/*/*
+          it "fails to prune with --no-dry-run" && {
+            expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose --format porcelain odb prune --no-dry-run
+          }
+          it "deleted nothing" && {
+            expect_run $SUCCESSFULLY test -f ".git/objects/${old:0:2}/${old:2}"
+          }
+          it "prunes with --no-dry-run --force" && {
+            expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --format porcelain odb prune --no-dry-run --force
+          }
+          it "deleted the unreachable object" && {
+            expect_run $SUCCESSFULLY test ! -e ".git/objects/${old:0:2}/${old:2}"
+          }
+        )
+        rm -Rf ../prune
+      )
+    )
+    (with "the 'odb verify-new-packs' sub-command"
+      (with 'no packs'
+        it "verifies nothing and records the state" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb verify-new-packs --state-file packs.state | wc -l | tr -d ' ')" = 0
+        }
+        it "creates the state file" && {
+          expect_run $SUCCESSFULLY test -f packs.state
+        }
+      )
+      (with 'a new pack'
+        rm -f .git/refs/heads/dangling
+        git repack -q
+        it "verifies the new pack" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb verify-new-packs --state-file packs.state | grep -c '\.git/objects/pack/pack-[0-9a-f]*\.pack')" = 1
+        }
+        it "doesn't verify it again" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb verify-new-packs --state-file packs.state | wc -l | tr -d ' ')" = 0
+        }
+      )
+    )
+    (with "the 'odb stats' sub-command"
+      (with 'porcelain output format'
+        it "prints the store metrics" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb stats | grep -c '^known_packs')" = 1
+        }
+        it "prints no pack statistics without --runtime" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb stats | grep -c '^pack')" = 0
+        }
+        it "prints the decode statistics of all packs with --runtime" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb stats --runtime | grep '^pack' | cut -f3)" -ge 9
+        }
+        it "prints the potential verbatim reuse of the objects reachable from a revision with --reuse" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb stats --reuse HEAD | grep -c '^reuse-total')" = 1
+        }
+        it "prints no reuse without --reuse" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain odb stats | grep -c '^reuse')" = 0
+        }
+      )
+    )
+    (with "the 'diff tree' sub-command"
+      snapshot="$snapshot/diff-tree"
+      it "lists the changed paths with the kind of change" && {
+        WITH_SNAPSHOT="$snapshot/name-status-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose diff tree unannotated HEAD
+      }
+      it "summarizes inserted and deleted lines" && {
+        WITH_SNAPSHOT="$snapshot/stat-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose diff tree --stat unannotated HEAD
+      }
+      it "lists only changes at or below the given paths" && {
+        expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose diff tree unannotated HEAD -- B)" = ""
+      }
+      it "lists changes at paths differing in case when ignoring case" && {
+        expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose diff tree --ignore-case unannotated HEAD -- B)" = "$(printf 'A\tb')"
+      }
+    )
+    (with "the 'bundle' sub-command"
+      (with "'bundle create' and all references"
+        it "succeeds" && {
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose bundle create ../all.bundle
+        }
+        it "writes a bundle that git accepts" && {
+          expect_run $SUCCESSFULLY git bundle verify -q ../all.bundle
+        }
+        it "writes a bundle that git can clone from" && {
+          expect_run $SUCCESSFULLY git clone -q ../all.bundle ../from-bundle
+        }
+        rm -Rf ../from-bundle ../all.bundle
+      )
+      (with "'bundle create' with an excluded revision"
+        it "writes a bundle with the excluded commit as prerequisite" && {
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose bundle create --version 3 --exclude HEAD~1 ../incremental.bundle HEAD
+        }
+        it "writes a bundle that git accepts" && {
+          expect_run $SUCCESSFULLY git bundle verify -q ../incremental.bundle
+        }
+        rm -f ../incremental.bundle
+      )
+      (with "'bundle verify' on a bundle written by git"
+        git bundle create -q ../git.bundle HEAD~1..HEAD
+        it "lists references and present prerequisites" && {
+          expect_run $SUCCESSFULLY test "$("$exe_plumbing" --no-verbose --format porcelain bundle verify ../git.bundle | cut -f1,3 | tr '\n\t' ' ')" = "ref HEAD prerequisite present "
+        }
+        rm -f ../git.bundle
+      )
+    )
+    (with "the 'odb explode' sub-command"
+      (with 'a destination directory'
+        it "succeeds" && {
+          expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose odb explode --destination exploded HEAD HEAD^{tree}
+        }
+        it "writes the objects as loose objects" && {
+          expect_run $SUCCESSFULLY test "$(find exploded -type f | wc -l | tr -d ' ')" = 2
+        }
+      )
     )
   )
 
@@ -100,6 +525,12 @@ title "gix (with repository)"
             }
           )
           fi
+          (with "--format porcelain"
+            it "lists kind, object, peeled object or target, and name" && {
+              WITH_SNAPSHOT="$snapshot/file-v-any-porcelain" \
+              expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --format porcelain remote -n . refs
+            }
+          )
         )
         fi
 
@@ -500,6 +931,34 @@ title "gix (with repository)"
           WITH_SNAPSHOT="$snapshot/success" \
           expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose free pack verify "$PACK_FILE"
         }
+        (with "no index file next to it"
+          (sandbox
+            cp "$PACK_FILE" pack.pack
+            it "verifies the pack checksum only" && {
+              WITH_SNAPSHOT="$snapshot/pack-without-index-success" \
+              expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose free pack verify pack.pack
+            }
+          )
+        )
+        (with "a damaged index file next to it"
+          (sandbox
+            cp "$PACK_FILE" pack.pack
+            cp "${PACK_FILE%.pack}.idx" pack.idx
+            echo $'\0' >> pack.idx
+            it "fails to verify the pair" && {
+              expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose free pack verify pack.pack
+            }
+          )
+        )
+      )
+      (with "a valid pack INDEX file without its pack"
+        (sandbox
+          cp "$fixtures/packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx" index.idx
+          it "verifies the index checksum only" && {
+            WITH_SNAPSHOT="$snapshot/index-without-pack-success" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose free pack verify index.idx
+          }
+        )
       )
       (with "a valid pack INDEX file"
         MULTI_PACK_INDEX="$fixtures/packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx"
@@ -521,6 +980,20 @@ title "gix (with repository)"
               expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose free pack verify --algorithm less-memory --statistics "$MULTI_PACK_INDEX"
             }
           )
+
+          (with "human units"
+            it "verifies the pack index successfully and prints sizes in binary units" && {
+              WITH_SNAPSHOT="$snapshot/index-with-statistics-human-units-success" \
+              expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose free pack verify --statistics --human-units "$MULTI_PACK_INDEX"
+            }
+          )
+
+          (with "porcelain output format"
+            it "verifies the pack index successfully and prints one statistic per line" && {
+              WITH_SNAPSHOT="$snapshot/index-with-statistics-porcelain-success" \
+              expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --format porcelain free pack verify --statistics "$MULTI_PACK_INDEX"
+            }
+          )
         )
         (with "decode"
           it "verifies the pack index successfully and with desired output, and decodes all objects" && {
@@ -542,6 +1015,41 @@ title "gix (with repository)"
           }
         )
         fi
+        (with "plan-only"
+          it "prints the work verification would do without verifying" && {
+            WITH_SNAPSHOT="$snapshot/index-plan-only-success" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --threads 2 free pack verify --plan-only "$MULTI_PACK_INDEX"
+          }
+          if test "$kind" = "max" || test "$kind" = "max-pure"; then
+          (with "and the less-memory algorithm (JSON)"
+            it "prints the work verification would do without verifying" && {
+              WITH_SNAPSHOT="$snapshot/index-plan-only-json-success" \
+              expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose --format json --threads 2 free pack verify --algorithm less-memory --plan-only "$MULTI_PACK_INDEX"
+            }
+          )
+          fi
+          (with "a pack file"
+            (sandbox
+              cp "${MULTI_PACK_INDEX%.idx}.pack" pack.pack
+              it "fails as only index files can be planned" && {
+                WITH_SNAPSHOT="$snapshot/pack-plan-only-failure" \
+                expect_run $WITH_FAILURE "$exe_plumbing" --no-verbose free pack verify --plan-only pack.pack
+              }
+            )
+          )
+        )
+        (with "a pack cache specification"
+          it "verifies the pack index using the given cache" && {
+            WITH_SNAPSHOT="$snapshot/index-with-pack-cache-success" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose free pack verify --algorithm less-memory --pack-cache lru-mem=1MB "$MULTI_PACK_INDEX"
+          }
+          (with "an invalid specification"
+            it "fails naming the valid specifications" && {
+              WITH_SNAPSHOT="$snapshot/index-with-pack-cache-failure" \
+              expect_run $WITH_CLAP_FAILURE "$exe_plumbing" --no-verbose free pack verify --pack-cache lru "$MULTI_PACK_INDEX"
+            }
+          )
+        )
       )
       (with "a valid multi-pack index"
         snapshot="$snapshot/multi-index"
@@ -563,6 +1071,13 @@ title "gix (with repository)"
               expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose free pack verify "$MULTI_PACK_INDEX"
             }
           )
+          (with "the '.midx' extension"
+            cp "$MULTI_PACK_INDEX" pack.midx
+            it "verifies the multi-pack index successfully" && {
+              WITH_SNAPSHOT="$snapshot/index-success" \
+              expect_run $SUCCESSFULLY "$exe_plumbing" --no-verbose free pack verify pack.midx
+            }
+          )
           (with "statistics"
             it "verifies the pack index successfully and with desired output" && {
               WITH_SNAPSHOT="$snapshot/index-with-statistics-success" \